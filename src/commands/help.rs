@@ -1,28 +1,155 @@
 use log::*;
 use serenity::framework::standard::CommandError;
 use serenity::model::channel::Message;
+use serenity::prelude::Context;
 
-pub fn help(message: &Message) -> Result<(), CommandError> {
+use crate::commands::servers::GuildCommandsKey;
+
+/// The help text is written with `!`, a guild might use something else
+fn with_prefix(text: &str, prefix: &str) -> String {
+    let mut prefixed = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(next) if c == '!' && next.is_ascii_lowercase() => prefixed.push_str(prefix),
+            _ => prefixed.push(c),
+        }
+    }
+    prefixed
+}
+
+pub fn help(context: &mut Context, message: &Message) -> Result<(), CommandError> {
     debug!("HELP COMMAND");
-    let _ = message.reply(
-        "Commands (server alias is optional, defaults to channel name): \n\
+    let (prefix, aliases) = {
+        let data = context.data.lock();
+        let guild_commands = data
+            .get::<GuildCommandsKey>()
+            .ok_or("No GuildCommands was created on startup. This is a bug.")?;
+        (
+            guild_commands.prefix(message.guild_id),
+            guild_commands.aliases(message.guild_id),
+        )
+    };
+    let mut text = with_prefix(HELP_TEXT, &prefix);
+    if !aliases.is_empty() {
+        text.push_str("\nAliases here: ");
+        text.push_str(
+            &aliases
+                .iter()
+                .map(|(alias, command)| format!("{}{} = {}{}", prefix, alias, prefix, command))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+    let _ = message.reply(&text)?;
+    Ok(())
+}
+
+const HELP_TEXT: &str = "Commands (server alias is optional, defaults to channel name): \n\
          - !add <address:port> <alias>: save the dom5 server address\n\
+         - !addmany <address:port> <alias> [<address:port> <alias> ...]: add several servers at once\n\
+         - !probe <address:port>: check there's a game at an address and whether it's a lobby or started, without saving anything\n\
          - !list: return a list of the saved server addresses and aliases\n\
+         - !hostgames <hostname>: list the games being checked on one host, with their ports and turns\n\
          - !delete <alias>: remove the server address from the list\n\
-         - !details <alias>: return a list of the nations and their statuses in the game\n\
-         - !register nation_prefix <alias>: register yourself as a nation in a game\n\
+         - !details [--all] [--sort={nation,status,player}] <alias>: return a list of the nations and their statuses in the game. --all lists defeated nations too, --sort=status puts whoever still has to play first\n\
+         - !register nation_prefix [EA|MA|LA] <alias>: register yourself as a nation in a game. Add the era if the name is ambiguous\n\
          - !register-id nation_id <alias>: register yourself as a nation in a game using the id\n\
+         - !claim nation_id <alias>: register yourself as a nation you're already playing in a started game that nobody here has registered\n\
          - !unregister <alias>: unregister yourself in a game\n\
+         - !leave <alias>: drop out of a lobby before it starts, freeing your slot\n\
          - !turns: show all of the games you're in and their turn status\n\
          - !notifications {true, false}: enable/disable turn notifications\n\
-         - !lobby {EA/MA/LA} <num_players> <alias>: create a lobby with no server\n\
+         - !lobby [{EA/MA/LA}] <num_players> <alias>: create a lobby with no server. The era can be left out if the channel has a default\n\
          - !lobbies: list available lobbies\n\
          - !start <address:port> <alias>: register a started server for a lobby game\n\
          - !{item, spell, unit, site, merc, event} <text>: get dom5inspector search url\n\
          - !help: display this text\n\
-         - !describe \"text\" <alias>: add a description to a lobby. Quotes required.\n\
+         - !describe {\"text\", --clear} [<alias>]: set or remove the description shown in !details, discord markdown works. Quotes required.\n\
          - !unstart <alias>: turn a game back into a lobby, if you need to change address\n\
-         Source is located at www.github.com/djmcgill/dominions-5-status Contributions welcome!",
-    )?;
-    Ok(())
-}
+         - !history [<num_turns>] <alias>: show how long the last few turns took\n\
+         - !turnnote [<turn>] \"<note>\" <alias>: owner only. Write down something about a turn, e.g. why its timer was extended. Defaults to the current turn\n\
+         - !turnnotes <alias>: show the notes written about the game's turns\n\
+         - !turnstats <alias>: average, median and longest turn, and how many turns in a row hosted on time\n\
+         - !json <alias>: the game's details as json, for dashboards and other bots\n\
+         - !rename <new_alias> <alias>: owner or admin only. Change a game's alias, keeping its players and history\n\
+         - !setaddress <address:port> <alias>: owner or admin only. Point a started game at its server's new address\n\
+         - !transfer @player <alias>: make someone else the owner of your lobby\n\
+         - !replace @leaver @sub <alias>: owner only. Hand every nation the leaver has in the game to the sub, and DM them both\n\
+         - !nickname {<name>, --clear} <alias>: show a name of your choice instead of your discord name in this game\n\
+         - !nextturn <alias>: guess when the next turn will host from the timer and how long turns usually take\n\
+         - !resync [--prune] <alias>: admin only. List nations nobody has registered as and registrations for nations not in the game\n\
+         - !whois <nation> <alias>: who is registered as a nation (name or ID), and whether they've played this turn\n\
+         - !defaultera {EA/MA/LA, --clear}: the era !lobby uses in this channel when it isn't given one\n\
+         - !status: when the bot last managed to read each game's server, and why it can't if it's failing\n\
+         - !reminder {\"text\", --clear} <alias>: your own wording for new turn DMs, with %nation%, %turn% and %hours% filled in\n\
+         - !nationinfo <nation> [EA/MA/LA]: the era and ID of each nation with this ID or name\n\
+         - !countdown {on, off, <mins>,<mins>...} <alias>: ping players who haven't played this many minutes before the deadline. \"on\" is 60,15\n\
+         - !remindercap {<count>, all, default} <alias>: only get the last this many of the game's countdown reminders each turn\n\
+         - !defaultremindercap {<count>, all} <alias>: game owner only. How many countdown reminders players get if they haven't used !remindercap\n\
+         - !simulatereminder <mins> <alias>: owner or admin only. Which countdown would fire with this many minutes left and who it would reach, without sending it\n\
+         - !notify {newturn, stall, reminder, waiting} {channel, dm, both, off} <alias>: where that kind of announcement goes for the game\n\
+         - !webhook {<url>, --clear} <alias>: owner only. POST each new turn to this URL as JSON, for other tools\n\
+         - !startreminder {<hours>, off, default} <alias>: DM the host if all the pretenders have been in this long but the game hasn't started. Default 12\n\
+         - !stallgrace {<hours>, default} <alias>: how far past its timer and its usual turn length a turn has to go before the game is called stalled. Default 6\n\
+         - !all [<page>]: one line per game posting in this channel, with its turn, time left and how many still have to play\n\
+         - !urgent: every game in this server with the soonest deadline first, as of the last poll. Paused games and lobbies go last\n\
+         - !export <alias>: the game's saved state, registrations and last poll as JSON, to move it to another bot\n\
+         - !import: admin only. Recreate a game from the JSON of !export, attached or pasted after the command\n\
+         - !reserveai [--clear] <nation> <alias>: lobby owner only. Keep a nation for the AI so it doesn't take up a player slot\n\
+         - !stragglers <alias>: how often each nation was one of the last to get its turn in\n\
+         - !registerfor <@user> <nation_prefix> [EA|MA|LA] <alias>: lobby owner or admin only. Register somebody else for a nation\n\
+         - !played <alias>: while the server is down, say you've taken your turn. Shown as unverified in !details until the server is back\n\
+         - !pollinterval {<seconds>, default} <alias>: how often the bot checks the game's server, between 30s (15s for blitz games) and 1h. Default 60s, or 15s for blitz games\n\
+         - !blitz {on, off} <alias>: owner only. For games on timers of minutes: show the time left to the second, poll every 15s and give countdown pings to the second\n\
+         - !pausepolling {[<hours>], --clear} <alias>: stop checking the game's server while it's down for maintenance, for up to a week or until --clear\n\
+         - !archive <alias>: owner or admin only. Mark a game as finished. It stops being checked but keeps its players and turn history\n\
+         - !unarchive <alias>: start checking a finished game again\n\
+         - !autoarchive {<hours>, off}: admin only. Archive games here this many hours after only one nation is left\n\
+         - !cancelarchive <alias>: stop a finished looking game from being archived by !autoarchive\n\
+         - !late [<turns>] <alias>: ping the players who haven't played yet and were among the last for this many turns in a row before. Default 2\n\
+         - !locale {<locale>, --clear}: show nation names and labels in this channel in another language, where the bot has translations\n\
+         - !remindme {<time>, <time> before, --clear} <alias>: DM you once, e.g. \"2h\" from now or \"30m before\" the next host. --clear cancels it\n\
+         - !clearreminders: forget every !remindercap and !remindme you have in all your games, so the games' own defaults apply\n\
+         - !prefix {<prefix>, --clear}: admin only. Start commands in this server with something other than !\n\
+         - !alias [{<name> <command>, --clear <name>}]: admin only. Add a shorter name for a command in this server, e.g. !alias d details. Lists them without arguments\n\
+         - !detailsformat {embed, text}: admin only. Send !details in this server as plain text instead of an embed\n\
+         - !timeformat {exact, rounded, coarse}: admin only. How precisely !details, !all, !urgent, !undone and !late show the time left in this server\n\
+         - !confirmsubmissions {on, off}: admin only. Post a ✅ in a game's channel the first time each registered player's turn goes in\n\
+         - !nationset {<nation,...>, --clear} <alias>: lobby owner only. Say which nations the lobby is for, so !openslots and !details list the unclaimed ones\n\
+         - !openslots <alias>: which nations in a lobby nobody has registered as yet, or how many slots are left\n\
+         - !vacation {<yyyy-mm-dd>, --clear} <alias>: no new turn DMs until the day you're back, and !details shows you're away\n\
+         - !subscribe <alias>: post a game's announcements in this channel as well as the one it was created in\n\
+         - !unsubscribe <alias>: stop posting a game's announcements in this channel\n\
+         - !statusmessage [--clear] <alias>: post a pinned status message for the game here that gets edited every new turn. --clear stops updating it\n\
+         - !seticon {<emoji> [#rrggbb], #rrggbb, --clear} <alias>: owner only. An emoji in front of the game in !all and !games, and a colour for its !details\n\
+         - !eliminated <alias>: which nations have been defeated, and the turn each one was first seen dead\n\
+         - !ainations <alias>: which nations the AI is playing right now, and which of them went AI\n\
+         - !spectate <alias>: get a game's announcements by DM without playing in it\n\
+         - !unspectate <alias>: stop getting a game's announcements by DM\n\
+         - !joined <alias>: when each nation registered, and who's been in the game longest\n\
+         - !digest {<day> <hh:mm>, --clear}: a weekly summary of this channel's games, at that time in UTC\n\
+         - !previewreminder <alias>: what your next new turn DM for a game will say\n\
+         - !moveregistration <@user> <from_alias> <to_alias> [--copy]: admin only. Register somebody in a lobby as the nation they have in another game\n\
+         - !reliability <alias>: how often each player has had their turn in by the time it hosted\n\
+         - !providers <provider,...> <alias>: where to look up nation names from (snek, file), in order. \"default\" to reset\n\
+         - !games: list every game you're registered in, most urgent first\n\
+         - !todo: DMs you the turns you still have to play in all your games, most urgent first\n\
+         - !playernations @player: every game somebody's registered in and as what, flagging a nation they play in more than one\n\
+         - !eras: how many of the games the bot tracks are EA, MA and LA\n\
+         - !timezone <UTC+hh:mm> <alias>: show turn deadlines for a game in this timezone\n\
+         - !mytimezone {<UTC+hh:mm>, --clear}: say what timezone you're in, for !localdeadline\n\
+         - !localdeadline <alias>: when the turn ends for each player, and whether that's their morning, afternoon, evening or overnight\n\
+         - !undone <alias>: ping the registered players who haven't played this turn yet\n\
+         - !progress <alias>: which nations have submitted since you last checked, and how many are still to go\n\
+         - !kick @player <alias>: remove a player from your lobby\n\
+         - !roster {add, remove} @player <alias>: lobby owner only. Keep a slot for a player you're expecting. Once a lobby has a roster, anyone else who registers goes on a waitlist if it's full, and gets in when someone is removed\n\
+         - !changes <from_turn> [<to_turn>] <alias>: which nations died, went AI or turned up between two turns. Defaults to up to the latest turn\n\
+         - !streaks <alias>: how many turns in a row each nation has been in human hands, or when it died\n\
+         - !announce <message>: bot owners only. Post a one-off message, e.g. about a restart, once in every channel that gets announcements for a game\n\
+         - !watch [[--clear] <nation_id>] <alias>: get a DM when a nation in a started game goes AI, is eliminated or changes status some other way. Lists what you're watching without a nation. --clear stops watching it\n\
+         - !rawstatus <alias>: bot owners only. The game's server's status response as hex, and whether the bot can parse it, for bug reports\n\
+         - !adminrole {<@role>, --clear}: admin only. Members with this role count as admins for !archive, !rename, !setaddress and !registerfor\n\
+         - !channelgamelimit {<games>, default}: admin only. How many games each channel in this server can !add. Default 50\n\
+         - !playercount <num_players> <alias>: change how many players your lobby has room for\n\
+         Source is located at www.github.com/djmcgill/dominions-5-status Contributions welcome!";
@@ -85,6 +85,19 @@ fn search<I: InspectorCategory>(message: &Message, args: &Args) -> Result<(), Co
     Ok(())
 }
 
+/// What `with_search_commands` registers, for anything that needs to know
+/// whether a command exists
+pub fn search_command_names() -> [&'static str; 6] {
+    [
+        Item::show(),
+        Spell::show(),
+        Unit::show(),
+        Site::show(),
+        Merc::show(),
+        Event::show(),
+    ]
+}
+
 use serenity::framework::standard::StandardFramework;
 pub trait WithSearchCommands: Sized {
     fn get_standard_framework(self) -> StandardFramework;
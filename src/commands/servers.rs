@@ -1,5 +1,5 @@
 mod add_server;
-use self::add_server::add_server;
+use self::add_server::{add_server, add_servers};
 
 mod list_servers;
 use self::list_servers::*;
@@ -19,6 +19,24 @@ pub use self::details::*;
 mod details2;
 pub use self::details2::*;
 
+mod game_cache;
+pub use self::game_cache::*;
+
+mod details_cooldown;
+pub use self::details_cooldown::*;
+
+mod self_report;
+pub use self::self_report::*;
+
+mod nation_emojis;
+pub use self::nation_emojis::*;
+
+mod translations;
+pub use self::translations::*;
+
+mod guild_commands;
+pub use self::guild_commands::*;
+
 mod lobby;
 use self::lobby::*;
 
@@ -42,9 +60,314 @@ pub use self::turns2::turns2;
 mod unstart;
 pub use self::unstart::unstart;
 
+mod history;
+pub use self::history::*;
+
+mod providers;
+use self::providers::*;
+
+mod games;
+use self::games::games;
+
+mod timezone;
+pub use self::timezone::*;
+
+mod undone;
+use self::undone::undone;
+
+mod kick;
+use self::kick::kick;
+
+mod player_count;
+use self::player_count::player_count;
+
+mod turnstats;
+use self::turnstats::turnstats;
+
+mod probe;
+use self::probe::probe;
+
+mod json;
+use self::json::json;
+
+mod rename;
+use self::rename::rename;
+
+mod transfer;
+use self::transfer::transfer;
+
+mod nickname;
+use self::nickname::nickname;
+
+mod nextturn;
+use self::nextturn::nextturn;
+
+mod resync;
+use self::resync::resync;
+
+mod whois;
+use self::whois::whois;
+
+mod default_era;
+use self::default_era::default_era;
+
+mod status;
+use self::status::status;
+
+mod reminder;
+use self::reminder::reminder;
+
+mod nation_info;
+use self::nation_info::nation_info;
+
+mod countdown;
+use self::countdown::countdown;
+
+mod start_reminder;
+use self::start_reminder::start_reminder;
+
+mod all;
+use self::all::all;
+
+mod snapshot;
+use self::snapshot::{export, import};
+
+mod reserve_ai;
+use self::reserve_ai::reserve_ai;
+
+mod stragglers;
+use self::stragglers::stragglers;
+
+mod register_for;
+use self::register_for::register_for;
+
+mod poll_interval;
+use self::poll_interval::poll_interval;
+mod archive;
+use self::archive::{archive, unarchive};
+mod late;
+use self::late::late;
+mod locale;
+use self::locale::locale;
+mod remindme;
+use self::remindme::remindme;
+mod prefix;
+use self::prefix::{alias, prefix};
+mod details_format;
+use self::details_format::details_format;
+mod open_slots;
+use self::open_slots::{nation_set, open_slots};
+mod vacation;
+use self::vacation::vacation;
+mod subscribe;
+use self::subscribe::{subscribe, unsubscribe};
+mod seticon;
+use self::seticon::seticon;
+mod eliminated;
+use self::eliminated::eliminated;
+mod spectate;
+use self::spectate::{spectate, unspectate};
+mod joined;
+use self::joined::joined;
+mod digest;
+use self::digest::digest;
+mod previewreminder;
+use self::previewreminder::previewreminder;
+mod move_registration;
+use self::move_registration::move_registration;
+mod confirm_submissions;
+use self::confirm_submissions::confirm_submissions;
+mod reliability;
+use self::reliability::reliability;
+mod notify;
+use self::notify::notify;
+mod progress;
+use self::progress::progress;
+mod reminder_cap;
+use self::reminder_cap::reminder_cap;
+mod claim;
+use self::claim::claim;
+mod host_games;
+use self::host_games::host_games;
+mod stall_grace;
+use self::stall_grace::stall_grace;
+mod status_message;
+use self::status_message::status_message;
+mod roster;
+use self::roster::roster;
+mod changes;
+use self::changes::changes;
+mod announce;
+use self::announce::announce;
+mod watch;
+use self::watch::watch;
+mod raw_status;
+use self::raw_status::raw_status;
+mod channel_game_limit;
+use self::channel_game_limit::channel_game_limit;
+mod pause_polling;
+use self::pause_polling::pause_polling;
+mod leave;
+use self::leave::leave;
+mod local_deadline;
+use self::local_deadline::{local_deadline, my_timezone};
+mod default_reminder_cap;
+use self::default_reminder_cap::default_reminder_cap;
+mod player_nations;
+use self::player_nations::player_nations;
+mod ai_nations;
+use self::ai_nations::ai_nations;
+mod webhook;
+use self::webhook::webhook;
+mod time_format;
+use self::time_format::time_format;
+mod urgent;
+use self::urgent::urgent;
+mod turn_note;
+use self::turn_note::{turn_note, turn_notes};
+mod blitz;
+use self::blitz::blitz;
+mod replace;
+use self::replace::replace;
+mod clear_reminders;
+use self::clear_reminders::clear_reminders;
+mod eras;
+use self::eras::eras;
+mod auto_archive;
+use self::auto_archive::{auto_archive, cancel_archive};
+mod streaks;
+use self::streaks::streaks;
+mod admin_role;
+use self::admin_role::admin_role;
+mod todo;
+use self::todo::todo;
+mod set_address;
+use self::set_address::set_address;
+mod simulate_reminder;
+use self::simulate_reminder::simulate_reminder;
+
+use crate::db::DbConnection;
+use crate::model::{GameServerState, LobbyState};
+use crate::notifier::Notifier;
 use crate::server::ServerConnection;
-use serenity::framework::standard::{Args, StandardFramework};
+use serenity::framework::standard::{Args, CommandError, StandardFramework};
 use serenity::model::channel::Message;
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::permissions::Permissions;
+
+/// Everything `with_servers_commands` registers, for anything that needs to know
+/// whether a command exists
+pub const COMMAND_NAMES: &[&str] = &[
+    "add",
+    "addmany",
+    "probe",
+    "list",
+    "delete",
+    "details",
+    "details2",
+    "deets",
+    "deets2",
+    "register",
+    "register-id",
+    "unregister",
+    "turns",
+    "turns2",
+    "lobby",
+    "notifications",
+    "start",
+    "lobbies",
+    "describe",
+    "desc",
+    "unstart",
+    "history",
+    "providers",
+    "games",
+    "timezone",
+    "undone",
+    "kick",
+    "playercount",
+    "turnstats",
+    "json",
+    "rename",
+    "transfer",
+    "nickname",
+    "nextturn",
+    "resync",
+    "whois",
+    "defaultera",
+    "status",
+    "reminder",
+    "nationinfo",
+    "countdown",
+    "startreminder",
+    "stallgrace",
+    "all",
+    "export",
+    "import",
+    "reserveai",
+    "stragglers",
+    "registerfor",
+    "played",
+    "pollinterval",
+    "archive",
+    "unarchive",
+    "late",
+    "locale",
+    "remindme",
+    "prefix",
+    "alias",
+    "detailsformat",
+    "nationset",
+    "openslots",
+    "vacation",
+    "subscribe",
+    "unsubscribe",
+    "seticon",
+    "eliminated",
+    "spectate",
+    "unspectate",
+    "joined",
+    "digest",
+    "previewreminder",
+    "moveregistration",
+    "confirmsubmissions",
+    "reliability",
+    "notify",
+    "progress",
+    "remindercap",
+    "claim",
+    "hostgames",
+    "statusmessage",
+    "roster",
+    "changes",
+    "announce",
+    "watch",
+    "rawstatus",
+    "channelgamelimit",
+    "pausepolling",
+    "leave",
+    "mytimezone",
+    "localdeadline",
+    "defaultremindercap",
+    "playernations",
+    "ainations",
+    "webhook",
+    "timeformat",
+    "urgent",
+    "turnnote",
+    "turnnotes",
+    "blitz",
+    "replace",
+    "clearreminders",
+    "eras",
+    "autoarchive",
+    "cancelarchive",
+    "streaks",
+    "adminrole",
+    "todo",
+    "setaddress",
+    "simulatereminder",
+];
 
 pub trait WithServersCommands: Sized {
     fn get_standard_framework(self) -> StandardFramework;
@@ -53,6 +376,12 @@ pub trait WithServersCommands: Sized {
             .command("add", |c| {
                 c.bucket(bucket).exec(|cx, m, a| add_server::<C>(cx, m, a))
             })
+            .command("addmany", |c| {
+                c.bucket(bucket).exec(|cx, m, a| add_servers::<C>(cx, m, a))
+            })
+            .command("probe", |c| {
+                c.bucket(bucket).exec(|cx, m, a| probe::<C>(cx, m, a))
+            })
             .command("list", |c| {
                 c.bucket(bucket).exec(|cx, m, _| list_servers(cx, m))
             })
@@ -110,6 +439,269 @@ pub trait WithServersCommands: Sized {
             .command("unstart", |c| {
                 c.bucket(bucket).exec(|cx, m, a| unstart(cx, m, a))
             })
+            .command("history", |c| {
+                c.bucket(bucket).exec(|cx, m, a| history(cx, m, a))
+            })
+            .command("providers", |c| {
+                c.bucket(bucket).exec(|cx, m, a| providers(cx, m, a))
+            })
+            .command("games", |c| c.bucket(bucket).exec(|cx, m, _| games(cx, m)))
+            .command("timezone", |c| {
+                c.bucket(bucket).exec(|cx, m, a| timezone(cx, m, a))
+            })
+            .command("undone", |c| {
+                c.bucket(bucket).exec(|cx, m, a| undone(cx, m, a))
+            })
+            .command("kick", |c| c.bucket(bucket).exec(|cx, m, a| kick(cx, m, a)))
+            .command("playercount", |c| {
+                c.bucket(bucket).exec(|cx, m, a| player_count(cx, m, a))
+            })
+            .command("turnstats", |c| {
+                c.bucket(bucket).exec(|cx, m, a| turnstats(cx, m, a))
+            })
+            .command("json", |c| c.bucket(bucket).exec(|cx, m, a| json(cx, m, a)))
+            .command("rename", |c| {
+                c.bucket(bucket).exec(|cx, m, a| rename(cx, m, a))
+            })
+            .command("transfer", |c| {
+                c.bucket(bucket).exec(|cx, m, a| transfer(cx, m, a))
+            })
+            .command("nickname", |c| {
+                c.bucket(bucket).exec(|cx, m, a| nickname(cx, m, a))
+            })
+            .command("nextturn", |c| {
+                c.bucket(bucket).exec(|cx, m, a| nextturn(cx, m, a))
+            })
+            .command("resync", |c| {
+                c.bucket(bucket)
+                    .required_permissions(Permissions::ADMINISTRATOR)
+                    .exec(|cx, m, a| resync(cx, m, a))
+            })
+            .command("whois", |c| {
+                c.bucket(bucket).exec(|cx, m, a| whois(cx, m, a))
+            })
+            .command("defaultera", |c| {
+                c.bucket(bucket).exec(|cx, m, a| default_era(cx, m, a))
+            })
+            .command("status", |c| {
+                c.bucket(bucket).exec(|cx, m, a| status(cx, m, a))
+            })
+            .command("reminder", |c| {
+                c.bucket(bucket).exec(|cx, m, a| reminder(cx, m, a))
+            })
+            .command("nationinfo", |c| {
+                c.bucket(bucket).exec(|cx, m, a| nation_info(cx, m, a))
+            })
+            .command("countdown", |c| {
+                c.bucket(bucket).exec(|cx, m, a| countdown(cx, m, a))
+            })
+            .command("startreminder", |c| {
+                c.bucket(bucket).exec(|cx, m, a| start_reminder(cx, m, a))
+            })
+            .command("stallgrace", |c| {
+                c.bucket(bucket).exec(|cx, m, a| stall_grace(cx, m, a))
+            })
+            .command("all", |c| {
+                c.bucket(bucket).exec(|cx, m, a| all::<C>(cx, m, a))
+            })
+            .command("export", |c| {
+                c.bucket(bucket).exec(|cx, m, a| export(cx, m, a))
+            })
+            .command("import", |c| {
+                c.bucket(bucket)
+                    .required_permissions(Permissions::ADMINISTRATOR)
+                    .exec(|cx, m, a| import(cx, m, a))
+            })
+            .command("reserveai", |c| {
+                c.bucket(bucket).exec(|cx, m, a| reserve_ai(cx, m, a))
+            })
+            .command("stragglers", |c| {
+                c.bucket(bucket).exec(|cx, m, a| stragglers(cx, m, a))
+            })
+            .command("registerfor", |c| {
+                c.bucket(bucket).exec(|cx, m, a| register_for(cx, m, a))
+            })
+            .command("played", |c| {
+                c.bucket(bucket).exec(|cx, m, a| played(cx, m, a))
+            })
+            .command("pollinterval", |c| {
+                c.bucket(bucket).exec(|cx, m, a| poll_interval(cx, m, a))
+            })
+            .command("archive", |c| {
+                c.bucket(bucket).exec(|cx, m, a| archive(cx, m, a))
+            })
+            .command("unarchive", |c| {
+                c.bucket(bucket).exec(|cx, m, a| unarchive(cx, m, a))
+            })
+            .command("late", |c| c.bucket(bucket).exec(|cx, m, a| late(cx, m, a)))
+            .command("locale", |c| {
+                c.bucket(bucket).exec(|cx, m, a| locale(cx, m, a))
+            })
+            .command("remindme", |c| {
+                c.bucket(bucket).exec(|cx, m, a| remindme(cx, m, a))
+            })
+            .command("prefix", |c| {
+                c.bucket(bucket).exec(|cx, m, a| prefix(cx, m, a))
+            })
+            .command("alias", |c| {
+                c.bucket(bucket).exec(|cx, m, a| alias(cx, m, a))
+            })
+            .command("detailsformat", |c| {
+                c.bucket(bucket).exec(|cx, m, a| details_format(cx, m, a))
+            })
+            .command("nationset", |c| {
+                c.bucket(bucket).exec(|cx, m, a| nation_set(cx, m, a))
+            })
+            .command("openslots", |c| {
+                c.bucket(bucket).exec(|cx, m, a| open_slots(cx, m, a))
+            })
+            .command("vacation", |c| {
+                c.bucket(bucket).exec(|cx, m, a| vacation(cx, m, a))
+            })
+            .command("subscribe", |c| {
+                c.bucket(bucket).exec(|cx, m, a| subscribe(cx, m, a))
+            })
+            .command("unsubscribe", |c| {
+                c.bucket(bucket).exec(|cx, m, a| unsubscribe(cx, m, a))
+            })
+            .command("seticon", |c| {
+                c.bucket(bucket).exec(|cx, m, a| seticon(cx, m, a))
+            })
+            .command("eliminated", |c| {
+                c.bucket(bucket).exec(|cx, m, a| eliminated(cx, m, a))
+            })
+            .command("spectate", |c| {
+                c.bucket(bucket).exec(|cx, m, a| spectate(cx, m, a))
+            })
+            .command("unspectate", |c| {
+                c.bucket(bucket).exec(|cx, m, a| unspectate(cx, m, a))
+            })
+            .command("joined", |c| {
+                c.bucket(bucket).exec(|cx, m, a| joined(cx, m, a))
+            })
+            .command("digest", |c| {
+                c.bucket(bucket).exec(|cx, m, a| digest(cx, m, a))
+            })
+            .command("previewreminder", |c| {
+                c.bucket(bucket)
+                    .exec(|cx, m, a| previewreminder::<C>(cx, m, a))
+            })
+            .command("moveregistration", |c| {
+                c.bucket(bucket)
+                    .required_permissions(Permissions::ADMINISTRATOR)
+                    .exec(|cx, m, a| move_registration(cx, m, a))
+            })
+            .command("confirmsubmissions", |c| {
+                c.bucket(bucket)
+                    .exec(|cx, m, a| confirm_submissions(cx, m, a))
+            })
+            .command("reliability", |c| {
+                c.bucket(bucket).exec(|cx, m, a| reliability(cx, m, a))
+            })
+            .command("notify", |c| {
+                c.bucket(bucket).exec(|cx, m, a| notify(cx, m, a))
+            })
+            .command("progress", |c| {
+                c.bucket(bucket).exec(|cx, m, a| progress(cx, m, a))
+            })
+            .command("remindercap", |c| {
+                c.bucket(bucket).exec(|cx, m, a| reminder_cap(cx, m, a))
+            })
+            .command("claim", |c| {
+                c.bucket(bucket).exec(|cx, m, a| claim::<C>(cx, m, a))
+            })
+            .command("hostgames", |c| {
+                c.bucket(bucket).exec(|cx, m, a| host_games(cx, m, a))
+            })
+            .command("statusmessage", |c| {
+                c.bucket(bucket).exec(|cx, m, a| status_message(cx, m, a))
+            })
+            .command("roster", |c| {
+                c.bucket(bucket).exec(|cx, m, a| roster(cx, m, a))
+            })
+            .command("changes", |c| {
+                c.bucket(bucket).exec(|cx, m, a| changes(cx, m, a))
+            })
+            .command("announce", |c| {
+                c.owners_only(true).exec(|cx, m, a| announce(cx, m, a))
+            })
+            .command("watch", |c| {
+                c.bucket(bucket).exec(|cx, m, a| watch(cx, m, a))
+            })
+            .command("rawstatus", |c| {
+                c.owners_only(true)
+                    .exec(|cx, m, a| raw_status::<C>(cx, m, a))
+            })
+            .command("channelgamelimit", |c| {
+                c.bucket(bucket)
+                    .exec(|cx, m, a| channel_game_limit(cx, m, a))
+            })
+            .command("pausepolling", |c| {
+                c.bucket(bucket).exec(|cx, m, a| pause_polling(cx, m, a))
+            })
+            .command("leave", |c| {
+                c.bucket(bucket).exec(|cx, m, a| leave(cx, m, a))
+            })
+            .command("mytimezone", |c| {
+                c.bucket(bucket).exec(|cx, m, a| my_timezone(cx, m, a))
+            })
+            .command("localdeadline", |c| {
+                c.bucket(bucket).exec(|cx, m, a| local_deadline(cx, m, a))
+            })
+            .command("defaultremindercap", |c| {
+                c.bucket(bucket)
+                    .exec(|cx, m, a| default_reminder_cap(cx, m, a))
+            })
+            .command("playernations", |c| {
+                c.bucket(bucket).exec(|cx, m, a| player_nations(cx, m, a))
+            })
+            .command("ainations", |c| {
+                c.bucket(bucket).exec(|cx, m, a| ai_nations(cx, m, a))
+            })
+            .command("webhook", |c| {
+                c.bucket(bucket).exec(|cx, m, a| webhook(cx, m, a))
+            })
+            .command("timeformat", |c| {
+                c.bucket(bucket).exec(|cx, m, a| time_format(cx, m, a))
+            })
+            .command("urgent", |c| {
+                c.bucket(bucket).exec(|cx, m, a| urgent(cx, m, a))
+            })
+            .command("turnnote", |c| {
+                c.bucket(bucket).exec(|cx, m, a| turn_note(cx, m, a))
+            })
+            .command("turnnotes", |c| {
+                c.bucket(bucket).exec(|cx, m, a| turn_notes(cx, m, a))
+            })
+            .command("blitz", |c| {
+                c.bucket(bucket).exec(|cx, m, a| blitz(cx, m, a))
+            })
+            .command("replace", |c| {
+                c.bucket(bucket).exec(|cx, m, a| replace(cx, m, a))
+            })
+            .command("clearreminders", |c| {
+                c.bucket(bucket).exec(|cx, m, a| clear_reminders(cx, m, a))
+            })
+            .command("eras", |c| c.bucket(bucket).exec(|cx, m, a| eras(cx, m, a)))
+            .command("autoarchive", |c| {
+                c.bucket(bucket).exec(|cx, m, a| auto_archive(cx, m, a))
+            })
+            .command("cancelarchive", |c| {
+                c.bucket(bucket).exec(|cx, m, a| cancel_archive(cx, m, a))
+            })
+            .command("streaks", |c| {
+                c.bucket(bucket).exec(|cx, m, a| streaks(cx, m, a))
+            })
+            .command("adminrole", |c| {
+                c.bucket(bucket).exec(|cx, m, a| admin_role(cx, m, a))
+            })
+            .command("todo", |c| c.bucket(bucket).exec(|cx, m, a| todo(cx, m, a)))
+            .command("setaddress", |c| {
+                c.bucket(bucket).exec(|cx, m, a| set_address::<C>(cx, m, a))
+            })
+            .command("simulatereminder", |c| {
+                c.bucket(bucket).exec(simulate_reminder)
+            })
     }
 }
 
@@ -119,6 +711,112 @@ impl WithServersCommands for StandardFramework {
     }
 }
 
+/// For commands that only the lobby owner is allowed to use
+fn lobby_state_for_alias(db_conn: &DbConnection, alias: &str) -> Result<LobbyState, CommandError> {
+    let server = db_conn.game_for_alias(alias)?;
+    match server.state {
+        GameServerState::Lobby(lobby_state) => Ok(lobby_state),
+        GameServerState::StartedState(_, _) => Err(CommandError::from(format!(
+            "{} has already started, this can only be done to lobbies",
+            alias
+        ))),
+        GameServerState::Archived(_, _) => Err(CommandError::from(format!(
+            "{} has finished, this can only be done to lobbies",
+            alias
+        ))),
+    }
+}
+
+fn lobby_state_for_owner(
+    db_conn: &DbConnection,
+    alias: &str,
+    author_id: UserId,
+) -> Result<LobbyState, CommandError> {
+    let lobby_state = lobby_state_for_alias(db_conn, alias)?;
+    if lobby_state.owner != author_id {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} can do this",
+            alias
+        )));
+    }
+    Ok(lobby_state)
+}
+
+/// Whether the author can manage `alias` as if they owned it: it posts in this
+/// server, and they're one of its admins or have the role set with `!adminrole`
+fn author_is_game_admin<N: Notifier>(
+    db_conn: &DbConnection,
+    message: &Message,
+    alias: &str,
+) -> Result<bool, CommandError> {
+    let guild = match message.guild() {
+        Some(guild) => guild,
+        None => return Ok(false),
+    };
+    let guild = guild.read();
+    let in_guild = db_conn
+        .notification_channels_for_alias(alias)?
+        .into_iter()
+        .any(|channel_id| N::guild_for_channel(channel_id) == Some(guild.id));
+    if !in_guild {
+        return Ok(false);
+    }
+    if guild.member_permissions(message.author.id).administrator() {
+        return Ok(true);
+    }
+    let role_id = match db_conn.admin_role_for_guild(guild.id)? {
+        Some(role_id) => role_id,
+        None => return Ok(false),
+    };
+    // Messages come with the author's roles, the cache might not have them
+    let roles = match &message.member {
+        Some(member) => member.roles.clone(),
+        None => guild
+            .members
+            .get(&message.author.id)
+            .map(|member| member.roles.clone())
+            .unwrap_or_default(),
+    };
+    Ok(roles.contains(&role_id))
+}
+
+/// For commands that the game's owner or an admin (see `author_is_game_admin`)
+/// can use
+fn check_owner_or_admin(
+    db_conn: &DbConnection,
+    alias: &str,
+    author_id: UserId,
+    author_is_admin: bool,
+) -> Result<(), CommandError> {
+    // Games added with !add have nobody in charge of them, so anyone can
+    let option_owner = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(lobby_state) => Some(lobby_state.owner),
+        GameServerState::StartedState(_, option_lobby_state)
+        | GameServerState::Archived(_, option_lobby_state) => {
+            option_lobby_state.map(|lobby_state| lobby_state.owner)
+        }
+    };
+    if !author_is_admin && option_owner.is_some_and(|owner| owner != author_id) {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} or a server admin can do this",
+            alias
+        )));
+    }
+    Ok(())
+}
+
+/// For commands that change how the bot behaves for everyone in the server
+fn admin_guild_id(message: &Message) -> Result<GuildId, CommandError> {
+    let guild = message
+        .guild()
+        .ok_or("This can only be changed in a server, not in DMs")?;
+    let guild = guild.read();
+    if !guild.member_permissions(message.author.id).administrator() {
+        return Err(CommandError::from("Only server admins can change this"));
+    }
+    Ok(guild.id)
+}
+
 fn alias_from_arg_or_channel_name(args: &mut Args, message: &Message) -> Result<String, String> {
     let result_alias = if !args.is_empty() {
         args.single_quoted::<String>().ok()
@@ -1,22 +1,117 @@
 use serenity::framework::standard::{Args, CommandError};
 use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, GuildId};
 use serenity::prelude::Context;
 
 use super::alias_from_arg_or_channel_name;
+use super::channel_game_limit::check_channel_game_limit;
 use crate::db::{DbConnection, DbConnectionKey};
-use crate::model::{GameServer, GameServerState, StartedState};
-use crate::server::ServerConnection;
+use crate::model::enums::Era;
+use crate::model::{GameData, GameServer, GameServerState, StartedState};
+use crate::server::{normalise_server_address, ServerConnection};
 use log::*;
 
 #[cfg(test)]
 mod tests;
 
+/// Long enough for a channel name, short enough to fit in a line of `!games`
+const MAX_GAME_ALIAS_LEN: usize = 64;
+/// Quotes and backticks break argument parsing and formatting, and the rest make
+/// mentions when a message shows the alias
+const RESERVED_ALIAS_CHARS: &[char] = &['"', '`', '<', '>', '@', '\\'];
+
+/// Every command refers to the game by this, so it has to survive being typed in
+/// as an argument and shown back in a message
+pub fn validate_game_alias(alias: &str) -> Result<(), CommandError> {
+    if alias.trim().is_empty() {
+        return Err(CommandError::from("A game's alias can't be empty"));
+    }
+    if alias.chars().count() > MAX_GAME_ALIAS_LEN {
+        return Err(CommandError::from(format!(
+            "A game's alias can be at most {} characters",
+            MAX_GAME_ALIAS_LEN
+        )));
+    }
+    if let Some(c) = alias
+        .chars()
+        .find(|c| RESERVED_ALIAS_CHARS.contains(c) || c.is_control())
+    {
+        return Err(CommandError::from(format!(
+            "A game's alias can't have {:?} in it",
+            c
+        )));
+    }
+    // Commands would read it as a flag like --clear instead of the alias
+    if alias.starts_with("--") {
+        return Err(CommandError::from("A game's alias can't start with --"));
+    }
+    Ok(())
+}
+
+/// The parts of a server's `GameData` worth showing before adding it
+#[derive(Debug, PartialEq, Eq)]
+pub struct GameDataSummary {
+    pub game_name: String,
+    pub turn: i32,
+    pub phase: ServerPhase,
+    pub nation_count: usize,
+    /// `None` if no nations have been picked yet
+    pub option_era: Option<String>,
+}
+
+/// Whether a server is still waiting for pretenders or has started hosting turns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerPhase {
+    Lobby,
+    Started,
+}
+
+impl ServerPhase {
+    /// Servers count turns from 1 once the game has started, and report zero or
+    /// less while pretenders are still being uploaded
+    pub fn from_turn(turn: i32) -> Self {
+        if turn > 0 {
+            ServerPhase::Started
+        } else {
+            ServerPhase::Lobby
+        }
+    }
+}
+
+impl GameDataSummary {
+    pub fn from_game_data(game_data: &GameData) -> Self {
+        GameDataSummary {
+            game_name: game_data.game_name.clone(),
+            turn: game_data.turn,
+            phase: ServerPhase::from_turn(game_data.turn),
+            nation_count: game_data.nations.len(),
+            option_era: game_data.nations.first().map(|nation| nation.era.clone()),
+        }
+    }
+}
+
+/// Checks there's a game at the address, without touching the db
+pub fn probe_server<C: ServerConnection>(server_address: &str) -> Result<GameData, CommandError> {
+    Ok(C::get_game_data(server_address)?)
+}
+
 fn add_server_helper<C: ServerConnection>(
     server_address: &str,
     game_alias: &str,
     db_connection: &DbConnection,
+    option_guild_id: Option<GuildId>,
+    channel_id: ChannelId,
 ) -> Result<(), CommandError> {
-    let game_data = C::get_game_data(server_address)?;
+    validate_game_alias(game_alias)?;
+    check_channel_game_limit(db_connection, option_guild_id, channel_id)?;
+    // Better to find out about a typo now than when the poller can't connect
+    let server_address = &normalise_server_address(server_address)?;
+    let game_data = probe_server::<C>(server_address)?;
+    // There's no lobby to remember it, and it's unknown until nations are picked
+    let option_era = game_data
+        .nations
+        .first()
+        .and_then(|nation| Era::from_string(&nation.era));
 
     let server = GameServer {
         alias: game_alias.to_string(),
@@ -24,26 +119,53 @@ fn add_server_helper<C: ServerConnection>(
             StartedState {
                 address: server_address.to_string(),
                 last_seen_turn: game_data.turn,
+                option_snek_game_id: None,
             },
             None,
         ),
     };
 
-    db_connection.insert_game_server(&server).map_err(|e| {
-        if e.to_string()
-            .contains("UNIQUE constraint failed: game_servers.alias")
-        {
-            CommandError::from(format!(
-                "A game called '{}' already exists, if you are starting a lobby use !start",
-                game_alias
-            ))
-        } else {
-            CommandError::from(e)
-        }
-    })?;
+    // Nothing is written until the probe has worked, and then all in one go
+    db_connection
+        .insert_game_server_with_channel(&server, channel_id, option_era)
+        .map_err(|e| {
+            if e.to_string()
+                .contains("UNIQUE constraint failed: game_servers.alias")
+            {
+                CommandError::from(format!(
+                    "A game called '{}' already exists, if you are starting a lobby use !start",
+                    game_alias
+                ))
+            } else {
+                CommandError::from(e)
+            }
+        })?;
     Ok(())
 }
 
+/// Adds each (address, alias) pair on its own, so one server being down doesn't
+/// stop the rest from being added. Each one is atomic by itself.
+fn add_servers_helper<C: ServerConnection>(
+    addresses_and_aliases: &[(String, String)],
+    db_connection: &DbConnection,
+    option_guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+) -> Vec<(String, Result<(), CommandError>)> {
+    addresses_and_aliases
+        .iter()
+        .map(|(server_address, alias)| {
+            let result = add_server_helper::<C>(
+                server_address,
+                alias,
+                db_connection,
+                option_guild_id,
+                channel_id,
+            );
+            (alias.clone(), result)
+        })
+        .collect()
+}
+
 pub fn add_server<C: ServerConnection>(
     context: &mut Context,
     message: &Message,
@@ -63,9 +185,57 @@ pub fn add_server<C: ServerConnection>(
     let db_connection = data
         .get::<DbConnectionKey>()
         .ok_or("No DbConnection was created on startup. This is a bug.")?;
-    add_server_helper::<C>(&server_address, &alias, db_connection)?;
+    add_server_helper::<C>(
+        &server_address,
+        &alias,
+        db_connection,
+        message.guild_id,
+        message.channel_id,
+    )?;
     let text = format!("Successfully inserted with alias {}", alias);
     let _ = message.reply(&text);
     info!("{}", text);
     Ok(())
 }
+
+pub fn add_servers<C: ServerConnection>(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let mut addresses_and_aliases = vec![];
+    while !args.is_empty() {
+        let server_address = args.single_quoted::<String>()?;
+        let alias = args
+            .single_quoted::<String>()
+            .map_err(|_| CommandError::from(format!("No alias given for {}", server_address)))?;
+        addresses_and_aliases.push((server_address, alias.to_lowercase()));
+    }
+    if addresses_and_aliases.is_empty() {
+        return Err(CommandError::from(
+            "Give at least one address and alias, e.g. !addmany host:1234 game1 host:1235 game2",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_connection = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let results = add_servers_helper::<C>(
+        &addresses_and_aliases,
+        db_connection,
+        message.guild_id,
+        message.channel_id,
+    );
+    let text = results
+        .iter()
+        .map(|(alias, result)| match result {
+            Ok(()) => format!("{}: added", alias),
+            Err(e) => format!("{}: FAILED ({})", alias, e.0),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    info!("addmany: {}", text);
+    let _ = message.reply(&text);
+    Ok(())
+}
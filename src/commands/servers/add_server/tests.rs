@@ -3,16 +3,64 @@ use super::*;
 use crate::model::GameData;
 use crate::{mock_conditional_server_connection, mock_server_connection};
 use lazy_static::lazy_static;
+use serenity::model::id::{ChannelId, GuildId};
 use std::io;
 
 #[test]
 fn should_return_error_on_no_connection() {
     mock_server_connection!(Mock, Err(io::Error::from_raw_os_error(-1)));
 
-    let result = add_server_helper::<Mock>("", "", &DbConnection::noop());
+    let result = add_server_helper::<Mock>("", "foo", &DbConnection::noop(), None, ChannelId(1));
     assert!(result.is_err());
 }
 
+#[test]
+fn should_reject_aliases_commands_cannot_use() {
+    mock_server_connection!(
+        Mock,
+        Ok::<_, io::Error>(GameData {
+            game_name: "foo".to_owned(),
+            nations: Vec::new(),
+            turn: 1,
+            turn_timer: 0,
+        })
+    );
+
+    let db_conn = DbConnection::test();
+    let servers_before = db_conn.count_servers();
+    let too_long = "a".repeat(MAX_GAME_ALIAS_LEN + 1);
+    for alias in &[
+        "",
+        "   ",
+        too_long.as_str(),
+        "say \"hi\"",
+        "<@123>",
+        "@everyone",
+        "two\nlines",
+        "--clear",
+    ] {
+        let err = add_server_helper::<Mock>("address:1234", alias, &db_conn, None, ChannelId(1))
+            .unwrap_err();
+        assert!(
+            err.0.starts_with("A game's alias"),
+            "{:?}: {}",
+            alias,
+            err.0
+        );
+    }
+    assert_eq!(db_conn.count_servers(), servers_before);
+
+    // spaces, emoji codes and channel names are all fine
+    for alias in &[
+        "my game",
+        ":butts:",
+        "dom-5-game",
+        &"a".repeat(MAX_GAME_ALIAS_LEN),
+    ] {
+        assert!(validate_game_alias(alias).is_ok(), "{:?}", alias);
+    }
+}
+
 #[test]
 fn should_insert_started_server_into_db() {
     static TEST_ADDRESS: &'static str = "address:1234";
@@ -36,7 +84,8 @@ fn should_insert_started_server_into_db() {
     });
 
     let db_conn = DbConnection::test();
-    let insert_result = add_server_helper::<Mock>(&TEST_ADDRESS, &TEST_ALIAS, &db_conn);
+    let insert_result =
+        add_server_helper::<Mock>(&TEST_ADDRESS, &TEST_ALIAS, &db_conn, None, ChannelId(1));
     println!("RESULT {:?}", insert_result);
     assert!(insert_result.is_ok());
 
@@ -49,6 +98,7 @@ fn should_insert_started_server_into_db() {
             StartedState {
                 last_seen_turn: TEST_GAMEDATA.turn,
                 address: TEST_ADDRESS.to_owned(),
+                option_snek_game_id: None,
             },
             None,
         ),
@@ -56,3 +106,225 @@ fn should_insert_started_server_into_db() {
 
     assert_eq!(fetch_result.unwrap(), expected_result);
 }
+
+#[test]
+fn should_remember_channel_for_game() {
+    let db_conn = DbConnection::test();
+    // Games from before channels were tracked don't have one
+    assert_eq!(db_conn.channel_for_alias("test server 1").unwrap(), None);
+
+    db_conn
+        .set_channel_for_alias("test server 1", ChannelId(1234))
+        .unwrap();
+    assert_eq!(
+        db_conn.channel_for_alias("test server 1").unwrap(),
+        Some(ChannelId(1234))
+    );
+    assert!(db_conn
+        .set_channel_for_alias("not a game", ChannelId(1234))
+        .is_err());
+}
+
+#[test]
+fn should_reject_malformed_address_before_connecting() {
+    mock_server_connection!(
+        Mock,
+        Ok::<_, io::Error>(GameData {
+            game_name: "foo".to_owned(),
+            nations: Vec::new(),
+            turn: 1,
+            turn_timer: 0,
+        })
+    );
+
+    let db_conn = DbConnection::test();
+    let err =
+        add_server_helper::<Mock>("[::1:1234", "foo", &db_conn, None, ChannelId(1)).unwrap_err();
+    assert!(format!("{:?}", err).contains("Invalid server address"));
+    assert!(db_conn.game_for_alias("foo").is_err());
+}
+
+#[test]
+fn should_remember_the_era_the_server_reports() {
+    use crate::model::enums::{NationStatus, SubmissionStatus};
+    use crate::model::Nation;
+
+    mock_conditional_server_connection!(Mock, |server_address| {
+        if server_address == "era.test:1234" {
+            Ok(GameData {
+                game_name: "era".to_owned(),
+                nations: vec![Nation {
+                    id: 49,
+                    status: NationStatus::Human,
+                    submitted: SubmissionStatus::NotSubmitted,
+                    connected: false,
+                    name: "Ulm".to_owned(),
+                    era: "MA".to_owned(),
+                }],
+                turn: 4,
+                turn_timer: 0,
+            })
+        } else {
+            Err(io::Error::from_raw_os_error(-1))
+        }
+    });
+
+    let db_conn = DbConnection::test();
+    add_server_helper::<Mock>("era.test:1234", "era", &db_conn, None, ChannelId(1)).unwrap();
+    assert_eq!(
+        db_conn.started_eras().unwrap().get("era"),
+        Some(&Era::Middle)
+    );
+}
+
+#[test]
+fn should_store_normalised_ipv6_address() {
+    mock_conditional_server_connection!(Mock, |server_address| {
+        if server_address == "[::1]:2556" {
+            Ok(GameData {
+                game_name: "foo".to_owned(),
+                nations: Vec::new(),
+                turn: 1,
+                turn_timer: 0,
+            })
+        } else {
+            Err(io::Error::from_raw_os_error(-1))
+        }
+    });
+
+    let db_conn = DbConnection::test();
+    add_server_helper::<Mock>("::1", "foo", &db_conn, None, ChannelId(1)).unwrap();
+    match db_conn.game_for_alias("foo").unwrap().state {
+        GameServerState::StartedState(started_state, _) => {
+            assert_eq!(started_state.address, "[::1]:2556")
+        }
+        GameServerState::Lobby(_) | GameServerState::Archived(_, _) => {
+            panic!("foo should have started")
+        }
+    }
+}
+
+#[test]
+fn should_add_the_servers_that_answer_and_report_the_rest() {
+    lazy_static! {
+        static ref TEST_GAMEDATA: GameData = GameData {
+            game_name: "up".to_owned(),
+            nations: Vec::new(),
+            turn: 32,
+            turn_timer: 3 * 360,
+        };
+    }
+    mock_conditional_server_connection!(Mock, |server_address| {
+        if server_address == "up.example.com:1234" {
+            Ok(TEST_GAMEDATA.clone())
+        } else {
+            Err(io::Error::from_raw_os_error(-1))
+        }
+    });
+
+    let db_conn = DbConnection::test();
+    let addresses_and_aliases = vec![
+        ("up.example.com:1234".to_owned(), "up".to_owned()),
+        ("down.example.com:1234".to_owned(), "down".to_owned()),
+    ];
+    let results =
+        add_servers_helper::<Mock>(&addresses_and_aliases, &db_conn, None, ChannelId(1234));
+
+    assert_eq!(results[0].0, "up");
+    assert!(results[0].1.is_ok());
+    assert_eq!(results[1].0, "down");
+    assert!(results[1].1.is_err());
+    // the failure didn't undo the success
+    assert_eq!(
+        db_conn.channel_for_alias("up").unwrap(),
+        Some(ChannelId(1234))
+    );
+    assert!(db_conn.game_for_alias("down").is_err());
+}
+
+#[test]
+fn should_leave_nothing_behind_when_insert_fails_after_probe() {
+    mock_server_connection!(
+        Mock,
+        Ok::<_, io::Error>(GameData {
+            game_name: "foo".to_owned(),
+            nations: Vec::new(),
+            turn: 3,
+            turn_timer: 0,
+        })
+    );
+
+    let db_conn = DbConnection::test();
+    let servers_before = db_conn.count_servers();
+    let started_servers_before = db_conn.count_started_server_state();
+    let game_before = db_conn.game_for_alias("test server 1").unwrap();
+
+    // The probe works, but the alias is taken so the insert doesn't
+    let err = add_server_helper::<Mock>(
+        "new.example.com:1234",
+        "test server 1",
+        &db_conn,
+        None,
+        ChannelId(331),
+    )
+    .unwrap_err();
+    assert!(format!("{:?}", err).contains("already exists"));
+    assert_eq!(db_conn.count_servers(), servers_before);
+    assert_eq!(db_conn.count_started_server_state(), started_servers_before);
+    assert_eq!(
+        db_conn.game_for_alias("test server 1").unwrap(),
+        game_before
+    );
+    assert_eq!(db_conn.channel_for_alias("test server 1").unwrap(), None);
+
+    // and trying again under another name goes through cleanly
+    add_server_helper::<Mock>(
+        "new.example.com:1234",
+        "foo",
+        &db_conn,
+        None,
+        ChannelId(331),
+    )
+    .unwrap();
+    assert_eq!(db_conn.count_servers(), servers_before + 1);
+    assert_eq!(
+        db_conn.channel_for_alias("foo").unwrap(),
+        Some(ChannelId(331))
+    );
+}
+
+#[test]
+fn should_not_add_past_the_channel_game_limit() {
+    mock_server_connection!(
+        Mock,
+        Ok::<_, io::Error>(GameData {
+            game_name: "foo".to_owned(),
+            nations: Vec::new(),
+            turn: 3,
+            turn_timer: 0,
+        })
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_max_games_per_channel_for_guild(GuildId(3584), Some(1))
+        .unwrap();
+    add_server_helper::<Mock>(
+        "new.example.com:1234",
+        "first",
+        &db_conn,
+        Some(GuildId(3584)),
+        ChannelId(3585),
+    )
+    .unwrap();
+    let err = add_server_helper::<Mock>(
+        "new.example.com:1235",
+        "second",
+        &db_conn,
+        Some(GuildId(3584)),
+        ChannelId(3585),
+    )
+    .unwrap_err();
+    assert!(err.0.contains("as many as this server allows"));
+    assert!(db_conn.game_for_alias("second").is_err());
+}
@@ -0,0 +1,58 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::{GuildId, RoleId};
+use serenity::prelude::Context;
+
+use super::admin_guild_id;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of a role to stop using one
+const CLEAR_FLAG: &str = "--clear";
+
+fn admin_role_helper(
+    db_conn: &DbConnection,
+    guild_id: GuildId,
+    arg: &str,
+) -> Result<String, CommandError> {
+    if arg == CLEAR_FLAG {
+        db_conn.set_admin_role_for_guild(guild_id, None)?;
+        return Ok("Only server admins and each game's owner can manage games here now".to_owned());
+    }
+    let role_id = arg
+        .parse::<RoleId>()
+        .map_err(|_| CommandError::from("Expected an @mention of a role, or --clear"))?;
+    db_conn.set_admin_role_for_guild(guild_id, Some(role_id))?;
+    Ok(format!(
+        "Anyone with <@&{}> can now !archive, !rename, !setaddress and !registerfor any game posting in this server, as if they owned it",
+        role_id
+    ))
+}
+
+pub fn admin_role(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected an @mention of a role, or --clear"))?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let guild_id = admin_guild_id(message)?;
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = admin_role_helper(db_conn, guild_id, &arg)?;
+    info!("admin_role: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn should_take_a_role_mention_or_id() {
+    let db_conn = DbConnection::test();
+    assert!(admin_role_helper(&db_conn, GuildId(3861), "<@&123>")
+        .unwrap()
+        .contains("<@&123>"));
+    assert_eq!(
+        db_conn.admin_role_for_guild(GuildId(3861)).unwrap(),
+        Some(RoleId(123))
+    );
+    admin_role_helper(&db_conn, GuildId(3861), "456").unwrap();
+    assert_eq!(
+        db_conn.admin_role_for_guild(GuildId(3861)).unwrap(),
+        Some(RoleId(456))
+    );
+    // Only this server's
+    assert_eq!(db_conn.admin_role_for_guild(GuildId(3862)).unwrap(), None);
+}
+
+#[test]
+fn should_forget_the_role_when_cleared() {
+    let db_conn = DbConnection::test();
+    admin_role_helper(&db_conn, GuildId(3863), "<@&123>").unwrap();
+    admin_role_helper(&db_conn, GuildId(3863), "--clear").unwrap();
+    assert_eq!(db_conn.admin_role_for_guild(GuildId(3863)).unwrap(), None);
+}
+
+#[test]
+fn should_refuse_anything_that_isnt_a_role() {
+    let db_conn = DbConnection::test();
+    assert!(admin_role_helper(&db_conn, GuildId(3864), "everyone").is_err());
+    assert_eq!(db_conn.admin_role_for_guild(GuildId(3864)).unwrap(), None);
+}
@@ -0,0 +1,75 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::undone::playing_state_for_alias;
+use crate::commands::servers::{GameCacheKey, PlayingState, PotentialPlayer};
+use crate::db::DbConnectionKey;
+use crate::model::enums::NationStatus;
+
+#[cfg(test)]
+mod tests;
+
+/// The AI nations still in the game. Independents and the dead aren't, see
+/// `!eliminated` for those.
+fn show_ai_nations(alias: &str, playing_state: &PlayingState) -> String {
+    let ai_lines: Vec<String> = playing_state
+        .players
+        .iter()
+        .filter_map(|potential_player| match potential_player {
+            PotentialPlayer::RegisteredAndGame(_, details) | PotentialPlayer::GameOnly(details)
+                if details.player_status == NationStatus::AI =>
+            {
+                Some(if details.went_ai {
+                    format!(
+                        "- {} ({}), was played by a registered player",
+                        details.nation_name, details.nation_id
+                    )
+                } else {
+                    format!("- {} ({})", details.nation_name, details.nation_id)
+                })
+            }
+            _ => None,
+        })
+        .collect();
+    if ai_lines.is_empty() {
+        return format!(
+            "No nations are being played by the AI in {} on turn {}",
+            alias, playing_state.turn
+        );
+    }
+    format!(
+        "AI nations in {} on turn {}:\n{}",
+        alias,
+        playing_state.turn,
+        ai_lines.join("\n")
+    )
+}
+
+pub fn ai_nations(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let playing_state = playing_state_for_alias(db_conn, game_cache, &alias)?;
+    let text = show_ai_nations(&alias, &playing_state);
+    info!("ai_nations: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
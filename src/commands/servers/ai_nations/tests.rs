@@ -0,0 +1,59 @@
+use super::*;
+
+use crate::commands::servers::PlayerDetails;
+use crate::model::enums::SubmissionStatus;
+use serenity::model::id::UserId;
+
+fn details(nation_id: u32, player_status: NationStatus, went_ai: bool) -> PlayerDetails {
+    PlayerDetails {
+        nation_id,
+        nation_name: format!("nation {}", nation_id),
+        submitted: SubmissionStatus::NotSubmitted,
+        player_status,
+        went_ai,
+        connected: false,
+    }
+}
+
+fn playing_state(players: Vec<PotentialPlayer>) -> PlayingState {
+    PlayingState {
+        players,
+        turn: 20,
+        mins_remaining: 0,
+        hours_remaining: 1,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: None,
+        paused: false,
+        blitz: false,
+    }
+}
+
+#[test]
+fn should_list_only_ai_nations_noting_the_ones_that_went_ai() {
+    let state = playing_state(vec![
+        PotentialPlayer::RegisteredAndGame(UserId(1), details(1, NationStatus::Human, false)),
+        PotentialPlayer::RegisteredAndGame(UserId(2), details(2, NationStatus::AI, true)),
+        PotentialPlayer::GameOnly(details(3, NationStatus::AI, false)),
+        PotentialPlayer::GameOnly(details(4, NationStatus::Independent, false)),
+        PotentialPlayer::GameOnly(details(5, NationStatus::Defeated, false)),
+        PotentialPlayer::RegisteredOnly(UserId(6), 6, "nation 6".to_owned()),
+    ]);
+    assert_eq!(
+        show_ai_nations("foo", &state),
+        "AI nations in foo on turn 20:\n- nation 2 (2), was played by a registered player\n- nation 3 (3)"
+    );
+}
+
+#[test]
+fn should_say_when_nobody_is_ai() {
+    let state = playing_state(vec![PotentialPlayer::GameOnly(details(
+        1,
+        NationStatus::Human,
+        false,
+    ))]);
+    assert_eq!(
+        show_ai_nations("foo", &state),
+        "No nations are being played by the AI in foo on turn 20"
+    );
+}
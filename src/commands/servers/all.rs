@@ -0,0 +1,140 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+
+use super::time_format::time_format_for_message;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::SubmissionStatus;
+use crate::server::ServerConnection;
+
+#[cfg(test)]
+mod tests;
+
+/// Each game on the page can mean a server poll, so keep pages short
+const GAMES_PER_PAGE: usize = 10;
+
+/// Humans in the game who haven't touched their turn yet, registered or not
+pub fn undone_count(playing_state: &PlayingState) -> usize {
+    playing_state
+        .players
+        .iter()
+        .filter(|potential_player| match potential_player {
+            PotentialPlayer::RegisteredAndGame(_, details) | PotentialPlayer::GameOnly(details) => {
+                details.player_status.is_human()
+                    && details.submitted == SubmissionStatus::NotSubmitted
+            }
+            PotentialPlayer::RegisteredOnly(_, _, _) => false,
+        })
+        .count()
+}
+
+pub fn summary_line(alias: &str, details: &GameDetails, time_format: TimeFormat) -> String {
+    match &details.nations {
+        NationDetails::Lobby(lobby_details) => format!(
+            "{}: lobby, {} registered, {}",
+            alias,
+            lobby_details.players.len(),
+            show_open_slots(lobby_details.remaining_slots)
+        ),
+        NationDetails::Started(started_details) => match &started_details.state {
+            StartedStateDetails::Uploading(uploading_state) => {
+                let progress = uploading_state.upload_progress();
+                format!(
+                    "{}: uploading, {}/{} registered pretenders in",
+                    alias, progress.uploaded, progress.expected
+                )
+            }
+            StartedStateDetails::Playing(playing_state) => format!(
+                "{}: turn {} ({}), {} undone",
+                alias,
+                playing_state.turn,
+                playing_state.show_time_remaining_as(time_format),
+                undone_count(playing_state)
+            ),
+        },
+    }
+}
+
+/// The aliases on 1-based `page`, and how many pages there are
+pub fn page_of_aliases(
+    aliases: &[String],
+    page: usize,
+) -> Result<(&[String], usize), CommandError> {
+    let num_pages = aliases.len().div_ceil(GAMES_PER_PAGE);
+    if page == 0 || page > num_pages {
+        return Err(CommandError::from(format!(
+            "Page must be between 1 and {}",
+            num_pages
+        )));
+    }
+    let start = (page - 1) * GAMES_PER_PAGE;
+    let end = usize::min(start + GAMES_PER_PAGE, aliases.len());
+    Ok((&aliases[start..end], num_pages))
+}
+
+fn all_helper<C: ServerConnection>(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    channel_id: ChannelId,
+    page: usize,
+    time_format: TimeFormat,
+) -> Result<String, CommandError> {
+    let aliases = db_conn.aliases_for_channel(channel_id)?;
+    if aliases.is_empty() {
+        return Ok("No games post in this channel".to_owned());
+    }
+    let (page_aliases, num_pages) = page_of_aliases(&aliases, page)?;
+    let mut text = String::new();
+    for alias in page_aliases {
+        // One broken server shouldn't hide the rest
+        let line = match get_details_for_alias::<C>(db_conn, game_cache, alias) {
+            Ok(details) => summary_line(alias, &details, time_format),
+            Err(e) => format!("{}: {}", alias, e.0),
+        };
+        text.push_str(&db_conn.icon_for_alias(alias)?.prefix());
+        text.push_str(&line);
+        text.push('\n');
+    }
+    if num_pages > 1 {
+        text.push_str(&format!(
+            "Page {} of {}, use `!all <page>` for the rest",
+            page, num_pages
+        ));
+    }
+    Ok(text)
+}
+
+pub fn all<C: ServerConnection>(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let page = match args.single_quoted_n::<usize>() {
+        Ok(page) => {
+            args.skip();
+            page
+        }
+        Err(_) => 1,
+    };
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let time_format = time_format_for_message(db_conn, message)?;
+    let text = all_helper::<C>(db_conn, game_cache, message.channel_id, page, time_format)?;
+    info!("all: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use super::*;
+
+use crate::model::enums::NationStatus;
+use serenity::model::id::UserId;
+use std::collections::HashMap;
+
+#[test]
+fn should_only_find_games_for_this_channel() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_channel_for_alias("test server 2", ChannelId(1234))
+        .unwrap();
+    db_conn
+        .set_channel_for_alias("test server 1", ChannelId(1234))
+        .unwrap();
+    db_conn
+        .set_channel_for_alias("test server 3", ChannelId(5678))
+        .unwrap();
+
+    assert_eq!(
+        db_conn.aliases_for_channel(ChannelId(1234)).unwrap(),
+        vec!["test server 1".to_owned(), "test server 2".to_owned()]
+    );
+}
+
+#[test]
+fn should_page_aliases() {
+    let aliases: Vec<String> = (1..=25).map(|i| format!("game {}", i)).collect();
+
+    let (page, num_pages) = page_of_aliases(&aliases, 1).unwrap();
+    assert_eq!(num_pages, 3);
+    assert_eq!(page.len(), GAMES_PER_PAGE);
+    let (page, _) = page_of_aliases(&aliases, 3).unwrap();
+    assert_eq!(page, &aliases[20..]);
+    assert!(page_of_aliases(&aliases, 4).is_err());
+    assert!(page_of_aliases(&aliases, 0).is_err());
+}
+
+#[test]
+fn should_count_undone_humans_in_summary() {
+    let player =
+        |nation_id: u32, submitted: SubmissionStatus, player_status: NationStatus| PlayerDetails {
+            nation_id,
+            nation_name: format!("nation {}", nation_id),
+            submitted,
+            player_status,
+            went_ai: false,
+            connected: false,
+        };
+    let details = GameDetails {
+        alias: "foo".to_owned(),
+        owner: None,
+        description: None,
+        player_nicknames: HashMap::new(),
+        player_vacations: HashMap::new(),
+        nations: NationDetails::Started(StartedDetails {
+            address: "foo.bar:1234".to_owned(),
+            game_name: "foo".to_owned(),
+            state: StartedStateDetails::Playing(PlayingState {
+                players: vec![
+                    PotentialPlayer::RegisteredAndGame(
+                        UserId(1),
+                        player(1, SubmissionStatus::NotSubmitted, NationStatus::Human),
+                    ),
+                    PotentialPlayer::RegisteredAndGame(
+                        UserId(2),
+                        player(2, SubmissionStatus::Submitted, NationStatus::Human),
+                    ),
+                    PotentialPlayer::GameOnly(player(
+                        3,
+                        SubmissionStatus::NotSubmitted,
+                        NationStatus::Human,
+                    )),
+                    PotentialPlayer::GameOnly(player(
+                        4,
+                        SubmissionStatus::NotSubmitted,
+                        NationStatus::AI,
+                    )),
+                ],
+                turn: 12,
+                mins_remaining: 20,
+                hours_remaining: 3,
+                days_remaining: 0,
+                secs_remaining: 0,
+                option_deadline: None,
+                paused: false,
+                blitz: false,
+            }),
+            option_snek_game_id: None,
+        }),
+        cache_entry: None,
+        warnings: vec![],
+    };
+
+    assert_eq!(
+        summary_line("foo", &details, TimeFormat::Exact),
+        "foo: turn 12 (3h 20m), 2 undone"
+    );
+}
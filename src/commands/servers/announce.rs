@@ -0,0 +1,93 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time;
+
+use super::details2::MAX_MESSAGE_LENGTH;
+use crate::db::DbConnectionKey;
+use crate::notifier::{DiscordNotifier, Notifier};
+
+#[cfg(test)]
+mod tests;
+
+/// Discord allows more than this, but nothing's in a hurry and the bot has
+/// game announcements to make at the same time
+const ANNOUNCE_SPACING: time::Duration = time::Duration::from_secs(1);
+
+/// So that running it again while the first one is going out doesn't post twice
+static ANNOUNCING: AtomicBool = AtomicBool::new(false);
+
+/// Posts to each channel once, waiting `spacing` between each. Failures are only
+/// logged, most likely the bot can't see that channel any more. Returns how many
+/// it got to.
+pub fn send_announcement<N: Notifier>(
+    channel_ids: &[ChannelId],
+    message: &str,
+    spacing: time::Duration,
+) -> usize {
+    let mut seen = Vec::with_capacity(channel_ids.len());
+    let mut sent = 0;
+    for &channel_id in channel_ids {
+        if seen.contains(&channel_id) {
+            continue;
+        }
+        seen.push(channel_id);
+        if !spacing.is_zero() && seen.len() > 1 {
+            thread::sleep(spacing);
+        }
+        match N::channel(channel_id, message) {
+            Ok(()) => sent += 1,
+            Err(e) => error!("Could not announce in {} with error {:?}", channel_id, e.0),
+        }
+    }
+    sent
+}
+
+pub fn announce(context: &mut Context, message: &Message, args: Args) -> Result<(), CommandError> {
+    let text = args.full().trim().to_owned();
+    if text.is_empty() {
+        return Err(CommandError::from(
+            "Expected something to announce, e.g. !announce restarting in 5 minutes",
+        ));
+    }
+    if text.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(CommandError::from(format!(
+            "Announcements can be at most {} characters",
+            MAX_MESSAGE_LENGTH
+        )));
+    }
+
+    let channel_ids = {
+        let data = context.data.lock();
+        let db_conn = data
+            .get::<DbConnectionKey>()
+            .ok_or("No DbConnection was created on startup. This is a bug.")?;
+        db_conn.active_notification_channels()?
+    };
+    if ANNOUNCING.swap(true, Ordering::SeqCst) {
+        return Err(CommandError::from(
+            "An announcement is still going out, wait for it to finish",
+        ));
+    }
+    let reply = format!(
+        "Announcing in {} channels, this will take about {} seconds",
+        channel_ids.len(),
+        channel_ids.len() as u64 * ANNOUNCE_SPACING.as_secs()
+    );
+    info!("announce: {}", reply);
+    thread::spawn(move || {
+        let sent = send_announcement::<DiscordNotifier>(&channel_ids, &text, ANNOUNCE_SPACING);
+        info!(
+            "announce: sent to {} of {} channels",
+            sent,
+            channel_ids.len()
+        );
+        ANNOUNCING.store(false, Ordering::SeqCst);
+    });
+    message.reply(&reply)?;
+    Ok(())
+}
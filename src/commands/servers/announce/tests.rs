@@ -0,0 +1,50 @@
+use super::*;
+
+use crate::db::DbConnection;
+use crate::notifier::test_helpers::RecordingNotifier;
+
+#[test]
+fn should_announce_in_each_active_channel_once() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_channel_for_alias("test server 1", ChannelId(3551))
+        .unwrap();
+    db_conn
+        .set_channel_for_alias("test server 2", ChannelId(3552))
+        .unwrap();
+    db_conn
+        .set_channel_for_alias("test server 3", ChannelId(3553))
+        .unwrap();
+    // subscribed to another game as well as its own
+    db_conn
+        .subscribe_channel("test server 1", ChannelId(3552))
+        .unwrap();
+    db_conn
+        .subscribe_channel("test server 1", ChannelId(3554))
+        .unwrap();
+    db_conn
+        .set_archived_for_alias("test server 3", true)
+        .unwrap();
+
+    let channel_ids = db_conn.active_notification_channels().unwrap();
+    assert_eq!(
+        channel_ids,
+        vec![ChannelId(3551), ChannelId(3552), ChannelId(3554)]
+    );
+
+    let mut twice = channel_ids.clone();
+    twice.extend(channel_ids);
+    let sent = send_announcement::<RecordingNotifier>(
+        &twice,
+        "bot restarting",
+        time::Duration::from_secs(0),
+    );
+    assert_eq!(sent, 3);
+    for channel_id in [3551, 3552, 3554] {
+        assert_eq!(
+            RecordingNotifier::channel_messages(ChannelId(channel_id)),
+            vec!["bot restarting".to_owned()]
+        );
+    }
+    assert!(RecordingNotifier::channel_messages(ChannelId(3553)).is_empty());
+}
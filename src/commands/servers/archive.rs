@@ -0,0 +1,102 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::{alias_from_arg_or_channel_name, author_is_game_admin, check_owner_or_admin};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+use crate::notifier::DiscordNotifier;
+
+#[cfg(test)]
+mod tests;
+
+fn archive_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    author_is_admin: bool,
+    alias: &str,
+) -> Result<String, CommandError> {
+    check_owner_or_admin(db_conn, alias, author_id, author_is_admin)?;
+    match db_conn.game_for_alias(alias)?.state {
+        GameServerState::StartedState(_, _) => {
+            db_conn.set_archived_for_alias(alias, true)?;
+            Ok(format!(
+                "{} is finished and won't be checked any more. Its players and turn history are kept, use !unarchive to start checking it again",
+                alias
+            ))
+        }
+        GameServerState::Lobby(_) => {
+            Err(CommandError::from(format!("{} hasn't started yet", alias)))
+        }
+        GameServerState::Archived(_, _) => {
+            Err(CommandError::from(format!("{} is already finished", alias)))
+        }
+    }
+}
+
+fn unarchive_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    author_is_admin: bool,
+    alias: &str,
+) -> Result<String, CommandError> {
+    check_owner_or_admin(db_conn, alias, author_id, author_is_admin)?;
+    match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Archived(_, _) => {
+            db_conn.set_archived_for_alias(alias, false)?;
+            Ok(format!(
+                "{} will be checked again from the next poll",
+                alias
+            ))
+        }
+        GameServerState::StartedState(_, _) | GameServerState::Lobby(_) => {
+            Err(CommandError::from(format!("{} isn't finished", alias)))
+        }
+    }
+}
+
+fn alias_only(args: &mut Args, message: &Message) -> Result<String, CommandError> {
+    let alias = alias_from_arg_or_channel_name(args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    Ok(alias)
+}
+
+pub fn archive(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_only(&mut args, message)?;
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let author_is_admin = author_is_game_admin::<DiscordNotifier>(db_conn, message, &alias)?;
+    let text = archive_helper(db_conn, message.author.id, author_is_admin, &alias)?;
+    info!("archive: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
+
+pub fn unarchive(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_only(&mut args, message)?;
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let author_is_admin = author_is_game_admin::<DiscordNotifier>(db_conn, message, &alias)?;
+    let text = unarchive_helper(db_conn, message.author.id, author_is_admin, &alias)?;
+    info!("unarchive: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use super::*;
+
+use crate::model::enums::Era;
+use crate::model::{GameServer, LobbyState, StartedState};
+
+#[test]
+fn should_archive_and_unarchive_started_game() {
+    let db_conn = DbConnection::test();
+    archive_helper(&db_conn, UserId(1), false, "test server 1").unwrap();
+    let server = db_conn.game_for_alias("test server 1").unwrap();
+    assert!(matches!(server.state, GameServerState::Archived(_, _)));
+    // registrations stay queryable
+    assert_eq!(
+        db_conn
+            .players_with_nations_for_game_alias("test server 1")
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(archive_helper(&db_conn, UserId(1), false, "test server 1").is_err());
+
+    unarchive_helper(&db_conn, UserId(1), false, "test server 1").unwrap();
+    let server = db_conn.game_for_alias("test server 1").unwrap();
+    assert!(matches!(server.state, GameServerState::StartedState(_, _)));
+    assert!(unarchive_helper(&db_conn, UserId(1), false, "test server 1").is_err());
+}
+
+#[test]
+fn should_not_archive_unknown_game() {
+    let db_conn = DbConnection::test();
+    assert!(archive_helper(&db_conn, UserId(1), false, "no such game").is_err());
+    assert!(unarchive_helper(&db_conn, UserId(1), false, "no such game").is_err());
+}
+
+#[test]
+fn should_only_let_the_owner_or_an_admin_archive_a_hosted_game() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "owned".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(3865),
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+    db_conn
+        .insert_started_state(
+            "owned",
+            &StartedState {
+                address: "owned.example.com:2556".to_owned(),
+                last_seen_turn: 40,
+                option_snek_game_id: None,
+            },
+        )
+        .unwrap();
+
+    assert!(archive_helper(&db_conn, UserId(3866), false, "owned").is_err());
+    archive_helper(&db_conn, UserId(3866), true, "owned").unwrap();
+    assert!(unarchive_helper(&db_conn, UserId(3866), false, "owned").is_err());
+    unarchive_helper(&db_conn, UserId(3865), false, "owned").unwrap();
+}
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Duration, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::{GuildId, UserId};
+use serenity::prelude::Context;
+
+use super::{admin_guild_id, alias_from_arg_or_channel_name};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::{GameData, GameServerState};
+use crate::notifier::Notifier;
+
+#[cfg(test)]
+mod tests;
+
+/// Anything longer and the host has had plenty of time to say it isn't over
+const MAX_GRACE_HOURS: u32 = 7 * 24;
+
+/// The shortest grace period any server the game posts in has asked for, `None`
+/// if none of them want finished games archived
+fn grace_hours<N: Notifier>(
+    db_conn: &DbConnection,
+    alias: &str,
+) -> Result<Option<u32>, CommandError> {
+    let mut option_hours: Option<u32> = None;
+    for channel_id in db_conn.notification_channels_for_alias(alias)? {
+        if let Some(guild_id) = N::guild_for_channel(channel_id) {
+            if let Some(hours) = db_conn.auto_archive_hours_for_guild(guild_id)? {
+                option_hours = Some(option_hours.map_or(hours, |shortest| shortest.min(hours)));
+            }
+        }
+    }
+    Ok(option_hours)
+}
+
+/// Called by the poller with what the server just said. Once only one nation is
+/// left the archive is scheduled and announced, and it happens on the first
+/// poll after the grace period unless someone uses `!cancelarchive` first.
+pub fn auto_archive_announcements<N: Notifier>(
+    db_conn: &DbConnection,
+    alias: &str,
+    game_data: &GameData,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, CommandError> {
+    let (option_at, skip) = db_conn.auto_archive_for_alias(alias)?;
+    if skip {
+        return Ok(vec![]);
+    }
+    Ok(match (game_data.sole_survivor(), option_at) {
+        (Some(_), Some(at)) if at <= now => {
+            db_conn.set_archived_for_alias(alias, true)?;
+            // So that an !unarchive sticks
+            db_conn.set_auto_archive_for_alias(alias, None, true)?;
+            vec![format!(
+                "{} has been archived, it won't be checked any more. Its players and turn history are kept, use !unarchive {} to start checking it again",
+                alias, alias
+            )]
+        }
+        (Some(_), Some(_)) => vec![],
+        (Some(survivor), None) => match grace_hours::<N>(db_conn, alias)? {
+            Some(hours) => {
+                db_conn.set_auto_archive_for_alias(
+                    alias,
+                    Some(now + Duration::hours(i64::from(hours))),
+                    false,
+                )?;
+                vec![format!(
+                    "{} looks finished, only {} is left. It will be archived in {}h unless someone uses !cancelarchive {}",
+                    alias, survivor.name, hours, alias
+                )]
+            }
+            None => vec![],
+        },
+        // e.g. a rollback to before the last nation was defeated
+        (None, Some(_)) => {
+            db_conn.set_auto_archive_for_alias(alias, None, false)?;
+            vec![format!(
+                "{} isn't finished after all, it won't be archived",
+                alias
+            )]
+        }
+        (None, None) => vec![],
+    })
+}
+
+fn auto_archive_helper(
+    db_conn: &DbConnection,
+    guild_id: GuildId,
+    arg: &str,
+) -> Result<String, CommandError> {
+    if arg == "off" {
+        db_conn.set_auto_archive_hours_for_guild(guild_id, None)?;
+        return Ok("Finished games won't be archived until someone uses !archive".to_owned());
+    }
+    let hours = match arg.parse::<u32>() {
+        Ok(hours) if (1..=MAX_GRACE_HOURS).contains(&hours) => hours,
+        _ => {
+            return Err(CommandError::from(format!(
+                "Expected how many hours to wait before archiving, between 1 and {}, or \"off\"",
+                MAX_GRACE_HOURS
+            )))
+        }
+    };
+    db_conn.set_auto_archive_hours_for_guild(guild_id, Some(hours))?;
+    Ok(format!(
+        "Games that post here will be archived {}h after only one nation is left. It's announced first, and !cancelarchive stops it",
+        hours
+    ))
+}
+
+fn cancel_archive_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+) -> Result<String, CommandError> {
+    // Games added with !add have nobody in charge of them, so anyone can
+    let option_owner = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::StartedState(_, option_lobby_state) => {
+            option_lobby_state.map(|lobby_state| lobby_state.owner)
+        }
+        GameServerState::Lobby(_) => {
+            return Err(CommandError::from(format!("{} hasn't started yet", alias)))
+        }
+        GameServerState::Archived(_, _) => {
+            return Err(CommandError::from(format!(
+                "{} has already been archived, use !unarchive instead",
+                alias
+            )))
+        }
+    };
+    if option_owner.is_some_and(|owner| owner != author_id) {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} can do this",
+            alias
+        )));
+    }
+    let (option_at, _) = db_conn.auto_archive_for_alias(alias)?;
+    db_conn.set_auto_archive_for_alias(alias, None, true)?;
+    Ok(match option_at {
+        Some(_) => format!(
+            "{} won't be archived, it'll keep being checked until someone uses !archive",
+            alias
+        ),
+        None => format!(
+            "{} wasn't due to be archived, and now it won't be even if it looks finished",
+            alias
+        ),
+    })
+}
+
+pub fn auto_archive(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg = args.single_quoted::<String>()?.to_lowercase();
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let guild_id = admin_guild_id(message)?;
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = auto_archive_helper(db_conn, guild_id, &arg)?;
+    info!("auto_archive: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
+
+pub fn cancel_archive(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = cancel_archive_helper(db_conn, message.author.id, &alias)?;
+    info!("cancel_archive: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use super::*;
+
+use crate::model::enums::{NationStatus, SubmissionStatus};
+use crate::model::Nation;
+use crate::notifier::test_helpers::RecordingNotifier;
+use chrono::TimeZone;
+use serenity::model::id::ChannelId;
+
+fn game_data(turn: i32, statuses: &[NationStatus]) -> GameData {
+    GameData {
+        game_name: "finished".to_owned(),
+        nations: statuses
+            .iter()
+            .enumerate()
+            .map(|(i, &status)| Nation {
+                id: 5 + i as u32,
+                status,
+                submitted: SubmissionStatus::NotSubmitted,
+                connected: false,
+                name: format!("nation {}", 5 + i),
+                era: "EA".to_owned(),
+            })
+            .collect(),
+        turn,
+        turn_timer: 0,
+    }
+}
+
+fn finished() -> GameData {
+    game_data(
+        80,
+        &[
+            NationStatus::Defeated,
+            NationStatus::Human,
+            NationStatus::Defeated,
+        ],
+    )
+}
+
+fn hour(h: i64) -> DateTime<Utc> {
+    Utc.ymd(2021, 3, 12).and_hms(0, 0, 0) + Duration::hours(h)
+}
+
+/// A game posting in a channel whose server archives after `option_hours`
+fn db_with_guild_hours(channel_id: u64, option_hours: Option<u32>) -> DbConnection {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_channel_for_alias("test server 1", ChannelId(channel_id))
+        .unwrap();
+    db_conn
+        .set_auto_archive_hours_for_guild(GuildId(channel_id), option_hours)
+        .unwrap();
+    db_conn
+}
+
+fn is_archived(db_conn: &DbConnection) -> bool {
+    matches!(
+        db_conn.game_for_alias("test server 1").unwrap().state,
+        GameServerState::Archived(_, _)
+    )
+}
+
+#[test]
+fn should_only_call_a_game_finished_when_one_nation_outlasts_the_rest() {
+    assert_eq!(finished().sole_survivor().map(|nation| nation.id), Some(6));
+    let ai_left = game_data(80, &[NationStatus::Defeated, NationStatus::AI]);
+    assert_eq!(ai_left.sole_survivor().map(|nation| nation.id), Some(6));
+    // Still fighting
+    assert!(game_data(
+        80,
+        &[
+            NationStatus::Defeated,
+            NationStatus::Human,
+            NationStatus::AI
+        ]
+    )
+    .sole_survivor()
+    .is_none());
+    // Nobody has been beaten, it's just a test game
+    assert!(game_data(3, &[NationStatus::Human])
+        .sole_survivor()
+        .is_none());
+    assert!(
+        game_data(-1, &[NationStatus::Defeated, NationStatus::Human])
+            .sole_survivor()
+            .is_none()
+    );
+}
+
+#[test]
+fn should_announce_then_archive_after_the_grace_period() {
+    let db_conn = db_with_guild_hours(3841, Some(24));
+    let announce = |now| {
+        auto_archive_announcements::<RecordingNotifier>(&db_conn, "test server 1", &finished(), now)
+            .unwrap()
+    };
+
+    assert_eq!(
+        announce(hour(0)),
+        vec!["test server 1 looks finished, only nation 6 is left. It will be archived in 24h unless someone uses !cancelarchive test server 1"]
+    );
+    assert!(announce(hour(23)).is_empty());
+    assert!(!is_archived(&db_conn));
+    assert!(announce(hour(24))[0].starts_with("test server 1 has been archived"));
+    assert!(is_archived(&db_conn));
+}
+
+#[test]
+fn should_not_archive_once_cancelled() {
+    let db_conn = db_with_guild_hours(3842, Some(1));
+    auto_archive_announcements::<RecordingNotifier>(
+        &db_conn,
+        "test server 1",
+        &finished(),
+        hour(0),
+    )
+    .unwrap();
+
+    assert!(cancel_archive_helper(&db_conn, UserId(1), "test server 1")
+        .unwrap()
+        .contains("won't be archived"));
+    assert!(auto_archive_announcements::<RecordingNotifier>(
+        &db_conn,
+        "test server 1",
+        &finished(),
+        hour(2)
+    )
+    .unwrap()
+    .is_empty());
+    assert!(!is_archived(&db_conn));
+}
+
+#[test]
+fn should_leave_finished_games_alone_unless_the_server_asked() {
+    let db_conn = db_with_guild_hours(3843, None);
+    assert!(auto_archive_announcements::<RecordingNotifier>(
+        &db_conn,
+        "test server 1",
+        &finished(),
+        hour(0)
+    )
+    .unwrap()
+    .is_empty());
+    assert_eq!(
+        db_conn.auto_archive_for_alias("test server 1").unwrap(),
+        (None, false)
+    );
+}
+
+#[test]
+fn should_call_it_off_if_the_game_turns_out_not_to_be_finished() {
+    let db_conn = db_with_guild_hours(3844, Some(1));
+    auto_archive_announcements::<RecordingNotifier>(
+        &db_conn,
+        "test server 1",
+        &finished(),
+        hour(0),
+    )
+    .unwrap();
+    let rolled_back = game_data(
+        79,
+        &[
+            NationStatus::Human,
+            NationStatus::Human,
+            NationStatus::Defeated,
+        ],
+    );
+    assert_eq!(
+        auto_archive_announcements::<RecordingNotifier>(
+            &db_conn,
+            "test server 1",
+            &rolled_back,
+            hour(2)
+        )
+        .unwrap(),
+        vec!["test server 1 isn't finished after all, it won't be archived"]
+    );
+    assert!(!is_archived(&db_conn));
+}
+
+#[test]
+fn should_only_take_a_grace_period_within_reason() {
+    let db_conn = DbConnection::test();
+    assert!(auto_archive_helper(&db_conn, GuildId(3845), "0").is_err());
+    assert!(auto_archive_helper(&db_conn, GuildId(3845), "1000").is_err());
+    auto_archive_helper(&db_conn, GuildId(3845), "48").unwrap();
+    assert_eq!(
+        db_conn.auto_archive_hours_for_guild(GuildId(3845)).unwrap(),
+        Some(48)
+    );
+    auto_archive_helper(&db_conn, GuildId(3845), "off").unwrap();
+    assert_eq!(
+        db_conn.auto_archive_hours_for_guild(GuildId(3845)).unwrap(),
+        None
+    );
+}
@@ -0,0 +1,73 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::turn_check::BLITZ_POLL_INTERVAL_SECS;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+fn blitz_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    arg_setting: &str,
+) -> Result<String, CommandError> {
+    let blitz = match arg_setting {
+        "on" => true,
+        "off" => false,
+        _ => {
+            return Err(CommandError::from(format!(
+                "Unknown setting \"{}\", try \"on\" or \"off\"",
+                arg_setting
+            )))
+        }
+    };
+    // Games added with !add have nobody in charge of them, so anyone can
+    let option_owner = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(lobby_state) => Some(lobby_state.owner),
+        GameServerState::StartedState(_, option_lobby_state)
+        | GameServerState::Archived(_, option_lobby_state) => {
+            option_lobby_state.map(|lobby_state| lobby_state.owner)
+        }
+    };
+    if option_owner.is_some_and(|owner| owner != author_id) {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} can do this",
+            alias
+        )));
+    }
+    db_conn.set_blitz_for_alias(alias, blitz)?;
+    Ok(if blitz {
+        format!(
+            "{} is a blitz game: the time left is shown to the second and it's polled every {}s unless !pollinterval says otherwise",
+            alias, BLITZ_POLL_INTERVAL_SECS
+        )
+    } else {
+        format!("{} is back to a normal game", alias)
+    })
+}
+
+pub fn blitz(context: &mut Context, message: &Message, mut args: Args) -> Result<(), CommandError> {
+    let arg_setting = args.single_quoted::<String>()?.to_lowercase();
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = blitz_helper(db_conn, message.author.id, &alias, &arg_setting)?;
+    info!("blitz: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use super::*;
+
+use crate::commands::servers::{PlayingState, TimeFormat};
+use crate::model::enums::Era;
+use crate::model::{GameServer, LobbyState};
+
+#[test]
+fn should_show_blitz_time_left_to_the_second_whatever_the_format() {
+    let playing_state = PlayingState {
+        players: vec![],
+        turn: 5,
+        mins_remaining: 4,
+        hours_remaining: 0,
+        days_remaining: 0,
+        secs_remaining: 30,
+        option_deadline: None,
+        paused: false,
+        blitz: true,
+    };
+    assert_eq!(playing_state.show_time_remaining(), "4m 30s");
+    assert_eq!(
+        playing_state.show_time_remaining_as(TimeFormat::Coarse),
+        "4m 30s"
+    );
+    let normal = PlayingState {
+        blitz: false,
+        ..playing_state
+    };
+    assert_eq!(normal.show_time_remaining(), "0h 4m");
+}
+
+#[test]
+fn should_only_let_the_owner_switch_blitz_mode() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(1),
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+    assert!(!db_conn.blitz_for_alias("lobby").unwrap());
+    assert!(blitz_helper(&db_conn, UserId(2), "lobby", "on").is_err());
+    assert!(blitz_helper(&db_conn, UserId(1), "lobby", "sometimes").is_err());
+    blitz_helper(&db_conn, UserId(1), "lobby", "on").unwrap();
+    assert!(db_conn.blitz_for_alias("lobby").unwrap());
+    assert_eq!(db_conn.blitz_aliases().unwrap(), vec!["lobby".to_owned()]);
+
+    blitz_helper(&db_conn, UserId(1), "lobby", "off").unwrap();
+    assert!(!db_conn.blitz_for_alias("lobby").unwrap());
+    assert!(blitz_helper(&db_conn, UserId(1), "not a game", "on").is_err());
+}
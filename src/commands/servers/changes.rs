@@ -0,0 +1,202 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use std::collections::HashMap;
+
+use super::alias_from_arg_or_channel_name;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::NationStatus;
+
+#[cfg(test)]
+mod tests;
+
+/// A nation's id, name and status in some turn
+pub type RecordedStatus = (u32, String, NationStatus);
+
+/// The turn and every nation's status in it, for keeping a history of. `None`
+/// if the game isn't being played yet.
+pub fn nation_statuses(details: &GameDetails) -> Option<(i32, Vec<RecordedStatus>)> {
+    match &details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => {
+            let statuses = playing_state
+                .players
+                .iter()
+                .filter_map(|potential_player| match potential_player {
+                    PotentialPlayer::RegisteredAndGame(_, player_details)
+                    | PotentialPlayer::GameOnly(player_details) => Some((
+                        player_details.nation_id,
+                        player_details.nation_name.clone(),
+                        player_details.player_status,
+                    )),
+                    PotentialPlayer::RegisteredOnly(_, _, _) => None,
+                })
+                .collect();
+            Some((playing_state.turn as i32, statuses))
+        }
+        _ => None,
+    }
+}
+
+/// The recorded turn closest to `turn`, the earlier one if two are as close
+fn nearest_turn(recorded: &[i32], turn: i32) -> Option<i32> {
+    recorded
+        .iter()
+        .copied()
+        .min_by_key(|&recorded_turn| ((recorded_turn - turn).abs(), recorded_turn))
+}
+
+#[derive(PartialEq, Debug, Default)]
+pub struct NationChanges {
+    pub died: Vec<String>,
+    pub went_ai: Vec<String>,
+    pub appeared: Vec<String>,
+}
+
+/// What happened to each nation between two turns' statuses
+pub fn nation_changes(from: &[RecordedStatus], to: &[RecordedStatus]) -> NationChanges {
+    let from: HashMap<u32, NationStatus> = from
+        .iter()
+        .map(|(nation_id, _, status)| (*nation_id, *status))
+        .collect();
+    let mut changes = NationChanges::default();
+    for (nation_id, nation_name, status) in to {
+        let name = format!("{} ({})", nation_name, nation_id);
+        match from.get(nation_id) {
+            None => changes.appeared.push(name),
+            Some(old_status) if !old_status.is_defeated() && status.is_defeated() => {
+                changes.died.push(name)
+            }
+            Some(NationStatus::Human) if *status == NationStatus::AI => changes.went_ai.push(name),
+            Some(_) => {}
+        }
+    }
+    changes
+}
+
+#[derive(PartialEq, Debug)]
+pub struct StatusTransition {
+    pub nation_id: u32,
+    pub nation_name: String,
+    pub from: NationStatus,
+    pub to: NationStatus,
+}
+
+/// The nations in both turns whose status changed. Going from defeated this turn
+/// to defeated is the same defeat, so doesn't count.
+pub fn status_transitions(from: &[RecordedStatus], to: &[RecordedStatus]) -> Vec<StatusTransition> {
+    to.iter()
+        .filter_map(|(nation_id, nation_name, status)| {
+            let (_, _, old_status) = from.iter().find(|(old_id, _, _)| old_id == nation_id)?;
+            if old_status == status || (old_status.is_defeated() && status.is_defeated()) {
+                return None;
+            }
+            Some(StatusTransition {
+                nation_id: *nation_id,
+                nation_name: nation_name.clone(),
+                from: *old_status,
+                to: *status,
+            })
+        })
+        .collect()
+}
+
+fn changes_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    from_turn: i32,
+    option_to_turn: Option<i32>,
+) -> Result<String, CommandError> {
+    let recorded = db_conn.nation_status_turns(alias)?;
+    let latest = match recorded.last() {
+        Some(&latest) => latest,
+        None => {
+            return Err(CommandError::from(format!(
+                "No turns of {} have been recorded yet, check back after the next one hosts",
+                alias
+            )))
+        }
+    };
+    let to_turn = option_to_turn.unwrap_or(latest);
+    let (from_turn, to_turn) = (from_turn.min(to_turn), from_turn.max(to_turn));
+
+    let mut text = String::new();
+    let mut resolve = |turn: i32| {
+        // Can't fail, there's at least one
+        let nearest = nearest_turn(&recorded, turn).unwrap_or(latest);
+        if nearest != turn {
+            text.push_str(&format!(
+                "Turn {} wasn't recorded, using turn {} instead\n",
+                turn, nearest
+            ));
+        }
+        nearest
+    };
+    let from_nearest = resolve(from_turn);
+    let to_nearest = resolve(to_turn);
+    if from_nearest == to_nearest {
+        return Err(CommandError::from(format!(
+            "{}Need two different recorded turns of {} to compare, only have turn {} near there",
+            text, alias, from_nearest
+        )));
+    }
+
+    let changes = nation_changes(
+        &db_conn.nation_statuses(alias, from_nearest)?,
+        &db_conn.nation_statuses(alias, to_nearest)?,
+    );
+    text.push_str(&format!(
+        "Changes in {} from turn {} to turn {}:\n",
+        alias, from_nearest, to_nearest
+    ));
+    if changes == NationChanges::default() {
+        text.push_str("Nothing changed");
+        return Ok(text);
+    }
+    for (label, names) in [
+        ("Died", &changes.died),
+        ("Went AI", &changes.went_ai),
+        ("Appeared", &changes.appeared),
+    ] {
+        if !names.is_empty() {
+            text.push_str(&format!("{}: {}\n", label, names.join(", ")));
+        }
+    }
+    Ok(text.trim_end().to_owned())
+}
+
+pub fn changes(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let from_turn = args
+        .single_quoted::<i32>()
+        .map_err(|_| CommandError::from("Expected the turn to compare from, e.g. !changes 10"))?;
+    let option_to_turn = match args.single_quoted_n::<i32>() {
+        Ok(to_turn) => {
+            args.skip();
+            Some(to_turn)
+        }
+        Err(_) => None,
+    };
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = changes_helper(db_conn, &alias, from_turn, option_to_turn)?;
+    info!("changes: replying with: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
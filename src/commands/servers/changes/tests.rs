@@ -0,0 +1,104 @@
+use super::*;
+
+fn record(db_conn: &DbConnection, turn: i32, statuses: &[(u32, &str, NationStatus)]) {
+    let statuses: Vec<(u32, String, NationStatus)> = statuses
+        .iter()
+        .map(|&(nation_id, name, status)| (nation_id, name.to_owned(), status))
+        .collect();
+    db_conn
+        .record_nation_statuses("test server 1", turn, &statuses)
+        .unwrap();
+}
+
+#[test]
+fn should_pick_the_nearest_recorded_turn() {
+    assert_eq!(nearest_turn(&[], 3), None);
+    assert_eq!(nearest_turn(&[2, 5, 9], 5), Some(5));
+    assert_eq!(nearest_turn(&[2, 5, 9], 8), Some(9));
+    // equally close goes to the earlier one
+    assert_eq!(nearest_turn(&[2, 6], 4), Some(2));
+    assert_eq!(nearest_turn(&[2, 6], 40), Some(6));
+}
+
+#[test]
+fn should_list_deaths_ai_and_new_nations() {
+    let changes = nation_changes(
+        &[
+            (5, "Arcoscephale".to_owned(), NationStatus::Human),
+            (6, "Ermor".to_owned(), NationStatus::Human),
+            (7, "Ulm".to_owned(), NationStatus::Defeated),
+        ],
+        &[
+            (5, "Arcoscephale".to_owned(), NationStatus::AI),
+            (6, "Ermor".to_owned(), NationStatus::DefeatedThisTurn),
+            (7, "Ulm".to_owned(), NationStatus::Defeated),
+            (8, "Marverni".to_owned(), NationStatus::Human),
+        ],
+    );
+    assert_eq!(
+        changes,
+        NationChanges {
+            died: vec!["Ermor (6)".to_owned()],
+            went_ai: vec!["Arcoscephale (5)".to_owned()],
+            appeared: vec!["Marverni (8)".to_owned()],
+        }
+    );
+}
+
+#[test]
+fn should_compare_with_the_latest_turn_by_default() {
+    let db_conn = DbConnection::test();
+    assert!(changes_helper(&db_conn, "test server 1", 10, None).is_err());
+
+    record(&db_conn, 10, &[(5, "Arcoscephale", NationStatus::Human)]);
+    record(&db_conn, 14, &[(5, "Arcoscephale", NationStatus::Defeated)]);
+    let text = changes_helper(&db_conn, "test server 1", 10, None).unwrap();
+    assert_eq!(
+        text,
+        "Changes in test server 1 from turn 10 to turn 14:\nDied: Arcoscephale (5)"
+    );
+
+    // the same turn twice has nothing to compare
+    assert!(changes_helper(&db_conn, "test server 1", 14, None).is_err());
+}
+
+#[test]
+fn should_say_when_it_used_a_nearby_turn() {
+    let db_conn = DbConnection::test();
+    record(&db_conn, 10, &[(5, "Arcoscephale", NationStatus::Human)]);
+    record(&db_conn, 14, &[(5, "Arcoscephale", NationStatus::Human)]);
+    let text = changes_helper(&db_conn, "test server 1", 9, Some(13)).unwrap();
+    assert_eq!(
+        text,
+        "Turn 9 wasn't recorded, using turn 10 instead\n\
+         Turn 13 wasn't recorded, using turn 14 instead\n\
+         Changes in test server 1 from turn 10 to turn 14:\n\
+         Nothing changed"
+    );
+}
+
+#[test]
+fn should_only_count_real_status_transitions() {
+    let transitions = status_transitions(
+        &[
+            (5, "Arcoscephale".to_owned(), NationStatus::Human),
+            (6, "Ermor".to_owned(), NationStatus::DefeatedThisTurn),
+            (7, "Ulm".to_owned(), NationStatus::AI),
+        ],
+        &[
+            (5, "Arcoscephale".to_owned(), NationStatus::AI),
+            (6, "Ermor".to_owned(), NationStatus::Defeated),
+            (7, "Ulm".to_owned(), NationStatus::AI),
+            (8, "Marverni".to_owned(), NationStatus::Human),
+        ],
+    );
+    assert_eq!(
+        transitions,
+        vec![StatusTransition {
+            nation_id: 5,
+            nation_name: "Arcoscephale".to_owned(),
+            from: NationStatus::Human,
+            to: NationStatus::AI,
+        }]
+    );
+}
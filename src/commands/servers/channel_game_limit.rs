@@ -0,0 +1,91 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, GuildId};
+use serenity::prelude::Context;
+
+use super::admin_guild_id;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Far more than anyone keeps in one channel on purpose
+pub const DEFAULT_MAX_GAMES_PER_CHANNEL: u32 = 50;
+/// Past this a channel is just a list of games, raising it further won't help
+const MAX_MAX_GAMES_PER_CHANNEL: u32 = 500;
+
+/// "default" or how many games. `None` is the default.
+fn parse_game_limit(text: &str) -> Result<Option<u32>, CommandError> {
+    match text {
+        "default" => Ok(None),
+        _ => match text.parse::<u32>() {
+            Ok(max_games) if (1..=MAX_MAX_GAMES_PER_CHANNEL).contains(&max_games) => {
+                Ok(Some(max_games))
+            }
+            _ => Err(CommandError::from(format!(
+                "The limit is \"default\" or a number of games from 1 to {}",
+                MAX_MAX_GAMES_PER_CHANNEL
+            ))),
+        },
+    }
+}
+
+/// Fails if the channel is already tracking as many games as its server allows.
+/// DMs aren't in a server so get the default.
+pub fn check_channel_game_limit(
+    db_conn: &DbConnection,
+    option_guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+) -> Result<(), CommandError> {
+    let max_games = match option_guild_id {
+        Some(guild_id) => db_conn.max_games_per_channel_for_guild(guild_id)?,
+        None => None,
+    }
+    .unwrap_or(DEFAULT_MAX_GAMES_PER_CHANNEL);
+    let count = db_conn.game_count_for_channel(channel_id)?;
+    if count >= max_games {
+        return Err(CommandError::from(format!(
+            "This channel already has {} games, which is as many as this server allows. \
+             Archive or remove one, or ask an admin to raise the limit with !channelgamelimit",
+            count
+        )));
+    }
+    Ok(())
+}
+
+fn channel_game_limit_helper(
+    db_conn: &DbConnection,
+    guild_id: GuildId,
+    text: &str,
+) -> Result<String, CommandError> {
+    let option_max_games = parse_game_limit(text)?;
+    db_conn.set_max_games_per_channel_for_guild(guild_id, option_max_games)?;
+    Ok(format!(
+        "Each channel in this server can have up to {} games",
+        option_max_games.unwrap_or(DEFAULT_MAX_GAMES_PER_CHANNEL)
+    ))
+}
+
+pub fn channel_game_limit(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let text = args.single_quoted::<String>()?.to_lowercase();
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let guild_id = admin_guild_id(message)?;
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = channel_game_limit_helper(db_conn, guild_id, &text)?;
+    info!("channel_game_limit: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+#[test]
+fn should_parse_limits() {
+    assert_eq!(parse_game_limit("default").unwrap(), None);
+    assert_eq!(parse_game_limit("40").unwrap(), Some(40));
+    assert!(parse_game_limit("0").is_err());
+    assert!(parse_game_limit("501").is_err());
+    assert!(parse_game_limit("lots").is_err());
+}
+
+#[test]
+fn should_stop_adding_games_at_the_guild_limit() {
+    let db_conn = DbConnection::test();
+    let guild_id = GuildId(3581);
+    let channel_id = ChannelId(3582);
+    for i in 1..=3 {
+        db_conn
+            .set_channel_for_alias(&format!("test server {}", i), channel_id)
+            .unwrap();
+    }
+    check_channel_game_limit(&db_conn, Some(guild_id), channel_id).unwrap();
+
+    channel_game_limit_helper(&db_conn, guild_id, "3").unwrap();
+    assert!(check_channel_game_limit(&db_conn, Some(guild_id), channel_id).is_err());
+    // Only the guild that set it
+    check_channel_game_limit(&db_conn, Some(GuildId(3583)), channel_id).unwrap();
+
+    // Archived games make room
+    db_conn
+        .set_archived_for_alias("test server 3", true)
+        .unwrap();
+    check_channel_game_limit(&db_conn, Some(guild_id), channel_id).unwrap();
+
+    channel_game_limit_helper(&db_conn, guild_id, "default").unwrap();
+    assert_eq!(
+        db_conn.max_games_per_channel_for_guild(guild_id).unwrap(),
+        None
+    );
+}
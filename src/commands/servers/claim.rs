@@ -0,0 +1,123 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::Player;
+use crate::server::ServerConnection;
+
+#[cfg(test)]
+mod tests;
+
+/// The name of the nation `user_id` can claim, if it's a human nation being
+/// played in the game that nobody here has registered as
+fn claimable_nation(
+    alias: &str,
+    nations: &NationDetails,
+    user_id: UserId,
+    nation_id: u32,
+) -> Result<String, CommandError> {
+    let playing_state = match nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => playing_state,
+        NationDetails::Started(_) => {
+            return Err(CommandError::from(format!(
+                "{} is still uploading pretenders, use !register instead",
+                alias
+            )))
+        }
+        NationDetails::Lobby(_) => {
+            return Err(CommandError::from(format!(
+                "{} hasn't started yet, use !register instead",
+                alias
+            )))
+        }
+    };
+    if let Some(potential_player) = playing_state
+        .players
+        .iter()
+        .find(|potential_player| potential_player.option_player_id() == Some(&user_id))
+    {
+        return Err(CommandError::from(format!(
+            "You're already registered as {} in {}",
+            potential_player.nation_name(),
+            alias
+        )));
+    }
+    let option_potential_player = playing_state
+        .players
+        .iter()
+        .find(|potential_player| potential_player.nation_id() == nation_id);
+    match option_potential_player {
+        Some(PotentialPlayer::GameOnly(details)) if details.player_status.is_human() => {
+            Ok(details.nation_name.clone())
+        }
+        Some(PotentialPlayer::GameOnly(details)) => Err(CommandError::from(format!(
+            "{} isn't being played by a human in {}",
+            details.nation_name, alias
+        ))),
+        Some(PotentialPlayer::RegisteredAndGame(claimant, details)) => {
+            Err(CommandError::from(format!(
+                "{} ({}) is already registered to <@{}> in {}",
+                details.nation_name, nation_id, claimant, alias
+            )))
+        }
+        Some(PotentialPlayer::RegisteredOnly(_, _, _)) | None => Err(CommandError::from(format!(
+            "Nation {} isn't in {}",
+            nation_id, alias
+        ))),
+    }
+}
+
+fn claim_helper<C: ServerConnection>(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+    user_id: UserId,
+    nation_id: u32,
+) -> Result<String, CommandError> {
+    let details = get_details_for_alias::<C>(db_conn, game_cache, alias)?;
+    let nation_name = claimable_nation(alias, &details.nations, user_id, nation_id)?;
+    let player = Player {
+        discord_user_id: user_id,
+        turn_notifications: true,
+    };
+    db_conn.insert_player_into_server(&player, alias, nation_id)?;
+    Ok(format!(
+        "You're now registered as {} ({}) in {}",
+        nation_name, nation_id, alias
+    ))
+}
+
+pub fn claim<C: ServerConnection>(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let nation_id = args
+        .single_quoted::<u32>()
+        .map_err(|_| CommandError::from("Expected the ID of the nation you're playing"))?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = claim_helper::<C>(db_conn, game_cache, &alias, message.author.id, nation_id)?;
+    info!("claim: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use super::*;
+
+use crate::model::enums::{NationStatus, SubmissionStatus};
+
+fn playing(players: Vec<PotentialPlayer>) -> NationDetails {
+    NationDetails::Started(StartedDetails {
+        address: "example.com:3000".to_owned(),
+        game_name: "claimgame".to_owned(),
+        state: StartedStateDetails::Playing(PlayingState {
+            players,
+            turn: 4,
+            mins_remaining: 0,
+            hours_remaining: 10,
+            days_remaining: 0,
+            secs_remaining: 0,
+            option_deadline: None,
+            paused: false,
+            blitz: false,
+        }),
+        option_snek_game_id: None,
+    })
+}
+
+fn nation(nation_id: u32, player_status: NationStatus) -> PlayerDetails {
+    PlayerDetails {
+        nation_id,
+        nation_name: format!("nation {}", nation_id),
+        submitted: SubmissionStatus::NotSubmitted,
+        player_status,
+        went_ai: false,
+        connected: false,
+    }
+}
+
+#[test]
+fn should_only_claim_unregistered_human_nations_in_the_game() {
+    let nations = playing(vec![
+        PotentialPlayer::GameOnly(nation(5, NationStatus::Human)),
+        PotentialPlayer::GameOnly(nation(6, NationStatus::AI)),
+        PotentialPlayer::RegisteredAndGame(UserId(2), nation(7, NationStatus::Human)),
+        PotentialPlayer::RegisteredOnly(UserId(3), 8, "nation 8".to_owned()),
+    ]);
+    assert_eq!(
+        claimable_nation("claimgame", &nations, UserId(1), 5).unwrap(),
+        "nation 5"
+    );
+    assert!(claimable_nation("claimgame", &nations, UserId(1), 6).is_err());
+    let err = claimable_nation("claimgame", &nations, UserId(1), 7).unwrap_err();
+    assert!(format!("{:?}", err).contains("already registered to <@2>"));
+    assert!(claimable_nation("claimgame", &nations, UserId(1), 8).is_err());
+    assert!(claimable_nation("claimgame", &nations, UserId(1), 99).is_err());
+}
+
+#[test]
+fn should_not_claim_a_second_nation() {
+    let nations = playing(vec![
+        PotentialPlayer::GameOnly(nation(5, NationStatus::Human)),
+        PotentialPlayer::RegisteredAndGame(UserId(2), nation(7, NationStatus::Human)),
+    ]);
+    let err = claimable_nation("claimgame", &nations, UserId(2), 5).unwrap_err();
+    assert!(format!("{:?}", err).contains("already registered as nation 7"));
+}
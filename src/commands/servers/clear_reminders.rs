@@ -0,0 +1,44 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// For taking a break from every game at once, instead of going through them
+/// one `!remindercap` at a time
+fn clear_reminders_helper(db_conn: &DbConnection, user_id: UserId) -> Result<String, CommandError> {
+    Ok(match db_conn.clear_all_reminders(user_id)? {
+        0 => "You don't have any reminder settings of your own to clear".to_owned(),
+        1 => "Cleared your reminder settings for 1 game, its own defaults still apply".to_owned(),
+        count => format!(
+            "Cleared your reminder settings for {} games, their own defaults still apply",
+            count
+        ),
+    })
+}
+
+pub fn clear_reminders(
+    context: &mut Context,
+    message: &Message,
+    args: Args,
+) -> Result<(), CommandError> {
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "!clearreminders covers all your games, it doesn't take any arguments",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = clear_reminders_helper(db_conn, message.author.id)?;
+    info!("clear_reminders: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+use crate::model::Player;
+use chrono::{TimeZone, Utc};
+
+fn register(db_conn: &DbConnection, alias: &str, user_id: u64, nation_id: u32) {
+    db_conn
+        .insert_player_into_server(
+            &Player {
+                discord_user_id: UserId(user_id),
+                turn_notifications: true,
+            },
+            alias,
+            nation_id,
+        )
+        .unwrap();
+}
+
+#[test]
+fn should_clear_caps_and_one_off_reminders_in_every_game() {
+    let db_conn = DbConnection::test();
+    register(&db_conn, "test server 1", 3811, 5);
+    register(&db_conn, "test server 2", 3811, 5);
+    register(&db_conn, "test server 3", 3811, 5);
+    db_conn
+        .set_reminder_cap("test server 1", UserId(3811), Some(Some(0)))
+        .unwrap();
+    db_conn
+        .set_reminder_cap("test server 2", UserId(3811), Some(None))
+        .unwrap();
+    db_conn
+        .set_one_off_reminder(
+            "test server 2",
+            UserId(3811),
+            Utc.ymd(2021, 3, 12).and_hms(12, 0, 0),
+            "play your turn",
+        )
+        .unwrap();
+    register(&db_conn, "test server 1", 3812, 6);
+    db_conn
+        .set_reminder_cap("test server 1", UserId(3812), Some(Some(1)))
+        .unwrap();
+
+    assert_eq!(
+        clear_reminders_helper(&db_conn, UserId(3811)).unwrap(),
+        "Cleared your reminder settings for 2 games, their own defaults still apply"
+    );
+    let caps = db_conn.reminder_caps_for_alias("test server 1").unwrap();
+    assert_eq!(caps.get(&UserId(3811)), None);
+    // Nobody else's settings go with them
+    assert_eq!(caps.get(&UserId(3812)), Some(&Some(1)));
+    assert!(db_conn
+        .reminder_caps_for_alias("test server 2")
+        .unwrap()
+        .is_empty());
+    assert!(db_conn
+        .take_due_one_off_reminders(Utc.ymd(2021, 3, 13).and_hms(0, 0, 0))
+        .unwrap()
+        .iter()
+        .all(|(user_id, _)| *user_id != UserId(3811)));
+}
+
+#[test]
+fn should_say_when_there_was_nothing_to_clear() {
+    let db_conn = DbConnection::test();
+    register(&db_conn, "test server 1", 3813, 5);
+    assert_eq!(
+        clear_reminders_helper(&db_conn, UserId(3813)).unwrap(),
+        "You don't have any reminder settings of your own to clear"
+    );
+}
@@ -0,0 +1,57 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::GuildId;
+use serenity::prelude::Context;
+
+use super::admin_guild_id;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+fn confirm_submissions_helper(
+    db_conn: &DbConnection,
+    guild_id: GuildId,
+    arg_setting: &str,
+) -> Result<String, CommandError> {
+    let submission_confirmations = match arg_setting {
+        "on" => true,
+        "off" => false,
+        _ => {
+            return Err(CommandError::from(format!(
+                "Unknown setting \"{}\", try \"on\" or \"off\"",
+                arg_setting
+            )))
+        }
+    };
+    db_conn.set_submission_confirmations_for_guild(guild_id, submission_confirmations)?;
+    Ok(if submission_confirmations {
+        "Games in this server will say when each registered player's turn goes in".to_owned()
+    } else {
+        "Games in this server won't say when turns go in".to_owned()
+    })
+}
+
+pub fn confirm_submissions(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_setting = args.single_quoted::<String>()?.to_lowercase();
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let guild_id = admin_guild_id(message)?;
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = confirm_submissions_helper(db_conn, guild_id, &arg_setting)?;
+    info!("confirm_submissions: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
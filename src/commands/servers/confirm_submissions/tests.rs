@@ -0,0 +1,26 @@
+use super::*;
+
+#[test]
+fn should_turn_confirmations_on_and_off() {
+    let db_conn = DbConnection::test();
+    let guild_id = GuildId(339_001);
+    assert!(!db_conn
+        .submission_confirmations_for_guild(guild_id)
+        .unwrap());
+
+    confirm_submissions_helper(&db_conn, guild_id, "on").unwrap();
+    assert!(db_conn
+        .submission_confirmations_for_guild(guild_id)
+        .unwrap());
+    // doesn't touch the guild's other settings
+    db_conn
+        .set_details_as_text_for_guild(guild_id, true)
+        .unwrap();
+    confirm_submissions_helper(&db_conn, guild_id, "off").unwrap();
+    assert!(!db_conn
+        .submission_confirmations_for_guild(guild_id)
+        .unwrap());
+    assert!(db_conn.details_as_text_for_guild(guild_id).unwrap());
+
+    assert!(confirm_submissions_helper(&db_conn, guild_id, "sometimes").is_err());
+}
@@ -0,0 +1,82 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// A reminder an hour out, then a louder one with a quarter of an hour to go
+const DEFAULT_COUNTDOWN_MINS: [u32; 2] = [60, 15];
+const MAX_COUNTDOWN_MINS: u32 = 24 * 60;
+
+/// "on", "off", or minutes before the deadline like "60,15". `None` is off.
+fn parse_countdown_mins(text: &str) -> Result<Option<Vec<u32>>, CommandError> {
+    match text {
+        "on" => Ok(Some(DEFAULT_COUNTDOWN_MINS.to_vec())),
+        "off" => Ok(None),
+        _ => {
+            let mut countdown_mins = text
+                .split(',')
+                .map(|mins| match mins.trim().parse::<u32>() {
+                    Ok(mins) if mins > 0 && mins <= MAX_COUNTDOWN_MINS => Ok(mins),
+                    _ => Err(CommandError::from(format!(
+                        "Countdowns are \"on\", \"off\" or minutes before the deadline between 1 and {}, like \"60,15\"",
+                        MAX_COUNTDOWN_MINS
+                    ))),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            // the poller wants the first (most minutes) one first
+            countdown_mins.sort_unstable_by(|mins1, mins2| mins2.cmp(mins1));
+            countdown_mins.dedup();
+            Ok(Some(countdown_mins))
+        }
+    }
+}
+
+fn countdown_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    text: &str,
+) -> Result<String, CommandError> {
+    let option_countdown_mins = parse_countdown_mins(text)?;
+    db_conn.set_countdown_mins_for_alias(alias, option_countdown_mins.as_deref())?;
+    Ok(match option_countdown_mins {
+        Some(countdown_mins) => format!(
+            "Players who haven't played in {} will be pinged {} minutes before the deadline",
+            alias,
+            countdown_mins
+                .iter()
+                .map(|mins| mins.to_string())
+                .collect::<Vec<_>>()
+                .join(" and ")
+        ),
+        None => format!("Turned off countdown pings for {}", alias),
+    })
+}
+
+pub fn countdown(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let text = args.single_quoted::<String>()?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = countdown_helper(db_conn, &alias, &text)?;
+    info!("countdown: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
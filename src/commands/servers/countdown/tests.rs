@@ -0,0 +1,57 @@
+use super::*;
+
+use crate::commands::servers::turn_check::countdown_threshold;
+
+#[test]
+fn should_parse_countdown_mins() {
+    assert_eq!(parse_countdown_mins("on").unwrap(), Some(vec![60, 15]));
+    assert_eq!(parse_countdown_mins("off").unwrap(), None);
+    assert_eq!(
+        parse_countdown_mins("15, 120,15").unwrap(),
+        Some(vec![120, 15])
+    );
+    assert!(parse_countdown_mins("0").is_err());
+    assert!(parse_countdown_mins("soon").is_err());
+}
+
+#[test]
+fn should_pick_the_closest_threshold_reached() {
+    assert_eq!(countdown_threshold(&[60, 15], 90), None);
+    assert_eq!(countdown_threshold(&[60, 15], 60), Some(60));
+    assert_eq!(countdown_threshold(&[60, 15], 40), Some(60));
+    // missing the first one (e.g. the bot was down) goes straight to the second
+    assert_eq!(countdown_threshold(&[60, 15], 10), Some(15));
+}
+
+#[test]
+fn should_ping_each_threshold_once_per_turn() {
+    let db_conn = DbConnection::test();
+    countdown_helper(&db_conn, "test server 1", "on").unwrap();
+    assert_eq!(
+        db_conn.countdown_mins_for_alias("test server 1").unwrap(),
+        Some(vec![60, 15])
+    );
+
+    assert!(db_conn
+        .mark_countdown_pinged("test server 1", 32, 60)
+        .unwrap());
+    assert!(!db_conn
+        .mark_countdown_pinged("test server 1", 32, 60)
+        .unwrap());
+    assert!(db_conn
+        .mark_countdown_pinged("test server 1", 32, 15)
+        .unwrap());
+    // never back to a looser one in the same turn
+    assert!(!db_conn
+        .mark_countdown_pinged("test server 1", 32, 60)
+        .unwrap());
+    assert!(db_conn
+        .mark_countdown_pinged("test server 1", 33, 60)
+        .unwrap());
+
+    countdown_helper(&db_conn, "test server 1", "off").unwrap();
+    assert_eq!(
+        db_conn.countdown_mins_for_alias("test server 1").unwrap(),
+        None
+    );
+}
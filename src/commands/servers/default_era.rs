@@ -0,0 +1,42 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use crate::db::DbConnectionKey;
+use crate::model::enums::Era;
+
+/// Pass this instead of an era to go back to having to give one every time
+const CLEAR_FLAG: &str = "--clear";
+
+pub fn default_era(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_era = args.single_quoted::<String>()?;
+    let option_era = if arg_era == CLEAR_FLAG {
+        None
+    } else {
+        Some(Era::from_string(&arg_era).ok_or("unknown era")?)
+    };
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    db_conn.set_default_era_for_channel(message.channel_id, option_era)?;
+
+    let text = match option_era {
+        Some(era) => format!("Lobbies created in this channel will be {} by default", era),
+        None => "Lobbies created in this channel need an era again".to_owned(),
+    };
+    info!("{}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::reminder_cap::parse_reminder_cap;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+fn default_reminder_cap_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    arg: &str,
+) -> Result<String, CommandError> {
+    // Games added with !add have nobody in charge of them, so anyone can
+    let option_owner = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(lobby_state) => Some(lobby_state.owner),
+        GameServerState::StartedState(_, option_lobby_state)
+        | GameServerState::Archived(_, option_lobby_state) => {
+            option_lobby_state.map(|lobby_state| lobby_state.owner)
+        }
+    };
+    if option_owner.is_some_and(|owner| owner != author_id) {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} can do this",
+            alias
+        )));
+    }
+
+    let option_max_reminders = parse_reminder_cap(arg)?;
+    db_conn.set_default_reminder_cap_for_alias(alias, option_max_reminders)?;
+    let what = match option_max_reminders {
+        None => "every countdown reminder".to_owned(),
+        Some(0) => "no countdown reminders".to_owned(),
+        Some(1) => "only the last countdown reminder".to_owned(),
+        Some(max_reminders) => format!("only the last {} countdown reminders", max_reminders),
+    };
+    Ok(format!(
+        "Players in {} will get {}, unless they've set their own with !remindercap",
+        alias, what
+    ))
+}
+
+pub fn default_reminder_cap(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg = args.single_quoted::<String>().map_err(|_| {
+        CommandError::from("Expected how many countdown reminders players get a turn, or \"all\"")
+    })?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = default_reminder_cap_helper(db_conn, message.author.id, &alias, &arg)?;
+    info!("defaultremindercap: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use super::*;
+
+use crate::commands::servers::reminder_cap::ALL_REMINDERS;
+use crate::model::enums::Era;
+use crate::model::{GameServer, LobbyState};
+
+#[test]
+fn should_set_default_for_the_game() {
+    let db_conn = DbConnection::test();
+    // "test server 1" was added without a lobby, so has no owner
+    default_reminder_cap_helper(&db_conn, UserId(327), "test server 1", "1").unwrap();
+    assert_eq!(
+        db_conn
+            .default_reminder_cap_for_alias("test server 1")
+            .unwrap(),
+        Some(1)
+    );
+    assert_eq!(
+        db_conn
+            .default_reminder_cap_for_alias("test server 2")
+            .unwrap(),
+        None
+    );
+
+    default_reminder_cap_helper(&db_conn, UserId(327), "test server 1", ALL_REMINDERS).unwrap();
+    assert_eq!(
+        db_conn
+            .default_reminder_cap_for_alias("test server 1")
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn should_only_let_the_owner_set_default() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(1),
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+
+    assert!(default_reminder_cap_helper(&db_conn, UserId(2), "lobby", "1").is_err());
+    assert!(default_reminder_cap_helper(&db_conn, UserId(1), "lobby", "some").is_err());
+    default_reminder_cap_helper(&db_conn, UserId(1), "lobby", "2").unwrap();
+    assert_eq!(
+        db_conn.default_reminder_cap_for_alias("lobby").unwrap(),
+        Some(2)
+    );
+}
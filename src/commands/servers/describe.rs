@@ -1,30 +1,108 @@
-use super::alias_from_arg_or_channel_name;
-
+use log::*;
 use serenity::framework::standard::{Args, CommandError};
 use serenity::model::channel::Message;
+use serenity::model::id::UserId;
 use serenity::prelude::Context;
 
-use crate::db::DbConnectionKey;
+use super::alias_from_arg_or_channel_name;
+use crate::commands::servers::GameCacheKey;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of a description to remove it
+const CLEAR_FLAG: &str = "--clear";
+/// Discord won't show an embed field longer than this
+const MAX_DESCRIPTION_LENGTH: usize = 1024;
+/// Doesn't show up, but stops discord seeing a mention
+const ZERO_WIDTH_SPACE: &str = "\u{200B}";
+
+/// Breaks up anything discord would ping for, so a description can't be used to
+/// ping everyone in the channel each time someone looks at the game. Doing it
+/// twice changes nothing.
+pub fn sanitise_mentions(text: &str) -> String {
+    text.replace("@everyone", &format!("@{}everyone", ZERO_WIDTH_SPACE))
+        .replace("@here", &format!("@{}here", ZERO_WIDTH_SPACE))
+        // <@user>, <@!user> and <@&role>
+        .replace("<@", &format!("<{}@", ZERO_WIDTH_SPACE))
+}
+
+fn describe_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    option_description: Option<&str>,
+) -> Result<String, CommandError> {
+    // Games added with !add have nobody in charge of them, so anyone can
+    let option_owner = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(lobby_state) => Some(lobby_state.owner),
+        GameServerState::StartedState(_, option_lobby_state)
+        | GameServerState::Archived(_, option_lobby_state) => {
+            option_lobby_state.map(|lobby_state| lobby_state.owner)
+        }
+    };
+    if option_owner.is_some_and(|owner| owner != author_id) {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} can do this",
+            alias
+        )));
+    }
+
+    let description = match option_description {
+        Some(description) => sanitise_mentions(description.trim()),
+        None => {
+            db_conn.set_description_for_alias(alias, None)?;
+            return Ok(format!("Removed the description of {}", alias));
+        }
+    };
+    if description.is_empty() {
+        return Err(CommandError::from(format!(
+            "The description can't be empty, use {} to remove it",
+            CLEAR_FLAG
+        )));
+    }
+    if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+        return Err(CommandError::from(format!(
+            "Descriptions can be at most {} characters",
+            MAX_DESCRIPTION_LENGTH
+        )));
+    }
+    db_conn.set_description_for_alias(alias, Some(&description))?;
+    Ok(format!("Added description to {}", alias))
+}
 
 pub fn describe(
     context: &mut Context,
     message: &Message,
     mut args: Args,
 ) -> Result<(), CommandError> {
-    let data = context.data.lock();
-    let db_conn = data
-        .get::<DbConnectionKey>()
-        .ok_or("No DbConnection was created on startup. This is a bug.")?;
-
-    let description = args.single_quoted::<String>()?;
+    let description = args.single_quoted::<String>().map_err(|_| {
+        CommandError::from("Expected a description in quotes, e.g. !describe \"**Bless** rush\"")
+    })?;
     let alias = alias_from_arg_or_channel_name(&mut args, &message)?;
     if !args.is_empty() {
         return Err(CommandError::from(
             "Too many arguments. TIP: the description needs to be in quotes",
         ));
     }
+    let option_description = if description == CLEAR_FLAG {
+        None
+    } else {
+        Some(description.as_str())
+    };
 
-    db_conn.update_lobby_with_description(&alias, &description)?;
-    message.reply(&format!("added description to {}", alias))?;
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = describe_helper(db_conn, message.author.id, &alias, option_description)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+    info!("describe: {}", text);
+    message.reply(&text)?;
     Ok(())
 }
@@ -0,0 +1,81 @@
+use super::*;
+
+use crate::model::enums::Era;
+use crate::model::{GameServer, LobbyState};
+
+const OWNER: UserId = UserId(1);
+
+fn lobby_description(db_conn: &DbConnection) -> Option<String> {
+    match db_conn.game_for_alias("lobby").unwrap().state {
+        GameServerState::Lobby(lobby_state) => lobby_state.description,
+        state => panic!("expected a lobby, got {:?}", state),
+    }
+}
+
+#[test]
+fn should_break_up_mentions_once() {
+    let sanitised = sanitise_mentions("@everyone @here <@123> <@!123> <@&456> me@example.com");
+    assert!(!sanitised.contains("@everyone"));
+    assert!(!sanitised.contains("@here"));
+    assert!(!sanitised.contains("<@"));
+    assert!(sanitised.contains("me@example.com"));
+    assert_eq!(sanitise_mentions(&sanitised), sanitised);
+}
+
+#[test]
+fn should_set_and_clear_started_game_description() {
+    let db_conn = DbConnection::test();
+    // "test server 1" was added without a lobby, so has no owner
+    let text = describe_helper(
+        &db_conn,
+        UserId(327),
+        "test server 1",
+        Some("**No** rushing @everyone"),
+    )
+    .unwrap();
+    assert_eq!(text, "Added description to test server 1");
+    assert_eq!(
+        db_conn.description_for_alias("test server 1").unwrap(),
+        Some(format!("**No** rushing @{}everyone", ZERO_WIDTH_SPACE))
+    );
+
+    describe_helper(&db_conn, UserId(327), "test server 1", None).unwrap();
+    assert_eq!(
+        db_conn.description_for_alias("test server 1").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn should_keep_lobby_description_on_lobby_and_only_let_owner() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: OWNER,
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+
+    assert!(describe_helper(&db_conn, UserId(2), "lobby", Some("mine now")).is_err());
+    describe_helper(&db_conn, OWNER, "lobby", Some("late age")).unwrap();
+    assert_eq!(lobby_description(&db_conn), Some("late age".to_owned()));
+    assert_eq!(db_conn.description_for_alias("lobby").unwrap(), None);
+}
+
+#[test]
+fn should_reject_long_or_empty_descriptions() {
+    let db_conn = DbConnection::test();
+    let long = "a".repeat(MAX_DESCRIPTION_LENGTH + 1);
+    assert!(describe_helper(&db_conn, UserId(327), "test server 1", Some(&long)).is_err());
+    assert!(describe_helper(&db_conn, UserId(327), "test server 1", Some("  ")).is_err());
+    assert!(describe_helper(&db_conn, UserId(327), "not a game", Some("hi")).is_err());
+    assert_eq!(
+        db_conn.description_for_alias("test server 1").unwrap(),
+        None
+    );
+}
@@ -1,79 +1,316 @@
+use crate::commands::servers::GameCache;
 use crate::server::ServerConnection;
 
 use serenity::framework::standard::CommandError;
 
 use crate::db::DbConnection;
+use crate::game_log::GameLogContext;
 use crate::model::enums::{Era, NationStatus, Nations, SubmissionStatus};
-use crate::model::{GameData, GameServerState, LobbyState, Nation, Player, StartedState};
-use crate::snek::SnekGameStatus;
+use crate::model::{
+    GameData, GameServerState, LobbyState, Nation, Player, RosterEntry, StartedState,
+};
+use crate::snek::{providers_from_names, NationNameProvider, SnekGameStatus, DEFAULT_PROVIDERS};
+use chrono::{DateTime, Duration, Utc};
 use log::*;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serenity::model::id::UserId;
 use std::cmp::max;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 /// We cache the call to the server (both the game itself and the snek api)
 /// but NOT the db call
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 pub struct CacheEntry {
     pub game_data: GameData,
     pub option_snek_state: Option<SnekGameStatus>,
+    /// When we actually got this from the server, as opposed to when we last tried
+    #[serde(
+        serialize_with = "serialize_fetched_at",
+        deserialize_with = "deserialize_fetched_at"
+    )]
+    pub fetched_at: DateTime<Utc>,
 }
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub struct GameDetails {
     pub alias: String,
+    #[serde(serialize_with = "serialize_option_user_id")]
     pub owner: Option<UserId>,
     pub description: Option<String>,
     pub nations: NationDetails,
+    /// Shown instead of the player's discord name, if they've set one
+    #[serde(serialize_with = "serialize_player_nicknames")]
+    pub player_nicknames: HashMap<UserId, String>,
+    /// Players who aren't getting new turn DMs right now, and until when
+    #[serde(serialize_with = "serialize_player_vacations")]
+    pub player_vacations: HashMap<UserId, DateTime<Utc>>,
+    /// The raw data that everything else came from, nobody outside needs this
+    #[serde(skip)]
     pub cache_entry: Option<CacheEntry>,
+    /// Nations that couldn't be resolved, they're still listed under a placeholder
+    pub warnings: Vec<String>,
 }
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub enum NationDetails {
     Lobby(LobbyDetails),
     Started(StartedDetails),
 }
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub struct StartedDetails {
     pub address: String,
     pub game_name: String,
     pub state: StartedStateDetails,
+    /// Falls back to the last one we saw if snek doesn't answer this time
+    pub option_snek_game_id: Option<u32>,
 }
 
-pub fn get_nation_string(option_snek_state: &Option<SnekGameStatus>, nation_id: u32) -> String {
+/// `None` if neither the providers, what they told us before, nor our own list
+/// know the nation
+fn resolve_nation_name(
+    option_snek_state: &Option<SnekGameStatus>,
+    recorded_names: &HashMap<u32, String>,
+    nation_id: u32,
+) -> Option<String> {
     let snek_nation_details = option_snek_state
         .as_ref()
         .and_then(|snek_details| snek_details.nations.get(&nation_id));
     match snek_nation_details {
-        Some(snek_nation) => snek_nation.name.clone(),
-        None => {
-            let &(nation_name, _) = Nations::get_nation_desc(nation_id);
-            nation_name.to_owned()
+        Some(snek_nation) => Some(snek_nation.name.clone()),
+        None => recorded_names.get(&nation_id).cloned().or_else(|| {
+            Nations::get_nation_desc(nation_id).map(|&(nation_name, _)| nation_name.to_owned())
+        }),
+    }
+}
+
+/// Saves what the poller learnt from a poll that should outlive it: any names
+/// the providers gave that we hadn't seen, so mod nations keep their names after
+/// a restart when the providers are down, and when we first saw the game playing.
+/// Showing a game only ever reads these.
+pub fn record_poll(
+    db_conn: &DbConnection,
+    alias: &str,
+    cache_entry: &CacheEntry,
+) -> Result<(), CommandError> {
+    if let Some(snek_state) = &cache_entry.option_snek_state {
+        let recorded_names = db_conn.nation_names_for_alias(alias)?;
+        let discovered: Vec<(u32, String)> = snek_state
+            .nations
+            .iter()
+            .filter(|(nation_id, snek_nation)| {
+                recorded_names.get(nation_id) != Some(&snek_nation.name)
+            })
+            .map(|(nation_id, snek_nation)| (*nation_id, snek_nation.name.clone()))
+            .collect();
+        if !discovered.is_empty() {
+            db_conn.record_nation_names(alias, &discovered)?;
         }
     }
+    if cache_entry.game_data.turn >= 0 {
+        db_conn.record_first_seen(alias, cache_entry.fetched_at, cache_entry.game_data.turn)?;
+    }
+    Ok(())
 }
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub enum StartedStateDetails {
     Playing(PlayingState),
     Uploading(UploadingState),
 }
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub struct UploadingState {
     pub uploading_players: Vec<UploadingPlayer>,
 }
-#[derive(PartialEq, Eq, Clone, Debug)]
+impl UploadingState {
+    pub fn upload_progress(&self) -> UploadProgress {
+        let mut progress = UploadProgress {
+            uploaded: 0,
+            expected: 0,
+            unregistered: 0,
+        };
+        for uploading_player in &self.uploading_players {
+            match (
+                uploading_player.option_player_id(),
+                uploading_player.uploaded,
+            ) {
+                (Some(_), true) => {
+                    progress.expected += 1;
+                    progress.uploaded += 1;
+                }
+                (Some(_), false) => progress.expected += 1,
+                (None, true) => progress.unregistered += 1,
+                (None, false) => {}
+            }
+        }
+        progress
+    }
+}
+/// "Expected" means the people who registered, since that's who the host is waiting on
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct UploadProgress {
+    /// Registered players whose pretender is in
+    pub uploaded: usize,
+    pub expected: usize,
+    /// Pretenders from people who didn't register, these don't count towards `expected`
+    pub unregistered: usize,
+}
+impl UploadProgress {
+    pub fn is_complete(&self) -> bool {
+        self.expected > 0 && self.uploaded == self.expected
+    }
+}
+impl fmt::Display for UploadProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} pretenders uploaded", self.uploaded, self.expected)?;
+        if self.unregistered > 0 {
+            write!(f, " (and {} from unregistered players)", self.unregistered)?;
+        }
+        Ok(())
+    }
+}
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub struct PlayingState {
     pub players: Vec<PotentialPlayer>,
     pub turn: u32,
     pub mins_remaining: i32,
+    /// All of the hours left, including any in `days_remaining`
     pub hours_remaining: i32,
+    /// The whole days in `hours_remaining`, for showing long timers
+    pub days_remaining: i32,
+    /// The seconds on top of `mins_remaining`, only shown for blitz games
+    pub secs_remaining: i32,
+    /// `None` if there's no timer set or the game is paused
+    #[serde(serialize_with = "serialize_option_deadline")]
+    pub option_deadline: Option<DateTime<Utc>>,
+    /// The time remaining is meaningless (and zeroed) while this is set
+    pub paused: bool,
+    /// On a timer of minutes, so the time left is shown to the second, see `!blitz`
+    pub blitz: bool,
+}
+/// How `show_time_remaining_as` shows the time left, picked per server with
+/// `!timeformat`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TimeFormat {
+    /// e.g. 1h 47m
+    #[default]
+    Exact,
+    /// To the nearest 15 minutes, e.g. ~1h 45m
+    Rounded,
+    /// To the nearest hour, or day once it's more than a day, e.g. ~2h
+    Coarse,
+}
+
+impl TimeFormat {
+    pub fn from_name(name: &str) -> Option<TimeFormat> {
+        match name {
+            "exact" => Some(TimeFormat::Exact),
+            "rounded" => Some(TimeFormat::Rounded),
+            "coarse" => Some(TimeFormat::Coarse),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TimeFormat::Exact => "exact",
+            TimeFormat::Rounded => "rounded",
+            TimeFormat::Coarse => "coarse",
+        }
+    }
 }
-#[derive(PartialEq, Eq, Clone, Debug)]
+
+/// Rounds to the nearest `step`, halves going up
+fn round_mins(mins: i32, step: i32) -> i32 {
+    (mins + step / 2) / step * step
+}
+
+impl PlayingState {
+    /// Every human nation has played as far as the server can tell, so the turn
+    /// is only waiting for the host to get to it. Not while paused, that's
+    /// waiting on a person.
+    pub fn waiting_on_host(&self) -> bool {
+        let mut humans = self
+            .players
+            .iter()
+            .filter_map(|potential_player| match potential_player {
+                PotentialPlayer::RegisteredAndGame(_, details)
+                | PotentialPlayer::GameOnly(details) => Some(details),
+                PotentialPlayer::RegisteredOnly(_, _, _) => None,
+            })
+            .filter(|details| details.player_status.is_human())
+            .peekable();
+        !self.paused
+            && humans.peek().is_some()
+            && humans.all(|details| details.submitted == SubmissionStatus::Submitted)
+    }
+
+    pub fn show_time_remaining(&self) -> String {
+        self.show_time_remaining_as(TimeFormat::Exact)
+    }
+
+    /// For the middle of a sentence, where "paused remaining" would read oddly
+    pub fn show_time_left_as(&self, time_format: TimeFormat) -> String {
+        if self.paused {
+            "paused".to_owned()
+        } else {
+            format!("{} remaining", self.show_time_remaining_as(time_format))
+        }
+    }
+
+    pub fn show_time_remaining_as(&self, time_format: TimeFormat) -> String {
+        if self.paused {
+            return "paused".to_owned();
+        }
+        let total_mins = self.hours_remaining * 60 + self.mins_remaining;
+        // Rounding a five minute timer to the nearest quarter hour tells nobody anything
+        if self.blitz {
+            return format!("{}m {}s", total_mins, self.secs_remaining);
+        }
+        match time_format {
+            TimeFormat::Exact if self.days_remaining > 0 => format!(
+                "{}d {}h {}m",
+                self.days_remaining,
+                self.hours_remaining % 24,
+                self.mins_remaining
+            ),
+            TimeFormat::Exact => format!("{}h {}m", self.hours_remaining, self.mins_remaining),
+            TimeFormat::Rounded => match round_mins(total_mins, 15) {
+                0 => "under 15m".to_owned(),
+                mins if mins >= 24 * 60 => {
+                    format!("~{}d {}h {}m", mins / (24 * 60), mins / 60 % 24, mins % 60)
+                }
+                mins if mins >= 60 => format!("~{}h {}m", mins / 60, mins % 60),
+                mins => format!("~{}m", mins),
+            },
+            TimeFormat::Coarse if total_mins < 60 => "under an hour".to_owned(),
+            TimeFormat::Coarse if total_mins < 24 * 60 => {
+                format!("~{}h", round_mins(total_mins, 60) / 60)
+            }
+            TimeFormat::Coarse => format!("~{}d", round_mins(total_mins, 24 * 60) / (24 * 60)),
+        }
+    }
+}
+
+/// A paused server reports a timer that's negative (its unsigned sentinel read as
+/// an `i32`) or right up against the maximum, rather than no timer at all
+pub fn is_paused_timer(turn_timer: i32) -> bool {
+    const PAUSED_TIMER_SLACK_MS: i32 = 60 * 1000;
+    !(0..i32::MAX - PAUSED_TIMER_SLACK_MS).contains(&turn_timer)
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub enum PotentialPlayer {
-    RegisteredOnly(UserId, u32, String),
-    RegisteredAndGame(UserId, PlayerDetails),
+    RegisteredOnly(
+        #[serde(serialize_with = "serialize_user_id")] UserId,
+        u32,
+        String,
+    ),
+    RegisteredAndGame(
+        #[serde(serialize_with = "serialize_user_id")] UserId,
+        PlayerDetails,
+    ),
     GameOnly(PlayerDetails),
 }
 impl PotentialPlayer {
@@ -104,19 +341,29 @@ impl PartialOrd<Self> for PotentialPlayer {
         Some(self.cmp(other))
     }
 }
+/// By name, then by ID for providers that give two nations the same name, so
+/// the order doesn't change from one poll to the next
 impl Ord for PotentialPlayer {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.nation_name().cmp(&other.nation_name())
+        self.nation_name()
+            .cmp(other.nation_name())
+            .then_with(|| self.nation_id().cmp(&other.nation_id()))
     }
 }
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub struct PlayerDetails {
     pub nation_id: u32,
     pub nation_name: String,
     pub submitted: SubmissionStatus,
     pub player_status: NationStatus,
+    /// Somebody registered as this nation but it's now being played by the AI,
+    /// as opposed to a nation that was AI from the start
+    pub went_ai: bool,
+    /// Whether the player has the game open right now. Dom5 reports this
+    /// separately from the nation's status, and only ever for humans.
+    pub connected: bool,
 }
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub struct UploadingPlayer {
     pub potential_player: PotentialPlayer,
     pub uploaded: bool,
@@ -132,30 +379,154 @@ impl UploadingPlayer {
         self.potential_player.option_player_id()
     }
 }
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub struct LobbyDetails {
     pub players: Vec<LobbyPlayer>,
     pub era: Option<Era>,
-    pub remaining_slots: u32,
+    /// `None` when the lobby's player count isn't known
+    pub remaining_slots: Option<u32>,
+    /// Kept for the AI by the owner, so not in `players` or taking up a slot
+    pub ai_reserved: Vec<AiReservedNation>,
+    /// Only known when the owner has said which nations are meant to be played
+    pub option_open_nations: Option<Vec<OpenNation>>,
+    /// On the roster but not registered yet. They each hold a slot.
+    #[serde(serialize_with = "serialize_user_ids")]
+    pub expected: Vec<UserId>,
+    /// Waiting for a slot, first in line first
+    #[serde(serialize_with = "serialize_user_ids")]
+    pub waitlist: Vec<UserId>,
+}
+impl LobbyDetails {
+    /// A lobby without a player count always has room
+    pub fn is_full(&self) -> bool {
+        self.remaining_slots == Some(0)
+    }
+}
+/// e.g. "3 open slots", for a lobby's `remaining_slots`
+pub fn show_open_slots(remaining_slots: Option<u32>) -> String {
+    match remaining_slots {
+        Some(remaining_slots) => format!("{} open slots", remaining_slots),
+        None => "open slots: unknown".to_owned(),
+    }
 }
-#[derive(PartialEq, Eq, Clone, Debug)]
+/// In the lobby's nation set, but nobody has registered as it or kept it for the AI
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
+pub struct OpenNation {
+    pub nation_id: u32,
+    pub nation_name: String,
+}
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
+pub struct AiReservedNation {
+    pub nation_id: u32,
+    pub nation_name: String,
+    /// Somebody registered as it anyway
+    #[serde(serialize_with = "serialize_option_user_id")]
+    pub option_player_id: Option<UserId>,
+}
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 pub struct LobbyPlayer {
+    #[serde(serialize_with = "serialize_user_id")]
     pub player_id: UserId,
     pub nation_id: u32,
     pub nation_name: String,
 }
 
+// Discord IDs are bigger than javascript can handle as numbers, so they go out as strings
+fn serialize_user_id<S: Serializer>(user_id: &UserId, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&user_id.0.to_string())
+}
+
+fn serialize_option_user_id<S: Serializer>(
+    option_user_id: &Option<UserId>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    option_user_id
+        .map(|user_id| user_id.0.to_string())
+        .serialize(serializer)
+}
+
+fn serialize_user_ids<S: Serializer>(
+    user_ids: &[UserId],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    user_ids
+        .iter()
+        .map(|user_id| user_id.0.to_string())
+        .collect::<Vec<_>>()
+        .serialize(serializer)
+}
+
+fn serialize_player_nicknames<S: Serializer>(
+    player_nicknames: &HashMap<UserId, String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    // sorted so that the same game always comes out the same
+    player_nicknames
+        .iter()
+        .map(|(user_id, nickname)| (user_id.0.to_string(), nickname))
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
+fn serialize_player_vacations<S: Serializer>(
+    player_vacations: &HashMap<UserId, DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    player_vacations
+        .iter()
+        .map(|(user_id, until)| (user_id.0.to_string(), until.to_rfc3339()))
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
+fn serialize_fetched_at<S: Serializer>(
+    fetched_at: &DateTime<Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    fetched_at.to_rfc3339().serialize(serializer)
+}
+
+fn deserialize_fetched_at<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DateTime<Utc>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    DateTime::parse_from_rfc3339(&text)
+        .map(|fetched_at| fetched_at.with_timezone(&Utc))
+        .map_err(de::Error::custom)
+}
+
+fn serialize_option_deadline<S: Serializer>(
+    option_deadline: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    option_deadline
+        .map(|deadline| deadline.to_rfc3339())
+        .serialize(serializer)
+}
+
 pub fn get_details_for_alias<C: ServerConnection>(
     db_conn: &DbConnection,
+    game_cache: &GameCache,
     alias: &str,
 ) -> Result<GameDetails, CommandError> {
     let server = db_conn.game_for_alias(&alias)?;
-    info!("got server details");
+    info!("got server details {}", GameLogContext::new(alias));
 
     let details = match server.state {
-        GameServerState::Lobby(ref lobby_state) => lobby_details(db_conn, lobby_state, &alias)?,
+        GameServerState::Lobby(ref lobby_state) => {
+            game_cache.get_or_build_lobby(alias, || lobby_details(db_conn, lobby_state, alias))?
+        }
         GameServerState::StartedState(ref started_state, ref option_lobby_state) => {
-            started_details::<C>(db_conn, started_state, option_lobby_state.as_ref(), &alias)?
+            started_details::<C>(
+                db_conn,
+                game_cache,
+                started_state,
+                option_lobby_state.as_ref(),
+                &alias,
+            )?
+        }
+        GameServerState::Archived(_, _) => {
+            return Err(CommandError::from(format!("{} has finished", alias)))
         }
     };
 
@@ -168,29 +539,84 @@ pub fn lobby_details(
     alias: &str,
 ) -> Result<GameDetails, CommandError> {
     let players_nations = db_conn.players_with_nations_for_game_alias(&alias)?;
+    let ai_reserved_nation_ids = db_conn.ai_reserved_nations_for_alias(alias)?;
+
+    let ai_reserved: Vec<AiReservedNation> = ai_reserved_nation_ids
+        .iter()
+        .map(|&nation_id| AiReservedNation {
+            nation_id,
+            nation_name: Nations::get_nation_name(nation_id),
+            option_player_id: players_nations
+                .iter()
+                .find(|&&(_, player_nation_id)| player_nation_id == nation_id)
+                .map(|(player, _)| player.discord_user_id),
+        })
+        .collect();
 
     let mut player_nation_details: Vec<LobbyPlayer> = players_nations
         .into_iter()
+        .filter(|(_, nation_id)| !ai_reserved_nation_ids.contains(nation_id))
         .map(|(player, nation_id)| -> LobbyPlayer {
-            let &(nation_name, _) = Nations::get_nation_desc(nation_id);
             LobbyPlayer {
                 player_id: player.discord_user_id,
                 nation_id,
-                nation_name: nation_name.to_owned(),
+                nation_name: Nations::get_nation_name(nation_id),
             }
         })
         .collect();
     player_nation_details.sort_by(|n1, n2| n1.nation_name.cmp(&n2.nation_name));
 
-    let remaining_slots = max(
-        0,
-        (lobby_state.player_count - player_nation_details.len() as i32) as u32,
-    );
+    let (waitlisted, confirmed): (Vec<RosterEntry>, Vec<RosterEntry>) = db_conn
+        .roster_for_alias(alias)?
+        .into_iter()
+        .partition(|entry| entry.waitlisted);
+    let expected: Vec<UserId> = confirmed
+        .into_iter()
+        .map(|entry| entry.user_id)
+        .filter(|user_id| {
+            !player_nation_details
+                .iter()
+                .any(|player| player.player_id == *user_id)
+        })
+        .collect();
+    let waitlist: Vec<UserId> = waitlisted.into_iter().map(|entry| entry.user_id).collect();
+
+    let remaining_slots = lobby_state.option_player_count().map(|player_count| {
+        max(
+            0,
+            player_count - player_nation_details.len() as i32 - expected.len() as i32,
+        ) as u32
+    });
+
+    let nation_set = db_conn.lobby_nation_set_for_alias(alias)?;
+    let option_open_nations = if nation_set.is_empty() {
+        None
+    } else {
+        Some(
+            nation_set
+                .into_iter()
+                .filter(|nation_id| {
+                    !ai_reserved_nation_ids.contains(nation_id)
+                        && !player_nation_details
+                            .iter()
+                            .any(|player| player.nation_id == *nation_id)
+                })
+                .map(|nation_id| OpenNation {
+                    nation_id,
+                    nation_name: Nations::get_nation_name(nation_id),
+                })
+                .collect(),
+        )
+    };
 
     let lobby_details = LobbyDetails {
         players: player_nation_details,
         era: Some(lobby_state.era),
         remaining_slots,
+        ai_reserved,
+        option_open_nations,
+        expected,
+        waitlist,
     };
 
     Ok(GameDetails {
@@ -198,27 +624,58 @@ pub fn lobby_details(
         owner: Some(lobby_state.owner),
         description: lobby_state.description.clone(),
         nations: NationDetails::Lobby(lobby_details),
+        player_nicknames: db_conn.player_nicknames_for_alias(alias)?,
+        player_vacations: db_conn.player_vacations_for_alias(alias, Utc::now())?,
         cache_entry: None, // lobbies have no cache entry
+        warnings: vec![],
     })
 }
 
+pub fn providers_for_alias(
+    db_conn: &DbConnection,
+    alias: &str,
+) -> Result<Vec<&'static dyn NationNameProvider>, CommandError> {
+    match db_conn.nation_providers_for_alias(alias)? {
+        Some(names) => Ok(providers_from_names(&names)?),
+        None => Ok(DEFAULT_PROVIDERS.to_vec()),
+    }
+}
+
 fn started_details<C: ServerConnection>(
     db_conn: &DbConnection,
+    game_cache: &GameCache,
     started_state: &StartedState,
     option_lobby_state: Option<&LobbyState>,
     alias: &str,
 ) -> Result<GameDetails, CommandError> {
-    let server_address = &started_state.address;
-    let game_data = C::get_game_data(&server_address)?;
-    let option_snek_details = C::get_snek_data(server_address)?;
+    let cache_entry = game_cache.get_or_fetch(alias, || -> Result<_, CommandError> {
+        let server_address = &started_state.address;
+        let game_data = C::get_game_data(&server_address)?;
+        let providers = providers_for_alias(db_conn, alias)?;
+        // Nation names from snek are nice to have, not worth failing over
+        let option_snek_state = C::get_snek_data(server_address, &providers).unwrap_or_else(|e| {
+            warn!(
+                "Could not get nation names, using the built-in ones: {} {}",
+                e,
+                GameLogContext::new(alias).address(server_address)
+            );
+            None
+        });
+        Ok(CacheEntry {
+            game_data,
+            option_snek_state,
+            fetched_at: Utc::now(),
+        })
+    })?;
 
     started_details_from_server(
         db_conn,
         started_state,
         option_lobby_state,
         alias,
-        game_data,
-        option_snek_details,
+        cache_entry.game_data,
+        cache_entry.option_snek_state,
+        cache_entry.fetched_at,
     )
 }
 
@@ -229,10 +686,16 @@ pub fn started_details_from_server(
     alias: &str,
     game_data: GameData,
     option_snek_details: Option<SnekGameStatus>,
+    fetched_at: DateTime<Utc>,
 ) -> Result<GameDetails, CommandError> {
     let id_player_nations = db_conn.players_with_nations_for_game_alias(&alias)?;
-    let player_details =
-        join_players_with_nations(&game_data.nations, &id_player_nations, &option_snek_details)?;
+    let recorded_names = db_conn.nation_names_for_alias(alias)?;
+    let (player_details, warnings) = join_players_with_nations(
+        &game_data.nations,
+        &id_player_nations,
+        &option_snek_details,
+        &recorded_names,
+    );
 
     let state_details = if game_data.turn < 0 {
         let uploaded_players_detail: Vec<UploadingPlayer> = player_details
@@ -265,41 +728,81 @@ pub fn started_details_from_server(
             uploading_players: uploaded_players_detail,
         })
     } else {
-        let total_mins_remaining = game_data.turn_timer / (1000 * 60);
+        let paused = is_paused_timer(game_data.turn_timer);
+        let turn_timer = if paused { 0 } else { game_data.turn_timer };
+        let total_mins_remaining = turn_timer / (1000 * 60);
         let hours_remaining = total_mins_remaining / 60;
         let mins_remaining = total_mins_remaining - hours_remaining * 60;
+        let days_remaining = hours_remaining / 24;
+        let secs_remaining = turn_timer / 1000 % 60;
+        // The timer counts down from when we asked, not from now
+        let option_deadline = if turn_timer > 0 {
+            Some(fetched_at + Duration::milliseconds(i64::from(turn_timer)))
+        } else {
+            None
+        };
         StartedStateDetails::Playing(PlayingState {
             players: player_details,
             mins_remaining,
             hours_remaining,
+            days_remaining,
+            secs_remaining,
+            option_deadline,
+            paused,
+            blitz: db_conn.blitz_for_alias(alias)?,
             turn: game_data.turn as u32, // game_data >= 0 checked above
         })
     };
 
+    let option_snek_game_id = option_snek_details
+        .as_ref()
+        .and_then(|snek_details| snek_details.option_game_id)
+        .or(started_state.option_snek_game_id);
     let started_details = StartedDetails {
         address: started_state.address.clone(),
         game_name: game_data.game_name.clone(),
         state: state_details,
+        option_snek_game_id,
     };
 
     Ok(GameDetails {
         alias: alias.to_owned(),
         owner: option_lobby_state.map(|lobby_state| lobby_state.owner.clone()),
-        description: option_lobby_state.and_then(|lobby_state| lobby_state.description.clone()),
+        description: match option_lobby_state {
+            Some(lobby_state) => lobby_state.description.clone(),
+            None => db_conn.description_for_alias(alias)?,
+        },
         nations: NationDetails::Started(started_details),
+        player_nicknames: db_conn.player_nicknames_for_alias(alias)?,
+        player_vacations: db_conn.player_vacations_for_alias(alias, Utc::now())?,
         cache_entry: Some(CacheEntry {
             game_data: game_data.clone(),
             option_snek_state: option_snek_details.clone(),
+            fetched_at,
         }),
+        warnings,
     })
 }
 
+/// One nation that can't be named shouldn't stop the rest of the game being
+/// shown, so it keeps the placeholder name and is explained in the warnings
 fn join_players_with_nations(
     nations: &Vec<Nation>,
     players_nations: &Vec<(Player, u32)>,
     option_snek_details: &Option<SnekGameStatus>,
-) -> Result<Vec<PotentialPlayer>, CommandError> {
+    recorded_names: &HashMap<u32, String>,
+) -> (Vec<PotentialPlayer>, Vec<String>) {
     let mut potential_players = vec![];
+    let mut warnings = vec![];
+    let mut nation_name = |nation_id: u32| {
+        resolve_nation_name(option_snek_details, recorded_names, nation_id).unwrap_or_else(|| {
+            warnings.push(format!(
+                "Couldn't resolve nation {}, is it from a mod or a newer patch?",
+                nation_id
+            ));
+            Nations::get_nation_name(nation_id)
+        })
+    };
 
     let mut players_by_nation_id = HashMap::new();
     for (player, nation_id) in players_nations {
@@ -311,9 +814,11 @@ fn join_players_with_nations(
             Some(player) => {
                 let player_details = PlayerDetails {
                     nation_id: nation.id,
-                    nation_name: get_nation_string(option_snek_details, nation.id),
+                    nation_name: nation_name(nation.id),
                     submitted: nation.submitted,
                     player_status: nation.status,
+                    went_ai: nation.status == NationStatus::AI,
+                    connected: nation.connected,
                 };
                 potential_players.push(PotentialPlayer::RegisteredAndGame(
                     player.discord_user_id,
@@ -323,21 +828,23 @@ fn join_players_with_nations(
             // Game only
             None => potential_players.push(PotentialPlayer::GameOnly(PlayerDetails {
                 nation_id: nation.id,
-                nation_name: get_nation_string(option_snek_details, nation.id),
+                nation_name: nation_name(nation.id),
                 submitted: nation.submitted,
                 player_status: nation.status,
+                went_ai: false,
+                connected: nation.connected,
             })),
         }
     }
     // Lobby only
     for (nation_id, player) in players_by_nation_id {
-        let &(nation_name, _) = Nations::get_nation_desc(nation_id);
         potential_players.push(PotentialPlayer::RegisteredOnly(
             player.discord_user_id,
             nation_id,
-            nation_name.to_owned(),
+            nation_name(nation_id),
         ));
     }
     potential_players.sort_unstable();
-    Ok(potential_players)
+    warnings.sort();
+    (potential_players, warnings)
 }
@@ -1,15 +1,61 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use log::*;
 use serenity::builder::CreateEmbed;
 use serenity::framework::standard::{Args, CommandError};
 use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, UserId};
+use serenity::model::misc::Mentionable;
 use serenity::prelude::Context;
+use serenity::utils::Colour;
 
 use crate::commands::servers::lobby_details;
+use crate::commands::servers::time_format::time_format_for_message;
+use crate::commands::servers::turn_check::current_stall;
 use crate::commands::servers::*;
 use crate::db::{DbConnection, DbConnectionKey};
-use crate::model::enums::{NationStatus, SubmissionStatus};
-use crate::model::{GameData, GameServerState};
+use crate::model::enums::{NationStatus, Nations, SubmissionStatus};
+use crate::model::{FirstSeen, GameData, GameServerState, Player, StartedState};
 use crate::server::ServerConnection;
-use crate::snek::SnekGameStatus;
+use crate::snek::snek_game_url;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this before the alias to list defeated nations instead of just counting them
+const SHOW_DEFEATED_FLAG: &str = "--all";
+/// e.g. `--sort=status`, see `PlayerSort`
+const SORT_FLAG_PREFIX: &str = "--sort=";
+/// Says they've played, but we couldn't check with the server
+const UNVERIFIED_SUBMISSION_SYMBOL: &str = "✓?";
+
+/// What order the playing nations are listed in. Only the listing is sorted,
+/// everything else keeps the players in nation order.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PlayerSort {
+    Nation,
+    /// Whoever still has to play first
+    Submission,
+    /// By the name shown for registered players, unregistered nations last
+    Player,
+}
+
+impl PlayerSort {
+    pub fn from_flag(flag: &str) -> Option<PlayerSort> {
+        if !flag.starts_with(SORT_FLAG_PREFIX) {
+            return None;
+        }
+        match &flag[SORT_FLAG_PREFIX.len()..] {
+            "nation" => Some(PlayerSort::Nation),
+            "status" => Some(PlayerSort::Submission),
+            "player" => Some(PlayerSort::Player),
+            _ => None,
+        }
+    }
+}
 
 pub fn details2<C: ServerConnection>(
     context: &mut Context,
@@ -21,119 +67,780 @@ pub fn details2<C: ServerConnection>(
         .get::<DbConnectionKey>()
         .ok_or("No DbConnection was created on startup. This is a bug.")?;
 
-    let read_handle = data
-        .get::<crate::DetailsReadHandleKey>()
-        .ok_or("No ReadHandle was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let nation_emojis = data
+        .get::<NationEmojisKey>()
+        .ok_or("No NationEmojis were loaded on startup. This is a bug.")?;
+    let self_reports = data
+        .get::<SelfReportsKey>()
+        .ok_or("No SelfReports were created on startup. This is a bug.")?;
+    let details_cooldowns = data
+        .get::<DetailsCooldownsKey>()
+        .ok_or("No DetailsCooldowns were created on startup. This is a bug.")?;
+    let translations = data
+        .get::<TranslationsKey>()
+        .ok_or("No Translations were loaded on startup. This is a bug.")?;
 
+    let mut show_defeated = false;
+    let mut player_sort = PlayerSort::Nation;
+    while let Ok(arg) = args.single_quoted_n::<String>() {
+        if arg == SHOW_DEFEATED_FLAG {
+            show_defeated = true;
+        } else if let Some(arg_player_sort) = PlayerSort::from_flag(&arg) {
+            player_sort = arg_player_sort;
+        } else if arg.starts_with(SORT_FLAG_PREFIX) {
+            return Err(CommandError::from(format!(
+                "Unknown sort \"{}\", try {}nation, {}status or {}player",
+                arg, SORT_FLAG_PREFIX, SORT_FLAG_PREFIX, SORT_FLAG_PREFIX
+            )));
+        } else {
+            break;
+        }
+        args.skip();
+    }
     let alias = alias_from_arg_or_channel_name(&mut args, &message)?;
     if !args.is_empty() {
         return Err(CommandError::from(
             "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
         ));
     }
-    let embed_response = details_helper(&alias, db_conn, read_handle)?;
+    let request = DetailsRequest {
+        user_id: message.author.id,
+        alias,
+        show_defeated,
+        player_sort,
+    };
+    let now = Utc::now();
+    let embed_response = match details_cooldowns.get(&request, now) {
+        Some(embed_response) => {
+            info!("details: {:?} is cooling down, resending", request);
+            embed_response
+        }
+        None => {
+            let option_locale = db_conn.locale_for_channel(message.channel_id)?;
+            let time_format = time_format_for_message(db_conn, message)?;
+            let embed_response = details_helper(
+                &request.alias,
+                db_conn,
+                game_cache,
+                nation_emojis,
+                translations.strings(option_locale.as_deref()),
+                self_reports,
+                show_defeated,
+                player_sort,
+                time_format,
+            )?;
+            details_cooldowns.insert(request, embed_response.clone(), now);
+            embed_response
+        }
+    };
 
-    message
-        .channel_id
-        .send_message(|m| m.embed(|_| embed_response))?;
+    let details_as_text = match message.guild_id {
+        Some(guild_id) => db_conn.details_as_text_for_guild(guild_id)?,
+        None => false,
+    };
+    if details_as_text {
+        for text in split_message(&embed_to_text(&embed_response)) {
+            message.channel_id.say(&text)?;
+        }
+    } else {
+        for embed in paginate_embed(embed_response) {
+            message.channel_id.send_message(|m| m.embed(|_| embed))?;
+        }
+    }
     Ok(())
 }
 
-fn details_helper(
+/// The game's details as of the last poll, and why the poll failed if it has
+/// since stopped responding
+pub fn cached_game_details(
     alias: &str,
     db_conn: &DbConnection,
-    read_handle: &crate::CacheReadHandle,
-) -> Result<CreateEmbed, CommandError> {
+    game_cache: &GameCache,
+) -> Result<(GameDetails, Option<String>), CommandError> {
     let server = db_conn.game_for_alias(&alias)?;
     match server.state {
         GameServerState::Lobby(ref lobby_state) => {
-            let details: GameDetails = lobby_details(db_conn, lobby_state, alias)?;
-            let embed: CreateEmbed = details_to_embed(details)?;
-            Ok(embed)
+            let details: GameDetails = game_cache
+                .get_or_build_lobby(alias, || lobby_details(db_conn, lobby_state, alias))?;
+            Ok((details, None))
         }
+        GameServerState::Archived(_, _) => Err(CommandError::from(format!(
+            "{} has finished, use !unarchive to start checking it again",
+            alias
+        ))),
         GameServerState::StartedState(ref started_state, ref option_lobby_state) => {
-            let option_option_game_cache = read_handle.get_clone(alias);
-
-            match option_option_game_cache {
-                Some(Some(cache)) => {
-                    let CacheEntry {
-                        game_data,
-                        option_snek_state,
-                    } = cache;
-
-                    let details: GameDetails = started_details_from_server(
-                        db_conn,
-                        started_state,
-                        option_lobby_state.as_ref(),
-                        alias,
-                        game_data,
-                        option_snek_state,
-                    )?;
-
-                    let embed: CreateEmbed = details_to_embed(details)?;
-                    Ok(embed)
-                }
-                Some(None) => Err("Got an error when trying to connect to the server".into()),
-                None => Err("Not yet got a response from server, try again in 1 min".into()),
+            let (cache, option_error) = match game_cache.get_poll(alias) {
+                Some(CachedPoll {
+                    option_cache_entry: Some(cache),
+                    option_error,
+                    ..
+                }) => (cache, option_error),
+                Some(CachedPoll {
+                    option_error: Some(e),
+                    ..
+                }) => return Err(format!("Could not get details for {}: {}", alias, e).into()),
+                _ => return Err("Not yet got a response from server, try again in 1 min".into()),
+            };
+            let CacheEntry {
+                game_data,
+                option_snek_state,
+                fetched_at,
+            } = cache;
+
+            let details: GameDetails = started_details_from_server(
+                db_conn,
+                started_state,
+                option_lobby_state.as_ref(),
+                alias,
+                game_data,
+                option_snek_state,
+                fetched_at,
+            )?;
+            Ok((details, option_error))
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn details_helper(
+    alias: &str,
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    nation_emojis: &NationEmojis,
+    strings: &LocaleStrings,
+    self_reports: &SelfReports,
+    show_defeated: bool,
+    player_sort: PlayerSort,
+    time_format: TimeFormat,
+) -> Result<CreateEmbed, CommandError> {
+    if let GameServerState::Archived(started_state, _) = db_conn.game_for_alias(alias)?.state {
+        return finished_embed(db_conn, alias, &started_state, nation_emojis, strings);
+    }
+    let utc_offset = db_conn
+        .utc_offset_for_alias(alias)?
+        .unwrap_or_else(|| FixedOffset::east(0));
+    let (details, option_error) = cached_game_details(alias, db_conn, game_cache)?;
+    let option_fetched_at = details
+        .cache_entry
+        .as_ref()
+        .map(|cache_entry| cache_entry.fetched_at);
+    let self_reported = match (&details.nations, option_fetched_at) {
+        (
+            NationDetails::Started(StartedDetails {
+                state: StartedStateDetails::Playing(playing_state),
+                ..
+            }),
+            Some(fetched_at),
+        ) => self_reports.unverified(alias, playing_state.turn as i32, fetched_at),
+        _ => vec![],
+    };
+    let icon = db_conn.icon_for_alias(alias)?;
+    let option_colour = match &details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => {
+            if everyone_has_played(playing_state, &self_reported) {
+                Some(Colour::DARK_GREEN)
+            } else if current_stall(db_conn, alias, playing_state.option_deadline, Utc::now())?
+                .is_some()
+            {
+                Some(Colour::RED)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+    // How the game is doing matters more than telling it apart from the others
+    .or_else(|| icon.option_colour.map(Colour::new));
+    let option_waiting_turn = match &details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) if playing_state.waiting_on_host() => Some(playing_state.turn),
+        _ => None,
+    };
+    let option_age = match &details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => db_conn
+            .first_seen_for_alias(alias)?
+            .map(|first_seen| show_game_age(&first_seen, playing_state.turn, Utc::now())),
+        _ => None,
+    };
+
+    let warnings = details.warnings.clone();
+    let mut embed: CreateEmbed = details_to_embed(
+        details,
+        utc_offset,
+        nation_emojis,
+        show_defeated,
+        player_sort,
+        &self_reported,
+        strings,
+        time_format,
+    )?;
+    if let Some(colour) = option_colour {
+        embed = embed.colour(colour);
+    }
+    if let Some((label, age)) = option_age {
+        embed = embed.field(label, age, false);
+    }
+    if let Some(turn) = option_waiting_turn {
+        embed = embed.field(
+            "Waiting on host",
+            format!(
+                "Every human has played turn {}, it will host as soon as the server gets to it",
+                turn
+            ),
+            false,
+        );
+    }
+    let duplicates = duplicate_registrations(&db_conn.players_with_nations_for_game_alias(alias)?);
+    if !duplicates.is_empty() {
+        embed = embed.field(
+            "WARNING: duplicate registrations",
+            duplicates
+                .iter()
+                .map(|(nation_id, user_ids)| {
+                    format!(
+                        "{} ({}) is registered to {}, unregister all but one",
+                        Nations::get_nation_name(*nation_id),
+                        nation_id,
+                        user_ids
+                            .iter()
+                            .map(|user_id| user_id.mention())
+                            .collect::<Vec<_>>()
+                            .join(" and ")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            false,
+        );
+    }
+    if !warnings.is_empty() {
+        embed = embed.field("WARNING: unresolved nations", warnings.join("\n"), false);
+    }
+    // Don't pretend that this is live if the server has since stopped responding
+    if let (Some(error), Some(fetched_at)) = (option_error, option_fetched_at) {
+        embed = embed.field(
+            "WARNING: stale data",
+            format!(
+                "Could not reach the server ({}), this data is {} minutes old",
+                error,
+                Utc::now().signed_duration_since(fetched_at).num_minutes(),
+            ),
+            false,
+        );
+    }
+    Ok(embed)
+}
+
+/// Nations with more than one player registered, and who they are. New
+/// registrations can't do this, but databases from before registrations were
+/// unique per nation can still have them and details would only show one.
+pub fn duplicate_registrations(players_nations: &[(Player, u32)]) -> Vec<(u32, Vec<UserId>)> {
+    let mut claimants: HashMap<u32, Vec<UserId>> = HashMap::new();
+    for (player, nation_id) in players_nations {
+        claimants
+            .entry(*nation_id)
+            .or_default()
+            .push(player.discord_user_id);
+    }
+    let mut duplicates: Vec<(u32, Vec<UserId>)> = claimants
+        .into_iter()
+        .filter(|(_, user_ids)| user_ids.len() > 1)
+        .collect();
+    duplicates.sort_by_key(|&(nation_id, _)| nation_id);
+    duplicates
+}
+
+/// The "Game age" field, or "Tracked for" if we only found the game part way
+/// through and don't know when it really started
+pub fn show_game_age(
+    first_seen: &FirstSeen,
+    turn: u32,
+    now: DateTime<Utc>,
+) -> (&'static str, String) {
+    let age = match now.signed_duration_since(first_seen.at).num_days() {
+        days if days < 1 => "less than a day".to_owned(),
+        1 => "1 day".to_owned(),
+        days => format!("{} days", days),
+    };
+    if first_seen.is_from_start() {
+        ("Game age", format!("{}, turn {}", age, turn))
+    } else {
+        (
+            "Tracked for",
+            format!("{} (since turn {}), turn {}", age, first_seen.turn, turn),
+        )
+    }
+}
+
+/// Every human has played, or says they have while the server can't tell us
+fn everyone_has_played(playing_state: &PlayingState, self_reported: &[u32]) -> bool {
+    playing_state
+        .players
+        .iter()
+        .all(|potential_player| match potential_player {
+            PotentialPlayer::RegisteredAndGame(_, details) | PotentialPlayer::GameOnly(details) => {
+                !details.player_status.is_human()
+                    || details.submitted == SubmissionStatus::Submitted
+                    || self_reported.contains(&details.nation_id)
+            }
+            PotentialPlayer::RegisteredOnly(_, _, _) => true,
+        })
+}
+
+/// A one line count of the nations listed below it, e.g. "14 nations: 9 human,
+/// 3 AI, 2 defeated — 4 turns outstanding". Counted the same way as the list,
+/// so defeated nations that are hidden still count, nations that went AI count
+/// as AI, and a self-reported turn isn't outstanding.
+fn player_summary(playing_state: &PlayingState, self_reported: &[u32]) -> String {
+    let mut total = 0;
+    let mut human = 0;
+    let mut ai = 0;
+    let mut went_ai = 0;
+    let mut defeated = 0;
+    let mut outstanding = 0;
+    for potential_player in &playing_state.players {
+        let details = match potential_player {
+            PotentialPlayer::RegisteredAndGame(_, details) | PotentialPlayer::GameOnly(details) => {
+                details
+            }
+            PotentialPlayer::RegisteredOnly(_, _, _) => continue,
+        };
+        total += 1;
+        if details.player_status.is_human() {
+            human += 1;
+            if details.submitted != SubmissionStatus::Submitted
+                && !self_reported.contains(&details.nation_id)
+            {
+                outstanding += 1;
             }
+        } else if details.player_status == NationStatus::AI {
+            ai += 1;
+            if details.went_ai {
+                went_ai += 1;
+            }
+        } else if details.player_status == NationStatus::Defeated {
+            defeated += 1;
         }
     }
+    let mut counts = vec![format!("{} human", human)];
+    if went_ai > 0 {
+        counts.push(format!("{} AI ({} formerly human)", ai, went_ai));
+    } else {
+        counts.push(format!("{} AI", ai));
+    }
+    counts.push(format!("{} defeated", defeated));
+    // Independents and closed slots, which servers rarely send
+    let other = total - human - ai - defeated;
+    if other > 0 {
+        counts.push(format!("{} other", other));
+    }
+    format!(
+        "{} nation{}: {} — {}",
+        total,
+        if total == 1 { "" } else { "s" },
+        counts.join(", "),
+        match outstanding {
+            0 => "everyone has played".to_owned(),
+            1 => "1 turn outstanding".to_owned(),
+            _ => format!("{} turns outstanding", outstanding),
+        }
+    )
+}
+
+/// The same details for servers that have embeds turned off, see `!detailsformat`
+pub fn embed_to_text(embed: &CreateEmbed) -> String {
+    let mut text = String::new();
+    if let Some(Value::Array(fields)) = embed.0.get(&"fields") {
+        for field in fields {
+            if let (Some(name), Some(value)) = (field["name"].as_str(), field["value"].as_str()) {
+                text.push_str(&format!("**{}**\n{}\n", name, value));
+            }
+        }
+    }
+    text
+}
+
+/// Discord won't take messages longer than this
+pub const MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// `text` in as few messages as fit, only breaking between lines
+pub fn split_message(text: &str) -> Vec<String> {
+    let mut messages = vec![String::new()];
+    for line in text.lines() {
+        let current_len = messages[messages.len() - 1].len();
+        if current_len > 0 && current_len + line.len() + 1 > MAX_MESSAGE_LENGTH {
+            messages.push(String::new());
+        }
+        let new_len = messages.len();
+        messages[new_len - 1].push_str(line);
+        messages[new_len - 1].push('\n');
+    }
+    messages
+}
+
+/// In a code block if it fits in one message, otherwise attached as
+/// `<alias>.json`. It's all on one line, so `split_message` can't help.
+pub fn send_json(channel_id: ChannelId, alias: &str, json: &str) -> Result<(), CommandError> {
+    let text = format!("```json\n{}\n```", json);
+    if text.len() <= MAX_MESSAGE_LENGTH {
+        channel_id.say(&text)?;
+    } else {
+        let file_name = format!("{}.json", alias);
+        channel_id.send_files(vec![(json.as_bytes(), file_name.as_str())], |m| m)?;
+    }
+    Ok(())
 }
 
-fn details_to_embed(details: GameDetails) -> Result<CreateEmbed, CommandError> {
+/// Discord cuts off embed fields longer than this
+const MAX_FIELD_LENGTH: usize = 1024;
+/// Long lists are hard to read even when they would fit
+const PLAYERS_PER_FIELD: usize = 20;
+/// Discord refuses embeds with more fields or text than these
+const MAX_EMBED_FIELDS: usize = 25;
+const MAX_EMBED_LENGTH: usize = 6000;
+
+/// One line per player, grouped into embed field values that Discord will show
+/// in full. A player's line is never split between fields.
+pub fn group_player_lines(lines: Vec<String>) -> Vec<String> {
+    let mut embed_texts: Vec<String> = vec![];
+    let mut lines_in_last = 0;
+    for line in lines {
+        let full = match embed_texts.last() {
+            Some(last) => {
+                lines_in_last >= PLAYERS_PER_FIELD || last.len() + line.len() > MAX_FIELD_LENGTH
+            }
+            None => true,
+        };
+        if full {
+            embed_texts.push(String::new());
+            lines_in_last = 0;
+        }
+        let new_len = embed_texts.len();
+        embed_texts[new_len - 1].push_str(&line);
+        lines_in_last += 1;
+    }
+    embed_texts
+}
+
+/// The embed as one message if Discord will take it, otherwise split between
+/// fields across as many as it needs. The first page keeps the turn and timer.
+pub fn paginate_embed(embed: CreateEmbed) -> Vec<CreateEmbed> {
+    let fields = match embed.0.get(&"fields") {
+        Some(Value::Array(fields)) => fields.clone(),
+        _ => return vec![embed],
+    };
+    let title_len = embed
+        .0
+        .get(&"title")
+        .and_then(|title| title.as_str())
+        .map_or(0, |title| title.len());
+    let field_len = |field: &Value| {
+        field["name"].as_str().map_or(0, |name| name.len())
+            + field["value"].as_str().map_or(0, |value| value.len())
+    };
+
+    let mut pages: Vec<Vec<Value>> = vec![vec![]];
+    let mut page_len = title_len;
+    for field in fields {
+        let len = field_len(&field);
+        let last_page = &pages[pages.len() - 1];
+        if !last_page.is_empty()
+            && (last_page.len() >= MAX_EMBED_FIELDS || page_len + len > MAX_EMBED_LENGTH)
+        {
+            pages.push(vec![]);
+            page_len = title_len;
+        }
+        page_len += len;
+        let new_len = pages.len();
+        pages[new_len - 1].push(field);
+    }
+    if pages.len() == 1 {
+        return vec![embed];
+    }
+    pages
+        .into_iter()
+        .map(|page_fields| {
+            let mut page = embed.clone();
+            // serenity's map appends rather than replacing
+            page.0.remove(&"fields");
+            page.0.insert("fields", Value::Array(page_fields));
+            page
+        })
+        .collect()
+}
+
+/// Archived games aren't polled any more, so all that's left to show is who
+/// played what
+fn finished_embed(
+    db_conn: &DbConnection,
+    alias: &str,
+    started_state: &StartedState,
+    nation_emojis: &NationEmojis,
+    strings: &LocaleStrings,
+) -> Result<CreateEmbed, CommandError> {
+    let mut players_nations = db_conn.players_with_nations_for_game_alias(alias)?;
+    players_nations.sort_by_key(|&(_, nation_id)| nation_id);
+
+    let embed_title = format!(
+        "{} ({}): Game finished on turn {}",
+        alias, started_state.address, started_state.last_seen_turn
+    );
+    let mut embed_texts = group_player_lines(
+        players_nations
+            .iter()
+            .map(|(player, nation_id)| {
+                let nation_name = Nations::get_nation_name(*nation_id);
+                format!(
+                    "{}{} ({}): {}\n",
+                    nation_emojis.prefix(*nation_id),
+                    strings.nation_name(*nation_id, &nation_name),
+                    nation_id,
+                    player.discord_user_id.mention(),
+                )
+            })
+            .collect(),
+    );
+    if embed_texts.is_empty() {
+        embed_texts.push("Nobody was registered".to_owned());
+    }
+
+    let mut e =
+        CreateEmbed::default()
+            .title("Details")
+            .field(embed_title, embed_texts[0].clone(), false);
+    for embed_text in &embed_texts[1..] {
+        e = e.field("-----", embed_text, false);
+    }
+    Ok(e)
+}
+
+/// The players to list in a started game, and how many defeated ones were left
+/// out. Players who registered but aren't in the game are never shown.
+fn players_to_show(
+    players: &[PotentialPlayer],
+    show_defeated: bool,
+) -> (Vec<(Option<&UserId>, &PlayerDetails)>, usize) {
+    let mut shown = vec![];
+    let mut defeated_count = 0;
+    for potential_player in players {
+        let (option_user_id, player_details) = match potential_player {
+            // If the game has started and they're not in it, too bad
+            PotentialPlayer::RegisteredOnly(_, _, _) => continue,
+            PotentialPlayer::RegisteredAndGame(user_id, player_details) => {
+                (Some(user_id), player_details)
+            }
+            PotentialPlayer::GameOnly(player_details) => (None, player_details),
+        };
+        if !show_defeated && player_details.player_status == NationStatus::Defeated {
+            defeated_count += 1;
+        } else {
+            shown.push((option_user_id, player_details));
+        }
+    }
+    (shown, defeated_count)
+}
+
+/// `option_shown_name` is who registered as the nation, if they're worth showing.
+/// Humans who have the game open right now get marked as online.
+fn show_player_name(
+    player_details: &PlayerDetails,
+    option_shown_name: Option<String>,
+    strings: &LocaleStrings,
+) -> String {
+    let player_name = match (player_details.player_status, option_shown_name) {
+        (NationStatus::Human, Some(shown_name)) => format!("**{}**", shown_name),
+        (NationStatus::AI, Some(shown_name)) if player_details.went_ai => format!(
+            "{} (formerly {})",
+            strings.show_status(NationStatus::AI),
+            shown_name
+        ),
+        _ => strings.show_status(player_details.player_status).to_owned(),
+    };
+    if player_details.player_status.is_human() && player_details.connected {
+        format!("{} (online)", player_name)
+    } else {
+        player_name
+    }
+}
+
+/// Undone first, with nations nobody has to play for counted as done
+fn submission_rank(player_details: &PlayerDetails) -> u8 {
+    if !player_details.player_status.is_human() {
+        return 3;
+    }
+    match player_details.submitted {
+        SubmissionStatus::NotSubmitted => 0,
+        SubmissionStatus::PartiallySubmitted => 1,
+        SubmissionStatus::Unknown(_) => 2,
+        SubmissionStatus::Submitted => 3,
+    }
+}
+
+/// A nation to list, who registered as it and the name shown for them, if any
+type PlayerRow<'a> = (Option<&'a UserId>, Option<String>, &'a PlayerDetails);
+
+/// Sorts are stable so ties stay in nation order
+fn sort_players(rows: &mut [PlayerRow], player_sort: PlayerSort) {
+    match player_sort {
+        PlayerSort::Nation => {}
+        PlayerSort::Submission => {
+            rows.sort_by_key(|(_, _, player_details)| submission_rank(player_details))
+        }
+        PlayerSort::Player => rows.sort_by(|(_, option_name1, _), (_, option_name2, _)| {
+            match (option_name1, option_name2) {
+                (Some(name1), Some(name2)) => name1.to_lowercase().cmp(&name2.to_lowercase()),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }),
+    }
+}
+
+/// Moves the rest of a player's nations up to just after the first one, for
+/// games where someone plays more than one. Only nations shown with their name
+/// are grouped.
+fn group_by_player(rows: Vec<PlayerRow>) -> Vec<PlayerRow> {
+    let mut grouped: Vec<PlayerRow> = Vec::with_capacity(rows.len());
+    for row in rows {
+        let option_group_end = match (row.0, &row.1) {
+            (Some(user_id), Some(_)) => {
+                grouped
+                    .iter()
+                    .rposition(|(option_user_id, option_name, _)| {
+                        *option_user_id == Some(user_id) && option_name.is_some()
+                    })
+            }
+            _ => None,
+        };
+        match option_group_end {
+            Some(index) => grouped.insert(index + 1, row),
+            None => grouped.push(row),
+        }
+    }
+    grouped
+}
+
+/// Their nickname for this game if they've set one, otherwise their discord name.
+/// Players on vacation get marked so the host knows not to wait on them.
+fn show_player(
+    user_id: &UserId,
+    player_nicknames: &HashMap<UserId, String>,
+    player_vacations: &HashMap<UserId, DateTime<Utc>>,
+) -> Result<String, CommandError> {
+    let name = match player_nicknames.get(user_id) {
+        Some(nickname) => nickname.clone(),
+        None => user_id.to_user()?.to_string(),
+    };
+    Ok(match player_vacations.get(user_id) {
+        Some(until) => format!("{} (on vacation until {})", name, until.format("%d %b")),
+        None => name,
+    })
+}
+
+/// `self_reported` are the nations whose players say they've played this turn
+/// while the server couldn't tell us, see `SelfReports`
+#[allow(clippy::too_many_arguments)]
+fn details_to_embed(
+    details: GameDetails,
+    utc_offset: FixedOffset,
+    nation_emojis: &NationEmojis,
+    show_defeated: bool,
+    player_sort: PlayerSort,
+    self_reported: &[u32],
+    strings: &LocaleStrings,
+    time_format: TimeFormat,
+) -> Result<CreateEmbed, CommandError> {
     let mut e = match details.nations {
         NationDetails::Started(started_details) => {
-            match &started_details.state {
+            let mut e = match &started_details.state {
                 StartedStateDetails::Playing(playing_state) => {
-                    let embed_title = format!(
-                        "{} ({}): turn {}, {}h {}m remaining",
-                        started_details.game_name,
-                        started_details.address,
-                        playing_state.turn,
-                        playing_state.hours_remaining,
-                        playing_state.mins_remaining
-                    );
+                    let embed_title = match playing_state.option_deadline {
+                        None if playing_state.paused => format!(
+                            "{} ({}): turn {}, Paused",
+                            started_details.game_name, started_details.address, playing_state.turn,
+                        ),
+                        Some(deadline) => format!(
+                            "{} ({}): turn {}, {} remaining (ends {} {})",
+                            started_details.game_name,
+                            started_details.address,
+                            playing_state.turn,
+                            playing_state.show_time_remaining_as(time_format),
+                            deadline.with_timezone(&utc_offset).format("%a %H:%M"),
+                            show_utc_offset(utc_offset),
+                        ),
+                        None => format!(
+                            "{} ({}): turn {}, no timer set",
+                            started_details.game_name, started_details.address, playing_state.turn,
+                        ),
+                    };
 
-                    // we can't have too many players per embed it's real annoying
-                    let mut embed_texts = vec![];
-                    for (ix, potential_player) in playing_state.players.iter().enumerate() {
-                        let (option_user_id, player_details) = match potential_player {
-                            // If the game has started and they're not in it, too bad
-                            PotentialPlayer::RegisteredOnly(_, _, _) => continue,
-                            PotentialPlayer::RegisteredAndGame(user_id, player_details) => {
-                                (Some(user_id), player_details)
+                    let mut lines = vec![format!(
+                        "{}\n",
+                        player_summary(playing_state, self_reported)
+                    )];
+                    let (players, defeated_count) =
+                        players_to_show(&playing_state.players, show_defeated);
+                    let mut rows = vec![];
+                    for (option_user_id, player_details) in players {
+                        // Only look up the names that are going to be shown
+                        let option_shown_name = match option_user_id {
+                            Some(user_id)
+                                if player_details.player_status == NationStatus::Human
+                                    || player_details.went_ai =>
+                            {
+                                Some(show_player(
+                                    user_id,
+                                    &details.player_nicknames,
+                                    &details.player_vacations,
+                                )?)
                             }
-                            PotentialPlayer::GameOnly(player_details) => (None, player_details),
-                        };
-
-                        let player_name = if let NationStatus::Human = player_details.player_status
-                        {
-                            match option_user_id {
-                                Some(user_id) => format!("**{}**", user_id.to_user()?),
-                                None => player_details.player_status.show().to_owned(),
-                            }
-                        } else {
-                            player_details.player_status.show().to_owned()
+                            _ => None,
                         };
+                        rows.push((option_user_id, option_shown_name, player_details));
+                    }
+                    sort_players(&mut rows, player_sort);
+                    for (_, option_shown_name, player_details) in group_by_player(rows) {
+                        let player_name =
+                            show_player_name(player_details, option_shown_name, strings);
 
                         let submission_symbol = if player_details.player_status.is_human() {
-                            player_details.submitted.show().to_owned()
+                            if player_details.submitted != SubmissionStatus::Submitted
+                                && self_reported.contains(&player_details.nation_id)
+                            {
+                                Cow::from(UNVERIFIED_SUBMISSION_SYMBOL)
+                            } else {
+                                player_details.submitted.show().to_owned()
+                            }
                         } else {
                             SubmissionStatus::Submitted.show().to_owned()
                         };
 
-                        if ix % 20 == 0 {
-                            embed_texts.push(String::new());
-                        }
-                        let new_len = embed_texts.len();
-                        embed_texts[new_len - 1].push_str(&format!(
-                            "`{}` {} ({}): {}\n",
+                        lines.push(format!(
+                            "`{}` {}{} ({}): {}\n",
                             submission_symbol,
-                            player_details.nation_name,
+                            nation_emojis.prefix(player_details.nation_id),
+                            strings
+                                .nation_name(player_details.nation_id, &player_details.nation_name),
                             player_details.nation_id,
                             player_name,
                         ));
                     }
+                    if defeated_count > 0 {
+                        lines.push(format!("+{} defeated\n", defeated_count));
+                    }
+                    let embed_texts = group_player_lines(lines);
 
                     // This is pretty hacky
                     let mut e = CreateEmbed::default().title("Details").field(
@@ -148,17 +855,26 @@ fn details_to_embed(details: GameDetails) -> Result<CreateEmbed, CommandError> {
                 }
                 StartedStateDetails::Uploading(uploading_state) => {
                     let embed_title = format!(
-                        "{} ({}): Pretender uploading",
-                        started_details.game_name, started_details.address,
+                        "{} ({}): Pretender uploading, {}",
+                        started_details.game_name,
+                        started_details.address,
+                        uploading_state.upload_progress(),
                     );
 
-                    let mut embed_texts = vec![];
-                    for (ix, uploading_player) in
-                        uploading_state.uploading_players.iter().enumerate()
-                    {
+                    let mut lines = vec![];
+                    for uploading_player in &uploading_state.uploading_players {
                         let player_name = match uploading_player.option_player_id() {
-                            Some(user_id) => format!("**{}**", user_id.to_user()?),
-                            None => NationStatus::Human.show().to_owned(),
+                            Some(user_id) => {
+                                format!(
+                                    "**{}**",
+                                    show_player(
+                                        user_id,
+                                        &details.player_nicknames,
+                                        &details.player_vacations
+                                    )?
+                                )
+                            }
+                            None => strings.show_status(NationStatus::Human).to_owned(),
                         };
 
                         let player_submitted_status = if uploading_player.uploaded {
@@ -167,18 +883,19 @@ fn details_to_embed(details: GameDetails) -> Result<CreateEmbed, CommandError> {
                             SubmissionStatus::NotSubmitted.show()
                         };
 
-                        if ix % 20 == 0 {
-                            embed_texts.push(String::new());
-                        }
-                        let new_len = embed_texts.len();
-                        embed_texts[new_len - 1].push_str(&format!(
-                            "`{}` {} ({}): {}\n",
+                        lines.push(format!(
+                            "`{}` {}{} ({}): {}\n",
                             player_submitted_status,
-                            uploading_player.nation_name(),
+                            nation_emojis.prefix(uploading_player.nation_id()),
+                            strings.nation_name(
+                                uploading_player.nation_id(),
+                                uploading_player.nation_name()
+                            ),
                             uploading_player.nation_id(),
                             player_name,
                         ));
                     }
+                    let embed_texts = group_player_lines(lines);
                     // This is pretty hacky
                     let mut e = CreateEmbed::default().title("Details").field(
                         embed_title,
@@ -190,35 +907,92 @@ fn details_to_embed(details: GameDetails) -> Result<CreateEmbed, CommandError> {
                     }
                     e
                 }
+            };
+            if let Some(snek_game_id) = started_details.option_snek_game_id {
+                e = e.field("Tracker", snek_game_url(snek_game_id), false);
             }
+            e
         }
         NationDetails::Lobby(lobby_details) => {
             let embed_title = match lobby_details.era {
                 Some(era) => format!("{} ({} Lobby)", details.alias, era),
                 None => format!("{} (Lobby)", details.alias),
             };
-            let mut embed_texts = vec![];
-
-            if lobby_details.players.len() != 0 {
-                for (ix, lobby_player) in lobby_details.players.iter().enumerate() {
-                    let discord_user = lobby_player.player_id.to_user()?;
-                    if ix % 20 == 0 {
-                        embed_texts.push(String::new());
+            let mut lines = vec![];
+            for lobby_player in &lobby_details.players {
+                let player_name = show_player(
+                    &lobby_player.player_id,
+                    &details.player_nicknames,
+                    &details.player_vacations,
+                )?;
+                lines.push(format!(
+                    "{}{} ({}): {}\n",
+                    nation_emojis.prefix(lobby_player.nation_id),
+                    strings.nation_name(lobby_player.nation_id, &lobby_player.nation_name),
+                    lobby_player.nation_id,
+                    player_name,
+                ));
+            }
+            for ai_reserved_nation in &lobby_details.ai_reserved {
+                let reserved = match &ai_reserved_nation.option_player_id {
+                    Some(player_id) => format!(
+                        "{} (but registered by {})",
+                        strings.show_status(NationStatus::AI),
+                        show_player(
+                            player_id,
+                            &details.player_nicknames,
+                            &details.player_vacations
+                        )?
+                    ),
+                    None => strings.show_status(NationStatus::AI).to_owned(),
+                };
+                lines.push(format!(
+                    "{}{} ({}): {}\n",
+                    nation_emojis.prefix(ai_reserved_nation.nation_id),
+                    strings.nation_name(
+                        ai_reserved_nation.nation_id,
+                        &ai_reserved_nation.nation_name
+                    ),
+                    ai_reserved_nation.nation_id,
+                    reserved,
+                ));
+            }
+            for user_id in &lobby_details.expected {
+                let player_name = show_player(
+                    user_id,
+                    &details.player_nicknames,
+                    &details.player_vacations,
+                )?;
+                lines.push(format!("{} (expected)\n", player_name));
+            }
+            match &lobby_details.option_open_nations {
+                Some(open_nations) => {
+                    for open_nation in open_nations {
+                        lines.push(format!(
+                            "{}{} ({}): {}\n",
+                            nation_emojis.prefix(open_nation.nation_id),
+                            strings.nation_name(open_nation.nation_id, &open_nation.nation_name),
+                            open_nation.nation_id,
+                            strings.label("open", "OPEN"),
+                        ));
                     }
-                    let new_len = embed_texts.len();
-                    embed_texts[new_len - 1].push_str(&format!(
-                        "{} ({}): {}\n",
-                        lobby_player.nation_name, lobby_player.nation_id, discord_user,
-                    ));
                 }
-            } else {
-                embed_texts.push(String::new());
+                None => match lobby_details.remaining_slots {
+                    Some(remaining_slots) => {
+                        for _ in 0..remaining_slots {
+                            lines.push(format!("{}\n", strings.label("open", "OPEN")));
+                        }
+                    }
+                    None => lines.push(format!(
+                        "{}\n",
+                        strings.label("open_slots_unknown", "open slots: unknown")
+                    )),
+                },
             }
-
-            // We don't increase the number of fields any more
-            let new_len = embed_texts.len();
-            for _ in 0..lobby_details.remaining_slots {
-                embed_texts[new_len - 1].push_str("OPEN\n");
+            // Everything goes through the same limits as a started game's players
+            let mut embed_texts = group_player_lines(lines);
+            if embed_texts.is_empty() {
+                embed_texts.push(String::new());
             }
             // This is pretty hacky
             let mut e = CreateEmbed::default().title("Details").field(
@@ -229,6 +1003,21 @@ fn details_to_embed(details: GameDetails) -> Result<CreateEmbed, CommandError> {
             for embed_text in &embed_texts[1..] {
                 e = e.field("-----", embed_text, false);
             }
+            if !lobby_details.waitlist.is_empty() {
+                let mut waitlist_lines = vec![];
+                for (ix, user_id) in lobby_details.waitlist.iter().enumerate() {
+                    waitlist_lines.push(format!(
+                        "{}. {}",
+                        ix + 1,
+                        show_player(
+                            user_id,
+                            &details.player_nicknames,
+                            &details.player_vacations
+                        )?
+                    ));
+                }
+                e = e.field("Waitlist", waitlist_lines.join("\n"), false);
+            }
             e
         }
     };
@@ -238,7 +1027,7 @@ fn details_to_embed(details: GameDetails) -> Result<CreateEmbed, CommandError> {
 
     for description in details.description {
         if !description.is_empty() {
-            e = e.field("Description", description, false);
+            e = e.field("Description", sanitise_mentions(&description), false);
         }
     }
     Ok(e)
@@ -0,0 +1,555 @@
+use super::*;
+
+use serenity::model::id::UserId;
+
+fn player_details(nation_id: u32, player_status: NationStatus) -> PlayerDetails {
+    PlayerDetails {
+        nation_id,
+        nation_name: format!("nation {}", nation_id),
+        submitted: SubmissionStatus::NotSubmitted,
+        player_status,
+        went_ai: false,
+        connected: false,
+    }
+}
+
+#[test]
+fn should_count_defeated_nations_instead_of_listing_them() {
+    let players = vec![
+        PotentialPlayer::GameOnly(player_details(1, NationStatus::Human)),
+        PotentialPlayer::GameOnly(player_details(2, NationStatus::Defeated)),
+        PotentialPlayer::RegisteredAndGame(UserId(3), player_details(3, NationStatus::Defeated)),
+        // still has to play this turn so is still shown
+        PotentialPlayer::GameOnly(player_details(4, NationStatus::DefeatedThisTurn)),
+        PotentialPlayer::RegisteredOnly(UserId(5), 5, "nation 5".to_owned()),
+    ];
+
+    let (shown, defeated_count) = players_to_show(&players, false);
+    let shown_ids: Vec<u32> = shown.iter().map(|(_, details)| details.nation_id).collect();
+    assert_eq!(shown_ids, vec![1, 4]);
+    assert_eq!(defeated_count, 2);
+
+    let (shown, defeated_count) = players_to_show(&players, true);
+    assert_eq!(shown.len(), 4);
+    assert_eq!(defeated_count, 0);
+}
+
+#[test]
+fn should_show_details_with_nations_from_newer_patches() {
+    use crate::model::{GameData, Nation};
+    let db_conn = DbConnection::test();
+    let game_cache = GameCache::test_polled(
+        "test server 1",
+        GameData {
+            game_name: "test server 1".to_owned(),
+            // well past any nation id we know about
+            nations: vec![Nation {
+                id: 9999,
+                status: NationStatus::Human,
+                submitted: SubmissionStatus::NotSubmitted,
+                connected: false,
+                name: "Unknown nation #9999".to_owned(),
+                era: "unknown".to_owned(),
+            }],
+            turn: 32,
+            turn_timer: 3_600_000,
+        },
+    );
+
+    let (details, _) = cached_game_details("test server 1", &db_conn, &game_cache).unwrap();
+    // the test players are registered as made up nations too
+    assert!(details
+        .warnings
+        .contains(&"Couldn't resolve nation 9999, is it from a mod or a newer patch?".to_owned()));
+    let players = match details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => playing_state.players,
+        other => panic!("unexpected nations {:?}", other),
+    };
+    assert!(players
+        .iter()
+        .any(|player| player.nation_name() == "Unknown nation #9999"));
+}
+
+#[test]
+fn should_remember_mod_nation_names_when_snek_is_down() {
+    use crate::model::{GameData, Nation};
+    use crate::snek::{SnekGameStatus, SnekNation};
+    let db_conn = DbConnection::test();
+    let game_cache = GameCache::test();
+    let mut snek_nations = HashMap::new();
+    snek_nations.insert(
+        9999,
+        SnekNation {
+            nation_id: 9999,
+            name: "Hidden Kingdom".to_owned(),
+        },
+    );
+    let cache_entry = |option_snek_state| CacheEntry {
+        game_data: GameData {
+            game_name: "test server 1".to_owned(),
+            nations: vec![Nation {
+                id: 9999,
+                status: NationStatus::Human,
+                submitted: SubmissionStatus::NotSubmitted,
+                connected: false,
+                name: "Unknown nation #9999".to_owned(),
+                era: "unknown".to_owned(),
+            }],
+            turn: 32,
+            turn_timer: 3_600_000,
+        },
+        option_snek_state,
+        fetched_at: Utc::now(),
+    };
+    let nation_names = |cache_entry| {
+        game_cache.insert("test server 1", cache_entry);
+        let (details, _) = cached_game_details("test server 1", &db_conn, &game_cache).unwrap();
+        match details.nations {
+            NationDetails::Started(StartedDetails {
+                state: StartedStateDetails::Playing(playing_state),
+                ..
+            }) => playing_state
+                .players
+                .iter()
+                .map(|player| player.nation_name().clone())
+                .collect::<Vec<_>>(),
+            other => panic!("unexpected nations {:?}", other),
+        }
+    };
+
+    let polled = cache_entry(Some(SnekGameStatus {
+        nations: snek_nations,
+        option_game_id: None,
+    }));
+    assert!(nation_names(polled.clone()).contains(&"Hidden Kingdom".to_owned()));
+    // showing the game doesn't write anything, the poller does
+    assert!(db_conn
+        .nation_names_for_alias("test server 1")
+        .unwrap()
+        .is_empty());
+    assert_eq!(db_conn.first_seen_for_alias("test server 1").unwrap(), None);
+    record_poll(&db_conn, "test server 1", &polled).unwrap();
+    // snek's down, e.g. after a restart
+    assert!(nation_names(cache_entry(None)).contains(&"Hidden Kingdom".to_owned()));
+    assert_eq!(
+        db_conn.nation_names_for_alias("test server 1").unwrap()[&9999],
+        "Hidden Kingdom"
+    );
+    assert_eq!(
+        db_conn
+            .first_seen_for_alias("test server 1")
+            .unwrap()
+            .map(|first_seen| first_seen.turn),
+        Some(32)
+    );
+}
+
+#[test]
+fn should_sort_listing_by_submission_or_player() {
+    let mut undone = player_details(1, NationStatus::Human);
+    undone.submitted = SubmissionStatus::NotSubmitted;
+    let mut done = player_details(2, NationStatus::Human);
+    done.submitted = SubmissionStatus::Submitted;
+    let ai = player_details(3, NationStatus::AI);
+    let mut partial = player_details(4, NationStatus::Human);
+    partial.submitted = SubmissionStatus::PartiallySubmitted;
+    let rows = vec![
+        (None, Some("zed".to_owned()), &done),
+        (None, None, &ai),
+        (None, Some("Bob".to_owned()), &undone),
+        (None, Some("alice".to_owned()), &partial),
+    ];
+    let nation_ids = |rows: &[PlayerRow]| -> Vec<u32> {
+        rows.iter()
+            .map(|(_, _, details)| details.nation_id)
+            .collect()
+    };
+
+    let mut by_nation = rows.clone();
+    sort_players(&mut by_nation, PlayerSort::Nation);
+    assert_eq!(nation_ids(&by_nation), vec![2, 3, 1, 4]);
+
+    let mut by_submission = rows.clone();
+    sort_players(&mut by_submission, PlayerSort::Submission);
+    // AI nations don't have to play so count as done
+    assert_eq!(nation_ids(&by_submission), vec![1, 4, 2, 3]);
+
+    let mut by_player = rows.clone();
+    sort_players(&mut by_player, PlayerSort::Player);
+    assert_eq!(nation_ids(&by_player), vec![4, 1, 2, 3]);
+
+    assert_eq!(
+        PlayerSort::from_flag("--sort=status"),
+        Some(PlayerSort::Submission)
+    );
+    assert_eq!(PlayerSort::from_flag("--sort=bogus"), None);
+}
+
+#[test]
+fn should_order_nations_with_the_same_name_by_id() {
+    let mut twins = player_details(9, NationStatus::Human);
+    twins.nation_name = "Twins".to_owned();
+    let mut other_twins = player_details(3, NationStatus::Human);
+    other_twins.nation_name = "Twins".to_owned();
+    let ids = |mut players: Vec<PotentialPlayer>| -> Vec<u32> {
+        players.sort_unstable();
+        players.iter().map(PotentialPlayer::nation_id).collect()
+    };
+
+    assert_eq!(
+        ids(vec![
+            PotentialPlayer::GameOnly(twins.clone()),
+            PotentialPlayer::GameOnly(other_twins.clone()),
+        ]),
+        vec![3, 9]
+    );
+    assert_eq!(
+        ids(vec![
+            PotentialPlayer::GameOnly(other_twins),
+            PotentialPlayer::GameOnly(twins),
+        ]),
+        vec![3, 9]
+    );
+}
+
+#[test]
+fn should_group_nations_played_by_the_same_player() {
+    let details: Vec<PlayerDetails> = (1..=5)
+        .map(|nation_id| player_details(nation_id, NationStatus::Human))
+        .collect();
+    let (alice, bob) = (UserId(1), UserId(2));
+    let rows: Vec<PlayerRow> = vec![
+        (Some(&alice), Some("alice".to_owned()), &details[0]),
+        (Some(&bob), Some("bob".to_owned()), &details[1]),
+        (None, None, &details[2]),
+        (Some(&alice), Some("alice".to_owned()), &details[3]),
+        (Some(&bob), Some("bob".to_owned()), &details[4]),
+    ];
+    let nation_ids: Vec<u32> = group_by_player(rows)
+        .iter()
+        .map(|(_, _, details)| details.nation_id)
+        .collect();
+    assert_eq!(nation_ids, vec![1, 4, 2, 5, 3]);
+}
+
+#[test]
+fn should_mark_connected_humans_as_online() {
+    let english = LocaleStrings::default();
+    let mut human = player_details(1, NationStatus::Human);
+    assert_eq!(
+        show_player_name(&human, Some("foo".to_owned()), &english),
+        "**foo**"
+    );
+    human.connected = true;
+    assert_eq!(
+        show_player_name(&human, Some("foo".to_owned()), &english),
+        "**foo** (online)"
+    );
+    assert_eq!(show_player_name(&human, None, &english), "Human (online)");
+
+    let mut ai = player_details(2, NationStatus::AI);
+    ai.went_ai = true;
+    assert_eq!(
+        show_player_name(&ai, Some("foo".to_owned()), &english),
+        "AI (formerly foo)"
+    );
+}
+
+#[test]
+fn should_show_translated_statuses() {
+    let strings = LocaleStrings::parse("label status.ai KI\nlabel status.human Mensch\n").unwrap();
+    let mut ai = player_details(2, NationStatus::AI);
+    assert_eq!(show_player_name(&ai, None, &strings), "KI");
+    ai.went_ai = true;
+    assert_eq!(
+        show_player_name(&ai, Some("foo".to_owned()), &strings),
+        "KI (formerly foo)"
+    );
+    let human = player_details(1, NationStatus::Human);
+    assert_eq!(show_player_name(&human, None, &strings), "Mensch");
+}
+
+#[test]
+fn should_show_archived_game_as_finished() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_archived_for_alias("test server 2", true)
+        .unwrap();
+    let game_cache = GameCache::test();
+
+    // nothing has been polled, so this would fail if it tried the server
+    let embed = details_helper(
+        "test server 2",
+        &db_conn,
+        &game_cache,
+        &NationEmojis::default(),
+        &LocaleStrings::default(),
+        &SelfReports::default(),
+        false,
+        PlayerSort::Nation,
+        TimeFormat::Exact,
+    )
+    .unwrap();
+    let embed_text = format!("{:?}", embed.0);
+    assert!(embed_text.contains("Game finished"));
+    assert!(embed_text.contains("<@102>"));
+}
+
+#[test]
+fn should_only_count_humans_as_still_to_play() {
+    let mut submitted = player_details(2, NationStatus::Human);
+    submitted.submitted = SubmissionStatus::Submitted;
+    let mut playing_state = PlayingState {
+        players: vec![
+            PotentialPlayer::GameOnly(player_details(1, NationStatus::Human)),
+            PotentialPlayer::RegisteredAndGame(UserId(2), submitted),
+            PotentialPlayer::GameOnly(player_details(3, NationStatus::AI)),
+            PotentialPlayer::RegisteredOnly(UserId(4), 4, "nation 4".to_owned()),
+        ],
+        turn: 5,
+        mins_remaining: 0,
+        hours_remaining: 1,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: None,
+        paused: false,
+        blitz: false,
+    };
+    assert!(!everyone_has_played(&playing_state, &[]));
+    // says they've played while the server is down
+    assert!(everyone_has_played(&playing_state, &[1]));
+    // but the host is still waiting on the server's word for it
+    assert!(!playing_state.waiting_on_host());
+    playing_state.players.remove(0);
+    assert!(everyone_has_played(&playing_state, &[]));
+    assert!(playing_state.waiting_on_host());
+    playing_state.paused = true;
+    assert!(!playing_state.waiting_on_host());
+}
+
+#[test]
+fn should_show_embed_fields_as_text() {
+    let embed = CreateEmbed::default()
+        .title("Details")
+        .field("foo (1.2.3.4:5): turn 3", "`✓` nation 1 (1): AI\n", false)
+        .field("Map", "bar", false);
+    let text = embed_to_text(&embed);
+    assert_eq!(
+        text,
+        "**foo (1.2.3.4:5): turn 3**\n`✓` nation 1 (1): AI\n\n**Map**\nbar\n"
+    );
+}
+
+#[test]
+fn should_split_long_text_between_lines() {
+    let line = "x".repeat(900);
+    let text = format!("{}\n{}\n{}\n", line, line, line);
+    let messages = split_message(&text);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0], format!("{}\n{}\n", line, line));
+    assert!(messages.iter().all(|message| message.len() <= 2000));
+}
+
+#[test]
+fn should_group_players_into_fields_discord_shows_in_full() {
+    let short_lines: Vec<String> = (0..45).map(|i| format!("nation {}\n", i)).collect();
+    let embed_texts = group_player_lines(short_lines);
+    assert_eq!(embed_texts.len(), 3);
+    assert_eq!(embed_texts[2].lines().count(), 5);
+
+    let long_line = format!("{}\n", "x".repeat(300));
+    let embed_texts = group_player_lines(vec![long_line.clone(); 7]);
+    assert_eq!(
+        embed_texts,
+        vec![long_line.repeat(3), long_line.repeat(3), long_line]
+    );
+}
+
+#[test]
+fn should_keep_every_lobby_line_inside_the_field_limits() {
+    let expected: Vec<UserId> = (1..=30).map(UserId).collect();
+    let player_nicknames = expected
+        .iter()
+        .map(|&user_id| (user_id, format!("{} {}", "x".repeat(60), user_id)))
+        .collect();
+    let details = GameDetails {
+        alias: "foo".to_owned(),
+        owner: None,
+        description: None,
+        player_nicknames,
+        player_vacations: HashMap::new(),
+        nations: NationDetails::Lobby(LobbyDetails {
+            players: vec![],
+            era: None,
+            remaining_slots: Some(25),
+            ai_reserved: vec![],
+            option_open_nations: None,
+            expected,
+            waitlist: vec![],
+        }),
+        cache_entry: None,
+        warnings: vec![],
+    };
+    let embed = details_to_embed(
+        details,
+        FixedOffset::east(0),
+        &NationEmojis::default(),
+        false,
+        PlayerSort::Nation,
+        &[],
+        &LocaleStrings::default(),
+        TimeFormat::Exact,
+    )
+    .unwrap();
+    let fields = embed.0.get(&"fields").unwrap().as_array().unwrap();
+    let values: Vec<&str> = fields
+        .iter()
+        .map(|field| field["value"].as_str().unwrap())
+        .collect();
+    assert!(values.len() > 1);
+    assert!(values.iter().all(|value| value.len() <= 1024));
+    assert!(values.iter().all(|value| value.lines().count() <= 20));
+    let shown: usize = values
+        .iter()
+        .map(|value| value.matches("(expected)").count())
+        .sum();
+    assert_eq!(shown, 30);
+}
+
+#[test]
+fn should_only_split_embeds_that_are_too_big() {
+    let embed = CreateEmbed::default()
+        .title("Details")
+        .field(
+            "foo: turn 3, 1h remaining",
+            "`X` nation 1 (1): **bob**\n",
+            false,
+        )
+        .field("Map", "bar", false);
+    assert_eq!(paginate_embed(embed).len(), 1);
+
+    let value = "x".repeat(1000);
+    let mut embed =
+        CreateEmbed::default()
+            .title("Details")
+            .field("foo: turn 3, 1h remaining", &value, false);
+    for _ in 0..9 {
+        embed = embed.field("-----", &value, false);
+    }
+    let pages = paginate_embed(embed);
+    assert_eq!(pages.len(), 2);
+    let first_page = format!("{:?}", pages[0].0);
+    assert!(first_page.contains("foo: turn 3, 1h remaining"));
+    assert!(!format!("{:?}", pages[1].0).contains("foo: turn 3, 1h remaining"));
+    for page in &pages {
+        let fields = page.0.get(&"fields").unwrap().as_array().unwrap();
+        let len: usize = fields
+            .iter()
+            .map(|field| {
+                field["name"].as_str().unwrap().len() + field["value"].as_str().unwrap().len()
+            })
+            .sum();
+        assert!(len <= 6000);
+    }
+}
+
+#[test]
+fn should_find_nations_registered_more_than_once() {
+    let player = |user_id: u64| Player {
+        discord_user_id: UserId(user_id),
+        turn_notifications: true,
+    };
+    let players_nations = vec![
+        (player(1), 5),
+        (player(2), 6),
+        (player(3), 5),
+        (player(4), 7),
+    ];
+    assert_eq!(
+        duplicate_registrations(&players_nations),
+        vec![(5, vec![UserId(1), UserId(3)])]
+    );
+    assert!(duplicate_registrations(&players_nations[1..]).is_empty());
+}
+
+#[test]
+fn should_label_the_age_of_games_found_part_way_as_tracked() {
+    use chrono::TimeZone;
+    let now = Utc.ymd(2021, 3, 12).and_hms(12, 0, 0);
+    let from_start = FirstSeen {
+        at: Utc.ymd(2021, 1, 29).and_hms(9, 0, 0),
+        turn: 1,
+    };
+    assert_eq!(
+        show_game_age(&from_start, 37, now),
+        ("Game age", "42 days, turn 37".to_owned())
+    );
+    let part_way = FirstSeen {
+        at: Utc.ymd(2021, 3, 11).and_hms(18, 0, 0),
+        turn: 25,
+    };
+    assert_eq!(
+        show_game_age(&part_way, 26, now),
+        (
+            "Tracked for",
+            "less than a day (since turn 25), turn 26".to_owned()
+        )
+    );
+}
+
+#[test]
+fn should_keep_the_first_time_a_game_was_seen() {
+    use chrono::TimeZone;
+    let db_conn = DbConnection::test();
+    assert_eq!(db_conn.first_seen_for_alias("test server 2").unwrap(), None);
+    let first = Utc.ymd(2021, 1, 29).and_hms(9, 0, 0);
+    db_conn
+        .record_first_seen("test server 2", first, 3)
+        .unwrap();
+    db_conn
+        .record_first_seen("test server 2", first + chrono::Duration::days(1), 4)
+        .unwrap();
+    assert_eq!(
+        db_conn.first_seen_for_alias("test server 2").unwrap(),
+        Some(FirstSeen { at: first, turn: 3 })
+    );
+}
+
+#[test]
+fn should_summarise_the_nations_like_the_list_counts_them() {
+    let mut submitted = player_details(2, NationStatus::Human);
+    submitted.submitted = SubmissionStatus::Submitted;
+    let mut went_ai = player_details(4, NationStatus::AI);
+    went_ai.went_ai = true;
+    let playing_state = PlayingState {
+        players: vec![
+            PotentialPlayer::GameOnly(player_details(1, NationStatus::Human)),
+            PotentialPlayer::RegisteredAndGame(UserId(2), submitted),
+            PotentialPlayer::GameOnly(player_details(3, NationStatus::AI)),
+            PotentialPlayer::RegisteredAndGame(UserId(4), went_ai),
+            PotentialPlayer::GameOnly(player_details(5, NationStatus::Defeated)),
+            // still has to play this turn
+            PotentialPlayer::GameOnly(player_details(6, NationStatus::DefeatedThisTurn)),
+            PotentialPlayer::RegisteredOnly(UserId(7), 7, "nation 7".to_owned()),
+        ],
+        turn: 5,
+        mins_remaining: 0,
+        hours_remaining: 1,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: None,
+        paused: false,
+        blitz: false,
+    };
+    assert_eq!(
+        player_summary(&playing_state, &[]),
+        "6 nations: 3 human, 2 AI (1 formerly human), 1 defeated — 2 turns outstanding"
+    );
+    assert_eq!(
+        player_summary(&playing_state, &[1]),
+        "6 nations: 3 human, 2 AI (1 formerly human), 1 defeated — 1 turn outstanding"
+    );
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Duration, Utc};
+use serenity::builder::CreateEmbed;
+use serenity::model::id::UserId;
+use serenity::prelude::Mutex;
+use std::collections::HashMap;
+use typemap::Key;
+
+use super::PlayerSort;
+
+#[cfg(test)]
+mod tests;
+
+/// How long somebody asking for the same details again just gets their last answer
+pub const DETAILS_COOLDOWN_SECS: i64 = 30;
+
+/// Everything that changes what `!details` shows
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DetailsRequest {
+    pub user_id: UserId,
+    pub alias: String,
+    pub show_defeated: bool,
+    pub player_sort: PlayerSort,
+}
+
+/// The last details embed each person was sent, so that spamming `!details`
+/// re-sends that instead of building a new one every time
+pub struct DetailsCooldowns {
+    cooldown: Duration,
+    sent: Mutex<HashMap<DetailsRequest, (DateTime<Utc>, CreateEmbed)>>,
+}
+
+pub struct DetailsCooldownsKey;
+impl Key for DetailsCooldownsKey {
+    type Value = DetailsCooldowns;
+}
+
+impl DetailsCooldowns {
+    pub fn new(cooldown: Duration) -> Self {
+        DetailsCooldowns {
+            cooldown,
+            sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `None` unless the same request was answered less than the cooldown ago
+    pub fn get(&self, request: &DetailsRequest, now: DateTime<Utc>) -> Option<CreateEmbed> {
+        self.sent
+            .lock()
+            .get(request)
+            .filter(|(sent_at, _)| now.signed_duration_since(*sent_at) < self.cooldown)
+            .map(|(_, embed)| embed.clone())
+    }
+
+    /// Also forgets anything that's cooled down, so this only ever holds the
+    /// last few seconds of requests
+    pub fn insert(&self, request: DetailsRequest, embed: CreateEmbed, now: DateTime<Utc>) {
+        let mut sent = self.sent.lock();
+        let cooldown = self.cooldown;
+        sent.retain(|_, (sent_at, _)| now.signed_duration_since(*sent_at) < cooldown);
+        sent.insert(request, (now, embed));
+    }
+}
@@ -0,0 +1,56 @@
+use super::*;
+
+use chrono::TimeZone;
+
+fn request(user_id: u64, alias: &str) -> DetailsRequest {
+    DetailsRequest {
+        user_id: UserId(user_id),
+        alias: alias.to_owned(),
+        show_defeated: false,
+        player_sort: PlayerSort::Nation,
+    }
+}
+
+fn embed(title: &str) -> CreateEmbed {
+    CreateEmbed::default().title(title)
+}
+
+fn title(embed: &CreateEmbed) -> Option<String> {
+    embed
+        .0
+        .get(&"title")
+        .and_then(|title| title.as_str())
+        .map(|title| title.to_owned())
+}
+
+#[test]
+fn should_reuse_answer_within_cooldown() {
+    let cooldowns = DetailsCooldowns::new(Duration::seconds(DETAILS_COOLDOWN_SECS));
+    let sent_at = Utc.timestamp(1_000_000, 0);
+    cooldowns.insert(request(1, "foo"), embed("first"), sent_at);
+
+    let option_embed = cooldowns.get(&request(1, "foo"), sent_at + Duration::seconds(5));
+    assert_eq!(
+        option_embed.as_ref().and_then(title),
+        Some("first".to_owned())
+    );
+    assert!(cooldowns
+        .get(
+            &request(1, "foo"),
+            sent_at + Duration::seconds(DETAILS_COOLDOWN_SECS)
+        )
+        .is_none());
+}
+
+#[test]
+fn should_not_share_answers_between_requests() {
+    let cooldowns = DetailsCooldowns::new(Duration::seconds(DETAILS_COOLDOWN_SECS));
+    let sent_at = Utc.timestamp(1_000_000, 0);
+    cooldowns.insert(request(1, "foo"), embed("first"), sent_at);
+
+    assert!(cooldowns.get(&request(2, "foo"), sent_at).is_none());
+    assert!(cooldowns.get(&request(1, "bar"), sent_at).is_none());
+    let mut sorted = request(1, "foo");
+    sorted.player_sort = PlayerSort::Submission;
+    assert!(cooldowns.get(&sorted, sent_at).is_none());
+}
@@ -0,0 +1,57 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::GuildId;
+use serenity::prelude::Context;
+
+use super::admin_guild_id;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+fn details_format_helper(
+    db_conn: &DbConnection,
+    guild_id: GuildId,
+    arg_format: &str,
+) -> Result<String, CommandError> {
+    let details_as_text = match arg_format {
+        "embed" => false,
+        "text" => true,
+        _ => {
+            return Err(CommandError::from(format!(
+                "Unknown format \"{}\", try \"embed\" or \"text\"",
+                arg_format
+            )))
+        }
+    };
+    db_conn.set_details_as_text_for_guild(guild_id, details_as_text)?;
+    Ok(if details_as_text {
+        "Details in this server will be sent as plain text".to_owned()
+    } else {
+        "Details in this server will be sent as embeds".to_owned()
+    })
+}
+
+pub fn details_format(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_format = args.single_quoted::<String>()?.to_lowercase();
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let guild_id = admin_guild_id(message)?;
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = details_format_helper(db_conn, guild_id, &arg_format)?;
+    info!("details_format: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
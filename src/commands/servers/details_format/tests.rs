@@ -0,0 +1,21 @@
+use super::*;
+
+#[test]
+fn should_switch_details_to_text_and_back() {
+    let db_conn = DbConnection::test();
+    let guild_id = GuildId(319_001);
+    assert!(!db_conn.details_as_text_for_guild(guild_id).unwrap());
+
+    details_format_helper(&db_conn, guild_id, "text").unwrap();
+    assert!(db_conn.details_as_text_for_guild(guild_id).unwrap());
+    // doesn't touch the guild's prefix
+    db_conn.set_prefix_for_guild(guild_id, Some(".")).unwrap();
+    details_format_helper(&db_conn, guild_id, "embed").unwrap();
+    assert!(!db_conn.details_as_text_for_guild(guild_id).unwrap());
+    assert!(db_conn
+        .guild_prefixes()
+        .unwrap()
+        .contains(&(guild_id, ".".to_owned())));
+
+    assert!(details_format_helper(&db_conn, guild_id, "fancy").is_err());
+}
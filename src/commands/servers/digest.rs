@@ -0,0 +1,216 @@
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Timelike, Utc, Weekday};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+
+use super::history::{hours, turn_intervals};
+use super::turn_check::ChannelNotification;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of a day to stop the digest
+const CLEAR_FLAG: &str = "--clear";
+/// Comfortably more turns than even a blitz game gets through in a week
+const DIGEST_HISTORY_TURNS: u32 = 1000;
+/// Indexed by the day as stored, 0 is Monday
+const DAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// `"friday" "18:00"` to (day, minute of the day), see `ChannelDigest`
+pub fn parse_digest_schedule(arg_day: &str, arg_time: &str) -> Result<(u32, u32), CommandError> {
+    let weekday: Weekday = arg_day
+        .parse()
+        .map_err(|_| CommandError::from(format!("\"{}\" isn't a day of the week", arg_day)))?;
+    let time = NaiveTime::parse_from_str(arg_time, "%H:%M").map_err(|_| {
+        CommandError::from(format!(
+            "\"{}\" isn't a time of day, use 24 hour UTC like 18:00",
+            arg_time
+        ))
+    })?;
+    Ok((
+        weekday.num_days_from_monday(),
+        time.hour() * 60 + time.minute(),
+    ))
+}
+
+fn show_digest_schedule(weekday: u32, minute: u32) -> String {
+    format!(
+        "{}s at {:02}:{:02} UTC",
+        DAY_NAMES[weekday as usize % 7],
+        minute / 60,
+        minute % 60
+    )
+}
+
+/// The most recent time the digest was meant to go out, up to and including `now`
+pub fn last_digest_time(weekday: u32, minute: u32, now: DateTime<Utc>) -> DateTime<Utc> {
+    let days_back = (now.weekday().num_days_from_monday() + 7 - weekday % 7) % 7;
+    let scheduled = (now.date() - Duration::days(i64::from(days_back))).and_hms(0, 0, 0)
+        + Duration::minutes(i64::from(minute));
+    if scheduled > now {
+        scheduled - Duration::days(7)
+    } else {
+        scheduled
+    }
+}
+
+/// One line about what a game got up to in the week before `now`, or `None`
+/// if no turns started in it. `history` is oldest first.
+pub fn game_digest(
+    alias: &str,
+    history: &[(i32, DateTime<Utc>)],
+    now: DateTime<Utc>,
+) -> Option<String> {
+    let week_start = now - Duration::days(7);
+    let new_turns = history
+        .iter()
+        .filter(|&&(_, started)| started > week_start && started <= now)
+        .count();
+    if new_turns == 0 {
+        return None;
+    }
+    let &(current_turn, _) = history.last()?;
+
+    let durations: Vec<Duration> = turn_intervals(history)
+        .into_iter()
+        .filter(|&(_, _, ended)| ended > week_start && ended <= now)
+        .map(|(_, started, ended)| ended.signed_duration_since(started))
+        .collect();
+    let average = if durations.is_empty() {
+        String::new()
+    } else {
+        let total_secs: i64 = durations
+            .iter()
+            .map(|duration| duration.num_seconds())
+            .sum();
+        let mean = Duration::seconds(total_secs / durations.len() as i64);
+        format!(", turns took {:.1}h on average", hours(mean))
+    };
+    Some(format!(
+        "{}: {} new turn{}, now on turn {}{}",
+        alias,
+        new_turns,
+        if new_turns == 1 { "" } else { "s" },
+        current_turn,
+        average
+    ))
+}
+
+/// The digest for every game posting in the channel, or `None` if none of them
+/// did anything this week
+fn channel_digest(
+    db_conn: &DbConnection,
+    channel_id: ChannelId,
+    now: DateTime<Utc>,
+) -> Result<Option<String>, CommandError> {
+    let mut lines = vec![];
+    for alias in db_conn.aliases_for_channel(channel_id)? {
+        let history = db_conn.select_turn_history(&alias, DIGEST_HISTORY_TURNS)?;
+        lines.extend(game_digest(&alias, &history, now));
+    }
+    if lines.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(format!("This week's games:\n{}", lines.join("\n"))))
+    }
+}
+
+/// Digests for the channels whose time has come round, they go out with the
+/// poller's other channel messages
+pub fn due_digests(db_conn: &DbConnection, now: DateTime<Utc>) -> Vec<ChannelNotification> {
+    let digests = match db_conn.channel_digests() {
+        Ok(digests) => digests,
+        Err(e) => {
+            error!("Could not get channel digests with error {:?}", e);
+            return vec![];
+        }
+    };
+    let mut ret = vec![];
+    for (channel_id, weekday, minute, last_sent) in digests {
+        if last_sent >= last_digest_time(weekday, minute, now) {
+            continue;
+        }
+        match channel_digest(db_conn, channel_id, now) {
+            Ok(Some(message)) => ret.push(ChannelNotification {
+                channel_id,
+                message,
+            }),
+            Ok(None) => info!("Nothing to put in the digest for {}", channel_id),
+            Err(e) => error!(
+                "Could not make the digest for {} with error {:?}",
+                channel_id, e
+            ),
+        }
+        // Even if it failed, so that it doesn't try again every poll
+        if let Err(e) = db_conn.mark_digest_sent(channel_id, now) {
+            error!(
+                "Could not mark the digest for {} as sent with error {:?}",
+                channel_id, e
+            );
+        }
+    }
+    ret
+}
+
+fn digest_helper(
+    db_conn: &DbConnection,
+    channel_id: ChannelId,
+    arg_day: &str,
+    option_arg_time: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<String, CommandError> {
+    if arg_day == CLEAR_FLAG {
+        db_conn.set_digest_for_channel(channel_id, None, now)?;
+        return Ok("This channel won't get a weekly digest any more".to_owned());
+    }
+    let arg_time = option_arg_time
+        .ok_or_else(|| CommandError::from("Expected a time after the day, like 18:00"))?;
+    let (weekday, minute) = parse_digest_schedule(arg_day, arg_time)?;
+    db_conn.set_digest_for_channel(channel_id, Some((weekday, minute)), now)?;
+    Ok(format!(
+        "This channel will get a digest of its games' turns {}, skipping weeks where nothing happened",
+        show_digest_schedule(weekday, minute)
+    ))
+}
+
+pub fn digest(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_day = args.single_quoted::<String>().map_err(|_| {
+        CommandError::from("Expected a day and a time like \"friday 18:00\", or --clear")
+    })?;
+    let option_arg_time = args.single_quoted::<String>().ok();
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = digest_helper(
+        db_conn,
+        message.channel_id,
+        &arg_day,
+        option_arg_time.as_deref(),
+        Utc::now(),
+    )?;
+    info!("digest: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
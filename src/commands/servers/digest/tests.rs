@@ -0,0 +1,71 @@
+use super::*;
+
+use chrono::TimeZone;
+
+#[test]
+fn should_find_the_last_time_the_digest_was_due() {
+    // a Wednesday
+    let now = Utc.ymd(2020, 10, 14).and_hms(18, 0, 0);
+    let (friday, six_pm) = parse_digest_schedule("friday", "18:00").unwrap();
+    assert_eq!(
+        last_digest_time(friday, six_pm, now),
+        Utc.ymd(2020, 10, 9).and_hms(18, 0, 0)
+    );
+    let (wednesday, noon) = parse_digest_schedule("Wed", "12:00").unwrap();
+    assert_eq!(
+        last_digest_time(wednesday, noon, now),
+        Utc.ymd(2020, 10, 14).and_hms(12, 0, 0)
+    );
+    let (_, eight_pm) = parse_digest_schedule("wednesday", "20:00").unwrap();
+    assert_eq!(
+        last_digest_time(wednesday, eight_pm, now),
+        Utc.ymd(2020, 10, 7).and_hms(20, 0, 0)
+    );
+
+    assert!(parse_digest_schedule("someday", "18:00").is_err());
+    assert!(parse_digest_schedule("friday", "6pm").is_err());
+}
+
+#[test]
+fn should_sum_up_the_week_and_skip_quiet_games() {
+    let now = Utc.ymd(2020, 10, 14).and_hms(18, 0, 0);
+    let history = vec![
+        (10, now - Duration::days(9)),
+        (11, now - Duration::days(6)),
+        (12, now - Duration::days(2)),
+    ];
+    assert_eq!(
+        game_digest("foo", &history, now),
+        Some("foo: 2 new turns, now on turn 12, turns took 84.0h on average".to_owned())
+    );
+    assert_eq!(game_digest("foo", &history[..1], now), None);
+}
+
+#[test]
+fn should_post_each_digest_once_per_week() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_channel_for_alias("test server 1", ChannelId(334))
+        .unwrap();
+    db_conn
+        .update_game_with_possibly_new_turn("test server 1", 32, None)
+        .unwrap();
+    let now = Utc::now();
+    let weekday = now.weekday().num_days_from_monday();
+    db_conn
+        .set_digest_for_channel(ChannelId(334), Some((weekday, 0)), now - Duration::days(8))
+        .unwrap();
+
+    let digests = due_digests(&db_conn, now);
+    assert_eq!(digests.len(), 1);
+    assert_eq!(digests[0].channel_id, ChannelId(334));
+    assert!(digests[0]
+        .message
+        .contains("test server 1: 1 new turn, now on turn 32"));
+    assert!(due_digests(&db_conn, now).is_empty());
+
+    db_conn
+        .set_digest_for_channel(ChannelId(334), None, now)
+        .unwrap();
+    assert!(db_conn.channel_digests().unwrap().is_empty());
+}
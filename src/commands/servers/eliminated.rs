@@ -0,0 +1,50 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+fn eliminated_helper(db_conn: &DbConnection, alias: &str) -> Result<String, CommandError> {
+    // So that a typo doesn't look like a game where nobody's died yet
+    db_conn.game_for_alias(alias)?;
+    let eliminations = db_conn.select_eliminations(alias)?;
+    if eliminations.is_empty() {
+        return Ok(format!(
+            "No nations have been seen eliminated in {} yet",
+            alias
+        ));
+    }
+
+    let mut text = format!("Nations eliminated in {}:\n", alias);
+    for (turn, _, nation_name) in eliminations {
+        text.push_str(&format!("- {} on turn {}\n", nation_name, turn));
+    }
+    Ok(text)
+}
+
+pub fn eliminated(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = eliminated_helper(db_conn, &alias)?;
+    info!("eliminated: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
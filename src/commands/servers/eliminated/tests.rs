@@ -0,0 +1,27 @@
+use super::*;
+
+#[test]
+fn should_list_eliminations_in_order_keeping_the_first_turn() {
+    let db_conn = DbConnection::test();
+    assert_eq!(
+        eliminated_helper(&db_conn, "test server 1").unwrap(),
+        "No nations have been seen eliminated in test server 1 yet"
+    );
+
+    db_conn
+        .record_elimination("test server 1", 30, 10, "Ulm")
+        .unwrap();
+    db_conn
+        .record_elimination("test server 1", 12, 5, "Arcoscephale")
+        .unwrap();
+    // Still dead the turn after, which doesn't change when it died
+    db_conn
+        .record_elimination("test server 1", 31, 10, "Ulm")
+        .unwrap();
+
+    assert_eq!(
+        eliminated_helper(&db_conn, "test server 1").unwrap(),
+        "Nations eliminated in test server 1:\n- Arcoscephale on turn 12\n- Ulm on turn 30\n"
+    );
+    assert!(eliminated_helper(&db_conn, "not a game").is_err());
+}
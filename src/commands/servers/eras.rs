@@ -0,0 +1,70 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::Era;
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+fn show_count(label: &str, count: usize, total: usize) -> String {
+    format!(
+        "{}: {} ({}%)",
+        label,
+        count,
+        (count * 100 + total / 2) / total
+    )
+}
+
+fn eras_helper(db_conn: &DbConnection) -> Result<String, CommandError> {
+    let started_eras = db_conn.started_eras()?;
+    let mut counts = [0; 3];
+    let mut unknown = 0;
+    let mut total = 0;
+    for server in db_conn.retrieve_all_servers()? {
+        let option_era = match server.state {
+            GameServerState::Lobby(lobby_state)
+            | GameServerState::StartedState(_, Some(lobby_state)) => Some(lobby_state.era),
+            GameServerState::StartedState(_, None) => started_eras.get(&server.alias).cloned(),
+            // Only the games still being played
+            GameServerState::Archived(_, _) => continue,
+        };
+        total += 1;
+        match option_era {
+            Some(era) => counts[era as usize] += 1,
+            None => unknown += 1,
+        }
+    }
+    if total == 0 {
+        return Ok("There aren't any games being tracked".to_owned());
+    }
+    let mut lines = vec![format!("Eras across {} tracked games:", total)];
+    for &era in &[Era::Early, Era::Middle, Era::Late] {
+        lines.push(show_count(&era.to_string(), counts[era as usize], total));
+    }
+    // Games added before the era was recorded, or before any nations were picked
+    if unknown > 0 {
+        lines.push(show_count("Unknown", unknown, total));
+    }
+    Ok(lines.join("\n"))
+}
+
+pub fn eras(context: &mut Context, message: &Message, args: Args) -> Result<(), CommandError> {
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "!eras counts every game the bot tracks, it doesn't take any arguments",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = eras_helper(db_conn)?;
+    info!("eras: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use super::*;
+
+use crate::model::{GameServer, LobbyState, StartedState};
+use serenity::model::id::{ChannelId, UserId};
+
+fn started(alias: &str) -> GameServer {
+    GameServer {
+        alias: alias.to_owned(),
+        state: GameServerState::StartedState(
+            StartedState {
+                address: format!("{}:1234", alias),
+                last_seen_turn: 5,
+                option_snek_game_id: None,
+            },
+            None,
+        ),
+    }
+}
+
+#[test]
+fn should_count_lobby_eras_and_the_ones_recorded_for_added_games() {
+    let db_conn = DbConnection::test();
+    for (alias, era) in &[("eras ea", Era::Early), ("eras la", Era::Late)] {
+        db_conn
+            .insert_game_server(&GameServer {
+                alias: (*alias).to_owned(),
+                state: GameServerState::Lobby(LobbyState {
+                    owner: UserId(3831),
+                    era: *era,
+                    player_count: 5,
+                    description: None,
+                }),
+            })
+            .unwrap();
+    }
+    db_conn
+        .insert_game_server_with_channel(&started("eras added"), ChannelId(3831), Some(Era::Early))
+        .unwrap();
+    db_conn
+        .set_archived_for_alias("test server 9", true)
+        .unwrap();
+
+    // The 8 test servers still going were added before eras were recorded
+    assert_eq!(
+        eras_helper(&db_conn).unwrap(),
+        "Eras across 11 tracked games:\nEA: 2 (18%)\nMA: 0 (0%)\nLA: 1 (9%)\nUnknown: 8 (73%)"
+    );
+}
@@ -0,0 +1,218 @@
+use chrono::{DateTime, Duration, Utc};
+use log::*;
+use serenity::prelude::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use typemap::Key;
+
+use super::{CacheEntry, GameDetails};
+
+#[cfg(test)]
+mod test_helpers;
+
+#[cfg(test)]
+mod tests;
+
+/// The poller refreshes every minute, so anything older means it's fallen behind
+pub const GAME_CACHE_TTL_MINS: i64 = 2;
+/// How long we keep showing game data for a server that has stopped responding
+pub const CACHE_TTL_HOURS: i64 = 24;
+
+/// The last poll for an alias. `option_cache_entry` is the last time the server
+/// was read successfully, so if the server goes down we can still show that
+/// (flagged as stale) alongside the error.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CachedPoll {
+    pub polled_at: DateTime<Utc>,
+    pub option_cache_entry: Option<CacheEntry>,
+    /// Set if the most recent poll failed
+    pub option_error: Option<String>,
+    /// See `ServerError::category`. `None` if the poll failed for some other
+    /// reason, e.g. the db
+    pub option_error_category: Option<&'static str>,
+}
+
+impl CachedPoll {
+    fn success(cache_entry: CacheEntry) -> Self {
+        CachedPoll {
+            polled_at: cache_entry.fetched_at,
+            option_cache_entry: Some(cache_entry),
+            option_error: None,
+            option_error_category: None,
+        }
+    }
+}
+
+/// The last poll of each game by alias, shared between the poller and any
+/// command that would otherwise talk to the server itself. Each alias has its
+/// own lock so that several people asking about the same game at once only
+/// cause one fetch.
+pub struct GameCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Arc<Mutex<Option<CachedPoll>>>>>,
+    /// Lobbies are built from the database rather than fetched, so they have
+    /// their own (usually much shorter) TTL. Zero means they aren't cached.
+    lobby_ttl: Duration,
+    lobbies: Mutex<HashMap<String, (DateTime<Utc>, GameDetails)>>,
+}
+
+pub struct GameCacheKey;
+impl Key for GameCacheKey {
+    type Value = Arc<GameCache>;
+}
+
+impl GameCache {
+    pub fn new(ttl: Duration) -> Self {
+        GameCache {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            lobby_ttl: Duration::zero(),
+            lobbies: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Also keep lobby details for `lobby_ttl`. Anything that changes a lobby
+    /// without calling `invalidate_lobby` can then take that long to show up.
+    pub fn with_lobby_ttl(mut self, lobby_ttl: Duration) -> Self {
+        self.lobby_ttl = lobby_ttl;
+        self
+    }
+
+    fn slot(&self, alias: &str) -> Arc<Mutex<Option<CachedPoll>>> {
+        self.entries
+            .lock()
+            .entry(alias.to_owned())
+            .or_insert_with(|| Arc::new(Mutex::new(None)))
+            .clone()
+    }
+
+    fn is_fresh(&self, cache_entry: &CacheEntry) -> bool {
+        Utc::now().signed_duration_since(cache_entry.fetched_at) < self.ttl
+    }
+
+    fn fresh_entry(&self, option_cached_poll: &Option<CachedPoll>) -> Option<CacheEntry> {
+        option_cached_poll
+            .as_ref()
+            .and_then(|cached_poll| cached_poll.option_cache_entry.as_ref())
+            .filter(|cache_entry| self.is_fresh(cache_entry))
+            .cloned()
+    }
+
+    /// The last poll however old it is, `None` if the game hasn't been polled
+    pub fn get_poll(&self, alias: &str) -> Option<CachedPoll> {
+        self.entries
+            .lock()
+            .get(alias)
+            .and_then(|slot| slot.lock().clone())
+    }
+
+    /// The latest poll result, with no fallback to older data
+    pub fn get_latest(&self, alias: &str) -> Option<Result<CacheEntry, String>> {
+        self.get_poll(alias)
+            .and_then(|cached_poll| match cached_poll.option_error {
+                Some(error) => Some(Err(error)),
+                None => cached_poll.option_cache_entry.map(Ok),
+            })
+    }
+
+    pub fn insert(&self, alias: &str, cache_entry: CacheEntry) {
+        *self.slot(alias).lock() = Some(CachedPoll::success(cache_entry));
+    }
+
+    /// Keeps the last good entry around so it can be shown as stale
+    pub fn insert_error(
+        &self,
+        alias: &str,
+        error: String,
+        option_error_category: Option<&'static str>,
+    ) {
+        let slot = self.slot(alias);
+        let mut option_cached_poll = slot.lock();
+        let option_cache_entry = option_cached_poll
+            .take()
+            .and_then(|cached_poll| cached_poll.option_cache_entry);
+        *option_cached_poll = Some(CachedPoll {
+            polled_at: Utc::now(),
+            option_cache_entry,
+            option_error: Some(error),
+            option_error_category,
+        });
+    }
+
+    /// e.g. after a game changes address, so that we don't show the old server
+    pub fn invalidate(&self, alias: &str) {
+        self.entries.lock().remove(alias);
+        self.lobbies.lock().remove(alias);
+    }
+
+    /// For anything that changes a lobby, so the change shows up straight away
+    /// rather than once the lobby TTL is up. Leaves any poll alone, since a
+    /// started game's server hasn't changed.
+    pub fn invalidate_lobby(&self, alias: &str) {
+        self.lobbies.lock().remove(alias);
+    }
+
+    /// Forget any data older than `ttl`, so that we never show really old turns
+    /// and don't keep polls for games that have since been deleted
+    pub fn invalidate_older_than(&self, ttl: Duration) {
+        let cutoff = Utc::now() - ttl;
+        self.entries.lock().retain(|alias, slot| {
+            let mut option_cached_poll = slot.lock();
+            if let Some(cached_poll) = option_cached_poll.as_mut() {
+                if cached_poll.polled_at < cutoff {
+                    info!("Removing expired cache entry for {}", alias);
+                    return false;
+                }
+                let is_stale = cached_poll
+                    .option_cache_entry
+                    .as_ref()
+                    .is_some_and(|cache_entry| cache_entry.fetched_at < cutoff);
+                if is_stale {
+                    info!("Removing stale game data for {}", alias);
+                    cached_poll.option_cache_entry = None;
+                }
+            }
+            true
+        });
+    }
+
+    /// The lobby's details if they were built recently enough, otherwise
+    /// `build` them. Always builds if lobbies aren't being cached.
+    pub fn get_or_build_lobby<E, F>(&self, alias: &str, build: F) -> Result<GameDetails, E>
+    where
+        F: FnOnce() -> Result<GameDetails, E>,
+    {
+        if self.lobby_ttl <= Duration::zero() {
+            return build();
+        }
+        let now = Utc::now();
+        if let Some((built_at, details)) = self.lobbies.lock().get(alias) {
+            if now.signed_duration_since(*built_at) < self.lobby_ttl {
+                return Ok(details.clone());
+            }
+        }
+        // Two builds at once would just both be right, so no need to hold the lock
+        let details = build()?;
+        self.lobbies
+            .lock()
+            .insert(alias.to_owned(), (now, details.clone()));
+        Ok(details)
+    }
+
+    /// The cached entry if it's fresh, otherwise `fetch` it. Anybody else after
+    /// the same alias waits for this fetch rather than starting their own.
+    /// Failures aren't cached, so the next caller tries again.
+    pub fn get_or_fetch<E, F>(&self, alias: &str, fetch: F) -> Result<CacheEntry, E>
+    where
+        F: FnOnce() -> Result<CacheEntry, E>,
+    {
+        let slot = self.slot(alias);
+        let mut option_cached_poll = slot.lock();
+        if let Some(cache_entry) = self.fresh_entry(&option_cached_poll) {
+            return Ok(cache_entry);
+        }
+        let cache_entry = fetch()?;
+        *option_cached_poll = Some(CachedPoll::success(cache_entry.clone()));
+        Ok(cache_entry)
+    }
+}
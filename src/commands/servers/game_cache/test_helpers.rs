@@ -0,0 +1,24 @@
+use super::*;
+
+use crate::model::GameData;
+
+impl GameCache {
+    /// With the poller's TTL, and nothing polled yet
+    pub fn test() -> Self {
+        GameCache::new(Duration::minutes(GAME_CACHE_TTL_MINS))
+    }
+
+    /// As if the poller had just read `game_data` from the alias's server
+    pub fn test_polled(alias: &str, game_data: GameData) -> Self {
+        let game_cache = GameCache::test();
+        game_cache.insert(
+            alias,
+            CacheEntry {
+                game_data,
+                option_snek_state: None,
+                fetched_at: Utc::now(),
+            },
+        );
+        game_cache
+    }
+}
@@ -0,0 +1,171 @@
+use super::*;
+
+use crate::commands::servers::{LobbyDetails, NationDetails};
+use crate::model::GameData;
+use chrono::DateTime;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time;
+
+fn cache_entry(turn: i32, fetched_at: DateTime<Utc>) -> CacheEntry {
+    CacheEntry {
+        game_data: GameData {
+            game_name: "foo".to_owned(),
+            nations: vec![],
+            turn,
+            turn_timer: 0,
+        },
+        option_snek_state: None,
+        fetched_at,
+    }
+}
+
+#[test]
+fn should_only_fetch_on_miss() {
+    let game_cache = GameCache::new(Duration::minutes(GAME_CACHE_TTL_MINS));
+    let fetches = AtomicUsize::new(0);
+    let fetch = || -> Result<CacheEntry, String> {
+        fetches.fetch_add(1, Ordering::SeqCst);
+        Ok(cache_entry(3, Utc::now()))
+    };
+
+    assert_eq!(game_cache.get_poll("foo"), None);
+    game_cache.get_or_fetch("foo", fetch).unwrap();
+    let cached = game_cache.get_or_fetch("foo", fetch).unwrap();
+    assert_eq!(cached.game_data.turn, 3);
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    assert!(game_cache.get_poll("foo").is_some());
+    assert_eq!(game_cache.get_poll("bar"), None);
+}
+
+#[test]
+fn should_refetch_expired_entries() {
+    let game_cache = GameCache::new(Duration::minutes(GAME_CACHE_TTL_MINS));
+    game_cache.insert("foo", cache_entry(3, Utc::now() - Duration::hours(1)));
+
+    let refetched = game_cache
+        .get_or_fetch("foo", || Ok::<_, String>(cache_entry(4, Utc::now())))
+        .unwrap();
+    assert_eq!(refetched.game_data.turn, 4);
+}
+
+#[test]
+fn should_not_cache_failures() {
+    let game_cache = GameCache::new(Duration::minutes(GAME_CACHE_TTL_MINS));
+    assert!(game_cache
+        .get_or_fetch("foo", || Err("connection refused".to_owned()))
+        .is_err());
+    assert_eq!(game_cache.get_poll("foo"), None);
+}
+
+#[test]
+fn should_fetch_once_for_concurrent_callers() {
+    let game_cache = Arc::new(GameCache::new(Duration::minutes(GAME_CACHE_TTL_MINS)));
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let game_cache = game_cache.clone();
+            let fetches = fetches.clone();
+            thread::spawn(move || {
+                game_cache
+                    .get_or_fetch("foo", || -> Result<CacheEntry, String> {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        // slow enough that everyone else turns up while we're fetching
+                        thread::sleep(time::Duration::from_millis(100));
+                        Ok(cache_entry(3, Utc::now()))
+                    })
+                    .unwrap()
+            })
+        })
+        .collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap().game_data.turn, 3);
+    }
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn should_only_cache_lobbies_when_given_a_lobby_ttl() {
+    let lobby = |alias: &str| GameDetails {
+        alias: alias.to_owned(),
+        owner: None,
+        description: None,
+        player_nicknames: HashMap::new(),
+        player_vacations: HashMap::new(),
+        nations: NationDetails::Lobby(LobbyDetails {
+            players: vec![],
+            era: None,
+            remaining_slots: Some(3),
+            ai_reserved: vec![],
+            option_open_nations: None,
+            expected: vec![],
+            waitlist: vec![],
+        }),
+        cache_entry: None,
+        warnings: vec![],
+    };
+    let builds = AtomicUsize::new(0);
+    let build = || -> Result<GameDetails, String> {
+        builds.fetch_add(1, Ordering::SeqCst);
+        Ok(lobby("foo"))
+    };
+
+    let game_cache = GameCache::new(Duration::minutes(GAME_CACHE_TTL_MINS));
+    game_cache.get_or_build_lobby("foo", build).unwrap();
+    game_cache.get_or_build_lobby("foo", build).unwrap();
+    assert_eq!(builds.load(Ordering::SeqCst), 2);
+
+    let game_cache = game_cache.with_lobby_ttl(Duration::seconds(30));
+    game_cache.get_or_build_lobby("foo", build).unwrap();
+    game_cache.get_or_build_lobby("foo", build).unwrap();
+    assert_eq!(builds.load(Ordering::SeqCst), 3);
+    game_cache.invalidate("foo");
+    game_cache.get_or_build_lobby("foo", build).unwrap();
+    assert_eq!(builds.load(Ordering::SeqCst), 4);
+
+    game_cache.insert("foo", cache_entry(3, Utc::now()));
+    game_cache.invalidate_lobby("foo");
+    game_cache.get_or_build_lobby("foo", build).unwrap();
+    assert_eq!(builds.load(Ordering::SeqCst), 5);
+    assert!(game_cache.get_poll("foo").is_some());
+}
+
+#[test]
+fn should_keep_the_last_good_entry_when_a_poll_fails() {
+    let game_cache = GameCache::new(Duration::minutes(GAME_CACHE_TTL_MINS));
+    assert_eq!(game_cache.get_poll("foo"), None);
+    game_cache.insert("foo", cache_entry(3, Utc::now()));
+    game_cache.insert_error("foo", "connection refused".to_owned(), Some("timeout"));
+
+    let cached_poll = game_cache.get_poll("foo").unwrap();
+    assert_eq!(
+        cached_poll.option_error.as_deref(),
+        Some("connection refused")
+    );
+    assert_eq!(cached_poll.option_cache_entry.unwrap().game_data.turn, 3);
+    assert_eq!(
+        game_cache.get_latest("foo"),
+        Some(Err("connection refused".to_owned()))
+    );
+    // still fresh, so commands can keep using it without a fetch
+    let cached = game_cache
+        .get_or_fetch("foo", || Err("connection refused".to_owned()))
+        .unwrap();
+    assert_eq!(cached.game_data.turn, 3);
+}
+
+#[test]
+fn should_forget_polls_older_than_the_ttl() {
+    let game_cache = GameCache::new(Duration::minutes(GAME_CACHE_TTL_MINS));
+    game_cache.insert("expired", cache_entry(3, Utc::now() - Duration::hours(2)));
+    game_cache.insert("stale", cache_entry(4, Utc::now() - Duration::hours(2)));
+    game_cache.insert_error("stale", "connection refused".to_owned(), None);
+    game_cache.insert("recent", cache_entry(5, Utc::now()));
+
+    game_cache.invalidate_older_than(Duration::hours(1));
+    assert_eq!(game_cache.get_poll("expired"), None);
+    let stale_poll = game_cache.get_poll("stale").unwrap();
+    assert_eq!(stale_poll.option_cache_entry, None);
+    assert!(stale_poll.option_error.is_some());
+    assert!(game_cache.get_poll("recent").is_some());
+}
@@ -0,0 +1,214 @@
+use log::*;
+use serenity::framework::standard::CommandError;
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use crate::commands::servers::*;
+use crate::db::*;
+use crate::model::enums::*;
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+/// One line of `!games`, with how long is left so that we can put the most
+/// urgent games first
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct GameLine {
+    pub option_mins_remaining: Option<i32>,
+    pub text: String,
+}
+
+/// Soonest deadline first, then anything without a deadline (lobbies, uploading, errors)
+pub fn sort_game_lines(game_lines: &mut [GameLine]) {
+    game_lines.sort_by_key(|game_line| {
+        (
+            game_line.option_mins_remaining.is_none(),
+            game_line.option_mins_remaining,
+        )
+    });
+}
+
+fn game_line_for_started_details(
+    alias: &str,
+    started_details: &StartedDetails,
+    user_id: UserId,
+    nation_id: u32,
+) -> GameLine {
+    match &started_details.state {
+        StartedStateDetails::Playing(playing_state) => {
+            let option_details =
+                playing_state
+                    .players
+                    .iter()
+                    .find_map(|potential_player| match potential_player {
+                        PotentialPlayer::RegisteredAndGame(player_user_id, details)
+                            if *player_user_id == user_id && details.nation_id == nation_id =>
+                        {
+                            Some(details)
+                        }
+                        _ => None,
+                    });
+            match option_details {
+                Some(details) => GameLine {
+                    // Nothing's urgent about a paused game
+                    option_mins_remaining: if playing_state.paused {
+                        None
+                    } else {
+                        Some(playing_state.hours_remaining * 60 + playing_state.mins_remaining)
+                    },
+                    text: format!(
+                        "{} turn {} ({}): {} ({}) (submitted: {})",
+                        alias,
+                        playing_state.turn,
+                        playing_state.show_time_remaining(),
+                        details.nation_name,
+                        details.nation_id,
+                        details.submitted.show(),
+                    ),
+                },
+                None => GameLine {
+                    option_mins_remaining: None,
+                    text: format!(
+                        "{} turn {}: you registered as nation {} but it isn't in the game",
+                        alias, playing_state.turn, nation_id,
+                    ),
+                },
+            }
+        }
+        StartedStateDetails::Uploading(uploading_state) => {
+            let option_uploading_player =
+                uploading_state
+                    .uploading_players
+                    .iter()
+                    .find(|uploading_player| {
+                        uploading_player.option_player_id() == Some(&user_id)
+                            && uploading_player.nation_id() == nation_id
+                    });
+            let (nation_name, uploaded) = match option_uploading_player {
+                Some(uploading_player) => (
+                    uploading_player.nation_name().clone(),
+                    uploading_player.uploaded,
+                ),
+                None => (Nations::get_nation_name(nation_id), false),
+            };
+            let submission_status = if uploaded {
+                SubmissionStatus::Submitted
+            } else {
+                SubmissionStatus::NotSubmitted
+            };
+            GameLine {
+                option_mins_remaining: None,
+                text: format!(
+                    "{} uploading: {} ({}) (uploaded: {})",
+                    alias,
+                    nation_name,
+                    nation_id,
+                    submission_status.show(),
+                ),
+            }
+        }
+    }
+}
+
+fn games_helper(
+    user_id: UserId,
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+) -> Result<String, CommandError> {
+    let servers_and_nations_for_player = db_conn.servers_for_player(user_id)?;
+    if servers_and_nations_for_player.is_empty() {
+        return Ok("You are not registered in any games".to_owned());
+    }
+
+    let mut game_lines = vec![];
+    for (server, nation_id) in servers_and_nations_for_player {
+        let mut game_line = match server.state {
+            GameServerState::Lobby(_) => GameLine {
+                option_mins_remaining: None,
+                text: format!(
+                    "{} lobby: {} ({})",
+                    server.alias,
+                    Nations::get_nation_name(nation_id),
+                    nation_id,
+                ),
+            },
+            GameServerState::Archived(_, _) => GameLine {
+                option_mins_remaining: None,
+                text: format!(
+                    "{} finished: {} ({})",
+                    server.alias,
+                    Nations::get_nation_name(nation_id),
+                    nation_id,
+                ),
+            },
+            GameServerState::StartedState(started_state, option_lobby_state) => {
+                match game_cache.get_latest(&server.alias) {
+                    Some(Ok(cache)) => {
+                        let details: GameDetails = started_details_from_server(
+                            db_conn,
+                            &started_state,
+                            option_lobby_state.as_ref(),
+                            &server.alias,
+                            cache.game_data,
+                            cache.option_snek_state,
+                            cache.fetched_at,
+                        )?;
+                        match details.nations {
+                            NationDetails::Started(started_details) => {
+                                game_line_for_started_details(
+                                    &server.alias,
+                                    &started_details,
+                                    user_id,
+                                    nation_id,
+                                )
+                            }
+                            NationDetails::Lobby(_) => continue,
+                        }
+                    }
+                    Some(Err(e)) => GameLine {
+                        option_mins_remaining: None,
+                        text: format!("{}: {}", server.alias, e),
+                    },
+                    None => GameLine {
+                        option_mins_remaining: None,
+                        text: format!(
+                            "{}: Server starting up, please try again in 1 min.",
+                            server.alias
+                        ),
+                    },
+                }
+            }
+        };
+        game_line.text = format!(
+            "{}{}",
+            db_conn.icon_for_alias(&server.alias)?.prefix(),
+            game_line.text
+        );
+        game_lines.push(game_line);
+    }
+    sort_game_lines(&mut game_lines);
+
+    let mut text = "Your games:\n".to_owned();
+    for game_line in game_lines {
+        text.push_str(&game_line.text);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+pub fn games(context: &mut Context, message: &Message) -> Result<(), CommandError> {
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = games_helper(message.author.id, db_conn, game_cache)?;
+    info!("games: replying with: {}", text);
+    let private_channel = message.author.id.create_dm_channel()?;
+    private_channel.say(&text)?;
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use super::*;
+
+#[test]
+fn should_sort_soonest_deadline_first() {
+    let game_line = |option_mins_remaining, text: &str| GameLine {
+        option_mins_remaining,
+        text: text.to_owned(),
+    };
+    let mut game_lines = vec![
+        game_line(None, "lobby"),
+        game_line(Some(600), "later"),
+        game_line(Some(5), "soon"),
+        game_line(None, "uploading"),
+    ];
+
+    sort_game_lines(&mut game_lines);
+    let texts: Vec<&str> = game_lines.iter().map(|line| line.text.as_str()).collect();
+    assert_eq!(texts, vec!["soon", "later", "lobby", "uploading"]);
+}
@@ -0,0 +1,162 @@
+use failure::Error;
+use serenity::client::Context;
+use serenity::framework::standard::StandardFramework;
+use serenity::framework::Framework;
+use serenity::model::channel::Message;
+use serenity::model::id::{GuildId, UserId};
+use serenity::prelude::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use threadpool::ThreadPool;
+use typemap::Key;
+
+use crate::db::DbConnection;
+
+/// For DMs, and guilds that haven't set their own with `!prefix`
+pub const DEFAULT_PREFIX: &str = "!";
+
+#[derive(Default)]
+struct GuildCommandSettings {
+    option_prefix: Option<String>,
+    /// alias -> the built-in command it stands for
+    aliases: HashMap<String, String>,
+}
+
+/// Each guild's command prefix and aliases. Every message needs them, so they're
+/// kept here and the db only gets written to when they change.
+#[derive(Default)]
+pub struct GuildCommands(RwLock<HashMap<GuildId, GuildCommandSettings>>);
+
+pub struct GuildCommandsKey;
+impl Key for GuildCommandsKey {
+    type Value = Arc<GuildCommands>;
+}
+
+impl GuildCommands {
+    pub fn load(db_conn: &DbConnection) -> Result<Self, Error> {
+        let mut settings: HashMap<GuildId, GuildCommandSettings> = HashMap::new();
+        for (guild_id, prefix) in db_conn.guild_prefixes()? {
+            settings.entry(guild_id).or_default().option_prefix = Some(prefix);
+        }
+        for (guild_id, alias, command) in db_conn.command_aliases()? {
+            settings
+                .entry(guild_id)
+                .or_default()
+                .aliases
+                .insert(alias, command);
+        }
+        Ok(GuildCommands(RwLock::new(settings)))
+    }
+
+    /// `None` means the default
+    pub fn option_prefix(&self, option_guild_id: Option<GuildId>) -> Option<String> {
+        let guild_id = option_guild_id?;
+        self.0
+            .read()
+            .get(&guild_id)
+            .and_then(|settings| settings.option_prefix.clone())
+    }
+
+    pub fn prefix(&self, option_guild_id: Option<GuildId>) -> String {
+        self.option_prefix(option_guild_id)
+            .unwrap_or_else(|| DEFAULT_PREFIX.to_owned())
+    }
+
+    /// (alias, command), sorted by alias
+    pub fn aliases(&self, option_guild_id: Option<GuildId>) -> Vec<(String, String)> {
+        let mut aliases: Vec<(String, String)> = option_guild_id
+            .and_then(|guild_id| {
+                self.0.read().get(&guild_id).map(|settings| {
+                    settings
+                        .aliases
+                        .iter()
+                        .map(|(alias, command)| (alias.clone(), command.clone()))
+                        .collect()
+                })
+            })
+            .unwrap_or_default();
+        aliases.sort();
+        aliases
+    }
+
+    pub fn set_prefix(
+        &self,
+        db_conn: &DbConnection,
+        guild_id: GuildId,
+        option_prefix: Option<&str>,
+    ) -> Result<(), Error> {
+        db_conn.set_prefix_for_guild(guild_id, option_prefix)?;
+        self.0.write().entry(guild_id).or_default().option_prefix =
+            option_prefix.map(|prefix| prefix.to_owned());
+        Ok(())
+    }
+
+    pub fn set_alias(
+        &self,
+        db_conn: &DbConnection,
+        guild_id: GuildId,
+        alias: &str,
+        command: &str,
+    ) -> Result<(), Error> {
+        db_conn.set_command_alias(guild_id, alias, command)?;
+        self.0
+            .write()
+            .entry(guild_id)
+            .or_default()
+            .aliases
+            .insert(alias.to_owned(), command.to_owned());
+        Ok(())
+    }
+
+    /// False if there was no such alias
+    pub fn remove_alias(
+        &self,
+        db_conn: &DbConnection,
+        guild_id: GuildId,
+        alias: &str,
+    ) -> Result<bool, Error> {
+        let removed = db_conn.remove_command_alias(guild_id, alias)?;
+        if let Some(settings) = self.0.write().get_mut(&guild_id) {
+            settings.aliases.remove(alias);
+        }
+        Ok(removed)
+    }
+
+    /// The message with an alias for a command swapped for the command itself,
+    /// or `None` if it doesn't start with one
+    pub fn expand_alias(&self, option_guild_id: Option<GuildId>, content: &str) -> Option<String> {
+        let guild_id = option_guild_id?;
+        let prefix = self.prefix(option_guild_id);
+        let without_prefix = content.strip_prefix(prefix.as_str())?;
+        let (name, rest) = match without_prefix.find(char::is_whitespace) {
+            Some(index) => without_prefix.split_at(index),
+            None => (without_prefix, ""),
+        };
+        let settings = self.0.read();
+        let command = settings.get(&guild_id)?.aliases.get(name)?;
+        Some(format!("{}{}{}", prefix, command, rest))
+    }
+}
+
+/// Rewrites aliased commands before the standard framework sees them. The
+/// prefix itself is handled by the standard framework's `dynamic_prefix`.
+pub struct GuildCommandsFramework {
+    pub framework: StandardFramework,
+    pub guild_commands: Arc<GuildCommands>,
+}
+
+impl Framework for GuildCommandsFramework {
+    fn dispatch(&mut self, context: Context, mut message: Message, threadpool: &ThreadPool) {
+        if let Some(content) = self
+            .guild_commands
+            .expand_alias(message.guild_id, &message.content)
+        {
+            message.content = content;
+        }
+        self.framework.dispatch(context, message, threadpool);
+    }
+
+    fn update_current_user(&mut self, user_id: UserId) {
+        self.framework.update_current_user(user_id);
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Duration, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::turn_note::show_turn_note;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+const DEFAULT_HISTORY_TURNS: u32 = 10;
+const MAX_HISTORY_TURNS: u32 = 50;
+const BAR_WIDTH: usize = 20;
+
+/// When each turn started and ended, from the times we saw each turn start.
+/// Turns we didn't see the start or end of (e.g. the bot was down over a host)
+/// are skipped rather than guessed at.
+pub fn turn_intervals(
+    history: &[(i32, DateTime<Utc>)],
+) -> Vec<(i32, DateTime<Utc>, DateTime<Utc>)> {
+    history
+        .windows(2)
+        .filter_map(|pair| {
+            let (turn, started) = pair[0];
+            let (next_turn, next_started) = pair[1];
+            if next_turn == turn + 1 {
+                Some((turn, started, next_started))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// How long each turn lasted, see `turn_intervals`
+pub fn turn_durations(history: &[(i32, DateTime<Utc>)]) -> Vec<(i32, Duration)> {
+    turn_intervals(history)
+        .into_iter()
+        .map(|(turn, started, ended)| (turn, ended.signed_duration_since(started)))
+        .collect()
+}
+
+pub fn hours(duration: Duration) -> f64 {
+    duration.num_seconds() as f64 / 3600.0
+}
+
+fn bar(value: f64, max_value: f64) -> String {
+    // eighths of a block so that short turns still look different from each other
+    const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    let eighths = if max_value > 0.0 {
+        (value / max_value * (BAR_WIDTH * 8) as f64).round() as usize
+    } else {
+        0
+    };
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 != 0 {
+        bar.push(PARTIAL_BLOCKS[eighths % 8]);
+    }
+    bar
+}
+
+fn history_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    num_turns: u32,
+) -> Result<String, CommandError> {
+    // One extra so that the oldest turn shown still has a start time
+    let history = db_conn.select_turn_history(alias, num_turns + 1)?;
+    let durations = turn_durations(&history);
+    if durations.is_empty() {
+        return Ok(format!(
+            "No turn history for {} yet, check back after a couple of turns have hosted",
+            alias
+        ));
+    }
+
+    let quickhosted_turns = db_conn.select_quickhosted_turns(alias)?;
+
+    let max_hours = durations
+        .iter()
+        .map(|&(_, duration)| hours(duration))
+        .fold(0.0, f64::max);
+    let mut text = format!("Hours per turn for {}:\n```\n", alias);
+    for &(turn, duration) in &durations {
+        text.push_str(&format!(
+            "{:>4} {:<width$} {:.1}h{}\n",
+            turn,
+            bar(hours(duration), max_hours),
+            hours(duration),
+            if quickhosted_turns.contains(&turn) {
+                " (hosted early)"
+            } else {
+                ""
+            },
+            width = BAR_WIDTH + 1,
+        ));
+    }
+    if let Some(&(current_turn, started)) = history.last() {
+        text.push_str(&format!(
+            "{:>4} (in progress, {:.1}h so far)\n",
+            current_turn,
+            hours(Utc::now().signed_duration_since(started)),
+        ));
+    }
+    text.push_str("```");
+    // Only the ones for turns on the chart, !turnnotes has the rest
+    let oldest_shown = durations[0].0;
+    for turn_note in db_conn
+        .turn_notes_for_alias(alias)?
+        .iter()
+        .filter(|turn_note| turn_note.turn >= oldest_shown)
+    {
+        text.push('\n');
+        text.push_str(&show_turn_note(turn_note));
+    }
+    Ok(text)
+}
+
+pub fn history(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let num_turns = match args.single_quoted_n::<u32>() {
+        Ok(num_turns) => {
+            args.skip();
+            num_turns
+        }
+        Err(_) => DEFAULT_HISTORY_TURNS,
+    };
+    if num_turns == 0 || num_turns > MAX_HISTORY_TURNS {
+        return Err(CommandError::from(format!(
+            "Number of turns must be between 1 and {}",
+            MAX_HISTORY_TURNS
+        )));
+    }
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = history_helper(db_conn, &alias, num_turns)?;
+    info!("history: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
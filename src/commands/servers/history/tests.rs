@@ -0,0 +1,75 @@
+use super::*;
+
+use chrono::TimeZone;
+use serenity::model::id::UserId;
+
+#[test]
+fn should_record_each_new_turn_once() {
+    let db_conn = DbConnection::test();
+    // "test server 1" was inserted on turn 31
+    assert!(db_conn
+        .update_game_with_possibly_new_turn("test server 1", 32, None)
+        .unwrap());
+    // e.g. a timer extension - same turn seen again
+    assert!(!db_conn
+        .update_game_with_possibly_new_turn("test server 1", 32, None)
+        .unwrap());
+    assert!(db_conn
+        .update_game_with_possibly_new_turn("test server 1", 33, None)
+        .unwrap());
+
+    let history = db_conn.select_turn_history("test server 1", 10).unwrap();
+    let turns: Vec<i32> = history.iter().map(|&(turn, _)| turn).collect();
+    assert_eq!(turns, vec![32, 33]);
+}
+
+#[test]
+fn should_not_record_turns_from_other_games() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .update_game_with_possibly_new_turn("test server 1", 32, None)
+        .unwrap();
+
+    assert!(db_conn
+        .select_turn_history("test server 2", 10)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_skip_turns_with_missing_start() {
+    let history = vec![
+        (1, Utc.timestamp(0, 0)),
+        (2, Utc.timestamp(3600, 0)),
+        // turn 3 was never seen
+        (4, Utc.timestamp(4 * 3600, 0)),
+        (5, Utc.timestamp(10 * 3600, 0)),
+    ];
+
+    let durations = turn_durations(&history);
+    assert_eq!(
+        durations,
+        vec![(1, Duration::hours(1)), (4, Duration::hours(6))]
+    );
+}
+
+#[test]
+fn should_list_notes_for_the_turns_shown() {
+    let db_conn = DbConnection::test();
+    for turn in 32..=34 {
+        db_conn
+            .update_game_with_possibly_new_turn("test server 1", turn, None)
+            .unwrap();
+    }
+    let noted_at = Utc.ymd(2020, 10, 14).and_hms(12, 0, 0);
+    db_conn
+        .add_turn_note("test server 1", 31, UserId(1), "before the chart", noted_at)
+        .unwrap();
+    db_conn
+        .add_turn_note("test server 1", 33, UserId(1), "server crashed", noted_at)
+        .unwrap();
+
+    let text = history_helper(&db_conn, "test server 1", 10).unwrap();
+    assert!(text.ends_with("```\nTurn 33 (14 Oct 12:00 UTC): server crashed"));
+    assert!(!text.contains("before the chart"));
+}
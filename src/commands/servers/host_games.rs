@@ -0,0 +1,71 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+use crate::server::split_server_address;
+
+#[cfg(test)]
+mod tests;
+
+/// Every game still being checked on the same machine as `arg_host`, by port.
+/// `arg_host` can have a port too, it's ignored.
+fn host_games_helper(db_conn: &DbConnection, arg_host: &str) -> Result<String, CommandError> {
+    let (host, _) = split_server_address(arg_host)?;
+    let mut games = vec![];
+    for server in db_conn.retrieve_all_servers()? {
+        if let GameServerState::StartedState(started_state, _) = server.state {
+            // An address that no longer parses can't be on this host either
+            if let Ok((game_host, port)) = split_server_address(&started_state.address) {
+                if game_host == host {
+                    games.push((port, server.alias, started_state.last_seen_turn));
+                }
+            }
+        }
+    }
+    if games.is_empty() {
+        return Err(CommandError::from(format!(
+            "No games are being checked on {}",
+            host
+        )));
+    }
+    games.sort();
+    let mut text = format!("Games on {}:\n", host);
+    for (port, alias, last_seen_turn) in games {
+        if last_seen_turn > 0 {
+            text.push_str(&format!(
+                "{} (port {}): turn {}\n",
+                alias, port, last_seen_turn
+            ));
+        } else {
+            text.push_str(&format!("{} (port {}): not started\n", alias, port));
+        }
+    }
+    Ok(text)
+}
+
+pub fn host_games(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_host = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected a hostname like dom5.example.com"))?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = host_games_helper(db_conn, &arg_host)?;
+    info!("hostgames: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
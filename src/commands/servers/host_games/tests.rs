@@ -0,0 +1,37 @@
+use super::*;
+
+use crate::model::{GameServer, StartedState};
+
+#[test]
+fn should_list_games_on_the_host_by_port() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "elsewhere".to_owned(),
+            state: GameServerState::StartedState(
+                StartedState {
+                    address: "other.server.address:1".to_owned(),
+                    last_seen_turn: 3,
+                    option_snek_game_id: None,
+                },
+                None,
+            ),
+        })
+        .unwrap();
+
+    // The port given is ignored, and case doesn't matter for hostnames
+    let text = host_games_helper(&db_conn, "Test.Server.Address:5").unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 10);
+    assert_eq!(lines[0], "Games on test.server.address:");
+    assert_eq!(lines[1], "test server 1 (port 1): turn 31");
+    assert_eq!(lines[9], "test server 9 (port 9): turn 39");
+    assert!(!text.contains("elsewhere"));
+}
+
+#[test]
+fn should_say_when_nothing_is_on_the_host() {
+    let db_conn = DbConnection::test();
+    let err = host_games_helper(&db_conn, "nobody.home").unwrap_err();
+    assert!(format!("{:?}", err).contains("No games are being checked on nobody.home"));
+}
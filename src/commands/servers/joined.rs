@@ -0,0 +1,76 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey, Registration};
+use crate::model::enums::Nations;
+
+#[cfg(test)]
+mod tests;
+
+/// `registrations` are earliest first with the unknown ones at the end, as
+/// `registrations_for_alias` gives them
+fn show_registrations(alias: &str, registrations: &[Registration]) -> String {
+    if registrations.is_empty() {
+        return format!("Nobody has registered for {} yet", alias);
+    }
+
+    let mut text = format!("Registrations for {}, earliest first:\n", alias);
+    for (nation_id, option_registered_at) in registrations {
+        let when = match option_registered_at {
+            Some(registered_at) => registered_at.format("%d %b %Y").to_string(),
+            None => "before registration dates were kept".to_owned(),
+        };
+        text.push_str(&format!(
+            "- {}: {}\n",
+            Nations::get_nation_name(*nation_id),
+            when
+        ));
+    }
+
+    let known: Vec<u32> = registrations
+        .iter()
+        .filter(|(_, option_registered_at)| option_registered_at.is_some())
+        .map(|(nation_id, _)| *nation_id)
+        .collect();
+    // With just the one there's nobody to compare them to
+    if known.len() >= 2 {
+        text.push_str(&format!(
+            "Longest serving: {}, newest: {}",
+            Nations::get_nation_name(known[0]),
+            Nations::get_nation_name(known[known.len() - 1])
+        ));
+    }
+    text
+}
+
+fn joined_helper(db_conn: &DbConnection, alias: &str) -> Result<String, CommandError> {
+    // So that a typo doesn't look like an empty game
+    db_conn.game_for_alias(alias)?;
+    let registrations = db_conn.registrations_for_alias(alias)?;
+    Ok(show_registrations(alias, &registrations))
+}
+
+pub fn joined(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = joined_helper(db_conn, &alias)?;
+    info!("joined: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
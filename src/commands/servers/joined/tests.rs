@@ -0,0 +1,44 @@
+use super::*;
+
+use chrono::{Duration, TimeZone, Utc};
+
+#[test]
+fn should_show_earliest_and_newest_with_unknown_dates_last() {
+    let registrations = vec![
+        (5, Some(Utc.ymd(2020, 10, 1).and_hms(12, 0, 0))),
+        (6, Some(Utc.ymd(2020, 10, 3).and_hms(12, 0, 0))),
+        (7, None),
+    ];
+    let text = show_registrations("foo", &registrations);
+    assert_eq!(
+        text,
+        format!(
+            "Registrations for foo, earliest first:\n- {}: 01 Oct 2020\n- {}: 03 Oct 2020\n- {}: before registration dates were kept\nLongest serving: {}, newest: {}",
+            Nations::get_nation_name(5),
+            Nations::get_nation_name(6),
+            Nations::get_nation_name(7),
+            Nations::get_nation_name(5),
+            Nations::get_nation_name(6),
+        )
+    );
+
+    // Nobody to compare to
+    assert!(!show_registrations("foo", &registrations[2..]).contains("Longest serving"));
+    assert_eq!(
+        show_registrations("foo", &[]),
+        "Nobody has registered for foo yet"
+    );
+}
+
+#[test]
+fn should_remember_when_players_registered() {
+    let db_conn = DbConnection::test();
+    // UserId(101) registered in "test server 1" as nation 1 when the db was made
+    let registrations = db_conn.registrations_for_alias("test server 1").unwrap();
+    assert_eq!(registrations.len(), 1);
+    let (nation_id, option_registered_at) = registrations[0];
+    assert_eq!(nation_id, 1);
+    assert!(option_registered_at.unwrap() > Utc::now() - Duration::minutes(1));
+
+    assert!(joined_helper(&db_conn, "not a game").is_err());
+}
@@ -0,0 +1,34 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::details2::{cached_game_details, send_json};
+use super::GameCacheKey;
+use crate::db::DbConnectionKey;
+
+#[cfg(test)]
+mod tests;
+
+pub fn json(context: &mut Context, message: &Message, mut args: Args) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+
+    let (details, _) = cached_game_details(&alias, db_conn, game_cache)?;
+    let json = serde_json::to_string(&details)?;
+    info!("json: replying with: {}", json);
+    send_json(message.channel_id, &alias, &json)
+}
@@ -0,0 +1,111 @@
+use crate::commands::servers::details::*;
+use crate::model::enums::{Era, NationStatus, SubmissionStatus};
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+use serenity::model::id::UserId;
+
+#[test]
+fn should_serialize_lobby_with_string_ids() {
+    let details = GameDetails {
+        alias: "lobby".to_owned(),
+        owner: Some(UserId(123_456_789_012_345_678)),
+        description: None,
+        nations: NationDetails::Lobby(LobbyDetails {
+            players: vec![LobbyPlayer {
+                player_id: UserId(101),
+                nation_id: 7,
+                nation_name: "Ulm".to_owned(),
+            }],
+            era: Some(Era::Early),
+            remaining_slots: Some(3),
+            ai_reserved: vec![AiReservedNation {
+                nation_id: 5,
+                nation_name: "Arcoscephale".to_owned(),
+                option_player_id: None,
+            }],
+            option_open_nations: None,
+            expected: vec![UserId(102)],
+            waitlist: vec![UserId(103)],
+        }),
+        player_nicknames: vec![(UserId(101), "Bob".to_owned())].into_iter().collect(),
+        player_vacations: vec![(UserId(101), Utc.timestamp(1_600_000_000, 0))]
+            .into_iter()
+            .collect(),
+        cache_entry: None,
+        warnings: vec![],
+    };
+
+    assert_eq!(
+        serde_json::to_value(&details).unwrap(),
+        json!({
+            "alias": "lobby",
+            "owner": "123456789012345678",
+            "description": null,
+            "nations": {
+                "Lobby": {
+                    "players": [{"player_id": "101", "nation_id": 7, "nation_name": "Ulm"}],
+                    "era": "EA",
+                    "remaining_slots": 3,
+                    "ai_reserved": [{
+                        "nation_id": 5,
+                        "nation_name": "Arcoscephale",
+                        "option_player_id": null,
+                    }],
+                    "option_open_nations": null,
+                    "expected": ["102"],
+                    "waitlist": ["103"],
+                }
+            },
+            "player_nicknames": {"101": "Bob"},
+            "player_vacations": {"101": "2020-09-13T12:26:40+00:00"},
+            "warnings": [],
+        })
+    );
+}
+
+#[test]
+fn should_serialize_statuses_as_names() {
+    let playing_state = PlayingState {
+        players: vec![PotentialPlayer::RegisteredAndGame(
+            UserId(101),
+            PlayerDetails {
+                nation_id: 7,
+                nation_name: "Ulm".to_owned(),
+                submitted: SubmissionStatus::PartiallySubmitted,
+                player_status: NationStatus::DefeatedThisTurn,
+                went_ai: false,
+                connected: false,
+            },
+        )],
+        turn: 4,
+        mins_remaining: 30,
+        hours_remaining: 0,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: Some(Utc.timestamp(0, 0)),
+        paused: false,
+        blitz: false,
+    };
+
+    assert_eq!(
+        serde_json::to_value(&playing_state).unwrap(),
+        json!({
+            "players": [{"RegisteredAndGame": ["101", {
+                "nation_id": 7,
+                "nation_name": "Ulm",
+                "submitted": "partially_submitted",
+                "player_status": "defeated_this_turn",
+                "went_ai": false,
+                "connected": false,
+            }]}],
+            "turn": 4,
+            "mins_remaining": 30,
+            "hours_remaining": 0,
+            "days_remaining": 0,
+            "secs_remaining": 0,
+            "option_deadline": "1970-01-01T00:00:00+00:00",
+            "paused": false,
+            "blitz": false,
+        })
+    );
+}
@@ -0,0 +1,61 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::roster::{remove_from_lobby, RemovedFromLobby};
+use super::{alias_from_arg_or_channel_name, lobby_state_for_owner};
+use crate::commands::servers::{show_open_slots, GameCacheKey};
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+fn kick_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    target_id: UserId,
+    alias: &str,
+) -> Result<RemovedFromLobby, CommandError> {
+    let lobby_state = lobby_state_for_owner(db_conn, alias, author_id)?;
+
+    remove_from_lobby(db_conn, &lobby_state, alias, target_id)?
+        .ok_or_else(|| CommandError::from(format!("That user is not registered in {}", alias)))
+}
+
+pub fn kick(context: &mut Context, message: &Message, mut args: Args) -> Result<(), CommandError> {
+    let target_id = args
+        .single_quoted::<UserId>()
+        .map_err(|_| CommandError::from("Expected an @mention of the player to remove"))?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let removed = kick_helper(db_conn, message.author.id, target_id, &alias)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+
+    let mut text = format!(
+        "Removed {} from {}, {}",
+        target_id.to_user()?,
+        alias,
+        show_open_slots(removed.remaining_slots)
+    );
+    for promotion in removed.promotions {
+        text.push('\n');
+        text.push_str(&promotion);
+    }
+    info!("{}", text);
+    message.reply(&text)?;
+    Ok(())
+}
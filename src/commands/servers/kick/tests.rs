@@ -0,0 +1,41 @@
+use super::*;
+
+use crate::model::enums::Era;
+
+const OWNER: UserId = UserId(1);
+
+#[test]
+fn should_free_up_a_slot_when_owner_kicks() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 5, &[(201, 211), (202, 212)]);
+
+    let RemovedFromLobby {
+        remaining_slots,
+        promotions,
+    } = kick_helper(&db_conn, OWNER, UserId(201), "lobby").unwrap();
+    assert_eq!(remaining_slots, Some(4));
+    assert!(promotions.is_empty());
+}
+
+#[test]
+fn should_only_let_the_owner_kick() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 5, &[(201, 211), (202, 212)]);
+
+    assert!(kick_helper(&db_conn, UserId(202), UserId(201), "lobby").is_err());
+    assert_eq!(
+        db_conn
+            .players_with_nations_for_game_alias("lobby")
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn should_error_if_player_not_registered() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 5, &[(201, 211)]);
+
+    assert!(kick_helper(&db_conn, OWNER, UserId(203), "lobby").is_err());
+}
@@ -0,0 +1,110 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::model::misc::Mentionable;
+use serenity::prelude::Context;
+use std::collections::HashMap;
+
+use super::alias_from_arg_or_channel_name;
+use super::stragglers::{late_streaks, STRAGGLER_HISTORY_TURNS};
+use super::time_format::time_format_for_message;
+use super::undone::{playing_state_for_alias, undone_players};
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+const DEFAULT_MIN_LATE_TURNS: usize = 2;
+
+/// Who still has to play this turn and was also one of the last for at least
+/// `min_late_turns` turns in a row before it, most turns first
+pub fn repeat_offenders<'a>(
+    playing_state: &'a PlayingState,
+    streaks: &HashMap<u32, usize>,
+    min_late_turns: usize,
+) -> Vec<(UserId, &'a PlayerDetails, usize)> {
+    let mut offenders: Vec<(UserId, &PlayerDetails, usize)> = undone_players(playing_state)
+        .into_iter()
+        .filter_map(|(user_id, details)| {
+            let late_turns = streaks.get(&details.nation_id).cloned().unwrap_or(0);
+            if late_turns >= min_late_turns {
+                Some((user_id, details, late_turns))
+            } else {
+                None
+            }
+        })
+        .collect();
+    offenders.sort_by(|a, b| b.2.cmp(&a.2).then(a.1.nation_id.cmp(&b.1.nation_id)));
+    offenders
+}
+
+fn late_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+    min_late_turns: usize,
+    time_format: TimeFormat,
+) -> Result<String, CommandError> {
+    let playing_state = playing_state_for_alias(db_conn, game_cache, alias)?;
+    let streaks = late_streaks(&db_conn.select_submission_history(alias)?);
+    let offenders = repeat_offenders(&playing_state, &streaks, min_late_turns);
+    if offenders.is_empty() {
+        return Ok(format!(
+            "Nobody still to play turn {} in {} was late for the {} turns before it",
+            playing_state.turn, alias, min_late_turns
+        ));
+    }
+    let mut text = format!(
+        "Still to play turn {} in {} after being late {} or more turns in a row ({}):\n",
+        playing_state.turn,
+        alias,
+        min_late_turns,
+        playing_state.show_time_left_as(time_format),
+    );
+    for (user_id, details, late_turns) in offenders {
+        text.push_str(&format!(
+            "{} ({}, late {} turns running)\n",
+            user_id.mention(),
+            details.nation_name,
+            late_turns
+        ));
+    }
+    Ok(text)
+}
+
+pub fn late(context: &mut Context, message: &Message, mut args: Args) -> Result<(), CommandError> {
+    let min_late_turns = match args.single_quoted_n::<usize>() {
+        Ok(min_late_turns) => {
+            args.skip();
+            min_late_turns
+        }
+        Err(_) => DEFAULT_MIN_LATE_TURNS,
+    };
+    if min_late_turns == 0 || min_late_turns > STRAGGLER_HISTORY_TURNS as usize {
+        return Err(CommandError::from(format!(
+            "Number of turns must be between 1 and {}",
+            STRAGGLER_HISTORY_TURNS
+        )));
+    }
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let time_format = time_format_for_message(db_conn, message)?;
+    let text = late_helper(db_conn, game_cache, &alias, min_late_turns, time_format)?;
+    info!("late: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use super::*;
+
+use crate::model::enums::{NationStatus, SubmissionStatus};
+
+fn player_details(nation_id: u32, submitted: SubmissionStatus) -> PlayerDetails {
+    PlayerDetails {
+        nation_id,
+        nation_name: format!("nation {}", nation_id),
+        submitted,
+        player_status: NationStatus::Human,
+        went_ai: false,
+        connected: false,
+    }
+}
+
+#[test]
+fn should_only_ping_undone_registered_players_late_enough_turns() {
+    let playing_state = PlayingState {
+        players: vec![
+            PotentialPlayer::RegisteredAndGame(
+                UserId(1),
+                player_details(1, SubmissionStatus::NotSubmitted),
+            ),
+            // late, but has played this turn
+            PotentialPlayer::RegisteredAndGame(
+                UserId(2),
+                player_details(2, SubmissionStatus::Submitted),
+            ),
+            // not late often enough
+            PotentialPlayer::RegisteredAndGame(
+                UserId(3),
+                player_details(3, SubmissionStatus::NotSubmitted),
+            ),
+            // nobody to ping
+            PotentialPlayer::GameOnly(player_details(4, SubmissionStatus::NotSubmitted)),
+            PotentialPlayer::RegisteredAndGame(
+                UserId(5),
+                player_details(5, SubmissionStatus::NotSubmitted),
+            ),
+        ],
+        turn: 10,
+        mins_remaining: 0,
+        hours_remaining: 1,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: None,
+        paused: false,
+        blitz: false,
+    };
+    let streaks: HashMap<u32, usize> = vec![(1, 2), (2, 4), (3, 1), (4, 5), (5, 3)]
+        .into_iter()
+        .collect();
+
+    let offenders: Vec<(UserId, usize)> = repeat_offenders(&playing_state, &streaks, 2)
+        .into_iter()
+        .map(|(user_id, _, late_turns)| (user_id, late_turns))
+        .collect();
+    assert_eq!(offenders, vec![(UserId(5), 3), (UserId(1), 2)]);
+}
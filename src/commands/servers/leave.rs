@@ -0,0 +1,68 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::roster::{remove_from_lobby, RemovedFromLobby};
+use crate::commands::servers::{show_open_slots, GameCacheKey};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+fn leave_helper(
+    db_conn: &DbConnection,
+    user_id: UserId,
+    alias: &str,
+) -> Result<RemovedFromLobby, CommandError> {
+    let lobby_state = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(lobby_state) => lobby_state,
+        GameServerState::StartedState(_, _) => {
+            return Err(CommandError::from(format!(
+                "{} has already started, ask the host to sort out your nation",
+                alias
+            )))
+        }
+        GameServerState::Archived(_, _) => {
+            return Err(CommandError::from(format!("{} has finished", alias)))
+        }
+    };
+
+    remove_from_lobby(db_conn, &lobby_state, alias, user_id)?
+        .ok_or_else(|| CommandError::from(format!("You aren't registered in {}", alias)))
+}
+
+pub fn leave(context: &mut Context, message: &Message, mut args: Args) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let removed = leave_helper(db_conn, message.author.id, &alias)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+
+    let mut text = format!(
+        "You've left {}, {}",
+        alias,
+        show_open_slots(removed.remaining_slots)
+    );
+    for promotion in removed.promotions {
+        text.push('\n');
+        text.push_str(&promotion);
+    }
+    info!("leave: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
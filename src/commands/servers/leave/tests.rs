@@ -0,0 +1,24 @@
+use super::*;
+
+use crate::model::enums::Era;
+
+#[test]
+fn should_free_up_the_callers_slot() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", UserId(1), Era::Early, 5, &[(201, 211), (202, 212)]);
+
+    let RemovedFromLobby {
+        remaining_slots,
+        promotions,
+    } = leave_helper(&db_conn, UserId(201), "lobby").unwrap();
+    assert_eq!(remaining_slots, Some(4));
+    assert!(promotions.is_empty());
+    assert!(leave_helper(&db_conn, UserId(201), "lobby").is_err());
+}
+
+#[test]
+fn should_send_players_of_started_games_to_the_host() {
+    let db_conn = DbConnection::test();
+    let error = leave_helper(&db_conn, UserId(1), "test server 1").unwrap_err();
+    assert!(error.0.contains("ask the host"));
+}
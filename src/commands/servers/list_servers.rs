@@ -26,6 +26,10 @@ fn list_servers_helper(db_conn: &DbConnection) -> Result<CreateEmbed, CommandErr
                     server_aliases.push_str(&format!("{}\n", server.alias));
                     server_addresses.push_str(&format!("{}\n", started_state.address));
                 }
+                GameServerState::Archived(_, _) => {
+                    server_aliases.push_str(&format!("{} (finished)\n", server.alias));
+                    server_addresses.push_str("-\n");
+                }
             }
         }
 
@@ -32,7 +32,12 @@ fn lobbies_helper(
     for (lobby, registered_count) in lobbies_and_player_count {
         aliases.push_str(&format!("{}\n", lobby.alias));
         if let GameServerState::Lobby(state) = lobby.state {
-            player_counts.push_str(&format!("{}/{}\n", registered_count, state.player_count));
+            match state.option_player_count() {
+                Some(player_count) => {
+                    player_counts.push_str(&format!("{}/{}\n", registered_count, player_count))
+                }
+                None => player_counts.push_str(&format!("{}/?\n", registered_count)),
+            }
         } else {
             player_counts.push_str(&"ERROR");
         }
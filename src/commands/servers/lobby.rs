@@ -1,6 +1,6 @@
 use serenity::framework::standard::{Args, CommandError};
 use serenity::model::channel::Message;
-use serenity::model::id::UserId;
+use serenity::model::id::{ChannelId, UserId};
 use serenity::prelude::Context;
 
 use super::alias_from_arg_or_channel_name;
@@ -11,6 +11,22 @@ use crate::model::{GameServer, GameServerState, LobbyState};
 #[cfg(test)]
 mod tests;
 
+/// The era passed in if there was one, otherwise the channel's default
+fn era_for_lobby(
+    db_conn: &DbConnection,
+    option_arg_era: Option<Era>,
+    channel_id: ChannelId,
+) -> Result<Era, CommandError> {
+    match option_arg_era {
+        Some(era) => Ok(era),
+        None => db_conn.default_era_for_channel(channel_id)?.ok_or_else(|| {
+            CommandError::from(
+                "No era given and this channel doesn't have a default one, see !defaultera",
+            )
+        }),
+    }
+}
+
 fn lobby_helper(
     db_conn: &DbConnection,
     era: Era,
@@ -31,8 +47,14 @@ fn lobby_helper(
 }
 
 pub fn lobby(context: &mut Context, message: &Message, mut args: Args) -> Result<(), CommandError> {
-    let era_str = args.single_quoted::<String>()?;
-    let era = Era::from_string(&era_str).ok_or("unknown era")?;
+    // The era can be left out in channels with a default one
+    let option_arg_era = args
+        .single_quoted_n::<String>()
+        .ok()
+        .and_then(|era_str| Era::from_string(&era_str));
+    if option_arg_era.is_some() {
+        args.skip();
+    }
     let player_count = args.single_quoted::<i32>()?;
     let alias = alias_from_arg_or_channel_name(&mut args, &message)?;
     let data = context.data.lock();
@@ -40,7 +62,9 @@ pub fn lobby(context: &mut Context, message: &Message, mut args: Args) -> Result
         .get::<DbConnectionKey>()
         .ok_or("No DbConnection was created on startup. This is a bug.")?;
 
+    let era = era_for_lobby(db_connection, option_arg_era, message.channel_id)?;
     lobby_helper(db_connection, era, player_count, &alias, message.author.id)?;
+    db_connection.set_channel_for_alias(&alias, message.channel_id)?;
 
     message.reply(&format!("Creating game lobby with name {}", alias))?;
     Ok(())
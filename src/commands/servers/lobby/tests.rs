@@ -36,6 +36,47 @@ fn add_two_lobbies() {
     assert_eq!(db_conn.count_servers(), initial_server_count + 2);
     assert_eq!(db_conn.count_lobby_state(), initial_lobby_state_count + 2);
 }
+
+#[test]
+fn should_use_channel_default_era_when_none_given() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_default_era_for_channel(ChannelId(1), Some(Era::Late))
+        .unwrap();
+
+    assert_eq!(
+        era_for_lobby(&db_conn, None, ChannelId(1)).unwrap(),
+        Era::Late
+    );
+    // Other channels don't get it
+    assert!(era_for_lobby(&db_conn, None, ChannelId(2)).is_err());
+}
+
+#[test]
+fn should_prefer_given_era_over_channel_default() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_default_era_for_channel(ChannelId(1), Some(Era::Late))
+        .unwrap();
+
+    assert_eq!(
+        era_for_lobby(&db_conn, Some(Era::Early), ChannelId(1)).unwrap(),
+        Era::Early
+    );
+}
+
+#[test]
+fn should_clear_channel_default_era() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_default_era_for_channel(ChannelId(1), Some(Era::Late))
+        .unwrap();
+    db_conn
+        .set_default_era_for_channel(ChannelId(1), None)
+        .unwrap();
+
+    assert_eq!(db_conn.default_era_for_channel(ChannelId(1)).unwrap(), None);
+}
 /*
 
 sqlite> .schema
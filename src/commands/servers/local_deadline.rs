@@ -0,0 +1,185 @@
+use chrono::{DateTime, FixedOffset, Timelike, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+use std::collections::HashMap;
+
+use super::alias_from_arg_or_channel_name;
+use super::timezone::{parse_utc_offset, show_utc_offset};
+use super::undone::playing_state_for_alias;
+use crate::commands::servers::{GameCacheKey, PlayingState, PotentialPlayer};
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of a timezone to forget it
+const CLEAR_FLAG: &str = "--clear";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TimeOfDay {
+    /// 06:00 to 12:00
+    Morning,
+    /// 12:00 to 18:00
+    Afternoon,
+    /// 18:00 to 23:00
+    Evening,
+    /// 23:00 to 06:00, when they're probably asleep
+    Overnight,
+}
+
+impl TimeOfDay {
+    pub fn at(local: DateTime<FixedOffset>) -> TimeOfDay {
+        match local.hour() {
+            6..=11 => TimeOfDay::Morning,
+            12..=17 => TimeOfDay::Afternoon,
+            18..=22 => TimeOfDay::Evening,
+            _ => TimeOfDay::Overnight,
+        }
+    }
+
+    pub fn show(self) -> &'static str {
+        match self {
+            TimeOfDay::Morning => "morning",
+            TimeOfDay::Afternoon => "afternoon",
+            TimeOfDay::Evening => "evening",
+            TimeOfDay::Overnight => "overnight",
+        }
+    }
+}
+
+/// A line for each registered human nation with when the deadline is for
+/// whoever plays it, if they've said where they are
+pub fn local_deadline_lines(
+    playing_state: &PlayingState,
+    deadline: DateTime<Utc>,
+    utc_offsets: &HashMap<UserId, FixedOffset>,
+) -> Vec<String> {
+    playing_state
+        .players
+        .iter()
+        .filter_map(|potential_player| match potential_player {
+            PotentialPlayer::RegisteredAndGame(user_id, details)
+                if details.player_status.is_human() =>
+            {
+                Some(match utc_offsets.get(user_id) {
+                    Some(utc_offset) => {
+                        let local = deadline.with_timezone(utc_offset);
+                        format!(
+                            "{}: {} {}, {}",
+                            details.nation_name,
+                            local.format("%a %H:%M"),
+                            show_utc_offset(*utc_offset),
+                            TimeOfDay::at(local).show()
+                        )
+                    }
+                    None => format!(
+                        "{}: no timezone set, its player can use !mytimezone",
+                        details.nation_name
+                    ),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn local_deadline_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    playing_state: &PlayingState,
+) -> Result<String, CommandError> {
+    let deadline = match playing_state.option_deadline {
+        Some(deadline) => deadline,
+        None => {
+            return Err(CommandError::from(format!(
+                "Turn {} in {} has no deadline, the timer is paused or not set",
+                playing_state.turn, alias
+            )))
+        }
+    };
+    let utc_offsets = db_conn.player_utc_offsets_for_alias(alias)?;
+    let lines = local_deadline_lines(playing_state, deadline, &utc_offsets);
+    if lines.is_empty() {
+        return Err(CommandError::from(format!(
+            "Nobody playing {} has registered",
+            alias
+        )));
+    }
+    Ok(format!(
+        "Turn {} in {} ends at {} UTC, which is:\n{}",
+        playing_state.turn,
+        alias,
+        deadline.format("%a %H:%M"),
+        lines.join("\n")
+    ))
+}
+
+fn my_timezone_helper(
+    db_conn: &DbConnection,
+    user_id: UserId,
+    arg: &str,
+) -> Result<String, CommandError> {
+    if arg == CLEAR_FLAG {
+        db_conn.set_player_utc_offset(user_id, None)?;
+        return Ok("Forgot your timezone".to_owned());
+    }
+    let utc_offset = parse_utc_offset(arg)?;
+    db_conn.set_player_utc_offset(user_id, Some(utc_offset))?;
+    Ok(format!(
+        "Your games' deadlines will be worked out in {} for !localdeadline",
+        show_utc_offset(utc_offset)
+    ))
+}
+
+pub fn my_timezone(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected your timezone like \"UTC+2\", or --clear"))?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = my_timezone_helper(db_conn, message.author.id, &arg)?;
+    info!("my_timezone: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
+
+pub fn local_deadline(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let playing_state = playing_state_for_alias(db_conn, game_cache, &alias)?;
+    let text = local_deadline_helper(db_conn, &alias, &playing_state)?;
+    info!("local_deadline: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use super::*;
+
+use crate::commands::servers::PlayerDetails;
+use crate::model::enums::{NationStatus, SubmissionStatus};
+use chrono::TimeZone;
+
+fn player(user_id: u64, nation_id: u32, player_status: NationStatus) -> PotentialPlayer {
+    PotentialPlayer::RegisteredAndGame(
+        UserId(user_id),
+        PlayerDetails {
+            nation_id,
+            nation_name: format!("nation {}", nation_id),
+            submitted: SubmissionStatus::NotSubmitted,
+            player_status,
+            went_ai: false,
+            connected: false,
+        },
+    )
+}
+
+#[test]
+fn should_classify_local_times() {
+    let at = |hour| TimeOfDay::at(FixedOffset::east(0).ymd(2020, 10, 20).and_hms(hour, 30, 0));
+    assert_eq!(at(5), TimeOfDay::Overnight);
+    assert_eq!(at(6), TimeOfDay::Morning);
+    assert_eq!(at(12), TimeOfDay::Afternoon);
+    assert_eq!(at(18), TimeOfDay::Evening);
+    assert_eq!(at(23), TimeOfDay::Overnight);
+}
+
+#[test]
+fn should_show_deadline_in_each_players_timezone() {
+    let deadline = Utc.ymd(2020, 10, 20).and_hms(21, 0, 0);
+    let playing_state = PlayingState {
+        players: vec![
+            player(1, 1, NationStatus::Human),
+            player(2, 2, NationStatus::Human),
+            player(3, 3, NationStatus::AI),
+        ],
+        turn: 10,
+        mins_remaining: 0,
+        hours_remaining: 1,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: Some(deadline),
+        paused: false,
+        blitz: false,
+    };
+    let mut utc_offsets = HashMap::new();
+    utc_offsets.insert(UserId(1), FixedOffset::east(3 * 3600));
+    utc_offsets.insert(UserId(3), FixedOffset::east(0));
+
+    assert_eq!(
+        local_deadline_lines(&playing_state, deadline, &utc_offsets),
+        vec![
+            "nation 1: Wed 00:00 UTC+3, overnight",
+            "nation 2: no timezone set, its player can use !mytimezone",
+        ]
+    );
+}
+
+#[test]
+fn should_remember_players_timezones() {
+    let db_conn = DbConnection::test();
+    // registered in "test server 1"
+    my_timezone_helper(&db_conn, UserId(101), "UTC-5").unwrap();
+    // not registered anywhere yet
+    my_timezone_helper(&db_conn, UserId(999), "UTC+1").unwrap();
+    let utc_offsets = db_conn
+        .player_utc_offsets_for_alias("test server 1")
+        .unwrap();
+    assert_eq!(utc_offsets.len(), 1);
+    assert_eq!(utc_offsets[&UserId(101)], FixedOffset::west(5 * 3600));
+
+    my_timezone_helper(&db_conn, UserId(101), CLEAR_FLAG).unwrap();
+    assert!(db_conn
+        .player_utc_offsets_for_alias("test server 1")
+        .unwrap()
+        .is_empty());
+    assert!(my_timezone_helper(&db_conn, UserId(101), "Mars/Olympus").is_err());
+}
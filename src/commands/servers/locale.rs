@@ -0,0 +1,69 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+
+use super::{Translations, TranslationsKey};
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of a locale to go back to English
+const CLEAR_FLAG: &str = "--clear";
+
+fn locale_helper(
+    db_conn: &DbConnection,
+    translations: &Translations,
+    channel_id: ChannelId,
+    arg_locale: &str,
+) -> Result<String, CommandError> {
+    if arg_locale == CLEAR_FLAG {
+        db_conn.set_locale_for_channel(channel_id, None)?;
+        return Ok("Nation names and labels in this channel will be in English".to_owned());
+    }
+    let locale = arg_locale.to_lowercase();
+    if !translations.has_locale(&locale) {
+        let locales = translations.locales();
+        return Err(CommandError::from(if locales.is_empty() {
+            "This bot has no translations".to_owned()
+        } else {
+            format!(
+                "No translations for \"{}\", try one of: {}",
+                arg_locale,
+                locales.join(", ")
+            )
+        }));
+    }
+    db_conn.set_locale_for_channel(channel_id, Some(&locale))?;
+    Ok(format!(
+        "Nation names and labels in this channel will be in \"{}\" where there's a translation",
+        locale
+    ))
+}
+
+pub fn locale(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_locale = args.single_quoted::<String>()?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let translations = data
+        .get::<TranslationsKey>()
+        .ok_or("No Translations were loaded on startup. This is a bug.")?;
+    let text = locale_helper(db_conn, translations, message.channel_id, &arg_locale)?;
+    info!("locale: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+use crate::commands::servers::LocaleStrings;
+use crate::model::enums::Era;
+use std::fs;
+
+#[test]
+fn should_only_set_locales_we_have_translations_for() {
+    let db_conn = DbConnection::test();
+    let channel_id = ChannelId(31_501);
+    let dir = std::env::temp_dir().join(format!("dom5bot-locales-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("de"), "label open Frei\n").unwrap();
+    let translations = Translations::load(&dir).unwrap();
+
+    assert!(locale_helper(&db_conn, &translations, channel_id, "fr").is_err());
+    locale_helper(&db_conn, &translations, channel_id, "DE").unwrap();
+    assert_eq!(
+        db_conn.locale_for_channel(channel_id).unwrap(),
+        Some("de".to_owned())
+    );
+    assert_ne!(translations.strings(Some("de")), &LocaleStrings::default());
+
+    locale_helper(&db_conn, &translations, channel_id, CLEAR_FLAG).unwrap();
+    assert_eq!(db_conn.locale_for_channel(channel_id).unwrap(), None);
+}
+
+#[test]
+fn should_keep_other_channel_settings() {
+    let db_conn = DbConnection::test();
+    let channel_id = ChannelId(31_502);
+    db_conn
+        .set_default_era_for_channel(channel_id, Some(Era::Middle))
+        .unwrap();
+    db_conn
+        .set_locale_for_channel(channel_id, Some("de"))
+        .unwrap();
+    db_conn
+        .set_default_era_for_channel(channel_id, Some(Era::Late))
+        .unwrap();
+    assert_eq!(
+        db_conn.default_era_for_channel(channel_id).unwrap(),
+        Some(Era::Late)
+    );
+    assert_eq!(
+        db_conn.locale_for_channel(channel_id).unwrap(),
+        Some("de".to_owned())
+    );
+}
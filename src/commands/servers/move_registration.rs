@@ -0,0 +1,152 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::lobby_state_for_alias;
+use crate::commands::servers::{lobby_details, GameCacheKey, NationDetails};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::Nations;
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this to leave the player registered in the old game as well
+const COPY_FLAG: &str = "--copy";
+
+/// For when a game is set up again under a new alias, so its players don't all
+/// have to `!register` again. Only ever into a lobby, with the same checks as
+/// `!registerfor`.
+fn move_registration_helper(
+    db_conn: &DbConnection,
+    user_id: UserId,
+    from_alias: &str,
+    to_alias: &str,
+    keep_original: bool,
+) -> Result<String, CommandError> {
+    if from_alias == to_alias {
+        return Err(CommandError::from("Those are the same game"));
+    }
+    let from_nation_ids: Vec<u32> = db_conn
+        .players_with_nations_for_game_alias(from_alias)?
+        .into_iter()
+        .filter(|(player, _)| player.discord_user_id == user_id)
+        .map(|(_, nation_id)| nation_id)
+        .collect();
+    let nation_id = match from_nation_ids.as_slice() {
+        [] => {
+            return Err(CommandError::from(format!(
+                "<@{}> isn't registered in {}",
+                user_id, from_alias
+            )))
+        }
+        [nation_id] => *nation_id,
+        _ => {
+            return Err(CommandError::from(format!(
+                "<@{}> is registered as more than one nation in {}, use !registerfor instead",
+                user_id, from_alias
+            )))
+        }
+    };
+
+    let lobby_state = lobby_state_for_alias(db_conn, to_alias)?;
+    let nation = Nations::from_id(nation_id)
+        .ok_or_else(|| CommandError::from(format!("Nation {} isn't one we know", nation_id)))?;
+    if nation.era != Some(lobby_state.era) {
+        return Err(CommandError::from(format!(
+            "{} ({}) can't be played in {}, it's an {} lobby",
+            nation.name, nation.id, to_alias, lobby_state.era
+        )));
+    }
+
+    for (player, claimed_id) in db_conn.players_with_nations_for_game_alias(to_alias)? {
+        if player.discord_user_id == user_id {
+            return Err(CommandError::from(format!(
+                "<@{}> is already registered in {}",
+                user_id, to_alias
+            )));
+        }
+        if claimed_id == nation.id {
+            return Err(CommandError::from(format!(
+                "{} ({}) is already registered to <@{}> in {}",
+                nation.name, nation.id, player.discord_user_id, to_alias
+            )));
+        }
+    }
+    if db_conn
+        .ai_reserved_nations_for_alias(to_alias)?
+        .contains(&nation.id)
+    {
+        return Err(CommandError::from(format!(
+            "{} ({}) is reserved for the AI in {}",
+            nation.name, nation.id, to_alias
+        )));
+    }
+    let full = match lobby_details(db_conn, &lobby_state, to_alias)?.nations {
+        NationDetails::Lobby(lobby_details) => lobby_details.is_full(),
+        NationDetails::Started(_) => {
+            return Err(CommandError::from(
+                "Lobby details were for a started game. This is a bug.",
+            ))
+        }
+    };
+    if full {
+        return Err(CommandError::from(format!("{} is already full", to_alias)));
+    }
+
+    db_conn.copy_player_between_games(from_alias, to_alias, user_id, keep_original)?;
+    Ok(format!(
+        "{} <@{}>'s registration as {} ({}) from {} to {}",
+        if keep_original { "Copied" } else { "Moved" },
+        user_id,
+        nation.name,
+        nation.id,
+        from_alias,
+        to_alias
+    ))
+}
+
+pub fn move_registration(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let user_id = args
+        .single_quoted::<UserId>()
+        .map_err(|_| CommandError::from("Expected an @mention of the player to move"))?;
+    let from_alias = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected the game to move them from"))?
+        .to_lowercase();
+    let to_alias = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected the game to move them to"))?
+        .to_lowercase();
+    let keep_original = match args.single_quoted_n::<String>() {
+        Ok(ref flag) if flag == COPY_FLAG => {
+            args.skip();
+            true
+        }
+        _ => false,
+    };
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = move_registration_helper(db_conn, user_id, &from_alias, &to_alias, keep_original)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&from_alias);
+    game_cache.invalidate_lobby(&to_alias);
+    info!("move_registration: replying with: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
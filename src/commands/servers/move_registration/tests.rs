@@ -0,0 +1,64 @@
+use super::*;
+
+use crate::model::enums::Era;
+use crate::model::Player;
+
+fn register(db_conn: &DbConnection, alias: &str, user_id: u64, nation_id: u32) {
+    db_conn
+        .insert_player_into_server(
+            &Player {
+                discord_user_id: UserId(user_id),
+                turn_notifications: true,
+            },
+            alias,
+            nation_id,
+        )
+        .unwrap();
+}
+
+fn registered(db_conn: &DbConnection, alias: &str) -> Vec<(UserId, u32)> {
+    db_conn
+        .players_with_nations_for_game_alias(alias)
+        .unwrap()
+        .into_iter()
+        .map(|(player, nation_id)| (player.discord_user_id, nation_id))
+        .collect()
+}
+
+#[test]
+fn should_move_or_copy_a_registration() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("old", UserId(1), Era::Early, 3, &[(338, 5), (3381, 6)]);
+    db_conn.insert_test_lobby("new", UserId(1), Era::Early, 3, &[]);
+
+    let text = move_registration_helper(&db_conn, UserId(338), "old", "new", false).unwrap();
+    assert!(text.starts_with("Moved <@338>'s registration"));
+    assert_eq!(registered(&db_conn, "old"), vec![(UserId(3381), 6)]);
+    assert_eq!(registered(&db_conn, "new"), vec![(UserId(338), 5)]);
+
+    move_registration_helper(&db_conn, UserId(3381), "old", "new", true).unwrap();
+    assert_eq!(registered(&db_conn, "old"), vec![(UserId(3381), 6)]);
+    assert_eq!(registered(&db_conn, "new").len(), 2);
+}
+
+#[test]
+fn should_report_conflicts_in_the_new_lobby() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("old", UserId(1), Era::Early, 3, &[(338, 5)]);
+    db_conn.insert_test_lobby("new", UserId(1), Era::Early, 1, &[(3381, 5)]);
+    db_conn.insert_test_lobby("middle", UserId(1), Era::Middle, 3, &[]);
+
+    let err = move_registration_helper(&db_conn, UserId(338), "old", "new", false).unwrap_err();
+    assert!(err.0.contains("already registered to <@3381>"));
+    let err = move_registration_helper(&db_conn, UserId(338), "old", "middle", false).unwrap_err();
+    assert!(err.0.contains("it's an MA lobby"));
+
+    db_conn.remove_nation_from_game("new", 5).unwrap();
+    register(&db_conn, "new", 3381, 6);
+    let err = move_registration_helper(&db_conn, UserId(338), "old", "new", false).unwrap_err();
+    assert!(err.0.contains("already full"));
+
+    assert!(move_registration_helper(&db_conn, UserId(339), "old", "new", false).is_err());
+    // nothing moved out of the old game along the way
+    assert_eq!(registered(&db_conn, "old"), vec![(UserId(338), 5)]);
+}
@@ -0,0 +1,78 @@
+use failure::{err_msg, Error};
+use log::*;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use typemap::Key;
+
+#[cfg(test)]
+mod tests;
+
+/// Optional custom emoji to show next to each nation, from a file of
+/// `<nation_id> <emoji>` lines, e.g. `5 <:arcoscephale:123456789012345678>`.
+/// Nations without one are shown exactly as before.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct NationEmojis(HashMap<u32, String>);
+
+pub struct NationEmojisKey;
+impl Key for NationEmojisKey {
+    type Value = NationEmojis;
+}
+
+impl NationEmojis {
+    /// Blank lines and lines starting with `#` are ignored
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut emojis = HashMap::new();
+        for (ix, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let (nation_id, emoji) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(nation_id), Some(emoji), None) => (nation_id, emoji),
+                _ => {
+                    return Err(err_msg(format!(
+                        "Line {}: expected '<nation_id> <emoji>' but got '{}'",
+                        ix + 1,
+                        line
+                    )))
+                }
+            };
+            let nation_id = nation_id.parse::<u32>().map_err(|_| {
+                err_msg(format!(
+                    "Line {}: '{}' is not a nation id",
+                    ix + 1,
+                    nation_id
+                ))
+            })?;
+            emojis.insert(nation_id, emoji.to_owned());
+        }
+        Ok(NationEmojis(emojis))
+    }
+
+    /// No file just means no emoji
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                let nation_emojis = Self::parse(&text)?;
+                info!("Loaded {} nation emojis", nation_emojis.0.len());
+                Ok(nation_emojis)
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+                info!("No nation emojis at {}", path.display());
+                Ok(NationEmojis::default())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The emoji and a space, ready to go in front of the nation name
+    pub fn prefix(&self, nation_id: u32) -> String {
+        match self.0.get(&nation_id) {
+            Some(emoji) => format!("{} ", emoji),
+            None => String::new(),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn should_parse_emoji_file() {
+    let nation_emojis = NationEmojis::parse(
+        "# early age\n\
+         5 <:arcoscephale:123456789012345678>\n\
+         \n\
+         7   <:ulm:876543210987654321>  \n",
+    )
+    .unwrap();
+    assert_eq!(
+        nation_emojis.prefix(5),
+        "<:arcoscephale:123456789012345678> "
+    );
+    assert_eq!(nation_emojis.prefix(7), "<:ulm:876543210987654321> ");
+    // nothing configured renders as it always has
+    assert_eq!(nation_emojis.prefix(6), "");
+}
+
+#[test]
+fn should_reject_malformed_lines() {
+    assert!(NationEmojis::parse("ulm <:ulm:1>").is_err());
+    assert!(NationEmojis::parse("7").is_err());
+    assert!(NationEmojis::parse("7 <:ulm:1> extra").is_err());
+}
+
+#[test]
+fn should_have_no_emojis_without_a_file() {
+    let nation_emojis = NationEmojis::load(Path::new("resources/does_not_exist")).unwrap();
+    assert_eq!(nation_emojis, NationEmojis::default());
+}
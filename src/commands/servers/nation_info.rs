@@ -0,0 +1,89 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::register_player::nation_not_found;
+use super::{LocaleStrings, TranslationsKey};
+use crate::db::DbConnectionKey;
+use crate::model::enums::{Era, Nation, Nations};
+
+#[cfg(test)]
+mod tests;
+
+/// Caps the reply for very short prefixes, which match most nations
+const MAX_NATIONS_SHOWN: usize = 20;
+
+/// Every built in nation with this ID, or whose name starts with this, in ID order.
+/// All we know about a nation is its name, ID and era. Names are looked up in
+/// English, but shown translated if there's a translation.
+fn nation_info_helper(
+    arg_nation: &str,
+    option_era: Option<Era>,
+    strings: &LocaleStrings,
+) -> Result<String, CommandError> {
+    let mut nations: Vec<Nation> = match arg_nation.parse::<u32>() {
+        Ok(nation_id) => Nations::from_id(nation_id).into_iter().collect(),
+        Err(_) => Nations::from_name_prefix(arg_nation, option_era),
+    };
+    if nations.is_empty() {
+        if arg_nation.parse::<u32>().is_err() {
+            let candidates = Nations::from_name_prefix("", option_era);
+            return Err(nation_not_found(arg_nation, &candidates));
+        }
+        return Err(CommandError::from(format!(
+            "Could not find nation \"{}\"",
+            arg_nation
+        )));
+    }
+    nations.sort_by_key(|nation| nation.id);
+    let mut lines: Vec<String> = nations
+        .iter()
+        .take(MAX_NATIONS_SHOWN)
+        .map(|nation| {
+            let nation_name = strings.nation_name(nation.id, &nation.name);
+            match nation.era {
+                Some(era) => format!("{} ({} {})", nation_name, era, nation.id),
+                None => format!("{} ({})", nation_name, nation.id),
+            }
+        })
+        .collect();
+    if nations.len() > MAX_NATIONS_SHOWN {
+        lines.push(format!("...and {} more", nations.len() - MAX_NATIONS_SHOWN));
+    }
+    Ok(lines.join("\n"))
+}
+
+pub fn nation_info(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_nation = args.single_quoted::<String>()?;
+    let option_era = match args.single_quoted::<String>() {
+        Ok(era_str) => Some(Era::from_string(&era_str).ok_or("unknown era")?),
+        Err(_) => None,
+    };
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let translations = data
+        .get::<TranslationsKey>()
+        .ok_or("No Translations were loaded on startup. This is a bug.")?;
+    let option_locale = db_conn.locale_for_channel(message.channel_id)?;
+    let text = nation_info_helper(
+        &arg_nation,
+        option_era,
+        translations.strings(option_locale.as_deref()),
+    )?;
+    info!("nationinfo: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
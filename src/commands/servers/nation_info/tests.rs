@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn should_find_nations_by_id_or_name() {
+    assert_eq!(
+        nation_info_helper("7", None, &LocaleStrings::default()).unwrap(),
+        "Ulm (EA 7)"
+    );
+    assert_eq!(
+        nation_info_helper("arco", Some(Era::Early), &LocaleStrings::default()).unwrap(),
+        "Arcoscephale (EA 5)"
+    );
+    // one line per era without one
+    assert!(
+        nation_info_helper("arco", None, &LocaleStrings::default())
+            .unwrap()
+            .lines()
+            .count()
+            > 1
+    );
+    assert!(nation_info_helper("9999", None, &LocaleStrings::default()).is_err());
+}
+
+#[test]
+fn should_suggest_nations_for_typos() {
+    let err = nation_info_helper("ulmm", Some(Era::Early), &LocaleStrings::default()).unwrap_err();
+    assert!(err.0.contains("Did you mean: Ulm (EA 7)"), "{}", err.0);
+}
+
+#[test]
+fn should_show_translated_nation_names() {
+    let strings = LocaleStrings::parse("nation 7 Ulmländer\n").unwrap();
+    assert_eq!(
+        nation_info_helper("ulm", Some(Era::Early), &strings).unwrap(),
+        "Ulmländer (EA 7)"
+    );
+}
@@ -0,0 +1,162 @@
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::turnstats::turn_durations_while_watching;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Any less than this and the average is mostly noise
+const MIN_HISTORY_TURNS: usize = 3;
+/// Games speed up and slow down, so only the recent pace counts
+const RECENT_TURNS: u32 = 10;
+
+#[derive(PartialEq, Debug)]
+pub struct NextTurnEstimate {
+    pub eta: DateTime<Utc>,
+    /// What the estimate is based on, so people know how much to trust it
+    pub note: String,
+}
+
+/// Halfway between when the timer runs out and when the turn would host at
+/// the game's recent pace, but never after the timer. Without enough history
+/// this is just the timer, and without a timer it's just the history.
+pub fn estimate_next_turn(
+    now: DateTime<Utc>,
+    option_deadline: Option<DateTime<Utc>>,
+    option_turn_started: Option<DateTime<Utc>>,
+    durations: &[(i32, Duration)],
+) -> Option<NextTurnEstimate> {
+    let option_average = if durations.len() >= MIN_HISTORY_TURNS {
+        let total_secs: i64 = durations
+            .iter()
+            .map(|&(_, duration)| duration.num_seconds())
+            .sum();
+        Some(Duration::seconds(total_secs / durations.len() as i64))
+    } else {
+        None
+    };
+    // A turn that's already taken longer than usual could host any minute now
+    let option_history_eta = match (option_average, option_turn_started) {
+        (Some(average), Some(turn_started)) => Some(std::cmp::max(now, turn_started + average)),
+        _ => None,
+    };
+
+    match (option_deadline, option_history_eta, option_average) {
+        (Some(deadline), Some(history_eta), Some(average)) => {
+            let blended = history_eta + deadline.signed_duration_since(history_eta) / 2;
+            Some(NextTurnEstimate {
+                eta: std::cmp::min(deadline, blended),
+                note: format!(
+                    "blending the timer with the last {} turns (averaging {:.1}h)",
+                    durations.len(),
+                    hours(average),
+                ),
+            })
+        }
+        (Some(deadline), _, _) => Some(NextTurnEstimate {
+            eta: deadline,
+            note: "from the timer only, there isn't enough turn history yet".to_owned(),
+        }),
+        (None, Some(history_eta), Some(average)) => Some(NextTurnEstimate {
+            eta: history_eta,
+            note: format!(
+                "there's no timer so this is only from the last {} turns (averaging {:.1}h), \
+                 don't count on it",
+                durations.len(),
+                hours(average),
+            ),
+        }),
+        _ => None,
+    }
+}
+
+fn nextturn_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+) -> Result<String, CommandError> {
+    let (details, _) = cached_game_details(alias, db_conn, game_cache)?;
+    let playing_state = match details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => playing_state,
+        _ => return Err(CommandError::from(format!("{} is not in progress", alias))),
+    };
+    if playing_state.paused {
+        return Ok(format!(
+            "{} is paused, turn {} won't host until it's unpaused",
+            alias, playing_state.turn
+        ));
+    }
+
+    // One extra so that the oldest turn counted still has a start time
+    let history = db_conn.select_turn_history(alias, RECENT_TURNS + 1)?;
+    let downtimes = db_conn.select_downtimes()?;
+    let durations = turn_durations_while_watching(&history, &downtimes);
+    let option_turn_started = history
+        .last()
+        .filter(|&&(turn, _)| turn as u32 == playing_state.turn)
+        .map(|&(_, started)| started);
+
+    let now = Utc::now();
+    match estimate_next_turn(
+        now,
+        playing_state.option_deadline,
+        option_turn_started,
+        &durations,
+    ) {
+        None => Ok(format!(
+            "{} has no timer and not enough turn history to guess when turn {} will host",
+            alias, playing_state.turn
+        )),
+        Some(estimate) => {
+            let utc_offset = db_conn
+                .utc_offset_for_alias(alias)?
+                .unwrap_or_else(|| FixedOffset::east(0));
+            let from_now = estimate.eta.signed_duration_since(now);
+            Ok(format!(
+                "Turn {} of {} will probably host around {} {} ({}h {}m from now), {}",
+                playing_state.turn,
+                alias,
+                estimate.eta.with_timezone(&utc_offset).format("%a %H:%M"),
+                show_utc_offset(utc_offset),
+                from_now.num_hours(),
+                from_now.num_minutes() % 60,
+                estimate.note,
+            ))
+        }
+    }
+}
+
+pub fn nextturn(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = nextturn_helper(db_conn, game_cache, &alias)?;
+    info!("nextturn: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+use chrono::TimeZone;
+
+fn hour(h: i64) -> DateTime<Utc> {
+    Utc.timestamp(h * 3600, 0)
+}
+
+fn durations(hours: &[i64]) -> Vec<(i32, Duration)> {
+    hours
+        .iter()
+        .enumerate()
+        .map(|(ix, &h)| (ix as i32 + 1, Duration::hours(h)))
+        .collect()
+}
+
+#[test]
+fn should_use_raw_timer_without_enough_history() {
+    let estimate = estimate_next_turn(
+        hour(1),
+        Some(hour(48)),
+        Some(hour(0)),
+        &durations(&[10, 10]),
+    )
+    .unwrap();
+    assert_eq!(estimate.eta, hour(48));
+    assert!(estimate.note.contains("timer only"));
+}
+
+#[test]
+fn should_blend_timer_with_history() {
+    // turns usually take 12h, the timer says 48h
+    let estimate = estimate_next_turn(
+        hour(1),
+        Some(hour(48)),
+        Some(hour(0)),
+        &durations(&[10, 14, 12]),
+    )
+    .unwrap();
+    assert_eq!(estimate.eta, hour(30));
+    assert!(estimate.note.contains("last 3 turns"));
+}
+
+#[test]
+fn should_not_estimate_past_the_timer() {
+    // slow game but the timer is about to run out
+    let estimate = estimate_next_turn(
+        hour(20),
+        Some(hour(22)),
+        Some(hour(0)),
+        &durations(&[48, 48, 48]),
+    )
+    .unwrap();
+    assert_eq!(estimate.eta, hour(22));
+}
+
+#[test]
+fn should_not_estimate_in_the_past_for_a_slow_turn() {
+    let estimate =
+        estimate_next_turn(hour(20), None, Some(hour(0)), &durations(&[10, 10, 10])).unwrap();
+    assert_eq!(estimate.eta, hour(20));
+}
+
+#[test]
+fn should_give_up_without_timer_or_history() {
+    assert_eq!(
+        estimate_next_turn(hour(1), None, Some(hour(0)), &durations(&[10])),
+        None
+    );
+}
@@ -0,0 +1,83 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of a nickname to go back to showing the discord name
+const CLEAR_NICKNAME_FLAG: &str = "--clear";
+const MAX_NICKNAME_LEN: usize = 32;
+
+fn validate_nickname(nickname: &str) -> Result<&str, CommandError> {
+    let nickname = nickname.trim();
+    if nickname.is_empty() || nickname.chars().count() > MAX_NICKNAME_LEN {
+        return Err(CommandError::from(format!(
+            "Nicknames must be between 1 and {} characters",
+            MAX_NICKNAME_LEN
+        )));
+    }
+    // No sneaking pings or broken formatting into the details embed
+    if nickname.contains(&['@', '`', '\n'][..]) {
+        return Err(CommandError::from(
+            "Nicknames can't contain '@', '`' or newlines",
+        ));
+    }
+    Ok(nickname)
+}
+
+fn nickname_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    user_id: UserId,
+    option_nickname: Option<&str>,
+) -> Result<(), CommandError> {
+    let option_nickname = match option_nickname {
+        Some(nickname) => Some(validate_nickname(nickname)?),
+        None => None,
+    };
+    db_conn.set_player_nickname(alias, user_id, option_nickname)?;
+    Ok(())
+}
+
+pub fn nickname(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_nickname = args.single_quoted::<String>()?;
+    let option_nickname = if arg_nickname == CLEAR_NICKNAME_FLAG {
+        None
+    } else {
+        Some(arg_nickname)
+    };
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    nickname_helper(
+        db_conn,
+        &alias,
+        message.author.id,
+        option_nickname.as_deref(),
+    )?;
+    let text = match option_nickname {
+        Some(nickname) => format!("You'll be shown as {} in {}", nickname.trim(), alias),
+        None => format!("Cleared your nickname in {}", alias),
+    };
+    info!("nickname: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
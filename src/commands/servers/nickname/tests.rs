@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn should_set_and_clear_nickname() {
+    let db_conn = DbConnection::test();
+    // UserId(101) is registered in "test server 1"
+    nickname_helper(&db_conn, "test server 1", UserId(101), Some("  Bob ")).unwrap();
+    let nicknames = db_conn.player_nicknames_for_alias("test server 1").unwrap();
+    assert_eq!(nicknames.get(&UserId(101)), Some(&"Bob".to_owned()));
+    assert!(db_conn
+        .player_nicknames_for_alias("test server 2")
+        .unwrap()
+        .is_empty());
+
+    nickname_helper(&db_conn, "test server 1", UserId(101), None).unwrap();
+    assert!(db_conn
+        .player_nicknames_for_alias("test server 1")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_not_set_nickname_in_game_you_are_not_in() {
+    let db_conn = DbConnection::test();
+    let err = nickname_helper(&db_conn, "test server 2", UserId(101), Some("Bob")).unwrap_err();
+    assert!(format!("{:?}", err).contains("not registered"));
+}
+
+#[test]
+fn should_not_allow_pings_in_nickname() {
+    let db_conn = DbConnection::test();
+    assert!(nickname_helper(&db_conn, "test server 1", UserId(101), Some("@everyone")).is_err());
+    assert!(nickname_helper(&db_conn, "test server 1", UserId(101), Some("   ")).is_err());
+}
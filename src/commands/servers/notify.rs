@@ -0,0 +1,96 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::NotificationRoute;
+
+#[cfg(test)]
+mod tests;
+
+fn notify_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    kind: &str,
+    route: &str,
+) -> Result<String, CommandError> {
+    let route = NotificationRoute::from_string(route).ok_or_else(|| {
+        CommandError::from(format!(
+            "\"{}\" isn't somewhere to send them, try channel, dm, both or off",
+            route
+        ))
+    })?;
+    let mut routes = db_conn.notification_routes_for_alias(alias)?;
+    let description = match kind.to_lowercase().as_ref() {
+        "newturn" => {
+            routes.new_turn = route;
+            "New turn announcements"
+        }
+        "stall" => {
+            routes.stall = route;
+            "Stall warnings"
+        }
+        "reminder" => {
+            routes.reminder = route;
+            "Countdown reminders"
+        }
+        "waiting" => {
+            routes.waiting_on_host = route;
+            "Waiting on host alerts"
+        }
+        _ => {
+            return Err(CommandError::from(format!(
+                "\"{}\" isn't a kind of announcement, try newturn, stall, reminder or waiting",
+                kind
+            )))
+        }
+    };
+    db_conn.set_notification_routes_for_alias(alias, &routes)?;
+    Ok(match route {
+        NotificationRoute::Channel => format!(
+            "{} for {} will only be posted in its channels",
+            description, alias
+        ),
+        NotificationRoute::Dm => format!(
+            "{} for {} will only be DMed to its players",
+            description, alias
+        ),
+        NotificationRoute::Both => format!(
+            "{} for {} will be posted in its channels and DMed to its players",
+            description, alias
+        ),
+        NotificationRoute::Off => {
+            format!("Turned off {} for {}", description.to_lowercase(), alias)
+        }
+    })
+}
+
+pub fn notify(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let kind = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected newturn, stall, reminder or waiting"))?;
+    let route = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected channel, dm, both or off"))?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = notify_helper(db_conn, &alias, &kind, &route)?;
+    info!("notify: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
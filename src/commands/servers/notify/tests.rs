@@ -0,0 +1,47 @@
+use super::*;
+
+use crate::model::NotificationRoutes;
+
+#[test]
+fn should_change_one_route_at_a_time() {
+    let db_conn = DbConnection::test();
+    assert_eq!(
+        db_conn
+            .notification_routes_for_alias("test server 1")
+            .unwrap(),
+        NotificationRoutes::default()
+    );
+
+    let text = notify_helper(&db_conn, "test server 1", "stall", "dm").unwrap();
+    assert_eq!(
+        text,
+        "Stall warnings for test server 1 will only be DMed to its players"
+    );
+    notify_helper(&db_conn, "test server 1", "NewTurn", "none").unwrap();
+    assert_eq!(
+        db_conn
+            .notification_routes_for_alias("test server 1")
+            .unwrap(),
+        NotificationRoutes {
+            new_turn: NotificationRoute::Off,
+            stall: NotificationRoute::Dm,
+            reminder: NotificationRoute::Channel,
+            waiting_on_host: NotificationRoute::Off,
+        }
+    );
+    // other games keep the defaults
+    assert_eq!(
+        db_conn
+            .notification_routes_for_alias("test server 2")
+            .unwrap(),
+        NotificationRoutes::default()
+    );
+}
+
+#[test]
+fn should_reject_unknown_kinds_routes_and_games() {
+    let db_conn = DbConnection::test();
+    assert!(notify_helper(&db_conn, "test server 1", "digest", "dm").is_err());
+    assert!(notify_helper(&db_conn, "test server 1", "stall", "email").is_err());
+    assert!(notify_helper(&db_conn, "not a game", "stall", "dm").is_err());
+}
@@ -0,0 +1,148 @@
+use either::Either;
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::register_player::get_nation_for_lobby;
+use super::{
+    alias_from_arg_or_channel_name, lobby_details, lobby_state_for_alias, lobby_state_for_owner,
+    show_open_slots, NationDetails,
+};
+use crate::commands::servers::GameCacheKey;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::Nation;
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of the nations to go back to just a number of slots
+const CLEAR_FLAG: &str = "--clear";
+
+/// `arg_nations` is comma separated, each a name or an ID like `!register` takes
+fn nation_set_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    arg_nations: &str,
+) -> Result<String, CommandError> {
+    let lobby_state = lobby_state_for_owner(db_conn, alias, author_id)?;
+    if arg_nations == CLEAR_FLAG {
+        db_conn.set_lobby_nation_set(alias, &[])?;
+        return Ok(format!("{} no longer has a set of nations", alias));
+    }
+    let mut nations: Vec<Nation> = vec![];
+    for arg_nation in arg_nations
+        .split(',')
+        .map(|arg_nation| arg_nation.trim().to_lowercase())
+        .filter(|arg_nation| !arg_nation.is_empty())
+    {
+        let arg_nation = match arg_nation.parse::<u32>() {
+            Ok(nation_id) => Either::Right(nation_id),
+            Err(_) => Either::Left(arg_nation.as_str()),
+        };
+        let nation = get_nation_for_lobby(arg_nation, lobby_state.era)?;
+        if !nations.iter().any(|other| other.id == nation.id) {
+            nations.push(nation);
+        }
+    }
+    if nations.is_empty() {
+        return Err(CommandError::from(
+            "Expected a comma separated list of nations, e.g. \"ulm, 5, mictlan\"",
+        ));
+    }
+    let nation_ids: Vec<u32> = nations.iter().map(|nation| nation.id).collect();
+    db_conn.set_lobby_nation_set(alias, &nation_ids)?;
+    let nation_names: Vec<String> = nations
+        .iter()
+        .map(|nation| format!("{} ({})", nation.name, nation.id))
+        .collect();
+    Ok(format!(
+        "{} is for these nations: {}",
+        alias,
+        nation_names.join(", ")
+    ))
+}
+
+fn open_slots_helper(db_conn: &DbConnection, alias: &str) -> Result<String, CommandError> {
+    let lobby_state = lobby_state_for_alias(db_conn, alias)?;
+    let lobby_details = match lobby_details(db_conn, &lobby_state, alias)?.nations {
+        NationDetails::Lobby(lobby_details) => lobby_details,
+        NationDetails::Started(_) => {
+            return Err(CommandError::from(format!("{} isn't a lobby", alias)))
+        }
+    };
+    Ok(match lobby_details.option_open_nations {
+        Some(ref open_nations) if open_nations.is_empty() => {
+            format!("Every nation in {} has been claimed", alias)
+        }
+        Some(open_nations) => {
+            let nation_names: Vec<String> = open_nations
+                .iter()
+                .map(|open_nation| {
+                    format!("{} ({})", open_nation.nation_name, open_nation.nation_id)
+                })
+                .collect();
+            format!("Still open in {}: {}", alias, nation_names.join(", "))
+        }
+        None => format!(
+            "{} has {}",
+            alias,
+            show_open_slots(lobby_details.remaining_slots)
+        ),
+    })
+}
+
+pub fn nation_set(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_nations = args.single_quoted::<String>().map_err(|_| {
+        CommandError::from(
+            "Expected a comma separated list of nations, e.g. \"ulm, 5, mictlan\", or --clear",
+        )
+    })?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = nation_set_helper(db_conn, message.author.id, &alias, &arg_nations)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+    info!("nation_set: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
+
+pub fn open_slots(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = open_slots_helper(db_conn, &alias)?;
+    info!("open_slots: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+use crate::model::enums::Era;
+
+const OWNER: UserId = UserId(1);
+
+#[test]
+fn should_show_slot_count_without_a_nation_set() {
+    let db_conn = DbConnection::test();
+    // Ulm
+    db_conn.insert_test_lobby("open lobby", OWNER, Era::Early, 4, &[(322, 7)]);
+
+    let text = open_slots_helper(&db_conn, "open lobby").unwrap();
+    assert_eq!(text, "open lobby has 3 open slots");
+}
+
+#[test]
+fn should_list_nations_nobody_has_claimed() {
+    let db_conn = DbConnection::test();
+    // Ulm
+    db_conn.insert_test_lobby("open lobby", OWNER, Era::Early, 4, &[(322, 7)]);
+
+    // Arcoscephale, Ulm and nation 8 by its ID
+    nation_set_helper(&db_conn, OWNER, "open lobby", "arco, ulm,8").unwrap();
+    db_conn.reserve_nation_for_ai("open lobby", 8).unwrap();
+
+    let text = open_slots_helper(&db_conn, "open lobby").unwrap();
+    assert_eq!(text, "Still open in open lobby: Arcoscephale (5)");
+
+    nation_set_helper(&db_conn, OWNER, "open lobby", CLEAR_FLAG).unwrap();
+    let text = open_slots_helper(&db_conn, "open lobby").unwrap();
+    assert_eq!(text, "open lobby has 3 open slots");
+}
+
+#[test]
+fn should_only_let_the_owner_set_nations() {
+    let db_conn = DbConnection::test();
+    // Ulm
+    db_conn.insert_test_lobby("open lobby", OWNER, Era::Early, 4, &[(322, 7)]);
+
+    assert!(nation_set_helper(&db_conn, UserId(322), "open lobby", "ulm").is_err());
+    assert!(nation_set_helper(&db_conn, OWNER, "open lobby", " , ").is_err());
+}
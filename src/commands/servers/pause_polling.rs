@@ -0,0 +1,100 @@
+use chrono::{DateTime, Duration, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of how long to start polling again straight away
+const CLEAR_FLAG: &str = "--clear";
+/// A server that's down for longer than this isn't maintenance, archive it
+const MAX_PAUSE_HOURS: u32 = 7 * 24;
+
+fn pause_polling_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    option_hours: Option<u32>,
+    clear: bool,
+    now: DateTime<Utc>,
+) -> Result<String, CommandError> {
+    if clear {
+        db_conn.set_polling_paused_for_alias(alias, false, None)?;
+        return Ok(format!("{} will be polled again from the next pass", alias));
+    }
+    match db_conn.game_for_alias(alias)?.state {
+        GameServerState::StartedState(_, _) => (),
+        GameServerState::Lobby(_) => {
+            return Err(CommandError::from(format!(
+                "{} is a lobby, there's no server to poll yet",
+                alias
+            )))
+        }
+        GameServerState::Archived(_, _) => {
+            return Err(CommandError::from(format!(
+                "{} has finished, it isn't polled any more",
+                alias
+            )))
+        }
+    }
+    let option_until = match option_hours {
+        Some(hours) if hours == 0 || hours > MAX_PAUSE_HOURS => {
+            return Err(CommandError::from(format!(
+                "Polling can be paused for between 1 and {} hours",
+                MAX_PAUSE_HOURS
+            )))
+        }
+        Some(hours) => Some(now + Duration::hours(i64::from(hours))),
+        None => None,
+    };
+    db_conn.set_polling_paused_for_alias(alias, true, option_until)?;
+    Ok(match option_until {
+        Some(until) => format!(
+            "Stopped polling {} until {}, there won't be any announcements for it until then",
+            alias,
+            until.format("%d %b %Y %H:%M UTC")
+        ),
+        None => format!(
+            "Stopped polling {}, there won't be any announcements for it until you use !pausepolling {}",
+            alias, CLEAR_FLAG
+        ),
+    })
+}
+
+pub fn pause_polling(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let clear = args.single_quoted_n::<String>().ok() == Some(CLEAR_FLAG.to_owned());
+    if clear {
+        args.skip();
+    }
+    let option_hours = match args.single_quoted_n::<u32>() {
+        Ok(hours) if !clear => {
+            args.skip();
+            Some(hours)
+        }
+        _ => None,
+    };
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = pause_polling_helper(db_conn, &alias, option_hours, clear, Utc::now())?;
+    info!("pause_polling: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
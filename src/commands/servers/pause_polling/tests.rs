@@ -0,0 +1,58 @@
+use super::*;
+
+use crate::model::enums::Era;
+use crate::model::{GameServer, LobbyState};
+use serenity::model::id::UserId;
+
+#[test]
+fn should_pause_until_the_timer_runs_out() {
+    let db_conn = DbConnection::test();
+    let now = Utc::now();
+    pause_polling_helper(&db_conn, "test server 1", Some(2), false, now).unwrap();
+    let pauses = db_conn.polling_pauses(now).unwrap();
+    assert_eq!(pauses.len(), 1);
+    assert!(pauses["test server 1"].is_some());
+    assert!(db_conn
+        .polling_pauses(now + Duration::hours(2))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_pause_until_cleared() {
+    let db_conn = DbConnection::test();
+    let now = Utc::now();
+    pause_polling_helper(&db_conn, "test server 1", None, false, now).unwrap();
+    assert_eq!(
+        db_conn
+            .polling_pauses(now + Duration::days(365))
+            .unwrap()
+            .get("test server 1"),
+        Some(&None)
+    );
+
+    pause_polling_helper(&db_conn, "test server 1", None, true, now).unwrap();
+    assert!(db_conn.polling_pauses(now).unwrap().is_empty());
+}
+
+#[test]
+fn should_only_pause_started_games_for_a_while() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(1),
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+    let now = Utc::now();
+    assert!(pause_polling_helper(&db_conn, "lobby", Some(2), false, now).is_err());
+    assert!(pause_polling_helper(&db_conn, "test server 1", Some(0), false, now).is_err());
+    assert!(pause_polling_helper(&db_conn, "test server 1", Some(1000), false, now).is_err());
+    assert!(pause_polling_helper(&db_conn, "no such game", Some(2), false, now).is_err());
+    assert!(db_conn.polling_pauses(now).unwrap().is_empty());
+}
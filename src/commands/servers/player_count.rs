@@ -0,0 +1,62 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::{alias_from_arg_or_channel_name, lobby_state_for_owner};
+use crate::commands::servers::GameCacheKey;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+fn player_count_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    player_count: i32,
+) -> Result<(), CommandError> {
+    lobby_state_for_owner(db_conn, alias, author_id)?;
+    if player_count <= 0 {
+        return Err(CommandError::from("A lobby needs at least one player"));
+    }
+    let registered_count = db_conn.players_with_nations_for_game_alias(alias)?.len() as i32;
+    if player_count < registered_count {
+        return Err(CommandError::from(format!(
+            "{} already has {} players registered, use !kick if you need fewer than that",
+            alias, registered_count
+        )));
+    }
+    db_conn.set_player_count(alias, player_count)?;
+    Ok(())
+}
+
+pub fn player_count(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let player_count = args.single_quoted::<i32>()?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    player_count_helper(db_conn, message.author.id, &alias, player_count)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+
+    let text = format!("{} now has room for {} players", alias, player_count);
+    info!("{}", text);
+    message.reply(&text)?;
+    Ok(())
+}
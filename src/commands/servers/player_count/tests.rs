@@ -0,0 +1,66 @@
+use super::*;
+
+use crate::commands::servers::{lobby_details, show_open_slots, NationDetails};
+use crate::model::enums::Era;
+use crate::model::GameServerState;
+
+const OWNER: UserId = UserId(1);
+
+fn remaining_slots(db_conn: &DbConnection) -> Option<u32> {
+    match db_conn.game_for_alias("lobby").unwrap().state {
+        GameServerState::Lobby(lobby_state) => {
+            match lobby_details(db_conn, &lobby_state, "lobby")
+                .unwrap()
+                .nations
+            {
+                NationDetails::Lobby(lobby_details) => lobby_details.remaining_slots,
+                NationDetails::Started(_) => panic!("lobby had started details"),
+            }
+        }
+        GameServerState::StartedState(_, _) | GameServerState::Archived(_, _) => {
+            panic!("lobby had started")
+        }
+    }
+}
+
+#[test]
+fn should_open_more_slots() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Middle, 3, &[(201, 201), (202, 202)]);
+    assert_eq!(remaining_slots(&db_conn), Some(1));
+
+    player_count_helper(&db_conn, OWNER, "lobby", 6).unwrap();
+    assert_eq!(remaining_slots(&db_conn), Some(4));
+}
+
+#[test]
+fn should_not_shrink_below_registered_players() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Middle, 3, &[(201, 201), (202, 202)]);
+
+    assert!(player_count_helper(&db_conn, OWNER, "lobby", 1).is_err());
+    player_count_helper(&db_conn, OWNER, "lobby", 2).unwrap();
+    assert_eq!(remaining_slots(&db_conn), Some(0));
+}
+
+#[test]
+fn should_only_let_the_owner_change_player_count() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Middle, 3, &[(201, 201), (202, 202)]);
+
+    assert!(player_count_helper(&db_conn, UserId(201), "lobby", 6).is_err());
+    assert_eq!(remaining_slots(&db_conn), Some(1));
+}
+
+#[test]
+fn should_not_guess_slots_for_lobby_without_player_count() {
+    let db_conn = DbConnection::test();
+    // As a lobby imported without a player count is stored
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Middle, 0, &[]);
+    assert_eq!(remaining_slots(&db_conn), None);
+    assert_eq!(show_open_slots(None), "open slots: unknown");
+    assert_eq!(show_open_slots(Some(2)), "2 open slots");
+
+    player_count_helper(&db_conn, OWNER, "lobby", 4).unwrap();
+    assert_eq!(remaining_slots(&db_conn), Some(4));
+}
@@ -0,0 +1,111 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+use std::collections::BTreeMap;
+
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::Nations;
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+/// A game someone's registered in, and as what
+pub struct PlayerNation {
+    pub alias: String,
+    pub nation_id: u32,
+    pub nation_name: String,
+}
+
+/// `player_nations` in the order they should be listed
+fn show_player_nations(player_nations: &[PlayerNation]) -> String {
+    if player_nations.is_empty() {
+        return "They aren't registered in any games".to_owned();
+    }
+
+    let mut text = format!("Registered in {} game(s):\n", player_nations.len());
+    for player_nation in player_nations {
+        text.push_str(&format!(
+            "- {}: {} ({})\n",
+            player_nation.alias, player_nation.nation_name, player_nation.nation_id
+        ));
+    }
+
+    // BTreeMap so they come out the same way each time
+    let mut aliases_by_nation: BTreeMap<u32, Vec<&str>> = BTreeMap::new();
+    for player_nation in player_nations {
+        aliases_by_nation
+            .entry(player_nation.nation_id)
+            .or_default()
+            .push(&player_nation.alias);
+    }
+    for (nation_id, aliases) in aliases_by_nation {
+        if aliases.len() >= 2 {
+            let nation_name = player_nations
+                .iter()
+                .find(|player_nation| player_nation.nation_id == nation_id)
+                .map_or_else(String::new, |player_nation| {
+                    player_nation.nation_name.clone()
+                });
+            text.push_str(&format!(
+                "Playing {} ({}) in {} games: {}\n",
+                nation_name,
+                nation_id,
+                aliases.len(),
+                aliases.join(", ")
+            ));
+        }
+    }
+    text
+}
+
+fn player_nations_helper(db_conn: &DbConnection, user_id: UserId) -> Result<String, CommandError> {
+    let mut player_nations = vec![];
+    for (server, nation_id) in db_conn.servers_for_player(user_id)? {
+        // Finished games don't spread anyone thin
+        if let GameServerState::Archived(_, _) = server.state {
+            continue;
+        }
+        // The names the game itself used, for mods
+        let nation_name = match db_conn
+            .nation_names_for_alias(&server.alias)?
+            .remove(&nation_id)
+        {
+            Some(nation_name) => nation_name,
+            None => Nations::get_nation_name(nation_id),
+        };
+        player_nations.push(PlayerNation {
+            alias: server.alias,
+            nation_id,
+            nation_name,
+        });
+    }
+    player_nations.sort_by(|a, b| a.alias.cmp(&b.alias));
+    Ok(show_player_nations(&player_nations))
+}
+
+pub fn player_nations(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let user_id = args
+        .single_quoted::<UserId>()
+        .map_err(|_| CommandError::from("Expected an @mention of the player to look up"))?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = player_nations_helper(db_conn, user_id)?;
+    info!("player_nations: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
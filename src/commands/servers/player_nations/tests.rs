@@ -0,0 +1,57 @@
+use super::*;
+
+use crate::model::Player;
+
+fn player_nation(alias: &str, nation_id: u32) -> PlayerNation {
+    PlayerNation {
+        alias: alias.to_owned(),
+        nation_id,
+        nation_name: Nations::get_nation_name(nation_id),
+    }
+}
+
+#[test]
+fn should_flag_the_same_nation_in_several_games() {
+    let text = show_player_nations(&[
+        player_nation("a", 5),
+        player_nation("b", 6),
+        player_nation("c", 5),
+    ]);
+    assert!(text.starts_with("Registered in 3 game(s):\n"));
+    assert!(text.contains(&format!(
+        "Playing {} (5) in 2 games: a, c",
+        Nations::get_nation_name(5)
+    )));
+    assert!(!text.contains("(6) in"));
+
+    assert!(!show_player_nations(&[player_nation("a", 5)]).contains("Playing"));
+    assert_eq!(
+        show_player_nations(&[]),
+        "They aren't registered in any games"
+    );
+}
+
+#[test]
+fn should_list_every_game_the_player_is_in() {
+    let db_conn = DbConnection::test();
+    let player = Player {
+        discord_user_id: UserId(101),
+        turn_notifications: true,
+    };
+    // UserId(101) is already nation 1 in "test server 1"
+    db_conn
+        .insert_player_into_server(&player, "test server 2", 1)
+        .unwrap();
+    db_conn
+        .record_nation_names("test server 2", &[(1, "Modded Arcoscephale".to_owned())])
+        .unwrap();
+
+    let text = player_nations_helper(&db_conn, UserId(101)).unwrap();
+    assert!(text.contains("- test server 1: "));
+    assert!(text.contains("- test server 2: Modded Arcoscephale (1)"));
+    assert!(text.contains("(1) in 2 games: test server 1, test server 2"));
+    assert_eq!(
+        player_nations_helper(&db_conn, UserId(327)).unwrap(),
+        "They aren't registered in any games"
+    );
+}
@@ -0,0 +1,74 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::turn_check::{default_poll_interval_secs, BLITZ_POLL_INTERVAL_SECS};
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Any more often than this is just hammering someone else's server, unless
+/// it's a blitz game that's over in minutes
+const MIN_POLL_INTERVAL_SECS: u32 = 30;
+/// Any less often and turn history stops meaning much
+const MAX_POLL_INTERVAL_SECS: u32 = 60 * 60;
+
+/// "default" or how many seconds. `None` is the default.
+fn parse_poll_interval_secs(text: &str, blitz: bool) -> Result<Option<u32>, CommandError> {
+    let min_secs = if blitz {
+        BLITZ_POLL_INTERVAL_SECS
+    } else {
+        MIN_POLL_INTERVAL_SECS
+    };
+    match text {
+        "default" => Ok(None),
+        _ => match text.parse::<u32>() {
+            Ok(secs) if (min_secs..=MAX_POLL_INTERVAL_SECS).contains(&secs) => Ok(Some(secs)),
+            _ => Err(CommandError::from(format!(
+                "Poll intervals are \"default\" or a number of seconds between {} and {}",
+                min_secs, MAX_POLL_INTERVAL_SECS
+            ))),
+        },
+    }
+}
+
+fn poll_interval_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    text: &str,
+) -> Result<String, CommandError> {
+    let blitz = db_conn.blitz_for_alias(alias)?;
+    let option_secs = parse_poll_interval_secs(text, blitz)?;
+    db_conn.set_poll_interval_secs_for_alias(alias, option_secs)?;
+    Ok(format!(
+        "{} will be polled every {}s from its next poll",
+        alias,
+        option_secs.unwrap_or_else(|| default_poll_interval_secs(blitz))
+    ))
+}
+
+pub fn poll_interval(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let text = args.single_quoted::<String>()?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = poll_interval_helper(db_conn, &alias, &text)?;
+    info!("poll_interval: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
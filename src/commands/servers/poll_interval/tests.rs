@@ -0,0 +1,27 @@
+use super::*;
+
+#[test]
+fn should_parse_poll_interval_secs() {
+    assert_eq!(parse_poll_interval_secs("default", false).unwrap(), None);
+    assert_eq!(parse_poll_interval_secs("300", false).unwrap(), Some(300));
+    assert!(parse_poll_interval_secs("5", false).is_err());
+    assert!(parse_poll_interval_secs("100000", false).is_err());
+    assert!(parse_poll_interval_secs("often", false).is_err());
+    // blitz games can go down to every tick
+    assert!(parse_poll_interval_secs("20", false).is_err());
+    assert_eq!(parse_poll_interval_secs("20", true).unwrap(), Some(20));
+    assert!(parse_poll_interval_secs("5", true).is_err());
+}
+
+#[test]
+fn should_save_poll_interval_secs() {
+    let db_conn = DbConnection::test();
+    poll_interval_helper(&db_conn, "test server 1", "300").unwrap();
+    assert_eq!(
+        db_conn.poll_intervals_secs().unwrap().get("test server 1"),
+        Some(&300)
+    );
+    poll_interval_helper(&db_conn, "test server 1", "default").unwrap();
+    assert!(db_conn.poll_intervals_secs().unwrap().is_empty());
+    assert!(poll_interval_helper(&db_conn, "no such game", "300").is_err());
+}
@@ -0,0 +1,184 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::GuildId;
+use serenity::prelude::Context;
+
+use super::{admin_guild_id, GuildCommands, GuildCommandsKey, COMMAND_NAMES, DEFAULT_PREFIX};
+use crate::commands::search_command_names;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+const CLEAR_FLAG: &str = "--clear";
+const MAX_PREFIX_LENGTH: usize = 8;
+const MAX_ALIAS_LENGTH: usize = 32;
+
+fn is_builtin_command(name: &str) -> bool {
+    name == "help" || COMMAND_NAMES.contains(&name) || search_command_names().contains(&name)
+}
+
+fn prefix_helper(
+    db_conn: &DbConnection,
+    guild_commands: &GuildCommands,
+    guild_id: GuildId,
+    option_prefix: Option<&str>,
+) -> Result<String, CommandError> {
+    if let Some(prefix) = option_prefix {
+        if prefix.is_empty()
+            || prefix.chars().count() > MAX_PREFIX_LENGTH
+            || prefix.contains(char::is_whitespace)
+        {
+            return Err(CommandError::from(format!(
+                "A prefix is 1 to {} characters with no spaces",
+                MAX_PREFIX_LENGTH
+            )));
+        }
+    }
+    guild_commands.set_prefix(db_conn, guild_id, option_prefix)?;
+    let prefix = option_prefix.unwrap_or(DEFAULT_PREFIX);
+    Ok(format!(
+        "Commands here now start with {}, e.g. {}details",
+        prefix, prefix
+    ))
+}
+
+/// `None` command removes the alias
+fn alias_helper(
+    db_conn: &DbConnection,
+    guild_commands: &GuildCommands,
+    guild_id: GuildId,
+    alias: &str,
+    option_command: Option<&str>,
+) -> Result<String, CommandError> {
+    let prefix = guild_commands.prefix(Some(guild_id));
+    let alias = alias.to_lowercase();
+    let command = match option_command {
+        Some(command) => command.trim_start_matches(prefix.as_str()).to_lowercase(),
+        None => {
+            return Ok(if guild_commands.remove_alias(db_conn, guild_id, &alias)? {
+                format!("Removed {}{}", prefix, alias)
+            } else {
+                format!("There's no {}{} alias here", prefix, alias)
+            });
+        }
+    };
+    if alias.is_empty()
+        || alias.chars().count() > MAX_ALIAS_LENGTH
+        || !alias.chars().all(char::is_alphanumeric)
+    {
+        return Err(CommandError::from(format!(
+            "An alias is 1 to {} letters or numbers",
+            MAX_ALIAS_LENGTH
+        )));
+    }
+    if is_builtin_command(&alias) {
+        return Err(CommandError::from(format!(
+            "{}{} is already a command",
+            prefix, alias
+        )));
+    }
+    if !is_builtin_command(&command) {
+        return Err(CommandError::from(format!(
+            "There's no {}{} command to make an alias for",
+            prefix, command
+        )));
+    }
+    guild_commands.set_alias(db_conn, guild_id, &alias, &command)?;
+    Ok(format!(
+        "{}{} now does the same as {}{}",
+        prefix, alias, prefix, command
+    ))
+}
+
+fn list_aliases(guild_commands: &GuildCommands, guild_id: GuildId) -> String {
+    let prefix = guild_commands.prefix(Some(guild_id));
+    let aliases = guild_commands.aliases(Some(guild_id));
+    if aliases.is_empty() {
+        return "No command aliases here yet".to_owned();
+    }
+    let mut text = "Command aliases here:\n".to_owned();
+    for (alias, command) in aliases {
+        text.push_str(&format!("{}{} = {}{}\n", prefix, alias, prefix, command));
+    }
+    text
+}
+
+pub fn prefix(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_prefix = args.single_quoted::<String>()?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let option_prefix = if arg_prefix == CLEAR_FLAG {
+        None
+    } else {
+        Some(arg_prefix.as_str())
+    };
+    let guild_id = admin_guild_id(message)?;
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let guild_commands = data
+        .get::<GuildCommandsKey>()
+        .ok_or("No GuildCommands was created on startup. This is a bug.")?;
+    let text = prefix_helper(db_conn, guild_commands, guild_id, option_prefix)?;
+    info!("prefix: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
+
+pub fn alias(context: &mut Context, message: &Message, mut args: Args) -> Result<(), CommandError> {
+    let option_change = if args.is_empty() {
+        None
+    } else {
+        let arg_alias = args.single_quoted::<String>()?;
+        Some(if arg_alias == CLEAR_FLAG {
+            (args.single_quoted::<String>()?, None)
+        } else {
+            (arg_alias, Some(args.single_quoted::<String>()?))
+        })
+    };
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let guild_commands = data
+        .get::<GuildCommandsKey>()
+        .ok_or("No GuildCommands was created on startup. This is a bug.")?;
+    let text = match option_change {
+        None => {
+            let guild_id = message
+                .guild_id
+                .ok_or("Aliases are set per server, there are none in DMs")?;
+            list_aliases(guild_commands, guild_id)
+        }
+        Some((arg_alias, option_command)) => {
+            let guild_id = admin_guild_id(message)?;
+            let db_conn = data
+                .get::<DbConnectionKey>()
+                .ok_or("No DbConnection was created on startup. This is a bug.")?;
+            alias_helper(
+                db_conn,
+                guild_commands,
+                guild_id,
+                &arg_alias,
+                option_command.as_deref(),
+            )?
+        }
+    };
+    info!("alias: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+#[test]
+fn should_set_and_clear_prefix() {
+    let db_conn = DbConnection::test();
+    let guild_commands = GuildCommands::default();
+    let guild_id = GuildId(318_001);
+
+    prefix_helper(&db_conn, &guild_commands, guild_id, Some(".")).unwrap();
+    assert_eq!(guild_commands.prefix(Some(guild_id)), ".");
+    // other guilds and DMs keep the default
+    assert_eq!(guild_commands.prefix(Some(GuildId(318_002))), "!");
+    assert_eq!(guild_commands.prefix(None), "!");
+
+    prefix_helper(&db_conn, &guild_commands, guild_id, None).unwrap();
+    assert_eq!(guild_commands.prefix(Some(guild_id)), "!");
+
+    assert!(prefix_helper(&db_conn, &guild_commands, guild_id, Some("a b")).is_err());
+    assert!(prefix_helper(&db_conn, &guild_commands, guild_id, Some("")).is_err());
+}
+
+#[test]
+fn should_only_alias_new_names_to_real_commands() {
+    let db_conn = DbConnection::test();
+    let guild_commands = GuildCommands::default();
+    let guild_id = GuildId(318_003);
+
+    alias_helper(&db_conn, &guild_commands, guild_id, "d", Some("!details")).unwrap();
+    assert_eq!(
+        guild_commands.aliases(Some(guild_id)),
+        vec![("d".to_owned(), "details".to_owned())]
+    );
+    // can't hide a built-in command
+    assert!(alias_helper(&db_conn, &guild_commands, guild_id, "list", Some("delete")).is_err());
+    assert!(alias_helper(&db_conn, &guild_commands, guild_id, "x", Some("nope")).is_err());
+    // search commands count too
+    alias_helper(&db_conn, &guild_commands, guild_id, "i", Some("item")).unwrap();
+
+    let text = alias_helper(&db_conn, &guild_commands, guild_id, "d", None).unwrap();
+    assert!(text.starts_with("Removed"));
+    let text = alias_helper(&db_conn, &guild_commands, guild_id, "d", None).unwrap();
+    assert!(text.starts_with("There's no"));
+}
+
+#[test]
+fn should_expand_aliases_with_guild_prefix() {
+    let db_conn = DbConnection::test();
+    let guild_commands = GuildCommands::default();
+    let guild_id = GuildId(318_004);
+    prefix_helper(&db_conn, &guild_commands, guild_id, Some(".")).unwrap();
+    alias_helper(&db_conn, &guild_commands, guild_id, "d", Some("details")).unwrap();
+
+    assert_eq!(
+        guild_commands.expand_alias(Some(guild_id), ".d --all \"my game\""),
+        Some(".details --all \"my game\"".to_owned())
+    );
+    assert_eq!(
+        guild_commands.expand_alias(Some(guild_id), ".d"),
+        Some(".details".to_owned())
+    );
+    // not an alias, or not this guild's prefix
+    assert_eq!(
+        guild_commands.expand_alias(Some(guild_id), ".details"),
+        None
+    );
+    assert_eq!(guild_commands.expand_alias(Some(guild_id), "!d"), None);
+    assert_eq!(guild_commands.expand_alias(Some(guild_id), ".dd"), None);
+    assert_eq!(guild_commands.expand_alias(None, ".d"), None);
+
+    // and it all comes back after a restart
+    let reloaded = GuildCommands::load(&db_conn).unwrap();
+    assert_eq!(reloaded.prefix(Some(guild_id)), ".");
+    assert_eq!(
+        reloaded.expand_alias(Some(guild_id), ".d"),
+        Some(".details".to_owned())
+    );
+}
+
+#[test]
+fn should_know_about_every_registered_command() {
+    let registered: Vec<&str> = include_str!("../../servers.rs")
+        .split(".command(\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .collect();
+    assert_eq!(registered.len(), COMMAND_NAMES.len());
+    for name in registered {
+        assert!(
+            COMMAND_NAMES.contains(&name),
+            "{} isn't in COMMAND_NAMES",
+            name
+        );
+    }
+}
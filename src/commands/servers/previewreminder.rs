@@ -0,0 +1,116 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::turn_check::new_turn_message;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::server::ServerConnection;
+
+#[cfg(test)]
+mod tests;
+
+/// What `user_id` would be sent on the next new turn, or why they wouldn't be
+/// sent anything
+fn preview_reminder(
+    alias: &str,
+    nations: &NationDetails,
+    user_id: UserId,
+    option_template: Option<&str>,
+) -> String {
+    let playing_state = match nations {
+        NationDetails::Lobby(_) => {
+            return format!(
+                "{} hasn't started yet, reminders are only sent once it has",
+                alias
+            )
+        }
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Uploading(_),
+            ..
+        }) => {
+            return format!(
+                "{} is still uploading, there's no turn to be reminded about yet",
+                alias
+            )
+        }
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => playing_state,
+    };
+    let option_details =
+        playing_state
+            .players
+            .iter()
+            .find_map(|potential_player| match potential_player {
+                PotentialPlayer::RegisteredAndGame(player_user_id, details)
+                    if *player_user_id == user_id =>
+                {
+                    Some(details)
+                }
+                _ => None,
+            });
+    match option_details {
+        None => format!(
+            "You aren't playing a nation in {}, so no reminder would be sent",
+            alias
+        ),
+        Some(details) if !details.player_status.is_human() => format!(
+            "{} isn't being played by a human any more, so no reminder would be sent",
+            details.nation_name
+        ),
+        Some(_) if playing_state.paused => format!(
+            "{} is paused, so no reminder would be sent until it carries on",
+            alias
+        ),
+        Some(details) => format!(
+            "Your next reminder for {} will look like:\n{}",
+            alias,
+            new_turn_message(alias, playing_state, details, option_template)
+        ),
+    }
+}
+
+fn previewreminder_helper<C: ServerConnection>(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+    user_id: UserId,
+) -> Result<String, CommandError> {
+    let details = get_details_for_alias::<C>(db_conn, game_cache, alias)?;
+    let option_template = db_conn.reminder_template_for_alias(alias)?;
+    Ok(preview_reminder(
+        alias,
+        &details.nations,
+        user_id,
+        option_template.as_deref(),
+    ))
+}
+
+pub fn previewreminder<C: ServerConnection>(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = previewreminder_helper::<C>(db_conn, game_cache, &alias, message.author.id)?;
+    info!("previewreminder: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+use crate::model::enums::{NationStatus, SubmissionStatus};
+
+fn started_with(players: Vec<PotentialPlayer>, paused: bool) -> NationDetails {
+    NationDetails::Started(StartedDetails {
+        address: "example.com:3000".to_owned(),
+        game_name: "previewgame".to_owned(),
+        state: StartedStateDetails::Playing(PlayingState {
+            players,
+            turn: 12,
+            mins_remaining: 30,
+            hours_remaining: 5,
+            days_remaining: 0,
+            secs_remaining: 0,
+            option_deadline: None,
+            paused,
+            blitz: false,
+        }),
+        option_snek_game_id: None,
+    })
+}
+
+fn ulm(player_status: NationStatus) -> PotentialPlayer {
+    PotentialPlayer::RegisteredAndGame(
+        UserId(336),
+        PlayerDetails {
+            nation_id: 7,
+            nation_name: "Ulm".to_owned(),
+            // having already submitted doesn't change what the next turn's DM says
+            submitted: SubmissionStatus::Submitted,
+            player_status,
+            went_ai: false,
+            connected: false,
+        },
+    )
+}
+
+#[test]
+fn should_fill_in_the_games_template() {
+    let nations = started_with(vec![ulm(NationStatus::Human)], false);
+    assert_eq!(
+        preview_reminder(
+            "previewgame",
+            &nations,
+            UserId(336),
+            Some("%nation%, %hours%h left on turn %turn%")
+        ),
+        "Your next reminder for previewgame will look like:\nUlm, 5h left on turn 12"
+    );
+    assert_eq!(
+        preview_reminder("previewgame", &nations, UserId(336), None),
+        "Your next reminder for previewgame will look like:\n\
+         New turn in previewgame! You are Ulm and you have 5h 30m remaining for turn 12."
+    );
+}
+
+#[test]
+fn should_explain_when_no_reminder_would_be_sent() {
+    let nations = started_with(vec![ulm(NationStatus::Human)], false);
+    assert!(preview_reminder("previewgame", &nations, UserId(337), None)
+        .contains("no reminder would be sent"));
+
+    let nations = started_with(vec![ulm(NationStatus::AI)], false);
+    assert!(preview_reminder("previewgame", &nations, UserId(336), None)
+        .starts_with("Ulm isn't being played by a human"));
+
+    let nations = started_with(vec![ulm(NationStatus::Human)], true);
+    assert!(preview_reminder("previewgame", &nations, UserId(336), None)
+        .starts_with("previewgame is paused"));
+}
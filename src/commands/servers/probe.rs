@@ -0,0 +1,50 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::add_server::{probe_server, GameDataSummary, ServerPhase};
+use crate::server::ServerConnection;
+
+#[cfg(test)]
+mod tests;
+
+fn probe_helper<C: ServerConnection>(server_address: &str) -> Result<String, CommandError> {
+    let game_data = probe_server::<C>(server_address)?;
+    let summary = GameDataSummary::from_game_data(&game_data);
+    let era = summary
+        .option_era
+        .unwrap_or_else(|| "unknown (no nations yet)".to_owned());
+    let phase = match summary.phase {
+        ServerPhase::Lobby => format!(
+            "open lobby waiting for pretenders, {} nations uploaded so far",
+            summary.nation_count
+        ),
+        ServerPhase::Started => format!(
+            "game in progress on turn {}, {} nations",
+            summary.turn, summary.nation_count
+        ),
+    };
+    Ok(format!(
+        "Found {} at {}: {}, era {}. Use !add to add it.",
+        summary.game_name, server_address, phase, era
+    ))
+}
+
+pub fn probe<C: ServerConnection>(
+    _context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let server_address = args.single_quoted::<String>()?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let text = probe_helper::<C>(&server_address)?;
+    info!("probe: replying with: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use super::*;
+
+use crate::mock_server_connection;
+use crate::model::enums::{NationStatus, SubmissionStatus};
+use crate::model::{GameData, Nation};
+use std::io;
+
+#[test]
+fn should_summarise_game_without_adding_it() {
+    mock_server_connection!(
+        Mock,
+        Ok::<GameData, io::Error>(GameData {
+            game_name: "probed game".to_owned(),
+            nations: vec![Nation {
+                id: 7,
+                status: NationStatus::Human,
+                submitted: SubmissionStatus::NotSubmitted,
+                connected: false,
+                name: "Ulm".to_owned(),
+                era: "EA".to_owned(),
+            }],
+            turn: 3,
+            turn_timer: 0,
+        })
+    );
+
+    let text = probe_helper::<Mock>("address:1234").unwrap();
+    assert_eq!(
+        text,
+        "Found probed game at address:1234: game in progress on turn 3, 1 nations, era EA. Use !add to add it."
+    );
+}
+
+#[test]
+fn should_tell_lobbies_from_started_games() {
+    assert_eq!(ServerPhase::from_turn(-1), ServerPhase::Lobby);
+    assert_eq!(ServerPhase::from_turn(0), ServerPhase::Lobby);
+    assert_eq!(ServerPhase::from_turn(1), ServerPhase::Started);
+
+    mock_server_connection!(
+        Mock,
+        Ok::<GameData, io::Error>(GameData {
+            game_name: "new game".to_owned(),
+            nations: vec![],
+            turn: -1,
+            turn_timer: 0,
+        })
+    );
+    let text = probe_helper::<Mock>("address:1234").unwrap();
+    assert_eq!(
+        text,
+        "Found new game at address:1234: open lobby waiting for pretenders, 0 nations uploaded so far, era unknown (no nations yet). Use !add to add it."
+    );
+}
+
+#[test]
+fn should_return_error_on_no_connection() {
+    mock_server_connection!(Mock, Err(io::Error::from_raw_os_error(-1)));
+
+    assert!(probe_helper::<Mock>("address:1234").is_err());
+}
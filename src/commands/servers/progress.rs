@@ -0,0 +1,131 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::undone::playing_state_for_alias;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::SubmissionStatus;
+
+#[cfg(test)]
+mod tests;
+
+/// Human nations that have their turn in, in the order the game lists them
+pub fn submitted_nations(playing_state: &PlayingState) -> Vec<&PlayerDetails> {
+    human_nations(playing_state)
+        .filter(|details| details.submitted == SubmissionStatus::Submitted)
+        .collect()
+}
+
+fn human_nations(playing_state: &PlayingState) -> impl Iterator<Item = &PlayerDetails> {
+    playing_state
+        .players
+        .iter()
+        .filter_map(|potential_player| match potential_player {
+            PotentialPlayer::RegisteredAndGame(_, details) | PotentialPlayer::GameOnly(details)
+                if details.player_status.is_human() =>
+            {
+                Some(details)
+            }
+            _ => None,
+        })
+}
+
+/// Who submitted since `option_bookmark`, the turn and submitted nations as of
+/// the last check. An old turn's bookmark is no use, so that's the same as none.
+pub fn progress_report(
+    alias: &str,
+    playing_state: &PlayingState,
+    option_bookmark: Option<&(i32, Vec<u32>)>,
+) -> String {
+    let submitted = submitted_nations(playing_state);
+    let still_to_go = human_nations(playing_state).count() - submitted.len();
+    let still_to_go = if still_to_go == 0 {
+        "everyone's in".to_owned()
+    } else {
+        format!("{} still to go", still_to_go)
+    };
+    match option_bookmark {
+        Some((turn, submitted_nation_ids)) if *turn == playing_state.turn as i32 => {
+            let newly_submitted: Vec<&str> = submitted
+                .iter()
+                .filter(|details| !submitted_nation_ids.contains(&details.nation_id))
+                .map(|details| details.nation_name.as_str())
+                .collect();
+            let since = if newly_submitted.is_empty() {
+                "nobody else has submitted".to_owned()
+            } else {
+                format!("{} submitted", newly_submitted.join(" and "))
+            };
+            format!(
+                "Since you last checked turn {} in {}: {}; {}",
+                playing_state.turn, alias, since, still_to_go
+            )
+        }
+        option_bookmark => {
+            let new_turn = if option_bookmark.is_some() {
+                "A new turn has started since you last checked. "
+            } else {
+                ""
+            };
+            format!(
+                "{}Turn {} in {}: {} submitted so far; {}",
+                new_turn,
+                playing_state.turn,
+                alias,
+                submitted.len(),
+                still_to_go
+            )
+        }
+    }
+}
+
+fn progress_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+    user_id: UserId,
+) -> Result<String, CommandError> {
+    let playing_state = playing_state_for_alias(db_conn, game_cache, alias)?;
+    let option_bookmark = db_conn.progress_bookmark(alias, user_id)?;
+    let text = progress_report(alias, &playing_state, option_bookmark.as_ref());
+    let submitted_nation_ids: Vec<u32> = submitted_nations(&playing_state)
+        .iter()
+        .map(|details| details.nation_id)
+        .collect();
+    db_conn.set_progress_bookmark(
+        alias,
+        user_id,
+        playing_state.turn as i32,
+        &submitted_nation_ids,
+    )?;
+    Ok(text)
+}
+
+pub fn progress(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = progress_helper(db_conn, game_cache, &alias, message.author.id)?;
+    info!("progress: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
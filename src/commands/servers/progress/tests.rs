@@ -0,0 +1,82 @@
+use super::*;
+
+use crate::model::enums::NationStatus;
+
+fn playing_state(turn: u32, submitted: &[SubmissionStatus]) -> PlayingState {
+    PlayingState {
+        players: submitted
+            .iter()
+            .enumerate()
+            .map(|(i, &submitted)| {
+                PotentialPlayer::GameOnly(PlayerDetails {
+                    nation_id: i as u32 + 1,
+                    nation_name: format!("nation {}", i + 1),
+                    submitted,
+                    player_status: NationStatus::Human,
+                    went_ai: false,
+                    connected: false,
+                })
+            })
+            .collect(),
+        turn,
+        mins_remaining: 0,
+        hours_remaining: 1,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: None,
+        paused: false,
+        blitz: false,
+    }
+}
+
+#[test]
+fn should_report_who_submitted_since_the_last_check() {
+    use crate::model::enums::SubmissionStatus::*;
+    let now = playing_state(5, &[Submitted, Submitted, PartiallySubmitted, Submitted]);
+
+    assert_eq!(
+        progress_report("foo", &now, Some(&(5, vec![1]))),
+        "Since you last checked turn 5 in foo: nation 2 and nation 4 submitted; 1 still to go"
+    );
+    assert_eq!(
+        progress_report("foo", &now, Some(&(5, vec![1, 2, 4]))),
+        "Since you last checked turn 5 in foo: nobody else has submitted; 1 still to go"
+    );
+    assert_eq!(
+        progress_report("foo", &now, None),
+        "Turn 5 in foo: 3 submitted so far; 1 still to go"
+    );
+    assert_eq!(
+        progress_report("foo", &playing_state(5, &[Submitted]), Some(&(4, vec![]))),
+        "A new turn has started since you last checked. Turn 5 in foo: 1 submitted so far; everyone's in"
+    );
+}
+
+#[test]
+fn should_keep_one_bookmark_per_player_and_game() {
+    let db_conn = DbConnection::test();
+    assert_eq!(
+        db_conn
+            .progress_bookmark("test server 1", UserId(101))
+            .unwrap(),
+        None
+    );
+    db_conn
+        .set_progress_bookmark("test server 1", UserId(101), 5, &[])
+        .unwrap();
+    db_conn
+        .set_progress_bookmark("test server 1", UserId(101), 6, &[1, 3])
+        .unwrap();
+    assert_eq!(
+        db_conn
+            .progress_bookmark("test server 1", UserId(101))
+            .unwrap(),
+        Some((6, vec![1, 3]))
+    );
+    assert_eq!(
+        db_conn
+            .progress_bookmark("test server 1", UserId(102))
+            .unwrap(),
+        None
+    );
+}
@@ -0,0 +1,63 @@
+use super::alias_from_arg_or_channel_name;
+
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::snek::{providers_from_names, ALL_PROVIDERS};
+
+#[cfg(test)]
+mod tests;
+
+/// "default" goes back to whatever games use when they haven't picked
+fn providers_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    names: &str,
+) -> Result<String, CommandError> {
+    if names.eq_ignore_ascii_case("default") {
+        db_conn.set_nation_providers_for_alias(alias, None)?;
+        return Ok(format!(
+            "{} will use the default nation name providers",
+            alias
+        ));
+    }
+    let providers = providers_from_names(names)?;
+    let provider_names: Vec<&str> = providers.iter().map(|provider| provider.name()).collect();
+    let provider_names = provider_names.join(",");
+    db_conn.set_nation_providers_for_alias(alias, Some(&provider_names))?;
+    Ok(format!(
+        "{} will look up nation names from: {}",
+        alias, provider_names
+    ))
+}
+
+pub fn providers(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+
+    let names = args.single_quoted::<String>().map_err(|_| {
+        let known: Vec<&str> = ALL_PROVIDERS.iter().map(|p| p.name()).collect();
+        CommandError::from(format!(
+            "Expected a comma separated list of providers or \"default\", known providers are: {}",
+            known.join(", ")
+        ))
+    })?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let text = providers_helper(db_conn, &alias, &names)?;
+    message.reply(&text)?;
+    Ok(())
+}
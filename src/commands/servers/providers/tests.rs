@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn should_store_providers_in_order() {
+    let db_conn = DbConnection::test();
+    assert_eq!(
+        db_conn.nation_providers_for_alias("test server 1").unwrap(),
+        None
+    );
+
+    providers_helper(&db_conn, "test server 1", "SNEK").unwrap();
+    assert_eq!(
+        db_conn.nation_providers_for_alias("test server 1").unwrap(),
+        Some("snek".to_owned())
+    );
+
+    providers_helper(&db_conn, "test server 1", "default").unwrap();
+    assert_eq!(
+        db_conn.nation_providers_for_alias("test server 1").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn should_reject_unknown_providers() {
+    let db_conn = DbConnection::test();
+    assert!(providers_helper(&db_conn, "test server 1", "snek,not a provider").is_err());
+    assert_eq!(
+        db_conn.nation_providers_for_alias("test server 1").unwrap(),
+        None
+    );
+}
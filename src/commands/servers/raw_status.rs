@@ -0,0 +1,122 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+use crate::server::{game_data_from_response, response_payload, ServerConnection};
+
+#[cfg(test)]
+mod tests;
+
+/// Enough to see the game name and the start of the nation arrays while
+/// leaving the reply comfortably under Discord's 2000 characters
+const MAX_DUMP_BYTES: usize = 256;
+const BYTES_PER_LINE: usize = 16;
+
+/// The usual offset, hex and ASCII columns
+pub fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(BYTES_PER_LINE)
+        .enumerate()
+        .map(|(ix, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!(
+                "{:08x}  {:<width$}  {}",
+                ix * BYTES_PER_LINE,
+                hex.join(" "),
+                ascii,
+                width = BYTES_PER_LINE * 3 - 1
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn raw_status_helper<C: ServerConnection>(
+    db_conn: &DbConnection,
+    alias: &str,
+) -> Result<String, CommandError> {
+    let server_address = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::StartedState(started_state, _)
+        | GameServerState::Archived(started_state, _) => started_state.address,
+        GameServerState::Lobby(_) => {
+            return Err(CommandError::from(format!(
+                "{} hasn't started yet, there's no server to ask",
+                alias
+            )))
+        }
+    };
+    let raw = C::get_raw_response(&server_address)?;
+    // Compressed bytes say nothing, so show what the parser would have seen
+    let (payload, description) = match response_payload(&raw) {
+        Ok(payload) if raw.get(1) == Some(&b'J') => {
+            let description = format!(
+                "{} bytes after decompressing {} from the server",
+                payload.len(),
+                raw.len()
+            );
+            (payload, description)
+        }
+        Ok(_) | Err(_) => (raw.clone(), format!("{} bytes from the server", raw.len())),
+    };
+    let parsed = match game_data_from_response(&raw) {
+        Ok(game_data) => format!(
+            "parses as {} on turn {} with {} nations",
+            game_data.game_name,
+            game_data.turn,
+            game_data.nations.len()
+        ),
+        Err(e) => format!("doesn't parse: {}", e),
+    };
+    let shown = payload.len().min(MAX_DUMP_BYTES);
+    let truncated = if shown < payload.len() {
+        format!(", first {} shown", shown)
+    } else {
+        String::new()
+    };
+    Ok(format!(
+        "{} ({}) sent {}{}, and it {}\n```\n{}\n```",
+        alias,
+        server_address,
+        description,
+        truncated,
+        parsed,
+        hex_dump(&payload[..shown])
+    ))
+}
+
+/// Bot owners only, see `owners_only` where it's registered
+pub fn raw_status<C: ServerConnection>(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = raw_status_helper::<C>(db_conn, &alias)?;
+    info!("raw_status: replying with: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use super::*;
+
+use crate::model::enums::Era;
+use crate::model::{GameServer, LobbyState, StartedState};
+use crate::server::test_helpers::RecordingServerConnection;
+use serenity::model::id::UserId;
+
+#[test]
+fn should_hex_dump_with_offsets_and_ascii() {
+    let bytes: Vec<u8> = (b'A'..=b'R').chain(vec![0, 255]).collect();
+    assert_eq!(
+        hex_dump(&bytes),
+        "00000000  41 42 43 44 45 46 47 48 49 4a 4b 4c 4d 4e 4f 50  ABCDEFGHIJKLMNOP\n\
+         00000010  51 52 00 ff                                      QR.."
+    );
+}
+
+#[test]
+fn should_dump_responses_that_do_not_parse() {
+    const ADDRESS: &str = "recording.test.raw:1234";
+    let mut raw = b"fH".to_vec();
+    raw.extend_from_slice(&[4, 0, 0, 0, 0, 0, 0, 0]);
+    raw.extend(std::iter::repeat_n(b'x', 300));
+    RecordingServerConnection::script_raw_response(ADDRESS, raw);
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "raw game".to_owned(),
+            state: GameServerState::StartedState(
+                StartedState {
+                    address: ADDRESS.to_owned(),
+                    last_seen_turn: 1,
+                    option_snek_game_id: None,
+                },
+                None,
+            ),
+        })
+        .unwrap();
+
+    let text = raw_status_helper::<RecordingServerConnection>(&db_conn, "raw game").unwrap();
+    assert!(text.starts_with(
+        "raw game (recording.test.raw:1234) sent 310 bytes from the server, first 256 shown, and it doesn't parse"
+    ));
+    assert!(text.contains("\n00000000  66 48 04 00 00 00 00 00 00 00 78 78"));
+    assert!(text.contains("\n000000f0  "));
+    assert!(!text.contains("\n00000100  "));
+}
+
+#[test]
+fn should_need_a_started_game_to_ask() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "raw lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(3895),
+                era: Era::Early,
+                player_count: 4,
+                description: None,
+            }),
+        })
+        .unwrap();
+    assert!(raw_status_helper::<RecordingServerConnection>(&db_conn, "raw lobby").is_err());
+    assert!(raw_status_helper::<RecordingServerConnection>(&db_conn, "no such game").is_err());
+}
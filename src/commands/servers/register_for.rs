@@ -0,0 +1,142 @@
+use either::Either;
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::register_player::get_nation_for_lobby;
+use super::{
+    alias_from_arg_or_channel_name, author_is_game_admin, lobby_state_for_alias,
+    lobby_state_for_owner,
+};
+use crate::commands::servers::{lobby_details, GameCacheKey, NationDetails};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::Era;
+use crate::model::Player;
+use crate::notifier::DiscordNotifier;
+
+#[cfg(test)]
+mod tests;
+
+/// Like `!register`, but for somebody else. Lobby owners can do this for their
+/// own lobbies, and server admins (or the `!adminrole`) for any lobby posting in
+/// their server.
+fn register_for_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    author_is_admin: bool,
+    target_id: UserId,
+    alias: &str,
+    arg_nation: Either<&str, u32>,
+    option_arg_era: Option<Era>,
+) -> Result<String, CommandError> {
+    let lobby_state = if author_is_admin {
+        lobby_state_for_alias(db_conn, alias)?
+    } else {
+        lobby_state_for_owner(db_conn, alias, author_id)?
+    };
+    if let Some(arg_era) = option_arg_era {
+        if arg_era != lobby_state.era {
+            return Err(CommandError::from(format!(
+                "{} is an {} lobby",
+                alias, lobby_state.era
+            )));
+        }
+    }
+    let nation = get_nation_for_lobby(arg_nation, lobby_state.era)?;
+
+    let players_nations = db_conn.players_with_nations_for_game_alias(alias)?;
+    if let Some((player, _)) = players_nations
+        .iter()
+        .find(|&&(_, nation_id)| nation_id == nation.id)
+    {
+        return Err(CommandError::from(format!(
+            "{} ({}) is already registered to <@{}> in {}",
+            nation.name, nation.id, player.discord_user_id, alias
+        )));
+    }
+    let ai_reserved = db_conn
+        .ai_reserved_nations_for_alias(alias)?
+        .contains(&nation.id);
+
+    let full = match lobby_details(db_conn, &lobby_state, alias)?.nations {
+        NationDetails::Lobby(lobby_details) => lobby_details.is_full(),
+        NationDetails::Started(_) => {
+            return Err(CommandError::from(
+                "Lobby details were for a started game. This is a bug.",
+            ))
+        }
+    };
+    // Reserved nations don't take up a slot, so can always be registered
+    if full && !ai_reserved {
+        return Err(CommandError::from(format!("{} is already full", alias)));
+    }
+
+    let player = Player {
+        discord_user_id: target_id,
+        turn_notifications: true,
+    };
+    db_conn.insert_player_into_server(&player, alias, nation.id)?;
+    let mut text = format!(
+        "Registered {} ({}) for <@{}> in {}",
+        nation.name, nation.id, target_id, alias
+    );
+    if ai_reserved {
+        text.push_str(&format!(
+            ". Warning: {} is reserved for the AI, `!reserveai --clear` it if they're really playing it",
+            nation.name
+        ));
+    }
+    Ok(text)
+}
+
+pub fn register_for(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let target_id = args
+        .single_quoted::<UserId>()
+        .map_err(|_| CommandError::from("Expected an @mention of the player to register"))?;
+    let arg_nation = args.single_quoted::<String>()?.to_lowercase();
+    let option_arg_era = args
+        .single_quoted_n::<String>()
+        .ok()
+        .and_then(|arg| Era::from_string(&arg));
+    if option_arg_era.is_some() {
+        args.skip();
+    }
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let arg_nation = match arg_nation.parse::<u32>() {
+        Ok(nation_id) => Either::Right(nation_id),
+        Err(_) => Either::Left(arg_nation.as_str()),
+    };
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let author_is_admin = author_is_game_admin::<DiscordNotifier>(db_conn, message, &alias)?;
+    let text = register_for_helper(
+        db_conn,
+        message.author.id,
+        author_is_admin,
+        target_id,
+        &alias,
+        arg_nation,
+        option_arg_era,
+    )?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+    info!("register_for: replying with: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
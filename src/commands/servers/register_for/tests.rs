@@ -0,0 +1,65 @@
+use super::*;
+
+const OWNER: UserId = UserId(1);
+
+fn register(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    author_is_admin: bool,
+    target_id: UserId,
+    nation_id: u32,
+) -> Result<String, CommandError> {
+    register_for_helper(
+        db_conn,
+        author_id,
+        author_is_admin,
+        target_id,
+        "lobby",
+        Either::Right(nation_id),
+        None,
+    )
+}
+
+#[test]
+fn should_register_another_player_for_owner() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 2, &[]);
+
+    let text = register(&db_conn, OWNER, false, UserId(201), 5).unwrap();
+    assert!(text.contains("<@201>"));
+    let players_nations = db_conn
+        .players_with_nations_for_game_alias("lobby")
+        .unwrap();
+    assert_eq!(players_nations.len(), 1);
+    assert_eq!(players_nations[0].0.discord_user_id, UserId(201));
+    assert_eq!(players_nations[0].1, 5);
+}
+
+#[test]
+fn should_only_let_owner_or_admin_register_others() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 2, &[]);
+
+    assert!(register(&db_conn, UserId(2), false, UserId(201), 5).is_err());
+    assert!(register(&db_conn, UserId(2), true, UserId(201), 5).is_ok());
+}
+
+#[test]
+fn should_say_who_already_has_the_nation() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 3, &[]);
+    register(&db_conn, OWNER, false, UserId(201), 5).unwrap();
+
+    let err = register(&db_conn, OWNER, false, UserId(202), 5).unwrap_err();
+    assert!(err.0.contains("<@201>"));
+}
+
+#[test]
+fn should_not_register_into_a_full_lobby() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 1, &[]);
+    register(&db_conn, OWNER, false, UserId(201), 5).unwrap();
+
+    let err = register(&db_conn, OWNER, false, UserId(202), 7).unwrap_err();
+    assert!(err.0.contains("full"));
+}
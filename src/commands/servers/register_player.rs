@@ -6,17 +6,204 @@ use serenity::prelude::Context;
 use std::str::FromStr;
 
 use super::alias_from_arg_or_channel_name;
+use super::roster::join_roster;
 use crate::commands::servers::*;
 use crate::db::{DbConnection, DbConnectionKey};
+use crate::fuzzy::edit_distance;
 use crate::model::enums::*;
 use crate::model::{GameServerState, Player};
 use crate::server::ServerConnection;
+use crate::snek::SnekGameStatus;
 use either::Either;
 
+#[cfg(test)]
+mod tests;
+
+/// How many suggestions a failed nation lookup comes back with
+const MAX_NATION_SUGGESTIONS: usize = 3;
+
+fn sanitise_nation_name(name: &str) -> String {
+    name.to_lowercase().replace("'", "").replace(" ", "")
+}
+
+/// The few `candidates` whose names are closest to `arg_nation_name`, best first,
+/// for when nothing matched it exactly. Typos in just the start of a name count
+/// too, so "arcas" still finds Arcoscephale. Nothing that's more wrong than right.
+pub fn closest_nations(arg_nation_name: &str, candidates: &[Nation]) -> Vec<Nation> {
+    let sanitised_name = sanitise_nation_name(arg_nation_name);
+    let max_distance = (sanitised_name.chars().count() / 3).max(1);
+    let mut scored: Vec<(usize, &Nation)> = candidates
+        .iter()
+        .filter_map(|nation| {
+            let candidate_name = sanitise_nation_name(&nation.name);
+            let candidate_start: String = candidate_name
+                .chars()
+                .take(sanitised_name.chars().count())
+                .collect();
+            let distance = edit_distance(&sanitised_name, &candidate_name)
+                .min(edit_distance(&sanitised_name, &candidate_start));
+            if distance <= max_distance {
+                Some((distance, nation))
+            } else {
+                None
+            }
+        })
+        .collect();
+    scored.sort_by(|(distance1, nation1), (distance2, nation2)| {
+        distance1
+            .cmp(distance2)
+            .then(nation1.name.cmp(&nation2.name))
+            .then(nation1.id.cmp(&nation2.id))
+    });
+    scored
+        .into_iter()
+        .take(MAX_NATION_SUGGESTIONS)
+        .map(|(_, nation)| nation.clone())
+        .collect()
+}
+
+pub fn show_nation_candidate(nation: &Nation) -> String {
+    match nation.era {
+        Some(era) => format!("{} ({} {})", nation.name, era, nation.id),
+        None => format!("{} ({})", nation.name, nation.id),
+    }
+}
+
+/// The "not found" error, with the closest `candidates` if any are close
+pub fn nation_not_found(arg_nation_name: &str, candidates: &[Nation]) -> CommandError {
+    let suggestions: Vec<String> = closest_nations(arg_nation_name, candidates)
+        .iter()
+        .map(show_nation_candidate)
+        .collect();
+    if suggestions.is_empty() {
+        CommandError::from(format!(
+            "Could not find nation starting with \"{}\"",
+            arg_nation_name
+        ))
+    } else {
+        CommandError::from(format!(
+            "Could not find nation starting with \"{}\". Did you mean: {}?",
+            arg_nation_name,
+            suggestions.join(", ")
+        ))
+    }
+}
+
+/// Looks up a nation by (the start of) its name. Names from snek are tried first
+/// since they know about modded nations, then the built in nations. A name that
+/// matches exactly wins over ones it's just the start of.
+pub fn nation_from_name(
+    arg_nation_name: &str,
+    option_era: Option<Era>,
+    option_snek_state: Option<&SnekGameStatus>,
+) -> Result<Nation, CommandError> {
+    let sanitised_name = sanitise_nation_name(arg_nation_name);
+
+    let all_snek_nations: Vec<Nation> = option_snek_state
+        .map(|snek_state| {
+            snek_state
+                .nations
+                .values()
+                .map(|snek_nation| Nation {
+                    id: snek_nation.nation_id,
+                    name: snek_nation.name.clone(),
+                    // Modded nations aren't in our table so we can't tell their era
+                    era: Nations::from_id(snek_nation.nation_id).and_then(|nation| nation.era),
+                })
+                .filter(|nation| match (option_era, nation.era) {
+                    (Some(era), Some(nation_era)) => era == nation_era,
+                    _ => true,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let snek_nations: Vec<Nation> = all_snek_nations
+        .iter()
+        .filter(|nation| sanitise_nation_name(&nation.name).starts_with(&sanitised_name))
+        .cloned()
+        .collect();
+    let possible_nations = if snek_nations.is_empty() {
+        Nations::from_name_prefix(arg_nation_name, option_era)
+    } else {
+        snek_nations
+    };
+    pick_nation(
+        arg_nation_name,
+        possible_nations,
+        || {
+            // snek's name for a nation wins over ours
+            let mut candidates = all_snek_nations;
+            for nation in Nations::from_name_prefix("", option_era) {
+                if !candidates.iter().any(|candidate| candidate.id == nation.id) {
+                    candidates.push(nation);
+                }
+            }
+            candidates
+        },
+        "Add the era (EA/MA/LA) after the name, or use !register-id.",
+    )
+}
+
+/// Like `nation_from_name`, but only ever one of `nations`, e.g. the ones in a
+/// game. Suggestions for a typo come from them too.
+pub fn nation_from_name_among(
+    arg_nation_name: &str,
+    nations: &[Nation],
+    ambiguous_hint: &str,
+) -> Result<Nation, CommandError> {
+    let sanitised_name = sanitise_nation_name(arg_nation_name);
+    let possible_nations = nations
+        .iter()
+        .filter(|nation| sanitise_nation_name(&nation.name).starts_with(&sanitised_name))
+        .cloned()
+        .collect();
+    pick_nation(
+        arg_nation_name,
+        possible_nations,
+        || nations.to_vec(),
+        ambiguous_hint,
+    )
+}
+
+/// The one of `possible_nations` that `arg_nation_name` must mean. A name that
+/// matches exactly wins over ones it's just the start of.
+fn pick_nation<F: FnOnce() -> Vec<Nation>>(
+    arg_nation_name: &str,
+    mut possible_nations: Vec<Nation>,
+    candidates: F,
+    ambiguous_hint: &str,
+) -> Result<Nation, CommandError> {
+    let sanitised_name = sanitise_nation_name(arg_nation_name);
+    let exact_nations: Vec<&Nation> = possible_nations
+        .iter()
+        .filter(|nation| sanitise_nation_name(&nation.name) == sanitised_name)
+        .collect();
+    if exact_nations.len() == 1 {
+        return Ok(exact_nations[0].clone());
+    }
+
+    match possible_nations.len() {
+        0 => Err(nation_not_found(arg_nation_name, &candidates())),
+        1 => Ok(possible_nations.remove(0)),
+        _ => {
+            possible_nations.sort_by_key(|nation| nation.id);
+            let candidates: Vec<String> =
+                possible_nations.iter().map(show_nation_candidate).collect();
+            Err(CommandError::from(format!(
+                "Found more than one nation starting with \"{}\": {}. {}",
+                arg_nation_name,
+                candidates.join(", "),
+                ambiguous_hint
+            )))
+        }
+    }
+}
+
 fn get_nation_for_started_server(
     arg_nation: Either<&str, u32>,
     started_state_details: &StartedStateDetails,
     era: Option<Era>,
+    option_snek_state: Option<&SnekGameStatus>,
 ) -> Result<Nation, CommandError> {
     match arg_nation {
         Either::Left(arg_nation_name) => {
@@ -41,7 +228,18 @@ fn get_nation_for_started_server(
                     let possible_ingame_nations = possible_ingame_nations;
                     match possible_ingame_nations.len() {
                         // Could not find nation. Error.
-                        0 => Err(CommandError::from(format!("Could not find nation starting with \"{}\"", arg_nation_name))),
+                        0 => {
+                            let ingame_nations: Vec<Nation> = playing_state
+                                .players
+                                .iter()
+                                .map(|potential_player| Nation {
+                                    id: potential_player.nation_id(),
+                                    name: potential_player.nation_name().clone(),
+                                    era: None,
+                                })
+                                .collect();
+                            Err(nation_not_found(arg_nation_name, &ingame_nations))
+                        }
                         // Found nation!
                         1 => {
                             let found_nation = possible_ingame_nations[0];
@@ -74,20 +272,8 @@ fn get_nation_for_started_server(
                     }
                     let possible_ingame_nations = possible_ingame_nations;
                     match possible_ingame_nations.len() {
-                        // Could not find nation. Try again with base nations.
-                        0 => {
-                            let possible_base_nations = Nations::from_name_prefix(arg_nation_name, era);
-                            match possible_base_nations.len() {
-                                0 => Err(CommandError::from(format!("Could not find nation starting with \"{}\"", arg_nation_name))),
-                                1 => Ok(possible_base_nations[0].clone()),
-                                _ => Err(CommandError::from(format!(
-                                    "Found more than one nation starting with \"{}\". Consider using !register-id if the name is ambiguous.",
-                                    arg_nation_name
-                                ))),
-                            }
-                        },
-
-
+                        // Could not find nation. Try again with snek's and then the base nations.
+                        0 => nation_from_name(arg_nation_name, era, option_snek_state),
                         // Found nation!
                         1 => {
                             let found_nation = possible_ingame_nations[0];
@@ -145,26 +331,19 @@ fn get_nation_for_started_server(
     }
 }
 
-fn get_nation_for_lobby(arg_nation: Either<&str, u32>, era: Era) -> Result<Nation, CommandError> {
+pub fn get_nation_for_lobby(
+    arg_nation: Either<&str, u32>,
+    era: Era,
+) -> Result<Nation, CommandError> {
     match arg_nation {
-        Either::Left(arg_nation_name) => {
-            let nations = Nations::from_name_prefix(arg_nation_name, Some(era));
-            let nations_len = nations.len();
-            if nations_len > 1 {
-                return Err(CommandError::from(format!(
-                    "ambiguous nation name: {}",
-                    arg_nation_name
-                )));
-            } else if nations_len < 1 {
+        Either::Left(arg_nation_name) => nation_from_name(arg_nation_name, Some(era), None)
+            .or_else(|err| {
                 // try to parse the name as a number
-                let mk_err =
-                    || CommandError::from(format!("could not find nation: {}", arg_nation_name));
-                return u32::from_str(arg_nation_name)
-                    .map_err(|_| mk_err())
-                    .and_then(|arg_nation_id| Nations::from_id(arg_nation_id).ok_or_else(mk_err));
-            };
-            Ok(nations[0].clone())
-        }
+                u32::from_str(arg_nation_name)
+                    .ok()
+                    .and_then(Nations::from_id)
+                    .ok_or(err)
+            }),
         Either::Right(arg_nation_id) => Ok(Nations::from_id(arg_nation_id).unwrap_or(Nation {
             id: arg_nation_id,
             name: "Unknown Nation".to_string(),
@@ -176,10 +355,11 @@ fn get_nation_for_lobby(arg_nation: Either<&str, u32>, era: Era) -> Result<Natio
 fn register_player_helper<C: ServerConnection>(
     user_id: UserId,
     arg_nation: Either<&str, u32>,
+    option_arg_era: Option<Era>,
     alias: &str,
     db_conn: &DbConnection,
     message: &Message,
-    details_read_handle: &crate::CacheReadHandle,
+    game_cache: &GameCache,
 ) -> Result<(), CommandError> {
     info!(
         "Registering player {} for nation {} in game {}",
@@ -190,21 +370,52 @@ fn register_player_helper<C: ServerConnection>(
     match server.state {
         GameServerState::Lobby(lobby_state) => {
             let players_nations = db_conn.players_with_nations_for_game_alias(&alias)?;
-            if players_nations.len() as i32 >= lobby_state.player_count {
+            let ai_reserved_nation_ids = db_conn.ai_reserved_nations_for_alias(alias)?;
+            let taken_slots = players_nations
+                .iter()
+                .filter(|(_, nation_id)| !ai_reserved_nation_ids.contains(nation_id))
+                .count();
+            // With a roster, a full lobby means going on the waitlist instead
+            let has_roster = !db_conn.roster_for_alias(alias)?.is_empty();
+            let full = lobby_state
+                .option_player_count()
+                .is_some_and(|player_count| taken_slots as i32 >= player_count);
+            if !has_roster && full {
                 return Err(CommandError::from("lobby already full"));
             };
 
+            if let Some(arg_era) = option_arg_era {
+                if arg_era != lobby_state.era {
+                    return Err(CommandError::from(format!(
+                        "{} is an {} lobby",
+                        alias, lobby_state.era
+                    )));
+                }
+            }
             let nation = get_nation_for_lobby(arg_nation, lobby_state.era)?;
 
-            if players_nations
+            if let Some((claimant, _)) = players_nations
                 .iter()
-                .any(|&(_, player_nation_id)| player_nation_id == nation.id)
+                .find(|&&(_, player_nation_id)| player_nation_id == nation.id)
             {
                 return Err(CommandError::from(format!(
-                    "Nation {} already exists in lobby",
-                    nation.name
+                    "{} ({}) is already registered to <@{}> in {}",
+                    nation.name, nation.id, claimant.discord_user_id, alias
                 )));
             }
+            if has_roster {
+                if let Some(position) =
+                    join_roster(db_conn, &lobby_state, alias, user_id, nation.id)?
+                {
+                    game_cache.invalidate_lobby(alias);
+                    message.reply(&format!(
+                        "{} is full of expected players, you're number {} on the waitlist. \
+                         You'll be registered as {} ({}) if a slot frees up and it's still free",
+                        alias, position, nation.name, nation.id
+                    ))?;
+                    return Ok(());
+                }
+            }
             let player = Player {
                 discord_user_id: user_id,
                 turn_notifications: true,
@@ -212,39 +423,63 @@ fn register_player_helper<C: ServerConnection>(
             db_conn
                 .insert_player_into_server(&player, &server.alias, nation.id)
                 .map_err(CommandError::from)?;
-            message.reply(&format!(
+            game_cache.invalidate_lobby(alias);
+            let mut text = format!(
                 "registering {} ({}) for {}",
                 nation.name,
                 nation.id,
                 user_id.to_user()?
-            ))?;
+            );
+            if ai_reserved_nation_ids.contains(&nation.id) {
+                text.push_str(&format!(
+                    ". Warning: the owner of {} has reserved {} for the AI, so check with them \
+                     or pick another nation",
+                    alias, nation.name
+                ));
+            }
+            message.reply(&text)?;
             Ok(())
         }
         GameServerState::StartedState(started_state, option_lobby_state) => {
-            let started_details = details_read_handle
-                .get_clone(alias)
-                .and_then(|option| option)
-                .map(|cache| {
-                    let game_details: GameDetails = started_details_from_server(
-                        db_conn,
-                        &started_state,
-                        option_lobby_state.as_ref(),
-                        alias,
-                        cache.game_data,
-                        cache.option_snek_state,
-                    )
-                    .unwrap();
-                    game_details
-                })
-                .and_then(|game_details| match game_details.nations {
-                    NationDetails::Lobby(_) => None,
-                    NationDetails::Started(started_details) => Some(started_details.state),
-                })
-                .ok_or(CommandError::from(
-                    "Could not find game cache something is wrong",
-                ))?;
-            let option_era = option_lobby_state.map(|lobby_state| lobby_state.era);
-            let nation = get_nation_for_started_server(arg_nation, &started_details, option_era)?;
+            let cache = game_cache
+                .get_latest(alias)
+                .and_then(Result::ok)
+                .ok_or("Could not find game cache something is wrong")?;
+            let option_snek_state = cache.option_snek_state.clone();
+            let game_details: GameDetails = started_details_from_server(
+                db_conn,
+                &started_state,
+                option_lobby_state.as_ref(),
+                alias,
+                cache.game_data,
+                cache.option_snek_state,
+                cache.fetched_at,
+            )?;
+            let started_details = match game_details.nations {
+                NationDetails::Lobby(_) => None,
+                NationDetails::Started(started_details) => Some(started_details.state),
+            }
+            .ok_or(CommandError::from(
+                "Could not find game cache something is wrong",
+            ))?;
+            let option_era =
+                option_arg_era.or_else(|| option_lobby_state.map(|lobby_state| lobby_state.era));
+            let nation = get_nation_for_started_server(
+                arg_nation,
+                &started_details,
+                option_era,
+                option_snek_state.as_ref(),
+            )?;
+            if let Some((claimant, _)) = db_conn
+                .players_with_nations_for_game_alias(alias)?
+                .iter()
+                .find(|&&(_, player_nation_id)| player_nation_id == nation.id)
+            {
+                return Err(CommandError::from(format!(
+                    "{} ({}) is already registered to <@{}> in {}",
+                    nation.name, nation.id, claimant.discord_user_id, alias
+                )));
+            }
             let player = Player {
                 discord_user_id: user_id,
                 turn_notifications: true,
@@ -259,6 +494,10 @@ fn register_player_helper<C: ServerConnection>(
             let _ = message.reply(&text);
             Ok(())
         }
+        GameServerState::Archived(_, _) => Err(CommandError::from(format!(
+            "{} has finished, !unarchive it first",
+            alias
+        ))),
     }
 }
 
@@ -275,17 +514,18 @@ pub fn register_player_id<C: ServerConnection>(
 
     let data = context.data.lock();
     let db_conn = data.get::<DbConnectionKey>().ok_or("no db connection")?;
-    let details_read_handle = data
-        .get::<crate::DetailsReadHandleKey>()
-        .ok_or("No details cache")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
 
     register_player_helper::<C>(
         message.author.id,
         Either::Right(arg_nation_id),
+        None,
         &alias,
         db_conn,
         message,
-        details_read_handle,
+        game_cache,
     )?;
     Ok(())
 }
@@ -296,6 +536,13 @@ pub fn register_player<C: ServerConnection>(
     mut args: Args,
 ) -> Result<(), CommandError> {
     let arg_nation_name: String = args.single_quoted::<String>()?.to_lowercase();
+    let option_arg_era = args
+        .single_quoted_n::<String>()
+        .ok()
+        .and_then(|arg| Era::from_string(&arg));
+    if option_arg_era.is_some() {
+        args.skip();
+    }
     let alias = alias_from_arg_or_channel_name(&mut args, &message)?;
     // FIXME: no idea why this isn't working
     //    if args.len() != 0 {
@@ -306,17 +553,18 @@ pub fn register_player<C: ServerConnection>(
 
     let data = context.data.lock();
     let db_conn = data.get::<DbConnectionKey>().ok_or("no db connection")?;
-    let details_read_handle = data
-        .get::<crate::DetailsReadHandleKey>()
-        .ok_or("No details cache")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
 
     register_player_helper::<C>(
         message.author.id,
         Either::Left(&arg_nation_name),
+        option_arg_era,
         &alias,
         db_conn,
         message,
-        details_read_handle,
+        game_cache,
     )?;
     Ok(())
 }
@@ -0,0 +1,83 @@
+use super::*;
+
+use crate::snek::SnekNation;
+use std::collections::HashMap;
+
+#[test]
+fn should_find_nation_by_name_and_era() {
+    let nation = nation_from_name("ulm", Some(Era::Middle), None).unwrap();
+    assert_eq!(nation.id, 49);
+}
+
+#[test]
+fn should_ask_for_era_when_name_is_ambiguous() {
+    let err = nation_from_name("Ulm", None, None).err().unwrap();
+    let text = format!("{:?}", err);
+    assert!(text.contains("Ulm (EA 7), Ulm (MA 49), Ulm (LA 84)"));
+}
+
+#[test]
+fn should_prefer_snek_names() {
+    let mut nations = HashMap::new();
+    nations.insert(
+        7,
+        SnekNation {
+            nation_id: 7,
+            name: "Ulm".to_owned(),
+        },
+    );
+    nations.insert(
+        150,
+        SnekNation {
+            nation_id: 150,
+            name: "Ulmish Modded Nation".to_owned(),
+        },
+    );
+    let snek_state = SnekGameStatus {
+        nations,
+        option_game_id: None,
+    };
+
+    // exact match wins over the longer name
+    let nation = nation_from_name("ulm", None, Some(&snek_state)).unwrap();
+    assert_eq!(nation.id, 7);
+    let nation = nation_from_name("ulmish", Some(Era::Early), Some(&snek_state)).unwrap();
+    assert_eq!(nation.id, 150);
+}
+
+#[test]
+fn should_suggest_nations_for_typos() {
+    let err = nation_from_name("Mictln", Some(Era::Early), None)
+        .err()
+        .unwrap();
+    assert!(err.0.contains("Did you mean: Mictlan (EA"), "{}", err.0);
+
+    // a typo in just the start of the name
+    let err = nation_from_name("arcas", Some(Era::Early), None)
+        .err()
+        .unwrap();
+    assert!(err.0.contains("Arcoscephale (EA 5)"), "{}", err.0);
+
+    let err = nation_from_name("zzzzzzzz", None, None).err().unwrap();
+    assert!(!err.0.contains("Did you mean"));
+}
+
+#[test]
+fn should_suggest_snek_names_for_typos() {
+    let mut nations = HashMap::new();
+    nations.insert(
+        150,
+        SnekNation {
+            nation_id: 150,
+            name: "Modded Nation".to_owned(),
+        },
+    );
+    let snek_state = SnekGameStatus {
+        nations,
+        option_game_id: None,
+    };
+    let err = nation_from_name("moddad", None, Some(&snek_state))
+        .err()
+        .unwrap();
+    assert!(err.0.contains("Modded Nation (150)"), "{}", err.0);
+}
@@ -0,0 +1,113 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+use std::collections::HashMap;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey, FinalSubmission};
+use crate::model::enums::Nations;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(PartialEq, Debug)]
+pub struct Reliability {
+    pub user_id: UserId,
+    /// The nation they played in the latest turn we know about
+    pub nation_id: u32,
+    /// Turns they had in by the time it hosted
+    pub submitted_turns: usize,
+    /// Turns they were registered for when it hosted
+    pub turns: usize,
+}
+
+impl Reliability {
+    pub fn percent(&self) -> usize {
+        self.submitted_turns * 100 / self.turns
+    }
+}
+
+/// Most reliable first. Players who've been around for more turns go first
+/// when it's a tie, they've had more chances to miss one.
+pub fn player_reliabilities(final_submissions: &[FinalSubmission]) -> Vec<Reliability> {
+    let mut by_player: HashMap<UserId, Reliability> = HashMap::new();
+    // Oldest turn first, so the nation ends up as the latest one
+    for &(_, user_id, nation_id, submitted) in final_submissions {
+        let reliability = by_player.entry(user_id).or_insert(Reliability {
+            user_id,
+            nation_id,
+            submitted_turns: 0,
+            turns: 0,
+        });
+        reliability.nation_id = nation_id;
+        reliability.turns += 1;
+        if submitted {
+            reliability.submitted_turns += 1;
+        }
+    }
+
+    let mut reliabilities: Vec<Reliability> = by_player.into_values().collect();
+    reliabilities.sort_by(|a, b| {
+        // a.submitted / a.turns against b.submitted / b.turns without rounding
+        (b.submitted_turns * a.turns)
+            .cmp(&(a.submitted_turns * b.turns))
+            .then(b.turns.cmp(&a.turns))
+            .then(a.nation_id.cmp(&b.nation_id))
+    });
+    reliabilities
+}
+
+fn reliability_helper(db_conn: &DbConnection, alias: &str) -> Result<String, CommandError> {
+    // So that a typo doesn't look like a game with no turns
+    db_conn.game_for_alias(alias)?;
+    let reliabilities = player_reliabilities(&db_conn.select_final_submissions(alias)?);
+    if reliabilities.is_empty() {
+        return Ok(format!(
+            "No turns have hosted in {} since the bot started keeping track, check back after the next one",
+            alias
+        ));
+    }
+
+    let names: Vec<String> = reliabilities
+        .iter()
+        .map(|reliability| Nations::get_nation_name(reliability.nation_id))
+        .collect();
+    let width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+    let mut text = format!("Turns in before they hosted for {}:\n```\n", alias);
+    for (reliability, name) in reliabilities.iter().zip(names.iter()) {
+        text.push_str(&format!(
+            "{:<width$} {:>3}% ({} of {} turns)\n",
+            name,
+            reliability.percent(),
+            reliability.submitted_turns,
+            reliability.turns,
+            width = width,
+        ));
+    }
+    text.push_str("```");
+    Ok(text)
+}
+
+pub fn reliability(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = reliability_helper(db_conn, &alias)?;
+    info!("reliability: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
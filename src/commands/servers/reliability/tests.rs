@@ -0,0 +1,68 @@
+use super::*;
+
+#[test]
+fn should_rank_by_share_of_turns_then_by_turns_played() {
+    let final_submissions = vec![
+        (1, UserId(1), 5, true),
+        (1, UserId(2), 6, false),
+        (2, UserId(1), 5, true),
+        (2, UserId(2), 6, true),
+        (2, UserId(3), 7, true),
+        // UserId(1) took over another nation
+        (3, UserId(1), 8, false),
+        (3, UserId(2), 6, true),
+        (3, UserId(3), 7, true),
+    ];
+    assert_eq!(
+        player_reliabilities(&final_submissions),
+        vec![
+            Reliability {
+                user_id: UserId(3),
+                nation_id: 7,
+                submitted_turns: 2,
+                turns: 2,
+            },
+            Reliability {
+                user_id: UserId(2),
+                nation_id: 6,
+                submitted_turns: 2,
+                turns: 3,
+            },
+            Reliability {
+                user_id: UserId(1),
+                nation_id: 8,
+                submitted_turns: 2,
+                turns: 3,
+            },
+        ]
+    );
+    assert_eq!(player_reliabilities(&final_submissions)[1].percent(), 66);
+}
+
+#[test]
+fn should_only_count_turns_seen_while_registered() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .record_final_submission("test server 1", 4, UserId(101), 1, false)
+        .unwrap();
+    db_conn
+        .record_final_submission("test server 1", 5, UserId(101), 1, true)
+        .unwrap();
+    // a second look at the same turn doesn't change how it ended
+    db_conn
+        .record_final_submission("test server 1", 5, UserId(101), 1, false)
+        .unwrap();
+    db_conn
+        .record_final_submission("test server 1", 5, UserId(102), 2, true)
+        .unwrap();
+
+    let text = reliability_helper(&db_conn, "test server 1").unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[2].contains("100% (1 of 1 turns)"), "{}", text);
+    assert!(lines[3].contains(" 50% (1 of 2 turns)"), "{}", text);
+
+    assert!(reliability_helper(&db_conn, "test server 2")
+        .unwrap()
+        .starts_with("No turns have hosted"));
+    assert!(reliability_helper(&db_conn, "not a game").is_err());
+}
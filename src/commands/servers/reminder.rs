@@ -0,0 +1,65 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of a template to go back to the usual wording
+const CLEAR_REMINDER_FLAG: &str = "--clear";
+/// Leaves plenty of room under discord's message limit once the tokens are filled in
+const MAX_REMINDER_LEN: usize = 500;
+
+fn reminder_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    option_template: Option<&str>,
+) -> Result<(), CommandError> {
+    // An empty template would just be an empty DM, so treat it like clearing it
+    let option_template = option_template
+        .map(|template| template.trim())
+        .filter(|template| !template.is_empty());
+    if let Some(template) = option_template {
+        if template.chars().count() > MAX_REMINDER_LEN {
+            return Err(CommandError::from(format!(
+                "Reminders can be at most {} characters",
+                MAX_REMINDER_LEN
+            )));
+        }
+    }
+    db_conn.set_reminder_template_for_alias(alias, option_template)?;
+    Ok(())
+}
+
+pub fn reminder(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_template = args.single_quoted::<String>()?;
+    let option_template = if arg_template == CLEAR_REMINDER_FLAG {
+        None
+    } else {
+        Some(arg_template)
+    };
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    reminder_helper(db_conn, &alias, option_template.as_deref())?;
+    let text = format!("Updated the new turn reminder for {}", alias);
+    info!("reminder: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use super::*;
+
+use crate::commands::servers::turn_check::fill_reminder_template;
+
+#[test]
+fn should_fill_in_known_tokens_only() {
+    assert_eq!(
+        fill_reminder_template(
+            "The council awaits your orders, %nation%. Turn %turn%, %hours%h left, 100% %unknown%",
+            "Ulm",
+            32,
+            47
+        ),
+        "The council awaits your orders, Ulm. Turn 32, 47h left, 100% %unknown%"
+    );
+}
+
+#[test]
+fn should_store_and_clear_reminder_template() {
+    let db_conn = DbConnection::test();
+    reminder_helper(&db_conn, "test server 1", Some(" Go %nation% ")).unwrap();
+    assert_eq!(
+        db_conn
+            .reminder_template_for_alias("test server 1")
+            .unwrap(),
+        Some("Go %nation%".to_owned())
+    );
+
+    // empty is the same as clearing it
+    reminder_helper(&db_conn, "test server 1", Some("  ")).unwrap();
+    assert_eq!(
+        db_conn
+            .reminder_template_for_alias("test server 1")
+            .unwrap(),
+        None
+    );
+}
@@ -0,0 +1,82 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Goes back to every countdown the game has configured
+pub const ALL_REMINDERS: &str = "all";
+/// Forgets your own cap, so the one the host set for the game applies
+const GAME_DEFAULT: &str = "default";
+
+/// `None` is no cap
+pub fn parse_reminder_cap(arg: &str) -> Result<Option<u32>, CommandError> {
+    if arg == ALL_REMINDERS {
+        return Ok(None);
+    }
+    arg.parse::<u32>().map(Some).map_err(|_| {
+        CommandError::from(format!(
+            "Expected how many countdown reminders you want a turn, or \"{}\", not \"{}\"",
+            ALL_REMINDERS, arg
+        ))
+    })
+}
+
+fn reminder_cap_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    user_id: UserId,
+    arg: &str,
+) -> Result<String, CommandError> {
+    if arg == GAME_DEFAULT {
+        db_conn.set_reminder_cap(alias, user_id, None)?;
+        return Ok(format!(
+            "You'll get the countdown reminders the host picked for {}",
+            alias
+        ));
+    }
+    let option_max_reminders = parse_reminder_cap(arg)?;
+    db_conn.set_reminder_cap(alias, user_id, Some(option_max_reminders))?;
+    Ok(match option_max_reminders {
+        None => format!("You'll get every countdown reminder for {}", alias),
+        Some(0) => format!("You won't get countdown reminders for {}", alias),
+        Some(1) => format!("You'll only get the last countdown reminder for {}", alias),
+        Some(max_reminders) => format!(
+            "You'll only get the last {} countdown reminders for {}",
+            max_reminders, alias
+        ),
+    })
+}
+
+pub fn reminder_cap(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg = args.single_quoted::<String>().map_err(|_| {
+        CommandError::from(
+            "Expected how many countdown reminders you want a turn, \"all\" or \"default\"",
+        )
+    })?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = reminder_cap_helper(db_conn, &alias, message.author.id, &arg)?;
+    info!("remindercap: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
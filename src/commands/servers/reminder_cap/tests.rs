@@ -0,0 +1,42 @@
+use super::*;
+
+use crate::commands::servers::turn_check::within_reminder_cap;
+
+#[test]
+fn should_keep_the_most_urgent_reminders() {
+    let countdown_mins = [120, 60, 15];
+    assert!(within_reminder_cap(&countdown_mins, 15, 1));
+    assert!(!within_reminder_cap(&countdown_mins, 60, 1));
+    assert!(within_reminder_cap(&countdown_mins, 60, 2));
+    assert!(!within_reminder_cap(&countdown_mins, 120, 2));
+    assert!(within_reminder_cap(&countdown_mins, 120, 3));
+    assert!(!within_reminder_cap(&countdown_mins, 15, 0));
+}
+
+#[test]
+fn should_cap_only_the_player_who_asked() {
+    let db_conn = DbConnection::test();
+    // UserId(101) and UserId(102) are registered in "test server 1"
+    reminder_cap_helper(&db_conn, "test server 1", UserId(101), "1").unwrap();
+    let caps = db_conn.reminder_caps_for_alias("test server 1").unwrap();
+    assert_eq!(caps.get(&UserId(101)), Some(&Some(1)));
+    assert_eq!(caps.get(&UserId(102)), None);
+
+    // Kept, so it beats a default the host sets for the game
+    reminder_cap_helper(&db_conn, "test server 1", UserId(101), ALL_REMINDERS).unwrap();
+    let caps = db_conn.reminder_caps_for_alias("test server 1").unwrap();
+    assert_eq!(caps.get(&UserId(101)), Some(&None));
+
+    reminder_cap_helper(&db_conn, "test server 1", UserId(101), GAME_DEFAULT).unwrap();
+    assert!(db_conn
+        .reminder_caps_for_alias("test server 1")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_not_cap_games_you_are_not_in() {
+    let db_conn = DbConnection::test();
+    assert!(reminder_cap_helper(&db_conn, "test server 2", UserId(101), "1").is_err());
+    assert!(reminder_cap_helper(&db_conn, "test server 1", UserId(101), "some").is_err());
+}
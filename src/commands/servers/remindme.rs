@@ -0,0 +1,189 @@
+use chrono::{DateTime, Duration, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::undone::playing_state_for_alias;
+use crate::commands::servers::{GameCache, GameCacheKey};
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+const CLEAR_FLAG: &str = "--clear";
+const BEFORE_HOST: &str = "before";
+const MAX_REMINDER_HOURS: i64 = 14 * 24;
+
+#[derive(Debug, PartialEq)]
+pub enum RemindAt {
+    /// This long from now
+    In(Duration),
+    /// This long before the current turn's deadline
+    BeforeHost(Duration),
+}
+
+/// Hours and/or minutes like "2h", "30m" or "1h30m"
+pub fn parse_reminder_duration(text: &str) -> Result<Duration, CommandError> {
+    let error = || {
+        CommandError::from(format!(
+            "Reminder times are hours and/or minutes like \"2h\", \"30m\" or \"1h30m\", up to {}h",
+            MAX_REMINDER_HOURS
+        ))
+    };
+    let (hours_text, mins_text) = match text.find('h') {
+        Some(index) => (&text[..index], &text[index + 1..]),
+        None => ("", text),
+    };
+    let hours = if hours_text.is_empty() {
+        0
+    } else {
+        hours_text.parse::<i64>().map_err(|_| error())?
+    };
+    let mins = if mins_text.is_empty() {
+        0
+    } else {
+        mins_text
+            .strip_suffix('m')
+            .ok_or_else(error)?
+            .parse::<i64>()
+            .map_err(|_| error())?
+    };
+    let duration = Duration::hours(hours) + Duration::minutes(mins);
+    if duration <= Duration::zero() || duration > Duration::hours(MAX_REMINDER_HOURS) {
+        return Err(error());
+    }
+    Ok(duration)
+}
+
+fn show_duration(duration: Duration) -> String {
+    match (duration.num_hours(), duration.num_minutes() % 60) {
+        (0, mins) => format!("{}m", mins),
+        (hours, 0) => format!("{}h", hours),
+        (hours, mins) => format!("{}h {}m", hours, mins),
+    }
+}
+
+/// When a reminder should go out. Reminders before the host need the turn to
+/// have a deadline, and one that's still far enough away.
+pub fn fire_time(
+    remind_at: &RemindAt,
+    now: DateTime<Utc>,
+    option_deadline: Option<DateTime<Utc>>,
+) -> Result<DateTime<Utc>, CommandError> {
+    match *remind_at {
+        RemindAt::In(duration) => Ok(now + duration),
+        RemindAt::BeforeHost(duration) => {
+            let deadline = option_deadline.ok_or_else(|| {
+                CommandError::from(
+                    "This turn has no timer running, so there's no host to remind you before",
+                )
+            })?;
+            let fire_at = deadline - duration;
+            if fire_at <= now {
+                return Err(CommandError::from(format!(
+                    "The turn hosts in {}, that's already too late for a reminder {} before",
+                    show_duration(deadline.signed_duration_since(now)),
+                    show_duration(duration)
+                )));
+            }
+            Ok(fire_at)
+        }
+    }
+}
+
+fn remindme_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    user_id: UserId,
+    alias: &str,
+    option_remind_at: Option<RemindAt>,
+    now: DateTime<Utc>,
+) -> Result<String, CommandError> {
+    let remind_at = match option_remind_at {
+        Some(remind_at) => remind_at,
+        None => {
+            return Ok(if db_conn.cancel_one_off_reminder(alias, user_id)? {
+                format!("Cancelled your reminder for {}", alias)
+            } else {
+                format!("You have no reminder set for {}", alias)
+            });
+        }
+    };
+    // Also checks that the game exists
+    let server = db_conn.game_for_alias(alias)?;
+    let (fire_at, message) = match remind_at {
+        RemindAt::In(duration) => (
+            fire_time(&remind_at, now, None)?,
+            format!(
+                "Reminder about {}, you asked for one {} ago",
+                server.alias,
+                show_duration(duration)
+            ),
+        ),
+        RemindAt::BeforeHost(duration) => {
+            let playing_state = playing_state_for_alias(db_conn, game_cache, alias)?;
+            (
+                fire_time(&remind_at, now, playing_state.option_deadline)?,
+                format!(
+                    "Reminder: turn {} of {} is due to host in {}",
+                    playing_state.turn,
+                    server.alias,
+                    show_duration(duration)
+                ),
+            )
+        }
+    };
+    db_conn.set_one_off_reminder(alias, user_id, fire_at, &message)?;
+    Ok(format!(
+        "I'll DM you about {} in {}",
+        alias,
+        show_duration(fire_at.signed_duration_since(now))
+    ))
+}
+
+pub fn remindme(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_time = args.single_quoted::<String>()?;
+    let option_remind_at = if arg_time == CLEAR_FLAG {
+        None
+    } else {
+        let duration = parse_reminder_duration(&arg_time)?;
+        if args.single_quoted_n::<String>().ok().as_deref() == Some(BEFORE_HOST) {
+            args.skip();
+            Some(RemindAt::BeforeHost(duration))
+        } else {
+            Some(RemindAt::In(duration))
+        }
+    };
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = remindme_helper(
+        db_conn,
+        game_cache,
+        message.author.id,
+        &alias,
+        option_remind_at,
+        Utc::now(),
+    )?;
+    info!("remindme: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
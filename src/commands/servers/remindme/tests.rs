@@ -0,0 +1,101 @@
+use super::*;
+
+use chrono::TimeZone;
+
+fn hour(hour: i64) -> DateTime<Utc> {
+    Utc.timestamp(hour * 3600, 0)
+}
+
+#[test]
+fn should_parse_hours_and_minutes() {
+    assert_eq!(parse_reminder_duration("2h").unwrap(), Duration::hours(2));
+    assert_eq!(
+        parse_reminder_duration("30m").unwrap(),
+        Duration::minutes(30)
+    );
+    assert_eq!(
+        parse_reminder_duration("1h30m").unwrap(),
+        Duration::minutes(90)
+    );
+    assert!(parse_reminder_duration("0m").is_err());
+    assert!(parse_reminder_duration("soon").is_err());
+    assert!(parse_reminder_duration("30").is_err());
+    assert!(parse_reminder_duration("1000h").is_err());
+}
+
+#[test]
+fn should_fire_before_the_deadline() {
+    assert_eq!(
+        fire_time(&RemindAt::In(Duration::hours(2)), hour(10), None).unwrap(),
+        hour(12)
+    );
+    assert_eq!(
+        fire_time(
+            &RemindAt::BeforeHost(Duration::hours(2)),
+            hour(10),
+            Some(hour(20))
+        )
+        .unwrap(),
+        hour(18)
+    );
+    // no timer
+    assert!(fire_time(&RemindAt::BeforeHost(Duration::hours(2)), hour(10), None).is_err());
+    // too close to the deadline already
+    assert!(fire_time(
+        &RemindAt::BeforeHost(Duration::hours(2)),
+        hour(19),
+        Some(hour(20))
+    )
+    .is_err());
+}
+
+#[test]
+fn should_deliver_each_reminder_once() {
+    let db_conn = DbConnection::test();
+    let game_cache = GameCache::test();
+    remindme_helper(
+        &db_conn,
+        &game_cache,
+        UserId(317),
+        "test server 3",
+        Some(RemindAt::In(Duration::hours(2))),
+        hour(10),
+    )
+    .unwrap();
+    assert!(db_conn
+        .take_due_one_off_reminders(hour(11))
+        .unwrap()
+        .is_empty());
+
+    let due = db_conn.take_due_one_off_reminders(hour(12)).unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].0, UserId(317));
+    assert!(due[0].1.contains("test server 3"));
+    assert!(db_conn
+        .take_due_one_off_reminders(hour(13))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_cancel_pending_reminder() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_one_off_reminder("test server 4", UserId(318), hour(12), "hi")
+        .unwrap();
+    let game_cache = GameCache::test();
+    let text = remindme_helper(
+        &db_conn,
+        &game_cache,
+        UserId(318),
+        "test server 4",
+        None,
+        hour(10),
+    )
+    .unwrap();
+    assert!(text.starts_with("Cancelled"));
+    assert!(db_conn
+        .take_due_one_off_reminders(hour(12))
+        .unwrap()
+        .is_empty());
+}
@@ -15,6 +15,7 @@ fn should_remove_started_server() {
                 StartedState {
                     address: "foo.bar:3000".to_owned(),
                     last_seen_turn: 23,
+                    option_snek_game_id: None,
                 },
                 None,
             ),
@@ -84,6 +85,7 @@ fn should_remove_started_server_with_lobby() {
                 StartedState {
                     address: "foo.bar:3000".to_owned(),
                     last_seen_turn: 23,
+                    option_snek_game_id: None,
                 },
                 Some(LobbyState {
                     owner: UserId(1),
@@ -0,0 +1,62 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::{alias_from_arg_or_channel_name, author_is_game_admin, check_owner_or_admin};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::notifier::DiscordNotifier;
+
+#[cfg(test)]
+mod tests;
+
+fn rename_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    author_is_admin: bool,
+    old_alias: &str,
+    new_alias: &str,
+) -> Result<(), CommandError> {
+    check_owner_or_admin(db_conn, old_alias, author_id, author_is_admin)?;
+    db_conn.rename_alias(old_alias, new_alias).map_err(|e| {
+        if e.to_string()
+            .contains("UNIQUE constraint failed: game_servers.alias")
+        {
+            CommandError::from(format!("A game called '{}' already exists", new_alias))
+        } else {
+            CommandError::from(e)
+        }
+    })
+}
+
+pub fn rename(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let new_alias = args.single_quoted::<String>()?.to_lowercase();
+    let old_alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let author_is_admin = author_is_game_admin::<DiscordNotifier>(db_conn, message, &old_alias)?;
+    rename_helper(
+        db_conn,
+        message.author.id,
+        author_is_admin,
+        &old_alias,
+        &new_alias,
+    )?;
+    let text = format!("Renamed {} to {}", old_alias, new_alias);
+    info!("{}", text);
+    message.reply(&text)?;
+    Ok(())
+}
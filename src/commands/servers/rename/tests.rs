@@ -0,0 +1,73 @@
+use super::*;
+
+use crate::model::enums::Era;
+use crate::model::{GameServer, GameServerState, LobbyState, Player};
+use serenity::model::id::UserId;
+
+#[test]
+fn should_keep_registrations_and_history_when_renaming() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_player_into_server(
+            &Player {
+                discord_user_id: UserId(101),
+                turn_notifications: true,
+            },
+            "test server 1",
+            7,
+        )
+        .unwrap();
+    db_conn
+        .update_game_with_possibly_new_turn("test server 1", 32, None)
+        .unwrap();
+
+    rename_helper(&db_conn, UserId(1), false, "test server 1", "renamed").unwrap();
+
+    assert!(db_conn.game_for_alias("test server 1").is_err());
+    assert!(db_conn.game_for_alias("renamed").is_ok());
+    let players = db_conn
+        .players_with_nations_for_game_alias("renamed")
+        .unwrap();
+    assert!(players
+        .iter()
+        .any(|(player, nation_id)| player.discord_user_id == UserId(101) && *nation_id == 7));
+    let history = db_conn.select_turn_history("renamed", 10).unwrap();
+    assert_eq!(history.len(), 1);
+}
+
+#[test]
+fn should_not_rename_over_existing_game() {
+    let db_conn = DbConnection::test();
+    let err =
+        rename_helper(&db_conn, UserId(1), false, "test server 1", "test server 2").unwrap_err();
+    assert!(format!("{:?}", err).contains("already exists"));
+    assert!(db_conn.game_for_alias("test server 1").is_ok());
+}
+
+#[test]
+fn should_not_rename_missing_game() {
+    let db_conn = DbConnection::test();
+    assert!(rename_helper(&db_conn, UserId(1), false, "not a game", "renamed").is_err());
+}
+
+#[test]
+fn should_only_let_the_owner_or_an_admin_rename_a_lobby() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(3867),
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+
+    let err = rename_helper(&db_conn, UserId(3868), false, "lobby", "renamed").unwrap_err();
+    assert!(format!("{:?}", err).contains("Only the owner of lobby or a server admin"));
+    rename_helper(&db_conn, UserId(3868), true, "lobby", "by an admin").unwrap();
+    rename_helper(&db_conn, UserId(3867), false, "by an admin", "by the owner").unwrap();
+    assert!(db_conn.game_for_alias("by the owner").is_ok());
+}
@@ -0,0 +1,122 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::commands::servers::GameCacheKey;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::Nations;
+use crate::model::GameServerState;
+use crate::notifier::{DiscordNotifier, Notifier};
+
+#[cfg(test)]
+mod tests;
+
+fn show_nations(nation_ids: &[u32]) -> String {
+    nation_ids
+        .iter()
+        .map(|&nation_id| match Nations::from_id(nation_id) {
+            Some(nation) => format!("{} ({})", nation.name, nation.id),
+            None => format!("nation {}", nation_id),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// For when someone drops out and a sub takes over all of their nations. The
+/// reassignment has already happened by the time the DMs go out, so a player
+/// with DMs turned off only gets logged.
+fn replace_helper<N: Notifier>(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    leaver: UserId,
+    sub: UserId,
+) -> Result<String, CommandError> {
+    if leaver == sub {
+        return Err(CommandError::from("Those are the same player"));
+    }
+    // Games added with !add have nobody in charge of them, so anyone can
+    let option_owner = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(lobby_state) => Some(lobby_state.owner),
+        GameServerState::StartedState(_, option_lobby_state) => {
+            option_lobby_state.map(|lobby_state| lobby_state.owner)
+        }
+        GameServerState::Archived(_, _) => {
+            return Err(CommandError::from(format!(
+                "{} has finished, there's nobody left to replace",
+                alias
+            )))
+        }
+    };
+    if option_owner.is_some_and(|owner| owner != author_id) {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} can do this",
+            alias
+        )));
+    }
+
+    let nation_ids = db_conn.reassign_player_nations(alias, leaver, sub)?;
+    let nations = show_nations(&nation_ids);
+    for (user_id, dm) in &[
+        (
+            leaver,
+            format!(
+                "You've been replaced in {} by <@{}>, thanks for playing",
+                alias, sub
+            ),
+        ),
+        (
+            sub,
+            format!(
+                "You're now playing {} in {}, taking over from <@{}>",
+                nations, alias, leaver
+            ),
+        ),
+    ] {
+        if let Err(e) = N::dm(*user_id, dm) {
+            warn!(
+                "Could not DM {} about {} with error {:?}",
+                user_id, alias, e.0
+            );
+        }
+    }
+    Ok(format!(
+        "<@{}> is now playing {} in {} instead of <@{}>",
+        sub, nations, alias, leaver
+    ))
+}
+
+pub fn replace(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let leaver = args
+        .single_quoted::<UserId>()
+        .map_err(|_| CommandError::from("Expected an @mention of the player who's leaving"))?;
+    let sub = args
+        .single_quoted::<UserId>()
+        .map_err(|_| CommandError::from("Expected an @mention of the player taking over"))?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = replace_helper::<DiscordNotifier>(db_conn, message.author.id, &alias, leaver, sub)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+    info!("replace: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use super::*;
+
+use crate::model::enums::Era;
+use crate::model::{GameServer, LobbyState, Player};
+use crate::notifier::test_helpers::RecordingNotifier;
+
+fn register(db_conn: &DbConnection, alias: &str, user_id: u64, nation_id: u32) {
+    db_conn
+        .insert_player_into_server(
+            &Player {
+                discord_user_id: UserId(user_id),
+                turn_notifications: true,
+            },
+            alias,
+            nation_id,
+        )
+        .unwrap();
+}
+
+fn registered(db_conn: &DbConnection, alias: &str) -> Vec<(UserId, u32)> {
+    let mut registered: Vec<_> = db_conn
+        .players_with_nations_for_game_alias(alias)
+        .unwrap()
+        .into_iter()
+        .map(|(player, nation_id)| (player.discord_user_id, nation_id))
+        .collect();
+    registered.sort_by_key(|&(_, nation_id)| nation_id);
+    registered
+}
+
+#[test]
+fn should_hand_every_nation_to_the_sub_and_dm_both() {
+    let db_conn = DbConnection::test();
+    register(&db_conn, "test server 1", 3791, 5);
+    register(&db_conn, "test server 1", 3791, 6);
+    db_conn
+        .set_reminder_cap("test server 1", UserId(3791), Some(Some(2)))
+        .unwrap();
+    db_conn
+        .set_player_nickname("test server 1", UserId(3791), Some("Leaver"))
+        .unwrap();
+
+    let text = replace_helper::<RecordingNotifier>(
+        &db_conn,
+        UserId(1),
+        "test server 1",
+        UserId(3791),
+        UserId(3792),
+    )
+    .unwrap();
+
+    assert!(text.contains("<@3792> is now playing"));
+    assert_eq!(
+        registered(&db_conn, "test server 1"),
+        vec![(UserId(101), 1), (UserId(3792), 5), (UserId(3792), 6)]
+    );
+    assert_eq!(
+        db_conn
+            .reminder_caps_for_alias("test server 1")
+            .unwrap()
+            .get(&UserId(3792)),
+        Some(&Some(2))
+    );
+    assert!(db_conn
+        .player_nicknames_for_alias("test server 1")
+        .unwrap()
+        .is_empty());
+    assert_eq!(RecordingNotifier::dms(UserId(3791)).len(), 1);
+    assert!(RecordingNotifier::dms(UserId(3792))[0].contains("taking over from <@3791>"));
+}
+
+#[test]
+fn should_reject_a_leaver_with_no_nations() {
+    let db_conn = DbConnection::test();
+    let err = replace_helper::<RecordingNotifier>(
+        &db_conn,
+        UserId(1),
+        "test server 1",
+        UserId(3793),
+        UserId(3794),
+    )
+    .unwrap_err();
+    assert_eq!(err.0, "<@3793> isn't registered in test server 1");
+    assert!(RecordingNotifier::dms(UserId(3794)).is_empty());
+}
+
+#[test]
+fn should_reject_replacing_someone_with_themselves() {
+    let db_conn = DbConnection::test();
+    let err = replace_helper::<RecordingNotifier>(
+        &db_conn,
+        UserId(1),
+        "test server 1",
+        UserId(101),
+        UserId(101),
+    )
+    .unwrap_err();
+    assert_eq!(err.0, "Those are the same player");
+}
+
+#[test]
+fn should_only_let_the_owner_replace() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "replace lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(3795),
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+    register(&db_conn, "replace lobby", 3796, 5);
+
+    let err = replace_helper::<RecordingNotifier>(
+        &db_conn,
+        UserId(3796),
+        "replace lobby",
+        UserId(3796),
+        UserId(3797),
+    )
+    .unwrap_err();
+    assert_eq!(err.0, "Only the owner of replace lobby can do this");
+    assert_eq!(
+        registered(&db_conn, "replace lobby"),
+        vec![(UserId(3796), 5)]
+    );
+}
@@ -0,0 +1,96 @@
+use either::Either;
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::register_player::get_nation_for_lobby;
+use super::{alias_from_arg_or_channel_name, lobby_state_for_owner};
+use crate::commands::servers::GameCacheKey;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this before the nation to give it back to the players
+const CLEAR_FLAG: &str = "--clear";
+
+fn reserve_ai_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    arg_nation: Either<&str, u32>,
+    clear: bool,
+) -> Result<String, CommandError> {
+    let lobby_state = lobby_state_for_owner(db_conn, alias, author_id)?;
+    let nation = get_nation_for_lobby(arg_nation, lobby_state.era)?;
+    if clear {
+        if !db_conn.unreserve_nation_for_ai(alias, nation.id)? {
+            return Err(CommandError::from(format!(
+                "{} ({}) isn't reserved for the AI in {}",
+                nation.name, nation.id, alias
+            )));
+        }
+        return Ok(format!(
+            "{} ({}) is open to players in {} again",
+            nation.name, nation.id, alias
+        ));
+    }
+    if !db_conn.reserve_nation_for_ai(alias, nation.id)? {
+        return Err(CommandError::from(format!(
+            "{} ({}) is already reserved for the AI in {}",
+            nation.name, nation.id, alias
+        )));
+    }
+    let mut text = format!(
+        "{} ({}) is reserved for the AI in {}",
+        nation.name, nation.id, alias
+    );
+    let players_nations = db_conn.players_with_nations_for_game_alias(alias)?;
+    if let Some((player, _)) = players_nations
+        .iter()
+        .find(|&&(_, nation_id)| nation_id == nation.id)
+    {
+        text.push_str(&format!(
+            ". Warning: <@{}> has already registered as it",
+            player.discord_user_id
+        ));
+    }
+    Ok(text)
+}
+
+pub fn reserve_ai(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let clear = args.single_quoted_n::<String>().ok() == Some(CLEAR_FLAG.to_owned());
+    if clear {
+        args.skip();
+    }
+    let arg_nation = args.single_quoted::<String>()?.to_lowercase();
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let arg_nation = match arg_nation.parse::<u32>() {
+        Ok(nation_id) => Either::Right(nation_id),
+        Err(_) => Either::Left(arg_nation.as_str()),
+    };
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = reserve_ai_helper(db_conn, message.author.id, &alias, arg_nation, clear)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+    info!("reserve_ai: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use super::*;
+
+use crate::commands::servers::{lobby_details, NationDetails};
+use crate::model::enums::Era;
+
+const OWNER: UserId = UserId(1);
+
+#[test]
+fn should_not_count_ai_reserved_nations_as_players() {
+    let db_conn = DbConnection::test();
+    // Ulm
+    let lobby_state = db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 3, &[(201, 7)]);
+
+    // Arcoscephale
+    reserve_ai_helper(&db_conn, OWNER, "lobby", Either::Right(5), false).unwrap();
+    let text = reserve_ai_helper(&db_conn, OWNER, "lobby", Either::Left("ulm"), false).unwrap();
+    assert!(text.contains("<@201> has already registered as it"));
+
+    let details = lobby_details(&db_conn, &lobby_state, "lobby").unwrap();
+    let lobby_details = match details.nations {
+        NationDetails::Lobby(lobby_details) => lobby_details,
+        NationDetails::Started(_) => panic!("expected a lobby"),
+    };
+    assert!(lobby_details.players.is_empty());
+    assert_eq!(lobby_details.remaining_slots, Some(3));
+    let reserved: Vec<(u32, Option<UserId>)> = lobby_details
+        .ai_reserved
+        .iter()
+        .map(|nation| (nation.nation_id, nation.option_player_id))
+        .collect();
+    assert_eq!(reserved, vec![(5, None), (7, Some(UserId(201)))]);
+}
+
+#[test]
+fn should_only_let_the_owner_reserve_nations() {
+    let db_conn = DbConnection::test();
+    // Ulm
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 3, &[(201, 7)]);
+
+    assert!(reserve_ai_helper(&db_conn, UserId(201), "lobby", Either::Right(5), false).is_err());
+    reserve_ai_helper(&db_conn, OWNER, "lobby", Either::Right(5), false).unwrap();
+    assert!(reserve_ai_helper(&db_conn, OWNER, "lobby", Either::Right(5), false).is_err());
+    reserve_ai_helper(&db_conn, OWNER, "lobby", Either::Right(5), true).unwrap();
+    assert!(db_conn
+        .ai_reserved_nations_for_alias("lobby")
+        .unwrap()
+        .is_empty());
+}
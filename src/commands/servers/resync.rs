@@ -0,0 +1,139 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::model::misc::Mentionable;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this before the alias to remove the orphaned registrations as well as listing them
+const PRUNE_FLAG: &str = "--prune";
+
+/// Where the registrations and the game disagree
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Reconciliation {
+    /// Human nations in the game that nobody has registered as
+    pub unclaimed: Vec<(u32, String)>,
+    /// Registrations for nations that aren't in the game at all
+    pub orphaned: Vec<(UserId, u32, String)>,
+}
+
+impl Reconciliation {
+    pub fn from_players(players: &[PotentialPlayer]) -> Self {
+        let mut reconciliation = Reconciliation::default();
+        for potential_player in players {
+            match potential_player {
+                PotentialPlayer::GameOnly(details) if details.player_status.is_human() => {
+                    reconciliation
+                        .unclaimed
+                        .push((details.nation_id, details.nation_name.clone()));
+                }
+                PotentialPlayer::RegisteredOnly(user_id, nation_id, nation_name) => {
+                    reconciliation
+                        .orphaned
+                        .push((*user_id, *nation_id, nation_name.clone()));
+                }
+                PotentialPlayer::GameOnly(_) | PotentialPlayer::RegisteredAndGame(_, _) => {}
+            }
+        }
+        reconciliation
+    }
+}
+
+fn resync_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+    prune: bool,
+) -> Result<String, CommandError> {
+    let (details, _) = cached_game_details(alias, db_conn, game_cache)?;
+    let playing_state = match details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => playing_state,
+        // While uploading, a registration without a nation just hasn't uploaded yet
+        NationDetails::Started(_) => {
+            return Err(CommandError::from(format!(
+                "{} is still uploading pretenders, try again once it's started",
+                alias
+            )))
+        }
+        NationDetails::Lobby(_) => {
+            return Err(CommandError::from(format!("{} has not started yet", alias)))
+        }
+    };
+    let reconciliation = Reconciliation::from_players(&playing_state.players);
+    if reconciliation == Reconciliation::default() {
+        return Ok(format!(
+            "Registrations for {} match the game, nothing to do",
+            alias
+        ));
+    }
+
+    let mut text = format!("Resync of {}:\n", alias);
+    if !reconciliation.unclaimed.is_empty() {
+        text.push_str("Unclaimed (in the game but nobody is registered):\n");
+        for (nation_id, nation_name) in &reconciliation.unclaimed {
+            text.push_str(&format!("- {} ({})\n", nation_name, nation_id));
+        }
+    }
+    if !reconciliation.orphaned.is_empty() {
+        text.push_str("Orphaned (registered but not in the game):\n");
+        for (user_id, nation_id, nation_name) in &reconciliation.orphaned {
+            text.push_str(&format!(
+                "- {} as {} ({})\n",
+                user_id.mention(),
+                nation_name,
+                nation_id
+            ));
+        }
+        if prune {
+            for (_, nation_id, _) in &reconciliation.orphaned {
+                db_conn.remove_nation_from_game(alias, *nation_id)?;
+            }
+            text.push_str(&format!(
+                "Removed {} orphaned registrations",
+                reconciliation.orphaned.len()
+            ));
+        } else {
+            text.push_str(&format!("Use `!resync {}` to remove them", PRUNE_FLAG));
+        }
+    }
+    Ok(text)
+}
+
+pub fn resync(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let prune = args.single_quoted_n::<String>().ok() == Some(PRUNE_FLAG.to_owned());
+    if prune {
+        args.skip();
+    }
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = resync_helper(db_conn, game_cache, &alias, prune)?;
+    info!("resync: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
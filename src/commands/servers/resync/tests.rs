@@ -0,0 +1,60 @@
+use super::*;
+
+use crate::model::enums::{NationStatus, SubmissionStatus};
+use crate::model::{GameData, Nation};
+
+fn game_data(nations: Vec<(u32, NationStatus)>) -> GameData {
+    GameData {
+        game_name: "test server 1".to_owned(),
+        nations: nations
+            .into_iter()
+            .map(|(id, status)| Nation {
+                id,
+                status,
+                submitted: SubmissionStatus::NotSubmitted,
+                connected: false,
+                name: format!("nation {}", id),
+                era: "EA".to_owned(),
+            })
+            .collect(),
+        turn: 32,
+        turn_timer: 3_600_000,
+    }
+}
+
+fn resync_test_server_1(db_conn: &DbConnection, prune: bool) -> Result<String, CommandError> {
+    // "test server 1" has UserId(101) registered as nation 1, which isn't in this game
+    let game_cache = GameCache::test_polled(
+        "test server 1",
+        game_data(vec![(7, NationStatus::Human), (8, NationStatus::AI)]),
+    );
+    resync_helper(db_conn, &game_cache, "test server 1", prune)
+}
+
+#[test]
+fn should_report_unclaimed_and_orphaned_without_pruning() {
+    let db_conn = DbConnection::test();
+    let text = resync_test_server_1(&db_conn, false).unwrap();
+    assert!(text.contains("(7)"));
+    // AI nations aren't anybody's to claim
+    assert!(!text.contains("(8)"));
+    assert!(text.contains("<@101>"));
+    assert_eq!(
+        db_conn
+            .players_with_nations_for_game_alias("test server 1")
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn should_prune_orphaned_registrations() {
+    let db_conn = DbConnection::test();
+    let text = resync_test_server_1(&db_conn, true).unwrap();
+    assert!(text.contains("Removed 1 orphaned registrations"));
+    assert!(db_conn
+        .players_with_nations_for_game_alias("test server 1")
+        .unwrap()
+        .is_empty());
+}
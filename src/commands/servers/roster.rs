@@ -0,0 +1,280 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::model::misc::Mentionable;
+use serenity::prelude::Context;
+
+use super::{alias_from_arg_or_channel_name, lobby_state_for_alias, lobby_state_for_owner};
+use crate::commands::servers::{lobby_details, GameCacheKey, NationDetails};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::Nations;
+use crate::model::{LobbyState, Player, RosterEntry};
+
+#[cfg(test)]
+mod tests;
+
+/// Whether every slot has somebody registered for or expected in it
+fn is_full(
+    db_conn: &DbConnection,
+    lobby_state: &LobbyState,
+    alias: &str,
+) -> Result<bool, CommandError> {
+    match lobby_details(db_conn, lobby_state, alias)?.nations {
+        NationDetails::Lobby(lobby_details) => Ok(lobby_details.is_full()),
+        NationDetails::Started(_) => Err(CommandError::from(
+            "Lobby details were for a started game. This is a bug.",
+        )),
+    }
+}
+
+/// Where registering as `nation_id` leaves somebody in a lobby that has a
+/// roster. `None` if they have a place, which they're given if there's one
+/// free, otherwise where they are on the waitlist.
+pub fn join_roster(
+    db_conn: &DbConnection,
+    lobby_state: &LobbyState,
+    alias: &str,
+    user_id: UserId,
+    nation_id: u32,
+) -> Result<Option<usize>, CommandError> {
+    let roster = db_conn.roster_for_alias(alias)?;
+    if roster
+        .iter()
+        .any(|entry| entry.user_id == user_id && !entry.waitlisted)
+    {
+        return Ok(None);
+    }
+    let waitlisted = is_full(db_conn, lobby_state, alias)?;
+    db_conn.set_roster_entry(
+        alias,
+        &RosterEntry {
+            user_id,
+            waitlisted,
+            option_nation_id: if waitlisted { Some(nation_id) } else { None },
+        },
+    )?;
+    if waitlisted {
+        Ok(Some(waitlist_position(db_conn, alias, user_id)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 1 is next in line
+fn waitlist_position(
+    db_conn: &DbConnection,
+    alias: &str,
+    user_id: UserId,
+) -> Result<usize, CommandError> {
+    db_conn
+        .roster_for_alias(alias)?
+        .iter()
+        .filter(|entry| entry.waitlisted)
+        .position(|entry| entry.user_id == user_id)
+        .map(|ix| ix + 1)
+        .ok_or_else(|| CommandError::from("Not on the waitlist. This is a bug."))
+}
+
+/// Gives free slots to the waitlist, first in line first. Whoever gets in is
+/// registered as the nation they asked for if nobody took it in the meantime.
+/// Returns what to tell them.
+pub fn promote_from_waitlist(
+    db_conn: &DbConnection,
+    lobby_state: &LobbyState,
+    alias: &str,
+) -> Result<Vec<String>, CommandError> {
+    let mut promotions = vec![];
+    for entry in db_conn
+        .roster_for_alias(alias)?
+        .into_iter()
+        .filter(|entry| entry.waitlisted)
+    {
+        if is_full(db_conn, lobby_state, alias)? {
+            break;
+        }
+        db_conn.set_roster_entry(
+            alias,
+            &RosterEntry {
+                user_id: entry.user_id,
+                waitlisted: false,
+                option_nation_id: None,
+            },
+        )?;
+        let players_nations = db_conn.players_with_nations_for_game_alias(alias)?;
+        let option_nation_id = entry.option_nation_id.filter(|nation_id| {
+            !players_nations
+                .iter()
+                .any(|(_, player_nation_id)| player_nation_id == nation_id)
+        });
+        promotions.push(match option_nation_id {
+            Some(nation_id) => {
+                let player = Player {
+                    discord_user_id: entry.user_id,
+                    turn_notifications: true,
+                };
+                db_conn.insert_player_into_server(&player, alias, nation_id)?;
+                format!(
+                    "{} is off the waitlist for {} and registered as {} ({})",
+                    entry.user_id.mention(),
+                    alias,
+                    Nations::get_nation_name(nation_id),
+                    nation_id
+                )
+            }
+            None => format!(
+                "{} is off the waitlist for {}, !register to pick a nation",
+                entry.user_id.mention(),
+                alias
+            ),
+        });
+    }
+    Ok(promotions)
+}
+
+/// What's left of a lobby after somebody is taken out of it
+#[derive(Debug)]
+pub struct RemovedFromLobby {
+    pub remaining_slots: Option<u32>,
+    /// What to tell whoever got in off the waitlist
+    pub promotions: Vec<String>,
+}
+
+/// Takes `user_id` out of a lobby along with the roster slot kept for them,
+/// and lets the waitlist into whatever that frees up. `None` if they weren't
+/// registered.
+pub fn remove_from_lobby(
+    db_conn: &DbConnection,
+    lobby_state: &LobbyState,
+    alias: &str,
+    user_id: UserId,
+) -> Result<Option<RemovedFromLobby>, CommandError> {
+    if db_conn.remove_player_from_game(alias, user_id)? == 0 {
+        return Ok(None);
+    }
+    // Otherwise their slot would still be kept for them
+    db_conn.remove_roster_entry(alias, user_id)?;
+    let promotions = promote_from_waitlist(db_conn, lobby_state, alias)?;
+
+    match lobby_details(db_conn, lobby_state, alias)?.nations {
+        NationDetails::Lobby(lobby_details) => Ok(Some(RemovedFromLobby {
+            remaining_slots: lobby_details.remaining_slots,
+            promotions,
+        })),
+        NationDetails::Started(_) => Err(CommandError::from(
+            "Lobby details were for a started game. This is a bug.",
+        )),
+    }
+}
+
+fn roster_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    arg_action: &str,
+    target_id: UserId,
+    alias: &str,
+) -> Result<String, CommandError> {
+    match arg_action {
+        "add" => {
+            let lobby_state = lobby_state_for_owner(db_conn, alias, author_id)?;
+            if db_conn
+                .roster_for_alias(alias)?
+                .iter()
+                .any(|entry| entry.user_id == target_id)
+            {
+                return Err(CommandError::from(format!(
+                    "{} is already on the roster for {}",
+                    target_id.mention(),
+                    alias
+                )));
+            }
+            // Somebody already registered already has a slot
+            let registered = db_conn
+                .players_with_nations_for_game_alias(alias)?
+                .iter()
+                .any(|(player, _)| player.discord_user_id == target_id);
+            let waitlisted = !registered && is_full(db_conn, &lobby_state, alias)?;
+            db_conn.set_roster_entry(
+                alias,
+                &RosterEntry {
+                    user_id: target_id,
+                    waitlisted,
+                    option_nation_id: None,
+                },
+            )?;
+            if waitlisted {
+                Ok(format!(
+                    "{} is full, {} is number {} on the waitlist",
+                    alias,
+                    target_id.mention(),
+                    waitlist_position(db_conn, alias, target_id)?
+                ))
+            } else {
+                Ok(format!(
+                    "{} is on the roster for {}, their slot is kept for them",
+                    target_id.mention(),
+                    alias
+                ))
+            }
+        }
+        "remove" => {
+            // Players can always take themselves off
+            let lobby_state = if author_id == target_id {
+                lobby_state_for_alias(db_conn, alias)?
+            } else {
+                lobby_state_for_owner(db_conn, alias, author_id)?
+            };
+            if !db_conn.remove_roster_entry(alias, target_id)? {
+                return Err(CommandError::from(format!(
+                    "{} isn't on the roster for {}",
+                    target_id.mention(),
+                    alias
+                )));
+            }
+            db_conn.remove_player_from_game(alias, target_id)?;
+            let mut lines = vec![format!(
+                "{} is off the roster for {}",
+                target_id.mention(),
+                alias
+            )];
+            lines.extend(promote_from_waitlist(db_conn, &lobby_state, alias)?);
+            Ok(lines.join("\n"))
+        }
+        _ => Err(CommandError::from(format!(
+            "Unknown roster change \"{}\", try add or remove",
+            arg_action
+        ))),
+    }
+}
+
+pub fn roster(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_action = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected add or remove"))?;
+    let target_id = args
+        .single_quoted::<UserId>()
+        .map_err(|_| CommandError::from("Expected an @mention of the player"))?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = roster_helper(db_conn, message.author.id, &arg_action, target_id, &alias)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+    info!("roster: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use super::*;
+
+use crate::model::enums::Era;
+
+const OWNER: UserId = UserId(3530);
+
+fn expected_and_waitlist(db_conn: &DbConnection, lobby_state: &LobbyState) -> (Vec<u64>, Vec<u64>) {
+    match lobby_details(db_conn, lobby_state, "lobby")
+        .unwrap()
+        .nations
+    {
+        NationDetails::Lobby(lobby_details) => (
+            lobby_details
+                .expected
+                .iter()
+                .map(|user_id| user_id.0)
+                .collect(),
+            lobby_details
+                .waitlist
+                .iter()
+                .map(|user_id| user_id.0)
+                .collect(),
+        ),
+        NationDetails::Started(_) => panic!("lobby should be a lobby"),
+    }
+}
+
+#[test]
+fn should_waitlist_once_the_roster_is_full_and_promote_on_removal() {
+    let db_conn = DbConnection::test();
+    let lobby_state = db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 2, &[]);
+
+    roster_helper(&db_conn, OWNER, "add", UserId(3531), "lobby").unwrap();
+    roster_helper(&db_conn, OWNER, "add", UserId(3532), "lobby").unwrap();
+    assert_eq!(
+        expected_and_waitlist(&db_conn, &lobby_state),
+        (vec![3531, 3532], vec![])
+    );
+    assert!(is_full(&db_conn, &lobby_state, "lobby").unwrap());
+
+    // expected players just register, everyone else queues with what they wanted
+    assert_eq!(
+        join_roster(&db_conn, &lobby_state, "lobby", UserId(3531), 12).unwrap(),
+        None
+    );
+    assert_eq!(
+        join_roster(&db_conn, &lobby_state, "lobby", UserId(3533), 10).unwrap(),
+        Some(1)
+    );
+    assert_eq!(
+        join_roster(&db_conn, &lobby_state, "lobby", UserId(3534), 11).unwrap(),
+        Some(2)
+    );
+    assert_eq!(
+        expected_and_waitlist(&db_conn, &lobby_state),
+        (vec![3531, 3532], vec![3533, 3534])
+    );
+
+    let text = roster_helper(&db_conn, OWNER, "remove", UserId(3532), "lobby").unwrap();
+    assert!(text.contains("<@3533> is off the waitlist for lobby and registered as"));
+    assert!(db_conn
+        .players_with_nations_for_game_alias("lobby")
+        .unwrap()
+        .iter()
+        .any(|(player, nation_id)| player.discord_user_id == UserId(3533) && *nation_id == 10));
+    assert_eq!(
+        expected_and_waitlist(&db_conn, &lobby_state),
+        (vec![3531], vec![3534])
+    );
+}
+
+#[test]
+fn should_only_let_the_owner_change_the_roster_but_anyone_leave() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 1, &[]);
+    roster_helper(&db_conn, OWNER, "add", UserId(3535), "lobby").unwrap();
+    // full, so this one is waitlisted
+    let text = roster_helper(&db_conn, OWNER, "add", UserId(3536), "lobby").unwrap();
+    assert!(text.contains("number 1 on the waitlist"));
+    assert!(roster_helper(&db_conn, OWNER, "add", UserId(3536), "lobby").is_err());
+
+    assert!(roster_helper(&db_conn, UserId(3536), "add", UserId(3537), "lobby").is_err());
+    assert!(roster_helper(&db_conn, UserId(3536), "remove", UserId(3535), "lobby").is_err());
+    roster_helper(&db_conn, UserId(3536), "remove", UserId(3536), "lobby").unwrap();
+    assert!(roster_helper(&db_conn, OWNER, "remove", UserId(3536), "lobby").is_err());
+    assert!(roster_helper(&db_conn, OWNER, "swap", UserId(3535), "lobby").is_err());
+}
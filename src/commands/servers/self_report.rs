@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::{Context, Mutex};
+use std::collections::HashMap;
+use typemap::Key;
+
+use super::alias_from_arg_or_channel_name;
+use crate::commands::servers::{CachedPoll, GameCacheKey};
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Players saying they've taken their turn while the server can't tell us.
+/// Only kept in memory: the next good poll makes them pointless either way.
+/// (turn, when they said so) by nation ID
+type AliasReports = HashMap<u32, (i32, DateTime<Utc>)>;
+
+#[derive(Default)]
+pub struct SelfReports {
+    reports: Mutex<HashMap<String, AliasReports>>,
+}
+
+pub struct SelfReportsKey;
+impl Key for SelfReportsKey {
+    type Value = SelfReports;
+}
+
+impl SelfReports {
+    pub fn report(&self, alias: &str, nation_id: u32, turn: i32, reported_at: DateTime<Utc>) {
+        self.reports
+            .lock()
+            .entry(alias.to_owned())
+            .or_default()
+            .insert(nation_id, (turn, reported_at));
+    }
+
+    /// The nations that say they've played `turn` since we last heard from the
+    /// server at `fetched_at`. Anything older is dropped, since the server has
+    /// answered for itself since.
+    pub fn unverified(&self, alias: &str, turn: i32, fetched_at: DateTime<Utc>) -> Vec<u32> {
+        let mut reports = self.reports.lock();
+        let alias_reports = match reports.get_mut(alias) {
+            Some(alias_reports) => alias_reports,
+            None => return vec![],
+        };
+        alias_reports.retain(|_, &mut (reported_turn, reported_at)| {
+            reported_turn == turn && reported_at > fetched_at
+        });
+        let mut nation_ids: Vec<u32> = alias_reports.keys().cloned().collect();
+        if nation_ids.is_empty() {
+            reports.remove(alias);
+        }
+        nation_ids.sort_unstable();
+        nation_ids
+    }
+}
+
+fn played_helper(
+    db_conn: &DbConnection,
+    self_reports: &SelfReports,
+    option_poll: Option<&CachedPoll>,
+    author_id: UserId,
+    alias: &str,
+    now: DateTime<Utc>,
+) -> Result<String, CommandError> {
+    let cache_entry = match option_poll {
+        Some(CachedPoll {
+            option_cache_entry: Some(cache_entry),
+            option_error: Some(_),
+            ..
+        }) => cache_entry,
+        Some(CachedPoll {
+            option_error: None, ..
+        }) => {
+            return Err(CommandError::from(format!(
+                "{} is answering fine, !details will show when your turn is in",
+                alias
+            )))
+        }
+        _ => {
+            return Err(CommandError::from(format!(
+                "Never got a response from {}, so don't know what turn it's on",
+                alias
+            )))
+        }
+    };
+    let nation_ids: Vec<u32> = db_conn
+        .players_with_nations_for_game_alias(alias)?
+        .into_iter()
+        .filter(|(player, _)| player.discord_user_id == author_id)
+        .map(|(_, nation_id)| nation_id)
+        .collect();
+    if nation_ids.is_empty() {
+        return Err(CommandError::from(format!(
+            "You're not registered in {}",
+            alias
+        )));
+    }
+
+    let turn = cache_entry.game_data.turn;
+    for nation_id in nation_ids {
+        self_reports.report(alias, nation_id, turn, now);
+    }
+    Ok(format!(
+        "Noted that you've played turn {} in {}. !details will show it as unverified until the server is back",
+        turn, alias
+    ))
+}
+
+pub fn played(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let self_reports = data
+        .get::<SelfReportsKey>()
+        .ok_or("No SelfReports were created on startup. This is a bug.")?;
+    let option_poll = game_cache.get_poll(&alias);
+    let text = played_helper(
+        db_conn,
+        self_reports,
+        option_poll.as_ref(),
+        message.author.id,
+        &alias,
+        Utc::now(),
+    )?;
+    info!("played: replying with: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use super::*;
+
+use crate::commands::servers::CacheEntry;
+use crate::model::{GameData, Player};
+use chrono::{Duration, TimeZone};
+
+fn cached_poll(turn: i32, fetched_at: DateTime<Utc>, option_error: Option<&str>) -> CachedPoll {
+    CachedPoll {
+        polled_at: fetched_at + Duration::minutes(10),
+        option_cache_entry: Some(CacheEntry {
+            game_data: GameData {
+                game_name: "foo".to_owned(),
+                nations: vec![],
+                turn,
+                turn_timer: 3_600_000,
+            },
+            option_snek_state: None,
+            fetched_at,
+        }),
+        option_error: option_error.map(|error| error.to_owned()),
+        option_error_category: option_error.map(|_| "down"),
+    }
+}
+
+#[test]
+fn should_keep_reports_until_the_server_answers() {
+    let self_reports = SelfReports::default();
+    let fetched_at = Utc.timestamp(10 * 3600, 0);
+    self_reports.report("foo", 5, 12, fetched_at + Duration::minutes(5));
+
+    assert_eq!(self_reports.unverified("foo", 12, fetched_at), vec![5]);
+    assert!(self_reports.unverified("bar", 12, fetched_at).is_empty());
+    // the server came back after the report
+    assert!(self_reports
+        .unverified("foo", 12, fetched_at + Duration::minutes(6))
+        .is_empty());
+    assert!(self_reports.unverified("foo", 12, fetched_at).is_empty());
+}
+
+#[test]
+fn should_drop_reports_for_other_turns() {
+    let self_reports = SelfReports::default();
+    let fetched_at = Utc.timestamp(10 * 3600, 0);
+    self_reports.report("foo", 5, 12, fetched_at + Duration::minutes(5));
+
+    assert!(self_reports.unverified("foo", 13, fetched_at).is_empty());
+}
+
+#[test]
+fn should_only_take_reports_while_the_server_is_down() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_player_into_server(
+            &Player {
+                discord_user_id: UserId(201),
+                turn_notifications: true,
+            },
+            "test server 1",
+            5,
+        )
+        .unwrap();
+    let self_reports = SelfReports::default();
+    let fetched_at = Utc.timestamp(10 * 3600, 0);
+    let now = fetched_at + Duration::minutes(20);
+
+    let up = cached_poll(12, fetched_at, None);
+    assert!(played_helper(
+        &db_conn,
+        &self_reports,
+        Some(&up),
+        UserId(201),
+        "test server 1",
+        now
+    )
+    .is_err());
+
+    let down = cached_poll(12, fetched_at, Some("timed out"));
+    assert!(played_helper(
+        &db_conn,
+        &self_reports,
+        Some(&down),
+        UserId(999),
+        "test server 1",
+        now
+    )
+    .is_err());
+    played_helper(
+        &db_conn,
+        &self_reports,
+        Some(&down),
+        UserId(201),
+        "test server 1",
+        now,
+    )
+    .unwrap();
+    assert_eq!(
+        self_reports.unverified("test server 1", 12, fetched_at),
+        vec![5]
+    );
+}
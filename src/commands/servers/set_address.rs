@@ -0,0 +1,114 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::add_server::probe_server;
+use super::{alias_from_arg_or_channel_name, author_is_game_admin, check_owner_or_admin};
+use crate::commands::servers::GameCacheKey;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+use crate::notifier::DiscordNotifier;
+use crate::server::{normalise_server_address, ServerConnection};
+
+#[cfg(test)]
+mod tests;
+
+/// For when the host's name itself changes. A server that only moves to a new
+/// IP under the same name needs nothing, it's looked up again on every poll.
+/// `option_game_name` is what the old address last said the game was called,
+/// to stop a typo pointing the game at somebody else's.
+fn set_address_helper<C: ServerConnection>(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    author_is_admin: bool,
+    alias: &str,
+    arg_address: &str,
+    option_game_name: Option<&str>,
+) -> Result<String, CommandError> {
+    check_owner_or_admin(db_conn, alias, author_id, author_is_admin)?;
+    let old_address = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::StartedState(started_state, _)
+        | GameServerState::Archived(started_state, _) => started_state.address,
+        GameServerState::Lobby(_) => {
+            return Err(CommandError::from(format!(
+                "{} hasn't started yet, use !start to give it an address",
+                alias
+            )))
+        }
+    };
+    let address = normalise_server_address(arg_address)?;
+    if address == old_address {
+        return Err(CommandError::from(format!(
+            "{} is already at {}",
+            alias, address
+        )));
+    }
+    let game_data = probe_server::<C>(&address)?;
+    if let Some(game_name) = option_game_name {
+        if game_data.game_name != game_name {
+            return Err(CommandError::from(format!(
+                "The server at {} is running {}, not {}",
+                address, game_data.game_name, game_name
+            )));
+        }
+    }
+    db_conn
+        .set_address_for_alias(alias, &address)
+        .map_err(|e| {
+            if e.to_string()
+                .contains("UNIQUE constraint failed: started_servers.address")
+            {
+                CommandError::from(format!("Another game is already at {}", address))
+            } else {
+                CommandError::from(e)
+            }
+        })?;
+    Ok(format!(
+        "{} has moved from {} to {}, it'll be checked there from the next poll",
+        alias, old_address, address
+    ))
+}
+
+pub fn set_address<C: ServerConnection>(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_address = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected the game's new address:port"))?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let option_game_name = game_cache
+        .get_poll(&alias)
+        .and_then(|cached_poll| cached_poll.option_cache_entry)
+        .map(|cache_entry| cache_entry.game_data.game_name);
+    let author_is_admin = author_is_game_admin::<DiscordNotifier>(db_conn, message, &alias)?;
+    let text = set_address_helper::<C>(
+        db_conn,
+        message.author.id,
+        author_is_admin,
+        &alias,
+        &arg_address,
+        option_game_name.as_deref(),
+    )?;
+    // Anything cached is from the old address
+    game_cache.invalidate(&alias);
+    info!("set_address: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
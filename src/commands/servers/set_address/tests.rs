@@ -0,0 +1,121 @@
+use super::*;
+
+use crate::model::enums::Era;
+use crate::model::{GameData, GameServer, LobbyState};
+use crate::server::test_helpers::RecordingServerConnection;
+
+fn game_data(game_name: &str) -> GameData {
+    GameData {
+        game_name: game_name.to_owned(),
+        nations: vec![],
+        turn: 12,
+        turn_timer: 0,
+    }
+}
+
+fn set_address(
+    db_conn: &DbConnection,
+    arg_address: &str,
+    option_game_name: Option<&str>,
+) -> Result<String, CommandError> {
+    set_address_helper::<RecordingServerConnection>(
+        db_conn,
+        UserId(3881),
+        false,
+        "test server 1",
+        arg_address,
+        option_game_name,
+    )
+}
+
+fn address(db_conn: &DbConnection) -> String {
+    match db_conn.game_for_alias("test server 1").unwrap().state {
+        GameServerState::StartedState(started_state, _) => started_state.address,
+        _ => panic!("test server 1 should have started"),
+    }
+}
+
+#[test]
+fn should_move_a_game_to_its_new_host() {
+    let db_conn = DbConnection::test();
+    RecordingServerConnection::script_game_data(
+        "new.host.3881.example.com:2556",
+        vec![game_data("renamed")],
+    );
+    let text = set_address(&db_conn, "new.host.3881.example.com", Some("renamed")).unwrap();
+    assert_eq!(
+        text,
+        "test server 1 has moved from test.server.address:1 to new.host.3881.example.com:2556, it'll be checked there from the next poll"
+    );
+    assert_eq!(address(&db_conn), "new.host.3881.example.com:2556");
+    // Registrations come along
+    assert_eq!(
+        db_conn
+            .players_with_nations_for_game_alias("test server 1")
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn should_refuse_an_address_running_another_game() {
+    let db_conn = DbConnection::test();
+    RecordingServerConnection::script_game_data(
+        "new.host.3882.example.com:2556",
+        vec![game_data("somebody else's")],
+    );
+    assert!(set_address(&db_conn, "new.host.3882.example.com", Some("mine")).is_err());
+    // Nor one that isn't answering
+    assert!(set_address(&db_conn, "nothing.3882.example.com", None).is_err());
+    assert_eq!(address(&db_conn), "test.server.address:1");
+}
+
+#[test]
+fn should_refuse_an_address_another_game_has() {
+    let db_conn = DbConnection::test();
+    RecordingServerConnection::script_game_data(
+        "test.server.address:2",
+        vec![game_data("test server 2")],
+    );
+    let err = set_address(&db_conn, "test.server.address:2", None).unwrap_err();
+    assert!(err.0.contains("Another game is already at"));
+    assert_eq!(address(&db_conn), "test.server.address:1");
+}
+
+#[test]
+fn should_only_let_the_owner_or_an_admin_move_a_game() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(3883),
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+    let err = set_address_helper::<RecordingServerConnection>(
+        &db_conn,
+        UserId(3884),
+        false,
+        "lobby",
+        "new.host.3883.example.com",
+        None,
+    )
+    .unwrap_err();
+    assert!(err.0.contains("Only the owner"));
+    // Even the owner has to !start it first
+    let err = set_address_helper::<RecordingServerConnection>(
+        &db_conn,
+        UserId(3883),
+        false,
+        "lobby",
+        "new.host.3883.example.com",
+        None,
+    )
+    .unwrap_err();
+    assert!(err.0.contains("use !start"));
+}
@@ -0,0 +1,134 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::{GameIcon, GameServerState};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of an emoji or colour to go back to the default look
+const CLEAR_FLAG: &str = "--clear";
+/// Enough for flags and skin tones, not enough to sneak a sentence in
+const MAX_EMOJI_CHARS: usize = 8;
+
+/// `#1abc9c` or `1abc9c`
+pub fn parse_colour(arg: &str) -> Option<u32> {
+    let hex = arg.trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// A custom discord emoji like `<:ulm:123456789012345678>`, or a short run of
+/// non-ASCII characters for the built-in ones
+pub fn is_emoji(arg: &str) -> bool {
+    let custom = arg
+        .strip_prefix("<:")
+        .or_else(|| arg.strip_prefix("<a:"))
+        .and_then(|rest| rest.strip_suffix('>'));
+    match custom {
+        Some(name_and_id) => match name_and_id.rsplit_once(':') {
+            Some((name, id)) => {
+                !name.is_empty()
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    && !id.is_empty()
+                    && id.chars().all(|c| c.is_ascii_digit())
+            }
+            None => false,
+        },
+        None => {
+            !arg.is_empty()
+                && arg.chars().count() <= MAX_EMOJI_CHARS
+                && arg.chars().all(|c| !c.is_ascii() && !c.is_whitespace())
+        }
+    }
+}
+
+fn seticon_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    arg_icons: &[String],
+) -> Result<String, CommandError> {
+    // Games added with !add have nobody in charge of them, so anyone can
+    let option_owner = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(lobby_state) => Some(lobby_state.owner),
+        GameServerState::StartedState(_, option_lobby_state)
+        | GameServerState::Archived(_, option_lobby_state) => {
+            option_lobby_state.map(|lobby_state| lobby_state.owner)
+        }
+    };
+    if option_owner.is_some_and(|owner| owner != author_id) {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} can do this",
+            alias
+        )));
+    }
+    if arg_icons.iter().any(|arg_icon| arg_icon == CLEAR_FLAG) {
+        db_conn.set_icon_for_alias(alias, &GameIcon::default())?;
+        return Ok(format!("{} is back to the default look", alias));
+    }
+
+    let mut icon = db_conn.icon_for_alias(alias)?;
+    for arg_icon in arg_icons {
+        if let Some(colour) = parse_colour(arg_icon) {
+            icon.option_colour = Some(colour);
+        } else if is_emoji(arg_icon) {
+            icon.option_emoji = Some(arg_icon.clone());
+        } else {
+            return Err(CommandError::from(format!(
+                "\"{}\" isn't an emoji or a colour like #1abc9c",
+                arg_icon
+            )));
+        }
+    }
+    db_conn.set_icon_for_alias(alias, &icon)?;
+    let colour = match icon.option_colour {
+        Some(colour) => format!("#{:06x}", colour),
+        None => "the default colour".to_owned(),
+    };
+    Ok(format!(
+        "{}{} will be shown in {}",
+        icon.prefix(),
+        alias,
+        colour
+    ))
+}
+
+pub fn seticon(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let mut arg_icons = vec![args.single_quoted::<String>().map_err(|_| {
+        CommandError::from("Expected an emoji and/or a colour like #1abc9c, or --clear")
+    })?];
+    // Both can be set at once, anything else after the first is the alias
+    if let Ok(arg_icon) = args.single_quoted_n::<String>() {
+        if parse_colour(&arg_icon).is_some() || is_emoji(&arg_icon) {
+            args.skip();
+            arg_icons.push(arg_icon);
+        }
+    }
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = seticon_helper(db_conn, message.author.id, &alias, &arg_icons)?;
+    info!("seticon: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
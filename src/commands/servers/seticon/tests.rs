@@ -0,0 +1,64 @@
+use super::*;
+
+#[test]
+fn should_validate_colours_and_emoji() {
+    assert_eq!(parse_colour("#1abc9c"), Some(0x1a_bc_9c));
+    assert_eq!(parse_colour("FF0000"), Some(0xff_00_00));
+    assert_eq!(parse_colour("#fff"), None);
+    assert_eq!(parse_colour("#zzzzzz"), None);
+
+    assert!(is_emoji("🐉"));
+    assert!(is_emoji("🇫🇮"));
+    assert!(is_emoji("<:ulm:123456789012345678>"));
+    assert!(is_emoji("<a:spin:42>"));
+    assert!(!is_emoji("dragon"));
+    assert!(!is_emoji("<:ulm:notanid>"));
+    assert!(!is_emoji("<@123>"));
+    assert!(!is_emoji(""));
+}
+
+#[test]
+fn should_set_parts_separately_and_clear_both() {
+    let db_conn = DbConnection::test();
+    // "test server 1" was added without a lobby, so has no owner
+    assert_eq!(
+        db_conn.icon_for_alias("test server 1").unwrap(),
+        GameIcon::default()
+    );
+
+    seticon_helper(&db_conn, UserId(327), "test server 1", &["🐉".to_owned()]).unwrap();
+    let text = seticon_helper(
+        &db_conn,
+        UserId(327),
+        "test server 1",
+        &["#1abc9c".to_owned()],
+    )
+    .unwrap();
+    assert_eq!(text, "🐉 test server 1 will be shown in #1abc9c");
+    assert_eq!(
+        db_conn.icon_for_alias("test server 1").unwrap(),
+        GameIcon {
+            option_emoji: Some("🐉".to_owned()),
+            option_colour: Some(0x1a_bc_9c),
+        }
+    );
+
+    seticon_helper(
+        &db_conn,
+        UserId(327),
+        "test server 1",
+        &[CLEAR_FLAG.to_owned()],
+    )
+    .unwrap();
+    assert_eq!(
+        db_conn.icon_for_alias("test server 1").unwrap(),
+        GameIcon::default()
+    );
+}
+
+#[test]
+fn should_reject_things_that_are_not_icons() {
+    let db_conn = DbConnection::test();
+    assert!(seticon_helper(&db_conn, UserId(327), "test server 1", &["big".to_owned()]).is_err());
+    assert!(seticon_helper(&db_conn, UserId(327), "not a game", &["🐉".to_owned()]).is_err());
+}
@@ -0,0 +1,179 @@
+use chrono::{DateTime, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::model::misc::Mentionable;
+use serenity::prelude::Context;
+
+use super::turn_check::{countdown_ping, countdown_threshold};
+use super::undone::{playing_state_for_alias, undone_players};
+use super::{alias_from_arg_or_channel_name, author_is_game_admin, check_owner_or_admin};
+use crate::commands::servers::{GameCacheKey, PlayingState};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::notifier::DiscordNotifier;
+
+#[cfg(test)]
+mod tests;
+
+/// Longer than any turn timer is likely to be, anything more is probably a typo
+const MAX_SIMULATED_MINS: u32 = 7 * 24 * 60;
+
+/// Their nations still to play, so the preview can say who without pinging them
+fn show_player(playing_state: &PlayingState, user_id: UserId) -> String {
+    undone_players(playing_state)
+        .into_iter()
+        .filter(|(undone_user_id, _)| *undone_user_id == user_id)
+        .map(|(_, details)| details.nation_name.clone())
+        .collect::<Vec<_>>()
+        .join(" & ")
+}
+
+fn show_players(playing_state: &PlayingState, user_ids: &[UserId]) -> String {
+    user_ids
+        .iter()
+        .map(|&user_id| show_player(playing_state, user_id))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Runs the poller's countdown with `mins_left` to go in the turn as it was last
+/// seen, and says what would go out. Nothing is sent or remembered, so the real
+/// countdown still fires when the time comes.
+fn simulate_reminder_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    author_is_admin: bool,
+    alias: &str,
+    playing_state: &PlayingState,
+    mins_left: u32,
+    now: DateTime<Utc>,
+) -> Result<String, CommandError> {
+    check_owner_or_admin(db_conn, alias, author_id, author_is_admin)?;
+    if mins_left > MAX_SIMULATED_MINS {
+        return Err(CommandError::from(format!(
+            "Expected at most {} minutes left",
+            MAX_SIMULATED_MINS
+        )));
+    }
+    let countdown_mins = match db_conn.countdown_mins_for_alias(alias)? {
+        Some(countdown_mins) => countdown_mins,
+        None => {
+            return Ok(format!(
+                "{} has no countdowns, nothing would be sent. Turn them on with !countdown",
+                alias
+            ))
+        }
+    };
+    let mins_left = mins_left as i32;
+    let threshold = match countdown_threshold(&countdown_mins, mins_left) {
+        Some(threshold) => threshold,
+        None => {
+            return Ok(format!(
+                "No countdown is due with {} minutes left, the first is at {} minutes",
+                mins_left,
+                countdown_mins.first().cloned().unwrap_or_default()
+            ))
+        }
+    };
+    let mut hypothetical_state = playing_state.clone();
+    hypothetical_state.days_remaining = mins_left / (24 * 60);
+    hypothetical_state.hours_remaining = mins_left / 60;
+    hypothetical_state.mins_remaining = mins_left % 60;
+    hypothetical_state.secs_remaining = 0;
+
+    let ping = match countdown_ping(db_conn, alias, &hypothetical_state, mins_left)? {
+        Some(ping) => ping,
+        None => {
+            return Ok(format!(
+                "The {} minute countdown would be due, but nobody it would ping is still to play turn {}",
+                threshold, playing_state.turn
+            ))
+        }
+    };
+    let routes = db_conn.notification_routes_for_alias(alias)?;
+    let mut message = ping.message.clone();
+    for &user_id in &ping.user_ids {
+        message = message.replace(
+            &user_id.mention(),
+            &format!("@{}", show_player(&hypothetical_state, user_id)),
+        );
+    }
+
+    let mut text = format!(
+        "With {} minutes left the {} minute countdown would fire:\n",
+        mins_left, threshold
+    );
+    if routes.reminder.to_channel() {
+        text.push_str(&format!("In the game's channels: {}\n", message));
+    }
+    if routes.reminder.to_dm() {
+        // Same as the poller, players on vacation asked not to be bothered
+        let vacations = db_conn.player_vacations_for_alias(alias, now)?;
+        let dm_user_ids: Vec<UserId> = ping
+            .user_ids
+            .iter()
+            .cloned()
+            .filter(|user_id| !vacations.contains_key(user_id))
+            .collect();
+        if dm_user_ids.is_empty() {
+            text.push_str("DMs: nobody, they're all on vacation\n");
+        } else {
+            text.push_str(&format!(
+                "DMs to: {}\n",
+                show_players(&hypothetical_state, &dm_user_ids)
+            ));
+        }
+    }
+    if !routes.reminder.to_channel() && !routes.reminder.to_dm() {
+        text.push_str(
+            "!notify has reminders for this game turned off, so it wouldn't go anywhere\n",
+        );
+    }
+    if !ping.capped.is_empty() {
+        text.push_str(&format!(
+            "Left out by their reminder cap: {}\n",
+            show_players(&hypothetical_state, &ping.capped)
+        ));
+    }
+    text.push_str("Nothing was actually sent");
+    Ok(text)
+}
+
+pub fn simulate_reminder(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let mins_left = args
+        .single_quoted::<u32>()
+        .map_err(|_| CommandError::from("Expected how many minutes would be left"))?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let author_is_admin = author_is_game_admin::<DiscordNotifier>(db_conn, message, &alias)?;
+    let playing_state = playing_state_for_alias(db_conn, game_cache, &alias)?;
+    let text = simulate_reminder_helper(
+        db_conn,
+        message.author.id,
+        author_is_admin,
+        &alias,
+        &playing_state,
+        mins_left,
+        Utc::now(),
+    )?;
+    info!("simulate_reminder: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
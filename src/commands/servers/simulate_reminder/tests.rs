@@ -0,0 +1,151 @@
+use super::*;
+
+use crate::commands::servers::{PlayerDetails, PotentialPlayer};
+use crate::model::enums::{Era, NationStatus, NotificationRoute, SubmissionStatus};
+use crate::model::{GameServer, GameServerState, LobbyState, NotificationRoutes, Player};
+use chrono::TimeZone;
+
+fn registered(user_id: UserId, nation_id: u32, submitted: SubmissionStatus) -> PotentialPlayer {
+    PotentialPlayer::RegisteredAndGame(
+        user_id,
+        PlayerDetails {
+            nation_id,
+            nation_name: format!("nation {}", nation_id),
+            submitted,
+            player_status: NationStatus::Human,
+            went_ai: false,
+            connected: false,
+        },
+    )
+}
+
+fn playing_state() -> PlayingState {
+    PlayingState {
+        players: vec![
+            registered(UserId(3891), 5, SubmissionStatus::NotSubmitted),
+            registered(UserId(3891), 6, SubmissionStatus::NotSubmitted),
+            registered(UserId(3892), 7, SubmissionStatus::NotSubmitted),
+            registered(UserId(3893), 8, SubmissionStatus::Submitted),
+        ],
+        turn: 12,
+        mins_remaining: 0,
+        hours_remaining: 20,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: Some(Utc.ymd(2021, 3, 12).and_hms(20, 0, 0)),
+        paused: false,
+        blitz: false,
+    }
+}
+
+fn simulate(db_conn: &DbConnection, mins_left: u32) -> Result<String, CommandError> {
+    simulate_reminder_helper(
+        db_conn,
+        UserId(3891),
+        false,
+        "test server 1",
+        &playing_state(),
+        mins_left,
+        Utc.ymd(2021, 3, 12).and_hms(0, 0, 0),
+    )
+}
+
+#[test]
+fn should_say_what_would_fire_without_sending_it() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_countdown_mins_for_alias("test server 1", Some(&[60, 15]))
+        .unwrap();
+
+    assert_eq!(
+        simulate(&db_conn, 10).unwrap(),
+        "With 10 minutes left the 15 minute countdown would fire:\n\
+         In the game's channels: **Only 10 minutes left for turn 12 in test server 1!** Still to play: @nation 5 & nation 6 @nation 7\n\
+         Nothing was actually sent"
+    );
+    // So the real one still goes out
+    assert!(db_conn
+        .mark_countdown_pinged("test server 1", 12, 15)
+        .unwrap());
+}
+
+#[test]
+fn should_list_dms_and_capped_players() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_countdown_mins_for_alias("test server 1", Some(&[60, 15]))
+        .unwrap();
+    db_conn
+        .insert_player_into_server(
+            &Player {
+                discord_user_id: UserId(3892),
+                turn_notifications: true,
+            },
+            "test server 1",
+            7,
+        )
+        .unwrap();
+    db_conn
+        .set_reminder_cap("test server 1", UserId(3892), Some(Some(1)))
+        .unwrap();
+    db_conn
+        .set_notification_routes_for_alias(
+            "test server 1",
+            &NotificationRoutes {
+                reminder: NotificationRoute::Dm,
+                ..NotificationRoutes::default()
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        simulate(&db_conn, 45).unwrap(),
+        "With 45 minutes left the 60 minute countdown would fire:\n\
+         DMs to: nation 5 & nation 6\n\
+         Left out by their reminder cap: nation 7\n\
+         Nothing was actually sent"
+    );
+}
+
+#[test]
+fn should_explain_when_nothing_would_fire() {
+    let db_conn = DbConnection::test();
+    assert!(simulate(&db_conn, 10)
+        .unwrap()
+        .contains("has no countdowns"));
+
+    db_conn
+        .set_countdown_mins_for_alias("test server 1", Some(&[60, 15]))
+        .unwrap();
+    assert_eq!(
+        simulate(&db_conn, 90).unwrap(),
+        "No countdown is due with 90 minutes left, the first is at 60 minutes"
+    );
+}
+
+#[test]
+fn should_only_let_the_owner_or_an_admin_simulate() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "owned game".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(3894),
+                era: Era::Early,
+                player_count: 4,
+                description: None,
+            }),
+        })
+        .unwrap();
+    let state = playing_state();
+    let now = Utc.ymd(2021, 3, 12).and_hms(0, 0, 0);
+
+    assert!(
+        simulate_reminder_helper(&db_conn, UserId(3891), false, "owned game", &state, 10, now)
+            .is_err()
+    );
+    assert!(
+        simulate_reminder_helper(&db_conn, UserId(3891), true, "owned game", &state, 10, now)
+            .is_ok()
+    );
+}
@@ -0,0 +1,275 @@
+use log::*;
+use serde::{Deserialize, Serialize};
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, UserId};
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::details2::send_json;
+use crate::commands::servers::{CacheEntry, GameCache, GameCacheKey};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::Era;
+use crate::model::{GameServer, GameServerState, LobbyState, Player, StartedState};
+
+#[cfg(test)]
+mod tests;
+
+/// Bump this whenever the snapshot changes shape, and keep importing the old
+/// versions by converting them in `parse_snapshot`
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Everything the db knows about one game, for moving it to another bot or for
+/// debugging. Discord ids are strings for the sake of javascript, like `!json`.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Snapshot {
+    pub version: u32,
+    pub alias: String,
+    pub option_started: Option<StartedSnapshot>,
+    pub option_lobby: Option<LobbySnapshot>,
+    pub players: Vec<PlayerSnapshot>,
+    /// The last poll, only there to help with debugging. Importing ignores it
+    /// since the poller will fetch it again.
+    #[serde(skip_deserializing)]
+    pub option_cache_entry: Option<CacheEntry>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct StartedSnapshot {
+    pub address: String,
+    pub last_seen_turn: i32,
+    pub option_snek_game_id: Option<u32>,
+    /// Missing from snapshots taken before games could be archived
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct LobbySnapshot {
+    pub owner: String,
+    pub era: String,
+    pub player_count: i32,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct PlayerSnapshot {
+    pub user_id: String,
+    pub nation_id: u32,
+    pub turn_notifications: bool,
+}
+
+fn parse_user_id(text: &str) -> Result<UserId, CommandError> {
+    text.parse::<u64>()
+        .map(UserId)
+        .map_err(|_| CommandError::from(format!("\"{}\" is not a discord user id", text)))
+}
+
+impl Snapshot {
+    pub fn new(
+        server: &GameServer,
+        players: &[(Player, u32)],
+        option_cache_entry: Option<CacheEntry>,
+    ) -> Self {
+        let archived = matches!(server.state, GameServerState::Archived(_, _));
+        let (option_started_state, option_lobby_state) = match &server.state {
+            GameServerState::StartedState(started_state, option_lobby_state)
+            | GameServerState::Archived(started_state, option_lobby_state) => {
+                (Some(started_state), option_lobby_state.as_ref())
+            }
+            GameServerState::Lobby(lobby_state) => (None, Some(lobby_state)),
+        };
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            alias: server.alias.clone(),
+            option_started: option_started_state.map(|started_state| StartedSnapshot {
+                address: started_state.address.clone(),
+                last_seen_turn: started_state.last_seen_turn,
+                option_snek_game_id: started_state.option_snek_game_id,
+                archived,
+            }),
+            option_lobby: option_lobby_state.map(|lobby_state| LobbySnapshot {
+                owner: lobby_state.owner.0.to_string(),
+                era: lobby_state.era.to_string(),
+                player_count: lobby_state.player_count,
+                description: lobby_state.description.clone(),
+            }),
+            players: players
+                .iter()
+                .map(|(player, nation_id)| PlayerSnapshot {
+                    user_id: player.discord_user_id.0.to_string(),
+                    nation_id: *nation_id,
+                    turn_notifications: player.turn_notifications,
+                })
+                .collect(),
+            option_cache_entry,
+        }
+    }
+
+    pub fn game_server(&self) -> Result<GameServer, CommandError> {
+        let option_lobby_state = match &self.option_lobby {
+            Some(lobby) => Some(LobbyState {
+                owner: parse_user_id(&lobby.owner)?,
+                era: Era::from_string(&lobby.era).ok_or_else(|| {
+                    CommandError::from(format!("\"{}\" is not an era", lobby.era))
+                })?,
+                player_count: lobby.player_count,
+                description: lobby.description.clone(),
+            }),
+            None => None,
+        };
+        let state = match (&self.option_started, option_lobby_state) {
+            (Some(started), option_lobby_state) => {
+                let started_state = StartedState {
+                    address: started.address.clone(),
+                    last_seen_turn: started.last_seen_turn,
+                    option_snek_game_id: started.option_snek_game_id,
+                };
+                if started.archived {
+                    GameServerState::Archived(started_state, option_lobby_state)
+                } else {
+                    GameServerState::StartedState(started_state, option_lobby_state)
+                }
+            }
+            (None, Some(lobby_state)) => GameServerState::Lobby(lobby_state),
+            (None, None) => {
+                return Err(CommandError::from(
+                    "Snapshot has neither a server nor a lobby",
+                ))
+            }
+        };
+        Ok(GameServer {
+            alias: self.alias.clone(),
+            state,
+        })
+    }
+}
+
+/// Reads any version of snapshot that we know how to, as the current version
+pub fn parse_snapshot(text: &str) -> Result<Snapshot, CommandError> {
+    let value: serde_json::Value = serde_json::from_str(text)?;
+    let version = value
+        .get("version")
+        .and_then(|version| version.as_u64())
+        .ok_or("Snapshot has no version")?;
+    if version > u64::from(SNAPSHOT_VERSION) {
+        return Err(CommandError::from(format!(
+            "Snapshot is version {} but this bot only understands up to version {}",
+            version, SNAPSHOT_VERSION
+        )));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+fn export_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+) -> Result<String, CommandError> {
+    let server = db_conn.game_for_alias(alias)?;
+    let players = db_conn.players_with_nations_for_game_alias(alias)?;
+    let option_cache_entry = game_cache
+        .get_poll(alias)
+        .and_then(|cached_poll| cached_poll.option_cache_entry);
+    let snapshot = Snapshot::new(&server, &players, option_cache_entry);
+    Ok(serde_json::to_string(&snapshot)?)
+}
+
+/// The game keeps posting where it's imported, since channel ids don't mean
+/// anything on another bot's servers
+fn import_helper(
+    db_conn: &DbConnection,
+    snapshot: &Snapshot,
+    channel_id: ChannelId,
+) -> Result<String, CommandError> {
+    if db_conn.game_for_alias(&snapshot.alias).is_ok() {
+        return Err(CommandError::from(format!(
+            "There is already a game called {}",
+            snapshot.alias
+        )));
+    }
+    let server = snapshot.game_server()?;
+    let players = snapshot
+        .players
+        .iter()
+        .map(|player| {
+            Ok((
+                Player {
+                    discord_user_id: parse_user_id(&player.user_id)?,
+                    turn_notifications: player.turn_notifications,
+                },
+                player.nation_id,
+            ))
+        })
+        .collect::<Result<Vec<_>, CommandError>>()?;
+
+    db_conn.insert_game_server(&server)?;
+    if let GameServerState::StartedState(started_state, _)
+    | GameServerState::Archived(started_state, _) = &server.state
+    {
+        if let Some(snek_game_id) = started_state.option_snek_game_id {
+            db_conn.set_snek_game_id_for_alias(&server.alias, snek_game_id)?;
+        }
+    }
+    for (player, nation_id) in &players {
+        db_conn.insert_player_into_server(player, &server.alias, *nation_id)?;
+    }
+    db_conn.set_channel_for_alias(&server.alias, channel_id)?;
+    Ok(format!(
+        "Imported {} with {} registered players",
+        server.alias,
+        players.len()
+    ))
+}
+
+pub fn export(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let json = export_helper(db_conn, game_cache, &alias)?;
+    info!("export: replying with: {}", json);
+    send_json(message.channel_id, &alias, &json)
+}
+
+/// Takes the snapshot as an attached file, or pasted after the command
+pub fn import(context: &mut Context, message: &Message, args: Args) -> Result<(), CommandError> {
+    let text = match message.attachments.first() {
+        Some(attachment) => String::from_utf8(attachment.download()?)?,
+        None => args
+            .full()
+            .trim()
+            .trim_start_matches("```json")
+            .trim_matches('`')
+            .to_owned(),
+    };
+    if text.trim().is_empty() {
+        return Err(CommandError::from(
+            "Attach the file from !export, or paste its contents after !import",
+        ));
+    }
+    let snapshot = parse_snapshot(&text)?;
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = import_helper(db_conn, &snapshot, message.channel_id)?;
+    info!("import: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
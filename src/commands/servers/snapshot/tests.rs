@@ -0,0 +1,62 @@
+use super::*;
+
+#[test]
+fn should_import_what_was_exported() {
+    let db_conn = DbConnection::test();
+    let game_cache = GameCache::test();
+    let server = db_conn.game_for_alias("test server 1").unwrap();
+    let mut players = db_conn
+        .players_with_nations_for_game_alias("test server 1")
+        .unwrap();
+
+    let json = export_helper(&db_conn, &game_cache, "test server 1").unwrap();
+    let snapshot = parse_snapshot(&json).unwrap();
+    // still there
+    assert!(import_helper(&db_conn, &snapshot, ChannelId(1234)).is_err());
+
+    db_conn.remove_server("test server 1").unwrap();
+    import_helper(&db_conn, &snapshot, ChannelId(1234)).unwrap();
+    assert_eq!(db_conn.game_for_alias("test server 1").unwrap(), server);
+    let mut imported_players = db_conn
+        .players_with_nations_for_game_alias("test server 1")
+        .unwrap();
+    players.sort_by_key(|&(_, nation_id)| nation_id);
+    imported_players.sort_by_key(|&(_, nation_id)| nation_id);
+    assert_eq!(imported_players, players);
+    assert_eq!(
+        db_conn.channel_for_alias("test server 1").unwrap(),
+        Some(ChannelId(1234))
+    );
+}
+
+#[test]
+fn should_round_trip_lobbies() {
+    let server = GameServer {
+        alias: "lobby".to_owned(),
+        state: GameServerState::Lobby(LobbyState {
+            owner: UserId(123_456_789_012_345_678),
+            era: Era::Late,
+            player_count: 8,
+            description: Some("no rushing".to_owned()),
+        }),
+    };
+    let snapshot = Snapshot::new(&server, &[], None);
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert!(json.contains("\"owner\":\"123456789012345678\""));
+
+    assert_eq!(
+        parse_snapshot(&json).unwrap().game_server().unwrap(),
+        server
+    );
+}
+
+#[test]
+fn should_refuse_snapshots_from_the_future() {
+    let json = format!(
+        "{{\"version\": {}, \"alias\": \"foo\", \"option_started\": null, \
+         \"option_lobby\": null, \"players\": []}}",
+        SNAPSHOT_VERSION + 1
+    );
+    assert!(parse_snapshot(&json).is_err());
+    assert!(parse_snapshot("{\"alias\": \"foo\"}").is_err());
+}
@@ -0,0 +1,98 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+fn spectate_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    user_id: UserId,
+) -> Result<String, CommandError> {
+    // So that a typo doesn't look like it worked
+    db_conn.game_for_alias(alias)?;
+    let is_player = db_conn
+        .players_with_nations_for_game_alias(alias)?
+        .iter()
+        .any(|(player, _)| player.discord_user_id == user_id);
+    if is_player {
+        return Err(CommandError::from(format!(
+            "You're playing in {}, so you get its DMs already",
+            alias
+        )));
+    }
+    if !db_conn.add_spectator(alias, user_id)? {
+        return Err(CommandError::from(format!(
+            "You're already spectating {}",
+            alias
+        )));
+    }
+    Ok(format!(
+        "You'll be DMed {}'s new turn, countdown and stall announcements",
+        alias
+    ))
+}
+
+fn unspectate_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    user_id: UserId,
+) -> Result<String, CommandError> {
+    if !db_conn.remove_spectator(alias, user_id)? {
+        return Err(CommandError::from(format!(
+            "You aren't spectating {}",
+            alias
+        )));
+    }
+    Ok(format!("You won't be DMed about {} any more", alias))
+}
+
+pub fn spectate(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = spectate_helper(db_conn, &alias, message.author.id)?;
+    info!("spectate: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
+
+pub fn unspectate(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = unspectate_helper(db_conn, &alias, message.author.id)?;
+    info!("unspectate: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
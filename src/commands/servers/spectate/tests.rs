@@ -0,0 +1,52 @@
+use super::*;
+
+use crate::commands::servers::turn_check::spectator_dms;
+
+#[test]
+fn should_spectate_without_joining_the_game() {
+    let db_conn = DbConnection::test();
+    let players_before = db_conn
+        .players_with_nations_for_game_alias("test server 1")
+        .unwrap();
+
+    spectate_helper(&db_conn, "test server 1", UserId(330)).unwrap();
+    assert!(spectate_helper(&db_conn, "test server 1", UserId(330)).is_err());
+    assert_eq!(
+        db_conn.spectators_for_alias("test server 1").unwrap(),
+        vec![UserId(330)]
+    );
+    assert_eq!(
+        db_conn
+            .players_with_nations_for_game_alias("test server 1")
+            .unwrap(),
+        players_before
+    );
+
+    unspectate_helper(&db_conn, "test server 1", UserId(330)).unwrap();
+    assert!(unspectate_helper(&db_conn, "test server 1", UserId(330)).is_err());
+    assert!(db_conn
+        .spectators_for_alias("test server 1")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_not_spectate_a_game_you_play_in_or_that_does_not_exist() {
+    let db_conn = DbConnection::test();
+    // UserId(101) is registered in "test server 1"
+    assert!(spectate_helper(&db_conn, "test server 1", UserId(101)).is_err());
+    assert!(spectate_helper(&db_conn, "not a game", UserId(330)).is_err());
+}
+
+#[test]
+fn should_dm_each_spectator_each_announcement_once() {
+    let dms = spectator_dms(
+        &[UserId(1), UserId(2), UserId(1)],
+        &["one".to_owned(), "two".to_owned()],
+    );
+    let sent: Vec<(u64, &str)> = dms
+        .iter()
+        .map(|dm| (dm.user_id.0, dm.message.as_str()))
+        .collect();
+    assert_eq!(sent, vec![(1, "one"), (1, "two"), (2, "one"), (2, "two")]);
+}
@@ -0,0 +1,65 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::turn_check::DEFAULT_STALL_GRACE_HOURS;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// A week past usual and nobody has noticed, an alert is the least of the problems
+const MAX_STALL_GRACE_HOURS: u32 = 7 * 24;
+
+/// "default" or how many hours. `None` is the default.
+fn parse_stall_grace_hours(text: &str) -> Result<Option<u32>, CommandError> {
+    match text {
+        "default" => Ok(None),
+        _ => match text.parse::<u32>() {
+            Ok(hours) if hours <= MAX_STALL_GRACE_HOURS => Ok(Some(hours)),
+            _ => Err(CommandError::from(format!(
+                "The stall grace period is \"default\" or a number of hours up to {}",
+                MAX_STALL_GRACE_HOURS
+            ))),
+        },
+    }
+}
+
+fn stall_grace_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    text: &str,
+) -> Result<String, CommandError> {
+    let option_hours = parse_stall_grace_hours(text)?;
+    db_conn.set_stall_grace_hours_for_alias(alias, option_hours)?;
+    Ok(format!(
+        "{} will only be called stalled once a turn is {}h past both its timer and how long its turns usually take",
+        alias,
+        option_hours.unwrap_or(DEFAULT_STALL_GRACE_HOURS)
+    ))
+}
+
+pub fn stall_grace(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let text = args.single_quoted::<String>()?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = stall_grace_helper(db_conn, &alias, &text)?;
+    info!("stall_grace: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use super::*;
+
+#[test]
+fn should_parse_stall_grace_hours() {
+    assert_eq!(parse_stall_grace_hours("default").unwrap(), None);
+    assert_eq!(parse_stall_grace_hours("0").unwrap(), Some(0));
+    assert_eq!(parse_stall_grace_hours("24").unwrap(), Some(24));
+    assert!(parse_stall_grace_hours("1000").is_err());
+    assert!(parse_stall_grace_hours("soon").is_err());
+}
+
+#[test]
+fn should_save_stall_grace_hours() {
+    let db_conn = DbConnection::test();
+    stall_grace_helper(&db_conn, "test server 1", "24").unwrap();
+    assert_eq!(
+        db_conn
+            .stall_grace_hours_for_alias("test server 1")
+            .unwrap(),
+        Some(24)
+    );
+    stall_grace_helper(&db_conn, "test server 1", "default").unwrap();
+    assert_eq!(
+        db_conn
+            .stall_grace_hours_for_alias("test server 1")
+            .unwrap(),
+        None
+    );
+    assert!(stall_grace_helper(&db_conn, "no such game", "12").is_err());
+}
@@ -1,4 +1,4 @@
-use crate::server::{ServerConnection, RealServerConnection};
+use crate::server::{normalise_server_address, ServerConnection, RealServerConnection};
 
 use serenity::framework::standard::{Args, CommandError};
 use serenity::model::channel::Message;
@@ -9,34 +9,45 @@ use crate::db::*;
 use crate::model::*;
 use crate::commands::servers::{get_details_for_alias, StartedStateDetails, PotentialPlayer};
 use crate::commands::servers::NationDetails;
+use crate::commands::servers::{GameCache, GameCacheKey};
 use crate::commands::servers::turn_check::{create_messages_for_new_turn, notify_player_for_new_turn, NewTurnNation};
+use crate::notifier::DiscordNotifier;
 
 fn start_helper<C: ServerConnection>(
     db_conn: &DbConnection,
+    game_cache: &GameCache,
     address: &str,
     alias: &str,
 ) -> Result<(), CommandError> {
     let server = db_conn.game_for_alias(&alias)?;
 
     match server.state {
-        GameServerState::StartedState(_, _) => {
+        GameServerState::StartedState(_, _) | GameServerState::Archived(_, _) => {
             return Err(CommandError::from("game already started"))
         }
         GameServerState::Lobby(lobby_state) => {
+            let address = &normalise_server_address(address)?;
             let game_data = C::get_game_data(&address)?;
-            if game_data.nations.len() as i32 > lobby_state.player_count {
+            let too_many = lobby_state
+                .option_player_count()
+                .is_some_and(|player_count| game_data.nations.len() as i32 > player_count);
+            if too_many {
                 return Err(CommandError::from("game has more players than the lobby"));
             }
 
             let started_state = StartedState {
                 address: address.to_string(),
                 last_seen_turn: game_data.turn,
+                option_snek_game_id: None,
             };
 
             db_conn.insert_started_state(&alias, &started_state)?;
+            // Anything cached from before an !unstart is from the wrong server
+            game_cache.invalidate(alias);
 
             // This is a bit of a hack, the turncheck should take care of it
-            let started_details = get_details_for_alias::<RealServerConnection>(db_conn, alias)?;
+            let started_details =
+                get_details_for_alias::<RealServerConnection>(db_conn, game_cache, alias)?;
             let mut new_turn_messages = vec![];
             if let NationDetails::Started(started_details) = started_details.nations {
                 if let StartedStateDetails::Uploading(uploading_details) = started_details.state {
@@ -54,7 +65,7 @@ fn start_helper<C: ServerConnection>(
                 }
             }
             for new_turn_message in &new_turn_messages {
-                let _ = notify_player_for_new_turn(new_turn_message);
+                let _ = notify_player_for_new_turn::<DiscordNotifier>(new_turn_message);
             }
         }
     }
@@ -70,6 +81,9 @@ pub fn start<C: ServerConnection>(
     let db_conn = data
         .get::<DbConnectionKey>()
         .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
     let address = args.single_quoted::<String>()?;
     let alias = alias_from_arg_or_channel_name(&mut args, &message)?;
     if !args.is_empty() {
@@ -77,7 +91,7 @@ pub fn start<C: ServerConnection>(
             "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
         ));
     }
-    start_helper::<C>(db_conn, &address, &alias)?;
+    start_helper::<C>(db_conn, game_cache, &address, &alias)?;
     message.reply(&"started!")?;
     Ok(())
 }
@@ -0,0 +1,70 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::turn_check::DEFAULT_START_REMINDER_HOURS;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// A week of waiting is already well past forgotten
+const MAX_START_REMINDER_HOURS: u32 = 7 * 24;
+
+/// "off", "default" or how many hours. 0 is off, `None` is the default.
+fn parse_start_reminder_hours(text: &str) -> Result<Option<u32>, CommandError> {
+    match text {
+        "off" => Ok(Some(0)),
+        "default" => Ok(None),
+        _ => match text.parse::<u32>() {
+            Ok(hours) if hours > 0 && hours <= MAX_START_REMINDER_HOURS => Ok(Some(hours)),
+            _ => Err(CommandError::from(format!(
+                "Start reminders are \"off\", \"default\" or a number of hours between 1 and {}",
+                MAX_START_REMINDER_HOURS
+            ))),
+        },
+    }
+}
+
+fn start_reminder_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    text: &str,
+) -> Result<String, CommandError> {
+    let option_hours = parse_start_reminder_hours(text)?;
+    db_conn.set_start_reminder_hours_for_alias(alias, option_hours)?;
+    Ok(
+        match option_hours.unwrap_or(DEFAULT_START_REMINDER_HOURS) {
+            0 => format!("Turned off start reminders for {}", alias),
+            hours => format!(
+                "The host of {} will be reminded to start it once all the pretenders have been in for {}h",
+                alias, hours
+            ),
+        },
+    )
+}
+
+pub fn start_reminder(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let text = args.single_quoted::<String>()?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = start_reminder_helper(db_conn, &alias, &text)?;
+    info!("start_reminder: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
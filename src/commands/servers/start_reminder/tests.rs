@@ -0,0 +1,30 @@
+use super::*;
+
+#[test]
+fn should_parse_start_reminder_hours() {
+    assert_eq!(parse_start_reminder_hours("off").unwrap(), Some(0));
+    assert_eq!(parse_start_reminder_hours("default").unwrap(), None);
+    assert_eq!(parse_start_reminder_hours("24").unwrap(), Some(24));
+    assert!(parse_start_reminder_hours("0").is_err());
+    assert!(parse_start_reminder_hours("1000").is_err());
+}
+
+#[test]
+fn should_save_start_reminder_hours() {
+    let db_conn = DbConnection::test();
+    start_reminder_helper(&db_conn, "test server 1", "24").unwrap();
+    assert_eq!(
+        db_conn
+            .start_reminder_hours_for_alias("test server 1")
+            .unwrap(),
+        Some(24)
+    );
+    start_reminder_helper(&db_conn, "test server 1", "default").unwrap();
+    assert_eq!(
+        db_conn
+            .start_reminder_hours_for_alias("test server 1")
+            .unwrap(),
+        None
+    );
+    assert!(start_reminder_helper(&db_conn, "no such game", "off").is_err());
+}
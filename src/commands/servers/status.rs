@@ -0,0 +1,95 @@
+use chrono::{DateTime, Duration, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use crate::commands::servers::{CachedPoll, GameCache, GameCacheKey};
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+fn show_ago(duration: Duration) -> String {
+    if duration.num_hours() > 0 {
+        format!(
+            "{}h {}m ago",
+            duration.num_hours(),
+            duration.num_minutes() % 60
+        )
+    } else {
+        format!("{}m ago", duration.num_minutes())
+    }
+}
+
+/// How the poller got on with one game: when it last read the server, and if
+/// the latest poll failed, why and whether there's older data to fall back on
+pub fn status_line(alias: &str, option_poll: Option<&CachedPoll>, now: DateTime<Utc>) -> String {
+    let cached_poll = match option_poll {
+        Some(cached_poll) => cached_poll,
+        None => return format!("{}: not polled yet", alias),
+    };
+    let option_last_success = cached_poll
+        .option_cache_entry
+        .as_ref()
+        .map(|cache_entry| show_ago(now.signed_duration_since(cache_entry.fetched_at)));
+    match (&cached_poll.option_error, option_last_success) {
+        (None, Some(last_success)) => format!("{}: ok, polled {}", alias, last_success),
+        (None, None) => format!("{}: not polled yet", alias),
+        (Some(error), option_last_success) => {
+            let category = cached_poll.option_error_category.unwrap_or("error");
+            let fallback = match option_last_success {
+                Some(last_success) => format!("using cached data from {}", last_success),
+                None => "no cached data".to_owned(),
+            };
+            format!(
+                "{}: {} ({}), {}",
+                alias,
+                category.to_uppercase(),
+                error,
+                fallback
+            )
+        }
+    }
+}
+
+fn status_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    now: DateTime<Utc>,
+) -> Result<String, CommandError> {
+    let mut lines = vec![];
+    for server in db_conn.retrieve_all_servers()? {
+        // Lobbies have no server to poll
+        if let GameServerState::StartedState(_, _) = server.state {
+            let option_poll = game_cache.get_poll(&server.alias);
+            lines.push(status_line(&server.alias, option_poll.as_ref(), now));
+        }
+    }
+    if lines.is_empty() {
+        return Ok("No started games to poll".to_owned());
+    }
+    lines.sort();
+    Ok(lines.join("\n"))
+}
+
+pub fn status(context: &mut Context, message: &Message, args: Args) -> Result<(), CommandError> {
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = status_helper(db_conn, game_cache, Utc::now())?;
+    info!("status: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use super::*;
+
+use crate::commands::servers::CacheEntry;
+use crate::model::GameData;
+use chrono::TimeZone;
+
+fn cache_entry(fetched_at: DateTime<Utc>) -> CacheEntry {
+    CacheEntry {
+        game_data: GameData {
+            game_name: "foo".to_owned(),
+            nations: vec![],
+            turn: 32,
+            turn_timer: 3_600_000,
+        },
+        option_snek_state: None,
+        fetched_at,
+    }
+}
+
+#[test]
+fn should_show_last_successful_poll() {
+    let now = Utc.timestamp(10 * 3600, 0);
+    let cached_poll = CachedPoll {
+        polled_at: now,
+        option_cache_entry: Some(cache_entry(now - Duration::minutes(5))),
+        option_error: None,
+        option_error_category: None,
+    };
+    assert_eq!(
+        status_line("foo", Some(&cached_poll), now),
+        "foo: ok, polled 5m ago"
+    );
+}
+
+#[test]
+fn should_show_error_category_and_cached_data() {
+    let now = Utc.timestamp(10 * 3600, 0);
+    let cached_poll = CachedPoll {
+        polled_at: now,
+        option_cache_entry: Some(cache_entry(now - Duration::minutes(90))),
+        option_error: Some("timed out".to_owned()),
+        option_error_category: Some("down"),
+    };
+    assert_eq!(
+        status_line("foo", Some(&cached_poll), now),
+        "foo: DOWN (timed out), using cached data from 1h 30m ago"
+    );
+}
+
+#[test]
+fn should_show_errors_with_nothing_to_fall_back_on() {
+    let now = Utc.timestamp(10 * 3600, 0);
+    let cached_poll = CachedPoll {
+        polled_at: now,
+        option_cache_entry: None,
+        option_error: Some("garbage".to_owned()),
+        option_error_category: Some("bad data"),
+    };
+    assert_eq!(
+        status_line("foo", Some(&cached_poll), now),
+        "foo: BAD DATA (garbage), no cached data"
+    );
+    assert_eq!(status_line("foo", None, now), "foo: not polled yet");
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::all::summary_line;
+use super::details2::cached_game_details;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::notifier::{DiscordNotifier, Notifier};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this before the alias to stop updating the channel's status message
+const CLEAR_FLAG: &str = "--clear";
+
+/// The game's summary and where each human nation is up to. No mentions, so
+/// that editing it every turn doesn't ping anyone.
+pub fn status_message_text(alias: &str, details: &GameDetails, now: DateTime<Utc>) -> String {
+    let footer = format!("Last updated {} UTC", now.format("%d %b %H:%M"));
+    let mut text = format!("{}\n", summary_line(alias, details, TimeFormat::Exact));
+    if let NationDetails::Started(StartedDetails {
+        state: StartedStateDetails::Playing(playing_state),
+        ..
+    }) = &details.nations
+    {
+        let lines: Vec<String> = playing_state
+            .players
+            .iter()
+            .filter_map(|potential_player| match potential_player {
+                PotentialPlayer::RegisteredAndGame(_, player_details)
+                | PotentialPlayer::GameOnly(player_details)
+                    if player_details.player_status.is_human() =>
+                {
+                    Some(format!(
+                        "`{}` {} ({})",
+                        player_details.submitted.show(),
+                        player_details.nation_name,
+                        player_details.nation_id
+                    ))
+                }
+                _ => None,
+            })
+            .collect();
+        let line_count = lines.len();
+        for (ix, line) in lines.into_iter().enumerate() {
+            // An edit can't be split, so leave room to say how many didn't fit
+            let more_after = match line_count - ix - 1 {
+                0 => 0,
+                count => format!("+{} more\n", count).len(),
+            };
+            if text.len() + line.len() + 1 + more_after + footer.len() > MAX_MESSAGE_LENGTH {
+                text.push_str(&format!("+{} more\n", line_count - ix));
+                break;
+            }
+            text.push_str(&line);
+            text.push('\n');
+        }
+    }
+    text.push_str(&footer);
+    text
+}
+
+/// Brings every out of date status message up to date, posting a new one where
+/// the old one has gone. Failures are only logged and not retried, since the
+/// next turn will try again anyway.
+pub fn update_status_messages<N: Notifier>(db_conn: &DbConnection) {
+    let pending = match db_conn.pending_status_messages() {
+        Ok(pending) => pending,
+        Err(e) => {
+            error!("Could not get status messages with error {:?}", e);
+            return;
+        }
+    };
+    for (alias, channel_id, message_id, text) in pending {
+        let result_message_id = match N::edit(channel_id, message_id, &text) {
+            Ok(true) => Ok(message_id),
+            Ok(false) => {
+                info!(
+                    "Status message {} for {} in {} has gone, posting it again",
+                    message_id, alias, channel_id
+                );
+                N::post_pinned(channel_id, &text)
+            }
+            Err(e) => Err(e),
+        };
+        let message_id = result_message_id.unwrap_or_else(|e| {
+            error!(
+                "Could not update the status message for {} in {} with error {:?}",
+                alias, channel_id, e
+            );
+            message_id
+        });
+        if let Err(e) = db_conn.set_status_message(&alias, channel_id, message_id) {
+            error!(
+                "Could not save the status message for {} in {} with error {:?}",
+                alias, channel_id, e
+            );
+        }
+    }
+}
+
+pub fn status_message(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let clear = args.single_quoted_n::<String>().ok().as_deref() == Some(CLEAR_FLAG);
+    if clear {
+        args.skip();
+    }
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    if clear {
+        if !db_conn.remove_status_message(&alias, message.channel_id)? {
+            return Err(CommandError::from(format!(
+                "This channel has no status message for {}",
+                alias
+            )));
+        }
+        message.reply(&format!(
+            "The status message for {} won't be updated any more",
+            alias
+        ))?;
+        return Ok(());
+    }
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+
+    let (details, _) = cached_game_details(&alias, db_conn, game_cache)?;
+    let text = status_message_text(&alias, &details, Utc::now());
+    info!("status_message: {}", text);
+    let message_id = DiscordNotifier::post_pinned(message.channel_id, &text)?;
+    // The new one takes over, so the old one would only go stale
+    if let Some(old_message_id) = db_conn.status_message(&alias, message.channel_id)? {
+        if let Err(e) = message.channel_id.delete_message(old_message_id) {
+            info!(
+                "Could not delete old status message {} with error {:?}",
+                old_message_id, e
+            );
+        }
+    }
+    db_conn.set_status_message(&alias, message.channel_id, message_id)?;
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+use crate::model::enums::{NationStatus, SubmissionStatus};
+use crate::notifier::test_helpers::RecordingNotifier;
+use chrono::TimeZone;
+use serenity::model::id::{ChannelId, MessageId, UserId};
+use std::collections::HashMap;
+
+fn player(
+    nation_id: u32,
+    submitted: SubmissionStatus,
+    player_status: NationStatus,
+) -> PlayerDetails {
+    PlayerDetails {
+        nation_id,
+        nation_name: format!("nation {}", nation_id),
+        submitted,
+        player_status,
+        went_ai: false,
+        connected: false,
+    }
+}
+
+fn playing_details(players: Vec<PotentialPlayer>) -> GameDetails {
+    GameDetails {
+        alias: "foo".to_owned(),
+        owner: None,
+        description: None,
+        player_nicknames: HashMap::new(),
+        player_vacations: HashMap::new(),
+        nations: NationDetails::Started(StartedDetails {
+            address: "foo.bar:1234".to_owned(),
+            game_name: "foo".to_owned(),
+            state: StartedStateDetails::Playing(PlayingState {
+                players,
+                turn: 12,
+                mins_remaining: 20,
+                hours_remaining: 3,
+                days_remaining: 0,
+                secs_remaining: 0,
+                option_deadline: None,
+                paused: false,
+                blitz: false,
+            }),
+            option_snek_game_id: None,
+        }),
+        cache_entry: None,
+        warnings: vec![],
+    }
+}
+
+#[test]
+fn should_show_each_human_without_mentioning_anyone() {
+    let details = playing_details(vec![
+        PotentialPlayer::RegisteredAndGame(
+            UserId(1),
+            player(1, SubmissionStatus::Submitted, NationStatus::Human),
+        ),
+        PotentialPlayer::GameOnly(player(
+            2,
+            SubmissionStatus::NotSubmitted,
+            NationStatus::Human,
+        )),
+        PotentialPlayer::GameOnly(player(3, SubmissionStatus::NotSubmitted, NationStatus::AI)),
+    ]);
+
+    assert_eq!(
+        status_message_text("foo", &details, Utc.ymd(2020, 10, 14).and_hms(18, 5, 0)),
+        "foo: turn 12 (3h 20m), 1 undone\n\
+         `✓` nation 1 (1)\n\
+         `X` nation 2 (2)\n\
+         Last updated 14 Oct 18:05 UTC"
+    );
+}
+
+#[test]
+fn should_fit_as_many_nations_as_it_can_in_one_message() {
+    let players = (1..=200)
+        .map(|nation_id| {
+            PotentialPlayer::GameOnly(player(
+                nation_id,
+                SubmissionStatus::NotSubmitted,
+                NationStatus::Human,
+            ))
+        })
+        .collect();
+    let text = status_message_text(
+        "foo",
+        &playing_details(players),
+        Utc.ymd(2020, 10, 14).and_hms(18, 5, 0),
+    );
+    assert!(text.len() <= MAX_MESSAGE_LENGTH);
+    assert!(text.ends_with("Last updated 14 Oct 18:05 UTC"));
+
+    let lines: Vec<&str> = text.lines().collect();
+    let shown = lines.len() - 3;
+    assert_eq!(lines[lines.len() - 2], format!("+{} more", 200 - shown));
+    // and one more nation wouldn't have fitted
+    let one_more = format!("`X` nation {} ({})\n", shown + 1, shown + 1);
+    assert!(text.len() + one_more.len() > MAX_MESSAGE_LENGTH);
+}
+
+#[test]
+fn should_edit_status_messages_and_repost_deleted_ones() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_status_message("test server 1", ChannelId(3521), MessageId(1))
+        .unwrap();
+    db_conn
+        .set_status_message("test server 2", ChannelId(3522), MessageId(2))
+        .unwrap();
+    db_conn
+        .queue_status_message_text("test server 1", "turn 32")
+        .unwrap();
+    db_conn
+        .queue_status_message_text("test server 2", "turn 33")
+        .unwrap();
+    RecordingNotifier::delete(ChannelId(3522), MessageId(2));
+
+    update_status_messages::<RecordingNotifier>(&db_conn);
+    assert_eq!(
+        RecordingNotifier::edits(ChannelId(3521)),
+        vec![(MessageId(1), "turn 32".to_owned())]
+    );
+    let pinned = RecordingNotifier::pinned(ChannelId(3522));
+    assert_eq!(pinned.len(), 1);
+    assert_eq!(pinned[0].1, "turn 33");
+    assert_eq!(
+        db_conn
+            .status_message("test server 2", ChannelId(3522))
+            .unwrap(),
+        Some(pinned[0].0)
+    );
+    assert!(db_conn.pending_status_messages().unwrap().is_empty());
+
+    // nothing new to say
+    update_status_messages::<RecordingNotifier>(&db_conn);
+    assert_eq!(RecordingNotifier::edits(ChannelId(3521)).len(), 1);
+
+    assert!(db_conn
+        .remove_status_message("test server 1", ChannelId(3521))
+        .unwrap());
+    assert!(!db_conn
+        .remove_status_message("test server 1", ChannelId(3521))
+        .unwrap());
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::Nations;
+
+#[cfg(test)]
+mod tests;
+
+/// How many turns of submission times we keep for each game
+pub const STRAGGLER_HISTORY_TURNS: i32 = 20;
+
+#[derive(PartialEq, Debug)]
+pub struct StragglerCount {
+    pub nation_id: u32,
+    /// Turns this nation was one of the last to get its turn in
+    pub last_turns: usize,
+    /// Turns we saw this nation get its turn in at all
+    pub turns_seen: usize,
+}
+
+/// Each turn's submitters, and whether they were one of the last, oldest turn
+/// first. The last quarter of each turn's submissions count as the last, and
+/// always at least the very last one. Nations seen at the same time (e.g.
+/// everyone who hadn't played when the turn hosted) count together. Turns we
+/// only saw one nation submit don't say anything about who was slower, so are
+/// skipped.
+fn last_submitters(history: &[(i32, u32, DateTime<Utc>)]) -> Vec<Vec<(u32, bool)>> {
+    let mut turns: BTreeMap<i32, Vec<(u32, DateTime<Utc>)>> = BTreeMap::new();
+    for &(turn, nation_id, seen_at) in history {
+        turns.entry(turn).or_default().push((nation_id, seen_at));
+    }
+
+    turns
+        .into_values()
+        .filter(|submissions| submissions.len() >= 2)
+        .map(|mut submissions| {
+            submissions.sort_by_key(|&(_, seen_at)| seen_at);
+            let num_last = (submissions.len() / 4).max(1);
+            let (_, last_seen_at) = submissions[submissions.len() - num_last];
+            submissions
+                .into_iter()
+                .map(|(nation_id, seen_at)| (nation_id, seen_at >= last_seen_at))
+                .collect()
+        })
+        .collect()
+}
+
+/// How often each nation was one of the last, see `last_submitters`
+pub fn straggler_counts(history: &[(i32, u32, DateTime<Utc>)]) -> Vec<StragglerCount> {
+    let mut counts: HashMap<u32, StragglerCount> = HashMap::new();
+    for submissions in last_submitters(history) {
+        for (nation_id, was_last) in submissions {
+            let count = counts.entry(nation_id).or_insert(StragglerCount {
+                nation_id,
+                last_turns: 0,
+                turns_seen: 0,
+            });
+            count.turns_seen += 1;
+            if was_last {
+                count.last_turns += 1;
+            }
+        }
+    }
+
+    let mut counts: Vec<StragglerCount> = counts.into_values().collect();
+    counts.sort_by(|a, b| {
+        b.last_turns
+            .cmp(&a.last_turns)
+            .then(a.turns_seen.cmp(&b.turns_seen))
+            .then(a.nation_id.cmp(&b.nation_id))
+    });
+    counts
+}
+
+/// How many of the most recent turns in a row each nation was one of the last
+/// in. Nations that weren't last in the latest turn we know about are left out.
+pub fn late_streaks(history: &[(i32, u32, DateTime<Utc>)]) -> HashMap<u32, usize> {
+    let mut streaks: HashMap<u32, usize> = HashMap::new();
+    let mut ended: HashSet<u32> = HashSet::new();
+    for submissions in last_submitters(history).into_iter().rev() {
+        for (nation_id, was_last) in submissions {
+            if ended.contains(&nation_id) {
+                continue;
+            }
+            if was_last {
+                *streaks.entry(nation_id).or_insert(0) += 1;
+            } else {
+                ended.insert(nation_id);
+            }
+        }
+    }
+    streaks
+}
+
+fn stragglers_helper(db_conn: &DbConnection, alias: &str) -> Result<String, CommandError> {
+    let history = db_conn.select_submission_history(alias)?;
+    let counts = straggler_counts(&history);
+    if counts.is_empty() {
+        return Ok(format!(
+            "No submissions seen for {} yet, check back after a couple of turns have hosted",
+            alias
+        ));
+    }
+
+    let names: Vec<String> = counts
+        .iter()
+        .map(|count| Nations::get_nation_name(count.nation_id))
+        .collect();
+    let width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+    let mut text = format!(
+        "Last to get their turns in for {} (up to the last {} turns):\n```\n",
+        alias, STRAGGLER_HISTORY_TURNS
+    );
+    for (count, name) in counts.iter().zip(names.iter()) {
+        text.push_str(&format!(
+            "{:<width$} last in {} of {} turns\n",
+            name,
+            count.last_turns,
+            count.turns_seen,
+            width = width,
+        ));
+    }
+    text.push_str("```");
+    Ok(text)
+}
+
+pub fn stragglers(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = stragglers_helper(db_conn, &alias)?;
+    info!("stragglers: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
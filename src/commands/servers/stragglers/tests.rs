@@ -0,0 +1,92 @@
+use super::*;
+
+use chrono::TimeZone;
+
+fn hour(h: i64) -> DateTime<Utc> {
+    Utc.timestamp(h * 3600, 0)
+}
+
+#[test]
+fn should_count_the_last_submitter_of_each_turn() {
+    let history = vec![
+        (1, 5, hour(1)),
+        (1, 7, hour(3)),
+        (2, 7, hour(4)),
+        (2, 5, hour(6)),
+        (3, 5, hour(7)),
+        (3, 7, hour(9)),
+    ];
+    assert_eq!(
+        straggler_counts(&history),
+        vec![
+            StragglerCount {
+                nation_id: 7,
+                last_turns: 2,
+                turns_seen: 3,
+            },
+            StragglerCount {
+                nation_id: 5,
+                last_turns: 1,
+                turns_seen: 3,
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_count_nations_seen_together_as_equally_last() {
+    // 6 and 7 hadn't played when the turn hosted
+    let history = vec![(1, 5, hour(1)), (1, 6, hour(5)), (1, 7, hour(5))];
+    let counts = straggler_counts(&history);
+    let last_turns: Vec<(u32, usize)> = counts
+        .iter()
+        .map(|count| (count.nation_id, count.last_turns))
+        .collect();
+    assert_eq!(last_turns, vec![(6, 1), (7, 1), (5, 0)]);
+}
+
+#[test]
+fn should_skip_turns_with_one_submission() {
+    let history = vec![(1, 5, hour(1)), (2, 5, hour(2)), (2, 7, hour(3))];
+    let counts = straggler_counts(&history);
+    assert!(counts.iter().all(|count| count.turns_seen == 1));
+}
+
+#[test]
+fn should_only_keep_recent_turns() {
+    let db_conn = DbConnection::test();
+    for turn in 1..=STRAGGLER_HISTORY_TURNS + 5 {
+        db_conn
+            .record_submission(
+                "test server 1",
+                turn,
+                5,
+                hour(i64::from(turn)),
+                STRAGGLER_HISTORY_TURNS,
+            )
+            .unwrap();
+    }
+    let history = db_conn.select_submission_history("test server 1").unwrap();
+    assert_eq!(history.len(), STRAGGLER_HISTORY_TURNS as usize);
+    assert_eq!(history[0].0, 6);
+    assert!(db_conn
+        .select_submission_history("test server 2")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_count_late_turns_in_a_row_back_from_the_latest() {
+    let history = vec![
+        // 7 was last, then 5 for the two turns since
+        (1, 5, hour(1)),
+        (1, 7, hour(3)),
+        (2, 7, hour(4)),
+        (2, 5, hour(6)),
+        (3, 7, hour(7)),
+        (3, 5, hour(9)),
+    ];
+    let streaks = late_streaks(&history);
+    assert_eq!(streaks.get(&5), Some(&2));
+    assert_eq!(streaks.get(&7), None);
+}
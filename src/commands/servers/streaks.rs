@@ -0,0 +1,143 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use std::collections::HashMap;
+
+use super::alias_from_arg_or_channel_name;
+use super::changes::RecordedStatus;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::NationStatus;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(PartialEq, Debug)]
+pub enum Streak {
+    /// Played by a human every turn from `since` to the latest, inclusive
+    Surviving { turns: i32, since: i32 },
+    /// The first turn we saw it defeated
+    Died(i32),
+    /// AI, closed or anything else that isn't a player keeping it alive
+    NotPlayed(NationStatus),
+}
+
+/// Each nation in the latest recorded turn and how it's doing, from a game's
+/// history oldest turn first. Any turn a nation wasn't in human hands (gone AI,
+/// or defeated and then brought back by a rollback) ends its streak.
+pub fn survival_streaks(history: &[(i32, Vec<RecordedStatus>)]) -> Vec<(u32, String, Streak)> {
+    let mut run_starts: HashMap<u32, i32> = HashMap::new();
+    let mut deaths: HashMap<u32, i32> = HashMap::new();
+    for (turn, statuses) in history {
+        for (nation_id, _, status) in statuses {
+            if *status == NationStatus::Human {
+                run_starts.entry(*nation_id).or_insert(*turn);
+            } else {
+                run_starts.remove(nation_id);
+            }
+            if status.is_defeated() {
+                deaths.entry(*nation_id).or_insert(*turn);
+            } else {
+                deaths.remove(nation_id);
+            }
+        }
+    }
+    let (latest, statuses) = match history.last() {
+        Some((latest, statuses)) => (*latest, statuses),
+        None => return vec![],
+    };
+    statuses
+        .iter()
+        .map(|(nation_id, nation_name, status)| {
+            let streak = match (run_starts.get(nation_id), deaths.get(nation_id)) {
+                (Some(&since), _) => Streak::Surviving {
+                    turns: latest - since + 1,
+                    since,
+                },
+                (None, Some(&died)) => Streak::Died(died),
+                (None, None) => Streak::NotPlayed(*status),
+            };
+            (*nation_id, nation_name.clone(), streak)
+        })
+        .collect()
+}
+
+/// Longest streak first, then anything not being played, then the dead with the
+/// most recent death first
+fn sort_streaks(streaks: &mut [(u32, String, Streak)]) {
+    streaks.sort_by_key(|(nation_id, _, streak)| match streak {
+        Streak::Surviving { turns, .. } => (0, -turns, *nation_id),
+        Streak::NotPlayed(_) => (1, 0, *nation_id),
+        Streak::Died(turn) => (2, -turn, *nation_id),
+    });
+}
+
+fn show_streak(first_recorded: i32, streak: &Streak) -> String {
+    match streak {
+        Streak::Surviving { turns, since } => {
+            let turns = format!("{} turn{}", turns, if *turns == 1 { "" } else { "s" });
+            if *since <= 1 {
+                format!("{}, since the start", turns)
+            } else if *since == first_recorded {
+                format!(
+                    "{}, since turn {} when the bot started watching",
+                    turns, since
+                )
+            } else {
+                format!("{}, since turn {}", turns, since)
+            }
+        }
+        Streak::Died(turn) => format!("died on turn {}", turn),
+        Streak::NotPlayed(status) => format!("no streak, {}", status.show()),
+    }
+}
+
+fn streaks_helper(db_conn: &DbConnection, alias: &str) -> Result<String, CommandError> {
+    let mut history = vec![];
+    for turn in db_conn.nation_status_turns(alias)? {
+        history.push((turn, db_conn.nation_statuses(alias, turn)?));
+    }
+    let first_recorded = match history.first() {
+        Some((first_recorded, _)) => *first_recorded,
+        None => {
+            return Err(CommandError::from(format!(
+                "No turns of {} have been recorded yet, check back after the next one hosts",
+                alias
+            )))
+        }
+    };
+    let mut streaks = survival_streaks(&history);
+    sort_streaks(&mut streaks);
+    let mut text = format!("Survival streaks in {}:\n", alias);
+    for (nation_id, nation_name, streak) in &streaks {
+        text.push_str(&format!(
+            "{} ({}): {}\n",
+            nation_name,
+            nation_id,
+            show_streak(first_recorded, streak)
+        ));
+    }
+    Ok(text.trim_end().to_owned())
+}
+
+pub fn streaks(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = streaks_helper(db_conn, &alias)?;
+    info!("streaks: replying with: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use super::*;
+
+fn turn(turn: i32, statuses: &[(u32, &str, NationStatus)]) -> (i32, Vec<RecordedStatus>) {
+    (
+        turn,
+        statuses
+            .iter()
+            .map(|&(nation_id, name, status)| (nation_id, name.to_owned(), status))
+            .collect(),
+    )
+}
+
+#[test]
+fn should_count_turns_since_a_nation_was_last_not_in_human_hands() {
+    let history = vec![
+        turn(
+            1,
+            &[
+                (5, "Arcoscephale", NationStatus::Human),
+                (6, "Ermor", NationStatus::Human),
+                (7, "Ulm", NationStatus::Human),
+            ],
+        ),
+        turn(
+            2,
+            &[
+                (5, "Arcoscephale", NationStatus::AI),
+                (6, "Ermor", NationStatus::Human),
+                (7, "Ulm", NationStatus::DefeatedThisTurn),
+            ],
+        ),
+        // A turn the bot missed doesn't break anything
+        turn(
+            5,
+            &[
+                (5, "Arcoscephale", NationStatus::Human),
+                (6, "Ermor", NationStatus::Human),
+                (7, "Ulm", NationStatus::Defeated),
+                (8, "Marverni", NationStatus::AI),
+            ],
+        ),
+    ];
+    assert_eq!(
+        survival_streaks(&history),
+        vec![
+            (
+                5,
+                "Arcoscephale".to_owned(),
+                Streak::Surviving { turns: 1, since: 5 }
+            ),
+            (
+                6,
+                "Ermor".to_owned(),
+                Streak::Surviving { turns: 5, since: 1 }
+            ),
+            (7, "Ulm".to_owned(), Streak::Died(2)),
+            (
+                8,
+                "Marverni".to_owned(),
+                Streak::NotPlayed(NationStatus::AI)
+            ),
+        ]
+    );
+    assert!(survival_streaks(&[]).is_empty());
+}
+
+#[test]
+fn should_forget_a_death_undone_by_a_rollback() {
+    let history = vec![
+        turn(3, &[(5, "Arcoscephale", NationStatus::DefeatedThisTurn)]),
+        turn(4, &[(5, "Arcoscephale", NationStatus::Human)]),
+        turn(6, &[(5, "Arcoscephale", NationStatus::Defeated)]),
+    ];
+    assert_eq!(
+        survival_streaks(&history),
+        vec![(5, "Arcoscephale".to_owned(), Streak::Died(6))]
+    );
+}
+
+#[test]
+fn should_list_the_longest_streaks_first_and_the_dead_last() {
+    let db_conn = DbConnection::test();
+    assert!(streaks_helper(&db_conn, "test server 1").is_err());
+
+    for (turn, statuses) in [
+        turn(
+            4,
+            &[
+                (5, "Arcoscephale", NationStatus::Human),
+                (6, "Ermor", NationStatus::AI),
+                (7, "Ulm", NationStatus::Human),
+            ],
+        ),
+        turn(
+            6,
+            &[
+                (5, "Arcoscephale", NationStatus::Defeated),
+                (6, "Ermor", NationStatus::Human),
+                (7, "Ulm", NationStatus::Human),
+            ],
+        ),
+    ] {
+        db_conn
+            .record_nation_statuses("test server 1", turn, &statuses)
+            .unwrap();
+    }
+    assert_eq!(
+        streaks_helper(&db_conn, "test server 1").unwrap(),
+        "Survival streaks in test server 1:\n\
+         Ulm (7): 3 turns, since turn 4 when the bot started watching\n\
+         Ermor (6): 1 turn, since turn 6\n\
+         Arcoscephale (5): died on turn 6"
+    );
+}
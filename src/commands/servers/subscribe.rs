@@ -0,0 +1,98 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+fn subscribe_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    channel_id: ChannelId,
+) -> Result<String, CommandError> {
+    // So that a typo doesn't look like it worked
+    db_conn.game_for_alias(alias)?;
+    if db_conn.channel_for_alias(alias)? == Some(channel_id)
+        || !db_conn.subscribe_channel(alias, channel_id)?
+    {
+        return Err(CommandError::from(format!(
+            "This channel already gets {}'s announcements",
+            alias
+        )));
+    }
+    Ok(format!(
+        "This channel will get {}'s new turn, countdown and stall announcements too",
+        alias
+    ))
+}
+
+fn unsubscribe_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    channel_id: ChannelId,
+) -> Result<String, CommandError> {
+    if !db_conn.unsubscribe_channel(alias, channel_id)? {
+        let reason = if db_conn.channel_for_alias(alias)? == Some(channel_id) {
+            "it's the channel the game was created in"
+        } else {
+            "it isn't subscribed"
+        };
+        return Err(CommandError::from(format!(
+            "Can't unsubscribe this channel from {}, {}",
+            alias, reason
+        )));
+    }
+    Ok(format!(
+        "This channel won't get {}'s announcements any more",
+        alias
+    ))
+}
+
+pub fn subscribe(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = subscribe_helper(db_conn, &alias, message.channel_id)?;
+    info!("subscribe: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
+
+pub fn unsubscribe(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = unsubscribe_helper(db_conn, &alias, message.channel_id)?;
+    info!("unsubscribe: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
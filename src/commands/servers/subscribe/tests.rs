@@ -0,0 +1,50 @@
+use super::*;
+
+use crate::commands::servers::turn_check::fan_out;
+
+#[test]
+fn should_post_once_to_each_subscribed_channel() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_channel_for_alias("test server 1", ChannelId(326))
+        .unwrap();
+    subscribe_helper(&db_conn, "test server 1", ChannelId(3261)).unwrap();
+    assert!(subscribe_helper(&db_conn, "test server 1", ChannelId(3261)).is_err());
+    // the game's own channel already gets everything
+    assert!(subscribe_helper(&db_conn, "test server 1", ChannelId(326)).is_err());
+
+    let channel_ids = db_conn
+        .notification_channels_for_alias("test server 1")
+        .unwrap();
+    assert_eq!(channel_ids, vec![ChannelId(326), ChannelId(3261)]);
+
+    unsubscribe_helper(&db_conn, "test server 1", ChannelId(3261)).unwrap();
+    assert!(unsubscribe_helper(&db_conn, "test server 1", ChannelId(3261)).is_err());
+    assert!(unsubscribe_helper(&db_conn, "test server 1", ChannelId(326)).is_err());
+    assert_eq!(
+        db_conn
+            .notification_channels_for_alias("test server 1")
+            .unwrap(),
+        vec![ChannelId(326)]
+    );
+}
+
+#[test]
+fn should_not_subscribe_to_missing_game() {
+    let db_conn = DbConnection::test();
+    assert!(subscribe_helper(&db_conn, "not a game", ChannelId(3262)).is_err());
+}
+
+#[test]
+fn should_fan_out_without_double_posting() {
+    let notifications = fan_out(
+        "test server 1",
+        &[ChannelId(1), ChannelId(2), ChannelId(1)],
+        vec!["one".to_owned(), "two".to_owned()],
+    );
+    let sent: Vec<(u64, &str)> = notifications
+        .iter()
+        .map(|notification| (notification.channel_id.0, notification.message.as_str()))
+        .collect();
+    assert_eq!(sent, vec![(1, "one"), (1, "two"), (2, "one"), (2, "two")]);
+}
@@ -0,0 +1,71 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::GuildId;
+use serenity::prelude::Context;
+
+use super::admin_guild_id;
+use crate::commands::servers::TimeFormat;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// How a server shows the time left in a turn, exact unless it's said otherwise.
+/// DMs have no server to ask, so they're always exact.
+pub fn time_format_for_message(
+    db_conn: &DbConnection,
+    message: &Message,
+) -> Result<TimeFormat, CommandError> {
+    Ok(match message.guild_id {
+        Some(guild_id) => db_conn.time_format_for_guild(guild_id)?,
+        None => TimeFormat::Exact,
+    })
+}
+
+fn time_format_helper(
+    db_conn: &DbConnection,
+    guild_id: GuildId,
+    arg_format: &str,
+) -> Result<String, CommandError> {
+    let time_format = TimeFormat::from_name(arg_format).ok_or_else(|| {
+        CommandError::from(format!(
+            "Unknown time format \"{}\", try \"exact\", \"rounded\" or \"coarse\"",
+            arg_format
+        ))
+    })?;
+    db_conn.set_time_format_for_guild(guild_id, time_format)?;
+    Ok(match time_format {
+        TimeFormat::Exact => "Time left in a turn will be shown exactly, e.g. 1h 47m",
+        TimeFormat::Rounded => {
+            "Time left in a turn will be rounded to the nearest 15 minutes, e.g. ~1h 45m"
+        }
+        TimeFormat::Coarse => {
+            "Time left in a turn will be rounded to the nearest hour or day, e.g. ~2h"
+        }
+    }
+    .to_owned())
+}
+
+pub fn time_format(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg_format = args.single_quoted::<String>()?.to_lowercase();
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let guild_id = admin_guild_id(message)?;
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = time_format_helper(db_conn, guild_id, &arg_format)?;
+    info!("time_format: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
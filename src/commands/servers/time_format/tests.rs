@@ -0,0 +1,77 @@
+use super::*;
+
+use crate::commands::servers::PlayingState;
+
+fn playing_state(hours_remaining: i32, mins_remaining: i32) -> PlayingState {
+    PlayingState {
+        players: vec![],
+        turn: 5,
+        mins_remaining,
+        hours_remaining,
+        days_remaining: hours_remaining / 24,
+        secs_remaining: 0,
+        option_deadline: None,
+        paused: false,
+        blitz: false,
+    }
+}
+
+#[test]
+fn should_show_time_remaining_in_each_format() {
+    let state = playing_state(1, 47);
+    assert_eq!(state.show_time_remaining_as(TimeFormat::Exact), "1h 47m");
+    assert_eq!(state.show_time_remaining_as(TimeFormat::Rounded), "~1h 45m");
+    assert_eq!(state.show_time_remaining_as(TimeFormat::Coarse), "~2h");
+
+    let state = playing_state(26, 50);
+    assert_eq!(state.show_time_remaining_as(TimeFormat::Exact), "1d 2h 50m");
+    assert_eq!(
+        state.show_time_remaining_as(TimeFormat::Rounded),
+        "~1d 2h 45m"
+    );
+    assert_eq!(state.show_time_remaining_as(TimeFormat::Coarse), "~1d");
+
+    let state = playing_state(0, 5);
+    assert_eq!(
+        state.show_time_remaining_as(TimeFormat::Rounded),
+        "under 15m"
+    );
+    assert_eq!(
+        state.show_time_remaining_as(TimeFormat::Coarse),
+        "under an hour"
+    );
+
+    let paused = PlayingState {
+        paused: true,
+        ..playing_state(0, 0)
+    };
+    assert_eq!(paused.show_time_remaining_as(TimeFormat::Coarse), "paused");
+    assert_eq!(paused.show_time_left_as(TimeFormat::Coarse), "paused");
+    assert_eq!(
+        state.show_time_left_as(TimeFormat::Coarse),
+        "under an hour remaining"
+    );
+}
+
+#[test]
+fn should_keep_time_format_per_guild() {
+    let db_conn = DbConnection::test();
+    let guild_id = GuildId(373_001);
+    assert_eq!(
+        db_conn.time_format_for_guild(guild_id).unwrap(),
+        TimeFormat::Exact
+    );
+
+    time_format_helper(&db_conn, guild_id, "coarse").unwrap();
+    assert_eq!(
+        db_conn.time_format_for_guild(guild_id).unwrap(),
+        TimeFormat::Coarse
+    );
+    // doesn't touch the guild's other settings
+    assert!(!db_conn.details_as_text_for_guild(guild_id).unwrap());
+    assert_eq!(
+        db_conn.time_format_for_guild(GuildId(373_002)).unwrap(),
+        TimeFormat::Exact
+    );
+    assert!(time_format_helper(&db_conn, guild_id, "vague").is_err());
+}
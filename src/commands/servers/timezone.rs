@@ -0,0 +1,105 @@
+use super::alias_from_arg_or_channel_name;
+
+use chrono::FixedOffset;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Accepts things like "UTC", "UTC+2", "GMT-5", "+05:30" or "-8"
+pub fn parse_utc_offset(text: &str) -> Result<FixedOffset, String> {
+    let error = || {
+        format!(
+            "Could not understand timezone '{}', try something like \"UTC+2\" or \"UTC-05:30\"",
+            text
+        )
+    };
+    let upper = text.trim().to_uppercase();
+    let offset_text = upper
+        .trim_start_matches("UTC")
+        .trim_start_matches("GMT")
+        .trim();
+    if offset_text.is_empty() {
+        return Ok(FixedOffset::east(0));
+    }
+
+    let (sign, unsigned_text) = match offset_text.chars().next() {
+        Some('+') => (1, &offset_text[1..]),
+        Some('-') => (-1, &offset_text[1..]),
+        _ => return Err(error()),
+    };
+    let mut parts = unsigned_text.splitn(2, ':');
+    let hours: i32 = parts
+        .next()
+        .and_then(|hours| hours.parse().ok())
+        .ok_or_else(error)?;
+    let mins: i32 = match parts.next() {
+        Some(mins) => mins.parse().map_err(|_| error())?,
+        None => 0,
+    };
+    if mins >= 60 {
+        return Err(error());
+    }
+    FixedOffset::east_opt(sign * (hours * 60 * 60 + mins * 60)).ok_or_else(error)
+}
+
+pub fn show_utc_offset(utc_offset: FixedOffset) -> String {
+    let secs = utc_offset.local_minus_utc();
+    if secs == 0 {
+        return "UTC".to_owned();
+    }
+    let sign = if secs < 0 { '-' } else { '+' };
+    let hours = secs.abs() / (60 * 60);
+    let mins = (secs.abs() / 60) % 60;
+    if mins == 0 {
+        format!("UTC{}{}", sign, hours)
+    } else {
+        format!("UTC{}{}:{:02}", sign, hours, mins)
+    }
+}
+
+fn timezone_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    timezone: &str,
+) -> Result<String, CommandError> {
+    let utc_offset = parse_utc_offset(timezone)?;
+    let option_utc_offset = if utc_offset.local_minus_utc() == 0 {
+        None
+    } else {
+        Some(utc_offset)
+    };
+    db_conn.set_utc_offset_for_alias(alias, option_utc_offset)?;
+    Ok(format!(
+        "Turn deadlines for {} will be shown in {}",
+        alias,
+        show_utc_offset(utc_offset)
+    ))
+}
+
+pub fn timezone(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+
+    let timezone = args.single_quoted::<String>()?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let text = timezone_helper(db_conn, &alias, &timezone)?;
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use super::*;
+
+#[test]
+fn should_parse_utc_offsets() {
+    assert_eq!(parse_utc_offset("UTC").unwrap(), FixedOffset::east(0));
+    assert_eq!(
+        parse_utc_offset("utc+2").unwrap(),
+        FixedOffset::east(2 * 3600)
+    );
+    assert_eq!(
+        parse_utc_offset("GMT-5").unwrap(),
+        FixedOffset::west(5 * 3600)
+    );
+    assert_eq!(
+        parse_utc_offset("+05:30").unwrap(),
+        FixedOffset::east(5 * 3600 + 30 * 60)
+    );
+    assert!(parse_utc_offset("Europe/London").is_err());
+    assert!(parse_utc_offset("UTC+2:75").is_err());
+    assert!(parse_utc_offset("UTC+30").is_err());
+}
+
+#[test]
+fn should_show_utc_offsets() {
+    assert_eq!(show_utc_offset(FixedOffset::east(0)), "UTC");
+    assert_eq!(show_utc_offset(FixedOffset::west(8 * 3600)), "UTC-8");
+    assert_eq!(
+        show_utc_offset(FixedOffset::east(5 * 3600 + 30 * 60)),
+        "UTC+5:30"
+    );
+}
+
+#[test]
+fn should_store_timezone_for_game() {
+    let db_conn = DbConnection::test();
+    timezone_helper(&db_conn, "test server 1", "UTC+1").unwrap();
+    assert_eq!(
+        db_conn.utc_offset_for_alias("test server 1").unwrap(),
+        Some(FixedOffset::east(3600))
+    );
+
+    timezone_helper(&db_conn, "test server 1", "UTC").unwrap();
+    assert_eq!(db_conn.utc_offset_for_alias("test server 1").unwrap(), None);
+}
@@ -0,0 +1,160 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::time_format::time_format_for_message;
+use super::undone::undone_players;
+use super::urgent::{sort_by_urgency, UrgentGame};
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+/// What the player still has to do in one game, `None` if they're done. Counts
+/// the same nations as `!undone`, plus pretenders still to upload.
+pub fn todo_for_game(
+    alias: &str,
+    state: &StartedStateDetails,
+    user_id: UserId,
+    time_format: TimeFormat,
+) -> Option<UrgentGame> {
+    match state {
+        StartedStateDetails::Playing(playing_state) => {
+            let nations: Vec<String> = undone_players(playing_state)
+                .into_iter()
+                .filter(|(player_user_id, _)| *player_user_id == user_id)
+                .map(|(_, details)| format!("{} ({})", details.nation_name, details.nation_id))
+                .collect();
+            if nations.is_empty() {
+                return None;
+            }
+            Some(UrgentGame {
+                option_deadline: if playing_state.paused {
+                    None
+                } else {
+                    playing_state.option_deadline
+                },
+                line: format!(
+                    "{} turn {} ({}): {}",
+                    alias,
+                    playing_state.turn,
+                    playing_state.show_time_remaining_as(time_format),
+                    nations.join(", ")
+                ),
+            })
+        }
+        StartedStateDetails::Uploading(uploading_state) => {
+            let nations: Vec<String> = uploading_state
+                .uploading_players
+                .iter()
+                .filter(|uploading_player| {
+                    !uploading_player.uploaded
+                        && uploading_player.option_player_id() == Some(&user_id)
+                })
+                .map(|uploading_player| {
+                    format!(
+                        "{} ({})",
+                        uploading_player.nation_name(),
+                        uploading_player.nation_id()
+                    )
+                })
+                .collect();
+            if nations.is_empty() {
+                return None;
+            }
+            Some(UrgentGame {
+                option_deadline: None,
+                line: format!(
+                    "{} uploading: a pretender for {}",
+                    alias,
+                    nations.join(", ")
+                ),
+            })
+        }
+    }
+}
+
+/// Only looks at what the poller last saw, like `!urgent`
+fn todo_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    user_id: UserId,
+    time_format: TimeFormat,
+) -> Result<String, CommandError> {
+    let mut seen_aliases = vec![];
+    let mut games = vec![];
+    for (server, _) in db_conn.servers_for_player(user_id)? {
+        // Several nations in one game come up once each
+        if seen_aliases.contains(&server.alias) {
+            continue;
+        }
+        seen_aliases.push(server.alias.clone());
+        if let GameServerState::StartedState(started_state, option_lobby_state) = server.state {
+            let alias = server.alias;
+            let option_game = match game_cache.get_latest(&alias) {
+                Some(Ok(cache)) => match started_details_from_server(
+                    db_conn,
+                    &started_state,
+                    option_lobby_state.as_ref(),
+                    &alias,
+                    cache.game_data,
+                    cache.option_snek_state,
+                    cache.fetched_at,
+                )?
+                .nations
+                {
+                    NationDetails::Started(started_details) => {
+                        todo_for_game(&alias, &started_details.state, user_id, time_format)
+                    }
+                    NationDetails::Lobby(_) => None,
+                },
+                // So that a game we can't see doesn't look like one that's done
+                Some(Err(e)) => Some(UrgentGame {
+                    option_deadline: None,
+                    line: format!("{}: couldn't check, {}", alias, e),
+                }),
+                None => Some(UrgentGame {
+                    option_deadline: None,
+                    line: format!("{}: couldn't check, the bot is still starting up", alias),
+                }),
+            };
+            games.extend(option_game);
+        }
+    }
+    if games.is_empty() {
+        return Ok("Nothing to do, you've played all your turns".to_owned());
+    }
+    sort_by_urgency(&mut games);
+    let mut text = "Still to do, soonest deadline first:\n".to_owned();
+    for game in games {
+        text.push_str(&game.line);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+pub fn todo(context: &mut Context, message: &Message, args: Args) -> Result<(), CommandError> {
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let time_format = time_format_for_message(db_conn, message)?;
+    let text = todo_helper(db_conn, game_cache, message.author.id, time_format)?;
+    info!("todo: replying with: {}", text);
+    let private_channel = message.author.id.create_dm_channel()?;
+    private_channel.say(&text)?;
+    Ok(())
+}
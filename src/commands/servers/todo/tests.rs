@@ -0,0 +1,103 @@
+use super::*;
+
+use crate::model::enums::{NationStatus, SubmissionStatus};
+use chrono::{TimeZone, Utc};
+
+const ME: UserId = UserId(3871);
+
+fn registered(user_id: UserId, nation_id: u32, submitted: SubmissionStatus) -> PotentialPlayer {
+    PotentialPlayer::RegisteredAndGame(
+        user_id,
+        PlayerDetails {
+            nation_id,
+            nation_name: format!("nation {}", nation_id),
+            submitted,
+            player_status: NationStatus::Human,
+            went_ai: false,
+            connected: false,
+        },
+    )
+}
+
+fn playing(players: Vec<PotentialPlayer>, hours_remaining: i32) -> StartedStateDetails {
+    StartedStateDetails::Playing(PlayingState {
+        players,
+        turn: 12,
+        mins_remaining: 0,
+        hours_remaining,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: Some(Utc.ymd(2021, 3, 12).and_hms(hours_remaining as u32, 0, 0)),
+        paused: false,
+        blitz: false,
+    })
+}
+
+#[test]
+fn should_only_list_the_callers_own_nations_still_to_play() {
+    let state = playing(
+        vec![
+            registered(ME, 5, SubmissionStatus::NotSubmitted),
+            registered(ME, 6, SubmissionStatus::Submitted),
+            registered(UserId(3872), 7, SubmissionStatus::NotSubmitted),
+            registered(ME, 8, SubmissionStatus::NotSubmitted),
+        ],
+        3,
+    );
+    let game = todo_for_game("game", &state, ME, TimeFormat::Exact).unwrap();
+    assert_eq!(
+        game.line,
+        "game turn 12 (3h 0m): nation 5 (5), nation 8 (8)"
+    );
+    assert!(game.option_deadline.is_some());
+
+    let done = playing(vec![registered(ME, 6, SubmissionStatus::Submitted)], 3);
+    assert!(todo_for_game("game", &done, ME, TimeFormat::Exact).is_none());
+}
+
+#[test]
+fn should_remind_about_pretenders_still_to_upload() {
+    let state = StartedStateDetails::Uploading(UploadingState {
+        uploading_players: vec![
+            UploadingPlayer {
+                potential_player: PotentialPlayer::RegisteredOnly(ME, 5, "nation 5".to_owned()),
+                uploaded: false,
+            },
+            UploadingPlayer {
+                potential_player: PotentialPlayer::RegisteredOnly(
+                    UserId(3872),
+                    6,
+                    "nation 6".to_owned(),
+                ),
+                uploaded: false,
+            },
+        ],
+    });
+    let game = todo_for_game("new game", &state, ME, TimeFormat::Exact).unwrap();
+    assert_eq!(
+        game.line,
+        "new game uploading: a pretender for nation 5 (5)"
+    );
+    assert_eq!(game.option_deadline, None);
+    assert!(todo_for_game("new game", &state, UserId(3873), TimeFormat::Exact).is_none());
+}
+
+#[test]
+fn should_put_the_soonest_deadline_first() {
+    let mut games: Vec<UrgentGame> = vec![
+        (
+            "later",
+            playing(vec![registered(ME, 5, SubmissionStatus::NotSubmitted)], 20),
+        ),
+        (
+            "sooner",
+            playing(vec![registered(ME, 6, SubmissionStatus::NotSubmitted)], 2),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(alias, state)| todo_for_game(alias, &state, ME, TimeFormat::Exact))
+    .collect();
+    sort_by_urgency(&mut games);
+    assert!(games[0].line.starts_with("sooner"));
+    assert!(games[1].line.starts_with("later"));
+}
@@ -0,0 +1,62 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::{alias_from_arg_or_channel_name, lobby_state_for_owner};
+use crate::commands::servers::GameCacheKey;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+fn transfer_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    new_owner: UserId,
+    alias: &str,
+) -> Result<(), CommandError> {
+    lobby_state_for_owner(db_conn, alias, author_id)?;
+    db_conn.set_owner(alias, new_owner)?;
+    Ok(())
+}
+
+pub fn transfer(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let new_owner = args
+        .single_quoted::<UserId>()
+        .map_err(|_| CommandError::from("Expected an @mention of the new owner"))?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    // Catch typos in the ID before someone gets locked out of their own lobby
+    let new_owner_user = new_owner
+        .to_user()
+        .map_err(|_| CommandError::from("Could not find that user"))?;
+    if new_owner_user.bot {
+        return Err(CommandError::from("A bot can't own a lobby"));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    transfer_helper(db_conn, message.author.id, new_owner, &alias)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
+
+    let text = format!("{} is now the owner of {}", new_owner_user, alias);
+    info!("{}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use super::*;
+
+use crate::model::enums::Era;
+
+const OWNER: UserId = UserId(1);
+const NEW_OWNER: UserId = UserId(2);
+
+#[test]
+fn should_hand_owner_only_commands_to_new_owner() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 5, &[]);
+
+    transfer_helper(&db_conn, OWNER, NEW_OWNER, "lobby").unwrap();
+
+    assert!(lobby_state_for_owner(&db_conn, "lobby", OWNER).is_err());
+    let lobby_state = lobby_state_for_owner(&db_conn, "lobby", NEW_OWNER).unwrap();
+    assert_eq!(lobby_state.owner, NEW_OWNER);
+}
+
+#[test]
+fn should_not_transfer_someone_elses_lobby() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 5, &[]);
+
+    assert!(transfer_helper(&db_conn, NEW_OWNER, NEW_OWNER, "lobby").is_err());
+    assert!(lobby_state_for_owner(&db_conn, "lobby", OWNER).is_ok());
+}
@@ -0,0 +1,144 @@
+use failure::{err_msg, Error};
+use lazy_static::lazy_static;
+use log::*;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use typemap::Key;
+
+use crate::model::enums::{NationStatus, Nations};
+
+#[cfg(test)]
+mod tests;
+
+/// One locale's strings, from a file of `nation <nation_id> <name>` and
+/// `label <key> <text>` lines. Whatever isn't in there stays in English.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct LocaleStrings {
+    nations: HashMap<u32, String>,
+    labels: HashMap<String, String>,
+}
+
+lazy_static! {
+    static ref ENGLISH: LocaleStrings = LocaleStrings::default();
+}
+
+impl LocaleStrings {
+    /// Blank lines and lines starting with `#` are ignored
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut strings = LocaleStrings::default();
+        for (ix, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.splitn(3, char::is_whitespace);
+            match (parts.next(), parts.next(), parts.next().map(str::trim)) {
+                (Some("nation"), Some(nation_id), Some(name)) if !name.is_empty() => {
+                    let nation_id = nation_id.parse::<u32>().map_err(|_| {
+                        err_msg(format!(
+                            "Line {}: '{}' is not a nation id",
+                            ix + 1,
+                            nation_id
+                        ))
+                    })?;
+                    strings.nations.insert(nation_id, name.to_owned());
+                }
+                (Some("label"), Some(key), Some(text)) if !text.is_empty() => {
+                    strings.labels.insert(key.to_owned(), text.to_owned());
+                }
+                _ => {
+                    return Err(err_msg(format!(
+                        "Line {}: expected 'nation <nation_id> <name>' or 'label <key> <text>' but got '{}'",
+                        ix + 1,
+                        line
+                    )))
+                }
+            }
+        }
+        Ok(strings)
+    }
+
+    /// `name` is what we'd show in English. Names that aren't the built in one
+    /// (e.g. from snek for a modded nation) are kept, since a translation of
+    /// the built in nation would be for a different nation.
+    pub fn nation_name<'a>(&'a self, nation_id: u32, name: &'a str) -> &'a str {
+        match self.nations.get(&nation_id) {
+            Some(translated) if Nations::get_nation_name(nation_id) == name => translated,
+            _ => name,
+        }
+    }
+
+    /// e.g. `label("open", "OPEN")`
+    pub fn label<'a>(&'a self, key: &str, english: &'a str) -> &'a str {
+        self.labels.get(key).map_or(english, String::as_str)
+    }
+
+    pub fn show_status(&self, status: NationStatus) -> &str {
+        let key = match status {
+            NationStatus::Empty => "status.empty",
+            NationStatus::Human => "status.human",
+            NationStatus::AI => "status.ai",
+            NationStatus::Independent => "status.independent",
+            NationStatus::Closed => "status.closed",
+            NationStatus::DefeatedThisTurn => "status.defeated_this_turn",
+            NationStatus::Defeated => "status.defeated",
+        };
+        self.label(key, status.show())
+    }
+}
+
+/// Every locale we have strings for, one file each in a directory and named
+/// after the locale, e.g. `resources/locales/de`
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Translations(HashMap<String, LocaleStrings>);
+
+pub struct TranslationsKey;
+impl Key for TranslationsKey {
+    type Value = Translations;
+}
+
+impl Translations {
+    /// No directory just means English only
+    pub fn load(dir: &Path) -> Result<Self, Error> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+                info!("No translations at {}", dir.display());
+                return Ok(Translations::default());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut locales = HashMap::new();
+        for entry in entries {
+            let path = entry?.path();
+            let locale = match path.file_name().and_then(|name| name.to_str()) {
+                Some(locale) if path.is_file() => locale.to_lowercase(),
+                _ => continue,
+            };
+            let strings = LocaleStrings::parse(&fs::read_to_string(&path)?)
+                .map_err(|e| err_msg(format!("{}: {}", path.display(), e)))?;
+            locales.insert(locale, strings);
+        }
+        info!("Loaded translations for {} locales", locales.len());
+        Ok(Translations(locales))
+    }
+
+    pub fn has_locale(&self, locale: &str) -> bool {
+        self.0.contains_key(&locale.to_lowercase())
+    }
+
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.0.keys().map(String::as_str).collect();
+        locales.sort_unstable();
+        locales
+    }
+
+    /// English (i.e. nothing translated) for no locale or one we don't have
+    pub fn strings(&self, option_locale: Option<&str>) -> &LocaleStrings {
+        option_locale
+            .and_then(|locale| self.0.get(&locale.to_lowercase()))
+            .unwrap_or(&ENGLISH)
+    }
+}
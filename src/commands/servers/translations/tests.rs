@@ -0,0 +1,49 @@
+use super::*;
+
+fn german() -> LocaleStrings {
+    LocaleStrings::parse(
+        "# early age\n\
+         nation 5 Arkoskephale\n\
+         \n\
+         label status.human   Mensch\n\
+         label open Frei\n",
+    )
+    .unwrap()
+}
+
+#[test]
+fn should_translate_built_in_names_and_labels() {
+    let strings = german();
+    let arcoscephale = Nations::get_nation_name(5);
+    assert_eq!(strings.nation_name(5, &arcoscephale), "Arkoskephale");
+    assert_eq!(strings.show_status(NationStatus::Human), "Mensch");
+    assert_eq!(strings.label("open", "OPEN"), "Frei");
+}
+
+#[test]
+fn should_fall_back_to_english() {
+    let strings = german();
+    let ulm = Nations::get_nation_name(7);
+    assert_eq!(strings.nation_name(7, &ulm), ulm);
+    assert_eq!(strings.show_status(NationStatus::AI), "AI");
+    assert_eq!(
+        strings.label("open_slots_unknown", "open slots: unknown"),
+        "open slots: unknown"
+    );
+    // e.g. snek's name for a modded nation reusing the ID
+    assert_eq!(strings.nation_name(5, "Mod Nation"), "Mod Nation");
+}
+
+#[test]
+fn should_reject_malformed_lines() {
+    assert!(LocaleStrings::parse("nation ulm Ulm").is_err());
+    assert!(LocaleStrings::parse("nation 7").is_err());
+    assert!(LocaleStrings::parse("colour 7 red").is_err());
+}
+
+#[test]
+fn should_be_english_without_a_directory() {
+    let translations = Translations::load(Path::new("resources/does_not_exist")).unwrap();
+    assert_eq!(translations, Translations::default());
+    assert_eq!(translations.strings(Some("de")), &LocaleStrings::default());
+}
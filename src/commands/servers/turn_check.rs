@@ -1,95 +1,368 @@
+use super::auto_archive::auto_archive_announcements;
+use super::changes::nation_statuses;
+use super::digest::due_digests;
+use super::status_message::{status_message_text, update_status_messages};
+use super::stragglers::STRAGGLER_HISTORY_TURNS;
+use super::turnstats::{turn_durations_while_watching, turn_stats};
+use super::undone::undone_players;
+use super::watch::watched_nation_dms;
+use super::webhook::new_turn_payload;
 use crate::commands::servers::*;
 use crate::db::*;
+use crate::game_log::GameLogContext;
+use crate::metrics::Metrics;
 use crate::model::enums::*;
-use crate::model::GameServerState;
-use crate::server::RealServerConnection;
-use crate::CacheWriteHandle;
-use chrono::Utc;
+use crate::model::{GameServer, GameServerState};
+use crate::notifier::Notifier;
+use crate::server::{ServerConnection, ServerError};
+use crate::snek::{NationNameProvider, DEFAULT_PROVIDERS};
+use chrono::{DateTime, Duration, Utc};
 use log::*;
 use serenity::framework::standard::CommandError;
-use serenity::model::id::UserId;
+use serenity::model::id::{ChannelId, UserId};
+use serenity::model::misc::Mentionable;
 use serenity::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
 use std::time;
 
-pub fn update_details_cache_loop(
+#[cfg(test)]
+mod lifecycle_tests;
+#[cfg(test)]
+mod test_helpers;
+#[cfg(test)]
+mod tests;
+
+/// How many servers we talk to at once
+const POLL_WORKERS: usize = 8;
+/// How long a single server gets, retries and all, before we give up on it until
+/// the next poll, if the bot isn't configured with something else
+pub const DEFAULT_POLL_TIMEOUT: time::Duration = time::Duration::from_secs(10);
+/// A gap between polls longer than this means we weren't watching for new turns
+pub const DOWNTIME_THRESHOLD_MINS: i64 = 30;
+/// How many times longer than usual a turn has to take before it looks stalled
+const STALL_FACTOR: i32 = 2;
+/// How far past usual a turn has to go before it's called stalled, unless the game
+/// sets its own. So that games that host every few minutes don't get called stalled
+/// over a short wait.
+pub const DEFAULT_STALL_GRACE_HOURS: u32 = 6;
+/// Only the recent pace counts when deciding what "usual" is
+const STALL_HISTORY_TURNS: u32 = 20;
+const MIN_STALL_HISTORY_TURNS: usize = 3;
+/// A turn that hosts this close to its deadline could just be the timer running
+/// out between polls
+const QUICKHOST_SLACK_MINS: i64 = 5;
+/// How far a turn's deadline has to move back before it counts as the host
+/// extending the timer, rather than the timer drifting between polls
+const TIMER_EXTENSION_MIN_MINS: i64 = 60;
+/// How long all the pretenders can sit uploaded before the host gets reminded to start
+pub const DEFAULT_START_REMINDER_HOURS: u32 = 12;
+/// How often the poller wakes up to see which games are due, so no game can be
+/// polled more often than this
+const POLL_TICK: time::Duration = time::Duration::from_secs(15);
+/// For games that haven't set their own, see `!pollinterval`
+pub const DEFAULT_POLL_INTERVAL_SECS: u32 = 60;
+/// Blitz games are polled every tick, a minute is most of their turn
+pub const BLITZ_POLL_INTERVAL_SECS: u32 = 15;
+
+pub fn default_poll_interval_secs(blitz: bool) -> u32 {
+    if blitz {
+        BLITZ_POLL_INTERVAL_SECS
+    } else {
+        DEFAULT_POLL_INTERVAL_SECS
+    }
+}
+
+pub fn update_details_cache_loop<C: ServerConnection, N: Notifier>(
     db_conn: DbConnection,
-    write_handle_mutex: Arc<Mutex<CacheWriteHandle>>,
+    game_cache: Arc<GameCache>,
+    metrics: Arc<Mutex<Metrics>>,
+    poll_timeout: time::Duration,
 ) {
+    let mut last_polls = LastPolls::default();
+    // Without it the poller just starts from scratch, so it's not worth failing over
+    if let Err(e) = warm_cache(&db_conn, &game_cache, &mut last_polls, Utc::now()) {
+        error!("Could not restore the last polls with error: {:?}", e);
+    }
+    let mut schedule = PollSchedule::default();
     loop {
         info!("Checking for new turns!");
-        let mut option_new_turn_nations = None;
-        for mut write_handle in write_handle_mutex.try_lock() {
-            let new_turn_nations = update_details_cache_for_all_games(&db_conn, &mut write_handle);
-            option_new_turn_nations = Some(new_turn_nations);
-        }
-        for new_turn_nation in option_new_turn_nations.unwrap_or(Vec::new()) {
-            match notify_player_for_new_turn(&new_turn_nation) {
-                Ok(()) => {}
-                Err(e) => {
-                    error!(
-                        "Failed to notify new turn {:?} with error: {:?}",
-                        new_turn_nation, e
-                    );
-                }
-            }
+        if let Err(e) =
+            db_conn.record_poller_heartbeat(Utc::now(), Duration::minutes(DOWNTIME_THRESHOLD_MINS))
+        {
+            error!("Could not record poller heartbeat with error: {:?}", e);
         }
-        thread::sleep(time::Duration::from_secs(60));
+        let (new_turn_nations, channel_notifications) = update_details_cache_for_all_games::<C, N>(
+            &db_conn,
+            &game_cache,
+            &mut last_polls,
+            &metrics,
+            &mut schedule,
+            poll_timeout,
+            Utc::now(),
+        );
+        send_notifications::<N>(&new_turn_nations, &channel_notifications);
+        update_status_messages::<N>(&db_conn);
+        thread::sleep(POLL_TICK);
     }
 }
 
-pub fn notify_player_for_new_turn(new_turn: &NewTurnNation) -> Result<(), CommandError> {
-    let private_channel = new_turn.user_id.create_dm_channel()?;
-    private_channel.say(&new_turn.message)?;
+/// Picks up from the last good poll of each game from before a restart, so the
+/// first poll afterwards is compared against it rather than treated as our
+/// first ever look. Polls from before a long outage are left out, since we'd
+/// have missed whatever happened in between anyway.
+pub fn warm_cache(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    last_polls: &mut LastPolls,
+    now: DateTime<Utc>,
+) -> Result<(), CommandError> {
+    let cutoff = now - Duration::minutes(DOWNTIME_THRESHOLD_MINS);
+    for (alias, cache_entry) in db_conn.cache_entries()? {
+        if cache_entry.fetched_at < cutoff {
+            continue;
+        }
+        game_cache.insert(&alias, cache_entry.clone());
+        last_polls.0.insert(alias, cache_entry);
+    }
     Ok(())
 }
 
+/// The last data the poller itself got for each game, which is what each poll
+/// is compared against to see what's changed. Commands fetching a game that's
+/// due a poll update the `GameCache` too, so it can't be used for this without
+/// the poller missing whatever they saw first.
+#[derive(Default)]
+pub struct LastPolls(HashMap<String, CacheEntry>);
+impl LastPolls {
+    pub fn get(&self, alias: &str) -> Option<&CacheEntry> {
+        self.0.get(alias)
+    }
+
+    /// Like the `GameCache`, so that deleted games and really old turns don't
+    /// hang around forever
+    fn retain_recent(&mut self, servers: &[GameServer], cutoff: DateTime<Utc>) {
+        self.0.retain(|alias, cache_entry| {
+            cache_entry.fetched_at >= cutoff && servers.iter().any(|server| &server.alias == alias)
+        });
+    }
+}
+
+/// When each game is next due a poll. Games we haven't polled yet are due
+/// straight away.
+#[derive(Default)]
+pub struct PollSchedule(HashMap<String, DateTime<Utc>>);
+impl PollSchedule {
+    pub fn is_due(&self, alias: &str, now: DateTime<Utc>) -> bool {
+        self.0.get(alias).is_none_or(|&next_poll| next_poll <= now)
+    }
+
+    pub fn polled(&mut self, alias: &str, now: DateTime<Utc>, interval: Duration) {
+        self.0.insert(alias.to_owned(), now + interval);
+    }
+
+    /// So that deleted and renamed games don't hang around forever
+    fn retain_aliases(&mut self, servers: &[GameServer]) {
+        self.0
+            .retain(|alias, _| servers.iter().any(|server| &server.alias == alias));
+    }
+}
+
+pub fn remove_old_entries_from_cache_loop(game_cache: Arc<GameCache>, ttl: Duration) {
+    loop {
+        thread::sleep(time::Duration::from_secs(60 * 60));
+        info!("Removing old entries from the cache");
+        game_cache.invalidate_older_than(ttl);
+    }
+}
+
+/// A failure to reach one person or channel shouldn't stop the rest hearing about it
+pub fn send_notifications<N: Notifier>(
+    new_turn_nations: &[NewTurnNation],
+    channel_notifications: &[ChannelNotification],
+) {
+    for new_turn_nation in new_turn_nations {
+        if let Err(e) = notify_player_for_new_turn::<N>(new_turn_nation) {
+            error!(
+                "Failed to notify new turn {:?} with error: {:?}",
+                new_turn_nation, e
+            );
+        }
+    }
+    for channel_notification in channel_notifications {
+        if let Err(e) = notify_channel::<N>(channel_notification) {
+            error!(
+                "Failed to notify channel {:?} with error: {:?}",
+                channel_notification, e
+            );
+        }
+    }
+}
+
+pub fn notify_player_for_new_turn<N: Notifier>(
+    new_turn: &NewTurnNation,
+) -> Result<(), CommandError> {
+    N::dm(new_turn.user_id, &new_turn.message)
+}
+
+pub fn notify_channel<N: Notifier>(
+    channel_notification: &ChannelNotification,
+) -> Result<(), CommandError> {
+    N::channel(
+        channel_notification.channel_id,
+        &channel_notification.message,
+    )
+}
+
+struct PollJob {
+    address: String,
+    providers: Vec<&'static dyn NationNameProvider>,
+    timeout: time::Duration,
+}
+
+fn poll_server<C: ServerConnection>(job: PollJob) -> Result<CacheEntry, ServerError> {
+    let game_data = C::get_game_data_with_timeout(&job.address, job.timeout)?;
+    // Providers log their own errors and fall through to the next one
+    let option_snek_state = C::get_snek_data(&job.address, &job.providers).unwrap_or(None);
+    Ok(CacheEntry {
+        game_data,
+        option_snek_state,
+        fetched_at: Utc::now(),
+    })
+}
+
+/// Runs `poll` over every job with at most `workers` running at once, keyed by alias.
+/// Each job is expected to enforce its own timeout so that one slow server only
+/// ties up one worker.
+pub fn poll_concurrently<J, R>(
+    jobs: Vec<(String, J)>,
+    workers: usize,
+    poll: fn(J) -> R,
+) -> HashMap<String, R>
+where
+    J: Send + 'static,
+    R: Send + 'static,
+{
+    let num_workers = workers.min(jobs.len());
+    let queue = Arc::new(Mutex::new(jobs.into_iter()));
+    let (sender, receiver) = mpsc::channel();
+    for _ in 0..num_workers {
+        let queue = queue.clone();
+        let sender = sender.clone();
+        thread::spawn(move || loop {
+            let option_job = queue.lock().next();
+            match option_job {
+                Some((alias, job)) => {
+                    if sender.send((alias, poll(job))).is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        });
+    }
+    // so that the receiver finishes once every worker has
+    drop(sender);
+    receiver.into_iter().collect()
+}
+
+fn details_from_poll(
+    db_conn: &DbConnection,
+    server: &GameServer,
+    option_poll: Option<Result<CacheEntry, String>>,
+) -> Result<GameDetails, CommandError> {
+    match (&server.state, option_poll) {
+        (GameServerState::Lobby(ref lobby_state), _) => {
+            lobby_details(db_conn, lobby_state, &server.alias)
+        }
+        (
+            GameServerState::StartedState(ref started_state, ref option_lobby_state),
+            Some(Ok(cache_entry)),
+        ) => started_details_from_server(
+            db_conn,
+            started_state,
+            option_lobby_state.as_ref(),
+            &server.alias,
+            cache_entry.game_data,
+            cache_entry.option_snek_state,
+            cache_entry.fetched_at,
+        ),
+        (GameServerState::StartedState(_, _), Some(Err(e))) => Err(CommandError(e)),
+        (GameServerState::StartedState(_, _), None) => {
+            Err(CommandError::from("Server was not polled. This is a bug."))
+        }
+        (GameServerState::Archived(_, _), _) => Err(CommandError::from(format!(
+            "{} has finished, it shouldn't be polled. This is a bug.",
+            server.alias
+        ))),
+    }
+}
+
 // FIXME: should just be regular error
-fn update_details_cache_for_game(
-    alias: &str,
+fn update_details_cache_for_game<N: Notifier>(
+    server: &GameServer,
+    option_poll: Option<Result<CacheEntry, ServerError>>,
     db_conn: &DbConnection,
-    write_handle: &mut CacheWriteHandle,
-) -> Result<Vec<NewTurnNation>, CommandError> {
-    info!("Checking turn for {}", alias);
+    game_cache: &GameCache,
+    last_polls: &mut LastPolls,
+) -> Result<(Vec<NewTurnNation>, Vec<ChannelNotification>), CommandError> {
+    let alias = server.alias.as_str();
+    let mut log_context = GameLogContext::new(alias);
+    if let GameServerState::StartedState(ref started_state, _) = server.state {
+        log_context = log_context.address(&started_state.address);
+    }
+    info!("Checking turn {}", log_context);
     let mut ret = vec![];
+    let mut channel_ret = vec![];
 
-    let option_old_cache: Option<Option<CacheEntry>> = write_handle
-        .0
-        .get_and(&alias.to_owned(), |results| (*results[0]).1.clone());
+    // Even if the last poll failed, the last data we did get is what's changed since
+    let option_old_cache: Option<CacheEntry> = last_polls.get(alias).cloned();
+
+    let option_error_category = match &option_poll {
+        Some(Err(e)) => Some(e.category()),
+        _ => None,
+    };
+    match &option_poll {
+        Some(Ok(cache_entry)) => info!(
+            "Polled {}",
+            log_context.turn(cache_entry.game_data.turn).outcome("ok")
+        ),
+        Some(Err(e)) => info!("Polled {}", log_context.outcome(e.category())),
+        // Lobbies have no server to poll yet
+        None => (),
+    }
+    let option_poll = option_poll.map(|poll| poll.map_err(|e| e.to_string()));
+    let result_details = details_from_poll(db_conn, server, option_poll);
 
-    let result_details = get_details_for_alias::<RealServerConnection>(db_conn, alias);
+    // A server that's stopped answering can be just as stuck as one that answers
+    // but never hosts, so only a paused or not yet playing game is exempt. The
+    // deadline is only known while it's answering.
+    let option_stall_check = match &result_details {
+        Ok(GameDetails {
+            nations:
+                NationDetails::Started(StartedDetails {
+                    state: StartedStateDetails::Playing(playing_state),
+                    ..
+                }),
+            ..
+        }) if !playing_state.paused => Some(playing_state.option_deadline),
+        Ok(_) => None,
+        Err(_) => Some(None),
+    };
 
-    let now = Utc::now();
     match result_details {
         Err(e) => {
             error!(
-                "Got an error when checking for details for alias {}: {:?}",
-                alias, e
+                "Got an error when checking for details: {:?} {}",
+                e, log_context
             );
-            write_handle
-                .0
-                .update(alias.to_owned(), Box::new((now, None)));
+            game_cache.insert_error(alias, e.0, option_error_category);
         }
         Ok(details) => {
-            // It's a bit of a hack to have 2 ways to check for turns
-            let updated = if let NationDetails::Started(started) = &details.nations {
-                let turn = if let StartedStateDetails::Playing(playing) = &started.state {
-                    playing.turn as i32
-                } else {
-                    -1
-                };
-                db_conn.update_game_with_possibly_new_turn(alias, turn)?
-            } else {
-                false
-            };
-
-            if updated {
-                if let NationDetails::Started(started_details) = &details.nations {
-                    ret.extend(create_messages_for_new_turn(alias, started_details));
-                }
-            } else {
-                for old_cache in option_old_cache.and_then(|x| x) {
+            let option_old_details = match option_old_cache {
+                Some(old_cache) => {
                     let server = db_conn.game_for_alias(&alias)?;
                     let old_details = match server.state {
                         GameServerState::Lobby(ref lobby_state) => {
@@ -105,27 +378,268 @@ fn update_details_cache_for_game(
                             alias,
                             old_cache.game_data,
                             old_cache.option_snek_state,
+                            old_cache.fetched_at,
                         )?,
+                        GameServerState::Archived(_, _) => {
+                            return Err(CommandError::from(format!("{} has finished", alias)))
+                        }
                     };
+                    Some(old_details)
+                }
+                None => None,
+            };
 
-                    if was_updated(&old_details, &details) {
-                        if let NationDetails::Started(started_details) = &details.nations {
-                            ret.extend(create_messages_for_new_turn(alias, started_details));
+            let routes = db_conn.notification_routes_for_alias(alias)?;
+            let mut option_rollback = None;
+            // It's a bit of a hack to have 2 ways to check for turns
+            let updated = if let NationDetails::Started(started) = &details.nations {
+                let turn = if let StartedStateDetails::Playing(playing) = &started.state {
+                    playing.turn as i32
+                } else {
+                    -1
+                };
+                if turn > 0 && db_conn.roll_back_to_turn(alias, turn)? {
+                    if let GameServerState::StartedState(started_state, _) = &server.state {
+                        warn!(
+                            "Turn went back from {} to {} {}",
+                            started_state.last_seen_turn, turn, log_context
+                        );
+                        option_rollback = Some((started_state.last_seen_turn, turn));
+                    }
+                }
+                // On a brand new turn the time remaining is (nearly) the whole timer
+                let option_timer_secs = details
+                    .cache_entry
+                    .as_ref()
+                    .map(|cache_entry| cache_entry.game_data.turn_timer)
+                    .filter(|&turn_timer| turn_timer > 0 && !is_paused_timer(turn_timer))
+                    .map(|turn_timer| turn_timer / 1000);
+                db_conn.update_game_with_possibly_new_turn(alias, turn, option_timer_secs)?
+            } else {
+                false
+            };
+
+            let new_turn = updated
+                || option_old_details
+                    .iter()
+                    .any(|old_details| was_updated(old_details, &details));
+            if new_turn {
+                db_conn.queue_status_message_text(
+                    alias,
+                    &status_message_text(alias, &details, Utc::now()),
+                )?;
+            }
+            // Our first look might be at a turn we've never seen, so keep that too
+            if new_turn || option_old_details.is_none() {
+                if let Some((turn, statuses)) = nation_statuses(&details) {
+                    // Coming back to a turn we've already recorded isn't news
+                    if new_turn {
+                        ret.extend(watched_nation_dms(db_conn, alias, turn, &statuses)?);
+                    }
+                    db_conn.record_nation_statuses(alias, turn, &statuses)?;
+                }
+            }
+            if new_turn && routes.new_turn.to_dm() {
+                if let NationDetails::Started(started_details) = &details.nations {
+                    let option_template = db_conn.reminder_template_for_alias(alias)?;
+                    // Players on vacation asked not to be bothered
+                    ret.extend(
+                        create_messages_for_new_turn(
+                            alias,
+                            started_details,
+                            option_template.as_deref(),
+                        )
+                        .into_iter()
+                        .filter(|new_turn_nation| {
+                            !details
+                                .player_vacations
+                                .contains_key(&new_turn_nation.user_id)
+                        }),
+                    );
+                }
+            }
+
+            let mut channel_messages = vec![];
+            if let Some((last_seen_turn, turn)) = option_rollback {
+                channel_messages.push(rollback_announcement(alias, last_seen_turn, turn));
+            }
+            // No old details means this is our first look since starting up, so any
+            // turn change might just be one that hosted while we weren't watching
+            if let Some(old_details) = &option_old_details {
+                ret.extend(submission_changes(alias, old_details, &details));
+                let seen_at = details
+                    .cache_entry
+                    .as_ref()
+                    .map_or_else(Utc::now, |cache_entry| cache_entry.fetched_at);
+                let mut newly_submitted = vec![];
+                for (turn, nation_id) in submissions_seen(old_details, &details) {
+                    // Only the first time, a rehosted turn going back in isn't news
+                    if db_conn.record_submission(
+                        alias,
+                        turn as i32,
+                        nation_id,
+                        seen_at,
+                        STRAGGLER_HISTORY_TURNS,
+                    )? {
+                        newly_submitted.push((turn, nation_id));
+                    }
+                }
+                let confirmations = submission_confirmations(alias, &details, &newly_submitted);
+                if !confirmations.is_empty() {
+                    channel_ret.extend(fan_out(
+                        alias,
+                        &submission_confirmation_channels::<N>(db_conn, alias)?,
+                        confirmations,
+                    ));
+                }
+                if updated {
+                    if let (Some(url), Some(playing_state)) = (
+                        db_conn.webhook_url_for_alias(alias)?,
+                        playing_state(&details),
+                    ) {
+                        // Whatever happens to it, the Discord side still goes out
+                        if let Err(e) = N::webhook(&url, &new_turn_payload(alias, playing_state)) {
+                            error!("Could not send webhook with error {:?} {}", e, log_context);
+                        }
+                    }
+                }
+                if updated && routes.new_turn.to_channel() {
+                    channel_messages.extend(new_turn_announcement(alias, &details));
+                    if let Some(old_turn) = quickhosted_turn(old_details, &details) {
+                        db_conn.mark_turn_quickhosted(alias, old_turn as i32)?;
+                        channel_messages.push(format!(
+                            "Turn {} in {} hosted early, everyone was done!",
+                            old_turn, alias
+                        ));
+                    }
+                }
+                for (turn, user_id, nation_id, submitted) in
+                    final_submissions(old_details, &details)
+                {
+                    db_conn.record_final_submission(
+                        alias,
+                        turn as i32,
+                        user_id,
+                        nation_id,
+                        submitted,
+                    )?;
+                }
+                if let Some(extension) = timer_extension(old_details, &details) {
+                    channel_messages.push(timer_extension_announcement(alias, extension));
+                }
+                channel_messages.extend(all_uploaded_announcement(alias, old_details, &details));
+                for nation_name in nations_gone_ai(old_details, &details) {
+                    channel_messages.push(format!("{} has gone AI in {}!", nation_name, alias));
+                }
+                for (turn, nation_id, nation_name) in nations_defeated(old_details, &details) {
+                    db_conn.record_elimination(alias, turn as i32, nation_id, &nation_name)?;
+                }
+                if let Some(turn) = waiting_on_host_turn(old_details, &details) {
+                    if routes.waiting_on_host != NotificationRoute::Off
+                        && db_conn.mark_waiting_on_host_alerted(alias, turn as i32)?
+                    {
+                        let message = waiting_on_host_announcement(alias, turn);
+                        if routes.waiting_on_host.to_dm() {
+                            let players: Vec<UserId> = db_conn
+                                .players_with_nations_for_game_alias(alias)?
+                                .into_iter()
+                                .map(|(player, _)| player.discord_user_id)
+                                .filter(|user_id| !details.player_vacations.contains_key(user_id))
+                                .collect();
+                            ret.extend(spectator_dms(&players, std::slice::from_ref(&message)));
+                        }
+                        if routes.waiting_on_host.to_channel() {
+                            channel_messages.push(message);
+                        }
+                    }
+                }
+            }
+            if let Some((message, undone)) = countdown_announcement(db_conn, alias, &details)? {
+                if routes.reminder.to_dm() {
+                    let undone: Vec<UserId> = undone
+                        .into_iter()
+                        .filter(|user_id| !details.player_vacations.contains_key(user_id))
+                        .collect();
+                    ret.extend(spectator_dms(&undone, std::slice::from_ref(&message)));
+                }
+                if routes.reminder.to_channel() {
+                    channel_messages.push(message);
+                }
+            }
+            ret.extend(start_reminder_dm(db_conn, server, &details, Utc::now())?);
+            if let (GameServerState::StartedState(_, _), Some(cache_entry)) =
+                (&server.state, &details.cache_entry)
+            {
+                channel_messages.extend(auto_archive_announcements::<N>(
+                    db_conn,
+                    alias,
+                    &cache_entry.game_data,
+                    Utc::now(),
+                )?);
+            }
+            if !channel_messages.is_empty() {
+                ret.extend(spectator_dms(
+                    &db_conn.spectators_for_alias(alias)?,
+                    &channel_messages,
+                ));
+                channel_ret.extend(fan_out(
+                    alias,
+                    &db_conn.notification_channels_for_alias(alias)?,
+                    channel_messages,
+                ));
+            }
+
+            // Remember where snek has the game, so the link survives snek going down
+            if let NationDetails::Started(started_details) = &details.nations {
+                if let GameServerState::StartedState(started_state, _) = &server.state {
+                    if let Some(snek_game_id) = started_details.option_snek_game_id {
+                        if started_state.option_snek_game_id != Some(snek_game_id) {
+                            db_conn.set_snek_game_id_for_alias(alias, snek_game_id)?;
                         }
                     }
                 }
             }
 
-            write_handle
-                .0
-                .update(alias.to_owned(), Box::new((now, details.cache_entry)));
+            // Lobbies have no cache entry, so there's nothing to save for them
+            if let Some(cache_entry) = details.cache_entry {
+                record_poll(db_conn, alias, &cache_entry)?;
+                db_conn.save_cache_entry(alias, &cache_entry)?;
+                last_polls.0.insert(alias.to_owned(), cache_entry);
+            }
+        }
+    }
+
+    if let Some(option_deadline) = option_stall_check {
+        if let Some(message) = stall_announcement(db_conn, alias, option_deadline, Utc::now())? {
+            let routes = db_conn.notification_routes_for_alias(alias)?;
+            if routes.stall.to_dm() {
+                let vacations = db_conn.player_vacations_for_alias(alias, Utc::now())?;
+                let players: Vec<UserId> = db_conn
+                    .players_with_nations_for_game_alias(alias)?
+                    .into_iter()
+                    .map(|(player, _)| player.discord_user_id)
+                    .filter(|user_id| !vacations.contains_key(user_id))
+                    .collect();
+                ret.extend(spectator_dms(&players, std::slice::from_ref(&message)));
+            }
+            if routes.stall.to_channel() {
+                ret.extend(spectator_dms(
+                    &db_conn.spectators_for_alias(alias)?,
+                    std::slice::from_ref(&message),
+                ));
+                channel_ret.extend(fan_out(
+                    alias,
+                    &db_conn.notification_channels_for_alias(alias)?,
+                    vec![message],
+                ));
+            }
         }
     }
 
     // FIXME: might just want to store the hash instead of cloning the string a bunch
-    info!("Checking turn for {}: SUCCESS", alias);
+    info!("Checking turn SUCCESS {}", log_context);
 
-    Ok(ret)
+    Ok((ret, channel_ret))
 }
 
 #[derive(Debug)]
@@ -134,31 +648,902 @@ pub struct NewTurnNation {
     pub message: String,
 }
 
-fn update_details_cache_for_all_games(
+#[derive(Debug)]
+pub struct ChannelNotification {
+    pub channel_id: ChannelId,
+    pub message: String,
+}
+
+/// The same announcements as `fan_out`, but DMed to spectators instead
+pub fn spectator_dms(user_ids: &[UserId], messages: &[String]) -> Vec<NewTurnNation> {
+    let mut seen = HashSet::new();
+    let mut ret = vec![];
+    for &user_id in user_ids {
+        if !seen.insert(user_id) {
+            continue;
+        }
+        for message in messages {
+            ret.push(NewTurnNation {
+                user_id,
+                message: message.clone(),
+            });
+        }
+    }
+    ret
+}
+
+/// Every message to every channel posting about the game. `channel_ids`
+/// shouldn't repeat, but a channel listed twice still only gets each message once.
+pub fn fan_out(
+    alias: &str,
+    channel_ids: &[ChannelId],
+    messages: Vec<String>,
+) -> Vec<ChannelNotification> {
+    if channel_ids.is_empty() {
+        info!("No channel to post about {} in", alias);
+    }
+    let mut seen = HashSet::new();
+    let mut ret = vec![];
+    for &channel_id in channel_ids {
+        if !seen.insert(channel_id) {
+            continue;
+        }
+        for message in &messages {
+            ret.push(ChannelNotification {
+                channel_id,
+                message: message.clone(),
+            });
+        }
+    }
+    ret
+}
+
+fn update_details_cache_for_all_games<C: ServerConnection, N: Notifier>(
     db_conn: &DbConnection,
-    write_handle: &mut CacheWriteHandle,
-) -> Vec<NewTurnNation> {
+    game_cache: &GameCache,
+    last_polls: &mut LastPolls,
+    metrics: &Mutex<Metrics>,
+    schedule: &mut PollSchedule,
+    poll_timeout: time::Duration,
+    now: DateTime<Utc>,
+) -> (Vec<NewTurnNation>, Vec<ChannelNotification>) {
     let mut ret = vec![];
+    let mut channel_ret = vec![];
     match db_conn.retrieve_all_servers() {
         Err(e) => {
             error!("Could not query the db for all servers with error: {:?}", e);
         }
-        Ok(servers) => {
-            // FIXME: might want to parallelise
+        Ok(all_servers) => {
+            // Finished games keep their players and history but aren't polled any more
+            let all_servers: Vec<GameServer> = all_servers
+                .into_iter()
+                .filter(|server| !matches!(server.state, GameServerState::Archived(_, _)))
+                .collect();
+            let poll_intervals_secs = db_conn.poll_intervals_secs().unwrap_or_else(|e| {
+                error!("Could not get poll intervals with error {:?}", e);
+                HashMap::new()
+            });
+            let blitz_aliases = db_conn.blitz_aliases().unwrap_or_else(|e| {
+                error!("Could not get blitz games with error {:?}", e);
+                vec![]
+            });
+            // Someone's working on their server, so errors and stalls aren't news
+            let polling_pauses = db_conn.polling_pauses(now).unwrap_or_else(|e| {
+                error!("Could not get polling pauses with error {:?}", e);
+                HashMap::new()
+            });
+            schedule.retain_aliases(&all_servers);
+            last_polls.retain_recent(&all_servers, now - Duration::hours(CACHE_TTL_HOURS));
+            let servers: Vec<&GameServer> = all_servers
+                .iter()
+                .filter(|server| {
+                    !polling_pauses.contains_key(&server.alias)
+                        && schedule.is_due(&server.alias, now)
+                })
+                .collect();
+
+            let mut jobs = vec![];
+            for server in &servers {
+                if let GameServerState::StartedState(ref started_state, _) = server.state {
+                    let providers =
+                        providers_for_alias(db_conn, &server.alias).unwrap_or_else(|e| {
+                            error!(
+                                "Could not get nation name providers with error {:?} {}",
+                                e,
+                                GameLogContext::new(&server.alias)
+                            );
+                            DEFAULT_PROVIDERS.to_vec()
+                        });
+                    jobs.push((
+                        server.alias.clone(),
+                        PollJob {
+                            address: started_state.address.clone(),
+                            providers,
+                            timeout: poll_timeout,
+                        },
+                    ));
+                }
+            }
+            let mut polls = poll_concurrently(jobs, POLL_WORKERS, poll_server::<C>);
+            // So that commands can use what we just fetched instead of asking again
+            for (alias, poll) in &polls {
+                if let Ok(cache_entry) = poll {
+                    game_cache.insert(alias, cache_entry.clone());
+                }
+            }
+            {
+                let mut metrics = metrics.lock();
+                metrics.record_pass(all_servers.iter().map(|server| server.alias.as_str()));
+                for (alias, poll) in &polls {
+                    metrics.record_poll(alias, poll, Utc::now());
+                }
+            }
+
             for server in servers {
-                match update_details_cache_for_game(&server.alias, db_conn, write_handle) {
-                    Ok(updates) => {
+                let interval_secs = poll_intervals_secs
+                    .get(&server.alias)
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        default_poll_interval_secs(blitz_aliases.contains(&server.alias))
+                    });
+                schedule.polled(
+                    &server.alias,
+                    now,
+                    Duration::seconds(i64::from(interval_secs)),
+                );
+                let option_poll = polls.remove(&server.alias);
+                match update_details_cache_for_game::<N>(
+                    server,
+                    option_poll,
+                    db_conn,
+                    game_cache,
+                    last_polls,
+                ) {
+                    Ok((updates, channel_updates)) => {
                         ret.extend(updates.into_iter());
+                        channel_ret.extend(channel_updates);
                     }
                     Err(e) => {
-                        error!("Could not update game {} with error {:?}", server.alias, e);
+                        error!(
+                            "Could not update game with error {:?} {}",
+                            e,
+                            GameLogContext::new(&server.alias)
+                        );
                     }
                 }
             }
-            write_handle.0.refresh();
         }
     }
-    ret
+    ret.extend(due_one_off_reminders(db_conn, now));
+    channel_ret.extend(due_digests(db_conn, now));
+    (ret, channel_ret)
+}
+
+/// `!remindme` reminders that are due, they go out with the new turn DMs
+fn due_one_off_reminders(db_conn: &DbConnection, now: DateTime<Utc>) -> Vec<NewTurnNation> {
+    match db_conn.take_due_one_off_reminders(now) {
+        Ok(due) => due
+            .into_iter()
+            .map(|(user_id, message)| NewTurnNation { user_id, message })
+            .collect(),
+        Err(e) => {
+            error!("Could not get due reminders with error {:?}", e);
+            vec![]
+        }
+    }
+}
+
+/// The current turn, how long it's been going and how long turns usually take,
+/// if it's been going for much longer than usual. Needs a few turns of history
+/// to know what usual is. A turn whose timer hasn't run out yet isn't stalled
+/// however long it is, and one whose timer has gets `grace` past the deadline too.
+pub fn stalled_turn(
+    now: DateTime<Utc>,
+    history: &[(i32, DateTime<Utc>)],
+    downtimes: &[Downtime],
+    option_deadline: Option<DateTime<Utc>>,
+    grace: Duration,
+) -> Option<(i32, Duration, Duration)> {
+    if option_deadline.is_some_and(|deadline| now <= deadline + grace) {
+        return None;
+    }
+    let durations = turn_durations_while_watching(history, downtimes);
+    if durations.len() < MIN_STALL_HISTORY_TURNS {
+        return None;
+    }
+    let median = turn_stats(&durations, &HashMap::new())?.median;
+    let &(turn, started) = history.last()?;
+    let elapsed = now.signed_duration_since(started);
+    if elapsed > median * STALL_FACTOR && elapsed > median + grace {
+        Some((turn, elapsed, median))
+    } else {
+        None
+    }
+}
+
+/// `stalled_turn` for a game, from what we've recorded of its turns
+pub fn current_stall(
+    db_conn: &DbConnection,
+    alias: &str,
+    option_deadline: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<Option<(i32, Duration, Duration)>, CommandError> {
+    let history = db_conn.select_turn_history(alias, STALL_HISTORY_TURNS + 1)?;
+    let downtimes = db_conn.select_downtimes()?;
+    let grace_hours = db_conn
+        .stall_grace_hours_for_alias(alias)?
+        .unwrap_or(DEFAULT_STALL_GRACE_HOURS);
+    Ok(stalled_turn(
+        now,
+        &history,
+        &downtimes,
+        option_deadline,
+        Duration::hours(i64::from(grace_hours)),
+    ))
+}
+
+/// Once per stalled turn; a new turn hosting resets it
+fn stall_announcement(
+    db_conn: &DbConnection,
+    alias: &str,
+    option_deadline: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<Option<String>, CommandError> {
+    match current_stall(db_conn, alias, option_deadline, now)? {
+        Some((turn, elapsed, median)) if db_conn.mark_stall_alerted(alias, turn)? => {
+            Ok(Some(format!(
+                "{} appears stalled: turn {} has been going for {:.1}h but turns usually \
+                 take {:.1}h. Is the server OK?",
+                alias,
+                turn,
+                hours(elapsed),
+                hours(median),
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The closest countdown threshold we're already within, if any. `countdown_mins`
+/// is most first.
+pub fn countdown_threshold(countdown_mins: &[u32], mins_left: i32) -> Option<u32> {
+    countdown_mins
+        .iter()
+        .rev()
+        .find(|&&mins| mins_left <= mins as i32)
+        .cloned()
+}
+
+pub fn rollback_announcement(alias: &str, last_seen_turn: i32, turn: i32) -> String {
+    format!(
+        "{} has gone back from turn {} to turn {}, has the host restored a backup?",
+        alias, last_seen_turn, turn
+    )
+}
+
+/// Whether a player who only wants `max_reminders` a turn should be pinged at
+/// `threshold`. They get the last, most urgent, ones.
+pub fn within_reminder_cap(countdown_mins: &[u32], threshold: u32, max_reminders: u32) -> bool {
+    let still_to_come = countdown_mins
+        .iter()
+        .filter(|&&mins| mins <= threshold)
+        .count();
+    still_to_come <= max_reminders as usize
+}
+
+/// What a countdown ping would say and who it's for
+#[derive(Debug, PartialEq)]
+pub struct CountdownPing {
+    pub threshold: u32,
+    pub message: String,
+    /// Once each, however many of their nations are still out
+    pub user_ids: Vec<UserId>,
+    /// Still to play, but their reminder cap leaves this threshold out
+    pub capped: Vec<UserId>,
+}
+
+/// The countdown with `mins_left` to go, without remembering that it went out.
+/// `None` if the game has no countdowns, none is due yet or nobody's left to
+/// ping.
+pub fn countdown_ping(
+    db_conn: &DbConnection,
+    alias: &str,
+    playing_state: &PlayingState,
+    mins_left: i32,
+) -> Result<Option<CountdownPing>, CommandError> {
+    let countdown_mins = match db_conn.countdown_mins_for_alias(alias)? {
+        Some(countdown_mins) => countdown_mins,
+        None => return Ok(None),
+    };
+    let threshold = match countdown_threshold(&countdown_mins, mins_left) {
+        Some(threshold) => threshold,
+        None => return Ok(None),
+    };
+    let reminder_caps = db_conn.reminder_caps_for_alias(alias)?;
+    let option_default_cap = db_conn.default_reminder_cap_for_alias(alias)?;
+    let mut user_ids: Vec<UserId> = vec![];
+    let mut capped: Vec<UserId> = vec![];
+    for (user_id, _) in undone_players(playing_state) {
+        // Their own setting, even "all", wins over the game's
+        let option_cap = match reminder_caps.get(&user_id) {
+            Some(&option_own_cap) => option_own_cap,
+            None => option_default_cap,
+        };
+        let pinged = option_cap.is_none_or(|max_reminders| {
+            within_reminder_cap(&countdown_mins, threshold, max_reminders)
+        });
+        let list = if pinged { &mut user_ids } else { &mut capped };
+        if !list.contains(&user_id) {
+            list.push(user_id);
+        }
+    }
+    if user_ids.is_empty() {
+        return Ok(None);
+    }
+    let mentions: Vec<String> = user_ids.iter().map(|user_id| user_id.mention()).collect();
+    let time_left = if playing_state.blitz {
+        playing_state.show_time_remaining()
+    } else {
+        format!("{} minutes", mins_left)
+    };
+    let message = if countdown_mins.first() == Some(&threshold) {
+        format!(
+            "{} left for turn {} in {}, still to play: {}",
+            time_left,
+            playing_state.turn,
+            alias,
+            mentions.join(" ")
+        )
+    } else {
+        format!(
+            "**Only {} left for turn {} in {}!** Still to play: {}",
+            time_left,
+            playing_state.turn,
+            alias,
+            mentions.join(" ")
+        )
+    };
+    Ok(Some(CountdownPing {
+        threshold,
+        message,
+        user_ids,
+        capped,
+    }))
+}
+
+/// Pings whoever still has to play as the deadline gets close, if the game has
+/// countdowns turned on. The first threshold is a reminder, the rest are louder.
+fn countdown_announcement(
+    db_conn: &DbConnection,
+    alias: &str,
+    details: &GameDetails,
+) -> Result<Option<(String, Vec<UserId>)>, CommandError> {
+    let playing_state = match &details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) if playing_state.option_deadline.is_some() => playing_state,
+        _ => return Ok(None),
+    };
+    let mut mins_left = playing_state.hours_remaining * 60 + playing_state.mins_remaining;
+    // A blitz game polled every few seconds would otherwise ping a minute early
+    if playing_state.blitz && playing_state.secs_remaining > 0 {
+        mins_left += 1;
+    }
+    let ping = match countdown_ping(db_conn, alias, playing_state, mins_left)? {
+        Some(ping) => ping,
+        None => return Ok(None),
+    };
+    if !db_conn.mark_countdown_pinged(alias, playing_state.turn as i32, ping.threshold)? {
+        return Ok(None);
+    }
+    Ok(Some((ping.message, ping.user_ids)))
+}
+
+/// Whether everyone has been uploaded for long enough that the host has probably
+/// forgotten to start the game. `since` is when the pretenders last changed.
+pub fn start_overdue(
+    progress: UploadProgress,
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+    start_reminder_hours: u32,
+) -> bool {
+    start_reminder_hours != 0
+        && progress.is_complete()
+        && now.signed_duration_since(since) >= Duration::hours(i64::from(start_reminder_hours))
+}
+
+/// DMs the host once when the game looks forgotten in uploading. More pretenders
+/// coming in starts the wait again, and the game starting forgets about it.
+fn start_reminder_dm(
+    db_conn: &DbConnection,
+    server: &GameServer,
+    details: &GameDetails,
+    now: DateTime<Utc>,
+) -> Result<Option<NewTurnNation>, CommandError> {
+    let alias = server.alias.as_str();
+    let uploading_state = match &details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Uploading(uploading_state),
+            ..
+        }) => uploading_state,
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(_),
+            ..
+        }) => {
+            db_conn.clear_pretenders_seen(alias)?;
+            return Ok(None);
+        }
+        NationDetails::Lobby(_) => return Ok(None),
+    };
+    // Only games made as lobbies have a host we know about
+    let owner = match &server.state {
+        GameServerState::StartedState(_, Some(lobby_state)) => lobby_state.owner,
+        _ => return Ok(None),
+    };
+    let progress = uploading_state.upload_progress();
+    let pretenders = (progress.uploaded + progress.unregistered) as i32;
+    let since = db_conn.update_game_with_pretenders_seen(alias, pretenders, now)?;
+    let start_reminder_hours = db_conn
+        .start_reminder_hours_for_alias(alias)?
+        .unwrap_or(DEFAULT_START_REMINDER_HOURS);
+    if !start_overdue(progress, since, now, start_reminder_hours)
+        || !db_conn.mark_start_reminded(alias)?
+    {
+        return Ok(None);
+    }
+    Ok(Some(NewTurnNation {
+        user_id: owner,
+        message: format!(
+            "All {} registered pretenders have been in for {} for {}h, \
+             don't forget to start the game!",
+            progress.expected,
+            alias,
+            now.signed_duration_since(since).num_hours()
+        ),
+    }))
+}
+
+pub fn new_turn_announcement(alias: &str, details: &GameDetails) -> Option<String> {
+    match &details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => {
+            let humans_to_play = playing_state
+                .players
+                .iter()
+                .filter(|potential_player| match potential_player {
+                    PotentialPlayer::RegisteredAndGame(_, details)
+                    | PotentialPlayer::GameOnly(details) => {
+                        details.player_status.is_human()
+                            && details.submitted == SubmissionStatus::NotSubmitted
+                    }
+                    PotentialPlayer::RegisteredOnly(_, _, _) => false,
+                })
+                .count();
+            Some(format!(
+                "Turn {} has begun in {}! {} nations still need to play.",
+                playing_state.turn, alias, humans_to_play
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn playing_state(details: &GameDetails) -> Option<&PlayingState> {
+    match &details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => Some(playing_state),
+        _ => None,
+    }
+}
+
+/// The turn every human has played, if it was already like that last poll and
+/// still hasn't hosted. A turn that's only just been finished is normal, one
+/// that's still sat there a poll later means the host is lagging.
+pub fn waiting_on_host_turn(old_details: &GameDetails, new_details: &GameDetails) -> Option<u32> {
+    let old_playing_state = playing_state(old_details)?;
+    let new_playing_state = playing_state(new_details)?;
+    if old_playing_state.turn == new_playing_state.turn
+        && old_playing_state.waiting_on_host()
+        && new_playing_state.waiting_on_host()
+    {
+        Some(new_playing_state.turn)
+    } else {
+        None
+    }
+}
+
+fn waiting_on_host_announcement(alias: &str, turn: u32) -> String {
+    format!(
+        "Everyone has played turn {} in {}, but it hasn't hosted yet. The server may be lagging.",
+        turn, alias
+    )
+}
+
+/// The turn that just ended, if it hosted well before its deadline. We saw the new
+/// turn at the latest when we fetched it, so if that's before the old deadline the
+/// timer can't have been what hosted it.
+pub fn quickhosted_turn(old_details: &GameDetails, new_details: &GameDetails) -> Option<u32> {
+    let old_playing_state = playing_state(old_details)?;
+    let new_playing_state = playing_state(new_details)?;
+    // If we missed a turn we don't know when the one we saw ended
+    if new_playing_state.turn != old_playing_state.turn + 1 {
+        return None;
+    }
+    let old_deadline = old_playing_state.option_deadline?;
+    let seen_at = new_details.cache_entry.as_ref()?.fetched_at;
+    if old_deadline.signed_duration_since(seen_at) > Duration::minutes(QUICKHOST_SLACK_MINS) {
+        Some(old_playing_state.turn)
+    } else {
+        None
+    }
+}
+
+/// How much later the current turn is due than it was last time we looked, if the
+/// host has pushed the deadline back. A new turn always gets a fresh timer, so
+/// only a turn we've already seen can have been extended.
+pub fn timer_extension(old_details: &GameDetails, new_details: &GameDetails) -> Option<Duration> {
+    let old_playing_state = playing_state(old_details)?;
+    let new_playing_state = playing_state(new_details)?;
+    if new_playing_state.turn != old_playing_state.turn {
+        return None;
+    }
+    let extension = new_playing_state
+        .option_deadline?
+        .signed_duration_since(old_playing_state.option_deadline?);
+    if extension >= Duration::minutes(TIMER_EXTENSION_MIN_MINS) {
+        Some(extension)
+    } else {
+        None
+    }
+}
+
+fn timer_extension_announcement(alias: &str, extension: Duration) -> String {
+    // to the nearest hour, the deadline moves a little between polls anyway
+    let hours = (extension.num_minutes() + 30) / 60;
+    format!("Timer extended by {}h in {}", hours, alias)
+}
+
+/// A line for each registered player we've just seen get their turn in, as
+/// opposed to nations that were still out when the turn hosted. Someone playing
+/// more than one nation is only done once all of theirs are in.
+pub fn submission_confirmations(
+    alias: &str,
+    details: &GameDetails,
+    newly_submitted: &[(u32, u32)],
+) -> Vec<String> {
+    let playing_state = match playing_state(details) {
+        Some(playing_state) => playing_state,
+        None => return vec![],
+    };
+    let is_new = |details: &PlayerDetails| {
+        newly_submitted.contains(&(playing_state.turn, details.nation_id))
+    };
+    let registered: Vec<(UserId, &PlayerDetails)> = playing_state
+        .players
+        .iter()
+        .filter_map(|player| match player {
+            PotentialPlayer::RegisteredAndGame(user_id, details) => Some((*user_id, details)),
+            _ => None,
+        })
+        .collect();
+    let mut confirmed_user_ids = vec![];
+    let mut confirmations = vec![];
+    for &(user_id, details) in &registered {
+        if !is_new(details) || confirmed_user_ids.contains(&user_id) {
+            continue;
+        }
+        confirmed_user_ids.push(user_id);
+        let nations: Vec<&PlayerDetails> = registered
+            .iter()
+            .filter(|&&(other_user_id, other_details)| {
+                other_user_id == user_id
+                    && (is_new(other_details) || other_details.player_status.is_human())
+            })
+            .map(|&(_, other_details)| other_details)
+            .collect();
+        let all_in = nations
+            .iter()
+            .all(|nation| is_new(nation) || nation.submitted == SubmissionStatus::Submitted);
+        if !all_in {
+            continue;
+        }
+        let nation_names: Vec<&str> = nations
+            .iter()
+            .map(|nation| nation.nation_name.as_str())
+            .collect();
+        let verb = if nation_names.len() == 1 {
+            "has"
+        } else {
+            "have"
+        };
+        confirmations.push(format!(
+            "✅ {} {} played turn {} in {}",
+            nation_names.join(" and "),
+            verb,
+            playing_state.turn,
+            alias
+        ));
+    }
+    confirmations
+}
+
+/// The game's channels that are in servers that turned on `!confirmsubmissions`
+fn submission_confirmation_channels<N: Notifier>(
+    db_conn: &DbConnection,
+    alias: &str,
+) -> Result<Vec<ChannelId>, CommandError> {
+    let mut channel_ids = vec![];
+    for channel_id in db_conn.notification_channels_for_alias(alias)? {
+        if let Some(guild_id) = N::guild_for_channel(channel_id) {
+            if db_conn.submission_confirmations_for_guild(guild_id)? {
+                channel_ids.push(channel_id);
+            }
+        }
+    }
+    Ok(channel_ids)
+}
+
+/// How the turn that just hosted ended for each registered human: (turn, player,
+/// nation, whether they had it in). Our last look was a little before it
+/// hosted, but if it hosted early everyone was in by definition. Nothing if we
+/// missed a turn, as we don't know how it ended.
+pub fn final_submissions(
+    old_details: &GameDetails,
+    new_details: &GameDetails,
+) -> Vec<(u32, UserId, u32, bool)> {
+    let (old_playing_state, new_playing_state) =
+        match (playing_state(old_details), playing_state(new_details)) {
+            (Some(old_playing_state), Some(new_playing_state)) => {
+                (old_playing_state, new_playing_state)
+            }
+            _ => return vec![],
+        };
+    if new_playing_state.turn != old_playing_state.turn + 1 {
+        return vec![];
+    }
+    let quickhosted = quickhosted_turn(old_details, new_details).is_some();
+    old_playing_state
+        .players
+        .iter()
+        .filter_map(|player| match player {
+            PotentialPlayer::RegisteredAndGame(user_id, details)
+                if details.player_status.is_human() =>
+            {
+                Some((
+                    old_playing_state.turn,
+                    *user_id,
+                    details.nation_id,
+                    quickhosted || details.submitted == SubmissionStatus::Submitted,
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The (turn, nation ID) of every human nation that got its turn in between two
+/// polls. Whoever hadn't played when the turn hosted was the last to get it in,
+/// or didn't at all, so they count as submitting when we saw the new turn.
+pub fn submissions_seen(old_details: &GameDetails, new_details: &GameDetails) -> Vec<(u32, u32)> {
+    let (old_playing_state, new_playing_state) =
+        match (playing_state(old_details), playing_state(new_details)) {
+            (Some(old_playing_state), Some(new_playing_state)) => {
+                (old_playing_state, new_playing_state)
+            }
+            _ => return vec![],
+        };
+    let unsubmitted_humans = |playing_state: &PlayingState| -> Vec<u32> {
+        playing_state
+            .players
+            .iter()
+            .filter_map(|player| match player {
+                PotentialPlayer::RegisteredAndGame(_, details)
+                | PotentialPlayer::GameOnly(details)
+                    if details.player_status.is_human()
+                        && details.submitted != SubmissionStatus::Submitted =>
+                {
+                    Some(details.nation_id)
+                }
+                _ => None,
+            })
+            .collect()
+    };
+    let old_unsubmitted = unsubmitted_humans(old_playing_state);
+    if new_playing_state.turn == old_playing_state.turn {
+        let new_unsubmitted = unsubmitted_humans(new_playing_state);
+        old_unsubmitted
+            .into_iter()
+            .filter(|nation_id| !new_unsubmitted.contains(nation_id))
+            .map(|nation_id| (old_playing_state.turn, nation_id))
+            .collect()
+    } else if new_playing_state.turn == old_playing_state.turn + 1 {
+        old_unsubmitted
+            .into_iter()
+            .map(|nation_id| (old_playing_state.turn, nation_id))
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+/// Only when the last registered pretender comes in, not on every poll after
+pub fn all_uploaded_announcement(
+    alias: &str,
+    old_details: &GameDetails,
+    new_details: &GameDetails,
+) -> Option<String> {
+    let upload_progress = |details: &GameDetails| match &details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Uploading(uploading_state),
+            ..
+        }) => Some(uploading_state.upload_progress()),
+        _ => None,
+    };
+    let old_progress = upload_progress(old_details)?;
+    let new_progress = upload_progress(new_details)?;
+    if new_progress.is_complete() && !old_progress.is_complete() {
+        Some(format!(
+            "All {} registered pretenders are in for {}, the host can start the game!",
+            new_progress.expected, alias
+        ))
+    } else {
+        None
+    }
+}
+
+/// The names of registered nations that were being played by a human last time
+/// we looked, but are now AI
+pub fn nations_gone_ai(old_details: &GameDetails, new_details: &GameDetails) -> Vec<String> {
+    match (&old_details.nations, &new_details.nations) {
+        (NationDetails::Started(old_started), NationDetails::Started(new_started)) => {
+            match (&old_started.state, &new_started.state) {
+                (
+                    StartedStateDetails::Playing(old_playing),
+                    StartedStateDetails::Playing(new_playing),
+                ) => {
+                    let old_statuses: HashMap<u32, NationStatus> = old_playing
+                        .players
+                        .iter()
+                        .filter_map(|player| match player {
+                            PotentialPlayer::RegisteredAndGame(_, details)
+                            | PotentialPlayer::GameOnly(details) => {
+                                Some((details.nation_id, details.player_status))
+                            }
+                            PotentialPlayer::RegisteredOnly(_, _, _) => None,
+                        })
+                        .collect();
+                    new_playing
+                        .players
+                        .iter()
+                        .filter_map(|player| match player {
+                            PotentialPlayer::RegisteredAndGame(_, details) if details.went_ai => {
+                                match old_statuses.get(&details.nation_id) {
+                                    Some(NationStatus::Human) => Some(details.nation_name.clone()),
+                                    _ => None,
+                                }
+                            }
+                            _ => None,
+                        })
+                        .collect()
+                }
+                _ => vec![],
+            }
+        }
+        _ => vec![],
+    }
+}
+
+/// (turn, nation ID, nation name) for nations that were still alive last time we
+/// looked but have been defeated since, the turn being the first one we saw them
+/// dead in
+pub fn nations_defeated(
+    old_details: &GameDetails,
+    new_details: &GameDetails,
+) -> Vec<(u32, u32, String)> {
+    let (old_playing, new_playing) = match (playing_state(old_details), playing_state(new_details))
+    {
+        (Some(old_playing), Some(new_playing)) => (old_playing, new_playing),
+        _ => return vec![],
+    };
+    let old_alive: HashSet<u32> = old_playing
+        .players
+        .iter()
+        .filter_map(|player| match player {
+            PotentialPlayer::RegisteredAndGame(_, details) | PotentialPlayer::GameOnly(details)
+                if !details.player_status.is_defeated() =>
+            {
+                Some(details.nation_id)
+            }
+            _ => None,
+        })
+        .collect();
+    new_playing
+        .players
+        .iter()
+        .filter_map(|player| match player {
+            PotentialPlayer::RegisteredAndGame(_, details) | PotentialPlayer::GameOnly(details)
+                if details.player_status.is_defeated()
+                    && old_alive.contains(&details.nation_id) =>
+            {
+                Some((
+                    new_playing.turn,
+                    details.nation_id,
+                    details.nation_name.clone(),
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// DMs for registered players whose own turn went in, or was undone (e.g. by the
+/// host rehosting the turn), since we last looked. A new turn resets everybody,
+/// but that's what the new turn DM is for.
+pub fn submission_changes(
+    alias: &str,
+    old_details: &GameDetails,
+    new_details: &GameDetails,
+) -> Vec<NewTurnNation> {
+    let (old_playing, new_playing) = match (&old_details.nations, &new_details.nations) {
+        (
+            NationDetails::Started(StartedDetails {
+                state: StartedStateDetails::Playing(old_playing),
+                ..
+            }),
+            NationDetails::Started(StartedDetails {
+                state: StartedStateDetails::Playing(new_playing),
+                ..
+            }),
+        ) if old_playing.turn == new_playing.turn => (old_playing, new_playing),
+        _ => return vec![],
+    };
+    let old_submissions: HashMap<(UserId, u32), SubmissionStatus> = old_playing
+        .players
+        .iter()
+        .filter_map(|player| match player {
+            PotentialPlayer::RegisteredAndGame(user_id, details) => {
+                Some(((*user_id, details.nation_id), details.submitted))
+            }
+            _ => None,
+        })
+        .collect();
+    new_playing
+        .players
+        .iter()
+        .filter_map(|player| match player {
+            PotentialPlayer::RegisteredAndGame(user_id, details) => {
+                let old_submitted = old_submissions.get(&(*user_id, details.nation_id))?;
+                let message = match (old_submitted, details.submitted) {
+                    (SubmissionStatus::NotSubmitted, SubmissionStatus::Submitted) => format!(
+                        "Your turn {} for {} in {} has gone in.",
+                        new_playing.turn, details.nation_name, alias
+                    ),
+                    (SubmissionStatus::Submitted, SubmissionStatus::NotSubmitted)
+                    | (SubmissionStatus::PartiallySubmitted, SubmissionStatus::NotSubmitted) => {
+                        format!(
+                        "Your turn {} for {} in {} is no longer submitted, maybe the host rehosted it. \
+                         You'll need to play it again, you have {} remaining.",
+                        new_playing.turn,
+                        details.nation_name,
+                        alias,
+                        new_playing.show_time_remaining()
+                        )
+                    }
+                    _ => return None,
+                };
+                Some(NewTurnNation {
+                    user_id: *user_id,
+                    message,
+                })
+            }
+            _ => None,
+        })
+        .collect()
 }
 
 pub fn was_updated(old_details: &GameDetails, new_details: &GameDetails) -> bool {
@@ -185,12 +1570,50 @@ pub fn was_updated(old_details: &GameDetails, new_details: &GameDetails) -> bool
     }
 }
 
+/// Fills in `%nation%`, `%turn%` and `%hours%`. Anything else that looks like a
+/// token is left as it is, it might just be a percent sign.
+pub fn fill_reminder_template(template: &str, nation_name: &str, turn: u32, hours: i32) -> String {
+    template
+        .replace("%nation%", nation_name)
+        .replace("%turn%", &turn.to_string())
+        .replace("%hours%", &hours.to_string())
+}
+
+/// The new turn DM for one player, in the game's own wording if it has any
+pub fn new_turn_message(
+    alias: &str,
+    playing_details: &PlayingState,
+    details: &PlayerDetails,
+    option_template: Option<&str>,
+) -> String {
+    match option_template.filter(|template| !template.trim().is_empty()) {
+        Some(template) => fill_reminder_template(
+            template,
+            &details.nation_name,
+            playing_details.turn,
+            playing_details.hours_remaining,
+        ),
+        None => format!(
+            "New turn in {}! You are {} and you have {} remaining for turn {}.",
+            alias,
+            details.nation_name,
+            playing_details.show_time_remaining(),
+            playing_details.turn,
+        ),
+    }
+}
+
+/// `option_template` is the game's own wording for new turn DMs, see
+/// `fill_reminder_template`
 pub fn create_messages_for_new_turn(
     alias: &str,
     new_started_details: &StartedDetails,
+    option_template: Option<&str>,
 ) -> Vec<NewTurnNation> {
     let mut ret = vec![];
     match new_started_details.state {
+        // Nobody needs reminding about a turn that isn't counting down
+        StartedStateDetails::Playing(ref new_playing_details) if new_playing_details.paused => {}
         StartedStateDetails::Playing(ref new_playing_details) => {
             for potential_player in &new_playing_details.players {
                 match potential_player {
@@ -201,18 +1624,15 @@ pub fn create_messages_for_new_turn(
                         if let SubmissionStatus::NotSubmitted = details.submitted {
                             // and if they're actually playing
                             if details.player_status.is_human() {
-                                ret.push(
-                                    NewTurnNation {
-                                        user_id: *user_id,
-                                        message: format!("New turn in {}! You are {} and you have {}h {}m remaining for turn {}.",
-                                                         alias,
-                                                         details.nation_name,
-                                                         new_playing_details.hours_remaining,
-                                                         new_playing_details.mins_remaining,
-                                                         new_playing_details.turn,
-                                        )
-                                    }
-                                );
+                                ret.push(NewTurnNation {
+                                    user_id: *user_id,
+                                    message: new_turn_message(
+                                        alias,
+                                        new_playing_details,
+                                        details,
+                                        option_template,
+                                    ),
+                                });
                             }
                         }
                     }
@@ -0,0 +1,289 @@
+//! One game followed from its lobby to being archived, through the same
+//! detail building and polling the bot does, so changes that only break when
+//! the pieces are put together show up here
+
+use super::test_helpers::TestPoller;
+use super::*;
+
+use crate::model::{GameData, LobbyState, Nation, Player, StartedState};
+use crate::notifier::test_helpers::RecordingNotifier;
+use crate::server::test_helpers::RecordingServerConnection;
+
+const ALIAS: &str = "lifecycle";
+const ADDRESS: &str = "recording.test.lifecycle:1234";
+const OWNER: UserId = UserId(3821);
+const OTHER: UserId = UserId(3822);
+const CHANNEL: ChannelId = ChannelId(3821);
+
+fn nation(id: u32, status: NationStatus, submitted: SubmissionStatus) -> Nation {
+    Nation {
+        id,
+        status,
+        submitted,
+        connected: false,
+        name: Nations::get_nation_name(id),
+        era: "EA".to_owned(),
+    }
+}
+
+fn game_data(turn: i32, nations: Vec<Nation>) -> GameData {
+    GameData {
+        game_name: ALIAS.to_owned(),
+        nations,
+        turn,
+        turn_timer: 0,
+    }
+}
+
+struct Bot {
+    db_conn: DbConnection,
+    poller: TestPoller,
+}
+
+impl Bot {
+    /// One pass of the poller, with everything it wants to say sent
+    fn poll(&mut self) {
+        self.poller.poll_and_send(&self.db_conn);
+    }
+
+    /// What `!details` would be built from after the last poll
+    fn details(&self) -> GameDetails {
+        match self.db_conn.game_for_alias(ALIAS).unwrap().state {
+            GameServerState::Lobby(lobby_state) => {
+                lobby_details(&self.db_conn, &lobby_state, ALIAS).unwrap()
+            }
+            GameServerState::StartedState(started_state, option_lobby_state) => {
+                let cache_entry = self
+                    .poller
+                    .game_cache
+                    .get_poll(ALIAS)
+                    .and_then(|cached_poll| cached_poll.option_cache_entry)
+                    .expect("the game should have been polled");
+                started_details_from_server(
+                    &self.db_conn,
+                    &started_state,
+                    option_lobby_state.as_ref(),
+                    ALIAS,
+                    cache_entry.game_data,
+                    cache_entry.option_snek_state,
+                    cache_entry.fetched_at,
+                )
+                .unwrap()
+            }
+            GameServerState::Archived(_, _) => panic!("{} has been archived", ALIAS),
+        }
+    }
+
+    fn playing_state(&self) -> PlayingState {
+        match self.details().nations {
+            NationDetails::Started(StartedDetails {
+                state: StartedStateDetails::Playing(playing_state),
+                ..
+            }) => playing_state,
+            nations => panic!("{} should be playing, not {:?}", ALIAS, nations),
+        }
+    }
+}
+
+fn register(db_conn: &DbConnection, user_id: UserId, nation_id: u32) {
+    db_conn
+        .insert_player_into_server(
+            &Player {
+                discord_user_id: user_id,
+                turn_notifications: true,
+            },
+            ALIAS,
+            nation_id,
+        )
+        .unwrap();
+}
+
+/// Only what's been sent since the last time this was asked
+fn new_messages(seen: &mut usize, messages: Vec<String>) -> Vec<String> {
+    let new = messages[*seen..].to_vec();
+    *seen = messages.len();
+    new
+}
+
+#[test]
+fn should_follow_a_game_from_lobby_to_archive() {
+    use crate::model::enums::Era;
+
+    let mut bot = Bot {
+        db_conn: DbConnection::test(),
+        poller: TestPoller::new(),
+    };
+    let mut seen_in_channel = 0;
+    let mut channel = || {
+        new_messages(
+            &mut seen_in_channel,
+            RecordingNotifier::channel_messages(CHANNEL),
+        )
+    };
+
+    // Lobby: both players register, leaving a slot free
+    bot.db_conn
+        .insert_game_server(&GameServer {
+            alias: ALIAS.to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: OWNER,
+                era: Era::Early,
+                player_count: 3,
+                description: None,
+            }),
+        })
+        .unwrap();
+    bot.db_conn.set_channel_for_alias(ALIAS, CHANNEL).unwrap();
+    register(&bot.db_conn, OWNER, 5);
+    register(&bot.db_conn, OTHER, 7);
+    match bot.details().nations {
+        NationDetails::Lobby(lobby_details) => {
+            assert_eq!(lobby_details.players.len(), 2);
+            assert_eq!(lobby_details.remaining_slots, Some(1));
+        }
+        nations => panic!("should still be a lobby, not {:?}", nations),
+    }
+
+    // What the server says at each poll: pretenders going in, two turns, Ulm
+    // going AI and then being wiped out
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![
+            game_data(
+                -1,
+                vec![nation(
+                    5,
+                    NationStatus::Human,
+                    SubmissionStatus::NotSubmitted,
+                )],
+            ),
+            game_data(
+                -1,
+                vec![
+                    nation(5, NationStatus::Human, SubmissionStatus::NotSubmitted),
+                    nation(7, NationStatus::Human, SubmissionStatus::NotSubmitted),
+                ],
+            ),
+            game_data(
+                1,
+                vec![
+                    nation(5, NationStatus::Human, SubmissionStatus::NotSubmitted),
+                    nation(7, NationStatus::Human, SubmissionStatus::NotSubmitted),
+                ],
+            ),
+            game_data(
+                2,
+                vec![
+                    nation(5, NationStatus::Human, SubmissionStatus::NotSubmitted),
+                    nation(7, NationStatus::Human, SubmissionStatus::NotSubmitted),
+                ],
+            ),
+            game_data(
+                3,
+                vec![
+                    nation(5, NationStatus::Human, SubmissionStatus::NotSubmitted),
+                    nation(7, NationStatus::AI, SubmissionStatus::Submitted),
+                ],
+            ),
+            game_data(
+                4,
+                vec![
+                    nation(5, NationStatus::Human, SubmissionStatus::NotSubmitted),
+                    nation(7, NationStatus::Defeated, SubmissionStatus::Submitted),
+                ],
+            ),
+        ],
+    );
+    bot.db_conn
+        .insert_started_state(
+            ALIAS,
+            &StartedState {
+                address: ADDRESS.to_owned(),
+                last_seen_turn: -1,
+                option_snek_game_id: None,
+            },
+        )
+        .unwrap();
+    // Pretender upload: only Arcoscephale is in at first
+    bot.poll();
+    match bot.details().nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Uploading(uploading_state),
+            ..
+        }) => {
+            let uploaded: Vec<(u32, bool)> = uploading_state
+                .uploading_players
+                .iter()
+                .map(|player| (player.nation_id(), player.uploaded))
+                .collect();
+            assert_eq!(uploaded, vec![(5, true), (7, false)]);
+        }
+        nations => panic!("should be uploading, not {:?}", nations),
+    }
+    // The first look is only a baseline
+    assert!(channel().is_empty());
+
+    bot.poll();
+    assert_eq!(
+        channel(),
+        vec!["All 2 registered pretenders are in for lifecycle, the host can start the game!"]
+    );
+
+    // Turns 1 and 2: everyone is told it's their turn
+    bot.poll();
+    assert_eq!(bot.playing_state().turn, 1);
+    assert_eq!(
+        channel(),
+        vec!["Turn 1 has begun in lifecycle! 2 nations still need to play."]
+    );
+    bot.poll();
+    assert_eq!(bot.playing_state().turn, 2);
+    assert_eq!(
+        channel(),
+        vec!["Turn 2 has begun in lifecycle! 2 nations still need to play."]
+    );
+    for user_id in &[OWNER, OTHER] {
+        assert_eq!(RecordingNotifier::dms(*user_id).len(), 2);
+    }
+
+    // Turn 3: Ulm's player drops and the game hands them to the AI
+    bot.poll();
+    let ulm = bot
+        .playing_state()
+        .players
+        .into_iter()
+        .find_map(|player| match player {
+            PotentialPlayer::RegisteredAndGame(user_id, details) if details.nation_id == 7 => {
+                Some((user_id, details))
+            }
+            _ => None,
+        })
+        .expect("Ulm should still be registered");
+    assert_eq!(ulm.0, OTHER);
+    assert!(ulm.1.went_ai);
+    assert_eq!(
+        channel(),
+        vec![
+            "Turn 3 has begun in lifecycle! 1 nations still need to play.",
+            "Ulm has gone AI in lifecycle!",
+        ]
+    );
+    // Nobody DMs an AI
+    assert_eq!(RecordingNotifier::dms(OTHER).len(), 2);
+    assert_eq!(RecordingNotifier::dms(OWNER).len(), 3);
+
+    // Turn 4: Ulm is wiped out and the host calls it
+    bot.poll();
+    assert_eq!(
+        bot.db_conn.select_eliminations(ALIAS).unwrap(),
+        vec![(4, 7, "Ulm".to_owned())]
+    );
+    bot.db_conn.set_archived_for_alias(ALIAS, true).unwrap();
+    let calls = RecordingServerConnection::game_data_calls(ADDRESS);
+    bot.poll();
+    assert_eq!(RecordingServerConnection::game_data_calls(ADDRESS), calls);
+    assert!(matches!(
+        bot.db_conn.game_for_alias(ALIAS).unwrap().state,
+        GameServerState::Archived(_, _)
+    ));
+}
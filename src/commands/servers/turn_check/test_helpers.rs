@@ -0,0 +1,87 @@
+use super::*;
+
+use crate::model::{GameData, Nation};
+use crate::notifier::test_helpers::RecordingNotifier;
+use crate::server::test_helpers::RecordingServerConnection;
+
+/// Everything the poller keeps between passes, polling whatever has been
+/// scripted for the `RecordingServerConnection`
+pub struct TestPoller {
+    pub game_cache: GameCache,
+    pub last_polls: LastPolls,
+    pub metrics: Mutex<Metrics>,
+    pub schedule: PollSchedule,
+}
+
+impl TestPoller {
+    pub fn new() -> Self {
+        TestPoller {
+            game_cache: GameCache::test(),
+            last_polls: LastPolls::default(),
+            metrics: Mutex::new(Metrics::default()),
+            schedule: PollSchedule::default(),
+        }
+    }
+
+    /// One pass of the poller, with every game due
+    pub fn poll(
+        &mut self,
+        db_conn: &DbConnection,
+    ) -> (Vec<NewTurnNation>, Vec<ChannelNotification>) {
+        update_details_cache_for_all_games::<RecordingServerConnection, RecordingNotifier>(
+            db_conn,
+            &self.game_cache,
+            &mut self.last_polls,
+            &self.metrics,
+            &mut PollSchedule::default(),
+            DEFAULT_POLL_TIMEOUT,
+            Utc::now(),
+        )
+    }
+
+    /// One pass of the poller at `now`, only polling the games that are due
+    pub fn poll_due(&mut self, db_conn: &DbConnection, now: DateTime<Utc>) {
+        update_details_cache_for_all_games::<RecordingServerConnection, RecordingNotifier>(
+            db_conn,
+            &self.game_cache,
+            &mut self.last_polls,
+            &self.metrics,
+            &mut self.schedule,
+            DEFAULT_POLL_TIMEOUT,
+            now,
+        );
+    }
+
+    /// `poll`, with everything it wants to say sent
+    pub fn poll_and_send(&mut self, db_conn: &DbConnection) {
+        let (new_turn_nations, channel_notifications) = self.poll(db_conn);
+        send_notifications::<RecordingNotifier>(&new_turn_nations, &channel_notifications);
+    }
+
+    /// What `poll` would DM `user_id`
+    pub fn poll_dms(&mut self, db_conn: &DbConnection, user_id: UserId) -> Vec<String> {
+        let (new_turn_nations, _) = self.poll(db_conn);
+        new_turn_nations
+            .into_iter()
+            .filter(|new_turn_nation| new_turn_nation.user_id == user_id)
+            .map(|new_turn_nation| new_turn_nation.message)
+            .collect()
+    }
+}
+
+/// A game with nobody but a human Ulm (7) in it
+pub fn ulm_game_data(game_name: &str, turn: i32, submitted: SubmissionStatus) -> GameData {
+    GameData {
+        game_name: game_name.to_owned(),
+        nations: vec![Nation {
+            id: 7,
+            status: NationStatus::Human,
+            submitted,
+            connected: false,
+            name: "Ulm".to_owned(),
+            era: "EA".to_owned(),
+        }],
+        turn,
+        turn_timer: 0,
+    }
+}
@@ -0,0 +1,1349 @@
+use super::*;
+
+use super::test_helpers::*;
+use crate::notifier::test_helpers::RecordingNotifier;
+
+fn slow_if_asked(secs: u64) -> u64 {
+    thread::sleep(time::Duration::from_secs(secs));
+    secs
+}
+
+#[test]
+fn should_not_wait_for_slow_servers_before_polling_others() {
+    let jobs = vec![
+        ("slow".to_owned(), 2),
+        ("fast 1".to_owned(), 0),
+        ("fast 2".to_owned(), 0),
+        ("fast 3".to_owned(), 0),
+    ];
+
+    let start = time::Instant::now();
+    let results = poll_concurrently(jobs, 2, slow_if_asked);
+    // one worker is stuck on the slow job the whole time, the other does the rest
+    assert!(start.elapsed() < time::Duration::from_secs(4));
+    assert_eq!(results.len(), 4);
+    assert_eq!(results["slow"], 2);
+    assert_eq!(results["fast 3"], 0);
+}
+
+#[test]
+fn should_poll_nothing_if_there_are_no_servers() {
+    let results = poll_concurrently(Vec::<(String, u64)>::new(), 8, slow_if_asked);
+    assert!(results.is_empty());
+}
+
+#[test]
+fn should_count_humans_still_to_play_in_new_turn_announcement() {
+    let player_details = |nation_id, submitted, player_status| PlayerDetails {
+        nation_id,
+        nation_name: format!("nation {}", nation_id),
+        submitted,
+        player_status,
+        went_ai: false,
+        connected: false,
+    };
+    let details = GameDetails {
+        alias: "foo".to_owned(),
+        owner: None,
+        description: None,
+        player_nicknames: HashMap::new(),
+        player_vacations: HashMap::new(),
+        nations: NationDetails::Started(StartedDetails {
+            address: "foo.bar:1234".to_owned(),
+            game_name: "foo".to_owned(),
+            state: StartedStateDetails::Playing(PlayingState {
+                players: vec![
+                    PotentialPlayer::GameOnly(player_details(
+                        1,
+                        SubmissionStatus::NotSubmitted,
+                        NationStatus::Human,
+                    )),
+                    PotentialPlayer::RegisteredAndGame(
+                        UserId(2),
+                        player_details(2, SubmissionStatus::NotSubmitted, NationStatus::Human),
+                    ),
+                    PotentialPlayer::RegisteredAndGame(
+                        UserId(3),
+                        player_details(3, SubmissionStatus::Submitted, NationStatus::Human),
+                    ),
+                    PotentialPlayer::GameOnly(player_details(
+                        4,
+                        SubmissionStatus::NotSubmitted,
+                        NationStatus::AI,
+                    )),
+                ],
+                turn: 12,
+                mins_remaining: 0,
+                hours_remaining: 24,
+                days_remaining: 1,
+                secs_remaining: 0,
+                option_deadline: None,
+                paused: false,
+                blitz: false,
+            }),
+            option_snek_game_id: None,
+        }),
+        cache_entry: None,
+        warnings: vec![],
+    };
+
+    assert_eq!(
+        new_turn_announcement("foo", &details),
+        Some("Turn 12 has begun in foo! 2 nations still need to play.".to_owned())
+    );
+}
+
+fn uploading_details(uploading_players: Vec<UploadingPlayer>) -> GameDetails {
+    GameDetails {
+        alias: "foo".to_owned(),
+        owner: None,
+        description: None,
+        player_nicknames: HashMap::new(),
+        player_vacations: HashMap::new(),
+        nations: NationDetails::Started(StartedDetails {
+            address: "foo.bar:1234".to_owned(),
+            game_name: "foo".to_owned(),
+            state: StartedStateDetails::Uploading(UploadingState { uploading_players }),
+            option_snek_game_id: None,
+        }),
+        cache_entry: None,
+        warnings: vec![],
+    }
+}
+
+fn uploading_player(
+    option_user_id: Option<UserId>,
+    nation_id: u32,
+    uploaded: bool,
+) -> UploadingPlayer {
+    let player_details = PlayerDetails {
+        nation_id,
+        nation_name: format!("nation {}", nation_id),
+        submitted: SubmissionStatus::NotSubmitted,
+        player_status: NationStatus::Human,
+        went_ai: false,
+        connected: false,
+    };
+    let potential_player = match (option_user_id, uploaded) {
+        (Some(user_id), true) => PotentialPlayer::RegisteredAndGame(user_id, player_details),
+        (Some(user_id), false) => {
+            PotentialPlayer::RegisteredOnly(user_id, nation_id, player_details.nation_name)
+        }
+        (None, _) => PotentialPlayer::GameOnly(player_details),
+    };
+    UploadingPlayer {
+        potential_player,
+        uploaded,
+    }
+}
+
+#[test]
+fn should_announce_when_last_registered_pretender_is_in() {
+    let old_details = uploading_details(vec![
+        uploading_player(Some(UserId(1)), 1, true),
+        uploading_player(Some(UserId(2)), 2, false),
+    ]);
+    let new_details = uploading_details(vec![
+        uploading_player(Some(UserId(1)), 1, true),
+        uploading_player(Some(UserId(2)), 2, true),
+        // somebody who didn't register doesn't hold anything up
+        uploading_player(None, 3, true),
+    ]);
+
+    assert_eq!(
+        all_uploaded_announcement("foo", &old_details, &new_details),
+        Some("All 2 registered pretenders are in for foo, the host can start the game!".to_owned())
+    );
+    // and it's only said once
+    assert_eq!(
+        all_uploaded_announcement("foo", &new_details, &new_details),
+        None
+    );
+}
+
+#[test]
+fn should_show_upload_progress_against_registered_players() {
+    let details = uploading_details(vec![
+        uploading_player(Some(UserId(1)), 1, true),
+        uploading_player(Some(UserId(2)), 2, false),
+        uploading_player(None, 3, true),
+    ]);
+    if let NationDetails::Started(StartedDetails {
+        state: StartedStateDetails::Uploading(uploading_state),
+        ..
+    }) = &details.nations
+    {
+        assert_eq!(
+            uploading_state.upload_progress().to_string(),
+            "1/2 pretenders uploaded (and 1 from unregistered players)"
+        );
+    } else {
+        panic!("not uploading");
+    }
+}
+
+#[test]
+fn should_keep_snek_link_when_snek_is_down() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .set_snek_game_id_for_alias("test server 1", 1234)
+        .unwrap();
+    let started_state = match db_conn.game_for_alias("test server 1").unwrap().state {
+        GameServerState::StartedState(started_state, _) => started_state,
+        GameServerState::Lobby(_) | GameServerState::Archived(_, _) => {
+            panic!("test server 1 should have started")
+        }
+    };
+    assert_eq!(started_state.option_snek_game_id, Some(1234));
+
+    let game_data = crate::model::GameData {
+        game_name: "test server 1".to_owned(),
+        nations: vec![],
+        turn: 31,
+        turn_timer: 0,
+    };
+    // no snek data this time
+    let details = started_details_from_server(
+        &db_conn,
+        &started_state,
+        None,
+        "test server 1",
+        game_data,
+        None,
+        Utc::now(),
+    )
+    .unwrap();
+    match details.nations {
+        NationDetails::Started(started_details) => {
+            assert_eq!(started_details.option_snek_game_id, Some(1234))
+        }
+        NationDetails::Lobby(_) => panic!("test server 1 should have started"),
+    }
+}
+
+#[test]
+fn should_record_what_a_poll_saw_for_later() {
+    use crate::server::test_helpers::RecordingServerConnection;
+    use crate::snek::{SnekGameStatus, SnekNation};
+
+    const ADDRESS: &str = "recording.test.record.poll:1234";
+    let game_data = ulm_game_data("recorded poll", 5, SubmissionStatus::NotSubmitted);
+    RecordingServerConnection::script_game_data(ADDRESS, vec![game_data]);
+    let mut nations = HashMap::new();
+    nations.insert(
+        7,
+        SnekNation {
+            nation_id: 7,
+            name: "Modded Ulm".to_owned(),
+        },
+    );
+    RecordingServerConnection::script_snek_data(
+        ADDRESS,
+        SnekGameStatus {
+            nations,
+            option_game_id: None,
+        },
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("recorded poll", ADDRESS, 5, &[(101, 7)]);
+    TestPoller::new().poll(&db_conn);
+
+    assert_eq!(
+        db_conn.nation_names_for_alias("recorded poll").unwrap()[&7],
+        "Modded Ulm"
+    );
+    assert_eq!(
+        db_conn
+            .first_seen_for_alias("recorded poll")
+            .unwrap()
+            .map(|first_seen| first_seen.turn),
+        Some(5)
+    );
+}
+
+#[test]
+fn should_notify_registered_players_when_poller_sees_new_turn() {
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.new.turn:1234";
+    let game_data_for_turn = |turn| ulm_game_data("recorded", turn, SubmissionStatus::NotSubmitted);
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![game_data_for_turn(31), game_data_for_turn(32)],
+    );
+    RecordingServerConnection::script_snek_data(
+        ADDRESS,
+        crate::snek::SnekGameStatus {
+            nations: HashMap::new(),
+            option_game_id: Some(77),
+        },
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("recorded", ADDRESS, 31, &[(101, 7)]);
+    let mut poller = TestPoller::new();
+
+    // the same turn as we already knew about
+    assert!(poller.poll_dms(&db_conn, UserId(101)).is_empty());
+
+    let (new_turn_nations, channel_notifications) = poller.poll(&db_conn);
+    let messages: Vec<&str> = new_turn_nations
+        .iter()
+        .filter(|new_turn_nation| new_turn_nation.user_id == UserId(101))
+        .map(|new_turn_nation| new_turn_nation.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec!["New turn in recorded! You are Ulm and you have 0h 0m remaining for turn 32."]
+    );
+    // we've never been told which channel the game is in
+    assert!(channel_notifications.is_empty());
+    assert_eq!(RecordingServerConnection::game_data_calls(ADDRESS), 2);
+    match db_conn.game_for_alias("recorded").unwrap().state {
+        GameServerState::StartedState(started_state, _) => {
+            assert_eq!(started_state.option_snek_game_id, Some(77))
+        }
+        GameServerState::Lobby(_) | GameServerState::Archived(_, _) => {
+            panic!("recorded should have started")
+        }
+    }
+}
+
+/// "test server 1" as seen by the poller, with UserId(101) playing Ulm
+fn test_server_1_with_timer(turn_timer: i32) -> StartedDetails {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_player_into_server(
+            &crate::model::Player {
+                discord_user_id: UserId(101),
+                turn_notifications: true,
+            },
+            "test server 1",
+            7,
+        )
+        .unwrap();
+    let started_state = match db_conn.game_for_alias("test server 1").unwrap().state {
+        GameServerState::StartedState(started_state, _) => started_state,
+        GameServerState::Lobby(_) | GameServerState::Archived(_, _) => {
+            panic!("test server 1 should have started")
+        }
+    };
+    let game_data = crate::model::GameData {
+        game_name: "test server 1".to_owned(),
+        nations: vec![crate::model::Nation {
+            id: 7,
+            status: NationStatus::Human,
+            submitted: SubmissionStatus::NotSubmitted,
+            connected: false,
+            name: "Ulm".to_owned(),
+            era: "EA".to_owned(),
+        }],
+        turn: 32,
+        turn_timer,
+    };
+
+    let details = started_details_from_server(
+        &db_conn,
+        &started_state,
+        None,
+        "test server 1",
+        game_data,
+        None,
+        Utc::now(),
+    )
+    .unwrap();
+    match details.nations {
+        NationDetails::Started(started_details) => started_details,
+        NationDetails::Lobby(_) => panic!("test server 1 should have started"),
+    }
+}
+
+#[test]
+fn should_not_remind_anyone_about_paused_games() {
+    // what an unsigned "forever" looks like once it's been read as an i32
+    let started_details = test_server_1_with_timer(-1);
+    match &started_details.state {
+        StartedStateDetails::Playing(playing_state) => {
+            assert!(playing_state.paused);
+            assert_eq!(playing_state.option_deadline, None);
+            assert_eq!(playing_state.show_time_remaining(), "paused");
+        }
+        StartedStateDetails::Uploading(_) => panic!("test server 1 should be playing"),
+    }
+    assert!(create_messages_for_new_turn("test server 1", &started_details, None).is_empty());
+}
+
+#[test]
+fn should_show_days_for_long_timers() {
+    let started_details = test_server_1_with_timer((72 * 60 + 5) * 60 * 1000);
+    match &started_details.state {
+        StartedStateDetails::Playing(playing_state) => {
+            // still the totals, for anything doing sums with them
+            assert_eq!(playing_state.hours_remaining, 72);
+            assert_eq!(playing_state.mins_remaining, 5);
+            assert_eq!(playing_state.days_remaining, 3);
+        }
+        StartedStateDetails::Uploading(_) => panic!("test server 1 should be playing"),
+    }
+    let messages = create_messages_for_new_turn("test server 1", &started_details, None);
+    assert_eq!(
+        messages[0].message,
+        "New turn in test server 1! You are Ulm and you have 3d 0h 5m remaining for turn 32."
+    );
+}
+
+#[test]
+fn should_use_the_games_reminder_template() {
+    let started_details = test_server_1_with_timer(5 * 60 * 60 * 1000);
+    let messages = create_messages_for_new_turn(
+        "test server 1",
+        &started_details,
+        Some("The council awaits your orders, %nation% (%hours%h, turn %turn%)"),
+    );
+    assert_eq!(
+        messages[0].message,
+        "The council awaits your orders, Ulm (5h, turn 32)"
+    );
+
+    // a blank template isn't a message
+    let messages = create_messages_for_new_turn("test server 1", &started_details, Some(" "));
+    assert!(messages[0]
+        .message
+        .starts_with("New turn in test server 1!"));
+}
+
+fn hour(h: i64) -> chrono::DateTime<Utc> {
+    use chrono::TimeZone;
+    Utc.timestamp(h * 3600, 0)
+}
+
+#[test]
+fn should_only_call_much_longer_turns_stalled() {
+    // turns 1-3 took 24h each, turn 4 started at hour 72
+    let history = vec![(1, hour(0)), (2, hour(24)), (3, hour(48)), (4, hour(72))];
+    let grace = Duration::hours(i64::from(DEFAULT_STALL_GRACE_HOURS));
+
+    assert_eq!(
+        stalled_turn(hour(72 + 40), &history, &[], None, grace),
+        None
+    );
+    assert_eq!(
+        stalled_turn(hour(72 + 50), &history, &[], None, grace),
+        Some((4, Duration::hours(50), Duration::hours(24)))
+    );
+    // not enough history to know what usual is
+    assert_eq!(
+        stalled_turn(hour(72 + 50), &history[1..], &[], None, grace),
+        None
+    );
+    // a longer grace period puts it off
+    assert_eq!(
+        stalled_turn(hour(72 + 50), &history, &[], None, Duration::hours(48)),
+        None
+    );
+}
+
+#[test]
+fn should_not_call_a_game_on_a_long_timer_stalled() {
+    // turns 1-3 took 2h each, then the host put turn 4 on a 72h timer
+    let history = vec![(1, hour(0)), (2, hour(2)), (3, hour(4)), (4, hour(6))];
+    let grace = Duration::hours(i64::from(DEFAULT_STALL_GRACE_HOURS));
+    let deadline = hour(6 + 72);
+
+    // far longer than usual, but the timer is still running: slow but normal
+    assert_eq!(
+        stalled_turn(hour(6 + 60), &history, &[], Some(deadline), grace),
+        None
+    );
+    // just past the timer, it could be about to host
+    assert_eq!(
+        stalled_turn(hour(6 + 75), &history, &[], Some(deadline), grace),
+        None
+    );
+    // well past the timer and still not hosted: genuinely stalled
+    assert_eq!(
+        stalled_turn(hour(6 + 80), &history, &[], Some(deadline), grace),
+        Some((4, Duration::hours(80), Duration::hours(2)))
+    );
+}
+
+#[test]
+fn should_only_alert_once_per_stalled_turn() {
+    let db_conn = DbConnection::test();
+    assert!(db_conn.mark_stall_alerted("test server 1", 4).unwrap());
+    assert!(!db_conn.mark_stall_alerted("test server 1", 4).unwrap());
+    // a new turn hosted and then stalled too
+    assert!(db_conn.mark_stall_alerted("test server 1", 5).unwrap());
+    assert!(db_conn.mark_stall_alerted("test server 2", 4).unwrap());
+}
+
+fn registered_playing_details(turn: u32, submissions: &[(u64, SubmissionStatus)]) -> GameDetails {
+    GameDetails {
+        alias: "foo".to_owned(),
+        owner: None,
+        description: None,
+        player_nicknames: HashMap::new(),
+        player_vacations: HashMap::new(),
+        nations: NationDetails::Started(StartedDetails {
+            address: "foo.bar:1234".to_owned(),
+            game_name: "foo".to_owned(),
+            state: StartedStateDetails::Playing(PlayingState {
+                players: submissions
+                    .iter()
+                    .map(|(user_id, submitted)| {
+                        PotentialPlayer::RegisteredAndGame(
+                            UserId(*user_id),
+                            PlayerDetails {
+                                nation_id: *user_id as u32,
+                                nation_name: format!("nation {}", user_id),
+                                submitted: *submitted,
+                                player_status: NationStatus::Human,
+                                went_ai: false,
+                                connected: false,
+                            },
+                        )
+                    })
+                    .collect(),
+                turn,
+                mins_remaining: 0,
+                hours_remaining: 5,
+                days_remaining: 0,
+                secs_remaining: 0,
+                option_deadline: None,
+                paused: false,
+                blitz: false,
+            }),
+            option_snek_game_id: None,
+        }),
+        cache_entry: None,
+        warnings: vec![],
+    }
+}
+
+#[test]
+fn should_tell_players_when_their_own_turn_goes_in_or_is_undone() {
+    let old_details = registered_playing_details(
+        12,
+        &[
+            (1, SubmissionStatus::NotSubmitted),
+            (2, SubmissionStatus::Submitted),
+            (3, SubmissionStatus::NotSubmitted),
+        ],
+    );
+    let new_details = registered_playing_details(
+        12,
+        &[
+            (1, SubmissionStatus::Submitted),
+            (2, SubmissionStatus::NotSubmitted),
+            (3, SubmissionStatus::NotSubmitted),
+        ],
+    );
+
+    let messages = submission_changes("foo", &old_details, &new_details);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].user_id, UserId(1));
+    assert_eq!(
+        messages[0].message,
+        "Your turn 12 for nation 1 in foo has gone in."
+    );
+    assert_eq!(messages[1].user_id, UserId(2));
+    assert!(messages[1].message.contains("no longer submitted"));
+}
+
+#[test]
+fn should_only_say_waiting_on_host_once_everyone_has_played_for_a_poll() {
+    let done = |turn| {
+        registered_playing_details(
+            turn,
+            &[
+                (1, SubmissionStatus::Submitted),
+                (2, SubmissionStatus::Submitted),
+            ],
+        )
+    };
+    let partial = registered_playing_details(
+        12,
+        &[
+            (1, SubmissionStatus::Submitted),
+            (2, SubmissionStatus::PartiallySubmitted),
+        ],
+    );
+
+    assert_eq!(waiting_on_host_turn(&done(12), &done(12)), Some(12));
+    // the last turn only just went in
+    assert_eq!(waiting_on_host_turn(&partial, &done(12)), None);
+    // it hosted and everyone played the new one straight away
+    assert_eq!(waiting_on_host_turn(&done(12), &done(13)), None);
+    // nobody there to be waiting on
+    let empty = registered_playing_details(12, &[]);
+    assert_eq!(waiting_on_host_turn(&empty, &empty), None);
+
+    let db_conn = DbConnection::test();
+    assert!(db_conn
+        .mark_waiting_on_host_alerted("test server 1", 12)
+        .unwrap());
+    assert!(!db_conn
+        .mark_waiting_on_host_alerted("test server 1", 12)
+        .unwrap());
+}
+
+#[test]
+fn should_not_report_submission_resets_from_a_new_turn() {
+    let old_details = registered_playing_details(12, &[(2, SubmissionStatus::Submitted)]);
+    let new_details = registered_playing_details(13, &[(2, SubmissionStatus::NotSubmitted)]);
+
+    assert!(submission_changes("foo", &old_details, &new_details).is_empty());
+}
+
+#[test]
+fn should_only_remind_to_start_once_everyone_has_waited_long_enough() {
+    let complete = UploadProgress {
+        uploaded: 3,
+        expected: 3,
+        unregistered: 0,
+    };
+    let incomplete = UploadProgress {
+        uploaded: 2,
+        expected: 3,
+        unregistered: 0,
+    };
+    assert!(!start_overdue(complete, hour(0), hour(11), 12));
+    assert!(start_overdue(complete, hour(0), hour(12), 12));
+    assert!(!start_overdue(incomplete, hour(0), hour(48), 12));
+    // turned off
+    assert!(!start_overdue(complete, hour(0), hour(48), 0));
+}
+
+#[test]
+fn should_restart_the_start_reminder_when_pretenders_change() {
+    let db_conn = DbConnection::test();
+    assert_eq!(
+        db_conn
+            .update_game_with_pretenders_seen("test server 1", 3, hour(0))
+            .unwrap(),
+        hour(0)
+    );
+    // same pretenders a poll later
+    assert_eq!(
+        db_conn
+            .update_game_with_pretenders_seen("test server 1", 3, hour(1))
+            .unwrap(),
+        hour(0)
+    );
+    assert!(db_conn.mark_start_reminded("test server 1").unwrap());
+    assert!(!db_conn.mark_start_reminded("test server 1").unwrap());
+
+    // another pretender came in
+    assert_eq!(
+        db_conn
+            .update_game_with_pretenders_seen("test server 1", 4, hour(2))
+            .unwrap(),
+        hour(2)
+    );
+    assert!(db_conn.mark_start_reminded("test server 1").unwrap());
+
+    // the game started
+    db_conn.clear_pretenders_seen("test server 1").unwrap();
+    assert_eq!(
+        db_conn
+            .update_game_with_pretenders_seen("test server 1", 4, hour(3))
+            .unwrap(),
+        hour(3)
+    );
+    assert!(db_conn.mark_start_reminded("test server 1").unwrap());
+}
+
+fn seen_playing_details(
+    turn: u32,
+    option_deadline_hour: Option<i64>,
+    seen_hour: i64,
+) -> GameDetails {
+    let mut details = registered_playing_details(turn, &[(1, SubmissionStatus::NotSubmitted)]);
+    if let NationDetails::Started(StartedDetails {
+        state: StartedStateDetails::Playing(playing_state),
+        ..
+    }) = &mut details.nations
+    {
+        playing_state.option_deadline = option_deadline_hour.map(hour);
+    }
+    details.cache_entry = Some(CacheEntry {
+        game_data: crate::model::GameData {
+            game_name: "foo".to_owned(),
+            nations: vec![],
+            turn: turn as i32,
+            turn_timer: 0,
+        },
+        option_snek_state: None,
+        fetched_at: hour(seen_hour),
+    });
+    details
+}
+
+#[test]
+fn should_spot_turns_that_hosted_before_their_deadline() {
+    // seen 10h before turn 12 was due
+    let old_details = seen_playing_details(12, Some(24), 13);
+    let new_details = seen_playing_details(13, Some(48), 14);
+    assert_eq!(quickhosted_turn(&old_details, &new_details), Some(12));
+
+    // the timer ran out between polls
+    let old_details = seen_playing_details(12, Some(24), 23);
+    let new_details = seen_playing_details(13, Some(48), 24);
+    assert_eq!(quickhosted_turn(&old_details, &new_details), None);
+
+    // no timer, so nothing to be early for
+    let old_details = seen_playing_details(12, None, 13);
+    let new_details = seen_playing_details(13, None, 14);
+    assert_eq!(quickhosted_turn(&old_details, &new_details), None);
+
+    // we missed turn 13 entirely
+    let old_details = seen_playing_details(12, Some(24), 13);
+    let new_details = seen_playing_details(14, Some(72), 14);
+    assert_eq!(quickhosted_turn(&old_details, &new_details), None);
+}
+
+#[test]
+fn should_spot_timer_extensions_within_a_turn() {
+    let old_details = seen_playing_details(12, Some(24), 13);
+    let new_details = seen_playing_details(12, Some(36), 14);
+    assert_eq!(
+        timer_extension(&old_details, &new_details),
+        Some(Duration::hours(12))
+    );
+    assert_eq!(
+        timer_extension_announcement("foo", Duration::hours(12)),
+        "Timer extended by 12h in foo"
+    );
+
+    // a new turn gets a fresh timer, that's not an extension
+    let new_details = seen_playing_details(13, Some(48), 14);
+    assert_eq!(timer_extension(&old_details, &new_details), None);
+
+    // the deadline wobbling a bit between polls
+    let new_details = seen_playing_details(12, Some(24), 14);
+    assert_eq!(timer_extension(&old_details, &new_details), None);
+
+    // the host unpausing isn't an extension either
+    let old_details = seen_playing_details(12, None, 13);
+    let new_details = seen_playing_details(12, Some(36), 14);
+    assert_eq!(timer_extension(&old_details, &new_details), None);
+}
+
+#[test]
+fn should_record_quickhosted_turns() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .update_game_with_possibly_new_turn("test server 1", 32, None)
+        .unwrap();
+    db_conn
+        .update_game_with_possibly_new_turn("test server 1", 33, None)
+        .unwrap();
+    db_conn.mark_turn_quickhosted("test server 1", 32).unwrap();
+
+    assert_eq!(
+        db_conn.select_quickhosted_turns("test server 1").unwrap(),
+        vec![32]
+    );
+    assert!(db_conn
+        .select_quickhosted_turns("test server 2")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_send_poller_notifications_through_the_notifier() {
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.notifier:1234";
+    let game_data_for_turn = |turn| ulm_game_data("notified", turn, SubmissionStatus::NotSubmitted);
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![game_data_for_turn(31), game_data_for_turn(32)],
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("notified", ADDRESS, 31, &[(303, 7)]);
+    db_conn
+        .set_channel_for_alias("notified", ChannelId(303))
+        .unwrap();
+    let mut poller = TestPoller::new();
+
+    for _ in 0..2 {
+        poller.poll_and_send(&db_conn);
+    }
+
+    assert_eq!(
+        RecordingNotifier::dms(UserId(303)),
+        vec!["New turn in notified! You are Ulm and you have 0h 0m remaining for turn 32."]
+    );
+    assert_eq!(
+        RecordingNotifier::channel_messages(ChannelId(303)),
+        vec!["Turn 32 has begun in notified! 1 nations still need to play."]
+    );
+}
+
+#[test]
+fn should_treat_turns_replayed_after_a_rollback_as_new() {
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.rollback:1234";
+    let game_data_for_turn =
+        |turn| ulm_game_data("rolledback", turn, SubmissionStatus::NotSubmitted);
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![
+            game_data_for_turn(40),
+            game_data_for_turn(35),
+            game_data_for_turn(36),
+        ],
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("rolledback", ADDRESS, 39, &[(306, 7)]);
+    let mut poller = TestPoller::new();
+    let mut poll = || poller.poll_dms(&db_conn, UserId(306));
+
+    assert_eq!(poll().len(), 1);
+    // going back isn't a new turn...
+    assert!(poll().is_empty());
+    let last_seen_turn = match db_conn.game_for_alias("rolledback").unwrap().state {
+        GameServerState::StartedState(started_state, _) => started_state.last_seen_turn,
+        _ => panic!("rolledback should have started"),
+    };
+    assert_eq!(last_seen_turn, 35);
+    // ...but hosting again from there is, even though it's still behind turn 40
+    assert_eq!(
+        poll(),
+        vec!["New turn in rolledback! You are Ulm and you have 0h 0m remaining for turn 36."]
+    );
+    assert!(rollback_announcement("rolledback", 40, 35).contains("from turn 40 to turn 35"));
+}
+
+#[test]
+fn should_resume_from_the_last_poll_without_announcing_it_again() {
+    use crate::model::{GameData, Nation};
+    use crate::server::test_helpers::RecordingServerConnection;
+    use crate::snek::{SnekGameStatus, SnekNation};
+
+    const ADDRESS: &str = "recording.test.restart:1234";
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![GameData {
+            game_name: "restarted".to_owned(),
+            nations: vec![Nation {
+                id: 7,
+                status: NationStatus::Human,
+                submitted: SubmissionStatus::PartiallySubmitted,
+                connected: true,
+                name: "Ulm".to_owned(),
+                era: "EA".to_owned(),
+            }],
+            turn: 31,
+            turn_timer: 0,
+        }],
+    );
+    let mut snek_nations = HashMap::new();
+    snek_nations.insert(
+        7,
+        SnekNation {
+            nation_id: 7,
+            name: "Modded Ulm".to_owned(),
+        },
+    );
+    RecordingServerConnection::script_snek_data(
+        ADDRESS,
+        SnekGameStatus {
+            nations: snek_nations,
+            option_game_id: Some(78),
+        },
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("restarted", ADDRESS, 30, &[(305, 7)]);
+    db_conn
+        .set_channel_for_alias("restarted", ChannelId(305))
+        .unwrap();
+    let mut poller = TestPoller::new();
+
+    poller.poll(&db_conn);
+    let option_polled = poller.last_polls.get("restarted").cloned();
+    assert!(option_polled.is_some());
+
+    // the bot comes back up with nothing in memory
+    let mut poller = TestPoller::new();
+    warm_cache(
+        &db_conn,
+        &poller.game_cache,
+        &mut poller.last_polls,
+        Utc::now(),
+    )
+    .unwrap();
+    assert_eq!(poller.last_polls.get("restarted").cloned(), option_polled);
+    assert_eq!(
+        poller
+            .game_cache
+            .get_poll("restarted")
+            .and_then(|cached_poll| cached_poll.option_cache_entry),
+        option_polled
+    );
+
+    let (new_turn_nations, channel_notifications) = poller.poll(&db_conn);
+    assert!(new_turn_nations.is_empty());
+    assert!(channel_notifications.is_empty());
+
+    // a poll from long enough ago isn't worth resuming from
+    let mut poller = TestPoller::new();
+    warm_cache(
+        &db_conn,
+        &poller.game_cache,
+        &mut poller.last_polls,
+        Utc::now() + Duration::minutes(DOWNTIME_THRESHOLD_MINS + 1),
+    )
+    .unwrap();
+    assert!(poller.last_polls.get("restarted").is_none());
+}
+
+#[test]
+fn should_only_announce_new_turns_where_the_game_routes_them() {
+    use crate::model::NotificationRoutes;
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.routed:1234";
+    let game_data_for_turn = |turn| ulm_game_data("routed", turn, SubmissionStatus::NotSubmitted);
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![game_data_for_turn(31), game_data_for_turn(32)],
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("routed", ADDRESS, 31, &[(304, 7)]);
+    db_conn
+        .set_channel_for_alias("routed", ChannelId(304))
+        .unwrap();
+    db_conn
+        .set_notification_routes_for_alias(
+            "routed",
+            &NotificationRoutes {
+                new_turn: NotificationRoute::Channel,
+                ..NotificationRoutes::default()
+            },
+        )
+        .unwrap();
+    let mut poller = TestPoller::new();
+
+    for _ in 0..2 {
+        poller.poll_and_send(&db_conn);
+    }
+
+    // the game asked for the channel only, so no DMs
+    assert!(RecordingNotifier::dms(UserId(304)).is_empty());
+    assert_eq!(
+        RecordingNotifier::channel_messages(ChannelId(304)),
+        vec!["Turn 32 has begun in routed! 1 nations still need to play."]
+    );
+}
+
+#[test]
+fn should_confirm_each_submission_once_where_the_server_asked_for_it() {
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.confirmations:1234";
+    let game_data = |submitted| ulm_game_data("confirmed", 31, submitted);
+    // submitted, then rehosted and submitted again
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![
+            game_data(SubmissionStatus::NotSubmitted),
+            game_data(SubmissionStatus::Submitted),
+            game_data(SubmissionStatus::NotSubmitted),
+            game_data(SubmissionStatus::Submitted),
+        ],
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("confirmed", ADDRESS, 31, &[(339, 7)]);
+    db_conn
+        .set_channel_for_alias("confirmed", ChannelId(339))
+        .unwrap();
+    db_conn
+        .subscribe_channel("confirmed", ChannelId(3391))
+        .unwrap();
+    // the recording notifier puts each channel in a server with the same ID
+    db_conn
+        .set_submission_confirmations_for_guild(GuildId(339), true)
+        .unwrap();
+    let mut poller = TestPoller::new();
+
+    for _ in 0..4 {
+        poller.poll_and_send(&db_conn);
+    }
+
+    assert_eq!(
+        RecordingNotifier::channel_messages(ChannelId(339)),
+        vec!["✅ Ulm has played turn 31 in confirmed"]
+    );
+    assert!(RecordingNotifier::channel_messages(ChannelId(3391)).is_empty());
+}
+
+#[test]
+fn should_not_confirm_nations_caught_out_by_the_host() {
+    let hosted_details = registered_playing_details(13, &[(1, SubmissionStatus::NotSubmitted)]);
+    assert!(submission_confirmations("hosted", &hosted_details, &[(12, 1)]).is_empty());
+    assert_eq!(
+        submission_confirmations("hosted", &hosted_details, &[(13, 1)]),
+        vec!["✅ nation 1 has played turn 13 in hosted"]
+    );
+}
+
+#[test]
+fn should_only_confirm_players_with_all_their_nations_in() {
+    let mut details = registered_playing_details(
+        13,
+        &[
+            (1, SubmissionStatus::Submitted),
+            (2, SubmissionStatus::NotSubmitted),
+            (3, SubmissionStatus::Submitted),
+        ],
+    );
+    // player 1 plays nations 1 and 2
+    if let NationDetails::Started(StartedDetails {
+        state: StartedStateDetails::Playing(playing_state),
+        ..
+    }) = &mut details.nations
+    {
+        if let PotentialPlayer::RegisteredAndGame(user_id, _) = &mut playing_state.players[1] {
+            *user_id = UserId(1);
+        }
+    }
+    assert_eq!(
+        submission_confirmations("multi", &details, &[(13, 1), (13, 3)]),
+        vec!["✅ nation 3 has played turn 13 in multi"]
+    );
+    assert_eq!(
+        submission_confirmations("multi", &details, &[(13, 1), (13, 2)]),
+        vec!["✅ nation 1 and nation 2 have played turn 13 in multi"]
+    );
+}
+
+#[test]
+fn should_see_submissions_between_polls_and_at_host() {
+    let old_details = registered_playing_details(
+        12,
+        &[
+            (1, SubmissionStatus::NotSubmitted),
+            (2, SubmissionStatus::NotSubmitted),
+            (3, SubmissionStatus::Submitted),
+        ],
+    );
+    let new_details = registered_playing_details(
+        12,
+        &[
+            (1, SubmissionStatus::Submitted),
+            (2, SubmissionStatus::PartiallySubmitted),
+            (3, SubmissionStatus::Submitted),
+        ],
+    );
+    assert_eq!(submissions_seen(&old_details, &new_details), vec![(12, 1)]);
+
+    // 2 was still going when the turn hosted
+    let hosted_details = registered_playing_details(
+        13,
+        &[
+            (1, SubmissionStatus::NotSubmitted),
+            (2, SubmissionStatus::NotSubmitted),
+            (3, SubmissionStatus::NotSubmitted),
+        ],
+    );
+    assert_eq!(
+        submissions_seen(&new_details, &hosted_details),
+        vec![(12, 2)]
+    );
+
+    // we missed a turn, so don't know who was last
+    let later_details = registered_playing_details(15, &[(1, SubmissionStatus::NotSubmitted)]);
+    assert!(submissions_seen(&hosted_details, &later_details).is_empty());
+}
+
+#[test]
+fn should_snapshot_who_was_in_when_the_turn_hosted() {
+    let old_details = registered_playing_details(
+        12,
+        &[
+            (1, SubmissionStatus::Submitted),
+            (2, SubmissionStatus::PartiallySubmitted),
+        ],
+    );
+    let hosted_details = registered_playing_details(13, &[(1, SubmissionStatus::NotSubmitted)]);
+    assert_eq!(
+        final_submissions(&old_details, &hosted_details),
+        vec![(12, UserId(1), 1, true), (12, UserId(2), 2, false)]
+    );
+    // nothing has hosted yet
+    assert!(final_submissions(&old_details, &old_details).is_empty());
+    // and we missed a turn, so don't know how it ended
+    let later_details = registered_playing_details(15, &[(1, SubmissionStatus::NotSubmitted)]);
+    assert!(final_submissions(&hosted_details, &later_details).is_empty());
+}
+
+#[test]
+fn should_only_poll_games_when_they_are_due() {
+    use crate::model::GameData;
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.poll.interval:1234";
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![GameData {
+            game_name: "interval".to_owned(),
+            nations: vec![],
+            turn: 31,
+            turn_timer: 0,
+        }],
+    );
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("interval", ADDRESS, 31, &[]);
+    db_conn
+        .set_poll_interval_secs_for_alias("interval", Some(120))
+        .unwrap();
+    let mut poller = TestPoller::new();
+
+    let started = Utc::now();
+    for &secs in &[0, 60, 119, 120] {
+        poller.poll_due(&db_conn, started + Duration::seconds(secs));
+    }
+    assert_eq!(RecordingServerConnection::game_data_calls(ADDRESS), 2);
+    // everything else uses the default of every minute, so was last polled at 120s
+    assert!(!poller
+        .schedule
+        .is_due("test server 1", started + Duration::seconds(179)));
+    assert!(poller
+        .schedule
+        .is_due("test server 1", started + Duration::seconds(180)));
+}
+
+#[test]
+fn should_not_poll_archived_games() {
+    use crate::model::StartedState;
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.poll.archived:1234";
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "archived".to_owned(),
+            state: GameServerState::Archived(
+                StartedState {
+                    address: ADDRESS.to_owned(),
+                    last_seen_turn: 31,
+                    option_snek_game_id: None,
+                },
+                None,
+            ),
+        })
+        .unwrap();
+    let mut poller = TestPoller::new();
+
+    poller.poll(&db_conn);
+    assert_eq!(RecordingServerConnection::game_data_calls(ADDRESS), 0);
+    assert!(poller.game_cache.get_poll("archived").is_none());
+}
+
+#[test]
+fn should_not_poll_paused_games_until_the_pause_ends() {
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.poll.paused:1234";
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("paused", ADDRESS, 31, &[]);
+    let started = Utc::now();
+    db_conn
+        .set_polling_paused_for_alias("paused", true, Some(started + Duration::hours(1)))
+        .unwrap();
+    let mut poller = TestPoller::new();
+
+    for &mins in &[0, 30, 60] {
+        poller.poll_due(&db_conn, started + Duration::minutes(mins));
+    }
+    assert_eq!(RecordingServerConnection::game_data_calls(ADDRESS), 1);
+}
+
+#[test]
+fn should_spot_nations_defeated_since_last_poll() {
+    let set_status = |details: &mut GameDetails, nation_id: u32, status: NationStatus| {
+        if let NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) = &mut details.nations
+        {
+            for player in playing_state.players.iter_mut() {
+                if let PotentialPlayer::RegisteredAndGame(_, player_details) = player {
+                    if player_details.nation_id == nation_id {
+                        player_details.player_status = status;
+                    }
+                }
+            }
+        }
+    };
+    let submissions = [
+        (1, SubmissionStatus::NotSubmitted),
+        (2, SubmissionStatus::NotSubmitted),
+        (3, SubmissionStatus::NotSubmitted),
+    ];
+    let mut old_details = registered_playing_details(20, &submissions);
+    set_status(&mut old_details, 3, NationStatus::Defeated);
+    let mut new_details = registered_playing_details(21, &submissions);
+    set_status(&mut new_details, 2, NationStatus::DefeatedThisTurn);
+    set_status(&mut new_details, 3, NationStatus::Defeated);
+
+    assert_eq!(
+        nations_defeated(&old_details, &new_details),
+        vec![(21, 2, "nation 2".to_owned())]
+    );
+    assert!(nations_defeated(&new_details, &new_details).is_empty());
+}
+
+#[test]
+fn should_queue_status_message_updates_on_new_turns() {
+    use crate::server::test_helpers::RecordingServerConnection;
+    use serenity::model::id::MessageId;
+
+    const ADDRESS: &str = "recording.test.status:1234";
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![ulm_game_data("statusful", 12, SubmissionStatus::Submitted)],
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("statusful", ADDRESS, 11, &[]);
+    db_conn
+        .set_status_message("statusful", ChannelId(3523), MessageId(3))
+        .unwrap();
+    let mut poller = TestPoller::new();
+
+    poller.poll(&db_conn);
+    let pending = db_conn.pending_status_messages().unwrap();
+    assert_eq!(pending.len(), 1);
+    let (alias, channel_id, message_id, text) = &pending[0];
+    assert_eq!(
+        (alias.as_str(), *channel_id, *message_id),
+        ("statusful", ChannelId(3523), MessageId(3))
+    );
+    assert!(text.starts_with("statusful: turn 12 (0h 0m), 0 undone\n`✓` Ulm (7)\n"));
+
+    // the same turn again is nothing new
+    db_conn
+        .set_status_message("statusful", ChannelId(3523), MessageId(3))
+        .unwrap();
+    poller.poll(&db_conn);
+    assert!(db_conn.pending_status_messages().unwrap().is_empty());
+}
+
+#[test]
+fn should_record_nation_statuses_each_turn() {
+    use crate::model::{GameData, Nation};
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.changes:1234";
+    let nation = |id: u32, name: &str, status: NationStatus| Nation {
+        id,
+        status,
+        submitted: SubmissionStatus::NotSubmitted,
+        connected: false,
+        name: name.to_owned(),
+        era: "EA".to_owned(),
+    };
+    let game_data = |turn: i32, nations: Vec<Nation>| GameData {
+        game_name: "changeful".to_owned(),
+        nations,
+        turn,
+        turn_timer: 0,
+    };
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![
+            game_data(
+                12,
+                vec![
+                    nation(5, "Arcoscephale", NationStatus::Human),
+                    nation(7, "Ulm", NationStatus::Human),
+                ],
+            ),
+            game_data(
+                13,
+                vec![
+                    nation(5, "Arcoscephale", NationStatus::AI),
+                    nation(7, "Ulm", NationStatus::DefeatedThisTurn),
+                ],
+            ),
+        ],
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("changeful", ADDRESS, 11, &[]);
+    db_conn
+        .add_nation_watch("changeful", UserId(3561), 5)
+        .unwrap();
+    let mut poller = TestPoller::new();
+    let mut watch_dms = vec![];
+    for _ in 0..2 {
+        watch_dms.extend(poller.poll_dms(&db_conn, UserId(3561)));
+    }
+    assert_eq!(
+        watch_dms,
+        vec!["Arcoscephale (5) in changeful has gone AI as of turn 13 (was Human)".to_owned()]
+    );
+
+    assert_eq!(
+        db_conn.nation_status_turns("changeful").unwrap(),
+        vec![12, 13]
+    );
+    assert_eq!(
+        db_conn.nation_statuses("changeful", 13).unwrap(),
+        vec![
+            (5, "Arcoscephale".to_owned(), NationStatus::AI),
+            (7, "Ulm".to_owned(), NationStatus::DefeatedThisTurn),
+        ]
+    );
+}
+
+#[test]
+fn should_send_new_turns_to_the_games_webhook() {
+    use crate::server::test_helpers::RecordingServerConnection;
+
+    const ADDRESS: &str = "recording.test.webhook:1234";
+    const URL: &str = "https://example.com/hooks/webhooked";
+    let game_data_for_turn =
+        |turn| ulm_game_data("webhooked", turn, SubmissionStatus::NotSubmitted);
+    RecordingServerConnection::script_game_data(
+        ADDRESS,
+        vec![
+            game_data_for_turn(31),
+            game_data_for_turn(31),
+            game_data_for_turn(32),
+        ],
+    );
+
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("webhooked", ADDRESS, 31, &[]);
+    db_conn
+        .set_webhook_url_for_alias("webhooked", Some(URL))
+        .unwrap();
+    let mut poller = TestPoller::new();
+
+    for _ in 0..3 {
+        poller.poll(&db_conn);
+    }
+
+    // Only the turn that changed, not the ones it was already on
+    assert_eq!(
+        RecordingNotifier::webhooks(URL),
+        vec![serde_json::json!({
+            "event": "new_turn",
+            "alias": "webhooked",
+            "turn": 32,
+            "undone": 1,
+            "deadline": null,
+        })]
+    );
+}
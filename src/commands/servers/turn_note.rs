@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::describe::sanitise_mentions;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::{GameServerState, TurnNote};
+
+#[cfg(test)]
+mod tests;
+
+/// Long enough for a sentence or two of context, short enough for `!history`
+const MAX_NOTE_LENGTH: usize = 200;
+/// Older notes are still kept, just not shown by `!turnnotes`
+const MAX_NOTES_SHOWN: usize = 20;
+
+pub fn show_turn_note(turn_note: &TurnNote) -> String {
+    format!(
+        "Turn {} ({}): {}",
+        turn_note.turn,
+        turn_note.created_at.format("%d %b %H:%M UTC"),
+        turn_note.note
+    )
+}
+
+fn turn_note_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    option_turn: Option<i32>,
+    note: &str,
+    now: DateTime<Utc>,
+) -> Result<String, CommandError> {
+    // Games added with !add have nobody in charge of them, so anyone can
+    let (last_seen_turn, option_owner) = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(_) => {
+            return Err(CommandError::from(format!(
+                "{} hasn't started yet, so it has no turns to add notes to",
+                alias
+            )))
+        }
+        GameServerState::StartedState(started_state, option_lobby_state)
+        | GameServerState::Archived(started_state, option_lobby_state) => (
+            started_state.last_seen_turn,
+            option_lobby_state.map(|lobby_state| lobby_state.owner),
+        ),
+    };
+    if option_owner.is_some_and(|owner| owner != author_id) {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} can do this",
+            alias
+        )));
+    }
+
+    let turn = option_turn.unwrap_or(last_seen_turn);
+    if turn <= 0 || turn > last_seen_turn {
+        return Err(CommandError::from(format!(
+            "{} has only got to turn {}",
+            alias, last_seen_turn
+        )));
+    }
+    let note = sanitise_mentions(note.trim());
+    if note.is_empty() {
+        return Err(CommandError::from("The note can't be empty"));
+    }
+    if note.chars().count() > MAX_NOTE_LENGTH {
+        return Err(CommandError::from(format!(
+            "Notes can be at most {} characters",
+            MAX_NOTE_LENGTH
+        )));
+    }
+    db_conn.add_turn_note(alias, turn, author_id, &note, now)?;
+    Ok(format!("Added a note to turn {} of {}", turn, alias))
+}
+
+fn turn_notes_helper(db_conn: &DbConnection, alias: &str) -> Result<String, CommandError> {
+    // Checked first so that a typo doesn't look like a game without notes
+    db_conn.game_for_alias(alias)?;
+    let turn_notes = db_conn.turn_notes_for_alias(alias)?;
+    if turn_notes.is_empty() {
+        return Ok(format!(
+            "No notes for {} yet, its owner can add them with !turnnote",
+            alias
+        ));
+    }
+    let shown = &turn_notes[turn_notes.len().saturating_sub(MAX_NOTES_SHOWN)..];
+    let mut text = format!("Notes for {}:\n", alias);
+    for turn_note in shown {
+        text.push_str(&show_turn_note(turn_note));
+        text.push('\n');
+    }
+    if shown.len() < turn_notes.len() {
+        text.push_str(&format!("(and {} older)", turn_notes.len() - shown.len()));
+    }
+    Ok(text)
+}
+
+pub fn turn_note(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let option_turn = match args.single_quoted_n::<i32>() {
+        Ok(turn) => {
+            args.skip();
+            Some(turn)
+        }
+        Err(_) => None,
+    };
+    let note = args.single_quoted::<String>().map_err(|_| {
+        CommandError::from("Expected a note in quotes, e.g. !turnnote \"extended for the holiday\"")
+    })?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: the note needs to be in quotes",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = turn_note_helper(
+        db_conn,
+        message.author.id,
+        &alias,
+        option_turn,
+        &note,
+        Utc::now(),
+    )?;
+    info!("turn_note: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
+
+pub fn turn_notes(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = turn_notes_helper(db_conn, &alias)?;
+    info!("turn_notes: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use super::*;
+
+use chrono::TimeZone;
+
+use crate::model::enums::Era;
+use crate::model::{GameServer, LobbyState};
+
+fn noon(day: u32) -> DateTime<Utc> {
+    Utc.ymd(2020, 10, day).and_hms(12, 0, 0)
+}
+
+#[test]
+fn should_keep_every_note_for_a_turn_in_order() {
+    let db_conn = DbConnection::test();
+    // "test server 1" was added without a lobby on turn 31, so has no owner
+    let text = turn_note_helper(
+        &db_conn,
+        UserId(327),
+        "test server 1",
+        None,
+        "extended for the holiday",
+        noon(14),
+    )
+    .unwrap();
+    assert_eq!(text, "Added a note to turn 31 of test server 1");
+    turn_note_helper(
+        &db_conn,
+        UserId(328),
+        "test server 1",
+        Some(30),
+        "rolled back after a crash @everyone",
+        noon(15),
+    )
+    .unwrap();
+    turn_note_helper(
+        &db_conn,
+        UserId(327),
+        "test server 1",
+        Some(31),
+        "and again",
+        noon(16),
+    )
+    .unwrap();
+
+    let turn_notes = db_conn.turn_notes_for_alias("test server 1").unwrap();
+    let turns: Vec<i32> = turn_notes.iter().map(|turn_note| turn_note.turn).collect();
+    assert_eq!(turns, vec![30, 31, 31]);
+    assert_eq!(turn_notes[0].author, UserId(328));
+    assert!(!turn_notes[0].note.contains("@everyone"));
+    assert_eq!(
+        show_turn_note(&turn_notes[1]),
+        "Turn 31 (14 Oct 12:00 UTC): extended for the holiday"
+    );
+    assert!(db_conn
+        .turn_notes_for_alias("test server 2")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_only_let_the_owner_note_turns_that_have_happened() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(1),
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+    assert!(turn_note_helper(&db_conn, UserId(1), "lobby", None, "hi", noon(14)).is_err());
+    assert!(turn_note_helper(
+        &db_conn,
+        UserId(1),
+        "test server 1",
+        Some(32),
+        "hi",
+        noon(14)
+    )
+    .is_err());
+    assert!(turn_note_helper(&db_conn, UserId(1), "test server 1", None, "  ", noon(14)).is_err());
+    let long = "a".repeat(MAX_NOTE_LENGTH + 1);
+    assert!(turn_note_helper(&db_conn, UserId(1), "test server 1", None, &long, noon(14)).is_err());
+    assert!(turn_note_helper(&db_conn, UserId(1), "not a game", None, "hi", noon(14)).is_err());
+    assert!(turn_notes_helper(&db_conn, "not a game").is_err());
+    assert_eq!(
+        turn_notes_helper(&db_conn, "test server 1").unwrap(),
+        "No notes for test server 1 yet, its owner can add them with !turnnote"
+    );
+}
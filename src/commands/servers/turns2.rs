@@ -13,7 +13,7 @@ use crate::server::ServerConnection;
 fn turns_helper<C: ServerConnection>(
     user_id: UserId,
     db_conn: &DbConnection,
-    read_handle: &crate::CacheReadHandle,
+    game_cache: &GameCache,
 ) -> Result<String, CommandError> {
     debug!("Starting !turns");
     let servers_and_nations_for_player = db_conn.servers_for_player(user_id)?;
@@ -21,9 +21,9 @@ fn turns_helper<C: ServerConnection>(
     let mut text = "Your turns:\n".to_string();
     for (server, nation_id) in servers_and_nations_for_player {
         if let GameServerState::StartedState(started_state, option_lobby_state) = server.state {
-            let option_option_game_details = read_handle.get_clone(&server.alias);
+            let option_option_game_details = game_cache.get_latest(&server.alias);
             match option_option_game_details {
-                Some(Some(cache)) => {
+                Some(Ok(cache)) => {
                     let details: GameDetails = started_details_from_server(
                         db_conn,
                         &started_state,
@@ -31,6 +31,7 @@ fn turns_helper<C: ServerConnection>(
                         &server.alias,
                         cache.game_data,
                         cache.option_snek_state,
+                        cache.fetched_at,
                     )
                     .unwrap();
 
@@ -58,8 +59,8 @@ fn turns_helper<C: ServerConnection>(
                         NationDetails::Lobby(_) => continue,
                     }
                 }
-                Some(None) => {
-                    text.push_str(&format!("{}: Cannot connect to server!\n", server.alias));
+                Some(Err(e)) => {
+                    text.push_str(&format!("{}: {}\n", server.alias, e));
                 }
                 None => {
                     text.push_str(&format!(
@@ -157,11 +158,10 @@ fn turns_for_playing_state(
                 {
                     if potential_player_details.player_status.is_human() {
                         let turn_str = format!(
-                            "{} turn {} ({}h {}m): {} ({}) (submitted: {}, {}/{})\n",
+                            "{} turn {} ({}): {} ({}) (submitted: {}, {}/{})\n",
                             alias,
                             playing_state.turn,
-                            playing_state.hours_remaining,
-                            playing_state.mins_remaining,
+                            playing_state.show_time_remaining(),
                             potential_player_details.nation_name,
                             potential_player_details.nation_id,
                             potential_player_details.submitted.show(),
@@ -211,10 +211,10 @@ pub fn turns2<C: ServerConnection>(
     let db_conn = data
         .get::<DbConnectionKey>()
         .ok_or_else(|| CommandError("No db connection".to_string()))?;
-    let read_handle = data
-        .get::<crate::DetailsReadHandleKey>()
-        .ok_or("No ReadHandle was created on startup. This is a bug.")?;
-    let text = turns_helper::<C>(message.author.id, db_conn, read_handle)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = turns_helper::<C>(message.author.id, db_conn, game_cache)?;
     info!("turns: replying with: {}", text);
     let private_channel = message.author.id.create_dm_channel()?;
     private_channel.say(&text)?;
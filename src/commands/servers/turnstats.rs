@@ -0,0 +1,135 @@
+use chrono::{DateTime, Duration, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use std::collections::HashMap;
+
+use super::alias_from_arg_or_channel_name;
+use super::history::{hours, turn_intervals};
+use crate::db::{DbConnection, DbConnectionKey, Downtime};
+
+#[cfg(test)]
+mod tests;
+
+/// Enough to cover every turn of any real game
+const ALL_TURNS: u32 = 100_000;
+/// Hosting isn't instant, so a turn this close to its timer still counts as on time
+const ON_TIME_GRACE_MINS: i64 = 5;
+
+#[derive(PartialEq, Debug)]
+pub struct TurnStats {
+    pub turns_counted: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub longest_turn: i32,
+    pub longest: Duration,
+    /// How many of the most recent turns in a row hosted when their timer ran out
+    pub on_time_streak: u32,
+}
+
+/// Like `turn_durations`, but also skips turns that the poller was down for some
+/// of, since we may have only noticed the next turn once we came back up
+pub fn turn_durations_while_watching(
+    history: &[(i32, DateTime<Utc>)],
+    downtimes: &[Downtime],
+) -> Vec<(i32, Duration)> {
+    turn_intervals(history)
+        .into_iter()
+        .filter(|&(_, started, ended)| {
+            !downtimes
+                .iter()
+                .any(|&(down_at, up_at)| down_at < ended && up_at > started)
+        })
+        .map(|(turn, started, ended)| (turn, ended.signed_duration_since(started)))
+        .collect()
+}
+
+/// `timers` is how long each turn's timer was in seconds. Turns without one
+/// neither add to nor break the on time streak.
+pub fn turn_stats(durations: &[(i32, Duration)], timers: &HashMap<i32, i32>) -> Option<TurnStats> {
+    let &(longest_turn, longest) = durations.iter().max_by_key(|&&(_, duration)| duration)?;
+
+    let total_secs: i64 = durations
+        .iter()
+        .map(|&(_, duration)| duration.num_seconds())
+        .sum();
+    let mean = Duration::seconds(total_secs / durations.len() as i64);
+
+    let mut sorted: Vec<Duration> = durations.iter().map(|&(_, duration)| duration).collect();
+    sorted.sort();
+    // the middle one, or the average of the middle two
+    let median = (sorted[(sorted.len() - 1) / 2] + sorted[sorted.len() / 2]) / 2;
+
+    let mut on_time_streak = 0;
+    for &(turn, duration) in durations.iter().rev() {
+        if let Some(&timer_secs) = timers.get(&turn) {
+            let allowed =
+                Duration::seconds(i64::from(timer_secs)) + Duration::minutes(ON_TIME_GRACE_MINS);
+            if duration <= allowed {
+                on_time_streak += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    Some(TurnStats {
+        turns_counted: durations.len(),
+        mean,
+        median,
+        longest_turn,
+        longest,
+        on_time_streak,
+    })
+}
+
+fn turnstats_helper(db_conn: &DbConnection, alias: &str) -> Result<String, CommandError> {
+    let history = db_conn.select_turn_history(alias, ALL_TURNS)?;
+    let downtimes = db_conn.select_downtimes()?;
+    let timers = db_conn.select_turn_timers(alias)?;
+    let durations = turn_durations_while_watching(&history, &downtimes);
+
+    match turn_stats(&durations, &timers) {
+        None => Ok(format!(
+            "No turn history for {} yet, check back after a couple of turns have hosted",
+            alias
+        )),
+        Some(stats) => Ok(format!(
+            "Turn stats for {} (from {} turns):\n\
+             Average: {:.1}h\n\
+             Median: {:.1}h\n\
+             Longest: {:.1}h (turn {})\n\
+             On time streak: {} turns",
+            alias,
+            stats.turns_counted,
+            hours(stats.mean),
+            hours(stats.median),
+            hours(stats.longest),
+            stats.longest_turn,
+            stats.on_time_streak,
+        )),
+    }
+}
+
+pub fn turnstats(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = turnstats_helper(db_conn, &alias)?;
+    info!("turnstats: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+use chrono::TimeZone;
+
+fn hour(h: i64) -> DateTime<Utc> {
+    Utc.timestamp(h * 3600, 0)
+}
+
+#[test]
+fn should_skip_turns_that_span_downtime() {
+    let history = vec![(1, hour(0)), (2, hour(10)), (3, hour(60)), (4, hour(70))];
+    // down for most of turn 2, so we might have only seen turn 3 when we came back
+    let downtimes = vec![(hour(12), hour(59))];
+
+    let durations = turn_durations_while_watching(&history, &downtimes);
+    assert_eq!(
+        durations,
+        vec![(1, Duration::hours(10)), (3, Duration::hours(10))]
+    );
+}
+
+#[test]
+fn should_compute_turn_stats() {
+    let durations = vec![
+        (1, Duration::hours(10)),
+        (2, Duration::hours(30)),
+        (3, Duration::hours(20)),
+        (4, Duration::hours(24)),
+        (5, Duration::hours(23)),
+    ];
+    let mut timers = HashMap::new();
+    timers.insert(2, 24 * 3600);
+    timers.insert(4, 24 * 3600);
+    timers.insert(5, 24 * 3600);
+    // turn 3 had no timer so doesn't count either way
+
+    let stats = turn_stats(&durations, &timers).unwrap();
+    assert_eq!(
+        stats,
+        TurnStats {
+            turns_counted: 5,
+            mean: Duration::hours(107) / 5,
+            median: Duration::hours(23),
+            longest_turn: 2,
+            longest: Duration::hours(30),
+            on_time_streak: 2,
+        }
+    );
+}
+
+#[test]
+fn should_have_no_stats_without_turns() {
+    assert_eq!(turn_stats(&[], &HashMap::new()), None);
+}
+
+#[test]
+fn should_record_downtime_when_heartbeats_are_far_apart() {
+    let db_conn = DbConnection::test();
+    let threshold = Duration::minutes(30);
+    db_conn.record_poller_heartbeat(hour(0), threshold).unwrap();
+    db_conn
+        .record_poller_heartbeat(hour(0) + Duration::minutes(1), threshold)
+        .unwrap();
+    db_conn.record_poller_heartbeat(hour(5), threshold).unwrap();
+
+    assert_eq!(
+        db_conn.select_downtimes().unwrap(),
+        vec![(hour(0) + Duration::minutes(1), hour(5))]
+    );
+}
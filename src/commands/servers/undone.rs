@@ -0,0 +1,136 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::model::misc::Mentionable;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::time_format::time_format_for_message;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::SubmissionStatus;
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+/// Registered humans who haven't touched their turn yet. Unregistered nations
+/// are left out since there's nobody to ping.
+pub fn undone_players(playing_state: &PlayingState) -> Vec<(UserId, &PlayerDetails)> {
+    playing_state
+        .players
+        .iter()
+        .filter_map(|potential_player| match potential_player {
+            PotentialPlayer::RegisteredAndGame(user_id, details)
+                if details.player_status.is_human()
+                    && details.submitted == SubmissionStatus::NotSubmitted =>
+            {
+                Some((*user_id, details))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The turn in progress as of the last poll, for commands about who still has
+/// to play it
+pub fn playing_state_for_alias(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+) -> Result<PlayingState, CommandError> {
+    let server = db_conn.game_for_alias(alias)?;
+    let (started_state, option_lobby_state) = match server.state {
+        GameServerState::Lobby(_) => {
+            return Err(CommandError::from(format!("{} has not started yet", alias)))
+        }
+        GameServerState::StartedState(started_state, option_lobby_state) => {
+            (started_state, option_lobby_state)
+        }
+        GameServerState::Archived(_, _) => {
+            return Err(CommandError::from(format!("{} has finished", alias)))
+        }
+    };
+    let cache = match game_cache.get_latest(alias) {
+        Some(Ok(cache)) => cache,
+        Some(Err(e)) => {
+            return Err(format!("Could not get details for {}: {}", alias, e).into());
+        }
+        None => return Err("Not yet got a response from server, try again in 1 min".into()),
+    };
+    let details = started_details_from_server(
+        db_conn,
+        &started_state,
+        option_lobby_state.as_ref(),
+        alias,
+        cache.game_data,
+        cache.option_snek_state,
+        cache.fetched_at,
+    )?;
+
+    match details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => Ok(playing_state),
+        _ => Err(CommandError::from(format!("{} is not in progress", alias))),
+    }
+}
+
+fn undone_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+    time_format: TimeFormat,
+) -> Result<String, CommandError> {
+    let playing_state = playing_state_for_alias(db_conn, game_cache, alias)?;
+    let undone = undone_players(&playing_state);
+    if undone.is_empty() {
+        return Ok(format!(
+            "Everybody registered in {} has played turn {}",
+            alias, playing_state.turn
+        ));
+    }
+    let mut text = format!(
+        "Still to play turn {} in {} ({}):\n",
+        playing_state.turn,
+        alias,
+        playing_state.show_time_left_as(time_format),
+    );
+    for (user_id, details) in undone {
+        text.push_str(&format!(
+            "{} ({})\n",
+            user_id.mention(),
+            details.nation_name
+        ));
+    }
+    Ok(text)
+}
+
+pub fn undone(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let time_format = time_format_for_message(db_conn, message)?;
+    let text = undone_helper(db_conn, game_cache, &alias, time_format)?;
+    info!("undone: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
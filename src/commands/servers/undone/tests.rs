@@ -0,0 +1,58 @@
+use super::*;
+
+use crate::model::enums::NationStatus;
+
+fn player_details(
+    nation_id: u32,
+    submitted: SubmissionStatus,
+    player_status: NationStatus,
+) -> PlayerDetails {
+    PlayerDetails {
+        nation_id,
+        nation_name: format!("nation {}", nation_id),
+        submitted,
+        player_status,
+        went_ai: false,
+        connected: false,
+    }
+}
+
+#[test]
+fn should_only_list_registered_humans_that_have_not_submitted() {
+    let playing_state = PlayingState {
+        players: vec![
+            PotentialPlayer::RegisteredAndGame(
+                UserId(1),
+                player_details(1, SubmissionStatus::NotSubmitted, NationStatus::Human),
+            ),
+            PotentialPlayer::RegisteredAndGame(
+                UserId(2),
+                player_details(2, SubmissionStatus::Submitted, NationStatus::Human),
+            ),
+            PotentialPlayer::RegisteredAndGame(
+                UserId(3),
+                player_details(3, SubmissionStatus::NotSubmitted, NationStatus::AI),
+            ),
+            PotentialPlayer::GameOnly(player_details(
+                4,
+                SubmissionStatus::NotSubmitted,
+                NationStatus::Human,
+            )),
+            PotentialPlayer::RegisteredOnly(UserId(5), 5, "nation 5".to_owned()),
+        ],
+        turn: 10,
+        mins_remaining: 0,
+        hours_remaining: 1,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: None,
+        paused: false,
+        blitz: false,
+    };
+
+    let undone: Vec<UserId> = undone_players(&playing_state)
+        .into_iter()
+        .map(|(user_id, _)| user_id)
+        .collect();
+    assert_eq!(undone, vec![UserId(1)]);
+}
@@ -5,21 +5,35 @@ use serenity::model::id::UserId;
 use serenity::prelude::Context;
 
 use super::alias_from_arg_or_channel_name;
+use super::roster::remove_from_lobby;
+use crate::commands::servers::GameCacheKey;
 use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
 
+#[cfg(test)]
+mod tests;
+
+/// Returns who got in off the waitlist, if the game is still a lobby
 fn unregister_player_helper(
     user_id: UserId,
     alias: &str,
     db_conn: &DbConnection,
-) -> Result<(), CommandError> {
+) -> Result<Vec<String>, CommandError> {
+    let not_in_game = || CommandError::from(format!("User is not in game {}", alias));
+    if let GameServerState::Lobby(lobby_state) = db_conn.game_for_alias(alias)?.state {
+        let removed =
+            remove_from_lobby(db_conn, &lobby_state, alias, user_id)?.ok_or_else(not_in_game)?;
+        return Ok(removed.promotions);
+    }
+
     let rows_affected = db_conn
         .remove_player_from_game(&alias, user_id)
         .map_err(CommandError::from)?;
 
     if rows_affected > 0 {
-        Ok(())
+        Ok(vec![])
     } else {
-        Err(format!("User is not in game {}", alias))?
+        Err(not_in_game())
     }
 }
 
@@ -31,12 +45,20 @@ pub fn unregister_player(
     let alias = alias_from_arg_or_channel_name(&mut args, &message)?;
     let data = context.data.lock();
     let db_conn = data.get::<DbConnectionKey>().ok_or("No db connection")?;
-    unregister_player_helper(message.author.id, &alias, db_conn)?;
+    let promotions = unregister_player_helper(message.author.id, &alias, db_conn)?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    game_cache.invalidate_lobby(&alias);
 
-    let text = format!(
+    let mut text = format!(
         "Removing user {} from all nations in game {}",
         message.author, alias
     );
+    for promotion in promotions {
+        text.push('\n');
+        text.push_str(&promotion);
+    }
     info!("{}", text);
     let _ = message.reply(&text);
     Ok(())
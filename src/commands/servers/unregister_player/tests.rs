@@ -0,0 +1,50 @@
+use super::*;
+
+use crate::commands::servers::roster::join_roster;
+use crate::model::enums::Era;
+use crate::model::RosterEntry;
+
+const OWNER: UserId = UserId(3540);
+
+#[test]
+fn should_give_a_rostered_players_slot_to_the_waitlist() {
+    let db_conn = DbConnection::test();
+    let lobby_state = db_conn.insert_test_lobby("lobby", OWNER, Era::Early, 1, &[(3541, 5)]);
+    db_conn
+        .set_roster_entry(
+            "lobby",
+            &RosterEntry {
+                user_id: UserId(3541),
+                waitlisted: false,
+                option_nation_id: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        join_roster(&db_conn, &lobby_state, "lobby", UserId(3542), 10).unwrap(),
+        Some(1)
+    );
+
+    let promotions = unregister_player_helper(UserId(3541), "lobby", &db_conn).unwrap();
+    assert_eq!(promotions.len(), 1);
+    assert!(promotions[0].contains("<@3542> is off the waitlist for lobby and registered as"));
+    let roster = db_conn.roster_for_alias("lobby").unwrap();
+    assert!(roster.iter().all(|entry| entry.user_id != UserId(3541)));
+    assert!(db_conn
+        .players_with_nations_for_game_alias("lobby")
+        .unwrap()
+        .iter()
+        .any(|(player, nation_id)| player.discord_user_id == UserId(3542) && *nation_id == 10));
+
+    assert!(unregister_player_helper(UserId(3541), "lobby", &db_conn).is_err());
+}
+
+#[test]
+fn should_unregister_from_started_games() {
+    let db_conn = DbConnection::test();
+    db_conn.insert_test_started_game("started", "started.test:1234", 3, &[(3543, 5)]);
+    assert!(unregister_player_helper(UserId(3543), "started", &db_conn)
+        .unwrap()
+        .is_empty());
+    assert!(unregister_player_helper(UserId(3543), "started", &db_conn).is_err());
+}
@@ -19,6 +19,11 @@ fn unstart_helper(db_conn: &DbConnection, alias: &str) -> Result<(), CommandErro
         GameServerState::Lobby(_) => {
             return Err(CommandError::from("cannot use this command on a lobby"))
         }
+        GameServerState::Archived(_, _) => {
+            return Err(CommandError::from(
+                "cannot use this command on a finished game, !unarchive it first",
+            ))
+        }
     }
     Ok(())
 }
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+
+use super::all::undone_count;
+use super::details2::split_message;
+use super::time_format::time_format_for_message;
+use super::undone::playing_state_for_alias;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+/// One game's line in `!urgent`, and the deadline it's sorted by
+pub struct UrgentGame {
+    /// `None` for lobbies, paused games and anything we haven't polled yet
+    pub option_deadline: Option<DateTime<Utc>>,
+    pub line: String,
+}
+
+/// Soonest deadline first. Games without one go at the bottom, in the order
+/// they came in.
+pub fn sort_by_urgency(games: &mut [UrgentGame]) {
+    games.sort_by_key(|game| (game.option_deadline.is_none(), game.option_deadline));
+}
+
+/// Only looks at what the poller last saw, so a big server doesn't set off a
+/// fetch for every game it has
+fn urgent_game(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+    time_format: TimeFormat,
+) -> Result<Option<UrgentGame>, CommandError> {
+    match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(_) => {
+            return Ok(Some(UrgentGame {
+                option_deadline: None,
+                line: format!("{}: lobby", alias),
+            }))
+        }
+        GameServerState::Archived(_, _) => return Ok(None),
+        GameServerState::StartedState(_, _) => (),
+    }
+    Ok(Some(
+        match playing_state_for_alias(db_conn, game_cache, alias) {
+            Ok(playing_state) => UrgentGame {
+                option_deadline: if playing_state.paused {
+                    None
+                } else {
+                    playing_state.option_deadline
+                },
+                line: format!(
+                    "{}: turn {} ({}), {} undone",
+                    alias,
+                    playing_state.turn,
+                    playing_state.show_time_remaining_as(time_format),
+                    undone_count(&playing_state)
+                ),
+            },
+            // One broken server shouldn't hide the rest
+            Err(e) => UrgentGame {
+                option_deadline: None,
+                line: format!("{}: {}", alias, e.0),
+            },
+        },
+    ))
+}
+
+fn urgent_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    aliases: &[String],
+    time_format: TimeFormat,
+) -> Result<String, CommandError> {
+    let mut games = vec![];
+    for alias in aliases {
+        if let Some(game) = urgent_game(db_conn, game_cache, alias, time_format)? {
+            games.push(game);
+        }
+    }
+    if games.is_empty() {
+        return Ok("No games post in this server".to_owned());
+    }
+    sort_by_urgency(&mut games);
+    let mut text = String::new();
+    for game in games {
+        text.push_str(&game.line);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+pub fn urgent(context: &mut Context, message: &Message, args: Args) -> Result<(), CommandError> {
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let channel_ids: Vec<_> = message
+        .guild()
+        .ok_or("!urgent lists the games in a server, it can't be used in DMs")?
+        .read()
+        .channels
+        .keys()
+        .cloned()
+        .collect();
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let mut aliases = vec![];
+    for channel_id in channel_ids {
+        aliases.extend(db_conn.aliases_for_channel(channel_id)?);
+    }
+    aliases.sort();
+    let time_format = time_format_for_message(db_conn, message)?;
+    let text = urgent_helper(db_conn, game_cache, &aliases, time_format)?;
+    info!("urgent: replying with: {}", text);
+    for part in split_message(&text) {
+        message.channel_id.say(&part)?;
+    }
+    Ok(())
+}
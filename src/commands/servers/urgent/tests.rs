@@ -0,0 +1,61 @@
+use super::*;
+
+use chrono::TimeZone;
+
+use crate::model::enums::Era;
+use crate::model::{GameServer, LobbyState};
+use serenity::model::id::UserId;
+
+fn game(option_deadline: Option<DateTime<Utc>>, line: &str) -> UrgentGame {
+    UrgentGame {
+        option_deadline,
+        line: line.to_owned(),
+    }
+}
+
+#[test]
+fn should_put_soonest_deadline_first_and_no_deadline_last() {
+    let hour = |hour| Utc.ymd(2020, 1, 1).and_hms(hour, 0, 0);
+    let mut games = vec![
+        game(None, "paused"),
+        game(Some(hour(20)), "later"),
+        game(None, "lobby"),
+        game(Some(hour(10)), "sooner"),
+    ];
+    sort_by_urgency(&mut games);
+    let lines: Vec<&str> = games.iter().map(|game| game.line.as_str()).collect();
+    assert_eq!(lines, vec!["sooner", "later", "paused", "lobby"]);
+}
+
+#[test]
+fn should_list_lobbies_and_unpolled_games_without_fetching() {
+    let db_conn = DbConnection::test();
+    db_conn
+        .insert_game_server(&GameServer {
+            alias: "lobby".to_owned(),
+            state: GameServerState::Lobby(LobbyState {
+                owner: UserId(1),
+                era: Era::Early,
+                player_count: 5,
+                description: None,
+            }),
+        })
+        .unwrap();
+    let game_cache = GameCache::test();
+
+    let text = urgent_helper(
+        &db_conn,
+        &game_cache,
+        &["lobby".to_owned(), "test server 1".to_owned()],
+        TimeFormat::Exact,
+    )
+    .unwrap();
+    assert_eq!(
+        text,
+        "lobby: lobby\ntest server 1: Not yet got a response from server, try again in 1 min\n"
+    );
+    assert_eq!(
+        urgent_helper(&db_conn, &game_cache, &[], TimeFormat::Exact).unwrap(),
+        "No games post in this server"
+    );
+}
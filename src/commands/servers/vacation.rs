@@ -0,0 +1,86 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use crate::db::{DbConnection, DbConnectionKey};
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of a date to come back early
+const CLEAR_FLAG: &str = "--clear";
+/// Anyone away for longer than this should probably find a sub instead
+const MAX_VACATION_DAYS: i64 = 60;
+
+/// The day they're back, e.g. "2020-10-20". Vacations end at the start of it, UTC.
+pub fn parse_vacation_end(arg: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, CommandError> {
+    let date = NaiveDate::parse_from_str(arg.trim(), "%Y-%m-%d").map_err(|_| {
+        CommandError::from(format!(
+            "Expected the date you're back like \"2020-10-20\", not \"{}\"",
+            arg
+        ))
+    })?;
+    let until = DateTime::<Utc>::from_utc(date.and_hms(0, 0, 0), Utc);
+    if until <= now {
+        return Err(CommandError::from("That date has already started"));
+    }
+    if until - now > Duration::days(MAX_VACATION_DAYS) {
+        return Err(CommandError::from(format!(
+            "Vacations can be at most {} days",
+            MAX_VACATION_DAYS
+        )));
+    }
+    Ok(until)
+}
+
+fn vacation_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    user_id: UserId,
+    arg: &str,
+    now: DateTime<Utc>,
+) -> Result<String, CommandError> {
+    if arg == CLEAR_FLAG {
+        db_conn.set_player_vacation(alias, user_id, None)?;
+        return Ok(format!(
+            "Welcome back! You'll get new turn DMs for {} again",
+            alias
+        ));
+    }
+    let until = parse_vacation_end(arg, now)?;
+    db_conn.set_player_vacation(alias, user_id, Some(until))?;
+    Ok(format!(
+        "No new turn DMs for {} until {}, enjoy your break",
+        alias,
+        until.format("%d %b %Y")
+    ))
+}
+
+pub fn vacation(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg = args.single_quoted::<String>().map_err(|_| {
+        CommandError::from("Expected the date you're back like \"2020-10-20\", or --clear")
+    })?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = vacation_helper(db_conn, &alias, message.author.id, &arg, Utc::now())?;
+    info!("vacation: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use super::*;
+
+use chrono::TimeZone;
+
+#[test]
+fn should_end_vacation_at_start_of_the_day_back() {
+    let now = Utc.ymd(2020, 10, 14).and_hms(18, 0, 0);
+    assert_eq!(
+        parse_vacation_end("2020-10-20", now).unwrap(),
+        Utc.ymd(2020, 10, 20).and_hms(0, 0, 0)
+    );
+    assert!(parse_vacation_end("2020-10-14", now).is_err());
+    assert!(parse_vacation_end("2021-10-14", now).is_err());
+    assert!(parse_vacation_end("next week", now).is_err());
+}
+
+#[test]
+fn should_only_list_current_vacations() {
+    let db_conn = DbConnection::test();
+    let now = Utc::now();
+    // UserId(101) is registered in "test server 1"
+    let until = (now + Duration::days(3)).format("%Y-%m-%d").to_string();
+    vacation_helper(&db_conn, "test server 1", UserId(101), &until, now).unwrap();
+
+    let vacations = db_conn
+        .player_vacations_for_alias("test server 1", now)
+        .unwrap();
+    assert!(vacations.contains_key(&UserId(101)));
+    assert!(db_conn
+        .player_vacations_for_alias("test server 1", now + Duration::days(4))
+        .unwrap()
+        .is_empty());
+
+    vacation_helper(&db_conn, "test server 1", UserId(101), CLEAR_FLAG, now).unwrap();
+    assert!(db_conn
+        .player_vacations_for_alias("test server 1", now)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn should_not_go_on_vacation_from_game_you_are_not_in() {
+    let db_conn = DbConnection::test();
+    let err = vacation_helper(
+        &db_conn,
+        "test server 2",
+        UserId(101),
+        CLEAR_FLAG,
+        Utc::now(),
+    )
+    .unwrap_err();
+    assert!(format!("{:?}", err).contains("not registered"));
+}
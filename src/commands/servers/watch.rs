@@ -0,0 +1,182 @@
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::changes::{status_transitions, RecordedStatus, StatusTransition};
+use super::turn_check::NewTurnNation;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::NationStatus;
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this before the nation to stop watching it
+const CLEAR_FLAG: &str = "--clear";
+
+fn describe_transition(alias: &str, turn: i32, transition: &StatusTransition) -> String {
+    let what = if transition.to.is_defeated() {
+        "has been eliminated".to_owned()
+    } else {
+        match transition.to {
+            NationStatus::AI => "has gone AI".to_owned(),
+            NationStatus::Human => "is being played by a human again".to_owned(),
+            status => format!("is now {}", status.show()),
+        }
+    };
+    format!(
+        "{} ({}) in {} {} as of turn {} (was {})",
+        transition.nation_name,
+        transition.nation_id,
+        alias,
+        what,
+        turn,
+        transition.from.show()
+    )
+}
+
+/// A DM for everyone watching a nation whose status changed since the last
+/// turn we recorded before `turn`
+pub fn watched_nation_dms(
+    db_conn: &DbConnection,
+    alias: &str,
+    turn: i32,
+    statuses: &[RecordedStatus],
+) -> Result<Vec<NewTurnNation>, CommandError> {
+    let watches = db_conn.nation_watches_for_alias(alias)?;
+    if watches.is_empty() {
+        return Ok(vec![]);
+    }
+    let option_previous_turn = db_conn
+        .nation_status_turns(alias)?
+        .into_iter()
+        .filter(|&recorded_turn| recorded_turn < turn)
+        .max();
+    let previous_turn = match option_previous_turn {
+        Some(previous_turn) => previous_turn,
+        None => return Ok(vec![]),
+    };
+    let transitions = status_transitions(&db_conn.nation_statuses(alias, previous_turn)?, statuses);
+    Ok(watches
+        .into_iter()
+        .filter_map(|(user_id, nation_id)| {
+            let transition = transitions
+                .iter()
+                .find(|transition| transition.nation_id == nation_id)?;
+            Some(NewTurnNation {
+                user_id,
+                message: describe_transition(alias, turn, transition),
+            })
+        })
+        .collect())
+}
+
+fn watch_helper(
+    db_conn: &DbConnection,
+    alias: &str,
+    user_id: UserId,
+    option_nation_id: Option<u32>,
+    clear: bool,
+) -> Result<String, CommandError> {
+    let nation_id = match option_nation_id {
+        Some(nation_id) => nation_id,
+        None => {
+            let watched: Vec<String> = db_conn
+                .nation_watches_for_alias(alias)?
+                .into_iter()
+                .filter(|&(watcher, _)| watcher == user_id)
+                .map(|(_, nation_id)| nation_id.to_string())
+                .collect();
+            return Ok(if watched.is_empty() {
+                format!("You aren't watching any nations in {}", alias)
+            } else {
+                format!(
+                    "You're watching these nations in {}: {}",
+                    alias,
+                    watched.join(", ")
+                )
+            });
+        }
+    };
+    if clear {
+        if !db_conn.remove_nation_watch(alias, user_id, nation_id)? {
+            return Err(CommandError::from(format!(
+                "You weren't watching nation {} in {}",
+                nation_id, alias
+            )));
+        }
+        return Ok(format!(
+            "You'll stop hearing about nation {} in {}",
+            nation_id, alias
+        ));
+    }
+
+    // Checked against the latest turn we saw, so the name's right too
+    let latest_statuses = match db_conn.nation_status_turns(alias)?.last() {
+        Some(&latest) => db_conn.nation_statuses(alias, latest)?,
+        None => {
+            return Err(CommandError::from(format!(
+                "{} hasn't started yet, or the bot hasn't seen a turn of it",
+                alias
+            )))
+        }
+    };
+    let nation_name = match latest_statuses
+        .iter()
+        .find(|(recorded_id, _, _)| *recorded_id == nation_id)
+    {
+        Some((_, nation_name, _)) => nation_name.clone(),
+        None => {
+            return Err(CommandError::from(format!(
+                "Nation {} isn't in {}",
+                nation_id, alias
+            )))
+        }
+    };
+    if !db_conn.add_nation_watch(alias, user_id, nation_id)? {
+        return Err(CommandError::from(format!(
+            "You're already watching {} ({}) in {}",
+            nation_name, nation_id, alias
+        )));
+    }
+    Ok(format!(
+        "You'll get a DM when {} ({}) in {} goes AI, is eliminated or otherwise changes status",
+        nation_name, nation_id, alias
+    ))
+}
+
+pub fn watch(context: &mut Context, message: &Message, mut args: Args) -> Result<(), CommandError> {
+    let clear = args.single_quoted_n::<String>().ok() == Some(CLEAR_FLAG.to_owned());
+    if clear {
+        args.skip();
+    }
+    let option_nation_id = match args.single_quoted_n::<u32>() {
+        Ok(nation_id) => {
+            args.skip();
+            Some(nation_id)
+        }
+        Err(_) if clear => {
+            return Err(CommandError::from(
+                "Expected the ID of the nation to stop watching",
+            ))
+        }
+        Err(_) => None,
+    };
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = watch_helper(db_conn, &alias, message.author.id, option_nation_id, clear)?;
+    info!("watch: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
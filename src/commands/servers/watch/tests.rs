@@ -0,0 +1,83 @@
+use super::*;
+
+fn record(db_conn: &DbConnection, turn: i32, statuses: &[(u32, &str, NationStatus)]) {
+    let statuses: Vec<RecordedStatus> = statuses
+        .iter()
+        .map(|&(nation_id, name, status)| (nation_id, name.to_owned(), status))
+        .collect();
+    db_conn
+        .record_nation_statuses("test server 1", turn, &statuses)
+        .unwrap();
+}
+
+#[test]
+fn should_watch_list_and_stop_watching_nations() {
+    let db_conn = DbConnection::test();
+    let user_id = UserId(3562);
+    assert!(watch_helper(&db_conn, "test server 1", user_id, Some(5), false).is_err());
+
+    record(
+        &db_conn,
+        10,
+        &[
+            (5, "Arcoscephale", NationStatus::Human),
+            (7, "Ulm", NationStatus::Human),
+        ],
+    );
+    watch_helper(&db_conn, "test server 1", user_id, Some(5), false).unwrap();
+    watch_helper(&db_conn, "test server 1", user_id, Some(7), false).unwrap();
+    assert!(watch_helper(&db_conn, "test server 1", user_id, Some(7), false).is_err());
+    assert!(watch_helper(&db_conn, "test server 1", user_id, Some(8), false).is_err());
+    assert_eq!(
+        watch_helper(&db_conn, "test server 1", user_id, None, false).unwrap(),
+        "You're watching these nations in test server 1: 5, 7"
+    );
+
+    watch_helper(&db_conn, "test server 1", user_id, Some(5), true).unwrap();
+    assert!(watch_helper(&db_conn, "test server 1", user_id, Some(5), true).is_err());
+    assert_eq!(
+        db_conn.nation_watches_for_alias("test server 1").unwrap(),
+        vec![(user_id, 7)]
+    );
+}
+
+#[test]
+fn should_dm_watchers_when_their_nation_changes() {
+    let db_conn = DbConnection::test();
+    record(
+        &db_conn,
+        10,
+        &[
+            (5, "Arcoscephale", NationStatus::Human),
+            (7, "Ulm", NationStatus::Human),
+        ],
+    );
+    db_conn
+        .add_nation_watch("test server 1", UserId(3563), 7)
+        .unwrap();
+    db_conn
+        .add_nation_watch("test server 1", UserId(3564), 5)
+        .unwrap();
+
+    let dms = watched_nation_dms(
+        &db_conn,
+        "test server 1",
+        12,
+        &[
+            (5, "Arcoscephale".to_owned(), NationStatus::Human),
+            (7, "Ulm".to_owned(), NationStatus::DefeatedThisTurn),
+        ],
+    )
+    .unwrap();
+    let dms: Vec<(UserId, &str)> = dms
+        .iter()
+        .map(|dm| (dm.user_id, dm.message.as_str()))
+        .collect();
+    assert_eq!(
+        dms,
+        vec![(
+            UserId(3563),
+            "Ulm (7) in test server 1 has been eliminated as of turn 12 (was Human)"
+        )]
+    );
+}
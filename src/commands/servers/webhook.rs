@@ -0,0 +1,108 @@
+use log::*;
+use reqwest::Url;
+use serde_json::json;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::all::undone_count;
+use crate::commands::servers::PlayingState;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::GameServerState;
+
+#[cfg(test)]
+mod tests;
+
+/// Pass this instead of a URL to stop sending
+const CLEAR_FLAG: &str = "--clear";
+
+/// What a game's webhook gets sent when a new turn starts
+pub fn new_turn_payload(alias: &str, playing_state: &PlayingState) -> serde_json::Value {
+    json!({
+        "event": "new_turn",
+        "alias": alias,
+        "turn": playing_state.turn,
+        "undone": undone_count(playing_state),
+        "deadline": playing_state
+            .option_deadline
+            .map(|deadline| deadline.to_rfc3339()),
+    })
+}
+
+fn parse_webhook_url(arg: &str) -> Result<String, CommandError> {
+    let invalid = || {
+        CommandError::from(format!(
+            "Expected a webhook URL starting with https:// or http://, or {}",
+            CLEAR_FLAG
+        ))
+    };
+    let url = Url::parse(arg.trim()).map_err(|_| invalid())?;
+    if !["https", "http"].contains(&url.scheme()) || url.host_str().is_none_or(str::is_empty) {
+        return Err(invalid());
+    }
+    Ok(url.to_string())
+}
+
+fn webhook_helper(
+    db_conn: &DbConnection,
+    author_id: UserId,
+    alias: &str,
+    arg: &str,
+) -> Result<String, CommandError> {
+    // Games added with !add have nobody in charge of them, so anyone can
+    let option_owner = match db_conn.game_for_alias(alias)?.state {
+        GameServerState::Lobby(lobby_state) => Some(lobby_state.owner),
+        GameServerState::StartedState(_, option_lobby_state)
+        | GameServerState::Archived(_, option_lobby_state) => {
+            option_lobby_state.map(|lobby_state| lobby_state.owner)
+        }
+    };
+    if option_owner.is_some_and(|owner| owner != author_id) {
+        return Err(CommandError::from(format!(
+            "Only the owner of {} can do this",
+            alias
+        )));
+    }
+
+    if arg == CLEAR_FLAG {
+        db_conn.set_webhook_url_for_alias(alias, None)?;
+        return Ok(format!(
+            "New turns in {} won't be sent anywhere else",
+            alias
+        ));
+    }
+    let url = parse_webhook_url(arg)?;
+    db_conn.set_webhook_url_for_alias(alias, Some(&url))?;
+    // Not repeating the URL, webhooks usually have their secret in it
+    Ok(format!(
+        "New turns in {} will be POSTed to your webhook as JSON",
+        alias
+    ))
+}
+
+pub fn webhook(
+    context: &mut Context,
+    message: &Message,
+    mut args: Args,
+) -> Result<(), CommandError> {
+    let arg = args
+        .single_quoted::<String>()
+        .map_err(|_| CommandError::from("Expected a webhook URL, or --clear"))?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let text = webhook_helper(db_conn, message.author.id, &alias, &arg)?;
+    info!("webhook: {}", text);
+    message.reply(&text)?;
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use super::*;
+
+use crate::commands::servers::{PlayerDetails, PotentialPlayer};
+use crate::model::enums::{NationStatus, SubmissionStatus};
+use chrono::{TimeZone, Utc};
+
+#[test]
+fn should_only_take_http_urls() {
+    assert_eq!(
+        parse_webhook_url("https://example.com/hooks/abc").unwrap(),
+        "https://example.com/hooks/abc"
+    );
+    assert!(parse_webhook_url("ftp://example.com/hooks").is_err());
+    assert!(parse_webhook_url("example.com/hooks").is_err());
+}
+
+#[test]
+fn should_set_and_clear_webhook() {
+    let db_conn = DbConnection::test();
+    // "test server 1" was added without a lobby, so has no owner
+    webhook_helper(
+        &db_conn,
+        UserId(327),
+        "test server 1",
+        "https://example.com/secret",
+    )
+    .unwrap();
+    assert_eq!(
+        db_conn.webhook_url_for_alias("test server 1").unwrap(),
+        Some("https://example.com/secret".to_owned())
+    );
+    assert_eq!(
+        db_conn.webhook_url_for_alias("test server 2").unwrap(),
+        None
+    );
+
+    let text = webhook_helper(&db_conn, UserId(327), "test server 1", CLEAR_FLAG).unwrap();
+    assert!(!text.contains("secret"));
+    assert_eq!(
+        db_conn.webhook_url_for_alias("test server 1").unwrap(),
+        None
+    );
+    assert!(webhook_helper(&db_conn, UserId(327), "not a game", CLEAR_FLAG).is_err());
+}
+
+#[test]
+fn should_describe_new_turn_in_payload() {
+    let deadline = Utc.ymd(2020, 10, 20).and_hms(21, 0, 0);
+    let player = |nation_id, submitted| {
+        PotentialPlayer::GameOnly(PlayerDetails {
+            nation_id,
+            nation_name: format!("nation {}", nation_id),
+            submitted,
+            player_status: NationStatus::Human,
+            went_ai: false,
+            connected: false,
+        })
+    };
+    let playing_state = PlayingState {
+        players: vec![
+            player(1, SubmissionStatus::NotSubmitted),
+            player(2, SubmissionStatus::Submitted),
+        ],
+        turn: 12,
+        mins_remaining: 0,
+        hours_remaining: 1,
+        days_remaining: 0,
+        secs_remaining: 0,
+        option_deadline: Some(deadline),
+        paused: false,
+        blitz: false,
+    };
+    assert_eq!(
+        new_turn_payload("foo", &playing_state),
+        json!({
+            "event": "new_turn",
+            "alias": "foo",
+            "turn": 12,
+            "undone": 1,
+            "deadline": "2020-10-20T21:00:00+00:00",
+        })
+    );
+}
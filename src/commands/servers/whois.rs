@@ -0,0 +1,173 @@
+use either::Either;
+use log::*;
+use serenity::framework::standard::{Args, CommandError};
+use serenity::model::channel::Message;
+use serenity::model::misc::Mentionable;
+use serenity::prelude::Context;
+
+use super::alias_from_arg_or_channel_name;
+use super::register_player::nation_from_name_among;
+use crate::commands::servers::*;
+use crate::db::{DbConnection, DbConnectionKey};
+use crate::model::enums::{Nation, Nations, SubmissionStatus};
+
+#[cfg(test)]
+mod tests;
+
+/// Which of `nations` the argument means: an exact ID, or a name looked up
+/// the same way !register does, but only among the game's own nations
+fn matching_nation(
+    nations: &[(u32, &str)],
+    arg_nation: Either<&str, u32>,
+) -> Result<usize, CommandError> {
+    let nation_id = match arg_nation {
+        Either::Right(arg_nation_id) => arg_nation_id,
+        Either::Left(arg_nation_name) => {
+            let game_nations: Vec<Nation> = nations
+                .iter()
+                .map(|&(nation_id, nation_name)| Nation {
+                    id: nation_id,
+                    name: nation_name.to_owned(),
+                    era: Nations::from_id(nation_id).and_then(|nation| nation.era),
+                })
+                .collect();
+            nation_from_name_among(arg_nation_name, &game_nations, "Try its ID instead.")?.id
+        }
+    };
+    nations
+        .iter()
+        .position(|&(game_nation_id, _)| game_nation_id == nation_id)
+        .ok_or_else(|| {
+            CommandError::from(format!(
+                "Could not find nation with ID \"{}\" in this game",
+                nation_id
+            ))
+        })
+}
+
+fn show_submission(submitted: &SubmissionStatus) -> String {
+    match submitted {
+        SubmissionStatus::NotSubmitted => "hasn't played this turn yet".to_owned(),
+        SubmissionStatus::PartiallySubmitted => "has partially played this turn".to_owned(),
+        SubmissionStatus::Submitted => "has played this turn".to_owned(),
+        SubmissionStatus::Unknown(x) => format!("has an unknown submission status ({})", x),
+    }
+}
+
+fn whois_helper(
+    db_conn: &DbConnection,
+    game_cache: &GameCache,
+    alias: &str,
+    arg_nation: Either<&str, u32>,
+) -> Result<String, CommandError> {
+    let (details, _) = cached_game_details(alias, db_conn, game_cache)?;
+    match details.nations {
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Playing(playing_state),
+            ..
+        }) => {
+            let nations: Vec<(u32, &str)> = playing_state
+                .players
+                .iter()
+                .map(|player| (player.nation_id(), player.nation_name().as_str()))
+                .collect();
+            let index = matching_nation(&nations, arg_nation)?;
+            let (nation_id, nation_name) = nations[index];
+            Ok(match &playing_state.players[index] {
+                PotentialPlayer::RegisteredAndGame(user_id, player_details) => format!(
+                    "{} ({}) in {} is played by {}, who {}",
+                    nation_name,
+                    nation_id,
+                    alias,
+                    user_id.mention(),
+                    show_submission(&player_details.submitted)
+                ),
+                PotentialPlayer::GameOnly(_) => format!(
+                    "{} ({}): nation is in-game but nobody has claimed it here",
+                    nation_name, nation_id
+                ),
+                PotentialPlayer::RegisteredOnly(user_id, _, _) => format!(
+                    "{} ({}) is registered to {} but isn't in {}",
+                    nation_name,
+                    nation_id,
+                    user_id.mention(),
+                    alias
+                ),
+            })
+        }
+        NationDetails::Started(StartedDetails {
+            state: StartedStateDetails::Uploading(uploading_state),
+            ..
+        }) => {
+            let nations: Vec<(u32, &str)> = uploading_state
+                .uploading_players
+                .iter()
+                .map(|player| (player.nation_id(), player.nation_name().as_str()))
+                .collect();
+            let index = matching_nation(&nations, arg_nation)?;
+            let (nation_id, nation_name) = nations[index];
+            let uploading_player = &uploading_state.uploading_players[index];
+            let uploaded = if uploading_player.uploaded {
+                "has uploaded"
+            } else {
+                "hasn't uploaded yet"
+            };
+            Ok(match uploading_player.option_player_id() {
+                Some(user_id) => format!(
+                    "{} ({}) in {} is registered to {}, who {}",
+                    nation_name,
+                    nation_id,
+                    alias,
+                    user_id.mention(),
+                    uploaded
+                ),
+                None => format!(
+                    "{} ({}): nation is in-game but nobody has claimed it here",
+                    nation_name, nation_id
+                ),
+            })
+        }
+        NationDetails::Lobby(lobby_details) => {
+            let nations: Vec<(u32, &str)> = lobby_details
+                .players
+                .iter()
+                .map(|player| (player.nation_id, player.nation_name.as_str()))
+                .collect();
+            let index = matching_nation(&nations, arg_nation)?;
+            let lobby_player = &lobby_details.players[index];
+            Ok(format!(
+                "{} ({}) in {} is registered to {}, the game hasn't started yet",
+                lobby_player.nation_name,
+                lobby_player.nation_id,
+                alias,
+                lobby_player.player_id.mention()
+            ))
+        }
+    }
+}
+
+pub fn whois(context: &mut Context, message: &Message, mut args: Args) -> Result<(), CommandError> {
+    let arg_nation = args.single_quoted::<String>()?;
+    let alias = alias_from_arg_or_channel_name(&mut args, message)?;
+    if !args.is_empty() {
+        return Err(CommandError::from(
+            "Too many arguments. TIP: spaces in arguments need to be quoted \"like this\"",
+        ));
+    }
+    let arg_nation = match arg_nation.parse::<u32>() {
+        Ok(nation_id) => Either::Right(nation_id),
+        Err(_) => Either::Left(arg_nation.as_str()),
+    };
+
+    let data = context.data.lock();
+    let db_conn = data
+        .get::<DbConnectionKey>()
+        .ok_or("No DbConnection was created on startup. This is a bug.")?;
+    let game_cache = data
+        .get::<GameCacheKey>()
+        .ok_or("No GameCache was created on startup. This is a bug.")?;
+    let text = whois_helper(db_conn, game_cache, &alias, arg_nation)?;
+    info!("whois: replying with: {}", text);
+    message.channel_id.say(&text)?;
+    Ok(())
+}
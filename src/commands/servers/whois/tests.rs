@@ -0,0 +1,64 @@
+use super::*;
+
+use crate::model::enums::NationStatus;
+use crate::model::{GameData, Nation};
+
+fn whois_test_server_1(
+    db_conn: &DbConnection,
+    arg_nation: Either<&str, u32>,
+) -> Result<String, CommandError> {
+    let nation = |id: u32| Nation {
+        id,
+        status: NationStatus::Human,
+        submitted: SubmissionStatus::Submitted,
+        connected: false,
+        name: format!("nation {}", id),
+        era: "EA".to_owned(),
+    };
+    // "test server 1" has UserId(101) registered as nation 1
+    let game_cache = GameCache::test_polled(
+        "test server 1",
+        GameData {
+            game_name: "test server 1".to_owned(),
+            nations: vec![nation(1), nation(7)],
+            turn: 32,
+            turn_timer: 3_600_000,
+        },
+    );
+    whois_helper(db_conn, &game_cache, "test server 1", arg_nation)
+}
+
+#[test]
+fn should_find_registered_player_by_id() {
+    let db_conn = DbConnection::test();
+    let text = whois_test_server_1(&db_conn, Either::Right(1)).unwrap();
+    assert!(text.contains("<@101>"));
+    assert!(text.contains("has played this turn"));
+}
+
+#[test]
+fn should_find_nation_by_start_of_name() {
+    let db_conn = DbConnection::test();
+    let text = whois_test_server_1(&db_conn, Either::Left("ul")).unwrap();
+    assert!(text.starts_with("Ulm (7)"));
+}
+
+#[test]
+fn should_say_when_nobody_has_claimed_a_nation() {
+    let db_conn = DbConnection::test();
+    let text = whois_test_server_1(&db_conn, Either::Right(7)).unwrap();
+    assert!(text.contains("nation is in-game but nobody has claimed it here"));
+}
+
+#[test]
+fn should_fail_for_nation_not_in_game() {
+    let db_conn = DbConnection::test();
+    assert!(whois_test_server_1(&db_conn, Either::Right(42)).is_err());
+}
+
+#[test]
+fn should_suggest_nations_for_a_typo() {
+    let db_conn = DbConnection::test();
+    let err = whois_test_server_1(&db_conn, Either::Left("ulmm")).unwrap_err();
+    assert!(err.0.ends_with("Did you mean: Ulm (EA 7)?"), "{}", err.0);
+}
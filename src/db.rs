@@ -1,15 +1,19 @@
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
 use failure::{err_msg, Error};
 use lazy_static::lazy_static;
 use log::*;
 use num_traits::{FromPrimitive, ToPrimitive};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::params;
-use serenity::model::id::UserId;
+use rusqlite::{params, OptionalExtension, Transaction};
+use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId, UserId};
 use typemap::Key;
 
+use crate::commands::servers::{CacheEntry, TimeFormat};
+use crate::fuzzy::closest_matches;
 use crate::model::enums::*;
 use crate::model::*;
+use std::collections::HashMap;
 use std::path::Path;
 
 use failure::SyncFailure;
@@ -19,20 +23,220 @@ use migrant_lib::{list, Config, EmbeddedMigration, Migratable, Migrator, Setting
 #[cfg(test)]
 pub mod test_helpers;
 
+#[cfg(test)]
+mod tests;
+
+/// How many other aliases a failed game lookup suggests
+const MAX_ALIAS_SUGGESTIONS: usize = 3;
+
+/// When the poller stopped and when it started again
+pub type Downtime = (DateTime<Utc>, DateTime<Utc>);
+
+/// A channel's weekly digest: the day (0 is Monday), the minute of that day in
+/// UTC, and when it last went out
+pub type ChannelDigest = (ChannelId, u32, u32, DateTime<Utc>);
+
+/// A nation in a game and when its player registered, if that was kept track of
+pub type Registration = (u32, Option<DateTime<Utc>>);
+
+/// A turn, a player registered in it, their nation, and whether they had their
+/// turn in when it hosted
+pub type FinalSubmission = (i32, UserId, u32, bool);
+
 pub struct DbConnectionKey;
 impl Key for DbConnectionKey {
     type Value = DbConnection;
 }
 
 lazy_static! {
-    static ref MIGRATIONS: [EmbeddedMigration; 2] = {
+    static ref MIGRATIONS: [EmbeddedMigration; 57] = {
         let mut m1 = EmbeddedMigration::with_tag("001-baseline");
         m1.up(include_str!("db/sql/migrations/001_baseline.sql"));
 
         let mut m2 = EmbeddedMigration::with_tag("002-lobby-description");
         m2.up(include_str!("db/sql/migrations/002_lobby_description.sql"));
 
-        [m1, m2]
+        let mut m3 = EmbeddedMigration::with_tag("003-turn-history");
+        m3.up(include_str!("db/sql/migrations/003_turn_history.sql"));
+
+        let mut m4 = EmbeddedMigration::with_tag("004-game-channel");
+        m4.up(include_str!("db/sql/migrations/004_game_channel.sql"));
+
+        let mut m5 = EmbeddedMigration::with_tag("005-nation-providers");
+        m5.up(include_str!("db/sql/migrations/005_nation_providers.sql"));
+
+        let mut m6 = EmbeddedMigration::with_tag("006-game-timezone");
+        m6.up(include_str!("db/sql/migrations/006_game_timezone.sql"));
+
+        let mut m7 = EmbeddedMigration::with_tag("007-turn-stats");
+        m7.up(include_str!("db/sql/migrations/007_turn_stats.sql"));
+
+        let mut m8 = EmbeddedMigration::with_tag("008-snek-game-id");
+        m8.up(include_str!("db/sql/migrations/008_snek_game_id.sql"));
+
+        let mut m9 = EmbeddedMigration::with_tag("009-player-nickname");
+        m9.up(include_str!("db/sql/migrations/009_player_nickname.sql"));
+
+        let mut m10 = EmbeddedMigration::with_tag("010-stall-alert");
+        m10.up(include_str!("db/sql/migrations/010_stall_alert.sql"));
+
+        let mut m11 = EmbeddedMigration::with_tag("011-default-era");
+        m11.up(include_str!("db/sql/migrations/011_default_era.sql"));
+
+        let mut m12 = EmbeddedMigration::with_tag("012-reminder-template");
+        m12.up(include_str!("db/sql/migrations/012_reminder_template.sql"));
+
+        let mut m13 = EmbeddedMigration::with_tag("013-countdown");
+        m13.up(include_str!("db/sql/migrations/013_countdown.sql"));
+
+        let mut m14 = EmbeddedMigration::with_tag("014-start-reminder");
+        m14.up(include_str!("db/sql/migrations/014_start_reminder.sql"));
+
+        let mut m15 = EmbeddedMigration::with_tag("015-quickhost");
+        m15.up(include_str!("db/sql/migrations/015_quickhost.sql"));
+
+        let mut m16 = EmbeddedMigration::with_tag("016-ai-reserved");
+        m16.up(include_str!("db/sql/migrations/016_ai_reserved.sql"));
+
+        let mut m17 = EmbeddedMigration::with_tag("017-submission-history");
+        m17.up(include_str!("db/sql/migrations/017_submission_history.sql"));
+
+        let mut m18 = EmbeddedMigration::with_tag("018-poll-interval");
+        m18.up(include_str!("db/sql/migrations/018_poll_interval.sql"));
+
+        let mut m19 = EmbeddedMigration::with_tag("019-archived");
+        m19.up(include_str!("db/sql/migrations/019_archived.sql"));
+
+        let mut m20 = EmbeddedMigration::with_tag("020-locale");
+        m20.up(include_str!("db/sql/migrations/020_locale.sql"));
+
+        let mut m21 = EmbeddedMigration::with_tag("021-one-off-reminder");
+        m21.up(include_str!("db/sql/migrations/021_one_off_reminder.sql"));
+
+        let mut m22 = EmbeddedMigration::with_tag("022-guild-commands");
+        m22.up(include_str!("db/sql/migrations/022_guild_commands.sql"));
+
+        let mut m23 = EmbeddedMigration::with_tag("023-details-format");
+        m23.up(include_str!("db/sql/migrations/023_details_format.sql"));
+
+        let mut m24 = EmbeddedMigration::with_tag("024-lobby-nation-set");
+        m24.up(include_str!("db/sql/migrations/024_lobby_nation_set.sql"));
+
+        let mut m25 = EmbeddedMigration::with_tag("025-vacation");
+        m25.up(include_str!("db/sql/migrations/025_vacation.sql"));
+
+        let mut m26 = EmbeddedMigration::with_tag("026-subscriptions");
+        m26.up(include_str!("db/sql/migrations/026_subscriptions.sql"));
+
+        let mut m27 = EmbeddedMigration::with_tag("027-game-icon");
+        m27.up(include_str!("db/sql/migrations/027_game_icon.sql"));
+
+        let mut m28 = EmbeddedMigration::with_tag("028-eliminations");
+        m28.up(include_str!("db/sql/migrations/028_eliminations.sql"));
+
+        let mut m29 = EmbeddedMigration::with_tag("029-spectators");
+        m29.up(include_str!("db/sql/migrations/029_spectators.sql"));
+
+        let mut m30 = EmbeddedMigration::with_tag("030-registered-at");
+        m30.up(include_str!("db/sql/migrations/030_registered_at.sql"));
+
+        let mut m31 = EmbeddedMigration::with_tag("031-digest");
+        m31.up(include_str!("db/sql/migrations/031_digest.sql"));
+
+        let mut m32 = EmbeddedMigration::with_tag("032-submission-confirmations");
+        m32.up(include_str!(
+            "db/sql/migrations/032_submission_confirmations.sql"
+        ));
+
+        let mut m33 = EmbeddedMigration::with_tag("033-final-submissions");
+        m33.up(include_str!("db/sql/migrations/033_final_submissions.sql"));
+
+        let mut m34 = EmbeddedMigration::with_tag("034-notification-routes");
+        m34.up(include_str!(
+            "db/sql/migrations/034_notification_routes.sql"
+        ));
+
+        let mut m35 = EmbeddedMigration::with_tag("035-progress-bookmarks");
+        m35.up(include_str!("db/sql/migrations/035_progress_bookmarks.sql"));
+
+        let mut m36 = EmbeddedMigration::with_tag("036-cached-polls");
+        m36.up(include_str!("db/sql/migrations/036_cached_polls.sql"));
+
+        let mut m37 = EmbeddedMigration::with_tag("037-reminder-caps");
+        m37.up(include_str!("db/sql/migrations/037_reminder_caps.sql"));
+
+        let mut m38 = EmbeddedMigration::with_tag("038-stall-grace");
+        m38.up(include_str!("db/sql/migrations/038_stall_grace.sql"));
+
+        let mut m39 = EmbeddedMigration::with_tag("039-status-messages");
+        m39.up(include_str!("db/sql/migrations/039_status_messages.sql"));
+
+        let mut m40 = EmbeddedMigration::with_tag("040-lobby-rosters");
+        m40.up(include_str!("db/sql/migrations/040_lobby_rosters.sql"));
+
+        let mut m41 = EmbeddedMigration::with_tag("041-nation-status-history");
+        m41.up(include_str!(
+            "db/sql/migrations/041_nation_status_history.sql"
+        ));
+
+        let mut m42 = EmbeddedMigration::with_tag("042-nation-watches");
+        m42.up(include_str!("db/sql/migrations/042_nation_watches.sql"));
+
+        let mut m43 = EmbeddedMigration::with_tag("043-max-games-per-channel");
+        m43.up(include_str!(
+            "db/sql/migrations/043_max_games_per_channel.sql"
+        ));
+
+        let mut m44 = EmbeddedMigration::with_tag("044-game-description");
+        m44.up(include_str!("db/sql/migrations/044_game_description.sql"));
+
+        let mut m45 = EmbeddedMigration::with_tag("045-nation-names");
+        m45.up(include_str!("db/sql/migrations/045_nation_names.sql"));
+
+        let mut m46 = EmbeddedMigration::with_tag("046-polling-pause");
+        m46.up(include_str!("db/sql/migrations/046_polling_pause.sql"));
+
+        let mut m47 = EmbeddedMigration::with_tag("047-player-timezone");
+        m47.up(include_str!("db/sql/migrations/047_player_timezone.sql"));
+
+        let mut m48 = EmbeddedMigration::with_tag("048-default-reminder-caps");
+        m48.up(include_str!(
+            "db/sql/migrations/048_default_reminder_caps.sql"
+        ));
+
+        let mut m49 = EmbeddedMigration::with_tag("049-webhooks");
+        m49.up(include_str!("db/sql/migrations/049_webhooks.sql"));
+
+        let mut m50 = EmbeddedMigration::with_tag("050-guild-time-format");
+        m50.up(include_str!("db/sql/migrations/050_guild_time_format.sql"));
+
+        let mut m51 = EmbeddedMigration::with_tag("051-waiting-on-host");
+        m51.up(include_str!("db/sql/migrations/051_waiting_on_host.sql"));
+
+        let mut m52 = EmbeddedMigration::with_tag("052-turn-notes");
+        m52.up(include_str!("db/sql/migrations/052_turn_notes.sql"));
+
+        let mut m53 = EmbeddedMigration::with_tag("053-blitz");
+        m53.up(include_str!("db/sql/migrations/053_blitz.sql"));
+
+        let mut m54 = EmbeddedMigration::with_tag("054-first-seen");
+        m54.up(include_str!("db/sql/migrations/054_first_seen.sql"));
+
+        let mut m55 = EmbeddedMigration::with_tag("055-started-era");
+        m55.up(include_str!("db/sql/migrations/055_started_era.sql"));
+
+        let mut m56 = EmbeddedMigration::with_tag("056-auto-archive");
+        m56.up(include_str!("db/sql/migrations/056_auto_archive.sql"));
+
+        let mut m57 = EmbeddedMigration::with_tag("057-admin-role");
+        m57.up(include_str!("db/sql/migrations/057_admin_role.sql"));
+
+        [
+            m1, m2, m3, m4, m5, m6, m7, m8, m9, m10, m11, m12, m13, m14, m15, m16, m17, m18, m19,
+            m20, m21, m22, m23, m24, m25, m26, m27, m28, m29, m30, m31, m32, m33, m34, m35, m36,
+            m37, m38, m39, m40, m41, m42, m43, m44, m45, m46, m47, m48, m49, m50, m51, m52, m53,
+            m54, m55, m56, m57,
+        ]
     };
 }
 #[derive(Clone)]
@@ -85,11 +289,62 @@ impl DbConnection {
     pub fn insert_game_server(&self, game_server: &GameServer) -> Result<(), Error> {
         info!("db::insert_game_server: {:?}", game_server);
         let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        Self::insert_game_server_in(&tx, game_server)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Adds the game and the channel to post about it in together, so that if
+    /// either fails neither is kept and the same add can just be tried again
+    /// `option_started_era` is for games added already started, which have no
+    /// lobby to say what era they are
+    pub fn insert_game_server_with_channel(
+        &self,
+        game_server: &GameServer,
+        channel_id: ChannelId,
+        option_started_era: Option<Era>,
+    ) -> Result<(), Error> {
+        info!("db::insert_game_server_with_channel: {:?}", game_server);
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        Self::insert_game_server_in(&tx, game_server)?;
+        tx.execute(
+            include_str!("db/sql/update_game_with_channel.sql"),
+            params![&game_server.alias, &(channel_id.0 as i64)],
+        )?;
+        if let Some(era) = option_started_era {
+            tx.execute(
+                include_str!("db/sql/update_game_with_started_era.sql"),
+                params![&game_server.alias, &era.to_i32()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The eras recorded for games added already started, see
+    /// `insert_game_server_with_channel`
+    pub fn started_eras(&self) -> Result<HashMap<String, Era>, Error> {
+        info!("db::started_eras");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_started_eras.sql"))?;
+        let eras = stmt
+            .query_map(params![], |row| {
+                let alias: String = row.get(0)?;
+                let era: i32 = row.get(1)?;
+                Ok((alias, era))
+            })?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter_map(|(alias, era)| Era::from_i32(era).map(|era| (alias, era)))
+            .collect();
+        Ok(eras)
+    }
 
+    fn insert_game_server_in(tx: &Transaction, game_server: &GameServer) -> Result<(), Error> {
         match game_server.state {
             GameServerState::Lobby(ref lobby_state) => {
-                let tx = conn.transaction()?;
-
                 tx.execute(
                     include_str!("db/sql/insert_player.sql"),
                     params![&(lobby_state.owner.0 as i64), &true],
@@ -113,11 +368,9 @@ impl DbConnection {
                         &lobby_state.player_count,
                     ],
                 )?;
-                tx.commit()?;
-                Ok(())
             }
-            GameServerState::StartedState(ref started_state, None) => {
-                let tx = conn.transaction()?;
+            GameServerState::StartedState(ref started_state, None)
+            | GameServerState::Archived(ref started_state, None) => {
                 tx.execute(
                     include_str!("db/sql/insert_started_server.sql"),
                     params![&started_state.address, &started_state.last_seen_turn],
@@ -126,11 +379,15 @@ impl DbConnection {
                     include_str!("db/sql/insert_started_game_server.sql"),
                     params![&game_server.alias, &started_state.address],
                 )?;
-                tx.commit()?;
-                Ok(())
+                if let GameServerState::Archived(_, _) = game_server.state {
+                    tx.execute(
+                        include_str!("db/sql/update_game_with_archived.sql"),
+                        params![&game_server.alias, &true],
+                    )?;
+                }
             }
-            GameServerState::StartedState(ref started_state, Some(ref lobby_state)) => {
-                let tx = conn.transaction()?;
+            GameServerState::StartedState(ref started_state, Some(ref lobby_state))
+            | GameServerState::Archived(ref started_state, Some(ref lobby_state)) => {
                 tx.execute(
                     include_str!("db/sql/insert_player.sql"),
                     params![&(lobby_state.owner.0 as i64), &true],
@@ -167,10 +424,15 @@ impl DbConnection {
                         &game_server.alias,
                     ],
                 )?;
-                tx.commit()?;
-                Ok(())
+                if let GameServerState::Archived(_, _) = game_server.state {
+                    tx.execute(
+                        include_str!("db/sql/update_game_with_archived.sql"),
+                        params![&game_server.alias, &true],
+                    )?;
+                }
             }
         }
+        Ok(())
     }
 
     pub fn insert_player_into_server(
@@ -189,14 +451,33 @@ impl DbConnection {
                 &player.turn_notifications,
             ],
         )?;
-        tx.execute(
+        let rows_modified = tx.execute(
             include_str!("db/sql/insert_server_player.sql"),
             params![
                 &nation_id,
                 &(player.discord_user_id.0 as i64),
-                &server_alias
+                &server_alias,
+                &Utc::now().timestamp(),
             ],
         )?;
+        // Two people registering at once both get past the commands' own checks,
+        // but only one of them gets the nation
+        if rows_modified == 0 {
+            let option_claimant: Option<i64> = tx
+                .query_row(
+                    include_str!("db/sql/select_nation_claimant.sql"),
+                    params![&server_alias, &nation_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            return Err(match option_claimant {
+                Some(claimant) => err_msg(format!(
+                    "Nation {} is already registered to <@{}> in {}",
+                    nation_id, claimant, server_alias
+                )),
+                None => err_msg(format!("Could not find game with name {}", server_alias)),
+            });
+        }
         tx.commit()?;
         Ok(())
     }
@@ -214,6 +495,8 @@ impl DbConnection {
                 let maybe_era: Option<i32> = row.get(4)?;
                 let maybe_player_count: Option<i32> = row.get(5)?;
                 let description: Option<String> = row.get(6)?;
+                let maybe_snek_game_id: Option<i64> = row.get(7)?;
+                let archived: bool = row.get(8)?;
 
                 let game_server = make_game_server(
                     alias,
@@ -223,6 +506,8 @@ impl DbConnection {
                     maybe_era,
                     maybe_player_count,
                     description,
+                    maybe_snek_game_id,
+                    archived,
                 )?;
 
                 Ok(game_server)
@@ -251,197 +536,1571 @@ impl DbConnection {
         Ok(vec)
     }
 
-    pub fn game_for_alias(&self, game_alias: &str) -> Result<GameServer, Error> {
-        info!("db::game_for_alias");
-        let conn = &*self.0.clone().get()?;
-        let mut stmt = conn.prepare(include_str!("db/sql/select_game_server_for_alias.sql"))?;
-        let foo = stmt.query_map(&[&game_alias], |ref row| {
-            let maybe_address: Option<String> = row.get(0).unwrap();
-            let maybe_last_seen_turn: Option<i32> = row.get(1).unwrap();
-            let maybe_owner: Option<i64> = row.get(2).unwrap();
-            let maybe_era: Option<i32> = row.get(3).unwrap();
-            let maybe_player_count: Option<i32> = row.get(4).unwrap();
-            let description: Option<String> = row.get(5).unwrap();
-            Ok(make_game_server(
-                game_alias.to_owned(),
-                maybe_address,
-                maybe_last_seen_turn,
-                maybe_owner,
-                maybe_era,
-                maybe_player_count,
-                description,
-            )
-            .unwrap())
-        })?;
-        let vec = foo.collect::<Result<Vec<_>, _>>()?;
-        if vec.len() == 1 {
-            Ok(vec
-                .into_iter()
-                .next()
-                .ok_or(err_msg("THIS SHOULD NEVER HAPPEN"))?) // TODO: *vomits*
-        } else {
-            Err(err_msg(format!(
-                "could not find the game with alias {}",
-                game_alias
-            )))
-        }
-    }
-
-    pub fn update_game_with_possibly_new_turn(
+    /// The players in a game who'd rather be shown by a nickname than a mention
+    pub fn player_nicknames_for_alias(
         &self,
         game_alias: &str,
-        current_turn: i32,
-    ) -> Result<bool, Error> {
-        info!("db::update_game_with_possibly_new_turn");
-        let conn = &mut *self.0.clone().get()?;
-        let rows = conn.execute(
-            include_str!("db/sql/update_game_with_turn.sql"),
-            params![&current_turn, &game_alias],
-        )?;
-        info!("db::update_game_with_possibly_new_turn FINISHED");
-        Ok(rows > 0)
-    }
-
-    pub fn remove_player_from_game(&self, game_alias: &str, user: UserId) -> Result<usize, Error> {
-        info!("db::remove_player_from_game");
+    ) -> Result<HashMap<UserId, String>, Error> {
+        info!("db::player_nicknames_for_alias");
         let conn = &*self.0.clone().get()?;
-        Ok(conn.execute(
-            include_str!("db/sql/delete_player_from_game.sql"),
-            params![&game_alias, &(user.0 as i64)],
-        )?)
+        let mut stmt = conn.prepare(include_str!("db/sql/select_player_nicknames.sql"))?;
+        let nicknames = stmt
+            .query_map(&[&game_alias], |row| {
+                let discord_user_id: i64 = row.get(0)?;
+                let nickname: String = row.get(1)?;
+                Ok((UserId(discord_user_id as u64), nickname))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(nicknames)
     }
 
-    pub fn remove_server(&self, game_alias: &str) -> Result<(), Error> {
-        info!("db::remove_server");
-        let conn = &mut *self.0.clone().get()?;
-        let tx = conn.transaction()?;
-        tx.execute(
-            include_str!("db/sql/delete_server_players.sql"),
-            params![&game_alias],
-        )?;
-        let rows_modified = tx.execute(
-            include_str!("db/sql/delete_game_server.sql"),
-            params![&game_alias],
+    /// `None` goes back to showing their discord name
+    pub fn set_player_nickname(
+        &self,
+        alias: &str,
+        user_id: UserId,
+        option_nickname: Option<&str>,
+    ) -> Result<(), Error> {
+        info!("db::set_player_nickname");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_server_player_nickname.sql"),
+            params![&alias, &(user_id.0 as i64), &option_nickname],
         )?;
-        tx.execute(include_str!("db/sql/delete_started_server.sql"), params![])?;
-        tx.execute(include_str!("db/sql/delete_lobby.sql"), params![])?;
         if rows_modified != 0 {
-            tx.commit()?;
             Ok(())
         } else {
-            Err(err_msg(format!(
-                "Could not find server with name {}",
-                game_alias
-            )))
-        }
-    }
-
-    pub fn servers_for_player(&self, user_id: UserId) -> Result<Vec<(GameServer, u32)>, Error> {
-        info!("servers_for_player");
-        let conn = &*self.0.clone().get()?;
-        let mut stmt = conn.prepare(include_str!("db/sql/select_servers_for_player.sql"))?;
-
-        let foo = stmt.query_map(&[&(user_id.0 as i64)], |ref row| {
-            let alias: String = row.get(1).unwrap();
-            let maybe_address: Option<String> = row.get(0).unwrap();
-            let maybe_last_seen_turn: Option<i32> = row.get(2).unwrap();
-            let maybe_owner: Option<i64> = row.get(4).unwrap();
-            let maybe_era: Option<i32> = row.get(5).unwrap();
-            let maybe_player_count: Option<i32> = row.get(6).unwrap();
-            let description: Option<String> = row.get(7).unwrap();
-            let server = make_game_server(
-                alias,
-                maybe_address,
-                maybe_last_seen_turn,
-                maybe_owner,
-                maybe_era,
-                maybe_player_count,
-                description,
-            )
-            .unwrap();
-
-            let nation_id: i32 = row.get(3).unwrap();
-            Ok((server, nation_id as u32))
-        })?;
-
-        let mut ret: Vec<(GameServer, u32)> = vec![];
-        for pair in foo {
-            ret.push(pair?);
+            Err(err_msg(format!("You are not registered in {}", alias)))
         }
-
-        Ok(ret)
     }
 
-    pub fn set_turn_notifications(
+    /// `None` to come back from vacation early
+    pub fn set_player_vacation(
         &self,
-        player: UserId,
-        desired_turn_notifications: bool,
+        alias: &str,
+        user_id: UserId,
+        option_until: Option<DateTime<Utc>>,
     ) -> Result<(), Error> {
-        info!("db::set_turn_notifications");
+        info!("db::set_player_vacation");
         let conn = &*self.0.clone().get()?;
-        conn.execute(
-            include_str!("db/sql/update_turn_notifications.sql"),
-            params![&(player.0 as i64), &desired_turn_notifications],
-        )?;
-        Ok(())
-    }
-
-    pub fn remove_started_state(&self, alias: &str) -> Result<(), Error> {
-        info!("remove_started_state");
-        let conn = &mut *self.0.clone().get()?;
-        let tx = conn.transaction()?;
-
-        let rows_modified = tx.execute(
-            include_str!("db/sql/update_game_with_null_started_state.sql"),
-            params![&alias],
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_server_player_vacation.sql"),
+            params![
+                &alias,
+                &(user_id.0 as i64),
+                &option_until.map(|until| until.timestamp())
+            ],
         )?;
-        tx.execute(include_str!("db/sql/delete_started_server.sql"), params![])?;
-        tx.commit()?;
-
-        if rows_modified == 0 {
-            Err(err_msg(format!(
-                "Could not find started lobby with alias '{}'",
-                alias
-            )))?
-        } else {
+        if rows_modified != 0 {
             Ok(())
+        } else {
+            Err(err_msg(format!("You are not registered in {}", alias)))
         }
     }
 
-    pub fn insert_started_state(
+    /// Players whose vacation hasn't ended by `now`, and when it does
+    pub fn player_vacations_for_alias(
         &self,
-        alias: &str,
-        started_state: &StartedState,
+        game_alias: &str,
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<UserId, DateTime<Utc>>, Error> {
+        info!("db::player_vacations_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_player_vacations.sql"))?;
+        let vacations = stmt
+            .query_map(params![&game_alias, &now.timestamp()], |row| {
+                let discord_user_id: i64 = row.get(0)?;
+                let until: i64 = row.get(1)?;
+                Ok((UserId(discord_user_id as u64), Utc.timestamp(until, 0)))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(vacations)
+    }
+
+    /// The player's own timezone, for every game they're in. `None` forgets it.
+    pub fn set_player_utc_offset(
+        &self,
+        user_id: UserId,
+        option_utc_offset: Option<FixedOffset>,
     ) -> Result<(), Error> {
-        info!("insert_started_state");
+        info!("db::set_player_utc_offset");
         let conn = &mut *self.0.clone().get()?;
         let tx = conn.transaction()?;
         tx.execute(
-            include_str!("db/sql/insert_started_state.sql"),
-            params![&started_state.address, &started_state.last_seen_turn],
+            include_str!("db/sql/insert_player.sql"),
+            params![&(user_id.0 as i64), &true],
         )?;
-
         tx.execute(
-            include_str!("db/sql/update_game_with_started_state.sql"),
+            include_str!("db/sql/update_player_utc_offset.sql"),
             params![
-                &started_state.address,
-                &started_state.last_seen_turn,
-                &alias,
+                &(user_id.0 as i64),
+                &option_utc_offset.map(|utc_offset| utc_offset.local_minus_utc())
             ],
         )?;
         tx.commit()?;
         Ok(())
     }
 
-    pub fn select_lobbies(&self) -> Result<Vec<(GameServer, i32)>, Error> {
-        info!("select_lobbies");
+    /// Players in the game who have said what timezone they're in
+    pub fn player_utc_offsets_for_alias(
+        &self,
+        game_alias: &str,
+    ) -> Result<HashMap<UserId, FixedOffset>, Error> {
+        info!("db::player_utc_offsets_for_alias");
         let conn = &*self.0.clone().get()?;
-        let mut stmt = conn.prepare(include_str!("db/sql/select_lobbies.sql"))?;
-        let foo = stmt.query_map(params![], |ref row| {
-            let alias: String = row.get(0).unwrap();
-            let maybe_owner: Option<i64> = row.get(1).unwrap();
-            let maybe_era: Option<i32> = row.get(2).unwrap();
-            let maybe_player_count: Option<i32> = row.get(3).unwrap();
+        let mut stmt = conn.prepare(include_str!("db/sql/select_player_utc_offsets.sql"))?;
+        let utc_offsets = stmt
+            .query_map(params![&game_alias], |row| {
+                let discord_user_id: i64 = row.get(0)?;
+                let utc_offset_secs: i32 = row.get(1)?;
+                Ok((UserId(discord_user_id as u64), utc_offset_secs))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(utc_offsets
+            .into_iter()
+            .filter_map(|(user_id, utc_offset_secs)| {
+                FixedOffset::east_opt(utc_offset_secs).map(|utc_offset| (user_id, utc_offset))
+            })
+            .collect())
+    }
+
+    /// At most this many of the game's countdown reminders each turn, `None` for all of them
+    /// `Some(None)` is every reminder whatever the game's default is, `None`
+    /// goes back to the game's default
+    pub fn set_reminder_cap(
+        &self,
+        alias: &str,
+        user_id: UserId,
+        option_cap: Option<Option<u32>>,
+    ) -> Result<(), Error> {
+        info!("db::set_reminder_cap");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_server_player_max_reminders.sql"),
+            params![
+                &alias,
+                &(user_id.0 as i64),
+                &option_cap.flatten(),
+                &option_cap.is_some()
+            ],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("You are not registered in {}", alias)))
+        }
+    }
+
+    /// Only the players who've set their own cap, `None` for the ones who want
+    /// every reminder
+    pub fn reminder_caps_for_alias(
+        &self,
+        game_alias: &str,
+    ) -> Result<HashMap<UserId, Option<u32>>, Error> {
+        info!("db::reminder_caps_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_reminder_caps.sql"))?;
+        let caps = stmt
+            .query_map(params![&game_alias], |row| {
+                let discord_user_id: i64 = row.get(0)?;
+                Ok((UserId(discord_user_id as u64), row.get(1)?))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(caps)
+    }
+
+    /// The cap for players in the game who haven't set their own, `None` is no cap
+    pub fn set_default_reminder_cap_for_alias(
+        &self,
+        alias: &str,
+        option_max_reminders: Option<u32>,
+    ) -> Result<(), Error> {
+        info!("db::set_default_reminder_cap_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_default_max_reminders.sql"),
+            params![&alias, &option_max_reminders],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    pub fn default_reminder_cap_for_alias(&self, alias: &str) -> Result<Option<u32>, Error> {
+        info!("db::default_reminder_cap_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_max_reminders = conn
+            .query_row(
+                include_str!("db/sql/select_default_max_reminders_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_max_reminders.flatten())
+    }
+
+    /// Everyone in the game, earliest first. Registrations from before this was
+    /// kept track of have no time and come last.
+    pub fn registrations_for_alias(&self, game_alias: &str) -> Result<Vec<Registration>, Error> {
+        info!("db::registrations_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_registrations.sql"))?;
+        let registrations = stmt
+            .query_map(params![&game_alias], |row| {
+                let nation_id: u32 = row.get(0)?;
+                let option_registered_at: Option<i64> = row.get(1)?;
+                Ok((
+                    nation_id,
+                    option_registered_at.map(|registered_at| Utc.timestamp(registered_at, 0)),
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(registrations)
+    }
+
+    pub fn game_for_alias(&self, game_alias: &str) -> Result<GameServer, Error> {
+        info!("db::game_for_alias");
+        // Let go of the connection before looking for suggestions, which needs one too
+        let vec = {
+            let conn = &*self.0.clone().get()?;
+            let mut stmt = conn.prepare(include_str!("db/sql/select_game_server_for_alias.sql"))?;
+            let foo = stmt.query_map(&[&game_alias], |ref row| {
+                let maybe_address: Option<String> = row.get(0).unwrap();
+                let maybe_last_seen_turn: Option<i32> = row.get(1).unwrap();
+                let maybe_owner: Option<i64> = row.get(2).unwrap();
+                let maybe_era: Option<i32> = row.get(3).unwrap();
+                let maybe_player_count: Option<i32> = row.get(4).unwrap();
+                let description: Option<String> = row.get(5).unwrap();
+                let maybe_snek_game_id: Option<i64> = row.get(6).unwrap();
+                let archived: bool = row.get(7).unwrap();
+                Ok(make_game_server(
+                    game_alias.to_owned(),
+                    maybe_address,
+                    maybe_last_seen_turn,
+                    maybe_owner,
+                    maybe_era,
+                    maybe_player_count,
+                    description,
+                    maybe_snek_game_id,
+                    archived,
+                )
+                .unwrap())
+            })?;
+            foo.collect::<Result<Vec<_>, _>>()?
+        };
+        if vec.len() == 1 {
+            Ok(vec
+                .into_iter()
+                .next()
+                .ok_or(err_msg("THIS SHOULD NEVER HAPPEN"))?) // TODO: *vomits*
+        } else {
+            // Only ever reached when there's no exact match
+            let suggestions = self.all_aliases()?;
+            let suggestions = closest_matches(game_alias, &suggestions, MAX_ALIAS_SUGGESTIONS);
+            if suggestions.is_empty() {
+                Err(err_msg(format!(
+                    "could not find the game with alias {}",
+                    game_alias
+                )))
+            } else {
+                Err(err_msg(format!(
+                    "could not find the game with alias {}, did you mean {}?",
+                    game_alias,
+                    suggestions.join(" or ")
+                )))
+            }
+        }
+    }
+
+    /// Every game's alias, alphabetically
+    pub fn all_aliases(&self) -> Result<Vec<String>, Error> {
+        info!("db::all_aliases");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_all_aliases.sql"))?;
+        let aliases = stmt
+            .query_map(params![], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(aliases)
+    }
+
+    /// Also records the time the new turn was first seen in the turn history,
+    /// along with how long its timer was if it had one.
+    /// A turn is only ever recorded once, however many times it gets seen.
+    pub fn update_game_with_possibly_new_turn(
+        &self,
+        game_alias: &str,
+        current_turn: i32,
+        option_timer_secs: Option<i32>,
+    ) -> Result<bool, Error> {
+        info!("db::update_game_with_possibly_new_turn");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let rows = tx.execute(
+            include_str!("db/sql/update_game_with_turn.sql"),
+            params![&current_turn, &game_alias],
+        )?;
+        if rows > 0 {
+            tx.execute(
+                include_str!("db/sql/insert_turn_history.sql"),
+                params![
+                    &game_alias,
+                    &current_turn,
+                    &Utc::now().timestamp(),
+                    &option_timer_secs
+                ],
+            )?;
+        }
+        tx.commit()?;
+        info!("db::update_game_with_possibly_new_turn FINISHED");
+        Ok(rows > 0)
+    }
+
+    /// The server is back on `current_turn` after we'd seen a later one, e.g. the
+    /// host restored a backup. Forgets the turns since so that they're new turns
+    /// again when they're replayed. False if `current_turn` isn't behind.
+    pub fn roll_back_to_turn(&self, game_alias: &str, current_turn: i32) -> Result<bool, Error> {
+        info!("db::roll_back_to_turn");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let rows = tx.execute(
+            include_str!("db/sql/update_game_with_rolled_back_turn.sql"),
+            params![&current_turn, &game_alias],
+        )?;
+        if rows > 0 {
+            tx.execute(
+                include_str!("db/sql/delete_turn_history_after.sql"),
+                params![&game_alias, &current_turn],
+            )?;
+        }
+        tx.commit()?;
+        Ok(rows > 0)
+    }
+
+    /// Remembers that we've said `turn` is stalled. False if we already had,
+    /// so that each stall only gets one alert.
+    pub fn mark_stall_alerted(&self, game_alias: &str, turn: i32) -> Result<bool, Error> {
+        info!("db::mark_stall_alerted");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_stall_alerted_turn.sql"),
+            params![&game_alias, &turn],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// Remembers that we've said everyone has played `turn`. False if we already
+    /// had, so a slow host only gets one alert a turn.
+    pub fn mark_waiting_on_host_alerted(&self, game_alias: &str, turn: i32) -> Result<bool, Error> {
+        info!("db::mark_waiting_on_host_alerted");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_waiting_on_host_alerted_turn.sql"),
+            params![&game_alias, &turn],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// True if this is the first countdown ping for `turn` at `mins` or fewer minutes
+    /// left, so that each threshold only fires once and never after a closer one
+    pub fn mark_countdown_pinged(
+        &self,
+        game_alias: &str,
+        turn: i32,
+        mins: u32,
+    ) -> Result<bool, Error> {
+        info!("db::mark_countdown_pinged");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_countdown_pinged.sql"),
+            params![&game_alias, &turn, &mins],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// When the game first had `pretenders` pretenders in. Seeing a different number
+    /// starts the wait again and allows another start reminder.
+    pub fn update_game_with_pretenders_seen(
+        &self,
+        game_alias: &str,
+        pretenders: i32,
+        now: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, Error> {
+        info!("db::update_game_with_pretenders_seen");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/update_game_with_pretenders_seen.sql"),
+            params![&game_alias, &pretenders, &now.timestamp()],
+        )?;
+        let timestamp: i64 = conn.query_row(
+            include_str!("db/sql/select_pretenders_seen_at_for_alias.sql"),
+            params![&game_alias],
+            |row| row.get(0),
+        )?;
+        Ok(Utc.timestamp(timestamp, 0))
+    }
+
+    /// The game has started, so there's nothing to wait on any more
+    pub fn clear_pretenders_seen(&self, game_alias: &str) -> Result<(), Error> {
+        info!("db::clear_pretenders_seen");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/update_game_clear_pretenders_seen.sql"),
+            params![&game_alias],
+        )?;
+        Ok(())
+    }
+
+    /// False if we've already reminded the host since the pretenders last changed
+    pub fn mark_start_reminded(&self, game_alias: &str) -> Result<bool, Error> {
+        info!("db::mark_start_reminded");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_start_reminded.sql"),
+            params![&game_alias],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// How long all the pretenders have to be in before reminding the host to start
+    /// the game. 0 turns the reminder off, `None` goes back to the default.
+    pub fn set_start_reminder_hours_for_alias(
+        &self,
+        alias: &str,
+        option_hours: Option<u32>,
+    ) -> Result<(), Error> {
+        info!("db::set_start_reminder_hours_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_start_reminder_hours.sql"),
+            params![&alias, &option_hours],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    pub fn start_reminder_hours_for_alias(&self, alias: &str) -> Result<Option<u32>, Error> {
+        info!("db::start_reminder_hours_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_hours: Option<u32> = conn
+            .query_row(
+                include_str!("db/sql/select_start_reminder_hours_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_hours)
+    }
+
+    /// How far past its usual turn length a turn has to go before it's called
+    /// stalled. `None` goes back to the default.
+    pub fn set_stall_grace_hours_for_alias(
+        &self,
+        alias: &str,
+        option_hours: Option<u32>,
+    ) -> Result<(), Error> {
+        info!("db::set_stall_grace_hours_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_stall_grace_hours.sql"),
+            params![&alias, &option_hours],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    pub fn stall_grace_hours_for_alias(&self, alias: &str) -> Result<Option<u32>, Error> {
+        info!("db::stall_grace_hours_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_hours: Option<u32> = conn
+            .query_row(
+                include_str!("db/sql/select_stall_grace_hours_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_hours)
+    }
+
+    /// `None` goes back to the default
+    pub fn set_poll_interval_secs_for_alias(
+        &self,
+        alias: &str,
+        option_secs: Option<u32>,
+    ) -> Result<(), Error> {
+        info!("db::set_poll_interval_secs_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_poll_interval.sql"),
+            params![&alias, &option_secs],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    /// See `!blitz`
+    pub fn set_blitz_for_alias(&self, alias: &str, blitz: bool) -> Result<(), Error> {
+        info!("db::set_blitz_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_blitz.sql"),
+            params![&alias, &blitz],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    /// False for games that don't exist
+    pub fn blitz_for_alias(&self, alias: &str) -> Result<bool, Error> {
+        info!("db::blitz_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_blitz: Option<bool> = conn
+            .query_row(
+                include_str!("db/sql/select_blitz_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_blitz.unwrap_or(false))
+    }
+
+    /// Remembers when we first saw the game playing, unless we already have
+    pub fn record_first_seen(
+        &self,
+        alias: &str,
+        at: DateTime<Utc>,
+        turn: i32,
+    ) -> Result<(), Error> {
+        info!("db::record_first_seen");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/update_game_with_first_seen.sql"),
+            params![&alias, &at.timestamp(), &turn],
+        )?;
+        Ok(())
+    }
+
+    /// `None` until the poller has seen the game playing
+    pub fn first_seen_for_alias(&self, alias: &str) -> Result<Option<FirstSeen>, Error> {
+        info!("db::first_seen_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_first_seen = conn
+            .query_row(
+                include_str!("db/sql/select_first_seen_for_alias.sql"),
+                params![&alias],
+                |row| {
+                    let at: i64 = row.get(0)?;
+                    Ok(FirstSeen {
+                        at: Utc.timestamp(at, 0),
+                        turn: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(option_first_seen)
+    }
+
+    /// Every game in blitz mode, for the poller
+    pub fn blitz_aliases(&self) -> Result<Vec<String>, Error> {
+        info!("db::blitz_aliases");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_blitz_aliases.sql"))?;
+        let aliases = stmt
+            .query_map(params![], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(aliases)
+    }
+
+    /// Stops the poller checking the game until `option_until`, or until it's
+    /// unpaused if that's `None`
+    pub fn set_polling_paused_for_alias(
+        &self,
+        alias: &str,
+        paused: bool,
+        option_until: Option<DateTime<Utc>>,
+    ) -> Result<(), Error> {
+        info!("db::set_polling_paused_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_polling_pause.sql"),
+            params![
+                &alias,
+                &paused,
+                &option_until.map(|until| until.timestamp())
+            ],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    /// Games the poller should skip at `now`, and when they start being polled
+    /// again. `None` is whenever someone unpauses it.
+    pub fn polling_pauses(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<String, Option<DateTime<Utc>>>, Error> {
+        info!("db::polling_pauses");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_polling_pauses.sql"))?;
+        let pauses = stmt
+            .query_map(params![&now.timestamp()], |row| {
+                let alias: String = row.get(0)?;
+                let option_until: Option<i64> = row.get(1)?;
+                Ok((alias, option_until.map(|until| Utc.timestamp(until, 0))))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(pauses)
+    }
+
+    /// Only started games can be archived, lobbies have nothing to finish
+    pub fn set_archived_for_alias(&self, alias: &str, archived: bool) -> Result<(), Error> {
+        info!("db::set_archived_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_archived.sql"),
+            params![&alias, &archived],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!(
+                "Could not find started game with name {}",
+                alias
+            )))
+        }
+    }
+
+    /// Only the games that don't use the default
+    pub fn poll_intervals_secs(&self) -> Result<HashMap<String, u32>, Error> {
+        info!("db::poll_intervals_secs");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_poll_intervals.sql"))?;
+        let rows = stmt.query_map(params![], |row| {
+            let alias: String = row.get(0)?;
+            let secs: u32 = row.get(1)?;
+            Ok((alias, secs))
+        })?;
+        let map = rows.collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(map)
+    }
+
+    /// How many minutes before the deadline to ping undone players, most first.
+    /// `None` turns the pings off.
+    pub fn set_countdown_mins_for_alias(
+        &self,
+        alias: &str,
+        option_countdown_mins: Option<&[u32]>,
+    ) -> Result<(), Error> {
+        info!("db::set_countdown_mins_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_text = option_countdown_mins.map(|countdown_mins| {
+            countdown_mins
+                .iter()
+                .map(|mins| mins.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_countdown_mins.sql"),
+            params![&alias, &option_text],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    pub fn countdown_mins_for_alias(&self, alias: &str) -> Result<Option<Vec<u32>>, Error> {
+        info!("db::countdown_mins_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_text: Option<String> = conn
+            .query_row(
+                include_str!("db/sql/select_countdown_mins_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match option_text {
+            Some(text) => {
+                let countdown_mins = text
+                    .split(',')
+                    .map(|mins| mins.parse::<u32>())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Some(countdown_mins))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// `turn` hosted before its timer ran out because everyone was done
+    pub fn mark_turn_quickhosted(&self, game_alias: &str, turn: i32) -> Result<(), Error> {
+        info!("db::mark_turn_quickhosted");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/update_turn_history_with_quickhosted.sql"),
+            params![&game_alias, &turn],
+        )?;
+        Ok(())
+    }
+
+    /// Remembers when we saw `nation_id` get its turn in, keeping only the last
+    /// `keep_turns` turns of them. False if we'd already seen it this turn.
+    pub fn record_submission(
+        &self,
+        game_alias: &str,
+        turn: i32,
+        nation_id: u32,
+        seen_at: DateTime<Utc>,
+        keep_turns: i32,
+    ) -> Result<bool, Error> {
+        info!("db::record_submission");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let rows_modified = tx.execute(
+            include_str!("db/sql/insert_submission_history.sql"),
+            params![&game_alias, &turn, &nation_id, &seen_at.timestamp()],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_old_submission_history.sql"),
+            params![&game_alias, &(turn - keep_turns)],
+        )?;
+        tx.commit()?;
+        Ok(rows_modified > 0)
+    }
+
+    /// (turn, nation ID, when we saw it submit), in the order they came in
+    pub fn select_submission_history(
+        &self,
+        game_alias: &str,
+    ) -> Result<Vec<(i32, u32, DateTime<Utc>)>, Error> {
+        info!("db::select_submission_history");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_submission_history.sql"))?;
+        let rows = stmt.query_map(params![&game_alias], |row| {
+            let turn: i32 = row.get(0)?;
+            let nation_id: u32 = row.get(1)?;
+            let timestamp: i64 = row.get(2)?;
+            Ok((turn, nation_id, Utc.timestamp(timestamp, 0)))
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Only the first turn we saw a nation dead is kept
+    /// Every nation's status as of `turn`, replacing anything recorded for it before
+    pub fn record_nation_statuses(
+        &self,
+        game_alias: &str,
+        turn: i32,
+        statuses: &[(u32, String, NationStatus)],
+    ) -> Result<(), Error> {
+        info!("db::record_nation_statuses");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        for (nation_id, nation_name, status) in statuses {
+            tx.execute(
+                include_str!("db/sql/upsert_nation_status.sql"),
+                params![&game_alias, &turn, nation_id, nation_name, &(*status as u8)],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The turns we have nation statuses for, oldest first
+    pub fn nation_status_turns(&self, game_alias: &str) -> Result<Vec<i32>, Error> {
+        info!("db::nation_status_turns");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_nation_status_turns.sql"))?;
+        let turns = stmt
+            .query_map(params![&game_alias], |row| row.get(0))?
+            .collect::<Result<Vec<i32>, _>>()?;
+        Ok(turns)
+    }
+
+    pub fn nation_statuses(
+        &self,
+        game_alias: &str,
+        turn: i32,
+    ) -> Result<Vec<(u32, String, NationStatus)>, Error> {
+        info!("db::nation_statuses");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_nation_statuses.sql"))?;
+        let rows = stmt
+            .query_map(params![&game_alias, &turn], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, u8>(2)?))
+            })?
+            .collect::<Result<Vec<(u32, String, u8)>, _>>()?;
+        rows.into_iter()
+            .map(|(nation_id, nation_name, status)| {
+                let status = NationStatus::from_int(status)
+                    .ok_or_else(|| err_msg(format!("Unknown nation status {}", status)))?;
+                Ok((nation_id, nation_name, status))
+            })
+            .collect()
+    }
+
+    pub fn record_elimination(
+        &self,
+        game_alias: &str,
+        turn: i32,
+        nation_id: u32,
+        nation_name: &str,
+    ) -> Result<(), Error> {
+        info!("db::record_elimination");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/insert_nation_elimination.sql"),
+            params![&game_alias, &turn, &nation_id, &nation_name],
+        )?;
+        Ok(())
+    }
+
+    /// (turn, nation ID, nation name), earliest first
+    pub fn select_eliminations(&self, game_alias: &str) -> Result<Vec<(i32, u32, String)>, Error> {
+        info!("db::select_eliminations");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_nation_eliminations.sql"))?;
+        let rows = stmt.query_map(params![&game_alias], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Whether a registered player had their turn in when it hosted. Only the
+    /// first look at each turn counts.
+    pub fn record_final_submission(
+        &self,
+        game_alias: &str,
+        turn: i32,
+        user_id: UserId,
+        nation_id: u32,
+        submitted: bool,
+    ) -> Result<(), Error> {
+        info!("db::record_final_submission");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/insert_final_submission.sql"),
+            params![
+                &game_alias,
+                &turn,
+                &(user_id.0 as i64),
+                &nation_id,
+                &submitted
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// (turn, player, nation ID, whether they were in), oldest turn first
+    pub fn select_final_submissions(
+        &self,
+        game_alias: &str,
+    ) -> Result<Vec<FinalSubmission>, Error> {
+        info!("db::select_final_submissions");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_final_submissions.sql"))?;
+        let rows = stmt.query_map(params![&game_alias], |row| {
+            let discord_user_id: i64 = row.get(1)?;
+            Ok((
+                row.get(0)?,
+                UserId(discord_user_id as u64),
+                row.get(2)?,
+                row.get(3)?,
+            ))
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// The poller's last good poll of the game, so it can pick up where it left
+    /// off after a restart
+    pub fn save_cache_entry(
+        &self,
+        game_alias: &str,
+        cache_entry: &CacheEntry,
+    ) -> Result<(), Error> {
+        info!("db::save_cache_entry");
+        let conn = &*self.0.clone().get()?;
+        let text = serde_json::to_string(cache_entry)?;
+        conn.execute(
+            include_str!("db/sql/upsert_cached_poll.sql"),
+            params![&game_alias, &text],
+        )?;
+        Ok(())
+    }
+
+    /// Every game that's still being polled and has been polled before
+    pub fn cache_entries(&self) -> Result<Vec<(String, CacheEntry)>, Error> {
+        info!("db::cache_entries");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_cached_polls.sql"))?;
+        let rows = stmt.query_map(params![], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut ret = vec![];
+        for row in rows {
+            let (alias, text) = row?;
+            ret.push((alias, serde_json::from_str(&text)?));
+        }
+        Ok(ret)
+    }
+
+    /// Which nations had submitted `turn` when the player last ran `!progress`,
+    /// replacing any earlier bookmark for the game
+    pub fn set_progress_bookmark(
+        &self,
+        game_alias: &str,
+        user_id: UserId,
+        turn: i32,
+        submitted_nation_ids: &[u32],
+    ) -> Result<(), Error> {
+        info!("db::set_progress_bookmark");
+        let conn = &*self.0.clone().get()?;
+        let text = submitted_nation_ids
+            .iter()
+            .map(|nation_id| nation_id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        conn.execute(
+            include_str!("db/sql/upsert_progress_bookmark.sql"),
+            params![&game_alias, &(user_id.0 as i64), &turn, &text],
+        )?;
+        Ok(())
+    }
+
+    pub fn progress_bookmark(
+        &self,
+        game_alias: &str,
+        user_id: UserId,
+    ) -> Result<Option<(i32, Vec<u32>)>, Error> {
+        info!("db::progress_bookmark");
+        let conn = &*self.0.clone().get()?;
+        let option_row: Option<(i32, String)> = conn
+            .query_row(
+                include_str!("db/sql/select_progress_bookmark.sql"),
+                params![&game_alias, &(user_id.0 as i64)],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        match option_row {
+            Some((turn, text)) => {
+                let submitted_nation_ids = text
+                    .split(',')
+                    .filter(|nation_id| !nation_id.is_empty())
+                    .map(|nation_id| nation_id.parse::<u32>())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Some((turn, submitted_nation_ids)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The lobby's expected players and waitlist, in the order they joined
+    pub fn roster_for_alias(&self, game_alias: &str) -> Result<Vec<RosterEntry>, Error> {
+        info!("db::roster_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_roster_for_alias.sql"))?;
+        let roster = stmt
+            .query_map(params![&game_alias], |row| {
+                let user_id: i64 = row.get(0)?;
+                Ok(RosterEntry {
+                    user_id: UserId(user_id as u64),
+                    waitlisted: row.get(1)?,
+                    option_nation_id: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(roster)
+    }
+
+    /// Adds them to the lobby's roster, or moves them between it and the waitlist.
+    /// Keeps their place in the queue either way.
+    pub fn set_roster_entry(&self, game_alias: &str, entry: &RosterEntry) -> Result<(), Error> {
+        info!("db::set_roster_entry");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_roster_entry.sql"),
+            params![
+                &game_alias,
+                &(entry.user_id.0 as i64),
+                &entry.waitlisted,
+                &entry.option_nation_id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// False if they weren't on the roster or waitlist
+    pub fn remove_roster_entry(&self, game_alias: &str, user_id: UserId) -> Result<bool, Error> {
+        info!("db::remove_roster_entry");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/delete_roster_entry.sql"),
+            params![&game_alias, &(user_id.0 as i64)],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// Each player has at most one pending reminder per game, a new one replaces it
+    pub fn set_one_off_reminder(
+        &self,
+        game_alias: &str,
+        user_id: UserId,
+        fire_at: DateTime<Utc>,
+        message: &str,
+    ) -> Result<(), Error> {
+        info!("db::set_one_off_reminder");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_one_off_reminder.sql"),
+            params![
+                &game_alias,
+                &(user_id.0 as i64),
+                &fire_at.timestamp(),
+                &message
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// False if there wasn't one to cancel
+    pub fn cancel_one_off_reminder(
+        &self,
+        game_alias: &str,
+        user_id: UserId,
+    ) -> Result<bool, Error> {
+        info!("db::cancel_one_off_reminder");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/delete_one_off_reminder.sql"),
+            params![&game_alias, &(user_id.0 as i64)],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// Forgets every `!remindercap` and `!remindme` the player has, returning
+    /// how many games that was across. The games' own defaults still apply.
+    pub fn clear_all_reminders(&self, user_id: UserId) -> Result<usize, Error> {
+        info!("db::clear_all_reminders");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let aliases = {
+            let mut stmt =
+                tx.prepare(include_str!("db/sql/select_personal_reminder_aliases.sql"))?;
+            let rows = stmt.query_map(params![&(user_id.0 as i64)], |row| row.get(0))?;
+            rows.collect::<Result<Vec<String>, _>>()?
+        };
+        tx.execute(
+            include_str!("db/sql/update_server_players_clear_max_reminders.sql"),
+            params![&(user_id.0 as i64)],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_one_off_reminders_for_user.sql"),
+            params![&(user_id.0 as i64)],
+        )?;
+        tx.commit()?;
+        Ok(aliases.len())
+    }
+
+    /// Reminders due by `now`, deleted as they're taken so that each only goes out once
+    pub fn take_due_one_off_reminders(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<(UserId, String)>, Error> {
+        info!("db::take_due_one_off_reminders");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let due = {
+            let mut stmt = tx.prepare(include_str!("db/sql/select_due_one_off_reminders.sql"))?;
+            let rows = stmt.query_map(params![&now.timestamp()], |row| {
+                let id: i64 = row.get(0)?;
+                let user_id: i64 = row.get(1)?;
+                let message: String = row.get(2)?;
+                Ok((id, UserId(user_id as u64), message))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        for (id, _, _) in &due {
+            tx.execute(
+                include_str!("db/sql/delete_one_off_reminder_by_id.sql"),
+                params![id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(due
+            .into_iter()
+            .map(|(_, user_id, message)| (user_id, message))
+            .collect())
+    }
+
+    pub fn select_quickhosted_turns(&self, game_alias: &str) -> Result<Vec<i32>, Error> {
+        info!("db::select_quickhosted_turns");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_quickhosted_turns.sql"))?;
+        let turns = stmt
+            .query_map(params![&game_alias], |row| row.get(0))?
+            .collect::<Result<Vec<i32>, _>>()?;
+        Ok(turns)
+    }
+
+    /// The most recent `limit` turns we've seen start, oldest first
+    pub fn select_turn_history(
+        &self,
+        game_alias: &str,
+        limit: u32,
+    ) -> Result<Vec<(i32, DateTime<Utc>)>, Error> {
+        info!("db::select_turn_history");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_turn_history.sql"))?;
+        let rows = stmt.query_map(params![&game_alias, &limit], |row| {
+            let turn: i32 = row.get(0)?;
+            let timestamp: i64 = row.get(1)?;
+            Ok((turn, Utc.timestamp(timestamp, 0)))
+        })?;
+        let mut vec = rows.collect::<Result<Vec<_>, _>>()?;
+        vec.reverse();
+        Ok(vec)
+    }
+
+    /// How long each turn's timer was when it started, for turns that had one
+    pub fn select_turn_timers(&self, game_alias: &str) -> Result<HashMap<i32, i32>, Error> {
+        info!("db::select_turn_timers");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_turn_timers.sql"))?;
+        let rows = stmt.query_map(params![&game_alias], |row| {
+            let turn: i32 = row.get(0)?;
+            let timer_secs: i32 = row.get(1)?;
+            Ok((turn, timer_secs))
+        })?;
+        let map = rows.collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(map)
+    }
+
+    /// Called every time the poller runs. If it's been longer than
+    /// `downtime_threshold` since the last time, the bot (or at least the poller)
+    /// must have been down in between so we remember that gap.
+    pub fn record_poller_heartbeat(
+        &self,
+        now: DateTime<Utc>,
+        downtime_threshold: chrono::Duration,
+    ) -> Result<(), Error> {
+        info!("db::record_poller_heartbeat");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let option_last_heartbeat: Option<i64> = tx
+            .query_row(
+                include_str!("db/sql/select_poller_heartbeat.sql"),
+                params![],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(last_heartbeat) = option_last_heartbeat {
+            if now.timestamp() - last_heartbeat > downtime_threshold.num_seconds() {
+                tx.execute(
+                    include_str!("db/sql/insert_downtime.sql"),
+                    params![&last_heartbeat, &now.timestamp()],
+                )?;
+            }
+        }
+        tx.execute(
+            include_str!("db/sql/upsert_poller_heartbeat.sql"),
+            params![&now.timestamp()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Periods where the poller wasn't running, oldest first
+    pub fn select_downtimes(&self) -> Result<Vec<Downtime>, Error> {
+        info!("db::select_downtimes");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_downtimes.sql"))?;
+        let rows = stmt.query_map(params![], |row| {
+            let started_at: i64 = row.get(0)?;
+            let ended_at: i64 = row.get(1)?;
+            Ok((Utc.timestamp(started_at, 0), Utc.timestamp(ended_at, 0)))
+        })?;
+        let vec = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(vec)
+    }
+
+    pub fn remove_player_from_game(&self, game_alias: &str, user: UserId) -> Result<usize, Error> {
+        info!("db::remove_player_from_game");
+        let conn = &*self.0.clone().get()?;
+        Ok(conn.execute(
+            include_str!("db/sql/delete_player_from_game.sql"),
+            params![&game_alias, &(user.0 as i64)],
+        )?)
+    }
+
+    /// Whoever is registered as `nation_id`, unlike `remove_player_from_game`
+    /// which removes all of one player's registrations
+    pub fn remove_nation_from_game(
+        &self,
+        game_alias: &str,
+        nation_id: u32,
+    ) -> Result<usize, Error> {
+        info!("db::remove_nation_from_game");
+        let conn = &*self.0.clone().get()?;
+        Ok(conn.execute(
+            include_str!("db/sql/delete_nation_from_game.sql"),
+            params![&game_alias, &nation_id],
+        )?)
+    }
+
+    /// Registers `user` in `to_alias` as the same nation they have in
+    /// `from_alias`, keeping when they first registered. Unless `keep_original`,
+    /// they're taken out of `from_alias` in the same go, so a failed move leaves
+    /// them where they were.
+    pub fn copy_player_between_games(
+        &self,
+        from_alias: &str,
+        to_alias: &str,
+        user: UserId,
+        keep_original: bool,
+    ) -> Result<(), Error> {
+        info!("db::copy_player_between_games");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let rows_modified = tx.execute(
+            include_str!("db/sql/insert_server_player_from_game.sql"),
+            params![&from_alias, &to_alias, &(user.0 as i64)],
+        )?;
+        if rows_modified == 0 {
+            return Err(err_msg(format!(
+                "Could not register <@{}> in {}, has someone else taken the nation?",
+                user.0, to_alias
+            )));
+        }
+        if !keep_original {
+            tx.execute(
+                include_str!("db/sql/delete_player_from_game.sql"),
+                params![&from_alias, &(user.0 as i64)],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Hands every nation `from` has in `alias` to `to`, returning which ones.
+    /// Their reminder caps and when they registered stay with the nation, the
+    /// nickname and vacation were `from`'s so they go.
+    pub fn reassign_player_nations(
+        &self,
+        alias: &str,
+        from: UserId,
+        to: UserId,
+    ) -> Result<Vec<u32>, Error> {
+        info!("db::reassign_player_nations");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let nation_ids = {
+            let mut stmt = tx.prepare(include_str!("db/sql/select_player_nations_for_game.sql"))?;
+            let rows = stmt.query_map(params![&alias, &(from.0 as i64)], |row| {
+                let nation_id: i32 = row.get(0)?;
+                Ok(nation_id as u32)
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        if nation_ids.is_empty() {
+            return Err(err_msg(format!(
+                "<@{}> isn't registered in {}",
+                from.0, alias
+            )));
+        }
+        tx.execute(
+            include_str!("db/sql/insert_player.sql"),
+            params![&(to.0 as i64), &true],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_server_players_reassign.sql"),
+            params![&alias, &(from.0 as i64), &(to.0 as i64)],
+        )?;
+        tx.commit()?;
+        Ok(nation_ids)
+    }
+
+    pub fn remove_server(&self, game_alias: &str) -> Result<(), Error> {
+        info!("db::remove_server");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            include_str!("db/sql/delete_server_players.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_turn_history.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_ai_reserved_nations.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_lobby_nation_set.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_channel_subscriptions.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_spectators.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_nation_watches.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_turn_notes.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_nation_names.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_submission_history.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_nation_eliminations.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_final_submissions.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_progress_bookmarks.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_cached_poll.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_one_off_reminders.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_status_messages.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_lobby_roster.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/delete_nation_status_history.sql"),
+            params![&game_alias],
+        )?;
+        let rows_modified = tx.execute(
+            include_str!("db/sql/delete_game_server.sql"),
+            params![&game_alias],
+        )?;
+        tx.execute(include_str!("db/sql/delete_started_server.sql"), params![])?;
+        tx.execute(include_str!("db/sql/delete_lobby.sql"), params![])?;
+        if rows_modified != 0 {
+            tx.commit()?;
+            Ok(())
+        } else {
+            Err(err_msg(format!(
+                "Could not find server with name {}",
+                game_alias
+            )))
+        }
+    }
+
+    /// Nations the lobby's owner is keeping for the AI. False if it already was.
+    pub fn reserve_nation_for_ai(&self, game_alias: &str, nation_id: u32) -> Result<bool, Error> {
+        info!("db::reserve_nation_for_ai");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/insert_ai_reserved_nation.sql"),
+            params![&game_alias, &nation_id],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// False if it wasn't reserved
+    pub fn unreserve_nation_for_ai(&self, game_alias: &str, nation_id: u32) -> Result<bool, Error> {
+        info!("db::unreserve_nation_for_ai");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/delete_ai_reserved_nation.sql"),
+            params![&game_alias, &nation_id],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    pub fn ai_reserved_nations_for_alias(&self, game_alias: &str) -> Result<Vec<u32>, Error> {
+        info!("db::ai_reserved_nations_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_ai_reserved_nations.sql"))?;
+        let nation_ids = stmt
+            .query_map(params![&game_alias], |row| row.get(0))?
+            .collect::<Result<Vec<u32>, _>>()?;
+        Ok(nation_ids)
+    }
+
+    /// The nations the lobby's owner means to be played, replacing any set
+    /// before. Empty to go back to just having a number of slots.
+    pub fn set_lobby_nation_set(&self, game_alias: &str, nation_ids: &[u32]) -> Result<(), Error> {
+        info!("db::set_lobby_nation_set");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            include_str!("db/sql/delete_lobby_nation_set.sql"),
+            params![&game_alias],
+        )?;
+        for nation_id in nation_ids {
+            tx.execute(
+                include_str!("db/sql/insert_lobby_nation.sql"),
+                params![&game_alias, nation_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn lobby_nation_set_for_alias(&self, game_alias: &str) -> Result<Vec<u32>, Error> {
+        info!("db::lobby_nation_set_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_lobby_nation_set.sql"))?;
+        let nation_ids = stmt
+            .query_map(params![&game_alias], |row| row.get(0))?
+            .collect::<Result<Vec<u32>, _>>()?;
+        Ok(nation_ids)
+    }
+
+    pub fn servers_for_player(&self, user_id: UserId) -> Result<Vec<(GameServer, u32)>, Error> {
+        info!("servers_for_player");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_servers_for_player.sql"))?;
+
+        let foo = stmt.query_map(&[&(user_id.0 as i64)], |ref row| {
+            let alias: String = row.get(1).unwrap();
+            let maybe_address: Option<String> = row.get(0).unwrap();
+            let maybe_last_seen_turn: Option<i32> = row.get(2).unwrap();
+            let maybe_owner: Option<i64> = row.get(4).unwrap();
+            let maybe_era: Option<i32> = row.get(5).unwrap();
+            let maybe_player_count: Option<i32> = row.get(6).unwrap();
+            let description: Option<String> = row.get(7).unwrap();
+            let maybe_snek_game_id: Option<i64> = row.get(8).unwrap();
+            let archived: bool = row.get(9).unwrap();
+            let server = make_game_server(
+                alias,
+                maybe_address,
+                maybe_last_seen_turn,
+                maybe_owner,
+                maybe_era,
+                maybe_player_count,
+                description,
+                maybe_snek_game_id,
+                archived,
+            )
+            .unwrap();
+
+            let nation_id: i32 = row.get(3).unwrap();
+            Ok((server, nation_id as u32))
+        })?;
+
+        let mut ret: Vec<(GameServer, u32)> = vec![];
+        for pair in foo {
+            ret.push(pair?);
+        }
+
+        Ok(ret)
+    }
+
+    pub fn set_turn_notifications(
+        &self,
+        player: UserId,
+        desired_turn_notifications: bool,
+    ) -> Result<(), Error> {
+        info!("db::set_turn_notifications");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/update_turn_notifications.sql"),
+            params![&(player.0 as i64), &desired_turn_notifications],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_started_state(&self, alias: &str) -> Result<(), Error> {
+        info!("remove_started_state");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+
+        let rows_modified = tx.execute(
+            include_str!("db/sql/update_game_with_null_started_state.sql"),
+            params![&alias],
+        )?;
+        tx.execute(include_str!("db/sql/delete_started_server.sql"), params![])?;
+        tx.commit()?;
+
+        if rows_modified == 0 {
+            Err(err_msg(format!(
+                "Could not find started lobby with alias '{}'",
+                alias
+            )))?
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn insert_started_state(
+        &self,
+        alias: &str,
+        started_state: &StartedState,
+    ) -> Result<(), Error> {
+        info!("insert_started_state");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            include_str!("db/sql/insert_started_state.sql"),
+            params![&started_state.address, &started_state.last_seen_turn],
+        )?;
+
+        tx.execute(
+            include_str!("db/sql/update_game_with_started_state.sql"),
+            params![
+                &started_state.address,
+                &started_state.last_seen_turn,
+                &alias,
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn select_lobbies(&self) -> Result<Vec<(GameServer, i32)>, Error> {
+        info!("select_lobbies");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_lobbies.sql"))?;
+        let foo = stmt.query_map(params![], |ref row| {
+            let alias: String = row.get(0).unwrap();
+            let maybe_owner: Option<i64> = row.get(1).unwrap();
+            let maybe_era: Option<i32> = row.get(2).unwrap();
+            let maybe_player_count: Option<i32> = row.get(3).unwrap();
             let registered_player_count: i32 = row.get(4).unwrap();
             let description: Option<String> = row.get(5).unwrap();
             let server = make_game_server(
@@ -452,33 +2111,1167 @@ impl DbConnection {
                 maybe_era,
                 maybe_player_count,
                 description,
+                None,
+                false,
+            )
+            .unwrap();
+            Ok((server, registered_player_count))
+        })?;
+        let vec = foo.collect::<Result<Vec<_>, _>>()?;
+        Ok(vec)
+    }
+
+    /// The channel a game was created in, which is where we post about it
+    pub fn set_channel_for_alias(&self, alias: &str, channel_id: ChannelId) -> Result<(), Error> {
+        info!("db::set_channel_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_channel.sql"),
+            params![&alias, &(channel_id.0 as i64)],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    pub fn channel_for_alias(&self, alias: &str) -> Result<Option<ChannelId>, Error> {
+        info!("db::channel_for_alias");
+        let conn = &*self.0.clone().get()?;
+        // Games added before we kept track of channels have a NULL channel
+        let channel_id: Option<Option<i64>> = conn
+            .query_row(
+                include_str!("db/sql/select_channel_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(channel_id
+            .and_then(|channel_id| channel_id)
+            .map(|channel_id| ChannelId(channel_id as u64)))
+    }
+
+    pub fn set_icon_for_alias(&self, alias: &str, icon: &GameIcon) -> Result<(), Error> {
+        info!("db::set_icon_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_icon.sql"),
+            params![
+                &alias,
+                &icon.option_emoji,
+                &icon.option_colour.map(i64::from)
+            ],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    /// Games without one, or that don't exist, get the default look
+    pub fn icon_for_alias(&self, alias: &str) -> Result<GameIcon, Error> {
+        info!("db::icon_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_icon = conn
+            .query_row(
+                include_str!("db/sql/select_icon_for_alias.sql"),
+                params![&alias],
+                |row| {
+                    let option_colour: Option<i64> = row.get(1)?;
+                    Ok(GameIcon {
+                        option_emoji: row.get(0)?,
+                        option_colour: option_colour.map(|colour| colour as u32),
+                    })
+                },
+            )
+            .optional()?;
+        Ok(option_icon.unwrap_or_default())
+    }
+
+    pub fn set_notification_routes_for_alias(
+        &self,
+        alias: &str,
+        routes: &NotificationRoutes,
+    ) -> Result<(), Error> {
+        info!("db::set_notification_routes_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_notification_routes.sql"),
+            params![
+                &alias,
+                &routes.new_turn.to_i32(),
+                &routes.stall.to_i32(),
+                &routes.reminder.to_i32(),
+                &routes.waiting_on_host.to_i32()
+            ],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    /// Anything that hasn't been set, or a game that doesn't exist, gets the
+    /// default route
+    pub fn notification_routes_for_alias(&self, alias: &str) -> Result<NotificationRoutes, Error> {
+        info!("db::notification_routes_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_routes: Option<[Option<i32>; 4]> = conn
+            .query_row(
+                include_str!("db/sql/select_notification_routes_for_alias.sql"),
+                params![&alias],
+                |row| Ok([row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?]),
+            )
+            .optional()?;
+        let defaults = NotificationRoutes::default();
+        let [new_turn, stall, reminder, waiting_on_host] =
+            option_routes.unwrap_or([None, None, None, None]);
+        let route = |option_route: Option<i32>, default: NotificationRoute| {
+            option_route
+                .and_then(NotificationRoute::from_i32)
+                .unwrap_or(default)
+        };
+        Ok(NotificationRoutes {
+            new_turn: route(new_turn, defaults.new_turn),
+            stall: route(stall, defaults.stall),
+            reminder: route(reminder, defaults.reminder),
+            waiting_on_host: route(waiting_on_host, defaults.waiting_on_host),
+        })
+    }
+
+    /// Somewhere else to post about the game, on top of the channel it was
+    /// created in. False if the channel was already subscribed.
+    pub fn subscribe_channel(&self, alias: &str, channel_id: ChannelId) -> Result<bool, Error> {
+        info!("db::subscribe_channel");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/insert_channel_subscription.sql"),
+            params![&alias, &(channel_id.0 as i64)],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// False if the channel wasn't subscribed
+    pub fn unsubscribe_channel(&self, alias: &str, channel_id: ChannelId) -> Result<bool, Error> {
+        info!("db::unsubscribe_channel");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/delete_channel_subscription.sql"),
+            params![&alias, &(channel_id.0 as i64)],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    pub fn channel_subscriptions_for_alias(&self, alias: &str) -> Result<Vec<ChannelId>, Error> {
+        info!("db::channel_subscriptions_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_channel_subscriptions.sql"))?;
+        let channel_ids = stmt
+            .query_map(params![&alias], |row| {
+                let channel_id: i64 = row.get(0)?;
+                Ok(ChannelId(channel_id as u64))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(channel_ids)
+    }
+
+    /// Every channel that gets announcements for a game that's still going, once each
+    pub fn active_notification_channels(&self) -> Result<Vec<ChannelId>, Error> {
+        info!("db::active_notification_channels");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!(
+            "db/sql/select_active_notification_channels.sql"
+        ))?;
+        let channel_ids = stmt
+            .query_map(params![], |row| {
+                let channel_id: i64 = row.get(0)?;
+                Ok(ChannelId(channel_id as u64))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(channel_ids)
+    }
+
+    /// Someone to DM the game's announcements to without them playing in it.
+    /// False if they were already spectating.
+    pub fn add_spectator(&self, alias: &str, user_id: UserId) -> Result<bool, Error> {
+        info!("db::add_spectator");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/insert_spectator.sql"),
+            params![&alias, &(user_id.0 as i64)],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// False if they weren't spectating
+    pub fn remove_spectator(&self, alias: &str, user_id: UserId) -> Result<bool, Error> {
+        info!("db::remove_spectator");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/delete_spectator.sql"),
+            params![&alias, &(user_id.0 as i64)],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    pub fn spectators_for_alias(&self, alias: &str) -> Result<Vec<UserId>, Error> {
+        info!("db::spectators_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_spectators.sql"))?;
+        let user_ids = stmt
+            .query_map(params![&alias], |row| {
+                let user_id: i64 = row.get(0)?;
+                Ok(UserId(user_id as u64))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(user_ids)
+    }
+
+    /// False if they were already watching it, or there's no such game
+    pub fn add_nation_watch(
+        &self,
+        alias: &str,
+        user_id: UserId,
+        nation_id: u32,
+    ) -> Result<bool, Error> {
+        info!("db::add_nation_watch");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/insert_nation_watch.sql"),
+            params![&alias, &(user_id.0 as i64), &nation_id],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// False if they weren't watching it
+    pub fn remove_nation_watch(
+        &self,
+        alias: &str,
+        user_id: UserId,
+        nation_id: u32,
+    ) -> Result<bool, Error> {
+        info!("db::remove_nation_watch");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/delete_nation_watch.sql"),
+            params![&alias, &(user_id.0 as i64), &nation_id],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// Who's watching which nation in the game
+    pub fn nation_watches_for_alias(&self, alias: &str) -> Result<Vec<(UserId, u32)>, Error> {
+        info!("db::nation_watches_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_nation_watches.sql"))?;
+        let watches = stmt
+            .query_map(params![&alias], |row| {
+                let user_id: i64 = row.get(0)?;
+                Ok((UserId(user_id as u64), row.get(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(watches)
+    }
+
+    /// A turn can have any number of notes, they're never replaced
+    pub fn add_turn_note(
+        &self,
+        alias: &str,
+        turn: i32,
+        user_id: UserId,
+        note: &str,
+        created_at: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        info!("db::add_turn_note");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/insert_turn_note.sql"),
+            params![
+                &alias,
+                &turn,
+                &(user_id.0 as i64),
+                &note,
+                &created_at.timestamp()
+            ],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    /// Oldest turn first, and in the order they were added within a turn
+    pub fn turn_notes_for_alias(&self, alias: &str) -> Result<Vec<TurnNote>, Error> {
+        info!("db::turn_notes_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_turn_notes.sql"))?;
+        let notes = stmt
+            .query_map(params![&alias], |row| {
+                let user_id: i64 = row.get(1)?;
+                let created_at: i64 = row.get(3)?;
+                Ok(TurnNote {
+                    turn: row.get(0)?,
+                    author: UserId(user_id as u64),
+                    note: row.get(2)?,
+                    created_at: Utc.timestamp(created_at, 0),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notes)
+    }
+
+    /// Remembers what the providers called each nation, replacing any older name
+    pub fn record_nation_names(
+        &self,
+        alias: &str,
+        nation_names: &[(u32, String)],
+    ) -> Result<(), Error> {
+        info!("db::record_nation_names");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        for (nation_id, nation_name) in nation_names {
+            tx.execute(
+                include_str!("db/sql/upsert_nation_name.sql"),
+                params![&alias, nation_id, nation_name],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Every nation name the providers have told us about for the game
+    pub fn nation_names_for_alias(&self, alias: &str) -> Result<HashMap<u32, String>, Error> {
+        info!("db::nation_names_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_nation_names.sql"))?;
+        let nation_names = stmt
+            .query_map(params![&alias], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<u32, String>, _>>()?;
+        Ok(nation_names)
+    }
+
+    /// Everywhere to post about the game: the channel it was created in and
+    /// any that subscribed, each once
+    pub fn notification_channels_for_alias(&self, alias: &str) -> Result<Vec<ChannelId>, Error> {
+        let mut channel_ids = self.channel_subscriptions_for_alias(alias)?;
+        if let Some(channel_id) = self.channel_for_alias(alias)? {
+            if !channel_ids.contains(&channel_id) {
+                channel_ids.insert(0, channel_id);
+            }
+        }
+        Ok(channel_ids)
+    }
+
+    /// The message in the channel that gets edited with the game's status, replacing
+    /// any earlier one. Also marks it as up to date.
+    pub fn set_status_message(
+        &self,
+        alias: &str,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<(), Error> {
+        info!("db::set_status_message");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_status_message.sql"),
+            params![&alias, &(channel_id.0 as i64), &(message_id.0 as i64)],
+        )?;
+        Ok(())
+    }
+
+    pub fn status_message(
+        &self,
+        alias: &str,
+        channel_id: ChannelId,
+    ) -> Result<Option<MessageId>, Error> {
+        info!("db::status_message");
+        let conn = &*self.0.clone().get()?;
+        let option_message_id: Option<i64> = conn
+            .query_row(
+                include_str!("db/sql/select_status_message.sql"),
+                params![&alias, &(channel_id.0 as i64)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_message_id.map(|message_id| MessageId(message_id as u64)))
+    }
+
+    /// False if the channel didn't have one for the game
+    pub fn remove_status_message(&self, alias: &str, channel_id: ChannelId) -> Result<bool, Error> {
+        info!("db::remove_status_message");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/delete_status_message.sql"),
+            params![&alias, &(channel_id.0 as i64)],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// What all of the game's status messages should be edited to say next
+    pub fn queue_status_message_text(&self, alias: &str, text: &str) -> Result<(), Error> {
+        info!("db::queue_status_message_text");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/update_status_messages_with_pending_text.sql"),
+            params![&alias, &text],
+        )?;
+        Ok(())
+    }
+
+    /// (alias, channel, message, text) for the status messages that are out of date
+    pub fn pending_status_messages(
+        &self,
+    ) -> Result<Vec<(String, ChannelId, MessageId, String)>, Error> {
+        info!("db::pending_status_messages");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_pending_status_messages.sql"))?;
+        let pending = stmt
+            .query_map(params![], |row| {
+                let channel_id: i64 = row.get(1)?;
+                let message_id: i64 = row.get(2)?;
+                Ok((
+                    row.get(0)?,
+                    ChannelId(channel_id as u64),
+                    MessageId(message_id as u64),
+                    row.get(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(pending)
+    }
+
+    /// Every game that posts in this channel, by alias
+    pub fn aliases_for_channel(&self, channel_id: ChannelId) -> Result<Vec<String>, Error> {
+        info!("db::aliases_for_channel");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_aliases_for_channel.sql"))?;
+        let aliases = stmt
+            .query_map(params![&(channel_id.0 as i64)], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(aliases)
+    }
+
+    /// The era `!lobby` uses in this channel when it isn't given one. `None` clears it.
+    pub fn set_default_era_for_channel(
+        &self,
+        channel_id: ChannelId,
+        option_era: Option<Era>,
+    ) -> Result<(), Error> {
+        info!("db::set_default_era_for_channel");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_channel_default_era.sql"),
+            params![
+                &(channel_id.0 as i64),
+                &option_era.and_then(|era| era.to_i32())
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn default_era_for_channel(&self, channel_id: ChannelId) -> Result<Option<Era>, Error> {
+        info!("db::default_era_for_channel");
+        let conn = &*self.0.clone().get()?;
+        let option_era: Option<Option<i32>> = conn
+            .query_row(
+                include_str!("db/sql/select_channel_default_era.sql"),
+                params![&(channel_id.0 as i64)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match option_era.and_then(|era| era) {
+            Some(era) => Ok(Some(Era::from_i32(era).ok_or(err_msg("unknown era"))?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Which translations to show in this channel. `None` is English.
+    pub fn set_locale_for_channel(
+        &self,
+        channel_id: ChannelId,
+        option_locale: Option<&str>,
+    ) -> Result<(), Error> {
+        info!("db::set_locale_for_channel");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_channel_locale.sql"),
+            params![&(channel_id.0 as i64), &option_locale],
+        )?;
+        Ok(())
+    }
+
+    /// `schedule` is (day, minute of the day), see `ChannelDigest`. `None` stops the
+    /// digest. Counts as having just been sent, so a time earlier in the week
+    /// doesn't go out straight away.
+    pub fn set_digest_for_channel(
+        &self,
+        channel_id: ChannelId,
+        option_schedule: Option<(u32, u32)>,
+        now: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        info!("db::set_digest_for_channel");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_channel_digest.sql"),
+            params![
+                &(channel_id.0 as i64),
+                &option_schedule.map(|(weekday, _)| weekday),
+                &option_schedule.map(|(_, minute)| minute),
+                &option_schedule.map(|_| now.timestamp()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn channel_digests(&self) -> Result<Vec<ChannelDigest>, Error> {
+        info!("db::channel_digests");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_channel_digests.sql"))?;
+        let digests = stmt
+            .query_map(params![], |row| {
+                let channel_id: i64 = row.get(0)?;
+                let last_sent: i64 = row.get(3)?;
+                Ok((
+                    ChannelId(channel_id as u64),
+                    row.get(1)?,
+                    row.get(2)?,
+                    Utc.timestamp(last_sent, 0),
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(digests)
+    }
+
+    pub fn mark_digest_sent(
+        &self,
+        channel_id: ChannelId,
+        sent_at: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        info!("db::mark_digest_sent");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/update_channel_digest_last_sent.sql"),
+            params![&(channel_id.0 as i64), &sent_at.timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// `None` goes back to the default prefix
+    pub fn set_prefix_for_guild(
+        &self,
+        guild_id: GuildId,
+        option_prefix: Option<&str>,
+    ) -> Result<(), Error> {
+        info!("db::set_prefix_for_guild");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_guild_prefix.sql"),
+            params![&(guild_id.0 as i64), &option_prefix],
+        )?;
+        Ok(())
+    }
+
+    /// Only the guilds that have changed their prefix
+    pub fn guild_prefixes(&self) -> Result<Vec<(GuildId, String)>, Error> {
+        info!("db::guild_prefixes");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_guild_prefixes.sql"))?;
+        let rows = stmt.query_map(params![], |row| {
+            let guild_id: i64 = row.get(0)?;
+            let prefix: String = row.get(1)?;
+            Ok((GuildId(guild_id as u64), prefix))
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Plain text instead of an embed for `!details`, for servers that turn embeds off
+    pub fn set_details_as_text_for_guild(
+        &self,
+        guild_id: GuildId,
+        details_as_text: bool,
+    ) -> Result<(), Error> {
+        info!("db::set_details_as_text_for_guild");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_guild_details_as_text.sql"),
+            params![&(guild_id.0 as i64), &details_as_text],
+        )?;
+        Ok(())
+    }
+
+    pub fn details_as_text_for_guild(&self, guild_id: GuildId) -> Result<bool, Error> {
+        info!("db::details_as_text_for_guild");
+        let conn = &*self.0.clone().get()?;
+        let option_details_as_text: Option<bool> = conn
+            .query_row(
+                include_str!("db/sql/select_guild_details_as_text.sql"),
+                params![&(guild_id.0 as i64)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_details_as_text.unwrap_or(false))
+    }
+
+    pub fn set_submission_confirmations_for_guild(
+        &self,
+        guild_id: GuildId,
+        submission_confirmations: bool,
+    ) -> Result<(), Error> {
+        info!("db::set_submission_confirmations_for_guild");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_guild_submission_confirmations.sql"),
+            params![&(guild_id.0 as i64), &submission_confirmations],
+        )?;
+        Ok(())
+    }
+
+    pub fn submission_confirmations_for_guild(&self, guild_id: GuildId) -> Result<bool, Error> {
+        info!("db::submission_confirmations_for_guild");
+        let conn = &*self.0.clone().get()?;
+        let option_submission_confirmations: Option<bool> = conn
+            .query_row(
+                include_str!("db/sql/select_guild_submission_confirmations.sql"),
+                params![&(guild_id.0 as i64)],
+                |row| row.get(0),
             )
-            .unwrap();
-            Ok((server, registered_player_count))
+            .optional()?;
+        Ok(option_submission_confirmations.unwrap_or(false))
+    }
+
+    pub fn set_time_format_for_guild(
+        &self,
+        guild_id: GuildId,
+        time_format: TimeFormat,
+    ) -> Result<(), Error> {
+        info!("db::set_time_format_for_guild");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_guild_time_format.sql"),
+            params![&(guild_id.0 as i64), &time_format.name()],
+        )?;
+        Ok(())
+    }
+
+    pub fn time_format_for_guild(&self, guild_id: GuildId) -> Result<TimeFormat, Error> {
+        info!("db::time_format_for_guild");
+        let conn = &*self.0.clone().get()?;
+        let option_name: Option<Option<String>> = conn
+            .query_row(
+                include_str!("db/sql/select_guild_time_format.sql"),
+                params![&(guild_id.0 as i64)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_name
+            .flatten()
+            .and_then(|name| TimeFormat::from_name(&name))
+            .unwrap_or_default())
+    }
+
+    /// `None` goes back to the default
+    pub fn set_max_games_per_channel_for_guild(
+        &self,
+        guild_id: GuildId,
+        option_max_games: Option<u32>,
+    ) -> Result<(), Error> {
+        info!("db::set_max_games_per_channel_for_guild");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_guild_max_games_per_channel.sql"),
+            params![&(guild_id.0 as i64), &option_max_games],
+        )?;
+        Ok(())
+    }
+
+    pub fn max_games_per_channel_for_guild(&self, guild_id: GuildId) -> Result<Option<u32>, Error> {
+        info!("db::max_games_per_channel_for_guild");
+        let conn = &*self.0.clone().get()?;
+        let option_max_games: Option<Option<u32>> = conn
+            .query_row(
+                include_str!("db/sql/select_guild_max_games_per_channel.sql"),
+                params![&(guild_id.0 as i64)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_max_games.flatten())
+    }
+
+    /// How long finished games get before they're archived, `None` is never
+    pub fn set_auto_archive_hours_for_guild(
+        &self,
+        guild_id: GuildId,
+        option_hours: Option<u32>,
+    ) -> Result<(), Error> {
+        info!("db::set_auto_archive_hours_for_guild");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_guild_auto_archive_hours.sql"),
+            params![&(guild_id.0 as i64), &option_hours],
+        )?;
+        Ok(())
+    }
+
+    pub fn auto_archive_hours_for_guild(&self, guild_id: GuildId) -> Result<Option<u32>, Error> {
+        info!("db::auto_archive_hours_for_guild");
+        let conn = &*self.0.clone().get()?;
+        let option_hours: Option<Option<u32>> = conn
+            .query_row(
+                include_str!("db/sql/select_guild_auto_archive_hours.sql"),
+                params![&(guild_id.0 as i64)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_hours.flatten())
+    }
+
+    /// Members with this role can manage any game in the server as if they owned
+    /// it. `None` leaves it to each game's owner and the server's admins.
+    pub fn set_admin_role_for_guild(
+        &self,
+        guild_id: GuildId,
+        option_role_id: Option<RoleId>,
+    ) -> Result<(), Error> {
+        info!("db::set_admin_role_for_guild");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_guild_admin_role.sql"),
+            params![
+                &(guild_id.0 as i64),
+                &option_role_id.map(|role_id| role_id.0 as i64)
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn admin_role_for_guild(&self, guild_id: GuildId) -> Result<Option<RoleId>, Error> {
+        info!("db::admin_role_for_guild");
+        let conn = &*self.0.clone().get()?;
+        let option_role_id: Option<Option<i64>> = conn
+            .query_row(
+                include_str!("db/sql/select_guild_admin_role.sql"),
+                params![&(guild_id.0 as i64)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_role_id
+            .flatten()
+            .map(|role_id| RoleId(role_id as u64)))
+    }
+
+    /// When the game is due to be archived, if it is, and whether it's been
+    /// taken out of auto-archiving for good
+    pub fn auto_archive_for_alias(
+        &self,
+        alias: &str,
+    ) -> Result<(Option<DateTime<Utc>>, bool), Error> {
+        info!("db::auto_archive_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_state: Option<(Option<i64>, bool)> = conn
+            .query_row(
+                include_str!("db/sql/select_auto_archive_for_alias.sql"),
+                params![&alias],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let (option_at, skip) = option_state
+            .ok_or_else(|| err_msg(format!("Could not find game with name {}", alias)))?;
+        Ok((option_at.map(|at| Utc.timestamp(at, 0)), skip))
+    }
+
+    pub fn set_auto_archive_for_alias(
+        &self,
+        alias: &str,
+        option_at: Option<DateTime<Utc>>,
+        skip: bool,
+    ) -> Result<(), Error> {
+        info!("db::set_auto_archive_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_auto_archive.sql"),
+            params![&alias, &option_at.map(|at| at.timestamp()), &skip],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    /// Archived games don't count, they aren't being checked any more
+    pub fn game_count_for_channel(&self, channel_id: ChannelId) -> Result<u32, Error> {
+        info!("db::game_count_for_channel");
+        let conn = &*self.0.clone().get()?;
+        let count = conn.query_row(
+            include_str!("db/sql/select_game_count_for_channel.sql"),
+            params![&(channel_id.0 as i64)],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    pub fn set_command_alias(
+        &self,
+        guild_id: GuildId,
+        alias: &str,
+        command: &str,
+    ) -> Result<(), Error> {
+        info!("db::set_command_alias");
+        let conn = &*self.0.clone().get()?;
+        conn.execute(
+            include_str!("db/sql/upsert_command_alias.sql"),
+            params![&(guild_id.0 as i64), &alias, &command],
+        )?;
+        Ok(())
+    }
+
+    /// False if there was no such alias
+    pub fn remove_command_alias(&self, guild_id: GuildId, alias: &str) -> Result<bool, Error> {
+        info!("db::remove_command_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/delete_command_alias.sql"),
+            params![&(guild_id.0 as i64), &alias],
+        )?;
+        Ok(rows_modified != 0)
+    }
+
+    /// (guild, alias, the command it stands for)
+    pub fn command_aliases(&self) -> Result<Vec<(GuildId, String, String)>, Error> {
+        info!("db::command_aliases");
+        let conn = &*self.0.clone().get()?;
+        let mut stmt = conn.prepare(include_str!("db/sql/select_command_aliases.sql"))?;
+        let rows = stmt.query_map(params![], |row| {
+            let guild_id: i64 = row.get(0)?;
+            let alias: String = row.get(1)?;
+            let command: String = row.get(2)?;
+            Ok((GuildId(guild_id as u64), alias, command))
         })?;
-        let vec = foo.collect::<Result<Vec<_>, _>>()?;
-        Ok(vec)
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub fn locale_for_channel(&self, channel_id: ChannelId) -> Result<Option<String>, Error> {
+        info!("db::locale_for_channel");
+        let conn = &*self.0.clone().get()?;
+        let option_locale: Option<Option<String>> = conn
+            .query_row(
+                include_str!("db/sql/select_channel_locale.sql"),
+                params![&(channel_id.0 as i64)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_locale.and_then(|locale| locale))
+    }
+
+    /// For a server that's moved, keeping everything else about the game
+    pub fn set_address_for_alias(&self, alias: &str, address: &str) -> Result<(), Error> {
+        info!("db::set_address_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_started_server_address.sql"),
+            params![&alias, &address],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!(
+                "Could not find started game with name {}",
+                alias
+            )))
+        }
+    }
+
+    pub fn set_snek_game_id_for_alias(&self, alias: &str, snek_game_id: u32) -> Result<(), Error> {
+        info!("db::set_snek_game_id_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_started_server_with_snek_game_id.sql"),
+            params![&alias, &snek_game_id],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!(
+                "Could not find started game with name {}",
+                alias
+            )))
+        }
     }
 
-    pub fn update_lobby_with_description(
+    /// `None` goes back to the default providers
+    pub fn set_nation_providers_for_alias(
         &self,
         alias: &str,
-        description: &str,
+        option_providers: Option<&str>,
     ) -> Result<(), Error> {
-        info!("update_lobby_with_description");
+        info!("db::set_nation_providers_for_alias");
         let conn = &*self.0.clone().get()?;
         let rows_modified = conn.execute(
-            include_str!("db/sql/update_lobby_with_description.sql"),
-            &[&alias, &description],
+            include_str!("db/sql/update_game_with_nation_providers.sql"),
+            params![&alias, &option_providers],
         )?;
         if rows_modified != 0 {
             Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    /// The comma separated provider names, if the game has picked any
+    pub fn nation_providers_for_alias(&self, alias: &str) -> Result<Option<String>, Error> {
+        info!("db::nation_providers_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_providers = conn
+            .query_row(
+                include_str!("db/sql/select_nation_providers_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_providers)
+    }
+
+    /// `None` goes back to UTC
+    pub fn set_utc_offset_for_alias(
+        &self,
+        alias: &str,
+        option_utc_offset: Option<FixedOffset>,
+    ) -> Result<(), Error> {
+        info!("db::set_utc_offset_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_utc_offset_secs =
+            option_utc_offset.map(|utc_offset| utc_offset.local_minus_utc());
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_utc_offset.sql"),
+            params![&alias, &option_utc_offset_secs],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    pub fn utc_offset_for_alias(&self, alias: &str) -> Result<Option<FixedOffset>, Error> {
+        info!("db::utc_offset_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_utc_offset_secs: Option<i32> = conn
+            .query_row(
+                include_str!("db/sql/select_utc_offset_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_utc_offset_secs.and_then(FixedOffset::east_opt))
+    }
+
+    /// What to DM players on a new turn instead of the usual wording. `None` goes
+    /// back to the usual wording.
+    pub fn set_reminder_template_for_alias(
+        &self,
+        alias: &str,
+        option_template: Option<&str>,
+    ) -> Result<(), Error> {
+        info!("db::set_reminder_template_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_reminder_template.sql"),
+            params![&alias, &option_template],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    pub fn reminder_template_for_alias(&self, alias: &str) -> Result<Option<String>, Error> {
+        info!("db::reminder_template_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_template: Option<String> = conn
+            .query_row(
+                include_str!("db/sql/select_reminder_template_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_template)
+    }
+
+    /// Registrations hang off the game's id rather than its alias so only the
+    /// tables keyed by alias need updating
+    pub fn rename_alias(&self, old_alias: &str, new_alias: &str) -> Result<(), Error> {
+        info!("db::rename_alias");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let rows_modified = tx.execute(
+            include_str!("db/sql/update_game_server_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        if rows_modified == 0 {
+            return Err(err_msg(format!(
+                "Could not find game with name {}",
+                old_alias
+            )));
+        }
+        tx.execute(
+            include_str!("db/sql/update_turn_history_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_submission_history_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_nation_eliminations_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_final_submissions_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_progress_bookmarks_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_cached_poll_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_one_off_reminder_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_status_messages_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_lobby_roster_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.execute(
+            include_str!("db/sql/update_nation_status_history_alias.sql"),
+            params![&old_alias, &new_alias],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn set_owner(&self, alias: &str, new_owner: UserId) -> Result<(), Error> {
+        info!("db::set_owner");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            include_str!("db/sql/insert_player.sql"),
+            params![&(new_owner.0 as i64), &true],
+        )?;
+        let rows_modified = tx.execute(
+            include_str!("db/sql/update_lobby_with_owner.sql"),
+            params![&alias, &(new_owner.0 as i64)],
+        )?;
+        if rows_modified != 0 {
+            tx.commit()?;
+            Ok(())
         } else {
             Err(err_msg(format!("Could not find lobby with name {}", alias)))
         }
     }
+
+    pub fn set_player_count(&self, alias: &str, player_count: i32) -> Result<(), Error> {
+        info!("db::set_player_count");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_lobby_with_player_count.sql"),
+            params![&alias, &player_count],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find lobby with name {}", alias)))
+        }
+    }
+
+    /// Games that came from a lobby keep it on the lobby, so it survives !unstart.
+    /// Games added with !add have nowhere else to put it. `None` clears it.
+    pub fn set_description_for_alias(
+        &self,
+        alias: &str,
+        option_description: Option<&str>,
+    ) -> Result<(), Error> {
+        info!("db::set_description_for_alias");
+        let conn = &mut *self.0.clone().get()?;
+        let tx = conn.transaction()?;
+        let mut rows_modified = tx.execute(
+            include_str!("db/sql/update_lobby_with_description.sql"),
+            params![&alias, &option_description],
+        )?;
+        if rows_modified == 0 {
+            rows_modified = tx.execute(
+                include_str!("db/sql/update_game_with_description.sql"),
+                params![&alias, &option_description],
+            )?;
+        }
+        tx.commit()?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    /// Only the one set on the game itself, lobbies' are in their `LobbyState`
+    pub fn description_for_alias(&self, alias: &str) -> Result<Option<String>, Error> {
+        info!("db::description_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_description = conn
+            .query_row(
+                include_str!("db/sql/select_description_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_description.flatten())
+    }
+
+    pub fn set_webhook_url_for_alias(
+        &self,
+        alias: &str,
+        option_url: Option<&str>,
+    ) -> Result<(), Error> {
+        info!("db::set_webhook_url_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let rows_modified = conn.execute(
+            include_str!("db/sql/update_game_with_webhook_url.sql"),
+            params![&alias, &option_url],
+        )?;
+        if rows_modified != 0 {
+            Ok(())
+        } else {
+            Err(err_msg(format!("Could not find game with name {}", alias)))
+        }
+    }
+
+    pub fn webhook_url_for_alias(&self, alias: &str) -> Result<Option<String>, Error> {
+        info!("db::webhook_url_for_alias");
+        let conn = &*self.0.clone().get()?;
+        let option_url = conn
+            .query_row(
+                include_str!("db/sql/select_webhook_url_for_alias.sql"),
+                params![&alias],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(option_url.flatten())
+    }
 }
 
+// One argument per (nullable) column, grouping them wouldn't make it any clearer
+#[allow(clippy::too_many_arguments)]
 fn make_game_server(
     alias: String,
     maybe_address: Option<String>,
@@ -487,7 +3280,10 @@ fn make_game_server(
     maybe_era: Option<i32>,
     maybe_player_count: Option<i32>,
     description: Option<String>,
+    maybe_snek_game_id: Option<i64>,
+    archived: bool,
 ) -> Result<GameServer, Error> {
+    let option_snek_game_id = maybe_snek_game_id.map(|snek_game_id| snek_game_id as u32);
     let state = match (
         maybe_address,
         maybe_last_seen_turn,
@@ -499,6 +3295,7 @@ fn make_game_server(
             StartedState {
                 address,
                 last_seen_turn,
+                option_snek_game_id,
             },
             None,
         ),
@@ -507,6 +3304,7 @@ fn make_game_server(
                 StartedState {
                     address,
                     last_seen_turn,
+                    option_snek_game_id,
                 },
                 Some(LobbyState {
                     owner: UserId(owner as u64),
@@ -526,6 +3324,12 @@ fn make_game_server(
         }
         _ => return Err(err_msg(format!("invalid db state for {}", alias))),
     };
+    let state = match state {
+        GameServerState::StartedState(started_state, option_lobby_state) if archived => {
+            GameServerState::Archived(started_state, option_lobby_state)
+        }
+        state => state,
+    };
 
     let server = GameServer { alias, state };
     Ok(server)
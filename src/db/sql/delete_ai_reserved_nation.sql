@@ -0,0 +1,3 @@
+DELETE FROM ai_reserved_nations
+WHERE nation_id = ?2 AND server_id IN
+    (SELECT id from game_servers WHERE alias = ?1);
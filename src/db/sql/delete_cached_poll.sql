@@ -0,0 +1,2 @@
+DELETE FROM cached_polls
+WHERE alias = ?1;
@@ -0,0 +1,4 @@
+DELETE FROM channel_subscriptions
+WHERE server_id IN
+    (SELECT id from game_servers WHERE alias = ?1)
+AND channel_id = ?2;
@@ -0,0 +1,2 @@
+DELETE FROM command_aliases
+WHERE guild_id = ?1 AND alias = ?2;
@@ -0,0 +1,2 @@
+DELETE FROM final_submissions
+WHERE alias = ?1;
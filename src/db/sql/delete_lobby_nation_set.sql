@@ -0,0 +1,3 @@
+DELETE FROM lobby_nation_set
+WHERE server_id IN
+    (SELECT id from game_servers WHERE alias = ?1);
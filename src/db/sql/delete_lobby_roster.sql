@@ -0,0 +1,2 @@
+DELETE FROM lobby_rosters
+WHERE alias = ?1;
@@ -0,0 +1,2 @@
+DELETE FROM nation_eliminations
+WHERE alias = ?1;
@@ -0,0 +1,2 @@
+DELETE FROM nation_status_history
+WHERE alias = ?1;
@@ -0,0 +1,3 @@
+DELETE FROM nation_watches
+WHERE discord_user_id = ?2 AND nation_id = ?3 AND server_id IN
+    (SELECT id from game_servers WHERE alias = ?1);
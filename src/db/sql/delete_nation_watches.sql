@@ -0,0 +1,3 @@
+DELETE FROM nation_watches
+WHERE server_id IN
+    (SELECT id from game_servers WHERE alias = ?1);
@@ -0,0 +1,2 @@
+DELETE FROM one_off_reminders
+WHERE alias = ?1 AND user_id = ?2;
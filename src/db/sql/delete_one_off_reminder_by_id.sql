@@ -0,0 +1,2 @@
+DELETE FROM one_off_reminders
+WHERE id = ?1;
@@ -0,0 +1,2 @@
+DELETE FROM one_off_reminders
+WHERE user_id = ?1;
@@ -0,0 +1,2 @@
+DELETE FROM progress_bookmarks
+WHERE alias = ?1;
@@ -0,0 +1,4 @@
+DELETE FROM spectators
+WHERE server_id IN
+    (SELECT id from game_servers WHERE alias = ?1)
+AND discord_user_id = ?2;
@@ -0,0 +1,2 @@
+DELETE FROM status_messages
+WHERE alias = ?1 AND channel_id = ?2;
@@ -0,0 +1,2 @@
+DELETE FROM submission_history
+WHERE alias = ?1;
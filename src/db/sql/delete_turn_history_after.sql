@@ -0,0 +1,2 @@
+DELETE FROM turn_history
+WHERE alias = ?1 AND turn > ?2;
@@ -0,0 +1,4 @@
+INSERT OR IGNORE INTO ai_reserved_nations (server_id, nation_id)
+SELECT g.id, ?2
+FROM game_servers g
+WHERE g.alias = ?1;
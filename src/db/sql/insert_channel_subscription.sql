@@ -0,0 +1,4 @@
+INSERT OR IGNORE INTO channel_subscriptions (server_id, channel_id)
+SELECT g.id, ?2
+FROM game_servers g
+WHERE g.alias = ?1;
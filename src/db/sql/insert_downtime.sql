@@ -0,0 +1,2 @@
+INSERT INTO downtime (started_at, ended_at)
+VALUES (?1, ?2);
@@ -0,0 +1,2 @@
+INSERT OR IGNORE INTO final_submissions (alias, turn, discord_user_id, nation_id, submitted)
+VALUES (?1, ?2, ?3, ?4, ?5);
@@ -0,0 +1,2 @@
+INSERT OR IGNORE INTO nation_eliminations (alias, turn, nation_id, nation_name)
+VALUES (?1, ?2, ?3, ?4);
@@ -0,0 +1,4 @@
+INSERT OR IGNORE INTO nation_watches (server_id, discord_user_id, nation_id)
+SELECT g.id, ?2, ?3
+FROM game_servers g
+WHERE g.alias = ?1;
@@ -1,5 +1,5 @@
-INSERT INTO server_players (server_id, player_id, nation_id)
-SELECT g.id, p.id, ?1
+INSERT OR IGNORE INTO server_players (server_id, player_id, nation_id, registered_at)
+SELECT g.id, p.id, ?1, ?4
 FROM game_servers g
 JOIN players p ON p.discord_user_id = ?2
 WHERE g.alias = ?3;
@@ -0,0 +1,7 @@
+INSERT OR IGNORE INTO server_players (server_id, player_id, nation_id, registered_at)
+SELECT target.id, sp.player_id, sp.nation_id, sp.registered_at
+FROM server_players sp
+JOIN game_servers source ON source.id = sp.server_id
+JOIN players p ON p.id = sp.player_id
+JOIN game_servers target ON target.alias = ?2
+WHERE source.alias = ?1 AND p.discord_user_id = ?3;
@@ -0,0 +1,4 @@
+INSERT OR IGNORE INTO spectators (server_id, discord_user_id)
+SELECT g.id, ?2
+FROM game_servers g
+WHERE g.alias = ?1;
@@ -0,0 +1,2 @@
+INSERT OR IGNORE INTO submission_history (alias, turn, nation_id, timestamp)
+VALUES (?1, ?2, ?3, ?4);
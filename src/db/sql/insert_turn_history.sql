@@ -0,0 +1,2 @@
+INSERT OR IGNORE INTO turn_history (alias, turn, timestamp, timer_secs)
+VALUES (?1, ?2, ?3, ?4);
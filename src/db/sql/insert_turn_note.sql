@@ -0,0 +1,4 @@
+INSERT INTO turn_notes (server_id, turn, discord_user_id, note, created_at)
+SELECT g.id, ?2, ?3, ?4, ?5
+FROM game_servers g
+WHERE g.alias = ?1;
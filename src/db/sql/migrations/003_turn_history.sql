@@ -0,0 +1,8 @@
+create table if not exists turn_history (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    turn int NOT NULL,
+    timestamp int NOT NULL,
+
+    CONSTRAINT turn_history_alias_turn_unique UNIQUE (alias, turn)
+);
@@ -0,0 +1 @@
+ALTER TABLE game_servers ADD COLUMN utc_offset_secs int;
@@ -0,0 +1,12 @@
+ALTER TABLE turn_history ADD COLUMN timer_secs int;
+
+create table if not exists poller_heartbeat (
+    id INTEGER NOT NULL PRIMARY KEY,
+    timestamp int NOT NULL
+);
+
+create table if not exists downtime (
+    id INTEGER NOT NULL PRIMARY KEY,
+    started_at int NOT NULL,
+    ended_at int NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE started_servers ADD COLUMN snek_game_id int;
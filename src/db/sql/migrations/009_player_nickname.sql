@@ -0,0 +1 @@
+ALTER TABLE server_players ADD COLUMN player_nickname TEXT;
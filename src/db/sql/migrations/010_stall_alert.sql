@@ -0,0 +1 @@
+ALTER TABLE game_servers ADD COLUMN stall_alerted_turn int;
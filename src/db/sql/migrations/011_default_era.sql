@@ -0,0 +1,4 @@
+create table if not exists channel_settings (
+    channel_id int NOT NULL PRIMARY KEY,
+    default_era int
+);
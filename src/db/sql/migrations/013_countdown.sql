@@ -0,0 +1,3 @@
+ALTER TABLE game_servers ADD COLUMN countdown_mins TEXT;
+ALTER TABLE game_servers ADD COLUMN countdown_pinged_turn int;
+ALTER TABLE game_servers ADD COLUMN countdown_pinged_mins int;
@@ -0,0 +1,4 @@
+ALTER TABLE game_servers ADD COLUMN start_reminder_hours int;
+ALTER TABLE game_servers ADD COLUMN pretenders_seen int;
+ALTER TABLE game_servers ADD COLUMN pretenders_seen_at int;
+ALTER TABLE game_servers ADD COLUMN start_reminded int NOT NULL DEFAULT 0;
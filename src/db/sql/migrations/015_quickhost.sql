@@ -0,0 +1 @@
+ALTER TABLE turn_history ADD COLUMN quickhosted int NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+create table if not exists ai_reserved_nations (
+    id INTEGER NOT NULL PRIMARY KEY,
+    server_id int NOT NULL REFERENCES game_servers(id),
+    nation_id int NOT NULL,
+
+    CONSTRAINT ai_reserved_nations_server_nation_unique UNIQUE (server_id, nation_id)
+);
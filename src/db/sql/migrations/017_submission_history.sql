@@ -0,0 +1,9 @@
+create table if not exists submission_history (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    turn int NOT NULL,
+    nation_id int NOT NULL,
+    timestamp int NOT NULL,
+
+    CONSTRAINT submission_history_alias_turn_nation_unique UNIQUE (alias, turn, nation_id)
+);
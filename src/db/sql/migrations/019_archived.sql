@@ -0,0 +1 @@
+ALTER TABLE game_servers ADD COLUMN archived int NOT NULL DEFAULT 0;
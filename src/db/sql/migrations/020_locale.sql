@@ -0,0 +1 @@
+ALTER TABLE channel_settings ADD COLUMN locale VARCHAR(16);
@@ -0,0 +1,9 @@
+create table if not exists one_off_reminders (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    user_id int NOT NULL,
+    fire_at int NOT NULL,
+    message TEXT NOT NULL,
+
+    CONSTRAINT one_off_reminders_alias_user_unique UNIQUE (alias, user_id)
+);
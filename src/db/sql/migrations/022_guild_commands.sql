@@ -0,0 +1,13 @@
+create table if not exists guild_settings (
+    guild_id int NOT NULL PRIMARY KEY,
+    prefix VARCHAR(8)
+);
+
+create table if not exists command_aliases (
+    id INTEGER NOT NULL PRIMARY KEY,
+    guild_id int NOT NULL,
+    alias VARCHAR(32) NOT NULL,
+    command VARCHAR(32) NOT NULL,
+
+    CONSTRAINT command_aliases_guild_alias_unique UNIQUE (guild_id, alias)
+);
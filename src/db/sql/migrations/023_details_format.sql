@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN details_as_text int NOT NULL DEFAULT 0;
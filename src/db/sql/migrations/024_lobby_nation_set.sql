@@ -0,0 +1,7 @@
+create table if not exists lobby_nation_set (
+    id INTEGER NOT NULL PRIMARY KEY,
+    server_id int NOT NULL REFERENCES game_servers(id),
+    nation_id int NOT NULL,
+
+    CONSTRAINT lobby_nation_set_server_nation_unique UNIQUE (server_id, nation_id)
+);
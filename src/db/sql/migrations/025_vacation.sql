@@ -0,0 +1 @@
+ALTER TABLE server_players ADD COLUMN vacation_until int;
@@ -0,0 +1,7 @@
+create table if not exists channel_subscriptions (
+    id INTEGER NOT NULL PRIMARY KEY,
+    server_id int NOT NULL REFERENCES game_servers(id),
+    channel_id int NOT NULL,
+
+    CONSTRAINT channel_subscriptions_server_channel_unique UNIQUE (server_id, channel_id)
+);
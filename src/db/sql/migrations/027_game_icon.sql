@@ -0,0 +1,2 @@
+ALTER TABLE game_servers ADD COLUMN icon_emoji TEXT;
+ALTER TABLE game_servers ADD COLUMN icon_colour int;
@@ -0,0 +1,9 @@
+create table if not exists nation_eliminations (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    turn int NOT NULL,
+    nation_id int NOT NULL,
+    nation_name VARCHAR(255) NOT NULL,
+
+    CONSTRAINT nation_eliminations_alias_nation_unique UNIQUE (alias, nation_id)
+);
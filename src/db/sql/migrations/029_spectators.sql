@@ -0,0 +1,7 @@
+create table if not exists spectators (
+    id INTEGER NOT NULL PRIMARY KEY,
+    server_id int NOT NULL REFERENCES game_servers(id),
+    discord_user_id int NOT NULL,
+
+    CONSTRAINT spectators_server_user_unique UNIQUE (server_id, discord_user_id)
+);
@@ -0,0 +1 @@
+ALTER TABLE server_players ADD COLUMN registered_at int;
@@ -0,0 +1,3 @@
+ALTER TABLE channel_settings ADD COLUMN digest_weekday int;
+ALTER TABLE channel_settings ADD COLUMN digest_minute int;
+ALTER TABLE channel_settings ADD COLUMN digest_last_sent int;
@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN submission_confirmations int NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+create table if not exists final_submissions (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    turn int NOT NULL,
+    discord_user_id int NOT NULL,
+    nation_id int NOT NULL,
+    submitted int NOT NULL,
+
+    CONSTRAINT final_submissions_alias_turn_nation_unique UNIQUE (alias, turn, nation_id)
+);
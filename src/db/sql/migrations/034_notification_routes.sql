@@ -0,0 +1,3 @@
+ALTER TABLE game_servers ADD COLUMN new_turn_route int;
+ALTER TABLE game_servers ADD COLUMN stall_route int;
+ALTER TABLE game_servers ADD COLUMN reminder_route int;
@@ -0,0 +1,9 @@
+create table if not exists progress_bookmarks (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    user_id int NOT NULL,
+    turn int NOT NULL,
+    submitted_nation_ids VARCHAR(255) NOT NULL,
+
+    CONSTRAINT progress_bookmarks_alias_user_unique UNIQUE (alias, user_id)
+);
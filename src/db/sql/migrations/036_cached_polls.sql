@@ -0,0 +1,7 @@
+create table if not exists cached_polls (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    cache_entry TEXT NOT NULL,
+
+    CONSTRAINT cached_polls_alias_unique UNIQUE (alias)
+);
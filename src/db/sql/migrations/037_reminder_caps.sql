@@ -0,0 +1 @@
+ALTER TABLE server_players ADD COLUMN max_reminders int;
@@ -0,0 +1 @@
+ALTER TABLE game_servers ADD COLUMN stall_grace_hours int;
@@ -0,0 +1,9 @@
+create table if not exists status_messages (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    channel_id int NOT NULL,
+    message_id int NOT NULL,
+    pending_text TEXT,
+
+    CONSTRAINT status_messages_alias_channel_unique UNIQUE (alias, channel_id)
+);
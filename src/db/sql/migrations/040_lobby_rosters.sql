@@ -0,0 +1,9 @@
+create table if not exists lobby_rosters (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    user_id int NOT NULL,
+    waitlisted BOOLEAN NOT NULL,
+    waitlist_nation_id int,
+
+    CONSTRAINT lobby_rosters_alias_user_unique UNIQUE (alias, user_id)
+);
@@ -0,0 +1,10 @@
+create table if not exists nation_status_history (
+    id INTEGER NOT NULL PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    turn int NOT NULL,
+    nation_id int NOT NULL,
+    nation_name VARCHAR(255) NOT NULL,
+    status int NOT NULL,
+
+    CONSTRAINT nation_status_history_alias_turn_nation_unique UNIQUE (alias, turn, nation_id)
+);
@@ -0,0 +1,8 @@
+create table if not exists nation_watches (
+    id INTEGER NOT NULL PRIMARY KEY,
+    server_id int NOT NULL REFERENCES game_servers(id),
+    discord_user_id int NOT NULL,
+    nation_id int NOT NULL,
+
+    CONSTRAINT nation_watches_server_user_nation_unique UNIQUE (server_id, discord_user_id, nation_id)
+);
@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN max_games_per_channel int;
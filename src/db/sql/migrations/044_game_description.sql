@@ -0,0 +1 @@
+ALTER TABLE game_servers ADD COLUMN description TEXT;
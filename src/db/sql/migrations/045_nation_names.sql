@@ -0,0 +1,8 @@
+create table if not exists nation_names (
+    id INTEGER NOT NULL PRIMARY KEY,
+    server_id int NOT NULL REFERENCES game_servers(id),
+    nation_id int NOT NULL,
+    nation_name VARCHAR(255) NOT NULL,
+
+    CONSTRAINT nation_names_server_nation_unique UNIQUE (server_id, nation_id)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE game_servers ADD COLUMN polling_paused int NOT NULL DEFAULT 0;
+ALTER TABLE game_servers ADD COLUMN polling_paused_until int;
@@ -0,0 +1 @@
+ALTER TABLE players ADD COLUMN utc_offset_secs int;
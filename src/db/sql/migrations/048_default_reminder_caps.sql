@@ -0,0 +1,4 @@
+ALTER TABLE game_servers ADD COLUMN default_max_reminders int;
+-- Whether the player picked their own cap, so "all" can override the game's default
+ALTER TABLE server_players ADD COLUMN max_reminders_set int NOT NULL DEFAULT 0;
+UPDATE server_players SET max_reminders_set = 1 WHERE max_reminders IS NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE game_servers ADD COLUMN waiting_on_host_route int;
+ALTER TABLE game_servers ADD COLUMN waiting_on_host_alerted_turn int;
@@ -0,0 +1,8 @@
+create table if not exists turn_notes (
+    id INTEGER NOT NULL PRIMARY KEY,
+    server_id int NOT NULL REFERENCES game_servers(id),
+    turn int NOT NULL,
+    discord_user_id int NOT NULL,
+    note TEXT NOT NULL,
+    created_at int NOT NULL
+);
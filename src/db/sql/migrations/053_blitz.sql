@@ -0,0 +1 @@
+ALTER TABLE game_servers ADD COLUMN blitz int NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE game_servers ADD COLUMN first_seen_at int;
+ALTER TABLE game_servers ADD COLUMN first_seen_turn int;
@@ -0,0 +1,3 @@
+ALTER TABLE guild_settings ADD COLUMN auto_archive_hours int;
+ALTER TABLE game_servers ADD COLUMN auto_archive_at int;
+ALTER TABLE game_servers ADD COLUMN auto_archive_skip int NOT NULL DEFAULT 0;
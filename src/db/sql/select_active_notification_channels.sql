@@ -0,0 +1,9 @@
+SELECT g.channel_id
+FROM game_servers g
+WHERE g.archived = 0 AND g.channel_id IS NOT NULL
+UNION
+SELECT c.channel_id
+FROM channel_subscriptions c
+JOIN game_servers g ON g.id = c.server_id
+WHERE g.archived = 0
+ORDER BY 1;
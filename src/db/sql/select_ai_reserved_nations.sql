@@ -0,0 +1,5 @@
+SELECT r.nation_id
+FROM ai_reserved_nations r
+JOIN game_servers g ON g.id = r.server_id
+WHERE g.alias = ?1
+ORDER BY r.nation_id;
@@ -0,0 +1,4 @@
+SELECT alias
+FROM game_servers
+WHERE channel_id = ?1
+ORDER BY alias;
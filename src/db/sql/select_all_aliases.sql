@@ -0,0 +1,3 @@
+SELECT alias
+FROM game_servers
+ORDER BY alias;
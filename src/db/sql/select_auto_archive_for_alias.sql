@@ -0,0 +1,3 @@
+SELECT auto_archive_at, auto_archive_skip
+FROM game_servers
+WHERE alias = ?1;
@@ -0,0 +1,4 @@
+SELECT alias
+FROM game_servers
+WHERE blitz
+ORDER BY alias;
@@ -0,0 +1,3 @@
+SELECT blitz
+FROM game_servers
+WHERE alias = ?1;
@@ -0,0 +1,4 @@
+SELECT c.alias, c.cache_entry
+FROM cached_polls c
+JOIN game_servers g ON g.alias = c.alias
+WHERE g.archived = 0;
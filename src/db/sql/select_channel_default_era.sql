@@ -0,0 +1,3 @@
+SELECT default_era
+FROM channel_settings
+WHERE channel_id = ?1;
@@ -0,0 +1,5 @@
+SELECT channel_id, digest_weekday, digest_minute, digest_last_sent
+FROM channel_settings
+WHERE digest_weekday IS NOT NULL
+AND digest_minute IS NOT NULL
+ORDER BY channel_id;
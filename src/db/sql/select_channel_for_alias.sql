@@ -0,0 +1,3 @@
+SELECT channel_id
+FROM game_servers
+WHERE alias = ?1 AND channel_id IS NOT NULL;
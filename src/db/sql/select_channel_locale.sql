@@ -0,0 +1,3 @@
+SELECT locale
+FROM channel_settings
+WHERE channel_id = ?1;
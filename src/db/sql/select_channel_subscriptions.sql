@@ -0,0 +1,5 @@
+SELECT c.channel_id
+FROM channel_subscriptions c
+JOIN game_servers g ON g.id = c.server_id
+WHERE g.alias = ?1
+ORDER BY c.channel_id;
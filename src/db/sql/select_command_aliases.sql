@@ -0,0 +1,2 @@
+SELECT guild_id, alias, command
+FROM command_aliases;
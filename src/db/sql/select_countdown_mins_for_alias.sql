@@ -0,0 +1,3 @@
+SELECT countdown_mins
+FROM game_servers
+WHERE alias = ?1 AND countdown_mins IS NOT NULL;
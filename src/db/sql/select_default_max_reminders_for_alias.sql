@@ -0,0 +1,3 @@
+SELECT default_max_reminders
+FROM game_servers
+WHERE alias = ?1;
@@ -0,0 +1,3 @@
+SELECT description
+FROM game_servers
+WHERE alias = ?1;
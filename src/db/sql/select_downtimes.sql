@@ -0,0 +1,3 @@
+SELECT started_at, ended_at
+FROM downtime
+ORDER BY started_at;
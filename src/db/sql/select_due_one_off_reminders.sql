@@ -0,0 +1,4 @@
+SELECT id, user_id, message
+FROM one_off_reminders
+WHERE fire_at <= ?1
+ORDER BY fire_at;
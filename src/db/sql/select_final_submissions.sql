@@ -0,0 +1,4 @@
+SELECT turn, discord_user_id, nation_id, submitted
+FROM final_submissions
+WHERE alias = ?1
+ORDER BY turn, nation_id;
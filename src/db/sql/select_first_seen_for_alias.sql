@@ -0,0 +1,3 @@
+SELECT first_seen_at, first_seen_turn
+FROM game_servers
+WHERE alias = ?1 AND first_seen_at IS NOT NULL;
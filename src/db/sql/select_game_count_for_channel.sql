@@ -0,0 +1,3 @@
+SELECT COUNT(*)
+FROM game_servers
+WHERE channel_id = ?1 AND archived = 0;
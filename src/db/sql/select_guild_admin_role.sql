@@ -0,0 +1,3 @@
+SELECT admin_role_id
+FROM guild_settings
+WHERE guild_id = ?1;
@@ -0,0 +1,3 @@
+SELECT auto_archive_hours
+FROM guild_settings
+WHERE guild_id = ?1;
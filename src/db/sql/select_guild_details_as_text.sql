@@ -0,0 +1,3 @@
+SELECT details_as_text
+FROM guild_settings
+WHERE guild_id = ?1;
@@ -0,0 +1,3 @@
+SELECT max_games_per_channel
+FROM guild_settings
+WHERE guild_id = ?1;
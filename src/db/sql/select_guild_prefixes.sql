@@ -0,0 +1,3 @@
+SELECT guild_id, prefix
+FROM guild_settings
+WHERE prefix IS NOT NULL;
@@ -0,0 +1,3 @@
+SELECT submission_confirmations
+FROM guild_settings
+WHERE guild_id = ?1;
@@ -0,0 +1,3 @@
+SELECT time_format
+FROM guild_settings
+WHERE guild_id = ?1;
@@ -0,0 +1,3 @@
+SELECT icon_emoji, icon_colour
+FROM game_servers
+WHERE alias = ?1;
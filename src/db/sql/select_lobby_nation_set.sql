@@ -0,0 +1,5 @@
+SELECT s.nation_id
+FROM lobby_nation_set s
+JOIN game_servers g ON g.id = s.server_id
+WHERE g.alias = ?1
+ORDER BY s.nation_id;
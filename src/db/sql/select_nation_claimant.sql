@@ -0,0 +1,5 @@
+SELECT p.discord_user_id
+FROM game_servers g
+JOIN server_players sp ON sp.server_id = g.id
+JOIN players p ON p.id = sp.player_id
+WHERE g.alias = ?1 AND sp.nation_id = ?2;
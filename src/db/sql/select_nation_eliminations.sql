@@ -0,0 +1,4 @@
+SELECT turn, nation_id, nation_name
+FROM nation_eliminations
+WHERE alias = ?1
+ORDER BY turn, nation_id;
@@ -0,0 +1,4 @@
+SELECT n.nation_id, n.nation_name
+FROM nation_names n
+JOIN game_servers g ON g.id = n.server_id
+WHERE g.alias = ?1;
@@ -0,0 +1,3 @@
+SELECT nation_providers
+FROM game_servers
+WHERE alias = ?1 AND nation_providers IS NOT NULL;
@@ -0,0 +1,4 @@
+SELECT DISTINCT turn
+FROM nation_status_history
+WHERE alias = ?1
+ORDER BY turn;
@@ -0,0 +1,4 @@
+SELECT nation_id, nation_name, status
+FROM nation_status_history
+WHERE alias = ?1 AND turn = ?2
+ORDER BY nation_id;
@@ -0,0 +1,5 @@
+SELECT w.discord_user_id, w.nation_id
+FROM nation_watches w
+JOIN game_servers g ON g.id = w.server_id
+WHERE g.alias = ?1
+ORDER BY w.discord_user_id, w.nation_id;
@@ -0,0 +1,3 @@
+SELECT new_turn_route, stall_route, reminder_route, waiting_on_host_route
+FROM game_servers
+WHERE alias = ?1;
@@ -0,0 +1,4 @@
+SELECT alias, channel_id, message_id, pending_text
+FROM status_messages
+WHERE pending_text IS NOT NULL
+ORDER BY id;
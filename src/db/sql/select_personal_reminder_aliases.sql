@@ -0,0 +1,10 @@
+SELECT s.alias
+FROM game_servers s
+JOIN server_players sp on sp.server_id = s.id
+JOIN players p on p.id = sp.player_id
+WHERE p.discord_user_id = ?1
+AND sp.max_reminders_set = 1
+UNION
+SELECT alias
+FROM one_off_reminders
+WHERE user_id = ?1;
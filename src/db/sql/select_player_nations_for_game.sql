@@ -0,0 +1,7 @@
+SELECT sp.nation_id
+FROM game_servers s
+JOIN server_players sp on sp.server_id = s.id
+JOIN players p on p.id = sp.player_id
+WHERE s.alias = ?1
+AND p.discord_user_id = ?2
+ORDER BY sp.nation_id;
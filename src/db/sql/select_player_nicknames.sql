@@ -0,0 +1,6 @@
+SELECT DISTINCT p.discord_user_id, sp.player_nickname
+FROM game_servers s
+JOIN server_players sp on sp.server_id = s.id
+JOIN players p on p.id = sp.player_id
+WHERE s.alias = ?1
+AND sp.player_nickname IS NOT NULL;
@@ -0,0 +1,3 @@
+SELECT alias, poll_interval_secs
+FROM game_servers
+WHERE poll_interval_secs IS NOT NULL;
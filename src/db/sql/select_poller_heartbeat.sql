@@ -0,0 +1,3 @@
+SELECT timestamp
+FROM poller_heartbeat
+WHERE id = 0;
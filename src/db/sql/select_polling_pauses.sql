@@ -0,0 +1,4 @@
+SELECT alias, polling_paused_until
+FROM game_servers
+WHERE polling_paused = 1
+AND (polling_paused_until IS NULL OR polling_paused_until > ?1);
@@ -0,0 +1,3 @@
+SELECT pretenders_seen_at
+FROM game_servers
+WHERE alias = ?1 AND pretenders_seen_at IS NOT NULL;
@@ -0,0 +1,3 @@
+SELECT turn, submitted_nation_ids
+FROM progress_bookmarks
+WHERE alias = ?1 AND user_id = ?2;
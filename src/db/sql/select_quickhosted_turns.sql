@@ -0,0 +1,4 @@
+SELECT turn
+FROM turn_history
+WHERE alias = ?1 AND quickhosted = 1
+ORDER BY turn;
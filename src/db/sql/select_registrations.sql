@@ -0,0 +1,5 @@
+SELECT sp.nation_id, sp.registered_at
+FROM game_servers s
+JOIN server_players sp on sp.server_id = s.id
+WHERE s.alias = ?1
+ORDER BY sp.registered_at IS NULL, sp.registered_at, sp.nation_id;
@@ -0,0 +1,6 @@
+SELECT DISTINCT p.discord_user_id, sp.max_reminders
+FROM game_servers s
+JOIN server_players sp on sp.server_id = s.id
+JOIN players p on p.id = sp.player_id
+WHERE s.alias = ?1
+AND sp.max_reminders_set = 1;
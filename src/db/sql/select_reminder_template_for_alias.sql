@@ -0,0 +1,3 @@
+SELECT reminder_template
+FROM game_servers
+WHERE alias = ?1 AND reminder_template IS NOT NULL;
@@ -0,0 +1,4 @@
+SELECT user_id, waitlisted, waitlist_nation_id
+FROM lobby_rosters
+WHERE alias = ?1
+ORDER BY id;
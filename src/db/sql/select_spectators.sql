@@ -0,0 +1,5 @@
+SELECT s.discord_user_id
+FROM spectators s
+JOIN game_servers g ON g.id = s.server_id
+WHERE g.alias = ?1
+ORDER BY s.discord_user_id;
@@ -0,0 +1,3 @@
+SELECT stall_grace_hours
+FROM game_servers
+WHERE alias = ?1 AND stall_grace_hours IS NOT NULL;
@@ -0,0 +1,3 @@
+SELECT start_reminder_hours
+FROM game_servers
+WHERE alias = ?1 AND start_reminder_hours IS NOT NULL;
@@ -0,0 +1,3 @@
+SELECT alias, started_era
+FROM game_servers
+WHERE started_era IS NOT NULL;
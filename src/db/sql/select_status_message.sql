@@ -0,0 +1,3 @@
+SELECT message_id
+FROM status_messages
+WHERE alias = ?1 AND channel_id = ?2;
@@ -0,0 +1,4 @@
+SELECT turn, nation_id, timestamp
+FROM submission_history
+WHERE alias = ?1
+ORDER BY turn, timestamp;
@@ -0,0 +1,5 @@
+SELECT turn, timestamp
+FROM turn_history
+WHERE alias = ?1
+ORDER BY turn DESC
+LIMIT ?2;
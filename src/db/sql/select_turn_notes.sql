@@ -0,0 +1,5 @@
+SELECT n.turn, n.discord_user_id, n.note, n.created_at
+FROM turn_notes n
+JOIN game_servers g ON g.id = n.server_id
+WHERE g.alias = ?1
+ORDER BY n.turn, n.created_at, n.id;
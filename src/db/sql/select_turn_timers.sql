@@ -0,0 +1,3 @@
+SELECT turn, timer_secs
+FROM turn_history
+WHERE alias = ?1 AND timer_secs IS NOT NULL;
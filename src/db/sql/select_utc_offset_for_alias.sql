@@ -0,0 +1,3 @@
+SELECT utc_offset_secs
+FROM game_servers
+WHERE alias = ?1 AND utc_offset_secs IS NOT NULL;
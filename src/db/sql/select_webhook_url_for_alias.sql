@@ -0,0 +1,3 @@
+SELECT webhook_url
+FROM game_servers
+WHERE alias = ?1;
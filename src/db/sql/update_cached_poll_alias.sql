@@ -0,0 +1,3 @@
+UPDATE cached_polls
+SET alias = ?2
+WHERE alias = ?1;
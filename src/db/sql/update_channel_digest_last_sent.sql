@@ -0,0 +1,3 @@
+UPDATE channel_settings
+SET digest_last_sent = ?2
+WHERE channel_id = ?1;
@@ -0,0 +1,3 @@
+UPDATE final_submissions
+SET alias = ?2
+WHERE alias = ?1;
@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET pretenders_seen = NULL, pretenders_seen_at = NULL, start_reminded = 0
+WHERE alias = ?1 AND pretenders_seen IS NOT NULL;
@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET alias = ?2
+WHERE alias = ?1;
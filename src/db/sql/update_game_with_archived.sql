@@ -0,0 +1 @@
+UPDATE game_servers SET archived = ?2 WHERE alias = ?1 AND started_server_id IS NOT NULL;
@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET auto_archive_at = ?2, auto_archive_skip = ?3
+WHERE alias = ?1;
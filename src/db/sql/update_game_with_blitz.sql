@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET blitz = ?2
+WHERE alias = ?1;
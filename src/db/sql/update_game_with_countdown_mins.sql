@@ -0,0 +1 @@
+UPDATE game_servers SET countdown_mins = ?2 WHERE alias = ?1;
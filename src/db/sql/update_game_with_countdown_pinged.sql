@@ -0,0 +1,4 @@
+UPDATE game_servers
+SET countdown_pinged_turn = ?2, countdown_pinged_mins = ?3
+WHERE alias = ?1
+AND (countdown_pinged_turn IS NULL OR countdown_pinged_turn != ?2 OR countdown_pinged_mins > ?3);
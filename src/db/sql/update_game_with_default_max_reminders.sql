@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET default_max_reminders = ?2
+WHERE alias = ?1;
@@ -0,0 +1,2 @@
+UPDATE game_servers SET description = ?2
+WHERE alias = ?1 AND lobby_id IS NULL;
@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET first_seen_at = ?2, first_seen_turn = ?3
+WHERE alias = ?1 AND first_seen_at IS NULL;
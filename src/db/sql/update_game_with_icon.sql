@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET icon_emoji = ?2, icon_colour = ?3
+WHERE alias = ?1;
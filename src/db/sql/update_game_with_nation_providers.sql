@@ -0,0 +1 @@
+UPDATE game_servers SET nation_providers = ?2 WHERE alias = ?1;
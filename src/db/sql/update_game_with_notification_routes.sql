@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET new_turn_route = ?2, stall_route = ?3, reminder_route = ?4, waiting_on_host_route = ?5
+WHERE alias = ?1;
@@ -1,3 +1,3 @@
 UPDATE game_servers
-SET started_server_id = NULL
+SET started_server_id = NULL, first_seen_at = NULL, first_seen_turn = NULL
 WHERE alias = ?1 AND lobby_id IS NOT NULL;
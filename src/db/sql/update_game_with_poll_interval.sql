@@ -0,0 +1 @@
+UPDATE game_servers SET poll_interval_secs = ?2 WHERE alias = ?1;
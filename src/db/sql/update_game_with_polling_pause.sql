@@ -0,0 +1 @@
+UPDATE game_servers SET polling_paused = ?2, polling_paused_until = ?3 WHERE alias = ?1;
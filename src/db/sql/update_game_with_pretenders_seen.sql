@@ -0,0 +1,4 @@
+UPDATE game_servers
+SET pretenders_seen = ?2, pretenders_seen_at = ?3, start_reminded = 0
+WHERE alias = ?1
+AND (pretenders_seen IS NULL OR pretenders_seen != ?2);
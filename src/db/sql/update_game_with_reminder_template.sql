@@ -0,0 +1 @@
+UPDATE game_servers SET reminder_template = ?2 WHERE alias = ?1;
@@ -0,0 +1,4 @@
+UPDATE game_servers
+SET stall_alerted_turn = ?2
+WHERE alias = ?1
+AND (stall_alerted_turn IS NULL OR stall_alerted_turn != ?2);
@@ -0,0 +1 @@
+UPDATE game_servers SET stall_grace_hours = ?2 WHERE alias = ?1;
@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET start_reminded = 1
+WHERE alias = ?1 AND start_reminded = 0;
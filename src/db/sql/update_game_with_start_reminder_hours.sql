@@ -0,0 +1 @@
+UPDATE game_servers SET start_reminder_hours = ?2 WHERE alias = ?1;
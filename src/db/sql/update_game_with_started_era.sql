@@ -0,0 +1,3 @@
+UPDATE game_servers
+SET started_era = ?2
+WHERE alias = ?1;
@@ -0,0 +1 @@
+UPDATE game_servers SET utc_offset_secs = ?2 WHERE alias = ?1;
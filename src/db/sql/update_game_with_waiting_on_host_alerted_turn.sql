@@ -0,0 +1,4 @@
+UPDATE game_servers
+SET waiting_on_host_alerted_turn = ?2
+WHERE alias = ?1
+AND (waiting_on_host_alerted_turn IS NULL OR waiting_on_host_alerted_turn != ?2);
@@ -0,0 +1,2 @@
+UPDATE game_servers SET webhook_url = ?2
+WHERE alias = ?1;
@@ -0,0 +1,3 @@
+UPDATE lobby_rosters
+SET alias = ?2
+WHERE alias = ?1;
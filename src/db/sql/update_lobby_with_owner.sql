@@ -0,0 +1,3 @@
+UPDATE lobbies
+SET owner_id = (SELECT id FROM players WHERE discord_user_id = ?2)
+WHERE id = (SELECT lobby_id FROM game_servers WHERE alias = ?1);
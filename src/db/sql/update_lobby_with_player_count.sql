@@ -0,0 +1,2 @@
+UPDATE lobbies SET player_count = ?2
+WHERE id = (SELECT lobby_id FROM game_servers WHERE alias = ?1);
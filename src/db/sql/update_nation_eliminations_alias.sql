@@ -0,0 +1,3 @@
+UPDATE nation_eliminations
+SET alias = ?2
+WHERE alias = ?1;
@@ -0,0 +1,3 @@
+UPDATE nation_status_history
+SET alias = ?2
+WHERE alias = ?1;
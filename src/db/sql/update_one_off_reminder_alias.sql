@@ -0,0 +1,3 @@
+UPDATE one_off_reminders
+SET alias = ?2
+WHERE alias = ?1;
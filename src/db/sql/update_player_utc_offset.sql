@@ -0,0 +1 @@
+UPDATE players SET utc_offset_secs = ?2 WHERE discord_user_id = ?1;
@@ -0,0 +1,3 @@
+UPDATE progress_bookmarks
+SET alias = ?2
+WHERE alias = ?1;
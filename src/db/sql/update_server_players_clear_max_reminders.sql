@@ -0,0 +1,4 @@
+UPDATE server_players
+SET max_reminders = NULL, max_reminders_set = 0
+WHERE player_id IN
+    (SELECT id from players WHERE discord_user_id = ?1);
@@ -0,0 +1,8 @@
+UPDATE server_players
+SET player_id = (SELECT id from players WHERE discord_user_id = ?3),
+    player_nickname = NULL,
+    vacation_until = NULL
+WHERE server_id IN
+    (SELECT id from game_servers WHERE alias = ?1)
+AND player_id IN
+    (SELECT id from players WHERE discord_user_id = ?2);
@@ -0,0 +1,3 @@
+UPDATE started_servers
+SET snek_game_id = ?2
+WHERE id = (SELECT started_server_id FROM game_servers WHERE alias = ?1);
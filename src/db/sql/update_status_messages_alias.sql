@@ -0,0 +1,3 @@
+UPDATE status_messages
+SET alias = ?2
+WHERE alias = ?1;
@@ -0,0 +1,3 @@
+UPDATE status_messages
+SET pending_text = ?2
+WHERE alias = ?1;
@@ -0,0 +1,3 @@
+UPDATE submission_history
+SET alias = ?2
+WHERE alias = ?1;
@@ -0,0 +1,3 @@
+UPDATE turn_history
+SET alias = ?2
+WHERE alias = ?1;
@@ -0,0 +1,3 @@
+UPDATE turn_history
+SET quickhosted = 1
+WHERE alias = ?1 AND turn = ?2;
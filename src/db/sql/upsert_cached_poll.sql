@@ -0,0 +1,3 @@
+INSERT INTO cached_polls (alias, cache_entry)
+VALUES (?1, ?2)
+ON CONFLICT (alias) DO UPDATE SET cache_entry = excluded.cache_entry;
@@ -0,0 +1,3 @@
+INSERT INTO channel_settings (channel_id, default_era)
+VALUES (?1, ?2)
+ON CONFLICT (channel_id) DO UPDATE SET default_era = excluded.default_era;
@@ -0,0 +1,6 @@
+INSERT INTO channel_settings (channel_id, digest_weekday, digest_minute, digest_last_sent)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT (channel_id) DO UPDATE SET
+    digest_weekday = excluded.digest_weekday,
+    digest_minute = excluded.digest_minute,
+    digest_last_sent = excluded.digest_last_sent;
@@ -0,0 +1,3 @@
+INSERT INTO channel_settings (channel_id, locale)
+VALUES (?1, ?2)
+ON CONFLICT (channel_id) DO UPDATE SET locale = excluded.locale;
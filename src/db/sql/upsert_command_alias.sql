@@ -0,0 +1,3 @@
+INSERT INTO command_aliases (guild_id, alias, command)
+VALUES (?1, ?2, ?3)
+ON CONFLICT (guild_id, alias) DO UPDATE SET command = excluded.command;
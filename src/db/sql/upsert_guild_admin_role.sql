@@ -0,0 +1,3 @@
+INSERT INTO guild_settings (guild_id, admin_role_id)
+VALUES (?1, ?2)
+ON CONFLICT (guild_id) DO UPDATE SET admin_role_id = excluded.admin_role_id;
@@ -0,0 +1,3 @@
+INSERT INTO guild_settings (guild_id, auto_archive_hours)
+VALUES (?1, ?2)
+ON CONFLICT (guild_id) DO UPDATE SET auto_archive_hours = excluded.auto_archive_hours;
@@ -0,0 +1,3 @@
+INSERT INTO guild_settings (guild_id, details_as_text)
+VALUES (?1, ?2)
+ON CONFLICT (guild_id) DO UPDATE SET details_as_text = excluded.details_as_text;
@@ -0,0 +1,3 @@
+INSERT INTO guild_settings (guild_id, max_games_per_channel)
+VALUES (?1, ?2)
+ON CONFLICT (guild_id) DO UPDATE SET max_games_per_channel = excluded.max_games_per_channel;
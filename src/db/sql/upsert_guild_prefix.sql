@@ -0,0 +1,3 @@
+INSERT INTO guild_settings (guild_id, prefix)
+VALUES (?1, ?2)
+ON CONFLICT (guild_id) DO UPDATE SET prefix = excluded.prefix;
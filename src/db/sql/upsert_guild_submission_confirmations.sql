@@ -0,0 +1,3 @@
+INSERT INTO guild_settings (guild_id, submission_confirmations)
+VALUES (?1, ?2)
+ON CONFLICT (guild_id) DO UPDATE SET submission_confirmations = excluded.submission_confirmations;
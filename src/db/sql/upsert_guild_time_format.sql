@@ -0,0 +1,3 @@
+INSERT INTO guild_settings (guild_id, time_format)
+VALUES (?1, ?2)
+ON CONFLICT (guild_id) DO UPDATE SET time_format = excluded.time_format;
@@ -0,0 +1,5 @@
+INSERT INTO nation_names (server_id, nation_id, nation_name)
+SELECT g.id, ?2, ?3
+FROM game_servers g
+WHERE g.alias = ?1
+ON CONFLICT (server_id, nation_id) DO UPDATE SET nation_name = excluded.nation_name;
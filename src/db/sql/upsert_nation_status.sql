@@ -0,0 +1,3 @@
+INSERT INTO nation_status_history (alias, turn, nation_id, nation_name, status)
+VALUES (?1, ?2, ?3, ?4, ?5)
+ON CONFLICT (alias, turn, nation_id) DO UPDATE SET nation_name = excluded.nation_name, status = excluded.status;
@@ -0,0 +1,3 @@
+INSERT INTO one_off_reminders (alias, user_id, fire_at, message)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT (alias, user_id) DO UPDATE SET fire_at = excluded.fire_at, message = excluded.message;
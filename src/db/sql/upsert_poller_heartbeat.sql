@@ -0,0 +1,2 @@
+INSERT OR REPLACE INTO poller_heartbeat (id, timestamp)
+VALUES (0, ?1);
@@ -0,0 +1,3 @@
+INSERT INTO progress_bookmarks (alias, user_id, turn, submitted_nation_ids)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT (alias, user_id) DO UPDATE SET turn = excluded.turn, submitted_nation_ids = excluded.submitted_nation_ids;
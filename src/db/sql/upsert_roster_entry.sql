@@ -0,0 +1,3 @@
+INSERT INTO lobby_rosters (alias, user_id, waitlisted, waitlist_nation_id)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT (alias, user_id) DO UPDATE SET waitlisted = excluded.waitlisted, waitlist_nation_id = excluded.waitlist_nation_id;
@@ -0,0 +1,3 @@
+INSERT INTO status_messages (alias, channel_id, message_id, pending_text)
+VALUES (?1, ?2, ?3, NULL)
+ON CONFLICT (alias, channel_id) DO UPDATE SET message_id = excluded.message_id, pending_text = NULL;
@@ -7,11 +7,14 @@ macro_rules! mock_server_connection {
     ($struct_name:ident, $ret_val:expr) => {
         struct $struct_name;
         impl ServerConnection for $struct_name {
-            fn get_game_data(_: &str) -> io::Result<crate::model::GameData> {
-                $ret_val
+            fn get_game_data(
+                _: &str,
+            ) -> Result<crate::model::GameData, crate::server::ServerError> {
+                $ret_val.map_err(crate::server::ServerError::from)
             }
             fn get_snek_data(
                 _server_address: &str,
+                _providers: &[&dyn crate::snek::NationNameProvider],
             ) -> Result<Option<crate::snek::SnekGameStatus>, Box<dyn std::error::Error>> {
                 Ok(None)
             }
@@ -24,11 +27,12 @@ macro_rules! mock_conditional_server_connection {
     ($struct_name:ident, $ret_fn:expr) => {
         struct $struct_name;
         impl ServerConnection for $struct_name {
-            fn get_game_data(server_address: &str) -> io::Result<GameData> {
-                $ret_fn(server_address)
+            fn get_game_data(server_address: &str) -> Result<GameData, crate::server::ServerError> {
+                $ret_fn(server_address).map_err(crate::server::ServerError::from)
             }
             fn get_snek_data(
                 _server_address: &str,
+                _providers: &[&dyn crate::snek::NationNameProvider],
             ) -> Result<Option<crate::snek::SnekGameStatus>, Box<dyn std::error::Error>> {
                 Ok(None)
             }
@@ -59,6 +63,7 @@ impl DbConnection {
                     StartedState {
                         address: format!("test.server.address:{}", i),
                         last_seen_turn: i + 30,
+                        option_snek_game_id: None,
                     },
                     None,
                 ),
@@ -93,6 +98,65 @@ impl DbConnection {
         db_conn
     }
 
+    /// A lobby with each `(discord user ID, nation ID)` in `registrations` signed up
+    pub fn insert_test_lobby(
+        &self,
+        alias: &str,
+        owner: UserId,
+        era: Era,
+        player_count: i32,
+        registrations: &[(u64, u32)],
+    ) -> LobbyState {
+        let lobby_state = LobbyState {
+            owner,
+            era,
+            player_count,
+            description: None,
+        };
+        self.insert_game_server(&GameServer {
+            alias: alias.to_owned(),
+            state: GameServerState::Lobby(lobby_state.clone()),
+        })
+        .unwrap();
+        self.insert_test_registrations(alias, registrations);
+        lobby_state
+    }
+
+    /// A game being polled at `address`, with each `(discord user ID, nation ID)`
+    /// in `registrations` playing
+    pub fn insert_test_started_game(
+        &self,
+        alias: &str,
+        address: &str,
+        last_seen_turn: i32,
+        registrations: &[(u64, u32)],
+    ) {
+        self.insert_game_server(&GameServer {
+            alias: alias.to_owned(),
+            state: GameServerState::StartedState(
+                StartedState {
+                    address: address.to_owned(),
+                    last_seen_turn,
+                    option_snek_game_id: None,
+                },
+                None,
+            ),
+        })
+        .unwrap();
+        self.insert_test_registrations(alias, registrations);
+    }
+
+    fn insert_test_registrations(&self, alias: &str, registrations: &[(u64, u32)]) {
+        for &(user_id, nation_id) in registrations {
+            let player = Player {
+                discord_user_id: UserId(user_id),
+                turn_notifications: true,
+            };
+            self.insert_player_into_server(&player, alias, nation_id)
+                .unwrap();
+        }
+    }
+
     pub fn noop() -> Self {
         let manager = SqliteConnectionManager::memory();
         let pool = Pool::new(manager).unwrap();
@@ -0,0 +1,86 @@
+use super::*;
+
+use std::env;
+use std::fs;
+
+fn columns(db_conn: &DbConnection, table: &str) -> Vec<String> {
+    let conn = &*db_conn.0.clone().get().unwrap();
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .unwrap();
+    let rows = stmt.query_map(params![], |row| row.get(1)).unwrap();
+    rows.collect::<Result<Vec<String>, _>>().unwrap()
+}
+
+#[test]
+fn should_migrate_an_empty_db_and_be_safe_to_run_again() {
+    let path = env::temp_dir().join(format!("dom5bot-migrations-{}.db", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let db_conn = DbConnection::new(&path).unwrap();
+    for &(table, column) in &[
+        ("game_servers", "alias"),
+        ("game_servers", "channel_id"),
+        ("game_servers", "utc_offset_secs"),
+        ("game_servers", "reminder_template"),
+        ("server_players", "player_nickname"),
+        ("turn_history", "timer_secs"),
+        ("poller_heartbeat", "timestamp"),
+        ("channel_settings", "default_era"),
+    ] {
+        assert!(
+            columns(&db_conn, table).contains(&column.to_owned()),
+            "{} should have {}",
+            table,
+            column
+        );
+    }
+    drop(db_conn);
+
+    // e.g. restarting the bot: everything's applied already so nothing happens
+    let db_conn = DbConnection::new(&path).unwrap();
+    assert!(columns(&db_conn, "game_servers").contains(&"reminder_template".to_owned()));
+    drop(db_conn);
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn should_name_claimant_when_nation_already_registered() {
+    let db_conn = DbConnection::test();
+    let player = Player {
+        discord_user_id: UserId(320_001),
+        turn_notifications: true,
+    };
+    // "test server 3" has UserId(103) registered as nation 3
+    let error = db_conn
+        .insert_player_into_server(&player, "test server 3", 3)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Nation 3 is already registered to <@103> in test server 3"
+    );
+    assert_eq!(
+        db_conn
+            .players_with_nations_for_game_alias("test server 3")
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn should_suggest_close_aliases_only_when_there_is_no_exact_match() {
+    let db_conn = DbConnection::test();
+    assert!(db_conn.game_for_alias("test server 1").is_ok());
+
+    let err = db_conn.game_for_alias("test servr 1").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not find the game with alias test servr 1, did you mean test server 1 or test server 2 or test server 3?"
+    );
+    let err = db_conn.game_for_alias("something else").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not find the game with alias something else"
+    );
+}
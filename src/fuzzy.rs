@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests;
+
+/// How many single character inserts, deletes or swaps turn `a` into `b`
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + if a_char == b_char { 0 } else { 1 };
+            row.push(substitution.min(previous_row[j + 1] + 1).min(row[j] + 1));
+        }
+        previous_row = row;
+    }
+    previous_row[b.len()]
+}
+
+/// Up to `max_matches` of `candidates` closest to `arg`, best first, ignoring
+/// case. Nothing that's more wrong than right.
+pub fn closest_matches<'a>(
+    arg: &str,
+    candidates: &'a [String],
+    max_matches: usize,
+) -> Vec<&'a str> {
+    let arg = arg.to_lowercase();
+    let max_distance = (arg.chars().count() / 3).max(1);
+    let mut scored: Vec<(usize, &str)> = candidates
+        .iter()
+        .map(|candidate| {
+            (
+                edit_distance(&arg, &candidate.to_lowercase()),
+                candidate.as_str(),
+            )
+        })
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+    scored.sort();
+    scored
+        .into_iter()
+        .take(max_matches)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
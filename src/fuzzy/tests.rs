@@ -0,0 +1,24 @@
+use super::*;
+
+#[test]
+fn should_count_single_character_edits() {
+    assert_eq!(edit_distance("mygame", "mygame"), 0);
+    assert_eq!(edit_distance("mygaem", "mygame"), 2);
+    assert_eq!(edit_distance("mygam", "mygame"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+}
+
+#[test]
+fn should_suggest_only_close_matches_best_first() {
+    let aliases = vec![
+        "mygame".to_owned(),
+        "mygames".to_owned(),
+        "othergame".to_owned(),
+    ];
+    assert_eq!(
+        closest_matches("mygaem", &aliases, 3),
+        vec!["mygame", "mygames"]
+    );
+    assert_eq!(closest_matches("MyGame", &aliases, 1), vec!["mygame"]);
+    assert!(closest_matches("something else", &aliases, 3).is_empty());
+}
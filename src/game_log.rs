@@ -0,0 +1,65 @@
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// `key=value` fields to put on the end of log lines about one game, so that a
+/// busy log can be filtered down to it, e.g. with `grep 'alias="foo"'`. The
+/// logger has no structured fields of its own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameLogContext<'a> {
+    pub alias: &'a str,
+    pub option_address: Option<&'a str>,
+    pub option_turn: Option<i32>,
+    /// e.g. "ok", or what kind of error it was
+    pub option_outcome: Option<&'a str>,
+}
+
+impl<'a> GameLogContext<'a> {
+    pub fn new(alias: &'a str) -> Self {
+        GameLogContext {
+            alias,
+            option_address: None,
+            option_turn: None,
+            option_outcome: None,
+        }
+    }
+
+    pub fn address(self, address: &'a str) -> Self {
+        GameLogContext {
+            option_address: Some(address),
+            ..self
+        }
+    }
+
+    pub fn turn(self, turn: i32) -> Self {
+        GameLogContext {
+            option_turn: Some(turn),
+            ..self
+        }
+    }
+
+    pub fn outcome(self, outcome: &'a str) -> Self {
+        GameLogContext {
+            option_outcome: Some(outcome),
+            ..self
+        }
+    }
+}
+
+/// Strings are quoted since aliases and outcomes can have spaces in them
+impl<'a> fmt::Display for GameLogContext<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "alias={:?}", self.alias)?;
+        if let Some(address) = self.option_address {
+            write!(f, " address={:?}", address)?;
+        }
+        if let Some(turn) = self.option_turn {
+            write!(f, " turn={}", turn)?;
+        }
+        if let Some(outcome) = self.option_outcome {
+            write!(f, " outcome={:?}", outcome)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use super::*;
+
+#[test]
+fn should_only_show_fields_that_are_known() {
+    assert_eq!(
+        GameLogContext::new("my game").to_string(),
+        "alias=\"my game\""
+    );
+    assert_eq!(
+        GameLogContext::new("foo")
+            .address("example.com:2556")
+            .turn(12)
+            .outcome("bad data")
+            .to_string(),
+        "alias=\"foo\" address=\"example.com:2556\" turn=12 outcome=\"bad data\""
+    );
+}
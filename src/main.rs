@@ -1,47 +1,44 @@
 mod commands;
 #[cfg_attr(test, macro_use)]
 mod db;
+mod fuzzy;
+mod game_log;
+mod metrics;
 mod model;
+mod notifier;
 mod server;
 mod snek;
 
 use serenity::framework::standard::StandardFramework;
+use serenity::model::id::UserId;
 use serenity::prelude::*;
 use simplelog::{Config, LogLevelFilter, SimpleLogger};
 
 use failure::*;
 use log::*;
+use std::collections::HashSet;
 use std::env;
-use std::fs::File;
-use std::io::Read;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::net::TcpListener;
 use std::sync::Arc;
 use std::thread;
+use std::time;
 
 use crate::db::*;
+use crate::metrics::{serve_metrics, Metrics};
+use crate::notifier::DiscordNotifier;
 use crate::server::RealServerConnection;
 
-use commands::servers::CacheEntry;
-use evmap;
-
-use chrono::{DateTime, Utc};
-
-pub struct CacheWriteHandle(
-    pub evmap::WriteHandle<String, Box<(DateTime<Utc>, Option<CacheEntry>)>>,
-);
-pub struct CacheReadHandle(
-    pub evmap::ReadHandleFactory<String, Box<(DateTime<Utc>, Option<CacheEntry>)>>,
-);
-impl CacheReadHandle {
-    fn get_clone(&self, alias: &str) -> Option<Option<CacheEntry>> {
-        self.0.handle().get_and(alias, |values| {
-            if values.len() != 1 {
-                panic!()
-            } else {
-                (*values[0]).1.clone()
-            }
-        })
-    }
-}
+use commands::servers::turn_check::DEFAULT_POLL_TIMEOUT;
+use commands::servers::{
+    DetailsCooldowns, DetailsCooldownsKey, GameCache, GameCacheKey, GuildCommands,
+    GuildCommandsFramework, GuildCommandsKey, NationEmojis, NationEmojisKey, SelfReports,
+    SelfReportsKey, Translations, TranslationsKey, CACHE_TTL_HOURS, DEFAULT_PREFIX,
+    DETAILS_COOLDOWN_SECS, GAME_CACHE_TTL_MINS,
+};
+
+use chrono::Duration;
 
 struct Handler;
 impl EventHandler for Handler {}
@@ -73,13 +70,79 @@ fn read_token() -> Result<String, Error> {
     Ok(temp_token)
 }
 
-struct DetailsReadHandleKey;
-impl typemap::Key for DetailsReadHandleKey {
-    type Value = CacheReadHandle;
+/// The discord users who run the bot, one ID per line. Without any nobody can
+/// use the commands that affect every game.
+fn read_owners() -> Result<HashSet<UserId>, Error> {
+    let owners_path = env::current_dir()?.join("resources/owners");
+    match fs::read_to_string(&owners_path) {
+        Ok(owners) => {
+            let owners = owners
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| {
+                    line.parse::<u64>()
+                        .map(UserId)
+                        .context(format!("Reading owner ID '{}'", line))
+                })
+                .collect::<Result<HashSet<UserId>, _>>()?;
+            info!("Read {} bot owners", owners.len());
+            Ok(owners)
+        }
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+            info!("No bot owners at {}", owners_path.display());
+            Ok(HashSet::new())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// How many seconds to keep a lobby's details for, so a busy lobby's !details
+/// doesn't go to the database every time. Without the file lobbies aren't
+/// cached, so changes to them show up straight away.
+fn read_lobby_cache_ttl() -> Result<Duration, Error> {
+    let lobby_cache_path = env::current_dir()?.join("resources/lobby_cache_secs");
+    match fs::read_to_string(&lobby_cache_path) {
+        Ok(secs) => {
+            let secs = secs
+                .trim()
+                .parse::<i64>()
+                .context(format!("Reading lobby cache TTL '{}'", secs.trim()))?;
+            info!("Caching lobbies for {}s", secs);
+            Ok(Duration::seconds(secs))
+        }
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+            info!("No lobby cache TTL at {}", lobby_cache_path.display());
+            Ok(Duration::zero())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// How many seconds the poller gives each server, retries included, before
+/// giving up on it until the next poll
+fn read_poll_timeout() -> Result<time::Duration, Error> {
+    let poll_timeout_path = env::current_dir()?.join("resources/poll_timeout_secs");
+    match fs::read_to_string(&poll_timeout_path) {
+        Ok(secs) => {
+            let secs = secs
+                .trim()
+                .parse::<u64>()
+                .context(format!("Reading poll timeout '{}'", secs.trim()))?;
+            info!("Giving each server {}s per poll", secs);
+            Ok(time::Duration::from_secs(secs))
+        }
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+            info!("No poll timeout at {}", poll_timeout_path.display());
+            Ok(DEFAULT_POLL_TIMEOUT)
+        }
+        Err(e) => Err(e.into()),
+    }
 }
 
 fn create_discord_client() -> Result<Client, Error> {
     let token = read_token().context("Reading token file")?;
+    let owners = read_owners().context("Reading owners file")?;
 
     let path = env::current_dir()?;
     let path = path.join("resources/dom5bot.db");
@@ -87,51 +150,104 @@ fn create_discord_client() -> Result<Client, Error> {
         DbConnection::new(&path).context(format!("Opening database '{}'", path.display()))?;
     info!("Opened database connection");
 
-    let (reader, write) = evmap::new();
+    let nation_emojis_path = env::current_dir()?.join("resources/nation_emojis");
+    let nation_emojis = NationEmojis::load(&nation_emojis_path).context(format!(
+        "Loading nation emojis '{}'",
+        nation_emojis_path.display()
+    ))?;
+
+    let guild_commands = Arc::new(GuildCommands::load(&db_conn).context("Loading guild commands")?);
+
+    let translations_path = env::current_dir()?.join("resources/locales");
+    let translations = Translations::load(&translations_path).context(format!(
+        "Loading translations '{}'",
+        translations_path.display()
+    ))?;
+
+    let lobby_cache_ttl = read_lobby_cache_ttl().context("Reading lobby cache TTL file")?;
+    let poll_timeout = read_poll_timeout().context("Reading poll timeout file")?;
+    let game_cache = Arc::new(
+        GameCache::new(Duration::minutes(GAME_CACHE_TTL_MINS)).with_lobby_ttl(lobby_cache_ttl),
+    );
 
     let mut discord_client = Client::new(&token, Handler).map_err(SyncFailure::new)?;
     info!("Created discord client");
     {
         let mut data = discord_client.data.lock();
         data.insert::<DbConnectionKey>(db_conn.clone());
-        data.insert::<DetailsReadHandleKey>(CacheReadHandle(reader.factory()));
+        data.insert::<GameCacheKey>(game_cache.clone());
+        data.insert::<NationEmojisKey>(nation_emojis);
+        data.insert::<TranslationsKey>(translations);
+        data.insert::<GuildCommandsKey>(guild_commands.clone());
+        data.insert::<DetailsCooldownsKey>(DetailsCooldowns::new(Duration::seconds(
+            DETAILS_COOLDOWN_SECS,
+        )));
+        data.insert::<SelfReportsKey>(SelfReports::default());
     }
 
     use crate::commands::servers::WithServersCommands;
     use crate::commands::WithSearchCommands;
-    discord_client.with_framework(
-        StandardFramework::new()
-            .configure(|c| c.prefix("!"))
-            .simple_bucket("simple", 1)
-            .with_search_commands("simple")
-            .with_servers_commands::<RealServerConnection>("simple")
-            .help(|_, msg, _, _, _| commands::help(msg))
-            .before(|_, msg, _| {
-                info!("received message {:?}", msg);
-                !msg.author.bot // ignore bots
-            })
-            .after(|_ctx, msg, _cmd_name, result| {
-                if let Err(err) = result {
-                    print!("command error: ");
-                    let text = format!("ERROR: {}", err.0);
-                    info!("replying with {}", text);
-                    let _ = msg.reply(&text);
-                }
-            }),
-    );
+    let prefix_guild_commands = guild_commands.clone();
+    let framework = StandardFramework::new()
+        .configure(|c| {
+            c.prefix(DEFAULT_PREFIX)
+                .owners(owners)
+                .dynamic_prefix(move |_, msg| prefix_guild_commands.option_prefix(msg.guild_id))
+        })
+        .simple_bucket("simple", 1)
+        .with_search_commands("simple")
+        .with_servers_commands::<RealServerConnection>("simple")
+        .help(|cx, msg, _, _, _| commands::help(cx, msg))
+        .before(|_, msg, _| {
+            info!("received message {:?}", msg);
+            !msg.author.bot // ignore bots
+        })
+        .after(|_ctx, msg, _cmd_name, result| {
+            if let Err(err) = result {
+                print!("command error: ");
+                let text = format!("ERROR: {}", err.0);
+                info!("replying with {}", text);
+                let _ = msg.reply(&text);
+            }
+        });
+    discord_client.with_framework(GuildCommandsFramework {
+        framework,
+        guild_commands,
+    });
     info!("Configured discord client");
 
-    let writer_mutex = Arc::new(Mutex::new(CacheWriteHandle(write)));
-    let writer_mutex_clone = writer_mutex.clone();
+    let metrics = Arc::new(Mutex::new(Metrics::default()));
+    // Nothing's exported unless there's somewhere to export it to
+    let metrics_address_path = env::current_dir()?.join("resources/metrics_address");
+    match fs::read_to_string(&metrics_address_path) {
+        Ok(metrics_address) => {
+            let listener = TcpListener::bind(metrics_address.trim()).context(format!(
+                "Listening for metrics on '{}'",
+                metrics_address.trim()
+            ))?;
+            info!("Serving metrics on {}", metrics_address.trim());
+            let metrics_clone = metrics.clone();
+            thread::spawn(move || serve_metrics(listener, metrics_clone));
+        }
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+            info!("No metrics address at {}", metrics_address_path.display());
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let game_cache_clone = game_cache.clone();
+    thread::spawn(move || {
+        crate::commands::servers::turn_check::update_details_cache_loop::<
+            RealServerConnection,
+            DiscordNotifier,
+        >(db_conn.clone(), game_cache_clone, metrics, poll_timeout);
+    });
     thread::spawn(move || {
-        crate::commands::servers::turn_check::update_details_cache_loop(
-            db_conn.clone(),
-            writer_mutex_clone,
+        crate::commands::servers::turn_check::remove_old_entries_from_cache_loop(
+            game_cache,
+            Duration::hours(CACHE_TTL_HOURS),
         );
     });
-    //    thread::spawn(move || {
-    //        crate::commands::servers::turn_check::remove_old_entries_from_cache_loop(writer_mutex);
-    //    });
 
     // start listening for events by starting a single shard
     Ok(discord_client)
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use log::*;
+use serenity::prelude::Mutex;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+
+use crate::commands::servers::{is_paused_timer, CacheEntry};
+use crate::server::ServerError;
+
+#[cfg(test)]
+mod tests;
+
+/// What the poller last knew about one game
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct GameMetrics {
+    pub poll_successes: u64,
+    pub poll_failures: u64,
+    /// Negative while the game is still uploading
+    pub option_turn: Option<i32>,
+    /// Only while the last poll worked and the timer is running
+    pub option_secs_to_deadline: Option<i64>,
+}
+
+/// Counters and gauges in the Prometheus text format, kept up to date by the
+/// poller so that scraping never has to talk to a game server
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Metrics {
+    pub tracked_games: usize,
+    pub games: BTreeMap<String, GameMetrics>,
+}
+
+impl Metrics {
+    /// Forgets games that have been removed, so their gauges don't linger
+    pub fn record_pass<'a>(&mut self, aliases: impl Iterator<Item = &'a str>) {
+        let aliases: Vec<&str> = aliases.collect();
+        self.tracked_games = aliases.len();
+        self.games
+            .retain(|alias, _| aliases.contains(&alias.as_str()));
+    }
+
+    pub fn record_poll(
+        &mut self,
+        alias: &str,
+        poll: &Result<CacheEntry, ServerError>,
+        now: DateTime<Utc>,
+    ) {
+        let game_metrics = self.games.entry(alias.to_owned()).or_default();
+        match poll {
+            Ok(cache_entry) => {
+                let turn_timer = cache_entry.game_data.turn_timer;
+                game_metrics.poll_successes += 1;
+                game_metrics.option_turn = Some(cache_entry.game_data.turn);
+                game_metrics.option_secs_to_deadline =
+                    if turn_timer > 0 && !is_paused_timer(turn_timer) {
+                        let since_fetched = now.signed_duration_since(cache_entry.fetched_at);
+                        Some(i64::from(turn_timer / 1000) - since_fetched.num_seconds())
+                    } else {
+                        None
+                    };
+            }
+            Err(_) => {
+                game_metrics.poll_failures += 1;
+                game_metrics.option_secs_to_deadline = None;
+            }
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        text.push_str("# HELP dom5status_tracked_games Number of games the bot knows about\n");
+        text.push_str("# TYPE dom5status_tracked_games gauge\n");
+        text.push_str(&format!(
+            "dom5status_tracked_games {}\n",
+            self.tracked_games
+        ));
+
+        text.push_str("# HELP dom5status_polls_total Server polls by game and result\n");
+        text.push_str("# TYPE dom5status_polls_total counter\n");
+        for (alias, game_metrics) in &self.games {
+            let alias = escape_label(alias);
+            text.push_str(&format!(
+                "dom5status_polls_total{{alias=\"{}\",result=\"success\"}} {}\n",
+                alias, game_metrics.poll_successes
+            ));
+            text.push_str(&format!(
+                "dom5status_polls_total{{alias=\"{}\",result=\"failure\"}} {}\n",
+                alias, game_metrics.poll_failures
+            ));
+        }
+
+        text.push_str("# HELP dom5status_turn Current turn, negative while uploading\n");
+        text.push_str("# TYPE dom5status_turn gauge\n");
+        for (alias, game_metrics) in &self.games {
+            if let Some(turn) = game_metrics.option_turn {
+                text.push_str(&format!(
+                    "dom5status_turn{{alias=\"{}\"}} {}\n",
+                    escape_label(alias),
+                    turn
+                ));
+            }
+        }
+
+        text.push_str("# HELP dom5status_seconds_to_deadline Seconds until the turn hosts\n");
+        text.push_str("# TYPE dom5status_seconds_to_deadline gauge\n");
+        for (alias, game_metrics) in &self.games {
+            if let Some(secs) = game_metrics.option_secs_to_deadline {
+                text.push_str(&format!(
+                    "dom5status_seconds_to_deadline{{alias=\"{}\"}} {}\n",
+                    escape_label(alias),
+                    secs
+                ));
+            }
+        }
+        text
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn respond(stream: &mut TcpStream, metrics: &Mutex<Metrics>) -> io::Result<()> {
+    // Only the request line matters, and it's always in the first read
+    let mut buf = [0; 1024];
+    let read = stream.read(&mut buf)?;
+    let request = String::from_utf8_lossy(&buf[..read]);
+    let response = if request.starts_with("GET /metrics ") {
+        let body = metrics.lock().render();
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    } else {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned()
+    };
+    stream.write_all(response.as_bytes())
+}
+
+/// Answers scrapes of `/metrics` one at a time, forever
+pub fn serve_metrics(listener: TcpListener, metrics: Arc<Mutex<Metrics>>) {
+    for stream in listener.incoming() {
+        let result = stream.and_then(|mut stream| respond(&mut stream, &metrics));
+        if let Err(e) = result {
+            error!("Could not serve metrics with error: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::*;
+
+use crate::model::GameData;
+use chrono::{Duration, TimeZone};
+
+fn cache_entry(turn: i32, turn_timer: i32, fetched_at: DateTime<Utc>) -> CacheEntry {
+    CacheEntry {
+        game_data: GameData {
+            game_name: "foo".to_owned(),
+            nations: vec![],
+            turn,
+            turn_timer,
+        },
+        option_snek_state: None,
+        fetched_at,
+    }
+}
+
+#[test]
+fn should_count_polls_and_keep_latest_turn() {
+    let fetched_at = Utc.timestamp(0, 0);
+    let mut metrics = Metrics::default();
+    metrics.record_pass(vec!["foo", "bar"].into_iter());
+    metrics.record_poll(
+        "foo",
+        &Ok(cache_entry(12, 3_600_000, fetched_at)),
+        fetched_at,
+    );
+    metrics.record_poll(
+        "foo",
+        &Ok(cache_entry(13, 3_600_000, fetched_at)),
+        fetched_at + Duration::seconds(60),
+    );
+    metrics.record_poll("bar", &Err(ServerError::Timeout), fetched_at);
+
+    assert_eq!(metrics.tracked_games, 2);
+    assert_eq!(
+        metrics.games["foo"],
+        GameMetrics {
+            poll_successes: 2,
+            poll_failures: 0,
+            option_turn: Some(13),
+            // a minute has passed since we read the timer
+            option_secs_to_deadline: Some(3600 - 60),
+        }
+    );
+    assert_eq!(metrics.games["bar"].poll_failures, 1);
+    assert_eq!(metrics.games["bar"].option_turn, None);
+}
+
+#[test]
+fn should_forget_removed_games() {
+    let now = Utc.timestamp(0, 0);
+    let mut metrics = Metrics::default();
+    metrics.record_pass(vec!["foo"].into_iter());
+    metrics.record_poll("foo", &Ok(cache_entry(12, 0, now)), now);
+    metrics.record_pass(vec!["bar"].into_iter());
+
+    assert_eq!(metrics.tracked_games, 1);
+    assert!(metrics.games.is_empty());
+}
+
+#[test]
+fn should_render_prometheus_text() {
+    let now = Utc.timestamp(0, 0);
+    let mut metrics = Metrics::default();
+    metrics.record_pass(vec!["say \"hi\""].into_iter());
+    // paused, so no deadline
+    metrics.record_poll("say \"hi\"", &Ok(cache_entry(3, -1, now)), now);
+
+    let text = metrics.render();
+    assert!(text.contains("dom5status_tracked_games 1\n"));
+    assert!(
+        text.contains("dom5status_polls_total{alias=\"say \\\"hi\\\"\",result=\"success\"} 1\n")
+    );
+    assert!(text.contains("dom5status_turn{alias=\"say \\\"hi\\\"\"} 3\n"));
+    assert!(!text.contains("dom5status_seconds_to_deadline{"));
+}
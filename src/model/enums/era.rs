@@ -1,4 +1,5 @@
 use enum_primitive_derive::Primitive;
+use serde::{Serialize, Serializer};
 use std::fmt;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Primitive)]
@@ -29,3 +30,9 @@ impl fmt::Display for Era {
         f.write_str(text)
     }
 }
+
+impl Serialize for Era {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
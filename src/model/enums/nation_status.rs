@@ -1,3 +1,5 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 #[repr(u8)]
 pub enum NationStatus {
@@ -41,4 +43,46 @@ impl NationStatus {
             _ => false,
         }
     }
+
+    pub fn is_defeated(&self) -> bool {
+        matches!(
+            self,
+            NationStatus::DefeatedThisTurn | NationStatus::Defeated
+        )
+    }
+}
+
+impl Serialize for NationStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let text = match self {
+            NationStatus::Empty => "empty",
+            NationStatus::Human => "human",
+            NationStatus::AI => "ai",
+            NationStatus::Independent => "independent",
+            NationStatus::Closed => "closed",
+            NationStatus::DefeatedThisTurn => "defeated_this_turn",
+            NationStatus::Defeated => "defeated",
+        };
+        serializer.serialize_str(text)
+    }
+}
+
+/// The other way round from `Serialize`, so that a persisted poll can be read back
+impl<'de> Deserialize<'de> for NationStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        match text.as_ref() {
+            "empty" => Ok(NationStatus::Empty),
+            "human" => Ok(NationStatus::Human),
+            "ai" => Ok(NationStatus::AI),
+            "independent" => Ok(NationStatus::Independent),
+            "closed" => Ok(NationStatus::Closed),
+            "defeated_this_turn" => Ok(NationStatus::DefeatedThisTurn),
+            "defeated" => Ok(NationStatus::Defeated),
+            _ => Err(de::Error::custom(format!(
+                "\"{}\" is not a nation status",
+                text
+            ))),
+        }
+    }
 }
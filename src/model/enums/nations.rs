@@ -5,11 +5,21 @@ use std::collections::HashMap;
 
 pub struct Nations;
 impl Nations {
-    pub fn get_nation_desc(n: u32) -> &'static NationEnum {
-        NATIONS_BY_ID.get(&n).unwrap_or_else(|| {
+    /// `None` for nations we don't know about, e.g. ones added by a newer patch
+    pub fn get_nation_desc(n: u32) -> Option<&'static NationEnum> {
+        let option_nation_desc = NATIONS_BY_ID.get(&n);
+        if option_nation_desc.is_none() {
             info!("unknown nation {}", n);
-            &("unknown nation", Era::Early) // FIXME
-        })
+        }
+        option_nation_desc
+    }
+
+    /// The nation's name, or a placeholder that makes it obvious we don't know it
+    pub fn get_nation_name(n: u32) -> String {
+        match Nations::get_nation_desc(n) {
+            Some(&(nation_name, _)) => nation_name.to_owned(),
+            None => format!("Unknown nation #{}", n),
+        }
     }
 
     pub fn from_id(id: u32) -> Option<Nation> {
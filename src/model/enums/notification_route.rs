@@ -0,0 +1,45 @@
+use enum_primitive_derive::Primitive;
+use std::fmt;
+
+/// Where one kind of announcement about a game goes, see `!notify`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Primitive)]
+pub enum NotificationRoute {
+    /// The game's channels, and the spectators that follow them
+    Channel = 0,
+    /// Each registered player it's about
+    Dm = 1,
+    Both = 2,
+    Off = 3,
+}
+
+impl NotificationRoute {
+    pub fn from_string(string: &str) -> Option<NotificationRoute> {
+        match string.to_lowercase().as_ref() {
+            "channel" => Some(NotificationRoute::Channel),
+            "dm" => Some(NotificationRoute::Dm),
+            "both" => Some(NotificationRoute::Both),
+            "off" | "none" => Some(NotificationRoute::Off),
+            _ => None,
+        }
+    }
+
+    pub fn to_channel(self) -> bool {
+        self == NotificationRoute::Channel || self == NotificationRoute::Both
+    }
+
+    pub fn to_dm(self) -> bool {
+        self == NotificationRoute::Dm || self == NotificationRoute::Both
+    }
+}
+
+impl fmt::Display for NotificationRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match *self {
+            NotificationRoute::Channel => "channel",
+            NotificationRoute::Dm => "dm",
+            NotificationRoute::Both => "both",
+            NotificationRoute::Off => "off",
+        };
+        f.write_str(text)
+    }
+}
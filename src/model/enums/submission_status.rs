@@ -1,3 +1,4 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::borrow::Cow;
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
@@ -26,3 +27,34 @@ impl SubmissionStatus {
         }
     }
 }
+
+/// Names rather than the numbers the server uses, so that they mean something
+/// to whoever's reading the json
+impl Serialize for SubmissionStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            SubmissionStatus::NotSubmitted => serializer.serialize_str("not_submitted"),
+            SubmissionStatus::PartiallySubmitted => serializer.serialize_str("partially_submitted"),
+            SubmissionStatus::Submitted => serializer.serialize_str("submitted"),
+            SubmissionStatus::Unknown(x) => serializer.serialize_str(&format!("unknown_{}", x)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SubmissionStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        match text.as_ref() {
+            "not_submitted" => Ok(SubmissionStatus::NotSubmitted),
+            "partially_submitted" => Ok(SubmissionStatus::PartiallySubmitted),
+            "submitted" => Ok(SubmissionStatus::Submitted),
+            _ => text
+                .strip_prefix("unknown_")
+                .and_then(|x| x.parse::<u8>().ok())
+                .map(SubmissionStatus::Unknown)
+                .ok_or_else(|| {
+                    de::Error::custom(format!("\"{}\" is not a submission status", text))
+                }),
+        }
+    }
+}
@@ -1,9 +1,33 @@
+use crate::model::enums::NationStatus;
 use crate::model::nation::Nation;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GameData {
     pub game_name: String,
     pub nations: Vec<Nation>,
     pub turn: i32,
     pub turn_timer: i32,
 }
+
+impl GameData {
+    /// The only nation left once every other one that played has been
+    /// defeated, which is as finished as a game gets without a host saying so
+    pub fn sole_survivor(&self) -> Option<&Nation> {
+        let anyone_defeated = self
+            .nations
+            .iter()
+            .any(|nation| nation.status.is_defeated());
+        if self.turn <= 0 || !anyone_defeated {
+            return None;
+        }
+        let mut survivors = self
+            .nations
+            .iter()
+            .filter(|nation| matches!(nation.status, NationStatus::Human | NationStatus::AI));
+        match (survivors.next(), survivors.next()) {
+            (Some(survivor), None) => Some(survivor),
+            _ => None,
+        }
+    }
+}
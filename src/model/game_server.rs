@@ -1,4 +1,5 @@
-use crate::model::enums::Era;
+use crate::model::enums::{Era, NotificationRoute};
+use chrono::{DateTime, Utc};
 use serenity::model::id::UserId;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -11,18 +12,102 @@ pub struct GameServer {
 pub enum GameServerState {
     StartedState(StartedState, Option<LobbyState>),
     Lobby(LobbyState),
+    /// A finished game: the poller leaves it alone, but its players and turn
+    /// history are kept around
+    Archived(StartedState, Option<LobbyState>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StartedState {
     pub address: String,
     pub last_seen_turn: i32,
+    /// Only known once snek has told us about the game
+    pub option_snek_game_id: Option<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct LobbyState {
     pub owner: UserId,
     pub era: Era,
+    /// 0 for lobbies imported without one, see `option_player_count`
     pub player_count: i32,
     pub description: Option<String>,
 }
+
+impl LobbyState {
+    /// `None` when nobody told us how many players the lobby is for, in which
+    /// case it's never full
+    pub fn option_player_count(&self) -> Option<i32> {
+        if self.player_count > 0 {
+            Some(self.player_count)
+        } else {
+            None
+        }
+    }
+}
+
+/// How a game stands out from the rest in listings and its details embed,
+/// see `!seticon`. Either part can be left unset.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GameIcon {
+    pub option_emoji: Option<String>,
+    /// As `0xRRGGBB`
+    pub option_colour: Option<u32>,
+}
+
+impl GameIcon {
+    /// The emoji and a space, ready to go in front of the game's alias
+    pub fn prefix(&self) -> String {
+        match &self.option_emoji {
+            Some(emoji) => format!("{} ", emoji),
+            None => String::new(),
+        }
+    }
+}
+
+/// Something a host wanted to remember about a turn, see `!turnnote`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnNote {
+    pub turn: i32,
+    pub author: UserId,
+    pub note: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// When we first saw a started game playing, see `record_first_seen`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FirstSeen {
+    pub at: DateTime<Utc>,
+    pub turn: i32,
+}
+
+impl FirstSeen {
+    /// Games added part way through were started some time before we saw them
+    pub fn is_from_start(&self) -> bool {
+        self.turn <= 1
+    }
+}
+
+/// Where each kind of announcement about a game goes. The defaults are how it
+/// worked before they could be changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationRoutes {
+    /// "Turn N has begun" in the channel, and the new turn DMs
+    pub new_turn: NotificationRoute,
+    pub stall: NotificationRoute,
+    /// The countdown pings as the deadline gets close
+    pub reminder: NotificationRoute,
+    /// Everyone has played but the turn hasn't hosted yet, off unless asked for
+    pub waiting_on_host: NotificationRoute,
+}
+
+impl Default for NotificationRoutes {
+    fn default() -> Self {
+        NotificationRoutes {
+            new_turn: NotificationRoute::Both,
+            stall: NotificationRoute::Channel,
+            reminder: NotificationRoute::Channel,
+            waiting_on_host: NotificationRoute::Off,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::model::enums::{NationStatus, SubmissionStatus};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Nation {
     pub id: u32,
     pub status: NationStatus,
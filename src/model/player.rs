@@ -5,3 +5,14 @@ pub struct Player {
     pub discord_user_id: UserId,
     pub turn_notifications: bool,
 }
+
+/// Somebody a lobby's owner is expecting, or who is waiting for one of their
+/// places to free up. See `!roster`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RosterEntry {
+    pub user_id: UserId,
+    pub waitlisted: bool,
+    /// What they tried to register as while waitlisted, so they can be registered
+    /// as it when they get in
+    pub option_nation_id: Option<u32>,
+}
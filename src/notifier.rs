@@ -0,0 +1,154 @@
+use log::*;
+use serenity::framework::standard::CommandError;
+use serenity::http::{HttpError, StatusCode};
+use serenity::model::id::{ChannelId, GuildId, MessageId, UserId};
+use std::thread;
+use std::time;
+
+#[cfg(test)]
+pub mod test_helpers;
+#[cfg(test)]
+mod tests;
+
+/// Tries before a webhook event is dropped
+const WEBHOOK_ATTEMPTS: u32 = 3;
+/// Doubled after each failed try
+const WEBHOOK_BACKOFF: time::Duration = time::Duration::from_secs(5);
+const WEBHOOK_TIMEOUT: time::Duration = time::Duration::from_secs(10);
+
+/// Where notifications end up. The bot mostly talks to Discord, plus whatever
+/// webhooks hosts have set up, but the poller doesn't need to know that.
+pub trait Notifier {
+    fn dm(user_id: UserId, message: &str) -> Result<(), CommandError>;
+    fn channel(channel_id: ChannelId, message: &str) -> Result<(), CommandError>;
+    /// For messages that are going to be edited later. Pinned if we're allowed.
+    fn post_pinned(channel_id: ChannelId, message: &str) -> Result<MessageId, CommandError>;
+    /// False if the message isn't there to edit any more, e.g. someone deleted it
+    fn edit(
+        channel_id: ChannelId,
+        message_id: MessageId,
+        message: &str,
+    ) -> Result<bool, CommandError>;
+    /// For settings that belong to a whole server. `None` for DMs and channels
+    /// we can't see.
+    fn guild_for_channel(channel_id: ChannelId) -> Option<GuildId>;
+    /// POSTs `payload` to a webhook outside Discord. Mustn't hold up the poller,
+    /// so it's fine to give up on it.
+    fn webhook(url: &str, payload: &serde_json::Value) -> Result<(), CommandError>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WebhookError {
+    /// Worth trying again, e.g. a timeout or the other end being overloaded
+    Transient(String),
+    Permanent(String),
+}
+
+/// Calls `send` until it works, it fails for good, or it's been tried
+/// `attempts` times
+pub fn with_retries<F>(
+    attempts: u32,
+    backoff: time::Duration,
+    mut send: F,
+) -> Result<(), WebhookError>
+where
+    F: FnMut() -> Result<(), WebhookError>,
+{
+    let mut delay = backoff;
+    let mut attempt = 1;
+    loop {
+        match send() {
+            Err(WebhookError::Transient(e)) if attempt < attempts => {
+                warn!(
+                    "Webhook try {} failed with {}, trying again in {:?}",
+                    attempt, e, delay
+                );
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn post_webhook(url: &str, body: &str) -> Result<(), WebhookError> {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| WebhookError::Permanent(e.to_string()))?;
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_owned())
+        .send()
+        .map_err(|e| WebhookError::Transient(e.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        Err(WebhookError::Transient(status.to_string()))
+    } else {
+        Err(WebhookError::Permanent(status.to_string()))
+    }
+}
+
+pub struct DiscordNotifier;
+impl Notifier for DiscordNotifier {
+    fn dm(user_id: UserId, message: &str) -> Result<(), CommandError> {
+        let private_channel = user_id.create_dm_channel()?;
+        private_channel.say(message)?;
+        Ok(())
+    }
+    fn channel(channel_id: ChannelId, message: &str) -> Result<(), CommandError> {
+        channel_id.say(message)?;
+        Ok(())
+    }
+    fn post_pinned(channel_id: ChannelId, message: &str) -> Result<MessageId, CommandError> {
+        let posted = channel_id.say(message)?;
+        if let Err(e) = posted.pin() {
+            warn!(
+                "Could not pin {} in {} with error {:?}",
+                posted.id, channel_id, e
+            );
+        }
+        Ok(posted.id)
+    }
+    fn edit(
+        channel_id: ChannelId,
+        message_id: MessageId,
+        message: &str,
+    ) -> Result<bool, CommandError> {
+        match channel_id.edit_message(message_id, |m| m.content(message)) {
+            Ok(_) => Ok(true),
+            Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ref response)))
+                if response.status == StatusCode::NotFound =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(CommandError::from(e)),
+        }
+    }
+    fn guild_for_channel(channel_id: ChannelId) -> Option<GuildId> {
+        channel_id
+            .to_channel_cached()
+            .and_then(|channel| channel.guild())
+            .map(|guild_channel| guild_channel.read().guild_id)
+    }
+    /// Sent from a thread of its own, so a slow webhook only holds up itself
+    fn webhook(url: &str, payload: &serde_json::Value) -> Result<(), CommandError> {
+        let url = url.to_owned();
+        let body = payload.to_string();
+        thread::Builder::new()
+            .name("webhook".to_owned())
+            .spawn(move || {
+                if let Err(e) = with_retries(WEBHOOK_ATTEMPTS, WEBHOOK_BACKOFF, || {
+                    post_webhook(&url, &body)
+                }) {
+                    // The host's webhook, the host's problem. Nobody else is waiting on it.
+                    warn!("Dropped webhook event {} with error {:?}", body, e);
+                }
+            })?;
+        Ok(())
+    }
+}
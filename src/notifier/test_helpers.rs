@@ -0,0 +1,137 @@
+use super::*;
+
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Default)]
+struct Sent {
+    dms: HashMap<UserId, Vec<String>>,
+    channel_messages: HashMap<ChannelId, Vec<String>>,
+    pinned: HashMap<ChannelId, Vec<(MessageId, String)>>,
+    edits: HashMap<ChannelId, Vec<(MessageId, String)>>,
+    deleted: Vec<(ChannelId, MessageId)>,
+    webhooks: HashMap<String, Vec<serde_json::Value>>,
+    last_message_id: u64,
+}
+
+lazy_static! {
+    // Tests run in parallel so each one should use IDs nobody else does.
+    static ref SENT: Mutex<Sent> = Mutex::new(Sent::default());
+}
+
+/// A `Notifier` that remembers everything it was asked to send instead of
+/// sending it
+pub struct RecordingNotifier;
+impl RecordingNotifier {
+    pub fn dms(user_id: UserId) -> Vec<String> {
+        SENT.lock()
+            .unwrap()
+            .dms
+            .get(&user_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn channel_messages(channel_id: ChannelId) -> Vec<String> {
+        SENT.lock()
+            .unwrap()
+            .channel_messages
+            .get(&channel_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn pinned(channel_id: ChannelId) -> Vec<(MessageId, String)> {
+        SENT.lock()
+            .unwrap()
+            .pinned
+            .get(&channel_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn edits(channel_id: ChannelId) -> Vec<(MessageId, String)> {
+        SENT.lock()
+            .unwrap()
+            .edits
+            .get(&channel_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn webhooks(url: &str) -> Vec<serde_json::Value> {
+        SENT.lock()
+            .unwrap()
+            .webhooks
+            .get(url)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// As if someone deleted it, so it can't be edited any more
+    pub fn delete(channel_id: ChannelId, message_id: MessageId) {
+        SENT.lock().unwrap().deleted.push((channel_id, message_id));
+    }
+}
+
+impl Notifier for RecordingNotifier {
+    fn dm(user_id: UserId, message: &str) -> Result<(), CommandError> {
+        SENT.lock()
+            .unwrap()
+            .dms
+            .entry(user_id)
+            .or_default()
+            .push(message.to_owned());
+        Ok(())
+    }
+    fn channel(channel_id: ChannelId, message: &str) -> Result<(), CommandError> {
+        SENT.lock()
+            .unwrap()
+            .channel_messages
+            .entry(channel_id)
+            .or_default()
+            .push(message.to_owned());
+        Ok(())
+    }
+    /// Messages get ids counting down from the top, out of the way of the ones
+    /// tests make up
+    fn post_pinned(channel_id: ChannelId, message: &str) -> Result<MessageId, CommandError> {
+        let mut sent = SENT.lock().unwrap();
+        sent.last_message_id += 1;
+        let message_id = MessageId(u64::MAX - sent.last_message_id);
+        sent.pinned
+            .entry(channel_id)
+            .or_default()
+            .push((message_id, message.to_owned()));
+        Ok(message_id)
+    }
+    fn edit(
+        channel_id: ChannelId,
+        message_id: MessageId,
+        message: &str,
+    ) -> Result<bool, CommandError> {
+        let mut sent = SENT.lock().unwrap();
+        if sent.deleted.contains(&(channel_id, message_id)) {
+            return Ok(false);
+        }
+        sent.edits
+            .entry(channel_id)
+            .or_default()
+            .push((message_id, message.to_owned()));
+        Ok(true)
+    }
+    /// Every channel gets a server of its own, with the same ID
+    fn guild_for_channel(channel_id: ChannelId) -> Option<GuildId> {
+        Some(GuildId(channel_id.0))
+    }
+    fn webhook(url: &str, payload: &serde_json::Value) -> Result<(), CommandError> {
+        SENT.lock()
+            .unwrap()
+            .webhooks
+            .entry(url.to_owned())
+            .or_default()
+            .push(payload.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn should_retry_transient_webhook_failures_then_give_up() {
+    let mut tries = 0;
+    let result = with_retries(3, time::Duration::from_millis(0), || {
+        tries += 1;
+        Err(WebhookError::Transient("timed out".to_owned()))
+    });
+    assert_eq!(result, Err(WebhookError::Transient("timed out".to_owned())));
+    assert_eq!(tries, 3);
+
+    let mut tries = 0;
+    let result = with_retries(3, time::Duration::from_millis(0), || {
+        tries += 1;
+        if tries < 2 {
+            Err(WebhookError::Transient("503".to_owned()))
+        } else {
+            Ok(())
+        }
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(tries, 2);
+}
+
+#[test]
+fn should_not_retry_permanent_webhook_failures() {
+    let mut tries = 0;
+    let result = with_retries(3, time::Duration::from_millis(0), || {
+        tries += 1;
+        Err(WebhookError::Permanent("404".to_owned()))
+    });
+    assert_eq!(result, Err(WebhookError::Permanent("404".to_owned())));
+    assert_eq!(tries, 1);
+}
@@ -1,25 +1,258 @@
 use crate::model::enums::{NationStatus, Nations, SubmissionStatus};
 use crate::model::{GameData, Nation, RawGameData};
-use crate::snek::{snek_details, SnekGameStatus};
-use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use crate::snek::{nation_names_from_providers, NationNameProvider, SnekGameStatus};
+use byteorder::{ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
 use flate2::read::ZlibDecoder;
 use hex_slice::AsHex;
 use log::*;
+use reqwest::Url;
 use std::error::Error;
+use std::fmt;
 use std::io;
 use std::io::{Cursor, Read, Write, BufRead};
 use std::net;
 use std::net::SocketAddr;
 use std::net::ToSocketAddrs;
-use std::time::Duration;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+pub mod test_helpers;
 
 pub trait ServerConnection {
-    fn get_game_data(server_address: &str) -> io::Result<GameData>;
-    fn get_snek_data(server_address: &str) -> Result<Option<SnekGameStatus>, Box<dyn Error>>;
+    fn get_game_data(server_address: &str) -> Result<GameData, ServerError>;
+    /// For the poller, so one slow server can't hold everything up. `timeout`
+    /// covers every attempt, retries included. Test connections answer straight
+    /// away so don't need to override this.
+    fn get_game_data_with_timeout(
+        server_address: &str,
+        _timeout: Duration,
+    ) -> Result<GameData, ServerError> {
+        Self::get_game_data(server_address)
+    }
+    /// Tries each provider in order, see `NationNameProvider`
+    fn get_snek_data(
+        server_address: &str,
+        providers: &[&dyn NationNameProvider],
+    ) -> Result<Option<SnekGameStatus>, Box<dyn Error>>;
+    /// The server's response exactly as it came, without retries or parsing. Only
+    /// for debugging, so connections that aren't really servers needn't bother.
+    fn get_raw_response(_server_address: &str) -> Result<Vec<u8>, ServerError> {
+        Err(ServerError::Protocol(
+            "this connection can't fetch raw responses".to_owned(),
+        ))
+    }
+}
+
+/// Why we couldn't get game data out of a server, so that "the server is down"
+/// and "the server sent us garbage" can be told apart
+#[derive(Debug)]
+pub enum ServerError {
+    Io(io::Error),
+    Timeout,
+    Protocol(String),
+    Unresolvable,
+    InvalidAddress(String),
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerError::Io(e) => write!(f, "Server is down or refused the connection ({})", e),
+            ServerError::Timeout => {
+                f.write_str("Server is down (timed out waiting for a response)")
+            }
+            ServerError::Protocol(msg) => {
+                write!(f, "Server returned data we could not understand ({})", msg)
+            }
+            ServerError::Unresolvable => f.write_str(
+                "Could not look up the host, its name doesn't point anywhere right now (it may be moving to a new IP)",
+            ),
+            ServerError::InvalidAddress(msg) => write!(f, "Invalid server address ({})", msg),
+        }
+    }
+}
+
+impl Error for ServerError {}
+
+impl ServerError {
+    /// Whether trying again could possibly help. A host that doesn't resolve
+    /// won't start to in the next few seconds, so the poller shows the last
+    /// good data as stale until it does instead. A server that sent garbage
+    /// sends the same garbage when asked again.
+    fn is_permanent(&self) -> bool {
+        match self {
+            ServerError::Unresolvable
+            | ServerError::InvalidAddress(_)
+            | ServerError::Protocol(_) => true,
+            ServerError::Io(_) | ServerError::Timeout => false,
+        }
+    }
+
+    /// A word or two for at-a-glance lists, the `Display` impl has the details
+    pub fn category(&self) -> &'static str {
+        match self {
+            ServerError::Io(_) | ServerError::Timeout => "down",
+            ServerError::Protocol(_) => "bad data",
+            ServerError::Unresolvable => "host unresolvable",
+            ServerError::InvalidAddress(_) => "bad address",
+        }
+    }
+}
+
+impl From<io::Error> for ServerError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => ServerError::Timeout,
+            _ => ServerError::Io(e),
+        }
+    }
+}
+
+/// What a dom5 server listens on when it's started without `--port`
+pub const DEFAULT_SERVER_PORT: u16 = 2556;
+
+/// Checks a `host:port` address and puts it in the form we store and connect to.
+/// IPv6 hosts go in brackets (`[::1]:1234`) and a missing port means the default.
+pub fn normalise_server_address(server_address: &str) -> Result<String, ServerError> {
+    let invalid = |msg: &str| ServerError::InvalidAddress(format!("'{}' {}", server_address, msg));
+    let server_address = server_address.trim();
+    if is_http_address(server_address) {
+        let url = Url::parse(server_address)
+            .map_err(|e| invalid(&format!("is not a valid URL ({})", e)))?;
+        if url.host_str().is_none_or(str::is_empty) {
+            return Err(invalid("has no host"));
+        }
+        return Ok(url.to_string());
+    }
+    // A bare IPv6 address, whose colons would otherwise look like a port
+    if let Ok(ipv6) = server_address.parse::<net::Ipv6Addr>() {
+        return Ok(format!("[{}]:{}", ipv6, DEFAULT_SERVER_PORT));
+    }
+    let (host, option_port) = if server_address.starts_with('[') {
+        let end = server_address
+            .find(']')
+            .ok_or_else(|| invalid("is missing a closing ']'"))?;
+        let host = &server_address[1..end];
+        if host.parse::<net::Ipv6Addr>().is_err() {
+            return Err(invalid("does not have an IPv6 address in the brackets"));
+        }
+        let rest = &server_address[end + 1..];
+        let option_port = match rest {
+            "" => None,
+            _ if rest.starts_with(':') => Some(&rest[1..]),
+            _ => return Err(invalid("has something other than a port after the ']'")),
+        };
+        (format!("[{}]", host), option_port)
+    } else {
+        let mut parts = server_address.splitn(2, ':');
+        let host = parts.next().unwrap_or("");
+        let option_port = parts.next();
+        if option_port.iter().any(|port| port.contains(':')) {
+            return Err(invalid(
+                "has too many ':'s, IPv6 addresses need to be in [brackets]",
+            ));
+        }
+        (host.to_owned(), option_port)
+    };
+    if host.is_empty() {
+        return Err(invalid("has no host"));
+    }
+    let port = match option_port {
+        None => DEFAULT_SERVER_PORT,
+        Some(port) => port
+            .parse::<u16>()
+            .ok()
+            .filter(|&port| port != 0)
+            .ok_or_else(|| invalid("does not end in a port between 1 and 65535"))?,
+    };
+    Ok(format!("{}:{}", host, port))
+}
+
+/// The host (lowercased, IPv6 in brackets) and port of an address, however it
+/// was written. URLs without a port get their scheme's default.
+pub fn split_server_address(server_address: &str) -> Result<(String, u16), ServerError> {
+    let normalised = normalise_server_address(server_address)?;
+    if is_http_address(&normalised) {
+        let url = Url::parse(&normalised).map_err(|e| {
+            ServerError::InvalidAddress(format!("'{}' is not a valid URL ({})", normalised, e))
+        })?;
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let port = url.port_or_known_default().unwrap_or_default();
+        return Ok((host, port));
+    }
+    // Always there and always valid after normalising
+    let (host, port) = normalised
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+        .ok_or_else(|| ServerError::InvalidAddress(format!("'{}' has no port", normalised)))?;
+    Ok((host.to_lowercase(), port))
+}
+
+/// Status pages served by an HTTP(S) proxy in front of the server, rather than
+/// the server's own port. See `HttpServerConnection`.
+pub fn is_http_address(server_address: &str) -> bool {
+    server_address.starts_with("http://") || server_address.starts_with("https://")
+}
+
+/// How long to wait on a server before giving up, if the caller doesn't say
+pub const DEFAULT_SERVER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Servers drop connections for a few seconds while hosting
+const FETCH_ATTEMPTS: u32 = 3;
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Calls `f` up to `attempts` times, doubling the wait between each attempt.
+/// Gives up straight away on errors that retrying won't fix, and once there
+/// isn't time left for another attempt. Each attempt is given whatever's left
+/// of `timeout`.
+fn retry_with_backoff<T, F>(
+    attempts: u32,
+    first_delay: Duration,
+    timeout: Duration,
+    mut f: F,
+) -> Result<T, ServerError>
+where
+    F: FnMut(Duration) -> Result<T, ServerError>,
+{
+    let deadline = Instant::now() + timeout;
+    let mut delay = first_delay;
+    let mut attempt = 1;
+    loop {
+        match f(deadline.saturating_duration_since(Instant::now())) {
+            Ok(t) => return Ok(t),
+            Err(e) => {
+                if e.is_permanent() || attempt >= attempts || Instant::now() + delay >= deadline {
+                    return Err(e);
+                }
+                info!(
+                    "attempt {} failed with {}, retrying in {:?}",
+                    attempt, e, delay
+                );
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+        }
+    }
 }
 
-fn get_game_data_cache(server_address: &str) -> io::Result<GameData> {
-    let raw_data = get_raw_game_data(server_address)?;
+/// `timeout` covers every attempt, so a server that accepts the connection but
+/// never answers can't hang us forever
+pub fn get_game_data_with_timeout(
+    server_address: &str,
+    timeout: Duration,
+) -> Result<GameData, ServerError> {
+    let raw_data = retry_with_backoff(FETCH_ATTEMPTS, FIRST_RETRY_DELAY, timeout, |remaining| {
+        get_raw_game_data(server_address, remaining)
+    })?;
+    game_data_from_raw(raw_data)
+}
+
+fn game_data_from_raw(raw_data: RawGameData) -> Result<GameData, ServerError> {
     let mut game_data = GameData {
         game_name: raw_data.game_name,
         nations: vec![],
@@ -32,17 +265,17 @@ fn get_game_data_cache(server_address: &str) -> io::Result<GameData> {
             let submitted = raw_data.f[i + 250];
             let connected = raw_data.f[i + 500];
             let nation_id = (i - 1) as u32; // why -1? No fucking idea
-            let &(nation_name, era) = Nations::get_nation_desc(nation_id);
+            let era = Nations::get_nation_desc(nation_id)
+                .map_or_else(|| "unknown".to_owned(), |&(_, era)| format!("{}", era));
             let nation = Nation {
                 id: nation_id,
-                status: NationStatus::from_int(status_num).ok_or(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Unknown nation status {}", status_num),
-                ))?,
+                status: NationStatus::from_int(status_num).ok_or_else(|| {
+                    ServerError::Protocol(format!("Unknown nation status {}", status_num))
+                })?,
                 submitted: SubmissionStatus::from_int(submitted),
                 connected: connected == 1,
-                name: nation_name.to_owned(),
-                era: format!("{}", era),
+                name: Nations::get_nation_name(nation_id),
+                era,
             };
             game_data.nations.push(nation);
         }
@@ -50,40 +283,148 @@ fn get_game_data_cache(server_address: &str) -> io::Result<GameData> {
     Ok(game_data)
 }
 
+/// Talks to the server directly, or through `HttpServerConnection` for games
+/// whose address is a URL
 pub struct RealServerConnection;
 
 impl ServerConnection for RealServerConnection {
-    fn get_game_data(server_address: &str) -> io::Result<GameData> {
-        get_game_data_cache(server_address)
+    fn get_game_data(server_address: &str) -> Result<GameData, ServerError> {
+        Self::get_game_data_with_timeout(server_address, DEFAULT_SERVER_TIMEOUT)
+    }
+    fn get_game_data_with_timeout(
+        server_address: &str,
+        timeout: Duration,
+    ) -> Result<GameData, ServerError> {
+        if is_http_address(server_address) {
+            HttpServerConnection::get_game_data_with_timeout(server_address, timeout)
+        } else {
+            get_game_data_with_timeout(server_address, timeout)
+        }
+    }
+    fn get_snek_data(
+        server_address: &str,
+        providers: &[&dyn NationNameProvider],
+    ) -> Result<Option<SnekGameStatus>, Box<dyn Error>> {
+        Ok(nation_names_from_providers(server_address, providers))
+    }
+    fn get_raw_response(server_address: &str) -> Result<Vec<u8>, ServerError> {
+        if is_http_address(server_address) {
+            HttpServerConnection::get_raw_response(server_address)
+        } else {
+            call_server_for_info(server_address, DEFAULT_SERVER_TIMEOUT)
+        }
+    }
+}
+
+/// Fetches the same response the server would send over TCP from a URL, for
+/// hosts who put the server behind an HTTP(S) proxy
+pub struct HttpServerConnection;
+
+impl ServerConnection for HttpServerConnection {
+    fn get_game_data(url: &str) -> Result<GameData, ServerError> {
+        Self::get_game_data_with_timeout(url, DEFAULT_SERVER_TIMEOUT)
+    }
+    fn get_game_data_with_timeout(url: &str, timeout: Duration) -> Result<GameData, ServerError> {
+        let raw_data =
+            retry_with_backoff(FETCH_ATTEMPTS, FIRST_RETRY_DELAY, timeout, |remaining| {
+                let buffer = call_http_for_info(url, remaining)?;
+                raw_game_data_from_response(&buffer)
+            })?;
+        game_data_from_raw(raw_data)
+    }
+    fn get_snek_data(
+        server_address: &str,
+        providers: &[&dyn NationNameProvider],
+    ) -> Result<Option<SnekGameStatus>, Box<dyn Error>> {
+        Ok(nation_names_from_providers(server_address, providers))
+    }
+    fn get_raw_response(url: &str) -> Result<Vec<u8>, ServerError> {
+        call_http_for_info(url, DEFAULT_SERVER_TIMEOUT)
+    }
+}
+
+impl From<reqwest::Error> for ServerError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            ServerError::Timeout
+        } else {
+            ServerError::Io(io::Error::other(e.to_string()))
+        }
     }
-    fn get_snek_data(server_address: &str) -> Result<Option<SnekGameStatus>, Box<dyn Error>> {
-        snek_details(server_address)
+}
+
+fn call_http_for_info(url: &str, timeout: Duration) -> Result<Vec<u8>, ServerError> {
+    info!("fetching {}", url);
+    let client = reqwest::Client::builder().timeout(timeout).build()?;
+    let mut response = client.get(url).send()?;
+    if !response.status().is_success() {
+        // Most likely the proxy is up but the server behind it isn't
+        return Err(ServerError::Io(io::Error::other(format!(
+            "{} answered with {}",
+            url,
+            response.status()
+        ))));
     }
+    let mut body = vec![];
+    response.copy_to(&mut body)?;
+    response_from_http_body(body, Instant::now() + timeout)
+}
+
+/// The same checks on the length as a response read straight off the server
+fn response_from_http_body(body: Vec<u8>, deadline: Instant) -> Result<Vec<u8>, ServerError> {
+    read_response(&mut Cursor::new(body), deadline)
+}
+
+fn get_raw_game_data(server_address: &str, timeout: Duration) -> Result<RawGameData, ServerError> {
+    let buffer = call_server_for_info(server_address, timeout)?;
+    raw_game_data_from_response(&buffer)
+}
+
+/// The bytes the parser works on, i.e. after the header and decompressed if the
+/// server compressed them
+pub fn response_payload(buffer: &[u8]) -> Result<Vec<u8>, ServerError> {
+    if buffer.len() < MIN_RESPONSE_LENGTH {
+        return Err(ServerError::Protocol(format!(
+            "response was only {} bytes long",
+            buffer.len()
+        )));
+    }
+    decompress_server_info(buffer)
+        .map_err(|e| ServerError::Protocol(format!("could not decompress response: {}", e)))
+}
+
+/// What `get_game_data` would have made of a response from `get_raw_response`
+pub fn game_data_from_response(buffer: &[u8]) -> Result<GameData, ServerError> {
+    game_data_from_raw(raw_game_data_from_response(buffer)?)
 }
 
-fn get_raw_game_data(server_address: &str) -> io::Result<RawGameData> {
-    let buffer = call_server_for_info(server_address)?;
-    let decompressed = decompress_server_info(&buffer)?;
-    let game_data = parse_data(&decompressed)?;
+fn raw_game_data_from_response(buffer: &[u8]) -> Result<RawGameData, ServerError> {
+    let decompressed = response_payload(buffer)?;
+    let game_data = parse_data(&decompressed)
+        .map_err(|e| ServerError::Protocol(format!("could not parse response: {}", e)))?;
     Ok(game_data)
 }
 
-fn call_server_for_info(server_address: &str) -> io::Result<Vec<u8>> {
+fn call_server_for_info(server_address: &str, timeout: Duration) -> Result<Vec<u8>, ServerError> {
     info!("starting to connect to {}", server_address);
-    let parsed_address: SocketAddr =
-        server_address
-            .to_socket_addrs()?
-            .next()
-            .ok_or(io::Error::new(
-                io::ErrorKind::Other,
-                "Could not resolve ip address",
-            ))?;
-    let mut stream = net::TcpStream::connect_timeout(&parsed_address, Duration::from_secs(30))?;
+    // Addresses saved before we checked them might still be missing a port. The
+    // host is looked up afresh every time, so a server that moves to a new IP
+    // under the same name is followed from the next poll.
+    let parsed_address: SocketAddr = normalise_server_address(server_address)?
+        .to_socket_addrs()
+        .map_err(|_| ServerError::Unresolvable)?
+        .next()
+        .ok_or(ServerError::Unresolvable)?;
+    let mut stream = net::TcpStream::connect_timeout(&parsed_address, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
     debug!("connected");
     // https://steamcommunity.com/app/722060/discussions/0/1749024748627269322/?ctp=2#c1749024925634051868
     // (b'f', b'H', b'\a', b'\x00', b'\x00',
     // b'\x00', b'=', b'\x1e', b'\x02', b'\x11', b'E', b'\x05', b'\x00')
     // b'<ccssssccccccc'
+    // There's no password in this exchange: a game's master/login passwords only
+    // come into it once a client tries to play a nation, the status port always answers
     let mut wtr = vec![];
     wtr.write_u8(b'f')?;
     wtr.write_u8(b'H')?;
@@ -104,9 +445,8 @@ fn call_server_for_info(server_address: &str) -> io::Result<Vec<u8>> {
     debug!("Sending {:x}", wtr.as_slice().as_hex());
     let _ = stream.write(&wtr)?;
     debug!("sent");
-    let mut buffer = [0; 2048];
     debug!("trying to receive");
-    let _ = stream.read(&mut buffer)?;
+    let response = read_response(&mut stream, Instant::now() + timeout)?;
 
     let mut wtr2 = vec![];
     wtr2.write_u8(b'f')?;
@@ -117,7 +457,62 @@ fn call_server_for_info(server_address: &str) -> io::Result<Vec<u8>> {
     let _ = stream.write(&wtr2)?;
     debug!("sent");
 
-    Ok(buffer.to_vec())
+    Ok(response)
+}
+
+/// 'f' and 'H' (or 'J' if compressed), then how many bytes follow
+const RESPONSE_HEADER_LENGTH: usize = 6;
+/// `decompress_server_info` skips this much before the data
+const MIN_RESPONSE_LENGTH: usize = 10;
+/// Far more than any game sends, so a garbled length can't keep us reading
+const MAX_RESPONSE_LENGTH: usize = 1 << 20;
+
+/// Keeps reading until we have as much as the header said was coming, since a
+/// big game's response can arrive over several packets. A server that hangs up
+/// early gets parsed with whatever it sent. `deadline` is for the whole
+/// response, the stream's own timeout only covers each read.
+fn read_response<R: Read>(stream: &mut R, deadline: Instant) -> Result<Vec<u8>, ServerError> {
+    let mut response = vec![];
+    let mut buffer = [0; 2048];
+    loop {
+        if response.len() >= RESPONSE_HEADER_LENGTH {
+            let expected = RESPONSE_HEADER_LENGTH
+                + LittleEndian::read_u32(&response[2..RESPONSE_HEADER_LENGTH]) as usize;
+            if expected > MAX_RESPONSE_LENGTH {
+                return Err(ServerError::Protocol(format!(
+                    "response says it's {} bytes long",
+                    expected
+                )));
+            }
+            if response.len() >= expected {
+                response.truncate(expected);
+                break;
+            }
+        }
+        if Instant::now() >= deadline {
+            return Err(ServerError::Timeout);
+        }
+        let read = match stream.read(&mut buffer) {
+            Ok(read) => read,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if read == 0 {
+            debug!(
+                "server closed the connection after {} bytes",
+                response.len()
+            );
+            break;
+        }
+        response.extend_from_slice(&buffer[..read]);
+    }
+    if response.len() < MIN_RESPONSE_LENGTH {
+        return Err(ServerError::Protocol(format!(
+            "response was only {} bytes long",
+            response.len()
+        )));
+    }
+    Ok(response)
 }
 
 fn decompress_server_info(raw: &[u8]) -> io::Result<Vec<u8>> {
@@ -0,0 +1,95 @@
+use super::*;
+
+use lazy_static::lazy_static;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+#[derive(Default)]
+struct Script {
+    game_data: VecDeque<GameData>,
+    option_snek_state: Option<SnekGameStatus>,
+    option_raw_response: Option<Vec<u8>>,
+    game_data_calls: usize,
+}
+
+lazy_static! {
+    // Global rather than thread local since the poller calls from its own threads.
+    // Tests run in parallel so each one should use addresses nobody else does.
+    static ref SCRIPTS: Mutex<HashMap<String, Script>> = Mutex::new(HashMap::new());
+}
+
+/// A `ServerConnection` that answers with whatever each address was scripted
+/// with, and remembers how many times it was asked. Addresses with no script
+/// act like a server that's down.
+pub struct RecordingServerConnection;
+impl RecordingServerConnection {
+    /// Each call to `get_game_data` gets the next response, and the last one
+    /// keeps being returned once the rest are used up
+    pub fn script_game_data(server_address: &str, responses: Vec<GameData>) {
+        let mut scripts = SCRIPTS.lock().unwrap();
+        let script = scripts.entry(server_address.to_owned()).or_default();
+        script.game_data = responses.into_iter().collect();
+    }
+
+    pub fn script_snek_data(server_address: &str, snek_state: SnekGameStatus) {
+        let mut scripts = SCRIPTS.lock().unwrap();
+        let script = scripts.entry(server_address.to_owned()).or_default();
+        script.option_snek_state = Some(snek_state);
+    }
+
+    pub fn script_raw_response(server_address: &str, raw_response: Vec<u8>) {
+        let mut scripts = SCRIPTS.lock().unwrap();
+        let script = scripts.entry(server_address.to_owned()).or_default();
+        script.option_raw_response = Some(raw_response);
+    }
+
+    pub fn game_data_calls(server_address: &str) -> usize {
+        SCRIPTS
+            .lock()
+            .unwrap()
+            .get(server_address)
+            .map_or(0, |script| script.game_data_calls)
+    }
+}
+
+impl ServerConnection for RecordingServerConnection {
+    fn get_game_data(server_address: &str) -> Result<GameData, ServerError> {
+        let mut scripts = SCRIPTS.lock().unwrap();
+        let script = scripts.entry(server_address.to_owned()).or_default();
+        script.game_data_calls += 1;
+        let option_game_data = if script.game_data.len() > 1 {
+            script.game_data.pop_front()
+        } else {
+            script.game_data.front().cloned()
+        };
+        option_game_data.ok_or_else(|| {
+            ServerError::Io(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("No game data scripted for {}", server_address),
+            ))
+        })
+    }
+    fn get_snek_data(
+        server_address: &str,
+        _providers: &[&dyn NationNameProvider],
+    ) -> Result<Option<SnekGameStatus>, Box<dyn Error>> {
+        Ok(SCRIPTS
+            .lock()
+            .unwrap()
+            .get(server_address)
+            .and_then(|script| script.option_snek_state.clone()))
+    }
+    fn get_raw_response(server_address: &str) -> Result<Vec<u8>, ServerError> {
+        SCRIPTS
+            .lock()
+            .unwrap()
+            .get(server_address)
+            .and_then(|script| script.option_raw_response.clone())
+            .ok_or_else(|| {
+                ServerError::Io(io::Error::new(
+                    io::ErrorKind::ConnectionRefused,
+                    format!("No raw response scripted for {}", server_address),
+                ))
+            })
+    }
+}
@@ -0,0 +1,287 @@
+use super::*;
+
+/// Laid out the way a server answers (after decompressing): the game name,
+/// timer, nation statuses, turn, and then whatever extra strings it sends
+fn server_payload(extra: &[u8]) -> Vec<u8> {
+    let mut payload = vec![0u8; 6];
+    payload.extend_from_slice(b"payload game\0");
+    payload.extend_from_slice(&[0u8; 6]);
+    payload.extend_from_slice(&3_600_000i32.to_le_bytes());
+    let mut f = vec![0u8; 750];
+    // nation 5 (Ermor) is a human who has submitted
+    f[6] = 1;
+    f[256] = 2;
+    payload.extend_from_slice(&f);
+    payload.push(0);
+    payload.extend_from_slice(&17u32.to_le_bytes());
+    payload.extend_from_slice(&0u32.to_le_bytes());
+    payload.push(0);
+    payload.extend_from_slice(extra);
+    payload
+}
+
+#[test]
+fn should_parse_whole_payload() {
+    let raw_data = parse_data(&server_payload(&[0; 16])).unwrap();
+    assert_eq!(raw_data.game_name, "payload game");
+    assert_eq!(raw_data.d, 3_600_000);
+    assert_eq!(raw_data.h, 17);
+    assert_eq!(raw_data.f[6], 1);
+}
+
+#[test]
+fn should_retry_until_success() {
+    let mut calls = 0;
+    let result = retry_with_backoff(3, Duration::from_millis(1), Duration::from_secs(10), |_| {
+        calls += 1;
+        if calls < 3 {
+            Err(ServerError::Timeout)
+        } else {
+            Ok(calls)
+        }
+    });
+    assert_eq!(result.unwrap(), 3);
+}
+
+#[test]
+fn should_return_last_error_after_all_attempts() {
+    let mut calls = 0;
+    let result: Result<(), ServerError> =
+        retry_with_backoff(3, Duration::from_millis(1), Duration::from_secs(10), |_| {
+            calls += 1;
+            Err(ServerError::Io(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("attempt {}", calls),
+            )))
+        });
+    match result {
+        Err(ServerError::Io(e)) => assert_eq!(e.to_string(), "attempt 3"),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn should_not_retry_permanent_errors() {
+    for permanent_error in &[
+        || ServerError::Unresolvable,
+        || ServerError::InvalidAddress("no port".to_owned()),
+        || ServerError::Protocol("garbage".to_owned()),
+    ] {
+        let mut calls = 0;
+        let result: Result<(), ServerError> =
+            retry_with_backoff(3, Duration::from_millis(1), Duration::from_secs(10), |_| {
+                calls += 1;
+                Err(permanent_error())
+            });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
+
+#[test]
+fn should_not_retry_past_the_timeout() {
+    let mut remainings = vec![];
+    let result: Result<(), ServerError> = retry_with_backoff(
+        3,
+        Duration::from_secs(1),
+        Duration::from_millis(200),
+        |remaining| {
+            remainings.push(remaining);
+            Err(ServerError::Timeout)
+        },
+    );
+    assert!(result.is_err());
+    assert_eq!(remainings.len(), 1);
+    assert!(remainings[0] <= Duration::from_millis(200));
+}
+
+#[test]
+fn should_give_each_attempt_what_is_left_of_the_timeout() {
+    let mut remainings = vec![];
+    let result: Result<(), ServerError> = retry_with_backoff(
+        3,
+        Duration::from_millis(20),
+        Duration::from_secs(10),
+        |remaining| {
+            remainings.push(remaining);
+            Err(ServerError::Timeout)
+        },
+    );
+    assert!(result.is_err());
+    assert_eq!(remainings.len(), 3);
+    assert!(remainings[1] <= Duration::from_secs(10) - Duration::from_millis(20));
+    assert!(remainings[2] < remainings[1]);
+}
+
+#[test]
+fn should_normalise_server_addresses() {
+    let cases = [
+        ("dom5.example.com:1234", "dom5.example.com:1234"),
+        ("1.2.3.4:1234", "1.2.3.4:1234"),
+        ("dom5.example.com", "dom5.example.com:2556"),
+        ("[::1]:1234", "[::1]:1234"),
+        ("[2001:db8::1]", "[2001:db8::1]:2556"),
+        ("2001:db8::1", "[2001:db8::1]:2556"),
+        (" 1.2.3.4:1234 ", "1.2.3.4:1234"),
+        (
+            "https://status.example.com/game?id=1",
+            "https://status.example.com/game?id=1",
+        ),
+        ("http://status.example.com", "http://status.example.com/"),
+    ];
+    for &(address, expected) in &cases {
+        assert_eq!(normalise_server_address(address).unwrap(), expected);
+    }
+}
+
+#[test]
+fn should_split_the_host_off_however_the_address_was_written() {
+    let cases = [
+        ("Dom5.Example.com:1234", ("dom5.example.com", 1234)),
+        (
+            "dom5.example.com",
+            ("dom5.example.com", DEFAULT_SERVER_PORT),
+        ),
+        ("[2001:db8::1]:1234", ("[2001:db8::1]", 1234)),
+        ("2001:db8::1", ("[2001:db8::1]", DEFAULT_SERVER_PORT)),
+        (
+            "https://status.example.com/game?id=1",
+            ("status.example.com", 443),
+        ),
+        (
+            "http://status.example.com:8080",
+            ("status.example.com", 8080),
+        ),
+    ];
+    for &(address, (host, port)) in &cases {
+        assert_eq!(
+            split_server_address(address).unwrap(),
+            (host.to_owned(), port)
+        );
+    }
+    assert!(split_server_address("host:port").is_err());
+}
+
+#[test]
+fn should_reject_malformed_server_addresses() {
+    for &address in &[
+        "",
+        ":1234",
+        "host:",
+        "host:port",
+        "host:99999",
+        "host:0",
+        "[::1:1234",
+        "[not ipv6]:1234",
+        "[::1]1234",
+        "host:1234:5678",
+        "https://",
+        "http://exa mple.com/",
+    ] {
+        match normalise_server_address(address) {
+            Err(ServerError::InvalidAddress(_)) => {}
+            other => panic!("{:?} should be invalid but got {:?}", address, other),
+        }
+    }
+}
+
+#[test]
+fn should_tell_down_servers_from_bad_data() {
+    assert_eq!(ServerError::Timeout.category(), "down");
+    assert_eq!(
+        ServerError::Protocol("garbage".to_owned()).category(),
+        "bad data"
+    );
+    assert_eq!(ServerError::Unresolvable.category(), "host unresolvable");
+    assert_eq!(
+        ServerError::InvalidAddress("no port".to_owned()).category(),
+        "bad address"
+    );
+}
+
+/// A connection that hands over its data a few bytes at a time
+struct ChunkedStream(Vec<Vec<u8>>);
+
+impl Read for ChunkedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() {
+            return Ok(0);
+        }
+        let chunk = self.0.remove(0);
+        buf[..chunk.len()].copy_from_slice(&chunk);
+        Ok(chunk.len())
+    }
+}
+
+fn response(payload: &[u8]) -> Vec<u8> {
+    let mut response = b"fH".to_vec();
+    response.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    response.extend_from_slice(payload);
+    response
+}
+
+fn later() -> Instant {
+    Instant::now() + Duration::from_secs(60)
+}
+
+#[test]
+fn should_read_response_split_over_several_packets() {
+    let whole = response(&server_payload(b""));
+    let mut chunks: Vec<Vec<u8>> = whole.chunks(100).map(|chunk| chunk.to_vec()).collect();
+    assert!(chunks.len() > 5);
+    // e.g. the start of whatever comes next on the connection
+    chunks.push(b"fH".to_vec());
+
+    let read = read_response(&mut ChunkedStream(chunks), later()).unwrap();
+    assert_eq!(read, whole);
+}
+
+#[test]
+fn should_use_what_was_sent_if_server_hangs_up_early() {
+    let whole = response(&server_payload(b""));
+    let read = read_response(&mut ChunkedStream(vec![whole[..500].to_vec()]), later()).unwrap();
+    assert_eq!(read, &whole[..500]);
+}
+
+#[test]
+fn should_reject_responses_too_short_or_too_long() {
+    let mut stream = ChunkedStream(vec![b"fH\x02\0\0\0ab".to_vec()]);
+    match read_response(&mut stream, later()) {
+        Err(ServerError::Protocol(_)) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    let mut stream = ChunkedStream(vec![b"fH\xff\xff\xff\xff".to_vec()]);
+    match read_response(&mut stream, later()) {
+        Err(ServerError::Protocol(_)) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn should_give_up_waiting_for_the_rest_after_the_deadline() {
+    let whole = response(&server_payload(b""));
+    let mut stream = ChunkedStream(vec![whole[..100].to_vec(), whole[100..].to_vec()]);
+    match read_response(&mut stream, Instant::now()) {
+        Err(ServerError::Timeout) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn should_read_a_proxied_response_like_a_direct_one() {
+    let payload = server_payload(b"");
+    // `decompress_server_info` skips 4 more bytes after the header
+    let mut padded = vec![0u8; 4];
+    padded.extend_from_slice(&payload);
+    let body = response(&padded);
+
+    let direct = read_response(&mut ChunkedStream(vec![body.clone()]), later()).unwrap();
+    let proxied = response_from_http_body(body, later()).unwrap();
+    assert_eq!(proxied, direct);
+    assert_eq!(
+        game_data_from_raw(raw_game_data_from_response(&proxied).unwrap()).unwrap(),
+        game_data_from_raw(parse_data(&payload).unwrap()).unwrap()
+    );
+    assert!(response_from_http_body(b"<html>".to_vec(), later()).is_err());
+}
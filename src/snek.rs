@@ -1,16 +1,186 @@
 use reqwest;
 use reqwest::StatusCode;
 use std::error::Error;
+use std::fs;
+use std::io;
 use std::str::FromStr;
+use std::time::Duration;
 use url::Url;
 
+use crate::server::normalise_server_address;
+use log::*;
 use serde::de;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg(test)]
+mod tests;
+
+/// Somewhere other than the game server itself that knows the (possibly modded)
+/// names of the nations in a game. Providers should return `Ok(None)` for
+/// addresses they don't host so that the next one can be tried.
+pub trait NationNameProvider: Sync {
+    /// What to call this provider in `!providers`
+    fn name(&self) -> &'static str;
+    fn get_nation_names(
+        &self,
+        server_address: &str,
+    ) -> Result<Option<SnekGameStatus>, Box<dyn Error>>;
+}
+
+pub struct Snek;
+impl NationNameProvider for Snek {
+    fn name(&self) -> &'static str {
+        "snek"
+    }
+    fn get_nation_names(
+        &self,
+        server_address: &str,
+    ) -> Result<Option<SnekGameStatus>, Box<dyn Error>> {
+        snek_details(server_address)
+    }
+}
+
+/// Names the bot's owner has written down for games snek doesn't host
+pub struct NationNamesFile;
+impl NationNameProvider for NationNamesFile {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+    fn get_nation_names(
+        &self,
+        server_address: &str,
+    ) -> Result<Option<SnekGameStatus>, Box<dyn Error>> {
+        // Read every time, it's small and this way edits don't need a restart
+        match fs::read_to_string(NATION_NAMES_PATH) {
+            Ok(text) => nation_names_from_file(&text, server_address),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// See `nation_names_from_file` for the format
+const NATION_NAMES_PATH: &str = "resources/nation_names";
+
+/// The names for the game at `server_address` from a file of
+/// `<address> <nation_id> <name>` lines, e.g. `dom5.example.com:2556 5 Arco`.
+/// Blank lines and lines starting with `#` are ignored. `Ok(None)` if the file
+/// has nothing for that address, so the next provider gets a go.
+pub fn nation_names_from_file(
+    text: &str,
+    server_address: &str,
+) -> Result<Option<SnekGameStatus>, Box<dyn Error>> {
+    let server_address = normalise_server_address(server_address)?;
+    let mut nations = HashMap::new();
+    for (ix, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let expected = || -> Box<dyn Error> {
+            format!(
+                "Line {}: expected '<address> <nation_id> <name>' but got '{}'",
+                ix + 1,
+                line
+            )
+            .into()
+        };
+        let mut parts = line.splitn(2, char::is_whitespace);
+        let address = parts.next().ok_or_else(expected)?;
+        let mut parts = parts
+            .next()
+            .ok_or_else(expected)?
+            .trim_start()
+            .splitn(2, char::is_whitespace);
+        let (nation_id, name) = match (parts.next(), parts.next().map(str::trim)) {
+            (Some(nation_id), Some(name)) if !name.is_empty() => (nation_id, name),
+            _ => return Err(expected()),
+        };
+        // Host names aren't case sensitive
+        if !normalise_server_address(address)?.eq_ignore_ascii_case(&server_address) {
+            continue;
+        }
+        let nation_id = u32::from_str(nation_id).map_err(|_| -> Box<dyn Error> {
+            format!("Line {}: '{}' is not a nation id", ix + 1, nation_id).into()
+        })?;
+        nations.insert(
+            nation_id,
+            SnekNation {
+                nation_id,
+                name: name.to_owned(),
+            },
+        );
+    }
+    if nations.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(SnekGameStatus {
+            nations,
+            option_game_id: None,
+        }))
+    }
+}
+
+pub static ALL_PROVIDERS: [&dyn NationNameProvider; 2] = [&Snek, &NationNamesFile];
+
+/// Used for games that haven't picked their own providers
+pub static DEFAULT_PROVIDERS: [&dyn NationNameProvider; 1] = [&Snek];
+
+/// Parses a comma separated list of provider names, in the order they should be tried
+pub fn providers_from_names(names: &str) -> Result<Vec<&'static dyn NationNameProvider>, String> {
+    names
+        .split(',')
+        .map(|name| {
+            let name = name.trim();
+            ALL_PROVIDERS
+                .iter()
+                .find(|provider| provider.name().eq_ignore_ascii_case(name))
+                .cloned()
+                .ok_or_else(|| {
+                    let known: Vec<&str> = ALL_PROVIDERS.iter().map(|p| p.name()).collect();
+                    format!(
+                        "Unknown provider '{}', known providers are: {}",
+                        name,
+                        known.join(", ")
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Asks each provider in turn, returning the first one that knows about the game.
+/// A provider that errors is skipped rather than failing the whole lookup, since
+/// we can always fall back to the default nation names.
+pub fn nation_names_from_providers(
+    server_address: &str,
+    providers: &[&dyn NationNameProvider],
+) -> Option<SnekGameStatus> {
+    for provider in providers {
+        match provider.get_nation_names(server_address) {
+            Ok(Some(nation_names)) => return Some(nation_names),
+            Ok(None) => {}
+            Err(e) => error!(
+                "Nation name provider {} failed for {} with error: {}",
+                provider.name(),
+                server_address,
+                e
+            ),
+        }
+    }
+    None
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 pub struct SnekGameStatus {
     pub nations: HashMap<u32, SnekNation>,
+    /// `None` for providers that aren't snek
+    pub option_game_id: Option<u32>,
+}
+
+/// Where players can see the game on snek's website
+pub fn snek_game_url(game_id: u32) -> String {
+    format!("https://dom5.snek.earth/games/{}", game_id)
 }
 
 #[derive(Deserialize, Debug)]
@@ -18,21 +188,35 @@ struct RawSnekGameStatus {
     nations: Vec<SnekNation>,
 }
 
-#[derive(PartialEq, Eq, Deserialize, Debug, Clone)]
+#[derive(PartialEq, Eq, Deserialize, Serialize, Debug, Clone)]
 pub struct SnekNation {
     #[serde(rename = "nationid", deserialize_with = "u32_from_str")]
     pub nation_id: u32,
     pub name: String,
 }
 
+/// Snek sends a string, but we write a number when persisting polls
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrU32 {
+    String(String),
+    U32(u32),
+}
+
 fn u32_from_str<'de, D>(d: D) -> Result<u32, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s = String::deserialize(d)?;
-    u32::from_str(&s).map_err(de::Error::custom)
+    match StringOrU32::deserialize(d)? {
+        StringOrU32::String(s) => u32::from_str(&s).map_err(de::Error::custom),
+        StringOrU32::U32(x) => Ok(x),
+    }
 }
 
+/// Short, since `!details` waits on it. If snek is slower than this we'd rather
+/// show the built-in nation names than keep everyone waiting.
+const SNEK_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub fn snek_details(address: &str) -> Result<Option<SnekGameStatus>, Box<dyn Error>> {
     let snek_url = Url::parse(&format!("https://{}", address)).or_else(|_| Url::parse(address))?;
 
@@ -52,10 +236,25 @@ pub fn snek_details(address: &str) -> Result<Option<SnekGameStatus>, Box<dyn Err
     };
     let game_id = port - 30_000;
 
-    let mut response = reqwest::get(&format!(
-        "https://dom5.snek.earth/api/games/{}/status",
-        game_id
-    ))?;
+    let client = reqwest::Client::builder().timeout(SNEK_TIMEOUT).build()?;
+    let mut response = client
+        .get(&format!(
+            "https://dom5.snek.earth/api/games/{}/status",
+            game_id
+        ))
+        .send()
+        .map_err(|e| -> Box<dyn Error> {
+            if e.is_timeout() {
+                warn!(
+                    "Snek took longer than {}s to answer for game {}, it may be degraded",
+                    SNEK_TIMEOUT.as_secs(),
+                    game_id
+                );
+                format!("Snek timed out after {}s", SNEK_TIMEOUT.as_secs()).into()
+            } else {
+                e.into()
+            }
+        })?;
     if response.status() != StatusCode::OK {
         return Err("Snek did not respond with OK".into());
     }
@@ -66,5 +265,8 @@ pub fn snek_details(address: &str) -> Result<Option<SnekGameStatus>, Box<dyn Err
         hash_map.insert(nation.nation_id, nation);
     }
 
-    Ok(Some(SnekGameStatus { nations: hash_map }))
+    Ok(Some(SnekGameStatus {
+        nations: hash_map,
+        option_game_id: Some(u32::from(game_id)),
+    }))
 }
@@ -0,0 +1,54 @@
+use super::*;
+
+const NATION_NAMES: &str = "# modded game\n\
+                            dom5.example.com:2556 5 Arcoscephale Reborn\n\
+                            \n\
+                            DOM5.example.com 6   Sunken Ermor \n\
+                            other.example.com:1234 5 Not This One\n";
+
+#[test]
+fn should_read_the_names_for_just_this_server() {
+    let nation_names = nation_names_from_file(NATION_NAMES, "dom5.example.com:2556")
+        .unwrap()
+        .unwrap();
+    assert_eq!(nation_names.option_game_id, None);
+    assert_eq!(nation_names.nations.len(), 2);
+    assert_eq!(nation_names.nations[&5].name, "Arcoscephale Reborn");
+    assert_eq!(nation_names.nations[&6].name, "Sunken Ermor");
+}
+
+#[test]
+fn should_leave_servers_it_has_nothing_for_to_the_next_provider() {
+    assert_eq!(
+        nation_names_from_file(NATION_NAMES, "elsewhere.example.com:2556").unwrap(),
+        None
+    );
+    assert_eq!(
+        nation_names_from_file("", "dom5.example.com").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn should_reject_malformed_lines() {
+    for &text in &[
+        "dom5.example.com:2556",
+        "dom5.example.com:2556 5",
+        "dom5.example.com:2556 five Arco",
+        "dom5.example.com:port 5 Arco",
+    ] {
+        assert!(
+            nation_names_from_file(text, "dom5.example.com:2556").is_err(),
+            "{}",
+            text
+        );
+    }
+}
+
+#[test]
+fn should_know_every_provider_by_name() {
+    let providers = providers_from_names("snek, FILE").unwrap();
+    let names: Vec<&str> = providers.iter().map(|provider| provider.name()).collect();
+    assert_eq!(names, vec!["snek", "file"]);
+    assert!(providers_from_names("snek,nope").is_err());
+}